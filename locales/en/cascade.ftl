@@ -0,0 +1,62 @@
+# Cascade messages, English (reference catalog)
+#
+# Every message shown through the i18n module has an entry here. Translations live in
+# locales/<locale>/cascade.ftl; messages they leave out fall back to this file.
+
+## Output labels
+
+output-tip = TIP:
+output-next-steps = Next steps:
+output-solution = Solution
+output-stack = Stack:
+output-stack-id = Stack ID: { $id }
+output-base-branch = Base branch: { $branch }
+output-working-branch = Working branch: { $branch }
+output-status = Status: { $status }
+output-status-active = Active
+entry-status-merged = [merged]
+entry-status-submitted = [submitted]
+entry-status-pending = [pending]
+
+## Plain output (--plain), in place of the status symbols
+
+output-plain-success = OK:
+output-plain-error = ERROR:
+output-plain-warning = WARNING:
+output-plain-info = INFO:
+output-plain-done = done
+output-plain-failed = failed
+
+## Error reports
+
+error-header = Error [{ $code }]:
+
+## ca recover
+
+recover-interrupted = '{ $command }' was interrupted { $age } and never finished
+recover-started-on = It started on branch: { $branch }
+recover-process = It ran as process { $pid }; if that process is still running, let it finish
+recover-tip = Run 'ca recover' to resume or abort it
+recover-blocked = Cannot start { $operation } while the interrupted '{ $command }' is unresolved
+recover-blocked-hint = Run 'ca recover' to resume or abort it first
+recover-none = No interrupted operation found
+recover-resume-tip = Resume it with: ca recover --resume
+recover-abort-tip = Abort it with: ca recover --abort
+recover-prompt = What should happen to it?
+recover-choice-resume = Resume: run '{ $command }' again
+recover-choice-abort = Abort: clean up and restore the stacks from before it started
+recover-choice-leave = Leave it for now
+recover-running-again = Running '{ $command }' again
+recover-failed-again = '{ $command }' failed again
+recover-restore-prompt = Restore the stacks from snapshot { $snapshot } (before { $operation })?
+recover-restored = Restored snapshot { $snapshot } ({ $count } branch(es) moved)
+recover-restore-failed = Could not restore snapshot { $snapshot }: { $error }
+recover-restore-later = Restore it later with: ca snapshots restore { $snapshot }
+recover-pop-tip = If the pop recorded its entries, put them back with: ca pop --restore-last
+recover-no-snapshot = No snapshot was taken before it changed anything
+recover-aborted = Aborted '{ $command }'
+recover-validate-tip = Run 'ca validate' to check the stacks match their branches
+recover-aborting-rebase = Aborting the unfinished git rebase
+recover-aborting-cherry-pick = Aborting the unfinished cherry-pick
+recover-returning = Returning to branch: { $branch }
+recover-checkout-failed = Could not check out '{ $branch }': { $error }
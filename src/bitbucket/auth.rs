@@ -0,0 +1,562 @@
+//! Token health checks
+//!
+//! An expired or read-only token otherwise surfaces as a bare 401 or 403 halfway
+//! through a submit or land. [`AuthPreflight`] looks the token up before the first
+//! API request of a command: it warns once the token is within
+//! `bitbucket.token_warning_days` of expiring or can't write to repositories, and
+//! stops with a clear message once it has expired. Results are cached in the git dir
+//! for a day so most commands don't pay for the extra requests.
+
+use crate::bitbucket::client::{BitbucketClient, RepositoryInfo};
+use crate::bitbucket::provider::RepositoryPermission;
+use crate::cli::output::Output;
+use crate::config::BitbucketConfig;
+use crate::errors::{CascadeError, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tokio::sync::OnceCell;
+use tracing::debug;
+
+/// Default for `bitbucket.token_warning_days`
+pub const DEFAULT_WARNING_DAYS: u32 = 14;
+
+/// Cached [`AuthStatus`], in the git dir
+const CACHE_FILE: &str = "CASCADE_AUTH_STATUS";
+
+/// How long a cached status is trusted before the server is asked again
+const CHECK_INTERVAL_HOURS: i64 = 24;
+
+/// Token permissions that allow pushing and creating pull requests
+const WRITE_PERMISSIONS: &[&str] = &["REPO_WRITE", "REPO_ADMIN", "PROJECT_WRITE", "PROJECT_ADMIN"];
+
+/// User the credentials belong to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthenticatedUser {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Personal access token details reported by the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenDetails {
+    pub name: String,
+    pub permissions: Vec<String>,
+    pub created: Option<DateTime<Utc>>,
+    /// `None` for tokens that never expire
+    pub expires: Option<DateTime<Utc>>,
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+impl TokenDetails {
+    /// Whether the token may push branches and create pull requests
+    pub fn can_write(&self) -> bool {
+        self.permissions
+            .iter()
+            .any(|p| WRITE_PERMISSIONS.contains(&p.as_str()))
+    }
+}
+
+/// What the server says about the configured credentials
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthStatus {
+    pub user: AuthenticatedUser,
+    /// `None` when the server doesn't list tokens (older servers, or a password)
+    pub token: Option<TokenDetails>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Something wrong with the token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenProblem {
+    Expired { expires: DateTime<Utc> },
+    ExpiresSoon { expires: DateTime<Utc>, days: i64 },
+    ReadOnly { permissions: Vec<String> },
+}
+
+impl fmt::Display for TokenProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenProblem::Expired { expires } => write!(
+                f,
+                "Your Bitbucket token expired on {}",
+                expires.format("%Y-%m-%d")
+            ),
+            TokenProblem::ExpiresSoon { expires, days } => write!(
+                f,
+                "Your Bitbucket token expires in {days} day(s), on {}",
+                expires.format("%Y-%m-%d")
+            ),
+            TokenProblem::ReadOnly { permissions } => write!(
+                f,
+                "Your Bitbucket token can't write to repositories (permissions: {}); \
+                 submitting and landing will fail",
+                permissions.join(", ")
+            ),
+        }
+    }
+}
+
+impl AuthStatus {
+    /// Problems with the token at `now`, warning about expiry `warning_days` ahead
+    pub fn problems(&self, now: DateTime<Utc>, warning_days: u32) -> Vec<TokenProblem> {
+        let Some(token) = &self.token else {
+            return Vec::new();
+        };
+        let mut problems = Vec::new();
+        if let Some(expires) = token.expires {
+            let days = (expires - now).num_days();
+            if expires <= now {
+                problems.push(TokenProblem::Expired { expires });
+            } else if warning_days > 0 && days < i64::from(warning_days) {
+                problems.push(TokenProblem::ExpiresSoon { expires, days });
+            }
+        }
+        if !token.permissions.is_empty() && !token.can_write() {
+            problems.push(TokenProblem::ReadOnly {
+                permissions: token.permissions.clone(),
+            });
+        }
+        problems
+    }
+}
+
+/// Token check run once per process before the first API request
+pub struct AuthPreflight {
+    cache_path: Option<PathBuf>,
+    fingerprint: String,
+    warning_days: u32,
+    /// Why requests must not go ahead, once checked
+    outcome: OnceCell<Option<String>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedStatus {
+    /// Hash of the token the status describes
+    token: String,
+    status: AuthStatus,
+}
+
+impl AuthPreflight {
+    /// Preflight for the token in `config`, if there is one; OAuth sessions refresh
+    /// their own tokens and need none
+    pub fn new(config: &BitbucketConfig, repo_root: &Path) -> Option<Self> {
+        if config.oauth.client_id.is_some() {
+            return None;
+        }
+        let token = config.token.as_deref().filter(|t| !t.is_empty())?;
+        Some(Self {
+            cache_path: crate::git::resolve_git_dir(repo_root)
+                .ok()
+                .map(|dir| dir.join(CACHE_FILE)),
+            fingerprint: fingerprint(token),
+            warning_days: config.token_warning_days,
+            outcome: OnceCell::new(),
+        })
+    }
+
+    /// Check the token on the first call; later calls repeat the outcome
+    pub(crate) async fn run(&self, client: &BitbucketClient) -> Result<()> {
+        let outcome = self.outcome.get_or_init(|| self.check(client)).await;
+        match outcome {
+            Some(message) => Err(CascadeError::auth(message.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Save `status` so the next commands don't have to ask the server
+    pub fn remember(&self, status: &AuthStatus) {
+        let Some(path) = &self.cache_path else {
+            return;
+        };
+        let cached = CachedStatus {
+            token: self.fingerprint.clone(),
+            status: status.clone(),
+        };
+        if let Err(e) = crate::utils::atomic_file::write_json(path, &cached) {
+            debug!("Could not cache token status: {e}");
+        }
+    }
+
+    async fn check(&self, client: &BitbucketClient) -> Option<String> {
+        let now = Utc::now();
+        let status = match self.cached(now) {
+            Some(status) => status,
+            None => match client.auth_status().await {
+                Ok(status) => {
+                    self.remember(&status);
+                    status
+                }
+                Err(e) => {
+                    // The request itself will report anything serious
+                    debug!("Skipping token check: {e}");
+                    return None;
+                }
+            },
+        };
+
+        let mut blocking = None;
+        for problem in status.problems(now, self.warning_days) {
+            match problem {
+                TokenProblem::Expired { .. } => {
+                    blocking = Some(format!(
+                        "{problem}. Create a new personal access token and run \
+                         'ca config set bitbucket.token <token>'"
+                    ));
+                }
+                _ => {
+                    Output::warning(&problem);
+                    Output::sub_item("Run 'ca auth status' for details");
+                }
+            }
+        }
+        blocking
+    }
+
+    fn cached(&self, now: DateTime<Utc>) -> Option<AuthStatus> {
+        let json = std::fs::read_to_string(self.cache_path.as_ref()?).ok()?;
+        let cached: CachedStatus = serde_json::from_str(&json).ok()?;
+        (cached.token == self.fingerprint
+            && now - cached.status.checked_at < Duration::hours(CHECK_INTERVAL_HOURS))
+        .then_some(cached.status)
+    }
+}
+
+fn fingerprint(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .take(8)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserResponse {
+    display_name: Option<String>,
+    email_address: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RepositoryPage {
+    values: Vec<RepositoryMatch>,
+}
+
+#[derive(Deserialize)]
+struct RepositoryMatch {
+    slug: String,
+    project: ProjectMatch,
+}
+
+#[derive(Deserialize)]
+struct ProjectMatch {
+    key: String,
+}
+
+#[derive(Deserialize)]
+struct AccessTokenPage {
+    values: Vec<AccessToken>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccessToken {
+    name: String,
+    #[serde(default)]
+    permissions: Vec<String>,
+    created_date: Option<i64>,
+    last_authenticated: Option<i64>,
+    expiry_date: Option<i64>,
+    expiry_days: Option<i64>,
+}
+
+impl AccessToken {
+    fn into_details(self) -> TokenDetails {
+        let created = self.created_date.and_then(DateTime::from_timestamp_millis);
+        let expires = self
+            .expiry_date
+            .and_then(DateTime::from_timestamp_millis)
+            .or_else(|| Some(created? + Duration::days(self.expiry_days?)));
+        TokenDetails {
+            name: self.name,
+            permissions: self.permissions,
+            created,
+            expires,
+            last_used: self
+                .last_authenticated
+                .and_then(DateTime::from_timestamp_millis),
+        }
+    }
+}
+
+impl BitbucketClient {
+    /// Who the credentials belong to and, where the server lists them, the token's
+    /// permissions and expiry.
+    ///
+    /// Bitbucket doesn't say which of a user's tokens authenticated a request, so the
+    /// one used most recently is taken to be ours; the lookup itself just used it.
+    pub async fn auth_status(&self) -> Result<AuthStatus> {
+        let (status, text) = self
+            .send_unchecked(
+                Method::GET,
+                &self.server_url("plugins/servlet/applinks/whoami"),
+                None,
+            )
+            .await?;
+        let name = text.trim().to_string();
+        if !status.is_success() || name.is_empty() {
+            return Err(CascadeError::auth(format!(
+                "Bitbucket did not accept the credentials (status {status})"
+            )));
+        }
+
+        let details: Option<UserResponse> = self
+            .get_from_server(&format!("rest/api/1.0/users/{name}"))
+            .await
+            .ok();
+        // OAuth access tokens and credential helper logins aren't the personal access
+        // token, so there is nothing to list
+        let token = if self.uses_oauth() || self.credential_helper_user().is_some() {
+            None
+        } else {
+            self.get_from_server::<AccessTokenPage>(&format!("rest/access-tokens/1.0/users/{name}"))
+                .await
+                .ok()
+                .and_then(|page| {
+                    page.values
+                        .into_iter()
+                        .max_by_key(|token| token.last_authenticated.unwrap_or(i64::MIN))
+                })
+                .map(AccessToken::into_details)
+        };
+
+        Ok(AuthStatus {
+            user: AuthenticatedUser {
+                name,
+                display_name: details.as_ref().and_then(|d| d.display_name.clone()),
+                email: details.and_then(|d| d.email_address),
+            },
+            token,
+            checked_at: Utc::now(),
+        })
+    }
+
+    /// Highest permission the authenticated user holds on the repository, or `None`
+    /// when they can't see it.
+    ///
+    /// Only admins may list a repository's permissions, so each level is checked by
+    /// asking for the repositories the user holds it on, highest first.
+    pub async fn repository_permission(&self) -> Result<Option<RepositoryPermission>> {
+        let (status, text) = self
+            .send_unchecked(Method::GET, &self.api_url(""), None)
+            .await?;
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let info: RepositoryInfo = Self::parse_response(status, text)?;
+        for permission in RepositoryPermission::ALL.into_iter().rev() {
+            let query = url::form_urlencoded::Serializer::new(String::new())
+                .append_pair("name", &info.name)
+                .append_pair("projectname", &info.project.name)
+                .append_pair("permission", permission.as_bitbucket())
+                .append_pair("limit", "100")
+                .finish();
+            let page: RepositoryPage = self
+                .get_from_server(&format!("rest/api/1.0/repos?{query}"))
+                .await?;
+            if page
+                .values
+                .iter()
+                .any(|repo| repo.slug == info.slug && repo.project.key == info.project.key)
+            {
+                return Ok(Some(permission));
+            }
+        }
+        // Everyone who can see the repository can read it, e.g. through public access
+        Ok(Some(RepositoryPermission::Read))
+    }
+
+    async fn get_from_server<T>(&self, path: &str) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let (status, text) = self
+            .send_unchecked(Method::GET, &self.server_url(path), None)
+            .await?;
+        Self::parse_response(status, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(permissions: &[&str], expires: Option<DateTime<Utc>>) -> AuthStatus {
+        AuthStatus {
+            user: AuthenticatedUser {
+                name: "jdoe".to_string(),
+                display_name: None,
+                email: None,
+            },
+            token: Some(TokenDetails {
+                name: "cascade".to_string(),
+                permissions: permissions.iter().map(|p| p.to_string()).collect(),
+                created: None,
+                expires,
+                last_used: None,
+            }),
+            checked_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_token_problems_respect_warning_days() {
+        let now = Utc::now();
+        let writable = ["PROJECT_READ", "REPO_WRITE"];
+
+        assert!(status(&writable, None).problems(now, 14).is_empty());
+        assert!(status(&writable, Some(now + Duration::days(30)))
+            .problems(now, 14)
+            .is_empty());
+        assert!(matches!(
+            status(&writable, Some(now + Duration::days(10))).problems(now, 14)[..],
+            [TokenProblem::ExpiresSoon { days: 9 | 10, .. }]
+        ));
+        assert!(status(&writable, Some(now + Duration::days(10)))
+            .problems(now, 0)
+            .is_empty());
+        assert!(matches!(
+            status(&writable, Some(now - Duration::days(1))).problems(now, 0)[..],
+            [TokenProblem::Expired { .. }]
+        ));
+        assert!(matches!(
+            status(&["REPO_READ"], None).problems(now, 14)[..],
+            [TokenProblem::ReadOnly { .. }]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_preflight_stops_expired_token_and_caches_status() {
+        let mut server = mockito::Server::new_async().await;
+        let whoami = server
+            .mock("GET", "/plugins/servlet/applinks/whoami")
+            .with_body("jdoe")
+            .expect(1)
+            .create_async()
+            .await;
+        let _user = server
+            .mock("GET", "/rest/api/1.0/users/jdoe")
+            .with_body(
+                r#"{"name":"jdoe","displayName":"Jane Doe","emailAddress":"jane@example.com"}"#,
+            )
+            .create_async()
+            .await;
+        let expired = (Utc::now() - Duration::days(2)).timestamp_millis();
+        let _tokens = server
+            .mock("GET", "/rest/access-tokens/1.0/users/jdoe")
+            .with_body(format!(
+                r#"{{"values":[
+                    {{"name":"old","permissions":["REPO_WRITE"],"lastAuthenticated":1}},
+                    {{"name":"cascade","permissions":["REPO_WRITE"],"lastAuthenticated":2,"expiryDate":{expired}}}
+                ]}}"#
+            ))
+            .create_async()
+            .await;
+        let _repo = server
+            .mock("GET", "/rest/api/1.0/projects/TEST/repos/app")
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let repo_dir = tempfile::TempDir::new().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let config = BitbucketConfig {
+            url: server.url(),
+            project: "TEST".to_string(),
+            repo: "app".to_string(),
+            token: Some("secret".to_string()),
+            ..Default::default()
+        };
+
+        let client = BitbucketClient::new(&config).unwrap();
+        let status = client.auth_status().await.unwrap();
+        assert_eq!(status.user.display_name.as_deref(), Some("Jane Doe"));
+        assert_eq!(status.token.as_ref().unwrap().name, "cascade");
+
+        let preflight = AuthPreflight::new(&config, repo_dir.path()).unwrap();
+        preflight.remember(&status);
+        let guarded = BitbucketClient::new(&config)
+            .unwrap()
+            .with_auth_preflight(AuthPreflight::new(&config, repo_dir.path()).unwrap());
+        let err = guarded.test_connection().await.unwrap_err();
+        assert!(err.to_string().contains("expired"), "{err}");
+        // The cached status answered, so the server was only asked once
+        whoami.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_repository_permission_checks_levels_highest_first() {
+        let mut server = mockito::Server::new_async().await;
+        let _repo = server
+            .mock("GET", "/rest/api/1.0/projects/TEST/repos/app/")
+            .with_body(
+                r#"{"id":1,"name":"App","slug":"app","description":null,"public":false,
+                    "project":{"id":2,"key":"TEST","name":"Test","description":null,"public":false},
+                    "links":{"clone":[],"self":[]}}"#,
+            )
+            .create_async()
+            .await;
+        let mut repos = |permission: &str, body: &'static str| {
+            server
+                .mock("GET", "/rest/api/1.0/repos")
+                .match_query(mockito::Matcher::AllOf(vec![
+                    mockito::Matcher::UrlEncoded("name".into(), "App".into()),
+                    mockito::Matcher::UrlEncoded("permission".into(), permission.into()),
+                ]))
+                .with_body(body)
+        };
+        let admin = repos("REPO_ADMIN", r#"{"values":[]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let write = repos(
+            "REPO_WRITE",
+            r#"{"values":[{"slug":"app-fork","project":{"key":"TEST"}},
+                          {"slug":"app","project":{"key":"TEST"}}]}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+        let config = BitbucketConfig {
+            url: server.url(),
+            project: "TEST".to_string(),
+            repo: "app".to_string(),
+            token: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let client = BitbucketClient::new(&config).unwrap();
+        assert_eq!(
+            client.repository_permission().await.unwrap(),
+            Some(RepositoryPermission::Write)
+        );
+        admin.assert_async().await;
+        write.assert_async().await;
+
+        let hidden = BitbucketClient::new(&BitbucketConfig {
+            repo: "secret".to_string(),
+            ..config
+        })
+        .unwrap();
+        let _missing = server
+            .mock("GET", "/rest/api/1.0/projects/TEST/repos/secret/")
+            .with_status(404)
+            .create_async()
+            .await;
+        assert_eq!(hidden.repository_permission().await.unwrap(), None);
+    }
+}
@@ -0,0 +1,235 @@
+use crate::bitbucket::pull_request::{BuildState, BuildStatus};
+use crate::config::BuildSettings;
+use crate::errors::{CascadeError, Result};
+use serde_json::Value;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use tracing::debug;
+
+/// Boxed future returned by [`BuildProvider::build_status`]
+pub type BuildStatusFuture<'a> = Pin<Box<dyn Future<Output = Result<BuildStatus>> + Send + 'a>>;
+
+/// Source of CI build results for a commit.
+///
+/// Bitbucket's build-status API is used when no provider is configured; implement
+/// this for CI systems that don't report their builds to Bitbucket.
+pub trait BuildProvider: Send + Sync {
+    /// Short name used in status messages
+    fn name(&self) -> &str;
+
+    /// Current build status for `commit_hash`
+    fn build_status<'a>(&'a self, commit_hash: &'a str) -> BuildStatusFuture<'a>;
+}
+
+/// Build provider that polls a URL template returning JSON (e.g. a Jenkins job API)
+pub struct UrlBuildProvider {
+    url_template: String,
+    token: Option<String>,
+    client: reqwest::Client,
+}
+
+impl UrlBuildProvider {
+    /// Create a provider from a URL template containing `{commit}` or `{short_commit}`
+    pub fn new(url_template: String, token: Option<String>) -> Result<Self> {
+        if !url_template.contains("{commit}") && !url_template.contains("{short_commit}") {
+            return Err(CascadeError::config(format!(
+                "build.status_url must contain {{commit}} or {{short_commit}}: {url_template}"
+            )));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| CascadeError::config(format!("Failed to create HTTP client: {e}")))?;
+
+        Ok(Self {
+            url_template,
+            token,
+            client,
+        })
+    }
+
+    /// Status URL for a specific commit
+    pub fn url_for(&self, commit_hash: &str) -> String {
+        let short = &commit_hash[..commit_hash.len().min(8)];
+        self.url_template
+            .replace("{commit}", commit_hash)
+            .replace("{short_commit}", short)
+    }
+
+    async fn fetch(&self, commit_hash: &str) -> Result<BuildStatus> {
+        let url = self.url_for(commit_hash);
+        debug!("GET {}", url);
+
+        let mut request = self.client.get(&url);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| CascadeError::bitbucket(format!("Build status request failed: {e}")))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(BuildStatus {
+                state: BuildState::Unknown,
+                url: None,
+                description: Some("No build found for commit".to_string()),
+                context: None,
+            });
+        }
+        if !response.status().is_success() {
+            return Err(CascadeError::bitbucket(format!(
+                "Build status request returned {}",
+                response.status()
+            )));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| CascadeError::bitbucket(format!("Invalid build status response: {e}")))?;
+
+        Ok(parse_build_response(&body))
+    }
+}
+
+impl BuildProvider for UrlBuildProvider {
+    fn name(&self) -> &str {
+        "build.status_url"
+    }
+
+    fn build_status<'a>(&'a self, commit_hash: &'a str) -> BuildStatusFuture<'a> {
+        Box::pin(self.fetch(commit_hash))
+    }
+}
+
+/// Build the provider described by the `build.*` settings, if any
+pub fn configured_build_provider(
+    settings: &BuildSettings,
+) -> Result<Option<Box<dyn BuildProvider>>> {
+    match &settings.status_url {
+        Some(url) => Ok(Some(Box::new(UrlBuildProvider::new(
+            url.clone(),
+            settings.token.clone(),
+        )?))),
+        None => Ok(None),
+    }
+}
+
+/// Interpret a JSON build status document.
+///
+/// Reads the state from `state`, `status` or `result` (Jenkins reports `result: null`
+/// with `building: true` while running), plus optional `url`, `description` and `name`.
+pub fn parse_build_response(body: &Value) -> BuildStatus {
+    let text = |key: &str| body.get(key).and_then(Value::as_str).map(str::to_string);
+
+    let state = if body.get("building").and_then(Value::as_bool) == Some(true) {
+        BuildState::InProgress
+    } else {
+        ["state", "status", "result"]
+            .iter()
+            .find_map(|key| body.get(*key).and_then(Value::as_str))
+            .map(parse_build_state)
+            .unwrap_or(BuildState::Unknown)
+    };
+
+    BuildStatus {
+        state,
+        url: text("url"),
+        description: text("description"),
+        context: text("name").or_else(|| text("fullDisplayName")),
+    }
+}
+
+/// Map the state names used by common CI systems onto [`BuildState`]
+pub fn parse_build_state(state: &str) -> BuildState {
+    match state.to_ascii_uppercase().replace(['-', ' '], "_").as_str() {
+        "SUCCESS" | "SUCCESSFUL" | "SUCCEEDED" | "PASSED" | "PASS" | "OK" => BuildState::Successful,
+        "FAILURE" | "FAILED" | "FAIL" | "ERROR" | "UNSTABLE" => BuildState::Failed,
+        "INPROGRESS" | "IN_PROGRESS" | "RUNNING" | "PENDING" | "QUEUED" | "BUILDING"
+        | "STARTED" => BuildState::InProgress,
+        "CANCELLED" | "CANCELED" | "ABORTED" | "STOPPED" => BuildState::Cancelled,
+        _ => BuildState::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_build_response() {
+        let status = parse_build_response(&json!({
+            "state": "passed",
+            "url": "https://ci.example.com/builds/42",
+            "name": "unit-tests"
+        }));
+        assert_eq!(status.state, BuildState::Successful);
+        assert_eq!(
+            status.url.as_deref(),
+            Some("https://ci.example.com/builds/42")
+        );
+        assert_eq!(status.context.as_deref(), Some("unit-tests"));
+
+        // Jenkins job API shapes
+        let running = parse_build_response(&json!({ "result": null, "building": true }));
+        assert_eq!(running.state, BuildState::InProgress);
+        let unstable = parse_build_response(&json!({ "result": "UNSTABLE", "building": false }));
+        assert_eq!(unstable.state, BuildState::Failed);
+        let aborted = parse_build_response(&json!({ "status": "aborted" }));
+        assert_eq!(aborted.state, BuildState::Cancelled);
+
+        assert_eq!(parse_build_response(&json!({})).state, BuildState::Unknown);
+        assert_eq!(parse_build_state("in-progress"), BuildState::InProgress);
+    }
+
+    #[test]
+    fn test_url_build_provider_template() {
+        assert!(UrlBuildProvider::new("https://ci.example.com/status".to_string(), None).is_err());
+
+        let provider = UrlBuildProvider::new(
+            "https://ci.example.com/commit/{commit}?short={short_commit}".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            provider.url_for("0123456789abcdef"),
+            "https://ci.example.com/commit/0123456789abcdef?short=01234567"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_url_build_provider_fetches_status() {
+        let mut server = mockito::Server::new_async().await;
+        let building = server
+            .mock("GET", "/commit/abc123")
+            .match_header("authorization", "Bearer secret")
+            .with_status(200)
+            .with_body(r#"{"state": "RUNNING"}"#)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("GET", "/commit/def456")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let provider = UrlBuildProvider::new(
+            format!("{}/commit/{{commit}}", server.url()),
+            Some("secret".to_string()),
+        )
+        .unwrap();
+
+        let status = provider.build_status("abc123").await.unwrap();
+        assert_eq!(status.state, BuildState::InProgress);
+        let status = provider.build_status("def456").await.unwrap();
+        assert_eq!(status.state, BuildState::Unknown);
+
+        building.assert_async().await;
+        missing.assert_async().await;
+    }
+}
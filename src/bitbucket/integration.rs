@@ -1,4 +1,3 @@
-use crate::bitbucket::client::BitbucketClient;
 use crate::bitbucket::pull_request::{
     CreatePullRequestRequest, Project, PullRequest, PullRequestManager, PullRequestRef,
     PullRequestState, Repository,
@@ -6,11 +5,171 @@ use crate::bitbucket::pull_request::{
 use crate::cli::output::Output;
 use crate::config::CascadeConfig;
 use crate::errors::{CascadeError, Result};
+use crate::git::trailers::PR_TRAILER;
 use crate::stack::{Stack, StackEntry, StackManager};
 use std::collections::HashMap;
 use tracing::{debug, error};
 use uuid::Uuid;
 
+/// First line of the comment that tracks a PR's place in its stack
+const STACK_UPDATE_HEADER: &str = "🔀 **Stack update**";
+
+/// An open entry in a stack's dependency chain
+#[derive(Debug, Clone)]
+struct ChainLink {
+    branch: String,
+    pr_id: Option<u64>,
+    title: Option<String>,
+    url: Option<String>,
+}
+
+impl ChainLink {
+    /// An entry that has no pull request (yet)
+    fn branch(branch: &str) -> Self {
+        Self {
+            branch: branch.to_string(),
+            pr_id: None,
+            title: None,
+            url: None,
+        }
+    }
+}
+
+/// A force push of a PR's branch, as shown in its update comment
+#[derive(Debug, Clone)]
+struct ForcePush {
+    /// Short hash now on the remote
+    commit: String,
+    /// Diff from the commit reviewers last saw to the new one
+    compare_url: Option<String>,
+}
+
+/// Text of the sticky comment for the PR at `position` in `chain`.
+///
+/// Contains no timestamps, so posting it twice for the same state changes nothing.
+fn stack_update_comment(
+    stack_name: &str,
+    base_branch: &str,
+    chain: &[ChainLink],
+    position: usize,
+    rebased: Option<&ForcePush>,
+    base_change: Option<(&str, &str)>,
+) -> String {
+    let mut text = format!("{STACK_UPDATE_HEADER}\n\n");
+    if let Some(push) = rebased {
+        text.push_str(&format!(
+            "This pull request was rebased and force-pushed (now `{}`). \
+             Its changes are the same, replayed on top of its updated base; review \
+             history and comments are preserved.\n",
+            push.commit
+        ));
+        if let Some(url) = &push.compare_url {
+            text.push_str(&format!("[Changes since last review]({url})\n"));
+        }
+    }
+    if let Some((old, new)) = base_change {
+        text.push_str(&format!(
+            "Its target branch changed from `{old}` to `{new}` because the stack below it \
+             changed.\n"
+        ));
+    }
+    text.push_str(&format!(
+        "\n**Position:** {} of {} in stack `{stack_name}`\n\n**Dependency chain** (merges into `{base_branch}`):\n",
+        position + 1,
+        chain.len()
+    ));
+    for (n, link) in chain.iter().enumerate() {
+        let line = match link.pr_id {
+            Some(pr_id) if n == position => format!(
+                "**#{pr_id} {}** ← this pull request",
+                link.title.as_deref().unwrap_or(&link.branch)
+            ),
+            Some(pr_id) => {
+                let title = link.title.as_deref().unwrap_or(&link.branch);
+                match &link.url {
+                    Some(url) => format!("[#{pr_id}]({url}) {title}"),
+                    None => format!("#{pr_id} {title}"),
+                }
+            }
+            None => format!("`{}` (no pull request yet)", link.branch),
+        };
+        text.push_str(&format!("{}. {line}\n", n + 1));
+    }
+    text
+}
+
+/// A PR description without the stack footer Cascade appends, or None if nothing else is left
+fn strip_stack_footer(description: &str) -> Option<String> {
+    description
+        .split("---\n\n## 📚 Stack:")
+        .next()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Branch each entry's pull request should merge into: the nearest entry below it that is
+/// still in the chain, or `base_branch`. `in_chain` holds each entry's branch and whether
+/// it is still in the chain (not merged or declined).
+fn chain_targets(base_branch: &str, in_chain: &[(&str, bool)]) -> Vec<String> {
+    let mut target = base_branch.to_string();
+    in_chain
+        .iter()
+        .map(|(branch, open)| {
+            let expected = target.clone();
+            if *open {
+                target = branch.to_string();
+            }
+            expected
+        })
+        .collect()
+}
+
+/// An open pull request's branches next to the ones its entry's place in the stack calls for
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullRequestChainCheck {
+    pub pr_id: u64,
+    /// Branch of the entry the pull request belongs to
+    pub branch: String,
+    /// Branch the pull request is opened from
+    pub source: String,
+    /// Branch the pull request merges into
+    pub target: String,
+    /// Nearest open entry branch below the entry, or the stack's base branch
+    pub expected_target: String,
+}
+
+impl PullRequestChainCheck {
+    pub fn source_drifted(&self) -> bool {
+        self.source != self.branch
+    }
+
+    pub fn target_drifted(&self) -> bool {
+        self.target != self.expected_target
+    }
+}
+
+/// What happened to an entry's pull request after its deleted branch was pushed again
+#[derive(Debug, Clone, PartialEq)]
+pub enum PullRequestRecovery {
+    /// The entry was pushed but never submitted
+    NoPullRequest,
+    StillOpen(u64),
+    /// The pull request had been declined and was reopened
+    Reopened(u64),
+    /// The pull request could not be reopened, so a new one replaces it
+    Replaced {
+        old: u64,
+        new: u64,
+    },
+}
+
+/// An entry branch that was deleted on origin and pushed again from the local branch
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestoredBranch {
+    pub branch: String,
+    pub pull_request: PullRequestRecovery,
+}
+
 /// High-level integration between stacks and Bitbucket
 pub struct BitbucketIntegration {
     stack_manager: StackManager,
@@ -26,8 +185,11 @@ impl BitbucketIntegration {
             .as_ref()
             .ok_or_else(|| CascadeError::config("Bitbucket configuration not found"))?;
 
-        let client = BitbucketClient::new(bitbucket_config)?;
-        let pr_manager = PullRequestManager::new(client);
+        let pr_manager = PullRequestManager::from_config(
+            bitbucket_config,
+            &config.cascade.build,
+            stack_manager.repo_path(),
+        )?;
 
         Ok(Self {
             stack_manager,
@@ -105,6 +267,314 @@ impl BitbucketIntegration {
         Ok(updated_prs)
     }
 
+    /// Rewrite an entry's PR title and description from its commit message.
+    /// A `[DRAFT]` prefix is kept; with a description template configured the existing
+    /// description is kept and only the stack footer is regenerated.
+    /// Returns the PR ID, or None if the entry has no PR.
+    pub async fn update_pr_from_commit_message(
+        &self,
+        stack_id: &Uuid,
+        entry_id: &Uuid,
+    ) -> Result<Option<u64>> {
+        let stack = self
+            .stack_manager
+            .get_stack(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+        let entry = stack
+            .get_entry(entry_id)
+            .ok_or_else(|| CascadeError::config(format!("Entry {entry_id} not found")))?;
+
+        let Some(pr_id) = entry
+            .pull_request_id
+            .as_deref()
+            .and_then(|id| id.parse::<u64>().ok())
+        else {
+            return Ok(None);
+        };
+
+        let pr = self.pr_manager.get_pull_request(pr_id).await?;
+        let (title, description) = self.pr_text_from_commit(stack, entry, &pr)?;
+
+        self.pr_manager
+            .update_pull_request(pr_id, Some(title), description)
+            .await?;
+
+        Ok(Some(pr_id))
+    }
+
+    /// Title and description (with stack footer) an existing PR should have for `entry`.
+    /// A `[DRAFT]` prefix is kept. With a description template configured the PR's own
+    /// description is kept, or the template is applied again when it is empty.
+    fn pr_text_from_commit(
+        &self,
+        stack: &Stack,
+        entry: &StackEntry,
+        pr: &PullRequest,
+    ) -> Result<(String, Option<String>)> {
+        let mut title = entry
+            .message
+            .lines()
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        if pr.title.starts_with("[DRAFT]") && !title.starts_with("[DRAFT]") {
+            title = format!("[DRAFT] {title}");
+        }
+
+        let description = if let Some(template) = self.description_template(stack) {
+            pr.description
+                .as_deref()
+                .and_then(strip_stack_footer)
+                .or_else(|| Some(template.clone()))
+        } else {
+            let body = entry
+                .message
+                .lines()
+                .skip(1)
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim()
+                .to_string();
+            (!body.is_empty()).then_some(body)
+        };
+        let description = self.add_stack_hierarchy_footer(description, stack, entry)?;
+
+        Ok((title, description))
+    }
+
+    /// Title and description (without the stack footer) an entry's PR has, or would be
+    /// created with, for editing before submit. `[DRAFT]` prefixes are left out.
+    pub async fn proposed_pr_text(
+        &self,
+        stack_id: &Uuid,
+        entry_id: &Uuid,
+    ) -> Result<(String, Option<String>)> {
+        let stack = self
+            .stack_manager
+            .get_stack(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+        let entry = stack
+            .get_entry(entry_id)
+            .ok_or_else(|| CascadeError::config(format!("Entry {entry_id} not found")))?;
+
+        if let Some(pr_id) = entry
+            .pull_request_id
+            .as_deref()
+            .and_then(|id| id.parse::<u64>().ok())
+        {
+            let pr = self.pr_manager.get_pull_request(pr_id).await?;
+            let title = pr.title.trim_start_matches("[DRAFT]").trim().to_string();
+            return Ok((
+                title,
+                pr.description.as_deref().and_then(strip_stack_footer),
+            ));
+        }
+
+        let title = entry
+            .message
+            .lines()
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        let description = match self.description_template(stack) {
+            Some(template) => Some(template.clone()),
+            None => {
+                let body = entry
+                    .message
+                    .lines()
+                    .skip(1)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+                    .trim()
+                    .to_string();
+                (!body.is_empty()).then_some(body)
+            }
+        };
+        Ok((title, description))
+    }
+
+    /// Publish the current state of an already submitted entry: force-push its branch,
+    /// refresh the PR title and description from the commit (or the given overrides)
+    /// and add any of the stack's reviewers the PR is missing.
+    pub async fn update_submitted_entry(
+        &mut self,
+        stack_id: &Uuid,
+        entry_id: &Uuid,
+        title: Option<String>,
+        description: Option<String>,
+    ) -> Result<PullRequest> {
+        let stack = self
+            .stack_manager
+            .get_stack(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?
+            .clone();
+        let entry = stack
+            .get_entry(entry_id)
+            .ok_or_else(|| CascadeError::config(format!("Entry {entry_id} not found in stack")))?;
+        let pr_id = entry
+            .pull_request_id
+            .as_deref()
+            .and_then(|id| id.parse::<u64>().ok())
+            .ok_or_else(|| {
+                CascadeError::validation(format!(
+                    "Entry '{}' has no pull request to update",
+                    entry.branch
+                ))
+            })?;
+
+        let pr = self.pr_manager.get_pull_request(pr_id).await?;
+        if !pr.is_open() {
+            return Err(CascadeError::validation(format!(
+                "PR #{pr_id} is {:?} and can no longer be updated",
+                pr.state
+            )));
+        }
+
+        if let Err(integrity_error) = stack.validate_git_integrity(self.stack_manager.git_repo()) {
+            return Err(CascadeError::validation(format!(
+                "Cannot update entry from corrupted stack '{}':\n{}",
+                stack.name, integrity_error
+            )));
+        }
+
+        let (commit_title, commit_description) = self.pr_text_from_commit(&stack, entry, &pr)?;
+        let title = match title {
+            Some(title) if pr.title.starts_with("[DRAFT]") && !title.starts_with("[DRAFT]") => {
+                format!("[DRAFT] {title}")
+            }
+            Some(title) => title,
+            None => commit_title,
+        };
+        let description = match description {
+            Some(desc) => Some(desc),
+            None => commit_description.as_deref().and_then(strip_stack_footer),
+        };
+        self.config
+            .cascade
+            .submit_policy
+            .check_text(&title, description.as_deref())?;
+        let description = self.add_stack_hierarchy_footer(description, &stack, entry)?;
+        self.run_submit_check(entry)?;
+
+        self.push_entry_branch(&stack.id, entry, true)?;
+
+        let reviewers = self.reviewers_for(&stack);
+
+        let pr = self
+            .pr_manager
+            .refresh_pull_request(pr_id, Some(title), description, &reviewers)
+            .await?;
+        self.record_pr_trailer(entry, &pr);
+        Ok(pr)
+    }
+
+    /// Note the pull request's URL on the entry's commit. Trailers are a convenience for
+    /// reading history, so failing to write one never fails the submit.
+    fn record_pr_trailer(&self, entry: &StackEntry, pr: &PullRequest) {
+        let Some(url) = pr.web_url() else {
+            return;
+        };
+        if let Err(e) = self
+            .stack_manager
+            .git_repo()
+            .add_trailers(&entry.commit_hash, &[(PR_TRAILER, &url)])
+        {
+            debug!("Failed to record PR trailer: {}", e);
+        }
+    }
+
+    /// Description new pull requests of the stack start with: the stack's own template if
+    /// it sets one, otherwise `cascade.pr_description_template`
+    fn description_template<'a>(&'a self, stack: &'a Stack) -> Option<&'a String> {
+        stack.overrides.pr_description_template.as_ref().or(self
+            .config
+            .cascade
+            .pr_description_template
+            .as_ref())
+    }
+
+    /// Reviewers the stack's pull requests get: the stack's own list if it sets one,
+    /// otherwise `bitbucket.default_reviewers`
+    fn reviewers_for(&self, stack: &Stack) -> Vec<String> {
+        stack.overrides.reviewers.clone().unwrap_or_else(|| {
+            self.config
+                .bitbucket
+                .as_ref()
+                .map(|bitbucket| bitbucket.default_reviewers.clone())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Run the submit policy's check command on the entry's commit, if one is configured
+    fn run_submit_check(&self, entry: &StackEntry) -> Result<()> {
+        let Some(command) = self.config.cascade.submit_policy.check_command.as_deref() else {
+            return Ok(());
+        };
+
+        debug!(
+            "Running submit check '{}' on {}",
+            command,
+            entry.short_hash()
+        );
+        let output = self
+            .stack_manager
+            .git_repo()
+            .run_in_temp_worktree(&entry.commit_hash, command)?;
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let details = if stderr.trim().is_empty() {
+            stdout
+        } else {
+            stderr
+        };
+        let tail: Vec<&str> = details.trim().lines().rev().take(10).collect();
+        Err(CascadeError::validation(format!(
+            "'{command}' failed on {} ({}); fix it before submitting \
+             (cascade.submit_policy.check_command):\n{}",
+            entry.short_hash(),
+            entry.branch,
+            tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+        )))
+    }
+
+    /// Push an entry's branch, force-pushing with its lease when the PR or remote branch
+    /// already exists, and record the pushed commit as the next lease
+    fn push_entry_branch(
+        &mut self,
+        stack_id: &Uuid,
+        entry: &StackEntry,
+        force: bool,
+    ) -> Result<()> {
+        let git_repo = self.stack_manager.git_repo();
+
+        if force {
+            // Set env var to skip interactive confirmation during submit (user already confirmed submit action)
+            std::env::set_var("FORCE_PUSH_NO_CONFIRM", "1");
+            let result = git_repo
+                .force_push_single_branch(&entry.branch, entry.remote_commit_hash.as_deref());
+            std::env::remove_var("FORCE_PUSH_NO_CONFIRM");
+            result.map_err(|e| CascadeError::bitbucket(e.to_string()))?;
+        } else {
+            // Regular push for brand new submissions
+            git_repo
+                .push(&entry.branch)
+                .map_err(|e| CascadeError::bitbucket(e.to_string()))?;
+        }
+
+        // Branch pushed successfully; remember where it is as the next force push's lease
+        if let Ok(pushed_commit) = git_repo.get_branch_head(&entry.branch) {
+            self.stack_manager
+                .set_entry_remote_commit(stack_id, &entry.id, pushed_commit)?;
+        }
+        Ok(())
+    }
+
     /// Submit a single stack entry as a pull request
     pub async fn submit_entry(
         &mut self,
@@ -136,6 +606,13 @@ impl BitbucketIntegration {
             )));
         }
 
+        // Determine target branch (parent entry's branch or stack base) and the request,
+        // which also enforces the submit policy before anything is pushed
+        let target_branch = self.get_target_branch(&stack, entry)?;
+        let pr_request =
+            self.create_pr_request(&stack, entry, &target_branch, title, description, draft)?;
+        self.run_submit_check(entry)?;
+
         // Push branch to remote
         let git_repo = self.stack_manager.git_repo();
 
@@ -144,22 +621,8 @@ impl BitbucketIntegration {
         // 2. Branch exists on remote but no PR yet (edge case: pushed but PR creation failed)
         let branch_has_remote = git_repo.get_upstream_branch(&entry.branch)?.is_some();
         let needs_force_push = entry.pull_request_id.is_some() || branch_has_remote;
-
-        if needs_force_push {
-            // Force push for existing PRs or branches already on remote
-            // Set env var to skip interactive confirmation during submit (user already confirmed submit action)
-            std::env::set_var("FORCE_PUSH_NO_CONFIRM", "1");
-            let result = git_repo.force_push_single_branch(&entry.branch);
-            std::env::remove_var("FORCE_PUSH_NO_CONFIRM");
-            result.map_err(|e| CascadeError::bitbucket(e.to_string()))?;
-        } else {
-            // Regular push for brand new submissions
-            git_repo
-                .push(&entry.branch)
-                .map_err(|e| CascadeError::bitbucket(e.to_string()))?;
-        }
-
-        // Branch pushed successfully
+        self.push_entry_branch(stack_id, entry, needs_force_push)?;
+        let git_repo = self.stack_manager.git_repo();
 
         // Mark as pushed in metadata
         if let Some(commit_meta) = self
@@ -174,9 +637,6 @@ impl BitbucketIntegration {
             // For now, we'll track this as a future enhancement
         }
 
-        // Determine target branch (parent entry's branch or stack base)
-        let target_branch = self.get_target_branch(&stack, entry)?;
-
         // Ensure target branch is also pushed to remote (if it's not the base branch)
         if target_branch != stack.base_branch {
             // Ensure target branch is pushed to remote
@@ -193,9 +653,6 @@ impl BitbucketIntegration {
         }
 
         // Create pull request
-        let pr_request =
-            self.create_pr_request(&stack, entry, &target_branch, title, description, draft)?;
-
         let pr = match self.pr_manager.create_pull_request(pr_request).await {
             Ok(pr) => pr,
             Err(e) => {
@@ -211,6 +668,31 @@ impl BitbucketIntegration {
         // Update stack manager with PR information
         self.stack_manager
             .submit_entry(stack_id, entry_id, pr.id.to_string())?;
+        if let Err(e) = crate::stack::PrSummaryCache::record_titles(
+            self.stack_manager.repo_path(),
+            [(pr.id.to_string(), pr.title.clone())],
+        ) {
+            tracing::debug!("Failed to record PR title: {}", e);
+        }
+        self.record_pr_trailer(entry, &pr);
+
+        // Reviewers set on the stack are added on top of the server's default reviewers
+        let pr = match stack.overrides.reviewers.as_deref() {
+            Some(reviewers) if !reviewers.is_empty() => {
+                match self
+                    .pr_manager
+                    .refresh_pull_request(pr.id, None, None, reviewers)
+                    .await
+                {
+                    Ok(updated) => updated,
+                    Err(e) => {
+                        Output::warning(format!("Could not add reviewers to PR #{}: {}", pr.id, e));
+                        pr
+                    }
+                }
+            }
+            _ => pr,
+        };
 
         // Pull request created for entry
         Ok(pr)
@@ -259,6 +741,224 @@ impl BitbucketIntegration {
         Ok(status)
     }
 
+    /// Compare the branches of the stack's open pull requests with the chain the stack
+    /// expects, e.g. after someone retargeted a pull request in Bitbucket by hand
+    pub async fn check_pull_request_chain(
+        &self,
+        stack_id: &Uuid,
+    ) -> Result<Vec<PullRequestChainCheck>> {
+        let stack = self
+            .stack_manager
+            .get_stack(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+
+        let mut prs = Vec::new();
+        for entry in &stack.entries {
+            let pr_id = entry
+                .pull_request_id
+                .as_deref()
+                .and_then(|id| id.parse::<u64>().ok());
+            let pr = match pr_id {
+                Some(pr_id) => match self.pr_manager.get_pull_request(pr_id).await {
+                    Ok(pr) => Some(pr),
+                    Err(e) => {
+                        debug!("Could not fetch PR #{} for chain check: {}", pr_id, e);
+                        None
+                    }
+                },
+                None => None,
+            };
+            prs.push(pr);
+        }
+
+        // Like the retarget pass after a rebase, the provider's PR state wins over local flags
+        let in_chain: Vec<(&str, bool)> = stack
+            .entries
+            .iter()
+            .zip(&prs)
+            .map(|(entry, pr)| {
+                let open = match pr {
+                    Some(pr) => pr.state == PullRequestState::Open,
+                    None => !entry.is_merged,
+                };
+                (entry.branch.as_str(), open)
+            })
+            .collect();
+        let targets = chain_targets(&stack.base_branch, &in_chain);
+
+        Ok(stack
+            .entries
+            .iter()
+            .zip(prs)
+            .zip(targets)
+            .filter_map(|((entry, pr), expected_target)| {
+                let pr = pr.filter(|pr| pr.state == PullRequestState::Open)?;
+                Some(PullRequestChainCheck {
+                    pr_id: pr.id,
+                    branch: entry.branch.clone(),
+                    source: pr.from_ref.display_id.clone(),
+                    target: pr.to_ref.display_id.clone(),
+                    expected_target,
+                })
+            })
+            .collect())
+    }
+
+    /// Entries whose pull request has merged in Bitbucket. Unlike
+    /// `check_enhanced_stack_status`, nothing is recorded on the stack.
+    pub async fn merged_entries(&self, stack_id: &Uuid) -> Result<Vec<Uuid>> {
+        let stack = self
+            .stack_manager
+            .get_stack(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+
+        let mut merged = Vec::new();
+        for entry in &stack.entries {
+            let Some(pr_id) = entry
+                .pull_request_id
+                .as_deref()
+                .and_then(|id| id.parse::<u64>().ok())
+            else {
+                continue;
+            };
+            match self.pr_manager.get_pull_request(pr_id).await {
+                Ok(pr) if pr.state == PullRequestState::Merged => merged.push(entry.id),
+                Ok(_) => {}
+                Err(e) => debug!("Could not fetch PR #{} for merge check: {}", pr_id, e),
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Point a pull request back at the target branch the chain expects
+    pub async fn retarget_to_chain(&self, check: &PullRequestChainCheck) -> Result<()> {
+        self.pr_manager
+            .retarget_pull_request(check.pr_id, &check.expected_target)
+            .await
+            .map(|_| ())
+    }
+
+    /// Entries that were pushed or submitted but whose branch no longer exists on origin,
+    /// e.g. after a repository cleanup, bottom of the stack first
+    pub fn deleted_remote_branches(&self, stack_id: &Uuid) -> Result<Vec<StackEntry>> {
+        let stack = self
+            .stack_manager
+            .get_stack(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+        let git_repo = self.stack_manager.git_repo();
+
+        let mut deleted = Vec::new();
+        for entry in &stack.entries {
+            let pushed = entry.pull_request_id.is_some() || entry.remote_commit_hash.is_some();
+            // Without a local branch there is nothing to push back
+            if entry.is_merged || !pushed || !git_repo.branch_exists(&entry.branch) {
+                continue;
+            }
+            if !git_repo.remote_branch_exists(&entry.branch)? {
+                deleted.push(entry.clone());
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Push entry branches that were deleted on origin back from their local branches, and
+    /// reopen their declined pull requests, or replace the ones that can't be reopened.
+    /// Entries whose pull request was merged are left alone: deleting a merged branch is
+    /// normal cleanup.
+    pub async fn restore_deleted_branches(
+        &mut self,
+        stack_id: &Uuid,
+    ) -> Result<Vec<RestoredBranch>> {
+        let mut restored = Vec::new();
+        for entry in self.deleted_remote_branches(stack_id)? {
+            let pr_id = entry
+                .pull_request_id
+                .as_deref()
+                .and_then(|id| id.parse::<u64>().ok());
+            let pr = match pr_id {
+                Some(pr_id) => Some(self.pr_manager.get_pull_request(pr_id).await?),
+                None => None,
+            };
+            if let Some(pr) = pr
+                .as_ref()
+                .filter(|pr| pr.state == PullRequestState::Merged)
+            {
+                debug!("Not restoring '{}': PR #{} is merged", entry.branch, pr.id);
+                continue;
+            }
+
+            // The lease refers to a branch that no longer exists, so this is a plain push
+            self.push_entry_branch(stack_id, &entry, false)
+                .map_err(|e| {
+                    CascadeError::bitbucket(format!(
+                        "Could not push '{}' back to origin: {e}",
+                        entry.branch
+                    ))
+                })?;
+
+            let pull_request = match pr {
+                None => PullRequestRecovery::NoPullRequest,
+                Some(pr) if pr.state == PullRequestState::Open => {
+                    PullRequestRecovery::StillOpen(pr.id)
+                }
+                Some(pr) => match self.pr_manager.reopen_pull_request(pr.id).await {
+                    Ok(_) => PullRequestRecovery::Reopened(pr.id),
+                    Err(e) => {
+                        debug!("Could not reopen PR #{}: {}", pr.id, e);
+                        let new_pr = self.replace_pull_request(stack_id, &entry, pr.id).await?;
+                        PullRequestRecovery::Replaced {
+                            old: pr.id,
+                            new: new_pr,
+                        }
+                    }
+                },
+            };
+            restored.push(RestoredBranch {
+                branch: entry.branch.clone(),
+                pull_request,
+            });
+        }
+        Ok(restored)
+    }
+
+    /// Open a new pull request for an entry whose old one can't be reopened, and link it
+    async fn replace_pull_request(
+        &mut self,
+        stack_id: &Uuid,
+        entry: &StackEntry,
+        old_pr_id: u64,
+    ) -> Result<u64> {
+        let stack = self
+            .stack_manager
+            .get_stack(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?
+            .clone();
+        let target_branch = self.get_target_branch(&stack, entry)?;
+        let request = self.create_pr_request(&stack, entry, &target_branch, None, None, false)?;
+        let pr = self
+            .pr_manager
+            .create_pull_request(request)
+            .await
+            .map_err(|e| {
+                CascadeError::bitbucket(format!(
+                    "PR #{old_pr_id} could not be reopened and no new pull request could be \
+                 created for '{}': {e}",
+                    entry.branch
+                ))
+            })?;
+        self.stack_manager
+            .submit_entry(stack_id, &entry.id, pr.id.to_string())?;
+
+        let comment = format!(
+            "Replaces #{old_pr_id}, which was closed when its branch was deleted and could \
+             not be reopened."
+        );
+        if let Err(e) = self.pr_manager.add_comment(pr.id, &comment).await {
+            debug!("Could not comment on PR #{}: {}", pr.id, e);
+        }
+        Ok(pr.id)
+    }
+
     /// List all pull requests for the repository
     pub async fn list_pull_requests(
         &self,
@@ -345,11 +1045,11 @@ impl BitbucketIntegration {
         }
 
         let description = {
-            // Priority order: 1) Template (if configured), 2) User description, 3) Commit message body, 4) None
-            if let Some(template) = &self.config.cascade.pr_description_template {
-                Some(template.clone()) // Always use template if configured
-            } else if let Some(desc) = description {
-                Some(desc) // Use provided description if no template
+            // Priority order: 1) User description, 2) Template (if configured), 3) Commit message body, 4) None
+            if let Some(desc) = description {
+                Some(desc) // Given with --description or written in the submit editor
+            } else if let Some(template) = self.description_template(stack) {
+                Some(template.clone())
             } else if entry.message.lines().count() > 1 {
                 // Fallback to commit message body if no template and no description
                 Some(
@@ -367,6 +1067,11 @@ impl BitbucketIntegration {
             }
         };
 
+        self.config
+            .cascade
+            .submit_policy
+            .check_text(&title, description.as_deref())?;
+
         // Add stack hierarchy footer to description
         let description_with_footer = self.add_stack_hierarchy_footer(description, stack, entry)?;
 
@@ -388,7 +1093,24 @@ impl BitbucketIntegration {
     ) -> Result<Option<String>> {
         let hierarchy = self.generate_stack_hierarchy(stack, current_entry)?;
 
-        let footer = format!("\n\n---\n\n## 📚 Stack: {}\n\n{}", stack.name, hierarchy);
+        // Bitbucket Server has no pull request labels, so they're listed in the footer
+        let labels = stack.pr_labels(current_entry);
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "**Labels:** {}\n\n",
+                labels
+                    .iter()
+                    .map(|label| format!("`{label}`"))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            )
+        };
+        let footer = format!(
+            "\n\n---\n\n## 📚 Stack: {}\n\n{labels}{}",
+            stack.name, hierarchy
+        );
 
         match description {
             Some(desc) => Ok(Some(format!("{desc}{footer}"))),
@@ -472,6 +1194,8 @@ impl BitbucketIntegration {
             .clone();
 
         let mut updated_branches = Vec::new();
+        // PRs whose branch was force-pushed
+        let mut rebased: HashMap<u64, ForcePush> = HashMap::new();
 
         for entry in &stack.entries {
             // Check if this entry has an existing PR and was remapped to a new branch
@@ -583,35 +1307,42 @@ impl BitbucketIntegration {
 
                             // Force push the new branch content to the old branch name
                             // This preserves the PR while updating its contents
-                            match self
-                                .stack_manager
-                                .git_repo()
-                                .force_push_branch(&entry.branch, new_branch)
-                            {
+                            match self.stack_manager.git_repo().force_push_branch_with_lease(
+                                &entry.branch,
+                                new_branch,
+                                entry.remote_commit_hash.as_deref(),
+                            ) {
                                 Ok(_) => {
                                     debug!(
                                         "Successfully force-pushed {} to preserve PR #{}",
                                         entry.branch, pr_id
                                     );
-
-                                    // Add a comment explaining the rebase
-                                    let rebase_comment = format!(
-                                        "🔄 **Automatic rebase completed**\n\n\
-                                        This PR has been automatically rebased onto the latest `{}`.\n\
-                                        - Updated commit: `{}`\n\
-                                        - All review history and comments are preserved",
-                                        stack.base_branch,
-                                        &entry.commit_hash[..8]
-                                    );
-
-                                    if let Err(e) =
-                                        self.pr_manager.add_comment(pr_id, &rebase_comment).await
+                                    if let Ok(pushed_commit) =
+                                        self.stack_manager.git_repo().get_branch_head(new_branch)
                                     {
-                                        tracing::debug!(
-                                            "Failed to add rebase comment to PR #{}: {}",
+                                        // The lease is what reviewers last saw; diff from it
+                                        let compare_url =
+                                            entry.remote_commit_hash.as_deref().and_then(|old| {
+                                                pr_status.pr.compare_url(old, &pushed_commit)
+                                            });
+                                        rebased.insert(
                                             pr_id,
-                                            e
+                                            ForcePush {
+                                                commit: pushed_commit[..8].to_string(),
+                                                compare_url,
+                                            },
                                         );
+                                        if let Err(e) = self.stack_manager.set_entry_remote_commit(
+                                            &stack.id,
+                                            &entry.id,
+                                            pushed_commit,
+                                        ) {
+                                            tracing::warn!(
+                                                "Failed to record pushed commit for entry {}: {}",
+                                                entry.id,
+                                                e
+                                            );
+                                        }
                                     }
 
                                     updated_branches.push(format!(
@@ -666,11 +1397,15 @@ impl BitbucketIntegration {
         // Derives correct targets from actual Bitbucket PR state (not local is_merged flags)
         // to avoid stale metadata causing wrong retargets.
         let mut prev_open_branch = stack.base_branch.clone();
+        // Open entries in stack order, for the dependency chain in update comments
+        let mut chain: Vec<ChainLink> = Vec::new();
+        let mut retargeted: HashMap<u64, (String, String)> = HashMap::new();
         for entry in &stack.entries {
             let Some(pr_id_str) = &entry.pull_request_id else {
                 // Entry without a PR but still in the chain
                 if !entry.is_merged {
                     prev_open_branch = entry.branch.clone();
+                    chain.push(ChainLink::branch(&entry.branch));
                 }
                 continue;
             };
@@ -686,6 +1421,10 @@ impl BitbucketIntegration {
                     // Can't determine state; treat as open to preserve chain
                     if !entry.is_merged {
                         prev_open_branch = entry.branch.clone();
+                        chain.push(ChainLink {
+                            pr_id: Some(pr_id),
+                            ..ChainLink::branch(&entry.branch)
+                        });
                     }
                     continue;
                 }
@@ -714,6 +1453,8 @@ impl BitbucketIntegration {
                 {
                     Ok(_) => {
                         Output::success(format!("Retargeted PR #{pr_id} → {prev_open_branch}"));
+                        retargeted
+                            .insert(pr_id, (current_target.clone(), prev_open_branch.clone()));
                     }
                     Err(e) => {
                         Output::warning(format!(
@@ -725,6 +1466,41 @@ impl BitbucketIntegration {
 
             // This open PR's branch becomes the target for the next entry
             prev_open_branch = entry.branch.clone();
+            chain.push(ChainLink {
+                branch: entry.branch.clone(),
+                pr_id: Some(pr_id),
+                title: Some(pr.title.clone()),
+                url: pr.web_url(),
+            });
+        }
+
+        // One sticky comment per PR explains the force-push or new base to reviewers
+        for (position, link) in chain.iter().enumerate() {
+            let Some(pr_id) = link.pr_id else {
+                continue;
+            };
+            let rebased_to = rebased.get(&pr_id);
+            let base_change = retargeted
+                .get(&pr_id)
+                .map(|(old, new)| (old.as_str(), new.as_str()));
+            if rebased_to.is_none() && base_change.is_none() {
+                continue;
+            }
+            let text = stack_update_comment(
+                &stack.name,
+                &stack.base_branch,
+                &chain,
+                position,
+                rebased_to,
+                base_change,
+            );
+            if let Err(e) = self
+                .pr_manager
+                .upsert_sticky_comment(pr_id, STACK_UPDATE_HEADER, &text)
+                .await
+            {
+                tracing::debug!("Failed to update stack comment on PR #{}: {}", pr_id, e);
+            }
         }
 
         Ok(updated_branches)
@@ -784,6 +1560,50 @@ impl BitbucketIntegration {
         Ok(())
     }
 
+    /// Record PR states fetched from the provider on the stack: merged PRs mark their entry
+    /// merged, declined ones detach it so it can be submitted again, and entries stranded
+    /// on top of newly merged ones flag the stack as needing a sync
+    fn reconcile_pr_states(&mut self, stack_id: &Uuid, pr_states: &[(Uuid, PullRequestState)]) {
+        let Some(stack) = self.stack_manager.get_stack(stack_id).cloned() else {
+            return;
+        };
+
+        let mut newly_merged = false;
+        for (entry_id, state) in pr_states {
+            let Some(entry) = stack.entry_map.get(entry_id) else {
+                continue;
+            };
+            let result = match state {
+                PullRequestState::Merged if !entry.is_merged => {
+                    newly_merged = true;
+                    self.stack_manager
+                        .set_entry_merged(stack_id, entry_id, true)
+                }
+                // Entries that landed another way (collapse, upstream patch) keep their state
+                PullRequestState::Declined if !entry.is_merged => {
+                    self.stack_manager.unsubmit_entry(stack_id, entry_id)
+                }
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to persist PR state for entry {}: {}", entry_id, e);
+            }
+        }
+
+        let has_unmerged = self
+            .stack_manager
+            .get_stack(stack_id)
+            .is_some_and(|s| s.entries.iter().any(|e| !e.is_merged));
+        if newly_merged && has_unmerged {
+            if let Err(e) = self
+                .stack_manager
+                .set_stack_status(stack_id, crate::stack::StackStatus::NeedsSync)
+            {
+                tracing::warn!("Failed to update stack status: {}", e);
+            }
+        }
+    }
+
     /// Check the enhanced status of all pull requests in a stack
     pub async fn check_enhanced_stack_status(
         &mut self,
@@ -808,7 +1628,7 @@ impl BitbucketIntegration {
             enhanced_statuses: Vec::new(),
         };
 
-        let mut merged_updates: Vec<(Uuid, bool)> = Vec::new();
+        let mut pr_states: Vec<(Uuid, PullRequestState)> = Vec::new();
 
         for entry in &stack.entries {
             if let Some(pr_id_str) = &entry.pull_request_id {
@@ -821,15 +1641,15 @@ impl BitbucketIntegration {
                             match enhanced_status.pr.state {
                                 crate::bitbucket::pull_request::PullRequestState::Open => {
                                     status.open_prs += 1;
-                                    merged_updates.push((entry.id, false));
+                                    pr_states.push((entry.id, PullRequestState::Open));
                                 }
                                 crate::bitbucket::pull_request::PullRequestState::Merged => {
                                     status.merged_prs += 1;
-                                    merged_updates.push((entry.id, true));
+                                    pr_states.push((entry.id, PullRequestState::Merged));
                                 }
                                 crate::bitbucket::pull_request::PullRequestState::Declined => {
                                     status.declined_prs += 1;
-                                    merged_updates.push((entry.id, false));
+                                    pr_states.push((entry.id, PullRequestState::Declined));
                                 }
                             }
                             status.pull_requests.push(enhanced_status.pr.clone());
@@ -847,15 +1667,15 @@ impl BitbucketIntegration {
                                     match pr.state {
                                         crate::bitbucket::pull_request::PullRequestState::Open => {
                                             status.open_prs += 1;
-                                            merged_updates.push((entry.id, false));
+                                            pr_states.push((entry.id, PullRequestState::Open));
                                         }
                                         crate::bitbucket::pull_request::PullRequestState::Merged => {
                                             status.merged_prs += 1;
-                                            merged_updates.push((entry.id, true));
+                                            pr_states.push((entry.id, PullRequestState::Merged));
                                         }
                                         crate::bitbucket::pull_request::PullRequestState::Declined => {
                                             status.declined_prs += 1;
-                                            merged_updates.push((entry.id, false));
+                                            pr_states.push((entry.id, PullRequestState::Declined));
                                         }
                                     }
                                     status.pull_requests.push(pr);
@@ -872,19 +1692,50 @@ impl BitbucketIntegration {
 
         drop(stack);
 
-        if !merged_updates.is_empty() {
-            for (entry_id, merged) in merged_updates {
-                if let Err(e) = self
-                    .stack_manager
-                    .set_entry_merged(&stack_uuid, &entry_id, merged)
-                {
-                    tracing::warn!(
-                        "Failed to persist merged state for entry {}: {}",
-                        entry_id,
-                        e
-                    );
-                }
-            }
+        self.reconcile_pr_states(&stack_uuid, &pr_states);
+
+        // Remember the counts so `ca prompt` can show them without calling Bitbucket
+        let advisory_patterns = &self.config.cascade.advisory_merge_checks;
+        let ready_to_land = status
+            .enhanced_statuses
+            .iter()
+            .filter(|enhanced| {
+                let mut enhanced = (*enhanced).clone();
+                enhanced.apply_advisory_filters(advisory_patterns);
+                enhanced.is_ready_to_land()
+            })
+            .count();
+        let failing_builds = status
+            .enhanced_statuses
+            .iter()
+            .filter(|enhanced| {
+                enhanced.pr.state == crate::bitbucket::pull_request::PullRequestState::Open
+                    && enhanced.build_status.as_ref().is_some_and(|build| {
+                        build.state == crate::bitbucket::pull_request::BuildState::Failed
+                    })
+            })
+            .count();
+        let summary = crate::stack::PrSummary {
+            open: status.open_prs,
+            ready_to_land,
+            failing_builds,
+            checked_at: chrono::Utc::now(),
+        };
+        if let Err(e) = crate::stack::PrSummaryCache::record(
+            self.stack_manager.repo_path(),
+            stack_uuid,
+            summary,
+        ) {
+            tracing::debug!("Failed to record PR summary: {}", e);
+        }
+        if let Err(e) = crate::stack::PrSummaryCache::record_titles(
+            self.stack_manager.repo_path(),
+            status
+                .pull_requests
+                .iter()
+                .map(|pr| (pr.id.to_string(), pr.title.clone())),
+        ) {
+            tracing::debug!("Failed to record PR titles: {}", e);
         }
 
         Ok(status)
@@ -928,3 +1779,204 @@ impl StackSubmissionStatus {
         self.submitted_entries > 0 && self.merged_prs == self.submitted_entries
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_targets_skip_entries_out_of_the_chain() {
+        assert_eq!(
+            chain_targets(
+                "main",
+                &[("auth", false), ("login", true), ("logout", true)]
+            ),
+            vec!["main", "main", "login"]
+        );
+        assert_eq!(
+            chain_targets(
+                "main",
+                &[("auth", true), ("login", false), ("logout", true)]
+            ),
+            vec!["main", "auth", "auth"]
+        );
+        assert!(chain_targets("main", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_stack_update_comment_marks_position_in_chain() {
+        let chain = vec![
+            ChainLink {
+                branch: "auth".to_string(),
+                pr_id: Some(12),
+                title: Some("Add auth".to_string()),
+                url: Some("https://bitbucket.example.com/pr/12".to_string()),
+            },
+            ChainLink {
+                branch: "login".to_string(),
+                pr_id: Some(13),
+                title: Some("Add login".to_string()),
+                url: None,
+            },
+            ChainLink::branch("logout"),
+        ];
+
+        let push = ForcePush {
+            commit: "abcd1234".to_string(),
+            compare_url: Some("https://bitbucket.example.com/compare".to_string()),
+        };
+        let text = stack_update_comment(
+            "feature",
+            "main",
+            &chain,
+            1,
+            Some(&push),
+            Some(("main", "auth")),
+        );
+        assert!(text.starts_with(STACK_UPDATE_HEADER));
+        assert!(text.contains("(now `abcd1234`)"));
+        assert!(text.contains("[Changes since last review](https://bitbucket.example.com/compare)"));
+        assert!(text.contains("from `main` to `auth`"));
+        assert!(text.contains("**Position:** 2 of 3 in stack `feature`"));
+        assert!(text.contains("1. [#12](https://bitbucket.example.com/pr/12) Add auth\n"));
+        assert!(text.contains("2. **#13 Add login** ← this pull request\n"));
+        assert!(text.contains("3. `logout` (no pull request yet)\n"));
+
+        // Only a rebase: no base change line, and the same state gives the same text
+        let rebased_only = stack_update_comment("feature", "main", &chain, 0, Some(&push), None);
+        assert!(!rebased_only.contains("target branch changed"));
+        assert_eq!(
+            rebased_only,
+            stack_update_comment("feature", "main", &chain, 0, Some(&push), None)
+        );
+    }
+
+    /// A pull request as Bitbucket returns it
+    fn pr_json(title: &str, description: &str) -> String {
+        let repository = serde_json::json!({
+            "id": 1, "name": "app", "slug": "app", "scmId": "git", "state": "AVAILABLE",
+            "forkable": true, "public": false,
+            "project": {"id": 1, "key": "TEST", "name": "Test", "public": false, "type": "NORMAL"}
+        });
+        serde_json::json!({
+            "id": 12, "version": 3, "title": title, "description": description,
+            "state": "OPEN", "open": true, "closed": false, "createdDate": 0, "updatedDate": 0,
+            "fromRef": {"id": "refs/heads/login", "displayId": "login", "latestCommit": "abc",
+                "repository": repository},
+            "toRef": {"id": "refs/heads/main", "displayId": "main", "latestCommit": "def",
+                "repository": repository},
+            "locked": false,
+            "author": {"user": {"name": "jdoe", "active": true}, "role": "AUTHOR",
+                "approved": false, "status": "UNAPPROVED"},
+            "links": {"self": []}
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_update_pr_from_commit_message_rebuilds_title_and_description() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_path = temp_dir.path();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(repo_path)
+                .output()
+                .unwrap();
+        };
+        git(&["init", "-b", "main"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["config", "user.email", "test@test.com"]);
+        std::fs::write(repo_path.join("README.md"), "# Test").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-m", "Initial"]);
+        crate::config::initialize_repo(repo_path, Some("https://test.bitbucket.com".to_string()))
+            .unwrap();
+
+        let mut manager = StackManager::new(repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("auth".to_string(), Some("main".to_string()), None)
+            .unwrap();
+        let stack = manager.get_stack_mut(&stack_id).unwrap();
+        let entry_id = stack.push_entry(
+            "login".to_string(),
+            "abc".to_string(),
+            "Add login\n\nAdds the login page.".to_string(),
+        );
+        stack.mark_entry_submitted(&entry_id, "12".to_string());
+        manager.save_to_disk().unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let path = "/rest/api/1.0/projects/TEST/repos/app/pull-requests/12";
+        let _get = server
+            .mock("GET", path)
+            .with_body(pr_json(
+                "[DRAFT] Old title",
+                "Notes a reviewer asked for\n\n---\n\n## 📚 Stack: auth\n\nold hierarchy",
+            ))
+            .create_async()
+            .await;
+        let url = server.url();
+        let config = |template: Option<&str>| CascadeConfig {
+            bitbucket: Some(crate::config::BitbucketConfig {
+                url: url.clone(),
+                project: "TEST".to_string(),
+                repo: "app".to_string(),
+                token: Some("secret".to_string()),
+                ..Default::default()
+            }),
+            cascade: crate::config::CascadeSettings {
+                pr_description_template: template.map(str::to_string),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // The new description: `body`, then exactly one fresh stack footer
+        let expect_update = |body: &'static str| {
+            move |request: &mockito::Request| {
+                let update: serde_json::Value =
+                    serde_json::from_slice(request.body().unwrap()).unwrap();
+                let description = update["description"].as_str().unwrap();
+                update["title"] == "[DRAFT] Add login"
+                    && description.starts_with(&format!("{body}\n\n---\n\n## 📚 Stack: auth"))
+                    && description.matches("📚 Stack:").count() == 1
+                    && !description.contains("old hierarchy")
+            }
+        };
+
+        // Without a template the description comes from the commit body
+        let put = server
+            .mock("PUT", path)
+            .match_request(expect_update("Adds the login page."))
+            .with_body(pr_json("[DRAFT] Add login", ""))
+            .create_async()
+            .await;
+        let integration =
+            BitbucketIntegration::new(StackManager::new(repo_path).unwrap(), config(None)).unwrap();
+        let updated = integration
+            .update_pr_from_commit_message(&stack_id, &entry_id)
+            .await
+            .unwrap();
+        assert_eq!(updated, Some(12));
+        put.assert_async().await;
+        put.remove_async().await;
+
+        // With a template the PR's own description is kept, minus its old footer
+        let put = server
+            .mock("PUT", path)
+            .match_request(expect_update("Notes a reviewer asked for"))
+            .with_body(pr_json("[DRAFT] Add login", ""))
+            .create_async()
+            .await;
+        let integration = BitbucketIntegration::new(
+            StackManager::new(repo_path).unwrap(),
+            config(Some("## Summary")),
+        )
+        .unwrap();
+        integration
+            .update_pr_from_commit_message(&stack_id, &entry_id)
+            .await
+            .unwrap();
+        put.assert_async().await;
+    }
+}
@@ -0,0 +1,1243 @@
+//! Repository provider that keeps pull requests in memory
+//!
+//! Selected with `bitbucket.provider = "mock"` so demos, the tutorial and integration
+//! tests can submit, review and land stacks with no Bitbucket instance. IDs and
+//! timestamps come from counters, so the same sequence of commands always produces the
+//! same pull requests. When the `origin` remote is a local repository, merging a pull
+//! request updates its target branch there just like the server would.
+
+use crate::bitbucket::auth::AuthenticatedUser;
+use crate::bitbucket::provider::{
+    BranchRestriction, ProviderFuture, ProviderIdentity, PullRequestQuery, PullRequestUpdate,
+    RepositoryPermission, RepositoryProvider,
+};
+use crate::bitbucket::pull_request::{
+    ActivityAction, BuildState, BuildStatus, CommitRef, CreatePullRequestRequest,
+    MergePullRequestRequest, MergeStrategy, MergeabilityDetails, Participant, ParticipantRole,
+    ParticipantStatus, PullRequest, PullRequestActivity, PullRequestComment, PullRequestLinks,
+    PullRequestPage, PullRequestProperties, PullRequestState, PullRequestTask, SelfLink, TaskState,
+    User,
+};
+use crate::config::{BitbucketConfig, MockSettings};
+use crate::errors::{CascadeError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::debug;
+
+const STATE_FILE: &str = "CASCADE_MOCK_PROVIDER";
+
+/// Time of the first mock event; every change moves the clock on by a minute
+const EPOCH_MS: u64 = 1_700_000_000_000;
+const TICK_MS: u64 = 60_000;
+
+/// Everything the mock server knows
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MockState {
+    next_id: u64,
+    clock: u64,
+    pull_requests: Vec<PullRequest>,
+    #[serde(deserialize_with = "deserialize_comments")]
+    comments: HashMap<u64, Vec<PullRequestComment>>,
+    #[serde(default)]
+    tasks: HashMap<u64, Vec<PullRequestTask>>,
+}
+
+/// Read comments, including state files written when they were plain strings
+fn deserialize_comments<'de, D>(
+    deserializer: D,
+) -> std::result::Result<HashMap<u64, Vec<PullRequestComment>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StoredComment {
+        Comment(PullRequestComment),
+        Text(String),
+    }
+
+    let stored: HashMap<u64, Vec<StoredComment>> = HashMap::deserialize(deserializer)?;
+    Ok(stored
+        .into_iter()
+        .map(|(pr_id, comments)| {
+            let comments = comments
+                .into_iter()
+                .enumerate()
+                .map(|(n, comment)| match comment {
+                    StoredComment::Comment(comment) => comment,
+                    StoredComment::Text(text) => PullRequestComment {
+                        id: n as u64 + 1,
+                        version: 0,
+                        text,
+                        author: None,
+                    },
+                })
+                .collect();
+            (pr_id, comments)
+        })
+        .collect())
+}
+
+impl MockState {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        EPOCH_MS + self.clock * TICK_MS
+    }
+
+    fn pull_request_mut(&mut self, pr_id: u64) -> Result<&mut PullRequest> {
+        self.pull_requests
+            .iter_mut()
+            .find(|pr| pr.id == pr_id)
+            .ok_or_else(|| CascadeError::bitbucket(format!("Pull request #{pr_id} not found")))
+    }
+}
+
+/// In-memory [`RepositoryProvider`] with configurable review and build results
+pub struct MockProvider {
+    base_url: String,
+    project: String,
+    repo: String,
+    author: String,
+    settings: MockSettings,
+    state: Mutex<MockState>,
+    /// Repository whose git dir stores the state and whose `origin` plays the server
+    repo_root: Option<PathBuf>,
+}
+
+impl MockProvider {
+    /// Create a provider whose pull requests live only as long as it does
+    pub fn new(config: &BitbucketConfig) -> Self {
+        Self {
+            base_url: config.url.trim_end_matches('/').to_string(),
+            project: config.project.clone(),
+            repo: config.repo.clone(),
+            author: config
+                .username
+                .clone()
+                .unwrap_or_else(|| "developer".to_string()),
+            settings: config.mock.clone(),
+            state: Mutex::new(MockState {
+                next_id: 1,
+                ..Default::default()
+            }),
+            repo_root: None,
+        }
+    }
+
+    /// Keep pull requests in the git dir of `repo_root` and merge into its `origin`
+    pub fn persisted(mut self, repo_root: &Path) -> Result<Self> {
+        self.repo_root = Some(repo_root.to_path_buf());
+        if let Some(state) = self.load()? {
+            self.state = Mutex::new(state);
+        }
+        Ok(self)
+    }
+
+    /// Comments posted on a pull request, oldest first
+    pub fn comments(&self, pr_id: u64) -> Vec<String> {
+        self.comments_of(pr_id)
+            .map(|comments| comments.into_iter().map(|c| c.text).collect())
+            .unwrap_or_default()
+    }
+
+    fn state_path(&self) -> Result<Option<PathBuf>> {
+        match &self.repo_root {
+            Some(root) => Ok(Some(crate::git::resolve_git_dir(root)?.join(STATE_FILE))),
+            None => Ok(None),
+        }
+    }
+
+    fn load(&self) -> Result<Option<MockState>> {
+        let Some(path) = self.state_path()? else {
+            return Ok(None);
+        };
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(&path).map_err(|e| {
+            CascadeError::config(format!("Failed to read mock provider state: {e}"))
+        })?;
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| CascadeError::config(format!("Invalid mock provider state: {e}")))
+    }
+
+    /// Run `f` against the latest state; another `ca` process may have changed it
+    fn read<R>(&self, f: impl FnOnce(&MockState) -> Result<R>) -> Result<R> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(latest) = self.load()? {
+            *state = latest;
+        }
+        f(&state)
+    }
+
+    /// Like [`Self::read`], saving the state when `f` succeeds
+    fn write<R>(&self, f: impl FnOnce(&mut MockState) -> Result<R>) -> Result<R> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(latest) = self.load()? {
+            *state = latest;
+        }
+        let result = f(&mut state)?;
+        if let Some(path) = self.state_path()? {
+            crate::utils::atomic_file::write_json(&path, &*state)?;
+        }
+        Ok(result)
+    }
+
+    fn user(&self, name: &str) -> User {
+        User {
+            name: name.to_string(),
+            display_name: Some(name.to_string()),
+            email_address: Some(format!("{name}@example.com")),
+            active: true,
+            slug: Some(name.to_string()),
+        }
+    }
+
+    fn author(&self) -> Participant {
+        Participant {
+            user: self.user(&self.author),
+            role: ParticipantRole::Author,
+            approved: false,
+            status: ParticipantStatus::Unapproved,
+        }
+    }
+
+    /// One approving reviewer per configured approval
+    fn reviewers(&self) -> Vec<Participant> {
+        (1..=self.settings.approvals)
+            .map(|n| Participant {
+                user: self.user(&format!("reviewer-{n}")),
+                role: ParticipantRole::Reviewer,
+                approved: true,
+                status: ParticipantStatus::Approved,
+            })
+            .collect()
+    }
+
+    /// The repository `origin` points at, when it is on this machine
+    fn server_repo(&self) -> Option<git2::Repository> {
+        let local = git2::Repository::open(self.repo_root.as_ref()?).ok()?;
+        let remote = local.find_remote("origin").ok()?;
+        let url = remote.url()?;
+        let path = url.strip_prefix("file://").unwrap_or(url);
+        git2::Repository::open_bare(path)
+            .or_else(|_| git2::Repository::open(path))
+            .ok()
+    }
+
+    /// Head of `branch` as the server sees it
+    fn branch_head(&self, branch: &str) -> Option<String> {
+        let repo = self.server_repo()?;
+        let head = repo.refname_to_id(&format!("refs/heads/{branch}")).ok()?;
+        Some(head.to_string())
+    }
+
+    /// Refresh commit IDs that change whenever the branches are pushed
+    fn with_live_refs(&self, mut pr: PullRequest) -> PullRequest {
+        if pr.is_open() {
+            if let Some(head) = self.branch_head(&pr.from_ref.display_id) {
+                pr.from_ref.latest_commit = head;
+            }
+            if let Some(head) = self.branch_head(&pr.to_ref.display_id) {
+                pr.to_ref.latest_commit = head;
+            }
+        }
+        pr
+    }
+
+    fn web_url(&self, pr_id: u64) -> String {
+        format!(
+            "{}/projects/{}/repos/{}/pull-requests/{pr_id}",
+            self.base_url, self.project, self.repo
+        )
+    }
+
+    fn check_version(pr: &PullRequest, version: u64) -> Result<()> {
+        if pr.version != version {
+            return Err(CascadeError::bitbucket(format!(
+                "Pull request #{} is at version {}, not {version}; refresh and try again",
+                pr.id, pr.version
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_open(pr: &PullRequest) -> Result<()> {
+        if !pr.is_open() {
+            return Err(CascadeError::bitbucket(format!(
+                "Pull request #{} is already {}",
+                pr.id,
+                pr.state.as_str().to_lowercase()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Refuse changes the mock user's `mock.permission` doesn't allow, as the server would
+    fn check_write(&self) -> Result<()> {
+        if self.settings.permission < RepositoryPermission::Write {
+            return Err(CascadeError::bitbucket(format!(
+                "Request failed with status 403 Forbidden: {} does not have write access \
+                 to {}/{}",
+                self.author, self.project, self.repo
+            )));
+        }
+        Ok(())
+    }
+
+    fn create(&self, request: &CreatePullRequestRequest) -> Result<PullRequest> {
+        self.check_write()?;
+        let from_head = self.branch_head(&request.from_ref.display_id);
+        let to_head = self.branch_head(&request.to_ref.display_id);
+
+        let pr = self.write(|state| {
+            if state.pull_requests.iter().any(|pr| {
+                pr.is_open()
+                    && pr.from_ref.id == request.from_ref.id
+                    && pr.to_ref.id == request.to_ref.id
+            }) {
+                return Err(CascadeError::bitbucket(format!(
+                    "Only one pull request may be open for {} into {}",
+                    request.from_ref.display_id, request.to_ref.display_id
+                )));
+            }
+
+            let id = state.next_id.max(1);
+            state.next_id = id + 1;
+            let now = state.tick();
+
+            let mut from_ref = request.from_ref.clone();
+            let mut to_ref = request.to_ref.clone();
+            if let Some(head) = from_head {
+                from_ref.latest_commit = head;
+            }
+            if let Some(head) = to_head {
+                to_ref.latest_commit = head;
+            }
+
+            let pr = PullRequest {
+                id,
+                version: 0,
+                title: request.title.clone(),
+                description: request.description.clone(),
+                state: PullRequestState::Open,
+                open: true,
+                closed: false,
+                created_date: now,
+                updated_date: now,
+                from_ref,
+                to_ref,
+                locked: false,
+                author: self.author(),
+                reviewers: self.reviewers(),
+                links: PullRequestLinks {
+                    self_link: vec![SelfLink {
+                        href: self.web_url(id),
+                    }],
+                },
+                properties: None,
+            };
+            state.pull_requests.push(pr.clone());
+            if self.settings.tasks > 0 {
+                let reviewer = self.user("reviewer-1");
+                let tasks = (1..=self.settings.tasks as u64)
+                    .map(|n| PullRequestTask {
+                        id: n,
+                        version: 0,
+                        text: format!("Mock task {n}"),
+                        author: Some(reviewer.clone()),
+                        state: TaskState::Open,
+                    })
+                    .collect();
+                state.tasks.insert(id, tasks);
+            }
+            Ok(pr)
+        })?;
+
+        debug!("Mock provider opened PR #{}", pr.id);
+        Ok(pr)
+    }
+
+    fn get(&self, pr_id: u64) -> Result<PullRequest> {
+        let pr = self.read(|state| {
+            state
+                .pull_requests
+                .iter()
+                .find(|pr| pr.id == pr_id)
+                .cloned()
+                .ok_or_else(|| CascadeError::bitbucket(format!("Pull request #{pr_id} not found")))
+        })?;
+        Ok(self.with_live_refs(pr))
+    }
+
+    fn update(&self, pr_id: u64, update: &PullRequestUpdate) -> Result<PullRequest> {
+        let target_head = update
+            .target_branch
+            .as_ref()
+            .and_then(|branch| self.branch_head(branch));
+        // Reviewers named in the update that the PR doesn't have yet join unapproved
+        let requested: Vec<User> = update.reviewers.iter().map(|n| self.user(n)).collect();
+
+        let pr = self.write(|state| {
+            let now = state.tick();
+            let pr = state.pull_request_mut(pr_id)?;
+            Self::check_open(pr)?;
+            Self::check_version(pr, update.version)?;
+
+            if let Some(title) = &update.title {
+                pr.title = title.clone();
+            }
+            if let Some(description) = &update.description {
+                pr.description = Some(description.clone());
+            }
+            for reviewer in &requested {
+                if !pr.reviewers.iter().any(|r| r.user.name == reviewer.name) {
+                    pr.reviewers.push(Participant {
+                        user: reviewer.clone(),
+                        role: ParticipantRole::Reviewer,
+                        approved: false,
+                        status: ParticipantStatus::Unapproved,
+                    });
+                }
+            }
+            if let Some(branch) = &update.target_branch {
+                pr.to_ref.id = format!("refs/heads/{branch}");
+                pr.to_ref.display_id = branch.clone();
+                pr.to_ref.latest_commit = target_head.unwrap_or_default();
+            }
+            pr.version += 1;
+            pr.updated_date = now;
+            Ok(pr.clone())
+        })?;
+        Ok(self.with_live_refs(pr))
+    }
+
+    fn list(&self, state_filter: Option<PullRequestState>) -> Result<PullRequestPage> {
+        let values: Vec<PullRequest> = self.read(|state| {
+            Ok(state
+                .pull_requests
+                .iter()
+                .filter(|pr| state_filter.as_ref().is_none_or(|s| pr.state == *s))
+                .cloned()
+                .collect())
+        })?;
+        let values: Vec<PullRequest> = values
+            .into_iter()
+            .map(|pr| self.with_live_refs(pr))
+            .collect();
+
+        Ok(PullRequestPage {
+            size: values.len() as u32,
+            limit: values.len() as u32,
+            is_last_page: true,
+            values,
+            start: 0,
+            next_page_start: None,
+        })
+    }
+
+    fn decline(&self, pr_id: u64, version: u64) -> Result<()> {
+        self.write(|state| {
+            let now = state.tick();
+            let pr = state.pull_request_mut(pr_id)?;
+            Self::check_open(pr)?;
+            Self::check_version(pr, version)?;
+            pr.state = PullRequestState::Declined;
+            pr.open = false;
+            pr.closed = true;
+            pr.version += 1;
+            pr.updated_date = now;
+            Ok(())
+        })
+    }
+
+    fn rerequest(&self, pr_id: u64, reviewers: &[String]) -> Result<PullRequest> {
+        self.write(|state| {
+            let now = state.tick();
+            let pr = state.pull_request_mut(pr_id)?;
+            Self::check_open(pr)?;
+            for reviewer in pr
+                .reviewers
+                .iter_mut()
+                .filter(|r| reviewers.contains(&r.user.name))
+            {
+                reviewer.approved = false;
+                reviewer.status = ParticipantStatus::Unapproved;
+            }
+            pr.version += 1;
+            pr.updated_date = now;
+            Ok(pr.clone())
+        })
+        .map(|pr| self.with_live_refs(pr))
+    }
+
+    fn reopen(&self, pr_id: u64, version: u64) -> Result<PullRequest> {
+        self.write(|state| {
+            let now = state.tick();
+            let pr = state.pull_request_mut(pr_id)?;
+            if pr.state != PullRequestState::Declined {
+                return Err(CascadeError::bitbucket(format!(
+                    "Pull request #{pr_id} is {}; only declined pull requests can be reopened",
+                    pr.state.as_str().to_lowercase()
+                )));
+            }
+            Self::check_version(pr, version)?;
+            pr.state = PullRequestState::Open;
+            pr.open = true;
+            pr.closed = false;
+            pr.version += 1;
+            pr.updated_date = now;
+            Ok(pr.clone())
+        })
+        .map(|pr| self.with_live_refs(pr))
+    }
+
+    fn comment(&self, pr_id: u64, text: &str) -> Result<PullRequestComment> {
+        let author = self.user(&self.author);
+        self.write(|state| {
+            state.pull_request_mut(pr_id)?;
+            let comments = state.comments.entry(pr_id).or_default();
+            let comment = PullRequestComment {
+                id: comments.iter().map(|c| c.id).max().unwrap_or(0) + 1,
+                version: 0,
+                text: text.to_string(),
+                author: Some(author),
+            };
+            comments.push(comment.clone());
+            Ok(comment)
+        })
+    }
+
+    fn edit_comment(
+        &self,
+        pr_id: u64,
+        comment: &PullRequestComment,
+        text: &str,
+    ) -> Result<PullRequestComment> {
+        self.write(|state| {
+            state.pull_request_mut(pr_id)?;
+            let stored = state
+                .comments
+                .get_mut(&pr_id)
+                .and_then(|comments| comments.iter_mut().find(|c| c.id == comment.id))
+                .ok_or_else(|| {
+                    CascadeError::bitbucket(format!(
+                        "Comment {} not found on pull request #{pr_id}",
+                        comment.id
+                    ))
+                })?;
+            if stored.version != comment.version {
+                return Err(CascadeError::bitbucket(format!(
+                    "Comment {} is at version {}, not {}",
+                    comment.id, stored.version, comment.version
+                )));
+            }
+            stored.text = text.to_string();
+            stored.version += 1;
+            Ok(stored.clone())
+        })
+    }
+
+    fn comments_of(&self, pr_id: u64) -> Result<Vec<PullRequestComment>> {
+        self.read(|state| {
+            if !state.pull_requests.iter().any(|pr| pr.id == pr_id) {
+                return Err(CascadeError::bitbucket(format!(
+                    "Pull request #{pr_id} not found"
+                )));
+            }
+            Ok(state.comments.get(&pr_id).cloned().unwrap_or_default())
+        })
+    }
+
+    fn open_tasks_of(&self, pr_id: u64) -> Result<Vec<PullRequestTask>> {
+        self.read(|state| {
+            if !state.pull_requests.iter().any(|pr| pr.id == pr_id) {
+                return Err(CascadeError::bitbucket(format!(
+                    "Pull request #{pr_id} not found"
+                )));
+            }
+            Ok(state
+                .tasks
+                .get(&pr_id)
+                .map(|tasks| {
+                    tasks
+                        .iter()
+                        .filter(|t| t.state == TaskState::Open)
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default())
+        })
+    }
+
+    fn resolve(&self, pr_id: u64, task: &PullRequestTask) -> Result<PullRequestTask> {
+        self.write(|state| {
+            state.pull_request_mut(pr_id)?;
+            let stored = state
+                .tasks
+                .get_mut(&pr_id)
+                .and_then(|tasks| tasks.iter_mut().find(|t| t.id == task.id))
+                .ok_or_else(|| {
+                    CascadeError::bitbucket(format!(
+                        "Task {} not found on pull request #{pr_id}",
+                        task.id
+                    ))
+                })?;
+            if stored.version != task.version {
+                return Err(CascadeError::bitbucket(format!(
+                    "Task {} is at version {}, not {}",
+                    task.id, stored.version, task.version
+                )));
+            }
+            stored.state = TaskState::Resolved;
+            stored.version += 1;
+            Ok(stored.clone())
+        })
+    }
+
+    fn participants_of(&self, pr_id: u64) -> Result<Vec<Participant>> {
+        let pr = self.get(pr_id)?;
+        let mut participants = vec![pr.author];
+        participants.extend(pr.reviewers);
+        Ok(participants)
+    }
+
+    /// Opened, one approval per reviewer, then merged or declined; newest first
+    fn activity_of(&self, pr_id: u64) -> Result<Vec<PullRequestActivity>> {
+        let pr = self.get(pr_id)?;
+        let mut activities = vec![PullRequestActivity {
+            id: pr_id * 100,
+            created_date: pr.created_date,
+            user: pr.author.user.clone(),
+            action: ActivityAction::Opened,
+            comment: None,
+            comment_action: None,
+        }];
+        for (n, reviewer) in pr.reviewers.iter().enumerate() {
+            let n = n as u64 + 1;
+            activities.push(PullRequestActivity {
+                id: pr_id * 100 + n,
+                created_date: pr.created_date + n * 1000,
+                user: reviewer.user.clone(),
+                action: ActivityAction::Approved,
+                comment: None,
+                comment_action: None,
+            });
+        }
+        let closed = match pr.state {
+            PullRequestState::Merged => Some(ActivityAction::Merged),
+            PullRequestState::Declined => Some(ActivityAction::Declined),
+            PullRequestState::Open => None,
+        };
+        if let Some(action) = closed {
+            activities.push(PullRequestActivity {
+                id: pr_id * 100 + 99,
+                created_date: pr.updated_date,
+                user: pr.author.user.clone(),
+                action,
+                comment: None,
+                comment_action: None,
+            });
+        }
+        activities.reverse();
+        Ok(activities)
+    }
+
+    fn mergeability(&self, pr_id: u64) -> Result<MergeabilityDetails> {
+        let pr = self.get(pr_id)?;
+        let mut blocking_reasons = Vec::new();
+
+        if !pr.is_open() {
+            blocking_reasons.push(format!(
+                "Pull request is {}",
+                pr.state.as_str().to_lowercase()
+            ));
+        }
+        if self.settings.approvals == 0 {
+            blocking_reasons.push("Requires at least 1 approval".to_string());
+        }
+        match self.settings.build_state {
+            BuildState::Successful => {}
+            BuildState::InProgress => {
+                blocking_reasons.push("Waiting for the build to finish".to_string())
+            }
+            _ => blocking_reasons.push("Not all required builds are successful".to_string()),
+        }
+        if !self.settings.mergeable {
+            blocking_reasons.push("Blocked by a merge check (mock.mergeable)".to_string());
+        }
+
+        Ok(MergeabilityDetails {
+            can_merge: blocking_reasons.is_empty(),
+            conflicted: false,
+            blocking_reasons,
+            server_enforced: true,
+        })
+    }
+
+    fn builds(&self, commit_hash: &str) -> Vec<BuildStatus> {
+        if self.settings.build_state == BuildState::Unknown {
+            return Vec::new();
+        }
+        let short = &commit_hash[..commit_hash.len().min(8)];
+        vec![BuildStatus {
+            state: self.settings.build_state.clone(),
+            url: Some(format!("{}/builds/{short}", self.base_url)),
+            description: Some(format!("Mock build of {short}")),
+            context: Some("mock-ci".to_string()),
+        }]
+    }
+
+    fn merge(&self, pr_id: u64, request: &MergePullRequestRequest) -> Result<PullRequest> {
+        let details = self.mergeability(pr_id)?;
+        if !details.can_merge {
+            return Err(CascadeError::bitbucket(format!(
+                "Merge blocked:\n{}",
+                details
+                    .blocking_reasons
+                    .iter()
+                    .map(|r| format!("  - {r}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )));
+        }
+
+        let pr = self.get(pr_id)?;
+        Self::check_version(&pr, request.version)?;
+        self.check_write()?;
+        if self
+            .settings
+            .read_only_branches
+            .contains(&pr.to_ref.display_id)
+        {
+            return Err(CascadeError::bitbucket(format!(
+                "Request failed with status 403 Forbidden: {} is read-only",
+                pr.to_ref.display_id
+            )));
+        }
+
+        let time = self.read(|state| Ok(EPOCH_MS + (state.clock + 1) * TICK_MS))?;
+        let merge_commit = match self.server_repo() {
+            Some(server) => Some(self.merge_on_server(&server, &pr, request, time)?),
+            None => {
+                debug!("origin is not a local repository; PR #{pr_id} merged in state only");
+                None
+            }
+        };
+
+        let pr = self.write(|state| {
+            let now = state.tick();
+            let pr = state.pull_request_mut(pr_id)?;
+            pr.state = PullRequestState::Merged;
+            pr.open = false;
+            pr.closed = true;
+            pr.version += 1;
+            pr.updated_date = now;
+            pr.properties = Some(PullRequestProperties {
+                merge_commit: merge_commit.map(|id| CommitRef { id }),
+            });
+            Ok(pr.clone())
+        })?;
+        Ok(pr)
+    }
+
+    /// Move the target branch in the `origin` repository the way the server would,
+    /// returning the commit the target branch ends up at
+    fn merge_on_server(
+        &self,
+        server: &git2::Repository,
+        pr: &PullRequest,
+        request: &MergePullRequestRequest,
+        time_ms: u64,
+    ) -> Result<String> {
+        let source_ref = format!("refs/heads/{}", pr.from_ref.display_id);
+        let target_ref = format!("refs/heads/{}", pr.to_ref.display_id);
+        let source = server.refname_to_id(&source_ref).map_err(|_| {
+            CascadeError::bitbucket(format!(
+                "Branch {} has not been pushed",
+                pr.from_ref.display_id
+            ))
+        })?;
+        let target = server.refname_to_id(&target_ref).map_err(|_| {
+            CascadeError::bitbucket(format!(
+                "Target branch {} does not exist",
+                pr.to_ref.display_id
+            ))
+        })?;
+
+        let fast_forward = source == target || server.graph_descendant_of(source, target)?;
+        if let MergeStrategy::FastForward = request.strategy {
+            if !fast_forward {
+                return Err(CascadeError::bitbucket(format!(
+                    "Merge blocked:\n  - {} must be rebased onto {} to fast-forward",
+                    pr.from_ref.display_id, pr.to_ref.display_id
+                )));
+            }
+            server.reference(&target_ref, source, true, "mock: fast-forward merge")?;
+            return Ok(source.to_string());
+        }
+
+        let source_commit = server.find_commit(source)?;
+        let target_commit = server.find_commit(target)?;
+        let mut index = server.merge_commits(&target_commit, &source_commit, None)?;
+        if index.has_conflicts() {
+            return Err(CascadeError::bitbucket(format!(
+                "Merge blocked:\n  - Pull request #{} has merge conflicts",
+                pr.id
+            )));
+        }
+        let tree = server.find_tree(index.write_tree_to(server)?)?;
+
+        let author = &pr.author.user;
+        let signature = git2::Signature::new(
+            author.display_name.as_deref().unwrap_or(&author.name),
+            author
+                .email_address
+                .as_deref()
+                .unwrap_or("developer@example.com"),
+            &git2::Time::new((time_ms / 1000) as i64, 0),
+        )?;
+        let message = request.message.clone().unwrap_or_else(|| {
+            format!(
+                "Merge pull request #{} in {}/{} from {} to {}",
+                pr.id, self.project, self.repo, pr.from_ref.display_id, pr.to_ref.display_id
+            )
+        });
+        let parents = match request.strategy {
+            MergeStrategy::Squash => vec![&target_commit],
+            _ => vec![&target_commit, &source_commit],
+        };
+        let merge_commit = server.commit(
+            Some(&target_ref),
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            &parents,
+        )?;
+        Ok(merge_commit.to_string())
+    }
+}
+
+impl RepositoryProvider for MockProvider {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn create_pull_request<'a>(
+        &'a self,
+        request: &'a CreatePullRequestRequest,
+    ) -> ProviderFuture<'a, PullRequest> {
+        Box::pin(std::future::ready(self.create(request)))
+    }
+
+    fn get_pull_request(&self, pr_id: u64) -> ProviderFuture<'_, PullRequest> {
+        Box::pin(std::future::ready(self.get(pr_id)))
+    }
+
+    fn update_pull_request<'a>(
+        &'a self,
+        pr_id: u64,
+        update: &'a PullRequestUpdate,
+    ) -> ProviderFuture<'a, PullRequest> {
+        Box::pin(std::future::ready(self.update(pr_id, update)))
+    }
+
+    fn list_pull_requests(
+        &self,
+        state: Option<PullRequestState>,
+    ) -> ProviderFuture<'_, PullRequestPage> {
+        Box::pin(std::future::ready(self.list(state)))
+    }
+
+    fn search_pull_requests<'a>(
+        &'a self,
+        query: &'a PullRequestQuery,
+    ) -> ProviderFuture<'a, PullRequestPage> {
+        Box::pin(std::future::ready(self.list(query.state.clone()).map(
+            |mut page| {
+                page.values.retain(|pr| query.matches(pr));
+                page.size = page.values.len() as u32;
+                page
+            },
+        )))
+    }
+
+    fn decline_pull_request(&self, pr_id: u64, version: u64) -> ProviderFuture<'_, ()> {
+        Box::pin(std::future::ready(self.decline(pr_id, version)))
+    }
+
+    fn rerequest_review<'a>(
+        &'a self,
+        pr_id: u64,
+        reviewers: &'a [String],
+    ) -> ProviderFuture<'a, PullRequest> {
+        Box::pin(std::future::ready(self.rerequest(pr_id, reviewers)))
+    }
+
+    fn reopen_pull_request(&self, pr_id: u64, version: u64) -> ProviderFuture<'_, PullRequest> {
+        Box::pin(std::future::ready(self.reopen(pr_id, version)))
+    }
+
+    fn add_comment<'a>(
+        &'a self,
+        pr_id: u64,
+        text: &'a str,
+    ) -> ProviderFuture<'a, PullRequestComment> {
+        Box::pin(std::future::ready(self.comment(pr_id, text)))
+    }
+
+    fn update_comment<'a>(
+        &'a self,
+        pr_id: u64,
+        comment: &'a PullRequestComment,
+        text: &'a str,
+    ) -> ProviderFuture<'a, PullRequestComment> {
+        Box::pin(std::future::ready(self.edit_comment(pr_id, comment, text)))
+    }
+
+    fn list_comments(&self, pr_id: u64) -> ProviderFuture<'_, Vec<PullRequestComment>> {
+        Box::pin(std::future::ready(self.comments_of(pr_id)))
+    }
+
+    fn open_tasks(&self, pr_id: u64) -> ProviderFuture<'_, Vec<PullRequestTask>> {
+        Box::pin(std::future::ready(self.open_tasks_of(pr_id)))
+    }
+
+    fn resolve_task<'a>(
+        &'a self,
+        pr_id: u64,
+        task: &'a PullRequestTask,
+    ) -> ProviderFuture<'a, PullRequestTask> {
+        Box::pin(std::future::ready(self.resolve(pr_id, task)))
+    }
+
+    fn participants(&self, pr_id: u64) -> ProviderFuture<'_, Vec<Participant>> {
+        Box::pin(std::future::ready(self.participants_of(pr_id)))
+    }
+
+    fn activities(&self, pr_id: u64) -> ProviderFuture<'_, Vec<PullRequestActivity>> {
+        Box::pin(std::future::ready(self.activity_of(pr_id)))
+    }
+
+    fn merge_status(&self, pr_id: u64) -> ProviderFuture<'_, MergeabilityDetails> {
+        Box::pin(std::future::ready(self.mergeability(pr_id)))
+    }
+
+    fn build_statuses<'a>(&'a self, commit_hash: &'a str) -> ProviderFuture<'a, Vec<BuildStatus>> {
+        Box::pin(std::future::ready(Ok(self.builds(commit_hash))))
+    }
+
+    fn merge_pull_request<'a>(
+        &'a self,
+        pr_id: u64,
+        request: &'a MergePullRequestRequest,
+    ) -> ProviderFuture<'a, PullRequest> {
+        Box::pin(std::future::ready(self.merge(pr_id, request)))
+    }
+
+    fn identity(&self) -> ProviderFuture<'_, ProviderIdentity> {
+        let user = self.user(&self.author);
+        Box::pin(std::future::ready(Ok(ProviderIdentity {
+            user: AuthenticatedUser {
+                name: user.name,
+                display_name: user.display_name,
+                email: user.email_address,
+            },
+            permission: Some(self.settings.permission),
+            token: None,
+        })))
+    }
+
+    fn merge_restrictions<'a>(
+        &'a self,
+        branch: &'a str,
+    ) -> ProviderFuture<'a, Option<Vec<BranchRestriction>>> {
+        let restrictions = self
+            .settings
+            .read_only_branches
+            .iter()
+            .filter(|read_only| *read_only == branch)
+            .map(|_| BranchRestriction::default())
+            .collect();
+        Box::pin(std::future::ready(Ok(Some(restrictions))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitbucket::pull_request::{
+        AutoMergeConditions, AutoMergeResult, Project, PullRequestManager, PullRequestRef,
+        Repository,
+    };
+    use tempfile::TempDir;
+
+    fn pr_ref(branch: &str) -> PullRequestRef {
+        PullRequestRef {
+            id: format!("refs/heads/{branch}"),
+            display_id: branch.to_string(),
+            latest_commit: String::new(),
+            repository: Repository {
+                id: 1,
+                name: "repo".to_string(),
+                slug: "repo".to_string(),
+                scm_id: "git".to_string(),
+                state: "AVAILABLE".to_string(),
+                status_message: None,
+                forkable: true,
+                project: Project {
+                    id: 1,
+                    key: "PROJECT".to_string(),
+                    name: "Project".to_string(),
+                    description: None,
+                    public: false,
+                    project_type: "NORMAL".to_string(),
+                },
+                public: false,
+            },
+        }
+    }
+
+    fn request(branch: &str) -> CreatePullRequestRequest {
+        CreatePullRequestRequest {
+            title: format!("Add {branch}"),
+            description: None,
+            from_ref: pr_ref(branch),
+            to_ref: pr_ref("main"),
+            draft: false,
+        }
+    }
+
+    fn manager(config: &BitbucketConfig) -> PullRequestManager {
+        PullRequestManager::with_provider(Box::new(MockProvider::new(config)))
+    }
+
+    #[tokio::test]
+    async fn test_pull_requests_are_deterministic() {
+        let manager = manager(&BitbucketConfig::default());
+
+        let first = manager.create_pull_request(request("a")).await.unwrap();
+        let second = manager.create_pull_request(request("b")).await.unwrap();
+        assert_eq!((first.id, second.id), (1, 2));
+        assert_eq!(first.created_date, EPOCH_MS + TICK_MS);
+        assert_eq!(second.created_date, EPOCH_MS + 2 * TICK_MS);
+        assert!(manager.create_pull_request(request("a")).await.is_err());
+
+        let retargeted = manager.retarget_pull_request(2, "a").await.unwrap();
+        assert_eq!(retargeted.to_ref.display_id, "a");
+        assert_eq!(retargeted.version, 1);
+
+        manager.decline_pull_request(1, "superseded").await.unwrap();
+        let open = manager
+            .list_pull_requests(Some(PullRequestState::Open))
+            .await
+            .unwrap();
+        assert_eq!(open.values.len(), 1);
+
+        let reopened = manager.reopen_pull_request(1).await.unwrap();
+        assert_eq!(reopened.state, PullRequestState::Open);
+        assert_eq!(reopened.version, 2);
+        assert!(manager.reopen_pull_request(1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sticky_comment_is_updated_in_place() {
+        let temp_dir = TempDir::new().unwrap();
+        git2::Repository::init(temp_dir.path()).unwrap();
+        let config = BitbucketConfig::default();
+        let provider = || {
+            MockProvider::new(&config)
+                .persisted(temp_dir.path())
+                .unwrap()
+        };
+        let manager = PullRequestManager::with_provider(Box::new(provider()));
+        let pr = manager.create_pull_request(request("a")).await.unwrap();
+
+        manager.add_comment(pr.id, "Looks good").await.unwrap();
+        manager
+            .upsert_sticky_comment(pr.id, "## Stack", "## Stack\nrebased")
+            .await
+            .unwrap();
+        manager
+            .upsert_sticky_comment(pr.id, "## Stack", "## Stack\nretargeted")
+            .await
+            .unwrap();
+        manager
+            .upsert_sticky_comment(pr.id, "## Stack", "## Stack\nretargeted")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            provider().comments(pr.id),
+            vec!["Looks good".to_string(), "## Stack\nretargeted".to_string()]
+        );
+        let sticky = provider().comments_of(pr.id).unwrap().pop().unwrap();
+        assert_eq!((sticky.id, sticky.version), (2, 1));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_adds_missing_reviewers() {
+        let config = BitbucketConfig::default();
+        let manager = PullRequestManager::with_provider(Box::new(MockProvider::new(&config)));
+        let pr = manager.create_pull_request(request("a")).await.unwrap();
+        let existing: Vec<String> = pr.reviewers.iter().map(|r| r.user.name.clone()).collect();
+
+        let mut wanted = existing.clone();
+        wanted.push("alice".to_string());
+        let refreshed = manager
+            .refresh_pull_request(pr.id, Some("New title".to_string()), None, &wanted)
+            .await
+            .unwrap();
+
+        assert_eq!(refreshed.title, "New title");
+        assert_eq!(refreshed.reviewers.len(), existing.len() + 1);
+        let alice = refreshed.reviewers.last().unwrap();
+        assert_eq!(alice.user.name, "alice");
+        assert!(!alice.approved);
+    }
+
+    #[test]
+    fn test_state_with_plain_text_comments_loads() {
+        let state: MockState = serde_json::from_str(
+            r#"{"next_id": 2, "clock": 1, "pull_requests": [], "comments": {"1": ["a", "b"]}}"#,
+        )
+        .unwrap();
+        let ids: Vec<u64> = state.comments[&1].iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(state.comments[&1][1].text, "b");
+    }
+
+    #[tokio::test]
+    async fn test_configured_states_gate_merging() {
+        let mut config = BitbucketConfig::default();
+        config.mock.approvals = 0;
+        config.mock.build_state = BuildState::Failed;
+        let manager = manager(&config);
+        let pr = manager.create_pull_request(request("a")).await.unwrap();
+
+        let status = manager.get_pull_request_status(pr.id).await.unwrap();
+        assert!(!status.is_ready_to_land());
+        assert_eq!(status.review_status.current_approvals, 0);
+        assert_eq!(status.build_status.unwrap().state, BuildState::Failed);
+        assert!(manager
+            .merge_pull_request(pr.id, MergeStrategy::Squash)
+            .await
+            .is_err());
+
+        let manager = self::manager(&BitbucketConfig::default());
+        let pr = manager.create_pull_request(request("a")).await.unwrap();
+        let status = manager.get_pull_request_status(pr.id).await.unwrap();
+        assert!(status.is_ready_to_land());
+        let merged = manager
+            .merge_pull_request(pr.id, MergeStrategy::Squash)
+            .await
+            .unwrap();
+        assert_eq!(merged.state, PullRequestState::Merged);
+    }
+
+    #[tokio::test]
+    async fn test_open_tasks_block_auto_merge_until_resolved() {
+        let mut config = BitbucketConfig::default();
+        config.mock.tasks = 2;
+        let manager = manager(&config);
+        let pr = manager.create_pull_request(request("a")).await.unwrap();
+
+        let status = manager.get_pull_request_status(pr.id).await.unwrap();
+        assert_eq!(status.open_tasks.len(), 2);
+        let conditions = AutoMergeConditions {
+            wait_for_builds: false,
+            ..Default::default()
+        };
+        assert!(matches!(
+            manager
+                .auto_merge_if_ready(pr.id, &conditions)
+                .await
+                .unwrap(),
+            AutoMergeResult::NotReady { .. }
+        ));
+
+        manager.resolve_task(pr.id, 1).await.unwrap();
+        assert!(manager.resolve_task(pr.id, 1).await.is_err());
+        let remaining = manager.get_open_tasks(pr.id).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].text, "Mock task 2");
+
+        manager.resolve_task(pr.id, 2).await.unwrap();
+        assert!(matches!(
+            manager
+                .auto_merge_if_ready(pr.id, &conditions)
+                .await
+                .unwrap(),
+            AutoMergeResult::Merged { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_state_persists_and_merges_into_origin() {
+        let temp_dir = TempDir::new().unwrap();
+        let server_path = temp_dir.path().join("origin.git");
+        let server = git2::Repository::init_bare(&server_path).unwrap();
+        let local = git2::Repository::init(temp_dir.path().join("work")).unwrap();
+        local
+            .remote("origin", server_path.to_str().unwrap())
+            .unwrap();
+
+        // main with one commit, feature one commit ahead of it
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = server
+            .find_tree(server.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let base = server
+            .commit(
+                Some("refs/heads/main"),
+                &signature,
+                &signature,
+                "Initial",
+                &tree,
+                &[],
+            )
+            .unwrap();
+        let base = server.find_commit(base).unwrap();
+        let feature = server
+            .commit(
+                Some("refs/heads/a"),
+                &signature,
+                &signature,
+                "Add a",
+                &tree,
+                &[&base],
+            )
+            .unwrap();
+
+        let root = temp_dir.path().join("work");
+        let config = BitbucketConfig::default();
+        let first = PullRequestManager::with_provider(Box::new(
+            MockProvider::new(&config).persisted(&root).unwrap(),
+        ));
+        let pr = first.create_pull_request(request("a")).await.unwrap();
+        assert_eq!(pr.from_ref.latest_commit, feature.to_string());
+        first.add_comment(pr.id, "Looks good").await.unwrap();
+
+        // A second process sees the same pull request
+        let provider = MockProvider::new(&config).persisted(&root).unwrap();
+        assert_eq!(provider.comments(pr.id), vec!["Looks good".to_string()]);
+        let second = PullRequestManager::with_provider(Box::new(provider));
+        let merged = second
+            .merge_pull_request(pr.id, MergeStrategy::Merge)
+            .await
+            .unwrap();
+
+        let main = server
+            .find_reference("refs/heads/main")
+            .unwrap()
+            .peel_to_commit()
+            .unwrap();
+        assert_eq!(main.parent_count(), 2);
+        assert_eq!(main.parent_id(1).unwrap(), feature);
+        assert_eq!(merged.merge_commit(), Some(main.id().to_string().as_str()));
+    }
+}
@@ -2,16 +2,30 @@
 //!
 //! This module provides integration with Bitbucket Server for:
 //! - API client for Bitbucket Server
-//! - Authentication handling
-//! - Pull request management
+//! - Authentication handling, token expiry checks and OAuth device login
+//! - Pull request management, behind a swappable repository provider
 //! - Repository operations
+//! - Recording and replaying API traffic for hermetic tests
 
+pub mod auth;
+pub mod build_provider;
 pub mod client;
 pub mod integration;
+pub mod mock_provider;
+pub mod oauth;
+pub mod provider;
 pub mod pull_request;
+pub mod recording;
 
+pub use auth::{AuthPreflight, AuthStatus};
+pub use build_provider::{BuildProvider, UrlBuildProvider};
 pub use client::BitbucketClient;
-pub use integration::{BitbucketIntegration, StackSubmissionStatus};
+pub use integration::{
+    BitbucketIntegration, PullRequestChainCheck, PullRequestRecovery, RestoredBranch,
+    StackSubmissionStatus,
+};
+pub use mock_provider::MockProvider;
+pub use provider::{configured_repository_provider, PullRequestQuery, RepositoryProvider};
 pub use pull_request::{
     CreatePullRequestRequest, Participant, Project, PullRequest, PullRequestManager,
     PullRequestRef, PullRequestState, Repository, User,
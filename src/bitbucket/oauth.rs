@@ -0,0 +1,464 @@
+//! OAuth 2.0 device authorization (RFC 8628)
+//!
+//! `ca auth login` asks the authorization server for a device code, shows the user
+//! where to approve it, and polls the token endpoint until they do. The refresh token
+//! goes to the git credential helper under the host `<server host>.cascade-oauth`, so
+//! git never offers it for clones and pushes. [`OAuthSession`] trades it for access
+//! tokens as API requests need them; nothing long-lived is written to `.cascade/`.
+
+use crate::config::credentials::{self, CredentialKey};
+use crate::config::BitbucketConfig;
+use crate::errors::{CascadeError, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use tracing::debug;
+use url::Url;
+
+/// Grant type for polling the token endpoint with a device code
+const DEVICE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Access tokens are refreshed this long before they expire
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+/// Polling interval when the server doesn't suggest one
+fn default_interval() -> u64 {
+    5
+}
+
+/// Code the user approves in a browser
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceAuthorization {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    /// Verification page with the code filled in
+    pub verification_uri_complete: Option<String>,
+    /// Seconds until the code stops working
+    pub expires_in: u64,
+    /// Seconds to wait between polls
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+}
+
+/// Tokens issued by the token endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Seconds until the access token expires
+    pub expires_in: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// OAuth client and endpoints from the `oauth.*` settings
+#[derive(Debug, Clone)]
+pub struct OAuthClient {
+    client_id: String,
+    device_url: Option<String>,
+    token_url: String,
+    scopes: Option<String>,
+    /// Where the refresh token is kept in the credential helper
+    credential: CredentialKey,
+}
+
+impl OAuthClient {
+    /// The configured client, or `None` when `oauth.client_id` is not set
+    pub fn from_config(config: &BitbucketConfig) -> Result<Option<Self>> {
+        let Some(client_id) = config.oauth.client_id.clone() else {
+            return Ok(None);
+        };
+        let server = Url::parse(&config.url)
+            .map_err(|e| CascadeError::config(format!("Invalid bitbucket.url: {e}")))?;
+        let host = server
+            .host_str()
+            .ok_or_else(|| CascadeError::config("bitbucket.url has no host"))?;
+        let credential = CredentialKey {
+            protocol: "https".to_string(),
+            host: match server.port() {
+                Some(port) => format!("{host}.cascade-oauth:{port}"),
+                None => format!("{host}.cascade-oauth"),
+            },
+            username: Some(client_id.clone()),
+        };
+
+        Ok(Some(Self {
+            token_url: config.oauth.token_url.clone().unwrap_or_else(|| {
+                format!(
+                    "{}/rest/oauth2/latest/token",
+                    config.url.trim_end_matches('/')
+                )
+            }),
+            device_url: config.oauth.device_url.clone(),
+            scopes: config.oauth.scopes.clone(),
+            client_id,
+            credential,
+        }))
+    }
+
+    /// Ask for a device code to show the user
+    pub async fn start_device_authorization(&self, http: &Client) -> Result<DeviceAuthorization> {
+        let device_url = self.device_url.as_deref().ok_or_else(|| {
+            CascadeError::config(
+                "oauth.device_url is not set; run 'ca config set oauth.device_url <url>' with \
+                 the device authorization endpoint of your authorization server",
+            )
+        })?;
+        let mut form = vec![("client_id", self.client_id.as_str())];
+        if let Some(scopes) = &self.scopes {
+            form.push(("scope", scopes));
+        }
+        let response = http
+            .post(device_url)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| CascadeError::auth(format!("Device authorization request failed: {e}")))?;
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(CascadeError::auth(format!(
+                "Device authorization failed with status {status}: {}",
+                describe_error(&text)
+            )));
+        }
+        serde_json::from_str(&text)
+            .map_err(|e| CascadeError::auth(format!("Invalid device authorization response: {e}")))
+    }
+
+    /// Poll until the user approves or denies `authorization`, or the code expires
+    pub async fn poll_device_token(
+        &self,
+        http: &Client,
+        authorization: &DeviceAuthorization,
+    ) -> Result<TokenResponse> {
+        let deadline = Utc::now() + Duration::seconds(authorization.expires_in as i64);
+        let mut interval = authorization.interval;
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+            let form = [
+                ("grant_type", DEVICE_GRANT),
+                ("device_code", authorization.device_code.as_str()),
+                ("client_id", self.client_id.as_str()),
+            ];
+            match self.token_request(http, &form).await {
+                Ok(tokens) => return Ok(tokens),
+                Err(TokenError::Server(error)) if error.error == "authorization_pending" => {}
+                Err(TokenError::Server(error)) if error.error == "slow_down" => interval += 5,
+                Err(TokenError::Server(error)) if error.error == "access_denied" => {
+                    return Err(CascadeError::auth("The login request was denied"));
+                }
+                Err(TokenError::Server(error)) if error.error == "expired_token" => {
+                    return Err(CascadeError::auth(
+                        "The login code expired; run 'ca auth login' again",
+                    ));
+                }
+                Err(error) => return Err(error.into()),
+            }
+            if Utc::now() >= deadline {
+                return Err(CascadeError::auth(
+                    "The login code expired; run 'ca auth login' again",
+                ));
+            }
+        }
+    }
+
+    /// Trade a refresh token for a new access token
+    pub async fn refresh(&self, http: &Client, refresh_token: &str) -> Result<TokenResponse> {
+        let form = [
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", self.client_id.as_str()),
+        ];
+        self.token_request(http, &form).await.map_err(|e| match e {
+            TokenError::Server(error) if error.error == "invalid_grant" => CascadeError::auth(
+                "The OAuth session has expired or was revoked; run 'ca auth login'",
+            ),
+            e => e.into(),
+        })
+    }
+
+    /// Keep the refresh token in the git credential helper
+    pub fn store_refresh_token(&self, refresh_token: &str) -> Result<()> {
+        credentials::approve(&self.credential, refresh_token)
+    }
+
+    /// Refresh token kept by `ca auth login`
+    pub fn stored_refresh_token(&self) -> Option<String> {
+        credentials::fill(&self.credential).map(|(_, token)| token)
+    }
+
+    /// Remove the refresh token from the credential helper
+    pub fn forget(&self) {
+        credentials::reject(&self.credential);
+    }
+
+    async fn token_request(
+        &self,
+        http: &Client,
+        form: &[(&str, &str)],
+    ) -> std::result::Result<TokenResponse, TokenError> {
+        let response = http
+            .post(&self.token_url)
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| TokenError::Other(format!("Token request failed: {e}")))?;
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        if status.is_success() {
+            return serde_json::from_str(&text)
+                .map_err(|e| TokenError::Other(format!("Invalid token response: {e}")));
+        }
+        match serde_json::from_str::<ErrorResponse>(&text) {
+            Ok(error) => Err(TokenError::Server(error)),
+            Err(_) => Err(TokenError::Other(format!(
+                "Token request failed with status {status}: {text}"
+            ))),
+        }
+    }
+}
+
+enum TokenError {
+    /// OAuth error code from the token endpoint
+    Server(ErrorResponse),
+    Other(String),
+}
+
+impl From<TokenError> for CascadeError {
+    fn from(error: TokenError) -> Self {
+        match error {
+            TokenError::Server(error) => CascadeError::auth(format!(
+                "Token request failed: {}",
+                error.error_description.unwrap_or(error.error)
+            )),
+            TokenError::Other(message) => CascadeError::auth(message),
+        }
+    }
+}
+
+fn describe_error(text: &str) -> String {
+    serde_json::from_str::<ErrorResponse>(text)
+        .map(|error| error.error_description.unwrap_or(error.error))
+        .unwrap_or_else(|_| text.to_string())
+}
+
+#[derive(Default)]
+struct SessionState {
+    refresh_token: Option<String>,
+    access_token: Option<(String, DateTime<Utc>)>,
+}
+
+/// Access tokens for the API client, refreshed shortly before they expire
+pub struct OAuthSession {
+    client: OAuthClient,
+    http: Client,
+    /// Whether rotated refresh tokens are written back to the credential helper
+    persist: bool,
+    state: Mutex<SessionState>,
+}
+
+impl OAuthSession {
+    /// Session using the refresh token kept by `ca auth login`
+    pub fn new(client: OAuthClient, http: Client) -> Self {
+        Self {
+            client,
+            http,
+            persist: true,
+            state: Mutex::new(SessionState::default()),
+        }
+    }
+
+    /// Use `refresh_token` instead of the credential helper's, without saving it
+    pub fn with_refresh_token(mut self, refresh_token: impl Into<String>) -> Self {
+        self.persist = false;
+        self.state.get_mut().refresh_token = Some(refresh_token.into());
+        self
+    }
+
+    /// A valid access token, refreshing it when it is about to expire
+    pub async fn access_token(&self) -> Result<String> {
+        let mut state = self.state.lock().await;
+        if let Some((token, expires)) = &state.access_token {
+            if Utc::now() + Duration::seconds(REFRESH_MARGIN_SECS) < *expires {
+                return Ok(token.clone());
+            }
+        }
+
+        if state.refresh_token.is_none() {
+            state.refresh_token = self.client.stored_refresh_token();
+        }
+        let refresh_token = state
+            .refresh_token
+            .clone()
+            .ok_or_else(|| CascadeError::auth("Not logged in to Bitbucket; run 'ca auth login'"))?;
+
+        debug!("Refreshing OAuth access token");
+        let tokens = self.client.refresh(&self.http, &refresh_token).await?;
+        if let Some(rotated) = tokens.refresh_token.filter(|t| *t != refresh_token) {
+            if self.persist {
+                self.client.store_refresh_token(&rotated)?;
+            }
+            state.refresh_token = Some(rotated);
+        }
+        // Servers that don't say get a conservative lifetime
+        let expires = Utc::now() + Duration::seconds(tokens.expires_in.unwrap_or(300));
+        state.access_token = Some((tokens.access_token.clone(), expires));
+        Ok(tokens.access_token)
+    }
+
+    /// Drop the current access token, e.g. after the server rejected it
+    pub async fn invalidate(&self) {
+        self.state.lock().await.access_token = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(server_url: &str) -> BitbucketConfig {
+        let mut config = BitbucketConfig {
+            url: server_url.to_string(),
+            ..Default::default()
+        };
+        config.oauth.client_id = Some("cascade-cli".to_string());
+        config.oauth.device_url = Some(format!("{server_url}/device"));
+        config
+    }
+
+    #[tokio::test]
+    async fn test_device_flow_polls_until_approved() {
+        let mut server = mockito::Server::new_async().await;
+        let _device = server
+            .mock("POST", "/device")
+            .match_body(mockito::Matcher::UrlEncoded(
+                "client_id".into(),
+                "cascade-cli".into(),
+            ))
+            .with_body(
+                r#"{"device_code":"dev-1","user_code":"ABCD-EFGH",
+                    "verification_uri":"https://example.com/activate","expires_in":60,"interval":0}"#,
+            )
+            .create_async()
+            .await;
+        let pending = server
+            .mock("POST", "/rest/oauth2/latest/token")
+            .with_status(400)
+            .with_body(r#"{"error":"authorization_pending"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _approved = server
+            .mock("POST", "/rest/oauth2/latest/token")
+            .match_body(mockito::Matcher::UrlEncoded(
+                "device_code".into(),
+                "dev-1".into(),
+            ))
+            .with_body(r#"{"access_token":"at-1","refresh_token":"rt-1","expires_in":3600}"#)
+            .create_async()
+            .await;
+
+        let client = OAuthClient::from_config(&config(&server.url()))
+            .unwrap()
+            .unwrap();
+        let http = Client::new();
+        let authorization = client.start_device_authorization(&http).await.unwrap();
+        assert_eq!(authorization.user_code, "ABCD-EFGH");
+
+        let tokens = client
+            .poll_device_token(&http, &authorization)
+            .await
+            .unwrap();
+        assert_eq!(tokens.access_token, "at-1");
+        assert_eq!(tokens.refresh_token.as_deref(), Some("rt-1"));
+        pending.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_session_refreshes_and_rotates_tokens() {
+        let mut server = mockito::Server::new_async().await;
+        let first = server
+            .mock("POST", "/rest/oauth2/latest/token")
+            .match_body(mockito::Matcher::UrlEncoded(
+                "refresh_token".into(),
+                "rt-1".into(),
+            ))
+            // Already inside the refresh margin, so the next call refreshes again
+            .with_body(r#"{"access_token":"at-1","refresh_token":"rt-2","expires_in":30}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let second = server
+            .mock("POST", "/rest/oauth2/latest/token")
+            .match_body(mockito::Matcher::UrlEncoded(
+                "refresh_token".into(),
+                "rt-2".into(),
+            ))
+            .with_body(r#"{"access_token":"at-2","expires_in":3600}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _revoked = server
+            .mock("POST", "/rest/oauth2/latest/token")
+            .with_status(400)
+            .with_body(r#"{"error":"invalid_grant"}"#)
+            .create_async()
+            .await;
+
+        let client = OAuthClient::from_config(&config(&server.url()))
+            .unwrap()
+            .unwrap();
+        let session = OAuthSession::new(client.clone(), Client::new()).with_refresh_token("rt-1");
+        assert_eq!(session.access_token().await.unwrap(), "at-1");
+        assert_eq!(session.access_token().await.unwrap(), "at-2");
+        assert_eq!(session.access_token().await.unwrap(), "at-2");
+        first.assert_async().await;
+        second.assert_async().await;
+
+        let expired = OAuthSession::new(client, Client::new()).with_refresh_token("rt-old");
+        let err = expired.access_token().await.unwrap_err();
+        assert!(err.to_string().contains("ca auth login"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_client_sends_bearer_and_retries_after_401() {
+        let mut server = mockito::Server::new_async().await;
+        let _tokens = server
+            .mock("POST", "/rest/oauth2/latest/token")
+            .with_body(r#"{"access_token":"at-1","expires_in":3600}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let rejected = server
+            .mock("GET", "/rest/api/1.0/projects/TEST/repos/app")
+            .with_status(401)
+            .expect(1)
+            .create_async()
+            .await;
+        let accepted = server
+            .mock("GET", "/rest/api/1.0/projects/TEST/repos/app")
+            .match_header("authorization", "Bearer at-1")
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let mut config = config(&server.url());
+        config.project = "TEST".to_string();
+        config.repo = "app".to_string();
+        let oauth = OAuthClient::from_config(&config).unwrap().unwrap();
+        let client = crate::bitbucket::BitbucketClient::new(&config).unwrap();
+        let session = OAuthSession::new(oauth, client.http().clone()).with_refresh_token("rt-1");
+        let client = client.with_oauth_session(session);
+
+        client.test_connection().await.unwrap();
+        rejected.assert_async().await;
+        accepted.assert_async().await;
+    }
+}
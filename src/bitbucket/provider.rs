@@ -0,0 +1,886 @@
+use crate::bitbucket::auth::{AuthPreflight, AuthenticatedUser, TokenDetails};
+use crate::bitbucket::client::BitbucketClient;
+use crate::bitbucket::pull_request::{
+    ActivitiesPage, ActivityAction, BuildStatus, BuildStatusResponse, CreatePullRequestRequest,
+    MergePullRequestRequest, MergeabilityDetails, Participant, ParticipantsResponse, PullRequest,
+    PullRequestActivity, PullRequestComment, PullRequestPage, PullRequestState, PullRequestTask,
+    TasksPage,
+};
+use crate::config::{BitbucketConfig, ProviderKind};
+use crate::errors::{CascadeError, Result};
+use crate::git::repository::branch_pattern_matches;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use tracing::debug;
+
+/// Boxed future returned by [`RepositoryProvider`] methods
+pub type ProviderFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Fields to change on an existing pull request.
+///
+/// Providers replace the whole pull request on update, so callers pass the current
+/// reviewers and version along with whatever they change.
+#[derive(Debug, Clone, Default)]
+pub struct PullRequestUpdate {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// New target branch name (without `refs/heads/`)
+    pub target_branch: Option<String>,
+    /// Usernames of the reviewers to keep
+    pub reviewers: Vec<String>,
+    pub version: u64,
+}
+
+/// Filters for [`RepositoryProvider::search_pull_requests`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PullRequestQuery {
+    pub state: Option<PullRequestState>,
+    /// Username of the author
+    pub author: Option<String>,
+    /// Username of a reviewer
+    pub reviewer: Option<String>,
+    /// Together with `reviewer`: only pull requests that reviewer hasn't approved
+    pub unapproved: bool,
+}
+
+impl PullRequestQuery {
+    /// Whether a pull request passes the filters (for providers that filter locally)
+    pub fn matches(&self, pr: &PullRequest) -> bool {
+        if self.state.as_ref().is_some_and(|state| pr.state != *state) {
+            return false;
+        }
+        if self
+            .author
+            .as_ref()
+            .is_some_and(|author| pr.author.user.name != *author)
+        {
+            return false;
+        }
+        match &self.reviewer {
+            Some(reviewer) => pr
+                .reviewers
+                .iter()
+                .any(|r| r.user.name == *reviewer && !(self.unapproved && r.approved)),
+            None => true,
+        }
+    }
+
+    /// Query string for Bitbucket's pull request list (`role.N`/`username.N` filters)
+    fn to_query_string(&self) -> String {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        query.append_pair("limit", "100");
+        if let Some(state) = &self.state {
+            query.append_pair("state", state.as_str());
+        }
+        let mut n = 0;
+        if let Some(author) = &self.author {
+            n += 1;
+            query.append_pair(&format!("role.{n}"), "AUTHOR");
+            query.append_pair(&format!("username.{n}"), author);
+        }
+        if let Some(reviewer) = &self.reviewer {
+            n += 1;
+            query.append_pair(&format!("role.{n}"), "REVIEWER");
+            query.append_pair(&format!("username.{n}"), reviewer);
+            if self.unapproved {
+                query.append_pair(&format!("approved.{n}"), "false");
+            }
+        }
+        query.finish()
+    }
+}
+
+/// Access level a user holds on the repository, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepositoryPermission {
+    Read,
+    Write,
+    Admin,
+}
+
+impl RepositoryPermission {
+    /// Every level, lowest first
+    pub const ALL: [RepositoryPermission; 3] = [Self::Read, Self::Write, Self::Admin];
+
+    /// Bitbucket's name for the permission
+    pub fn as_bitbucket(&self) -> &'static str {
+        match self {
+            Self::Read => "REPO_READ",
+            Self::Write => "REPO_WRITE",
+            Self::Admin => "REPO_ADMIN",
+        }
+    }
+}
+
+impl fmt::Display for RepositoryPermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Admin => "admin",
+        })
+    }
+}
+
+/// Who the provider's credentials belong to and what they may do in the repository
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderIdentity {
+    pub user: AuthenticatedUser,
+    /// Highest permission the user holds on the repository; `None` when they have none
+    pub permission: Option<RepositoryPermission>,
+    /// Scopes of the token in use, where the server lists them
+    pub token: Option<TokenDetails>,
+}
+
+impl ProviderIdentity {
+    /// Whether the token's own scopes allow writing; tokens without listed scopes
+    /// carry the user's permissions
+    pub fn token_can_write(&self) -> bool {
+        self.token
+            .as_ref()
+            .is_none_or(|token| token.permissions.is_empty() || token.can_write())
+    }
+
+    /// Whether the credentials may push branches and open pull requests
+    pub fn can_push(&self) -> bool {
+        self.permission >= Some(RepositoryPermission::Write) && self.token_can_write()
+    }
+
+    /// Whether the credentials may merge pull requests. Merging needs the same write
+    /// access as pushing; branch restrictions can still refuse it for a target branch.
+    pub fn can_merge(&self) -> bool {
+        self.can_push()
+    }
+}
+
+/// Read-only branch restriction: nobody but the exempt users and groups may change
+/// the branch, which includes merging pull requests into it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BranchRestriction {
+    pub exempt_users: Vec<String>,
+    pub exempt_groups: Vec<String>,
+}
+
+impl BranchRestriction {
+    /// Whether the restriction stops `user` from merging. Group membership isn't
+    /// visible to most users, so a restriction exempting any group doesn't count.
+    pub fn blocks(&self, user: &str) -> bool {
+        self.exempt_groups.is_empty() && !self.exempt_users.iter().any(|u| u == user)
+    }
+}
+
+/// Server hosting the repository's pull requests.
+///
+/// [`PullRequestManager`](crate::bitbucket::PullRequestManager) layers review and
+/// merge policy on top of these primitive operations; Bitbucket Server is the real
+/// implementation and [`MockProvider`](crate::bitbucket::MockProvider) serves demos
+/// and tests without a network.
+pub trait RepositoryProvider: Send + Sync {
+    /// Short name used in status messages
+    fn name(&self) -> &str;
+
+    fn create_pull_request<'a>(
+        &'a self,
+        request: &'a CreatePullRequestRequest,
+    ) -> ProviderFuture<'a, PullRequest>;
+
+    fn get_pull_request(&self, pr_id: u64) -> ProviderFuture<'_, PullRequest>;
+
+    fn update_pull_request<'a>(
+        &'a self,
+        pr_id: u64,
+        update: &'a PullRequestUpdate,
+    ) -> ProviderFuture<'a, PullRequest>;
+
+    fn list_pull_requests(
+        &self,
+        state: Option<PullRequestState>,
+    ) -> ProviderFuture<'_, PullRequestPage>;
+
+    /// Pull requests matching author, reviewer and approval filters
+    fn search_pull_requests<'a>(
+        &'a self,
+        query: &'a PullRequestQuery,
+    ) -> ProviderFuture<'a, PullRequestPage>;
+
+    fn decline_pull_request(&self, pr_id: u64, version: u64) -> ProviderFuture<'_, ()>;
+
+    /// Ask reviewers already on a pull request (by username) to review it again
+    fn rerequest_review<'a>(
+        &'a self,
+        pr_id: u64,
+        reviewers: &'a [String],
+    ) -> ProviderFuture<'a, PullRequest>;
+
+    /// Reopen a declined pull request
+    fn reopen_pull_request(&self, pr_id: u64, version: u64) -> ProviderFuture<'_, PullRequest>;
+
+    fn add_comment<'a>(
+        &'a self,
+        pr_id: u64,
+        text: &'a str,
+    ) -> ProviderFuture<'a, PullRequestComment>;
+
+    /// Replace the text of an existing comment
+    fn update_comment<'a>(
+        &'a self,
+        pr_id: u64,
+        comment: &'a PullRequestComment,
+        text: &'a str,
+    ) -> ProviderFuture<'a, PullRequestComment>;
+
+    /// Top-level comments on a pull request, oldest first
+    fn list_comments(&self, pr_id: u64) -> ProviderFuture<'_, Vec<PullRequestComment>>;
+
+    /// Unresolved tasks on a pull request
+    fn open_tasks(&self, pr_id: u64) -> ProviderFuture<'_, Vec<PullRequestTask>>;
+
+    /// Mark a task as done
+    fn resolve_task<'a>(
+        &'a self,
+        pr_id: u64,
+        task: &'a PullRequestTask,
+    ) -> ProviderFuture<'a, PullRequestTask>;
+
+    fn participants(&self, pr_id: u64) -> ProviderFuture<'_, Vec<Participant>>;
+
+    /// Activity stream in the order the provider returns it
+    fn activities(&self, pr_id: u64) -> ProviderFuture<'_, Vec<PullRequestActivity>>;
+
+    /// Server-side merge checks for a pull request
+    fn merge_status(&self, pr_id: u64) -> ProviderFuture<'_, MergeabilityDetails>;
+
+    /// Every build reported for a commit
+    fn build_statuses<'a>(&'a self, commit_hash: &'a str) -> ProviderFuture<'a, Vec<BuildStatus>>;
+
+    fn merge_pull_request<'a>(
+        &'a self,
+        pr_id: u64,
+        request: &'a MergePullRequestRequest,
+    ) -> ProviderFuture<'a, PullRequest>;
+
+    /// The authenticated user and their access to the repository
+    fn identity(&self) -> ProviderFuture<'_, ProviderIdentity>;
+
+    /// Read-only restrictions on `branch`, or `None` when the credentials can't see
+    /// its branch permissions
+    fn merge_restrictions<'a>(
+        &'a self,
+        branch: &'a str,
+    ) -> ProviderFuture<'a, Option<Vec<BranchRestriction>>>;
+}
+
+/// Build the provider selected by `bitbucket.provider`.
+///
+/// The mock keeps its pull requests in the git dir of `repo_root` so they survive
+/// from one `ca` invocation to the next. Bitbucket checks its token before the first
+/// request, caching the result in the same git dir.
+pub fn configured_repository_provider(
+    config: &BitbucketConfig,
+    repo_root: &Path,
+) -> Result<Box<dyn RepositoryProvider>> {
+    match config.provider {
+        ProviderKind::Bitbucket => {
+            let mut client = BitbucketClient::new(config)?;
+            if let Some(preflight) = AuthPreflight::new(config, repo_root) {
+                client = client.with_auth_preflight(preflight);
+            }
+            Ok(Box::new(client))
+        }
+        ProviderKind::Mock => Ok(Box::new(
+            crate::bitbucket::MockProvider::new(config).persisted(repo_root)?,
+        )),
+    }
+}
+
+/// Lightweight reviewer reference for PUT request payloads
+#[derive(Debug, Serialize)]
+struct ReviewerRef {
+    user: UserRef,
+}
+
+/// Lightweight user reference for PUT request payloads and restriction exemptions
+#[derive(Debug, Serialize, Deserialize)]
+struct UserRef {
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct BranchRef {
+    id: String,
+}
+
+/// PUT payload; Bitbucket Server replaces the entire resource
+#[derive(Debug, Serialize)]
+struct UpdatePullRequestBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(rename = "toRef", skip_serializing_if = "Option::is_none")]
+    to_ref: Option<BranchRef>,
+    reviewers: Vec<ReviewerRef>,
+    version: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct RestrictionPage {
+    values: Vec<Restriction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Restriction {
+    matcher: RestrictionMatcher,
+    #[serde(default)]
+    users: Vec<UserRef>,
+    #[serde(default)]
+    groups: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RestrictionMatcher {
+    id: String,
+    #[serde(rename = "type")]
+    kind: MatcherType,
+}
+
+#[derive(Debug, Deserialize)]
+struct MatcherType {
+    id: String,
+}
+
+impl RestrictionMatcher {
+    /// Whether the matcher covers `branch`. Branch model matchers (`MODEL_BRANCH`,
+    /// `MODEL_CATEGORY`) would need the branching model to resolve and never match.
+    fn matches(&self, branch: &str) -> bool {
+        let full_ref = format!("refs/heads/{branch}");
+        match self.kind.id.as_str() {
+            "BRANCH" => self.id == full_ref || self.id == branch,
+            "PATTERN" => {
+                branch_pattern_matches(&self.id, branch)
+                    || branch_pattern_matches(&self.id, &full_ref)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl BitbucketClient {
+    async fn fetch_merge_restrictions(
+        &self,
+        branch: &str,
+    ) -> Result<Option<Vec<BranchRestriction>>> {
+        // Bitbucket shows branch permissions to repository admins only
+        let page: RestrictionPage = match self
+            .get_branch_restrictions("type=read-only&limit=1000")
+            .await
+        {
+            Ok(page) => page,
+            Err(e) => {
+                debug!("Branch restrictions are not visible: {}", e);
+                return Ok(None);
+            }
+        };
+        Ok(Some(
+            page.values
+                .into_iter()
+                .filter(|restriction| restriction.matcher.matches(branch))
+                .map(|restriction| BranchRestriction {
+                    exempt_users: restriction.users.into_iter().map(|u| u.name).collect(),
+                    exempt_groups: restriction.groups,
+                })
+                .collect(),
+        ))
+    }
+
+    async fn fetch_activities(&self, pr_id: u64) -> Result<Vec<PullRequestActivity>> {
+        let mut activities = Vec::new();
+        let mut start = 0;
+        loop {
+            let path = format!("pull-requests/{pr_id}/activities?start={start}");
+            let page: ActivitiesPage = self.get(&path).await?;
+            activities.extend(page.values);
+            match page.next_page_start {
+                Some(next) if !page.is_last_page => start = next,
+                _ => break,
+            }
+        }
+        Ok(activities)
+    }
+
+    /// Tasks are blocker comments since Bitbucket Server 7; the old tasks API is gone in 8
+    async fn fetch_open_tasks(&self, pr_id: u64) -> Result<Vec<PullRequestTask>> {
+        let mut tasks = Vec::new();
+        let mut start = 0;
+        loop {
+            let path = format!("pull-requests/{pr_id}/blocker-comments?state=OPEN&start={start}");
+            let page: TasksPage = self.get(&path).await?;
+            tasks.extend(page.values);
+            match page.next_page_start {
+                Some(next) if !page.is_last_page => start = next,
+                _ => break,
+            }
+        }
+        Ok(tasks)
+    }
+
+    async fn fetch_merge_status(&self, pr_id: u64) -> Result<MergeabilityDetails> {
+        let response: serde_json::Value = self.get(&format!("pull-requests/{pr_id}/merge")).await?;
+
+        let can_merge = response
+            .get("canMerge")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let conflicted = response
+            .get("conflicted")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        // Extract detailed veto reasons if present
+        let mut blocking_reasons = Vec::new();
+
+        if let Some(vetoes) = response.get("vetoes").and_then(|v| v.as_array()) {
+            for veto in vetoes {
+                if let Some(summary) = veto.get("summaryMessage").and_then(|s| s.as_str()) {
+                    blocking_reasons.push(summary.to_string());
+                } else if let Some(detailed) = veto.get("detailedMessage").and_then(|s| s.as_str())
+                {
+                    blocking_reasons.push(detailed.to_string());
+                }
+            }
+        }
+
+        // Add conflict information
+        if conflicted {
+            blocking_reasons.push("Pull request has merge conflicts".to_string());
+        }
+
+        Ok(MergeabilityDetails {
+            can_merge,
+            conflicted,
+            blocking_reasons,
+            server_enforced: true, // This comes from Bitbucket's authoritative check
+        })
+    }
+
+    async fn post_merge(
+        &self,
+        pr_id: u64,
+        request: &MergePullRequestRequest,
+    ) -> Result<PullRequest> {
+        match self
+            .post(&format!("pull-requests/{pr_id}/merge"), request)
+            .await
+        {
+            Ok(result) => Ok(result),
+            Err(e) => {
+                // Parse merge veto errors into human-readable messages
+                let error_str = e.to_string();
+                if let Some(json_start) = error_str.find('{') {
+                    if let Ok(body) =
+                        serde_json::from_str::<serde_json::Value>(&error_str[json_start..])
+                    {
+                        if let Some(vetoes) = body
+                            .get("errors")
+                            .and_then(|e| e.as_array())
+                            .and_then(|arr| arr.first())
+                            .and_then(|e| e.get("vetoes"))
+                            .and_then(|v| v.as_array())
+                        {
+                            let reasons: Vec<String> = vetoes
+                                .iter()
+                                .filter_map(|v| {
+                                    v.get("summaryMessage").and_then(|s| s.as_str()).map(|s| {
+                                        // Strip leading numbering (e.g. "1. Waiting..." → "Waiting...")
+                                        s.trim_start_matches(|c: char| {
+                                            c.is_ascii_digit() || c == '.'
+                                        })
+                                        .trim()
+                                        .to_string()
+                                    })
+                                })
+                                .collect();
+
+                            if !reasons.is_empty() {
+                                return Err(CascadeError::bitbucket(format!(
+                                    "Merge blocked:\n{}",
+                                    reasons
+                                        .iter()
+                                        .map(|r| format!("  - {r}"))
+                                        .collect::<Vec<_>>()
+                                        .join("\n")
+                                )));
+                            }
+                        }
+                    }
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl RepositoryProvider for BitbucketClient {
+    fn name(&self) -> &str {
+        "Bitbucket"
+    }
+
+    fn create_pull_request<'a>(
+        &'a self,
+        request: &'a CreatePullRequestRequest,
+    ) -> ProviderFuture<'a, PullRequest> {
+        Box::pin(self.post("pull-requests", request))
+    }
+
+    fn get_pull_request(&self, pr_id: u64) -> ProviderFuture<'_, PullRequest> {
+        Box::pin(async move { self.get(&format!("pull-requests/{pr_id}")).await })
+    }
+
+    fn update_pull_request<'a>(
+        &'a self,
+        pr_id: u64,
+        update: &'a PullRequestUpdate,
+    ) -> ProviderFuture<'a, PullRequest> {
+        Box::pin(async move {
+            let body = UpdatePullRequestBody {
+                title: update.title.clone(),
+                description: update.description.clone(),
+                to_ref: update.target_branch.as_ref().map(|branch| BranchRef {
+                    id: format!("refs/heads/{branch}"),
+                }),
+                reviewers: update
+                    .reviewers
+                    .iter()
+                    .map(|name| ReviewerRef {
+                        user: UserRef { name: name.clone() },
+                    })
+                    .collect(),
+                version: update.version,
+            };
+            self.put(&format!("pull-requests/{pr_id}"), &body).await
+        })
+    }
+
+    fn list_pull_requests(
+        &self,
+        state: Option<PullRequestState>,
+    ) -> ProviderFuture<'_, PullRequestPage> {
+        Box::pin(async move {
+            let mut path = "pull-requests".to_string();
+            if let Some(state) = state {
+                path.push_str(&format!("?state={}", state.as_str()));
+            }
+            self.get(&path).await
+        })
+    }
+
+    fn search_pull_requests<'a>(
+        &'a self,
+        query: &'a PullRequestQuery,
+    ) -> ProviderFuture<'a, PullRequestPage> {
+        Box::pin(async move {
+            self.get(&format!("pull-requests?{}", query.to_query_string()))
+                .await
+        })
+    }
+
+    fn decline_pull_request(&self, pr_id: u64, version: u64) -> ProviderFuture<'_, ()> {
+        #[derive(Serialize)]
+        struct DeclineRequest {
+            version: u64,
+            #[serde(rename = "participantStatus")]
+            participant_status: String,
+        }
+
+        Box::pin(async move {
+            let body = DeclineRequest {
+                version,
+                participant_status: "DECLINED".to_string(),
+            };
+            let _: serde_json::Value = self
+                .post(&format!("pull-requests/{pr_id}/decline"), &body)
+                .await?;
+            Ok(())
+        })
+    }
+
+    fn rerequest_review<'a>(
+        &'a self,
+        pr_id: u64,
+        reviewers: &'a [String],
+    ) -> ProviderFuture<'a, PullRequest> {
+        // Bitbucket Server has no re-request; taking reviewers off and adding them back
+        // notifies them like a new review request
+        Box::pin(async move {
+            let pr: PullRequest = self.get(&format!("pull-requests/{pr_id}")).await?;
+            let all: Vec<String> = pr.reviewers.iter().map(|r| r.user.name.clone()).collect();
+            let others: Vec<String> = all
+                .iter()
+                .filter(|name| !reviewers.contains(name))
+                .cloned()
+                .collect();
+
+            let without = self
+                .update_pull_request(
+                    pr_id,
+                    &PullRequestUpdate {
+                        reviewers: others,
+                        version: pr.version,
+                        ..Default::default()
+                    },
+                )
+                .await?;
+            self.update_pull_request(
+                pr_id,
+                &PullRequestUpdate {
+                    reviewers: all,
+                    version: without.version,
+                    ..Default::default()
+                },
+            )
+            .await
+        })
+    }
+
+    fn reopen_pull_request(&self, pr_id: u64, version: u64) -> ProviderFuture<'_, PullRequest> {
+        #[derive(Serialize)]
+        struct ReopenRequest {
+            version: u64,
+        }
+
+        Box::pin(async move {
+            self.post(
+                &format!("pull-requests/{pr_id}/reopen"),
+                &ReopenRequest { version },
+            )
+            .await
+        })
+    }
+
+    fn add_comment<'a>(
+        &'a self,
+        pr_id: u64,
+        text: &'a str,
+    ) -> ProviderFuture<'a, PullRequestComment> {
+        #[derive(Serialize)]
+        struct CommentRequest<'t> {
+            text: &'t str,
+        }
+
+        Box::pin(async move {
+            self.post(
+                &format!("pull-requests/{pr_id}/comments"),
+                &CommentRequest { text },
+            )
+            .await
+        })
+    }
+
+    fn update_comment<'a>(
+        &'a self,
+        pr_id: u64,
+        comment: &'a PullRequestComment,
+        text: &'a str,
+    ) -> ProviderFuture<'a, PullRequestComment> {
+        #[derive(Serialize)]
+        struct UpdateCommentRequest<'t> {
+            text: &'t str,
+            version: u64,
+        }
+
+        Box::pin(async move {
+            self.put(
+                &format!("pull-requests/{pr_id}/comments/{}", comment.id),
+                &UpdateCommentRequest {
+                    text,
+                    version: comment.version,
+                },
+            )
+            .await
+        })
+    }
+
+    fn list_comments(&self, pr_id: u64) -> ProviderFuture<'_, Vec<PullRequestComment>> {
+        Box::pin(async move {
+            // Bitbucket Server only lists comments through the activity stream, which
+            // is newest first and has an ADDED entry per top-level comment
+            let mut comments: Vec<PullRequestComment> = Vec::new();
+            for activity in self.fetch_activities(pr_id).await?.into_iter().rev() {
+                if activity.action != ActivityAction::Commented
+                    || activity.comment_action.as_deref() != Some("ADDED")
+                {
+                    continue;
+                }
+                if let Some(comment) = activity.comment {
+                    if !comments.iter().any(|c| c.id == comment.id) {
+                        comments.push(comment);
+                    }
+                }
+            }
+            Ok(comments)
+        })
+    }
+
+    fn open_tasks(&self, pr_id: u64) -> ProviderFuture<'_, Vec<PullRequestTask>> {
+        Box::pin(self.fetch_open_tasks(pr_id))
+    }
+
+    fn resolve_task<'a>(
+        &'a self,
+        pr_id: u64,
+        task: &'a PullRequestTask,
+    ) -> ProviderFuture<'a, PullRequestTask> {
+        #[derive(Serialize)]
+        struct ResolveTaskRequest {
+            state: &'static str,
+            version: u64,
+        }
+
+        Box::pin(async move {
+            self.put(
+                &format!("pull-requests/{pr_id}/blocker-comments/{}", task.id),
+                &ResolveTaskRequest {
+                    state: "RESOLVED",
+                    version: task.version,
+                },
+            )
+            .await
+        })
+    }
+
+    fn participants(&self, pr_id: u64) -> ProviderFuture<'_, Vec<Participant>> {
+        Box::pin(async move {
+            let response: ParticipantsResponse = self
+                .get(&format!("pull-requests/{pr_id}/participants"))
+                .await?;
+            Ok(response.values)
+        })
+    }
+
+    fn activities(&self, pr_id: u64) -> ProviderFuture<'_, Vec<PullRequestActivity>> {
+        Box::pin(self.fetch_activities(pr_id))
+    }
+
+    fn merge_status(&self, pr_id: u64) -> ProviderFuture<'_, MergeabilityDetails> {
+        Box::pin(self.fetch_merge_status(pr_id))
+    }
+
+    fn build_statuses<'a>(&'a self, commit_hash: &'a str) -> ProviderFuture<'a, Vec<BuildStatus>> {
+        Box::pin(async move {
+            let response: BuildStatusResponse = self.get_build_statuses(commit_hash).await?;
+            Ok(response
+                .values
+                .into_iter()
+                .map(|build| BuildStatus {
+                    state: build.state,
+                    url: build.url,
+                    description: build.description,
+                    context: build.name,
+                })
+                .collect())
+        })
+    }
+
+    fn merge_pull_request<'a>(
+        &'a self,
+        pr_id: u64,
+        request: &'a MergePullRequestRequest,
+    ) -> ProviderFuture<'a, PullRequest> {
+        Box::pin(self.post_merge(pr_id, request))
+    }
+
+    fn merge_restrictions<'a>(
+        &'a self,
+        branch: &'a str,
+    ) -> ProviderFuture<'a, Option<Vec<BranchRestriction>>> {
+        Box::pin(self.fetch_merge_restrictions(branch))
+    }
+
+    fn identity(&self) -> ProviderFuture<'_, ProviderIdentity> {
+        Box::pin(async move {
+            let status = self.auth_status().await?;
+            let permission = self.repository_permission().await?;
+            Ok(ProviderIdentity {
+                user: status.user,
+                permission,
+                token: status.token,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_merge_restrictions_match_branch_and_pattern() {
+        let mut server = mockito::Server::new_async().await;
+        let restrictions = server
+            .mock(
+                "GET",
+                "/rest/branch-permissions/2.0/projects/TEST/repos/app/restrictions",
+            )
+            .match_query(mockito::Matcher::UrlEncoded(
+                "type".into(),
+                "read-only".into(),
+            ))
+            .with_body(
+                r#"{"values":[
+                    {"matcher":{"id":"refs/heads/main","type":{"id":"BRANCH"}},
+                     "users":[{"name":"alice"}],"groups":[]},
+                    {"matcher":{"id":"release/*","type":{"id":"PATTERN"}},
+                     "users":[],"groups":["release-managers"]},
+                    {"matcher":{"id":"production","type":{"id":"MODEL_BRANCH"}}}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+        let config = BitbucketConfig {
+            url: server.url(),
+            project: "TEST".to_string(),
+            repo: "app".to_string(),
+            token: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let client = BitbucketClient::new(&config).unwrap();
+
+        let main = client.merge_restrictions("main").await.unwrap().unwrap();
+        assert_eq!(main.len(), 1);
+        assert!(main[0].blocks("jdoe"));
+        assert!(!main[0].blocks("alice"));
+
+        let release = client
+            .merge_restrictions("release/1.2")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(release.len(), 1);
+        assert!(!release[0].blocks("jdoe"));
+
+        assert!(client
+            .merge_restrictions("feature")
+            .await
+            .unwrap()
+            .unwrap()
+            .is_empty());
+        restrictions.remove_async().await;
+
+        let _forbidden = server
+            .mock(
+                "GET",
+                "/rest/branch-permissions/2.0/projects/TEST/repos/app/restrictions",
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(401)
+            .create_async()
+            .await;
+        assert_eq!(client.merge_restrictions("main").await.unwrap(), None);
+    }
+}
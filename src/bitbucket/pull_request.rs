@@ -1,19 +1,66 @@
+use crate::bitbucket::build_provider::{configured_build_provider, BuildProvider};
 use crate::bitbucket::client::BitbucketClient;
+use crate::bitbucket::provider::{
+    configured_repository_provider, BranchRestriction, ProviderIdentity, PullRequestQuery,
+    PullRequestUpdate, RepositoryProvider,
+};
+use crate::config::{BitbucketConfig, BuildSettings};
 use crate::errors::{CascadeError, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tracing::{debug, info};
 
 /// Pull request manager for Bitbucket operations
 pub struct PullRequestManager {
-    client: BitbucketClient,
+    provider: Box<dyn RepositoryProvider>,
+    /// External CI to read build results from instead of the provider's build statuses
+    build_provider: Option<Box<dyn BuildProvider>>,
+    /// Repository whose fetch cache a merge invalidates
+    repo_root: Option<PathBuf>,
 }
 
 impl PullRequestManager {
     /// Create a new pull request manager
     pub fn new(client: BitbucketClient) -> Self {
-        Self { client }
+        Self::with_provider(Box::new(client))
+    }
+
+    /// Create a manager backed by any [`RepositoryProvider`]
+    pub fn with_provider(provider: Box<dyn RepositoryProvider>) -> Self {
+        Self {
+            provider,
+            build_provider: None,
+            repo_root: None,
+        }
+    }
+
+    /// Create a manager for the provider and build provider selected in `settings`
+    pub fn from_settings(settings: &crate::config::Settings, repo_root: &Path) -> Result<Self> {
+        Self::from_config(&settings.bitbucket, &settings.cascade.build, repo_root)
+    }
+
+    /// Create a manager for the provider selected by `bitbucket.provider`, reading
+    /// builds from the `build.*` provider when one is configured
+    pub fn from_config(
+        bitbucket: &BitbucketConfig,
+        build: &BuildSettings,
+        repo_root: &Path,
+    ) -> Result<Self> {
+        let mut manager =
+            Self::with_provider(configured_repository_provider(bitbucket, repo_root)?);
+        manager.repo_root = Some(repo_root.to_path_buf());
+        if let Some(provider) = configured_build_provider(build)? {
+            manager = manager.with_build_provider(provider);
+        }
+        Ok(manager)
+    }
+
+    /// Read build status from `provider` instead of Bitbucket
+    pub fn with_build_provider(mut self, provider: Box<dyn BuildProvider>) -> Self {
+        self.build_provider = Some(provider);
+        self
     }
 
     /// Create a new pull request
@@ -29,15 +76,25 @@ impl PullRequestManager {
             request.title, request.description, request.draft
         );
 
-        let pr: PullRequest = self.client.post("pull-requests", &request).await?;
+        let pr = self.provider.create_pull_request(&request).await?;
 
         // Pull request created successfully
         Ok(pr)
     }
 
+    /// The authenticated user and their access to the repository
+    pub async fn identity(&self) -> Result<ProviderIdentity> {
+        self.provider.identity().await
+    }
+
+    /// Read-only restrictions on `branch`, or `None` when they aren't visible
+    pub async fn merge_restrictions(&self, branch: &str) -> Result<Option<Vec<BranchRestriction>>> {
+        self.provider.merge_restrictions(branch).await
+    }
+
     /// Get a pull request by ID
     pub async fn get_pull_request(&self, pr_id: u64) -> Result<PullRequest> {
-        self.client.get(&format!("pull-requests/{pr_id}")).await
+        self.provider.get_pull_request(pr_id).await
     }
 
     /// Update a pull request (title, description, etc)
@@ -49,38 +106,46 @@ impl PullRequestManager {
     ) -> Result<PullRequest> {
         let current_pr = self.get_pull_request(pr_id).await?;
 
-        // Bitbucket Server PUT replaces the entire resource.
+        // The update replaces the entire resource.
         // Carry existing reviewers through so they aren't wiped.
-        let reviewers: Vec<ReviewerRef> = current_pr
-            .reviewers
-            .iter()
-            .map(|p| ReviewerRef {
-                user: UserRef {
-                    name: p.user.name.clone(),
-                },
-            })
-            .collect();
-
-        #[derive(Debug, Serialize)]
-        struct UpdatePullRequestRequest {
-            #[serde(skip_serializing_if = "Option::is_none")]
-            title: Option<String>,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            description: Option<String>,
-            reviewers: Vec<ReviewerRef>,
-            version: u64,
+        let update = PullRequestUpdate {
+            title,
+            description,
+            target_branch: None,
+            reviewers: reviewer_names(&current_pr),
+            version: current_pr.version,
+        };
+
+        self.provider.update_pull_request(pr_id, &update).await
+    }
+
+    /// Update a pull request's title and description and add any of `reviewers` it lacks.
+    /// Existing reviewers are never removed.
+    pub async fn refresh_pull_request(
+        &self,
+        pr_id: u64,
+        title: Option<String>,
+        description: Option<String>,
+        reviewers: &[String],
+    ) -> Result<PullRequest> {
+        let current_pr = self.get_pull_request(pr_id).await?;
+
+        let mut names = reviewer_names(&current_pr);
+        for reviewer in reviewers {
+            if !names.contains(reviewer) && *reviewer != current_pr.author.user.name {
+                names.push(reviewer.clone());
+            }
         }
 
-        let request = UpdatePullRequestRequest {
+        let update = PullRequestUpdate {
             title,
             description,
-            reviewers,
+            target_branch: None,
+            reviewers: names,
             version: current_pr.version,
         };
 
-        self.client
-            .put(&format!("pull-requests/{pr_id}"), &request)
-            .await
+        self.provider.update_pull_request(pr_id, &update).await
     }
 
     /// Update a pull request's target (destination) branch
@@ -92,40 +157,15 @@ impl PullRequestManager {
         let current_pr = self.get_pull_request(pr_id).await?;
 
         // Carry existing reviewers through so they aren't wiped.
-        let reviewers: Vec<ReviewerRef> = current_pr
-            .reviewers
-            .iter()
-            .map(|p| ReviewerRef {
-                user: UserRef {
-                    name: p.user.name.clone(),
-                },
-            })
-            .collect();
-
-        #[derive(Debug, Serialize)]
-        struct BranchRef {
-            id: String,
-        }
-
-        #[derive(Debug, Serialize)]
-        struct RetargetRequest {
-            #[serde(rename = "toRef")]
-            to_ref: BranchRef,
-            reviewers: Vec<ReviewerRef>,
-            version: u64,
-        }
-
-        let request = RetargetRequest {
-            to_ref: BranchRef {
-                id: format!("refs/heads/{new_target_branch}"),
-            },
-            reviewers,
+        let update = PullRequestUpdate {
+            title: None,
+            description: None,
+            target_branch: Some(new_target_branch.to_string()),
+            reviewers: reviewer_names(&current_pr),
             version: current_pr.version,
         };
 
-        self.client
-            .put(&format!("pull-requests/{pr_id}"), &request)
-            .await
+        self.provider.update_pull_request(pr_id, &update).await
     }
 
     /// List pull requests with optional filters
@@ -133,13 +173,12 @@ impl PullRequestManager {
         &self,
         state: Option<PullRequestState>,
     ) -> Result<PullRequestPage> {
-        let mut path = "pull-requests".to_string();
-
-        if let Some(state) = state {
-            path.push_str(&format!("?state={}", state.as_str()));
-        }
+        self.provider.list_pull_requests(state).await
+    }
 
-        self.client.get(&path).await
+    /// Pull requests matching author, reviewer and approval filters
+    pub async fn search_pull_requests(&self, query: &PullRequestQuery) -> Result<PullRequestPage> {
+        self.provider.search_pull_requests(query).await
     }
 
     /// Update a pull request's source branch by closing the old PR and creating a new one
@@ -187,50 +226,74 @@ impl PullRequestManager {
     pub async fn decline_pull_request(&self, pr_id: u64, reason: &str) -> Result<()> {
         info!("Declining pull request #{}: {}", pr_id, reason);
 
-        #[derive(Serialize)]
-        struct DeclineRequest {
-            version: u64,
-            #[serde(rename = "participantStatus")]
-            participant_status: String,
-        }
-
         // First get the current PR to get its version
         let pr = self.get_pull_request(pr_id).await?;
+        self.provider
+            .decline_pull_request(pr_id, pr.version)
+            .await?;
 
-        let decline_body = DeclineRequest {
-            version: pr.version,
-            participant_status: "DECLINED".to_string(),
-        };
+        info!("Successfully declined pull request #{}", pr_id);
+        Ok(())
+    }
 
-        let path = format!("pull-requests/{pr_id}/decline");
+    /// Reopen a declined pull request
+    pub async fn reopen_pull_request(&self, pr_id: u64) -> Result<PullRequest> {
+        debug!("Reopening pull request #{}", pr_id);
 
-        // Use the client to make the decline request
-        let _: serde_json::Value = self.client.post(&path, &decline_body).await?;
+        let pr = self.get_pull_request(pr_id).await?;
+        self.provider.reopen_pull_request(pr_id, pr.version).await
+    }
 
-        info!("Successfully declined pull request #{}", pr_id);
-        Ok(())
+    /// Ask reviewers of a pull request to review it again
+    pub async fn rerequest_review(&self, pr_id: u64, reviewers: &[String]) -> Result<PullRequest> {
+        debug!("Re-requesting review of PR #{} from {:?}", pr_id, reviewers);
+        self.provider.rerequest_review(pr_id, reviewers).await
     }
 
     /// Add a comment to a pull request explaining the branch update
     pub async fn add_comment(&self, pr_id: u64, comment: &str) -> Result<()> {
         debug!("Adding comment to PR #{}", pr_id);
 
-        #[derive(Serialize)]
-        struct CommentRequest {
-            text: String,
-        }
-
-        let comment_body = CommentRequest {
-            text: comment.to_string(),
-        };
-
-        let path = format!("pull-requests/{pr_id}/comments");
-        let _: serde_json::Value = self.client.post(&path, &comment_body).await?;
+        self.provider.add_comment(pr_id, comment).await?;
 
         debug!("Added comment to PR #{}", pr_id);
         Ok(())
     }
 
+    /// Keep a single comment starting with `header` up to date.
+    ///
+    /// The newest comment that starts with `header` is edited in place, so reviewers see
+    /// one comment that tracks the latest state instead of a new one on every update.
+    /// Nothing is sent when its text is already `text`.
+    pub async fn upsert_sticky_comment(&self, pr_id: u64, header: &str, text: &str) -> Result<()> {
+        let existing = self
+            .provider
+            .list_comments(pr_id)
+            .await?
+            .into_iter()
+            .rev()
+            .find(|comment| comment.text.starts_with(header));
+
+        match existing {
+            Some(comment) if comment.text == text => {
+                debug!("Sticky comment on PR #{} is already up to date", pr_id);
+            }
+            Some(comment) => {
+                debug!("Updating sticky comment {} on PR #{}", comment.id, pr_id);
+                if let Err(e) = self.provider.update_comment(pr_id, &comment, text).await {
+                    // Someone else edited it or it was deleted meanwhile; start a new one
+                    debug!("Could not update comment {}: {}", comment.id, e);
+                    self.provider.add_comment(pr_id, text).await?;
+                }
+            }
+            None => {
+                debug!("Adding sticky comment to PR #{}", pr_id);
+                self.provider.add_comment(pr_id, text).await?;
+            }
+        }
+        Ok(())
+    }
+
     /// Get comprehensive status information for a pull request
     pub async fn get_pull_request_status(&self, pr_id: u64) -> Result<PullRequestStatus> {
         // Get the pull request
@@ -250,6 +313,9 @@ impl PullRequestManager {
         // Get conflicts (fallback gracefully if not available)
         let conflicts = self.get_conflicts(pr_id).await.ok();
 
+        // Get open tasks (fallback gracefully if not available)
+        let open_tasks = self.get_open_tasks(pr_id).await.unwrap_or_default();
+
         Ok(PullRequestStatus {
             pr,
             mergeable,
@@ -258,59 +324,53 @@ impl PullRequestManager {
             build_status,
             review_status,
             conflicts,
+            open_tasks,
         })
     }
 
+    /// Unresolved tasks of a PR, oldest first
+    pub async fn get_open_tasks(&self, pr_id: u64) -> Result<Vec<PullRequestTask>> {
+        let mut tasks = self.provider.open_tasks(pr_id).await?;
+        tasks.sort_by_key(|task| task.id);
+        Ok(tasks)
+    }
+
+    /// Mark an open task of a PR as done
+    pub async fn resolve_task(&self, pr_id: u64, task_id: u64) -> Result<PullRequestTask> {
+        let task = self
+            .provider
+            .open_tasks(pr_id)
+            .await?
+            .into_iter()
+            .find(|task| task.id == task_id)
+            .ok_or_else(|| {
+                CascadeError::validation(format!("PR #{pr_id} has no open task {task_id}"))
+            })?;
+        debug!("Resolving task {} on PR #{}", task_id, pr_id);
+        self.provider.resolve_task(pr_id, &task).await
+    }
+
     /// Get all participants (including reviewers) for a PR
     pub async fn get_pull_request_participants(&self, pr_id: u64) -> Result<Vec<Participant>> {
-        let path = format!("pull-requests/{pr_id}/participants");
-        let response: ParticipantsResponse = self.client.get(&path).await?;
-        Ok(response.values)
+        self.provider.participants(pr_id).await
     }
 
-    /// Check if PR is mergeable and get detailed blocking reasons
-    pub async fn check_mergeable_detailed(&self, pr_id: u64) -> Result<MergeabilityDetails> {
-        let path = format!("pull-requests/{pr_id}/merge");
-
-        match self.client.get::<serde_json::Value>(&path).await {
-            Ok(response) => {
-                let can_merge = response
-                    .get("canMerge")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(false);
-
-                let conflicted = response
-                    .get("conflicted")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(false);
-
-                // Extract detailed veto reasons if present
-                let mut blocking_reasons = Vec::new();
-
-                if let Some(vetoes) = response.get("vetoes").and_then(|v| v.as_array()) {
-                    for veto in vetoes {
-                        if let Some(summary) = veto.get("summaryMessage").and_then(|s| s.as_str()) {
-                            blocking_reasons.push(summary.to_string());
-                        } else if let Some(detailed) =
-                            veto.get("detailedMessage").and_then(|s| s.as_str())
-                        {
-                            blocking_reasons.push(detailed.to_string());
-                        }
-                    }
-                }
+    /// Get a PR's activity stream (opened, approvals, merge...), oldest first
+    pub async fn get_pull_request_activities(
+        &self,
+        pr_id: u64,
+    ) -> Result<Vec<PullRequestActivity>> {
+        let mut activities = self.provider.activities(pr_id).await?;
 
-                // Add conflict information
-                if conflicted {
-                    blocking_reasons.push("Pull request has merge conflicts".to_string());
-                }
+        // Bitbucket returns the newest activity first
+        activities.sort_by_key(|activity| activity.created_date);
+        Ok(activities)
+    }
 
-                Ok(MergeabilityDetails {
-                    can_merge,
-                    conflicted,
-                    blocking_reasons,
-                    server_enforced: true, // This comes from Bitbucket's authoritative check
-                })
-            }
+    /// Check if PR is mergeable and get detailed blocking reasons
+    pub async fn check_mergeable_detailed(&self, pr_id: u64) -> Result<MergeabilityDetails> {
+        match self.provider.merge_status(pr_id).await {
+            Ok(details) => Ok(details),
             Err(_) => {
                 // Fallback: assume mergeable but note we couldn't check
                 Ok(MergeabilityDetails {
@@ -334,13 +394,24 @@ impl PullRequestManager {
         let pr = self.get_pull_request(pr_id).await?;
         let commit_hash = &pr.from_ref.latest_commit;
 
-        match self
-            .client
-            .get_build_statuses::<BuildStatusResponse>(commit_hash)
-            .await
-        {
-            Ok(response) => {
-                if response.values.is_empty() {
+        if let Some(provider) = &self.build_provider {
+            return match provider.build_status(commit_hash).await {
+                Ok(status) => Ok(status),
+                Err(e) => Ok(BuildStatus {
+                    state: BuildState::Unknown,
+                    url: None,
+                    description: Some(format!(
+                        "Build status unavailable from {}: {e}",
+                        provider.name()
+                    )),
+                    context: None,
+                }),
+            };
+        }
+
+        match self.provider.build_statuses(commit_hash).await {
+            Ok(builds) => {
+                if builds.is_empty() {
                     Ok(BuildStatus {
                         state: BuildState::Unknown,
                         url: None,
@@ -350,7 +421,7 @@ impl PullRequestManager {
                 } else {
                     let mut aggregated_state = BuildState::Unknown;
 
-                    for build in &response.values {
+                    for build in &builds {
                         match build.state {
                             BuildState::Failed => {
                                 aggregated_state = BuildState::Failed;
@@ -378,13 +449,13 @@ impl PullRequestManager {
                         }
                     }
 
-                    let representative = response.values.first().unwrap();
+                    let representative = builds.first().unwrap();
 
                     Ok(BuildStatus {
                         state: aggregated_state,
                         url: representative.url.clone(),
                         description: representative.description.clone(),
-                        context: representative.name.clone(),
+                        context: representative.context.clone(),
                     })
                 }
             }
@@ -471,56 +542,15 @@ impl PullRequestManager {
             strategy: merge_strategy,
         };
 
-        match self
-            .client
-            .post(&format!("pull-requests/{pr_id}/merge"), &merge_request)
-            .await
-        {
-            Ok(result) => Ok(result),
-            Err(e) => {
-                // Parse merge veto errors into human-readable messages
-                let error_str = e.to_string();
-                if let Some(json_start) = error_str.find('{') {
-                    if let Ok(body) =
-                        serde_json::from_str::<serde_json::Value>(&error_str[json_start..])
-                    {
-                        if let Some(vetoes) = body
-                            .get("errors")
-                            .and_then(|e| e.as_array())
-                            .and_then(|arr| arr.first())
-                            .and_then(|e| e.get("vetoes"))
-                            .and_then(|v| v.as_array())
-                        {
-                            let reasons: Vec<String> = vetoes
-                                .iter()
-                                .filter_map(|v| {
-                                    v.get("summaryMessage").and_then(|s| s.as_str()).map(|s| {
-                                        // Strip leading numbering (e.g. "1. Waiting..." → "Waiting...")
-                                        s.trim_start_matches(|c: char| {
-                                            c.is_ascii_digit() || c == '.'
-                                        })
-                                        .trim()
-                                        .to_string()
-                                    })
-                                })
-                                .collect();
-
-                            if !reasons.is_empty() {
-                                return Err(CascadeError::bitbucket(format!(
-                                    "Merge blocked:\n{}",
-                                    reasons
-                                        .iter()
-                                        .map(|r| format!("  - {r}"))
-                                        .collect::<Vec<_>>()
-                                        .join("\n")
-                                )));
-                            }
-                        }
-                    }
-                }
-                Err(e)
-            }
+        let merged = self
+            .provider
+            .merge_pull_request(pr_id, &merge_request)
+            .await?;
+        // The base branch moved on the server; the next fetch must see it
+        if let Some(repo_root) = &self.repo_root {
+            crate::git::repository::forget_fetch(repo_root);
         }
+        Ok(merged)
     }
 
     /// Auto-merge a pull request if conditions are met
@@ -585,6 +615,11 @@ impl PullRequestManager {
     }
 }
 
+/// Usernames of a pull request's reviewers
+fn reviewer_names(pr: &PullRequest) -> Vec<String> {
+    pr.reviewers.iter().map(|p| p.user.name.clone()).collect()
+}
+
 /// Request to create a new pull request
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CreatePullRequestRequest {
@@ -621,6 +656,26 @@ pub struct PullRequest {
     #[serde(default)]
     pub reviewers: Vec<Participant>,
     pub links: PullRequestLinks,
+    /// Server-side extras; `mergeCommit` is set once the pull request has been merged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<PullRequestProperties>,
+}
+
+/// Extra pull request properties reported by the server
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct PullRequestProperties {
+    #[serde(
+        rename = "mergeCommit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub merge_commit: Option<CommitRef>,
+}
+
+/// A commit the server refers to
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CommitRef {
+    pub id: String,
 }
 
 /// Pull request reference (branch information)
@@ -696,18 +751,6 @@ pub struct User {
     pub slug: Option<String>, // Make nullable - can be null in some cases
 }
 
-/// Lightweight reviewer reference for PUT request payloads
-#[derive(Debug, Serialize)]
-struct ReviewerRef {
-    user: UserRef,
-}
-
-/// Lightweight user reference for PUT request payloads
-#[derive(Debug, Serialize)]
-struct UserRef {
-    name: String,
-}
-
 /// Pull request state
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "UPPERCASE")]
@@ -765,6 +808,25 @@ impl PullRequest {
         self.links.self_link.first().map(|link| link.href.clone())
     }
 
+    /// Web URL of the diff between two commits in the pull request's repository
+    pub fn compare_url(&self, from_commit: &str, to_commit: &str) -> Option<String> {
+        let web_url = self.web_url()?;
+        let (repo_url, _) = web_url.split_once("/pull-requests/")?;
+        Some(format!(
+            "{repo_url}/compare/diff?sourceBranch={to_commit}&targetBranch={from_commit}"
+        ))
+    }
+
+    /// The commit a merged pull request landed as on its target branch (the squash or
+    /// merge commit), when the server reports it
+    pub fn merge_commit(&self) -> Option<&str> {
+        self.properties
+            .as_ref()?
+            .merge_commit
+            .as_ref()
+            .map(|commit| commit.id.as_str())
+    }
+
     /// Check if the pull request is still open
     pub fn is_open(&self) -> bool {
         self.state == PullRequestState::Open && self.open && !self.closed
@@ -791,6 +853,9 @@ pub struct PullRequestStatus {
     pub build_status: Option<BuildStatus>,
     pub review_status: ReviewStatus,
     pub conflicts: Option<Vec<String>>,
+    /// Unresolved tasks; a checklist reviewers expect done before the PR lands
+    #[serde(default)]
+    pub open_tasks: Vec<PullRequestTask>,
 }
 
 /// Build status from CI/CD systems
@@ -823,9 +888,102 @@ pub struct ReviewStatus {
     pub missing_reviewers: Vec<String>,
 }
 
+/// An event in a pull request's activity stream
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullRequestActivity {
+    pub id: u64,
+    #[serde(rename = "createdDate")]
+    pub created_date: u64,
+    pub user: User,
+    pub action: ActivityAction,
+    /// The comment, for [`ActivityAction::Commented`] activities
+    #[serde(default)]
+    pub comment: Option<PullRequestComment>,
+    /// `ADDED`, `EDITED`, `REPLIED` or `DELETED` for comment activities
+    #[serde(default, rename = "commentAction")]
+    pub comment_action: Option<String>,
+}
+
+impl PullRequestActivity {
+    /// Get the activity time as a DateTime
+    pub fn created_at(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.created_date as i64 / 1000, 0).unwrap_or_else(Utc::now)
+    }
+}
+
+/// A comment on a pull request
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PullRequestComment {
+    pub id: u64,
+    /// Bumped on every edit; updates must send the current version
+    pub version: u64,
+    pub text: String,
+    #[serde(default)]
+    pub author: Option<User>,
+}
+
+/// A task on a pull request; Bitbucket Server 7+ keeps tasks as blocker comments
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PullRequestTask {
+    pub id: u64,
+    /// Bumped on every change; resolving must send the current version
+    pub version: u64,
+    pub text: String,
+    #[serde(default)]
+    pub author: Option<User>,
+    pub state: TaskState,
+}
+
+/// Whether a task still needs doing
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TaskState {
+    Open,
+    Resolved,
+}
+
+/// Kind of pull request activity
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ActivityAction {
+    Opened,
+    Approved,
+    Unapproved,
+    /// A reviewer marked the PR as needing work
+    Reviewed,
+    Merged,
+    Declined,
+    Reopened,
+    Rescoped,
+    Updated,
+    Commented,
+    #[serde(other)]
+    Other,
+}
+
+/// Paginated activity results
+#[derive(Debug, Deserialize)]
+pub(crate) struct ActivitiesPage {
+    pub values: Vec<PullRequestActivity>,
+    #[serde(rename = "isLastPage")]
+    pub is_last_page: bool,
+    #[serde(rename = "nextPageStart")]
+    pub next_page_start: Option<u32>,
+}
+
+/// Paginated task (blocker comment) results
+#[derive(Debug, Deserialize)]
+pub(crate) struct TasksPage {
+    pub values: Vec<PullRequestTask>,
+    #[serde(rename = "isLastPage")]
+    pub is_last_page: bool,
+    #[serde(rename = "nextPageStart")]
+    pub next_page_start: Option<u32>,
+}
+
 /// Response for participants endpoint
 #[derive(Debug, Deserialize)]
-struct ParticipantsResponse {
+pub(crate) struct ParticipantsResponse {
     pub values: Vec<Participant>,
 }
 
@@ -840,13 +998,13 @@ struct MergeabilityResponse {
 
 /// Response for build status
 #[derive(Debug, Deserialize)]
-struct BuildStatusResponse {
+pub(crate) struct BuildStatusResponse {
     pub values: Vec<BuildInfo>,
 }
 
 /// Build information from Bitbucket
 #[derive(Debug, Deserialize)]
-struct BuildInfo {
+pub(crate) struct BuildInfo {
     pub state: BuildState,
     pub name: Option<String>,
     pub url: Option<String>,
@@ -1040,6 +1198,15 @@ impl PullRequestStatus {
             }
         }
 
+        // Task Check
+        if !self.open_tasks.is_empty() {
+            reasons.push(format!(
+                "Tasks: {} open task{}",
+                self.open_tasks.len(),
+                if self.open_tasks.len() == 1 { "" } else { "s" }
+            ));
+        }
+
         // ⚠️ Merge Conflicts Check
         if let Some(conflicts) = &self.conflicts {
             if !conflicts.is_empty() {
@@ -1068,6 +1235,11 @@ impl PullRequestStatus {
             }
         }
 
+        // ✅ Tasks are the reviewers' checklist; never land past unfinished ones
+        if !self.open_tasks.is_empty() {
+            return false;
+        }
+
         // ✅ Use Bitbucket's authoritative merge endpoint result
         // This checks all server-side requirements: approvals, builds, conflicts, etc.
         self.mergeable.unwrap_or(false)
@@ -1095,7 +1267,7 @@ impl Default for AutoMergeConditions {
 }
 
 /// Merge strategy for pull requests
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum MergeStrategy {
     #[serde(rename = "merge-commit")]
@@ -1117,6 +1289,29 @@ impl MergeStrategy {
             _ => None, // Use Bitbucket default
         }
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeStrategy::Merge => "merge",
+            MergeStrategy::Squash => "squash",
+            MergeStrategy::FastForward => "fast-forward",
+        }
+    }
+}
+
+impl std::str::FromStr for MergeStrategy {
+    type Err = CascadeError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "merge" | "merge-commit" => Ok(MergeStrategy::Merge),
+            "squash" => Ok(MergeStrategy::Squash),
+            "fast-forward" | "ff" => Ok(MergeStrategy::FastForward),
+            _ => Err(CascadeError::config(format!(
+                "Invalid merge strategy: {value} (expected 'merge', 'squash' or 'fast-forward')"
+            ))),
+        }
+    }
 }
 
 /// Result of auto-merge attempt
@@ -1136,12 +1331,12 @@ pub enum AutoMergeResult {
 
 /// Merge request payload for Bitbucket Server
 #[derive(Debug, Serialize)]
-struct MergePullRequestRequest {
-    version: u64,
+pub struct MergePullRequestRequest {
+    pub version: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
-    message: Option<String>,
+    pub message: Option<String>,
     #[serde(rename = "strategy")]
-    strategy: MergeStrategy,
+    pub strategy: MergeStrategy,
 }
 
 /// Mergeability details
@@ -1193,6 +1388,7 @@ mod tests {
                     ),
                 }],
             },
+            properties: None,
         }
     }
 
@@ -1269,6 +1465,10 @@ mod tests {
             url.unwrap(),
             "http://bitbucket.local/projects/TEST/repos/test/pull-requests/123"
         );
+        assert_eq!(
+            pr.compare_url("aaa", "bbb").unwrap(),
+            "http://bitbucket.local/projects/TEST/repos/test/compare/diff?sourceBranch=bbb&targetBranch=aaa"
+        );
     }
 
     #[test]
@@ -1349,6 +1549,7 @@ mod tests {
             build_status: Some(create_test_build_status(BuildState::Successful)),
             review_status,
             conflicts: None,
+            open_tasks: Vec::new(),
         };
 
         assert!(status.is_ready_to_land());
@@ -1377,6 +1578,7 @@ mod tests {
             build_status: Some(create_test_build_status(BuildState::Failed)),
             review_status,
             conflicts: Some(vec!["Conflict in file.txt".to_string()]),
+            open_tasks: Vec::new(),
         };
 
         assert!(!status.is_ready_to_land());
@@ -1402,6 +1604,7 @@ mod tests {
                 missing_reviewers: vec!["reviewer1".to_string()],
             },
             conflicts: None,
+            open_tasks: Vec::new(),
         };
 
         let blocking_reasons = pr_status.get_blocking_reasons();
@@ -1441,6 +1644,7 @@ mod tests {
             build_status: Some(create_test_build_status(BuildState::Successful)),
             review_status,
             conflicts: None,
+            open_tasks: Vec::new(),
         };
 
         let conditions = AutoMergeConditions::default();
@@ -1457,6 +1661,21 @@ mod tests {
         let mut status_not_mergeable = status.clone();
         status_not_mergeable.mergeable = Some(false);
         assert!(!status_not_mergeable.can_auto_merge(&conditions));
+
+        // Open tasks hold the PR back even when Bitbucket would merge it
+        let mut status_with_tasks = status.clone();
+        status_with_tasks.open_tasks.push(PullRequestTask {
+            id: 7,
+            version: 0,
+            text: "Update the changelog".to_string(),
+            author: None,
+            state: TaskState::Open,
+        });
+        assert!(!status_with_tasks.can_auto_merge(&conditions));
+        assert_eq!(
+            status_with_tasks.get_blocking_reasons(),
+            vec!["Tasks: 1 open task".to_string()]
+        );
     }
 
     #[test]
@@ -1530,6 +1749,23 @@ mod tests {
         assert!(!needs_work.approved);
     }
 
+    #[test]
+    fn test_activity_deserialization() {
+        let json = r#"{
+            "values": [
+                {"id": 2, "createdDate": 1700000600000, "user": {"name": "jane", "emailAddress": "jane@example.com", "displayName": "Jane", "id": 1, "active": true, "slug": "jane", "type": "NORMAL"}, "action": "APPROVED"},
+                {"id": 1, "createdDate": 1700000000000, "user": {"name": "joe", "emailAddress": "joe@example.com", "displayName": "Joe", "id": 2, "active": true, "slug": "joe", "type": "NORMAL"}, "action": "AUTO_MERGE_REQUESTED"}
+            ],
+            "isLastPage": true
+        }"#;
+
+        let page: ActivitiesPage = serde_json::from_str(json).unwrap();
+        assert!(page.is_last_page);
+        assert_eq!(page.values[0].action, ActivityAction::Approved);
+        assert_eq!(page.values[0].created_at().timestamp(), 1_700_000_600);
+        assert_eq!(page.values[1].action, ActivityAction::Other);
+    }
+
     #[test]
     fn test_polling_frequency_constant() {
         // Test that the polling frequency is 30 seconds as documented
@@ -0,0 +1,270 @@
+//! Record and replay Bitbucket API traffic
+//!
+//! Setting `CASCADE_HTTP_RECORD=<file>` makes [`BitbucketClient`](crate::bitbucket::BitbucketClient)
+//! append every request and response to a JSON fixture. `CASCADE_HTTP_REPLAY=<file>`
+//! answers requests from that fixture instead of the network, so submit and land flows
+//! can run hermetically and a user's recording can reproduce their bug. Credentials are
+//! never written; URLs are stored relative to the server so fixtures work with any
+//! `bitbucket.url`.
+
+use crate::errors::{CascadeError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::debug;
+
+/// Fixture file that receives new traffic
+pub const RECORD_ENV: &str = "CASCADE_HTTP_RECORD";
+/// Fixture file that answers requests instead of the server
+pub const REPLAY_ENV: &str = "CASCADE_HTTP_REPLAY";
+
+/// One request and the server's answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+    /// `ca` arguments of the invocation that made the request
+    #[serde(default)]
+    pub command: String,
+    pub method: String,
+    /// URL path and query below `bitbucket.url`
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<serde_json::Value>,
+    pub status: u16,
+    pub body: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Fixture {
+    exchanges: Vec<Exchange>,
+}
+
+enum Mode {
+    Record,
+    Replay { used: Mutex<Vec<bool>> },
+}
+
+/// Fixture-backed transport selected by [`RECORD_ENV`] or [`REPLAY_ENV`]
+pub struct HttpRecording {
+    path: PathBuf,
+    command: String,
+    mode: Mode,
+    exchanges: Mutex<Vec<Exchange>>,
+}
+
+impl HttpRecording {
+    /// Recording or replay requested through the environment, if any
+    pub fn from_env() -> Result<Option<Self>> {
+        let record = std::env::var_os(RECORD_ENV).filter(|v| !v.is_empty());
+        let replay = std::env::var_os(REPLAY_ENV).filter(|v| !v.is_empty());
+        match (record, replay) {
+            (Some(_), Some(_)) => Err(CascadeError::config(format!(
+                "{RECORD_ENV} and {REPLAY_ENV} cannot be used together"
+            ))),
+            (Some(path), None) => Self::record(Path::new(&path)).map(Some),
+            (None, Some(path)) => Self::replay(Path::new(&path)).map(Some),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Append traffic to `path`, keeping exchanges recorded by earlier invocations
+    pub fn record(path: &Path) -> Result<Self> {
+        let fixture = if path.exists() {
+            load(path)?
+        } else {
+            Fixture::default()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            command: current_command(),
+            mode: Mode::Record,
+            exchanges: Mutex::new(fixture.exchanges),
+        })
+    }
+
+    /// Answer requests from the exchanges in `path`
+    pub fn replay(path: &Path) -> Result<Self> {
+        let fixture = load(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            command: current_command(),
+            mode: Mode::Replay {
+                used: Mutex::new(vec![false; fixture.exchanges.len()]),
+            },
+            exchanges: Mutex::new(fixture.exchanges),
+        })
+    }
+
+    /// Attribute requests to `command` instead of the process arguments
+    pub fn with_command(mut self, command: impl Into<String>) -> Self {
+        self.command = command.into();
+        self
+    }
+
+    pub fn is_replay(&self) -> bool {
+        matches!(self.mode, Mode::Replay { .. })
+    }
+
+    /// Recorded answer for a request.
+    ///
+    /// Exchanges from the same `ca` command are preferred, so a fixture holding a
+    /// whole submit-then-land flow answers each invocation with its own traffic.
+    /// Identical requests get their recorded responses in order, and the last one
+    /// repeats once they run out.
+    pub fn replay_response(
+        &self,
+        method: &str,
+        path: &str,
+        request: Option<&serde_json::Value>,
+    ) -> Result<(u16, String)> {
+        let Mode::Replay { used } = &self.mode else {
+            return Err(CascadeError::bitbucket(
+                "HTTP recording is not in replay mode",
+            ));
+        };
+        let exchanges = self.exchanges.lock().unwrap_or_else(|e| e.into_inner());
+        let mut used = used.lock().unwrap_or_else(|e| e.into_inner());
+
+        let matches = |e: &Exchange| {
+            e.method == method
+                && e.path == path
+                && (request.is_none() || e.request.as_ref() == request)
+        };
+        let candidates: Vec<usize> = {
+            let same_command: Vec<usize> = (0..exchanges.len())
+                .filter(|&i| exchanges[i].command == self.command && matches(&exchanges[i]))
+                .collect();
+            if same_command.is_empty() {
+                (0..exchanges.len())
+                    .filter(|&i| matches(&exchanges[i]))
+                    .collect()
+            } else {
+                same_command
+            }
+        };
+
+        let index = candidates
+            .iter()
+            .copied()
+            .find(|&i| !used[i])
+            .or_else(|| candidates.last().copied())
+            .ok_or_else(|| {
+                CascadeError::bitbucket(format!(
+                    "No recorded response for {method} {path} in {}",
+                    self.path.display()
+                ))
+            })?;
+        used[index] = true;
+        debug!("Replaying {} {} from {}", method, path, self.path.display());
+        Ok((exchanges[index].status, exchanges[index].body.clone()))
+    }
+
+    /// Add an exchange and rewrite the fixture so nothing is lost if `ca` exits early
+    pub fn record_exchange(
+        &self,
+        method: &str,
+        path: &str,
+        request: Option<serde_json::Value>,
+        status: u16,
+        body: &str,
+    ) -> Result<()> {
+        let mut exchanges = self.exchanges.lock().unwrap_or_else(|e| e.into_inner());
+        exchanges.push(Exchange {
+            command: self.command.clone(),
+            method: method.to_string(),
+            path: path.to_string(),
+            request,
+            status,
+            body: body.to_string(),
+        });
+        let fixture = Fixture {
+            exchanges: exchanges.clone(),
+        };
+        crate::utils::atomic_file::write_json(&self.path, &fixture)
+    }
+}
+
+fn load(path: &Path) -> Result<Fixture> {
+    let json = std::fs::read_to_string(path).map_err(|e| {
+        CascadeError::config(format!(
+            "Failed to read HTTP fixture {}: {e}",
+            path.display()
+        ))
+    })?;
+    serde_json::from_str(&json)
+        .map_err(|e| CascadeError::config(format!("Invalid HTTP fixture {}: {e}", path.display())))
+}
+
+/// Arguments of this `ca` invocation, ignoring flags
+fn current_command() -> String {
+    std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_recorded_exchanges_replay_in_order() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("fixture.json");
+
+        let recorder = HttpRecording::record(&path).unwrap().with_command("submit");
+        recorder
+            .record_exchange("GET", "/pr/1", None, 200, r#"{"state":"OPEN"}"#)
+            .unwrap();
+        let land = HttpRecording::record(&path).unwrap().with_command("land");
+        land.record_exchange("GET", "/pr/1", None, 200, r#"{"state":"OPEN"}"#)
+            .unwrap();
+        land.record_exchange("GET", "/pr/1", None, 200, r#"{"state":"MERGED"}"#)
+            .unwrap();
+
+        let replay = HttpRecording::replay(&path).unwrap().with_command("land");
+        assert!(replay.is_replay());
+        let first = replay.replay_response("GET", "/pr/1", None).unwrap();
+        let second = replay.replay_response("GET", "/pr/1", None).unwrap();
+        let third = replay.replay_response("GET", "/pr/1", None).unwrap();
+        assert_eq!(first.1, r#"{"state":"OPEN"}"#);
+        assert_eq!(second.1, r#"{"state":"MERGED"}"#);
+        assert_eq!(third, second);
+
+        let other = HttpRecording::replay(&path).unwrap().with_command("status");
+        assert_eq!(
+            other.replay_response("GET", "/pr/1", None).unwrap().1,
+            r#"{"state":"OPEN"}"#
+        );
+        assert!(other.replay_response("GET", "/pr/2", None).is_err());
+    }
+
+    #[test]
+    fn test_replay_matches_request_bodies() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("fixture.json");
+
+        let recorder = HttpRecording::record(&path).unwrap();
+        for (title, id) in [("first", 1), ("second", 2)] {
+            recorder
+                .record_exchange(
+                    "POST",
+                    "/pull-requests",
+                    Some(serde_json::json!({ "title": title })),
+                    201,
+                    &format!(r#"{{"id":{id}}}"#),
+                )
+                .unwrap();
+        }
+
+        let replay = HttpRecording::replay(&path).unwrap();
+        let second = serde_json::json!({ "title": "second" });
+        assert_eq!(
+            replay
+                .replay_response("POST", "/pull-requests", Some(&second))
+                .unwrap(),
+            (201, r#"{"id":2}"#.to_string())
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use crate::bitbucket::pull_request::{
+    Participant, ParticipantRole, ParticipantStatus, PullRequestManager,
+};
+use crate::cli::output::Output;
+use crate::errors::{CascadeError, Result};
+use crate::git::find_repository_root;
+use crate::stack::{Stack, StackManager};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::env;
+
+/// Where a reviewer stands on one pull request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Review {
+    Approved,
+    NeedsWork,
+    Unreviewed,
+}
+
+impl Review {
+    fn from_status(status: &ParticipantStatus) -> Self {
+        match status {
+            ParticipantStatus::Approved => Self::Approved,
+            ParticipantStatus::NeedsWork => Self::NeedsWork,
+            ParticipantStatus::Unapproved => Self::Unreviewed,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Approved => "approved",
+            Self::NeedsWork => "needs work",
+            Self::Unreviewed => "-",
+        }
+    }
+}
+
+/// One row of the matrix
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct EntryReviews {
+    /// 1-based position in the stack
+    entry: usize,
+    branch: String,
+    pull_request_id: String,
+    merged: bool,
+    /// Reviewers on the pull request by username; others weren't asked
+    reviews: BTreeMap<String, Review>,
+}
+
+/// A submitted entry with the participants of its pull request
+struct SubmittedEntry {
+    entry: usize,
+    branch: String,
+    pull_request_id: String,
+    merged: bool,
+    participants: Vec<Participant>,
+}
+
+/// Entries × reviewers of a stack's submitted entries
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ApprovalMatrix {
+    stack: String,
+    /// Column order: by the first entry each reviewer appears on
+    reviewers: Vec<String>,
+    entries: Vec<EntryReviews>,
+}
+
+impl ApprovalMatrix {
+    /// Build the matrix from each submitted entry's participants (bottom entry first).
+    /// Authors are left out; other participants only count once they've reviewed.
+    fn build(stack_name: &str, submitted: Vec<SubmittedEntry>) -> Self {
+        let mut reviewers: Vec<String> = Vec::new();
+        let mut entries = Vec::new();
+        for submitted in submitted {
+            let mut reviews = BTreeMap::new();
+            for participant in submitted.participants {
+                let review = Review::from_status(&participant.status);
+                let counts = match participant.role {
+                    ParticipantRole::Author => false,
+                    ParticipantRole::Reviewer => true,
+                    ParticipantRole::Participant => review != Review::Unreviewed,
+                };
+                if !counts {
+                    continue;
+                }
+                if !reviewers.contains(&participant.user.name) {
+                    reviewers.push(participant.user.name.clone());
+                }
+                reviews.insert(participant.user.name, review);
+            }
+            entries.push(EntryReviews {
+                entry: submitted.entry,
+                branch: submitted.branch,
+                pull_request_id: submitted.pull_request_id,
+                merged: submitted.merged,
+                reviews,
+            });
+        }
+        Self {
+            stack: stack_name.to_string(),
+            reviewers,
+            entries,
+        }
+    }
+
+    /// Reviewers with open pull requests they haven't reviewed yet, with those PRs;
+    /// the people to nudge, busiest first
+    fn waiting_on(&self) -> Vec<(&str, Vec<&str>)> {
+        let mut waiting: Vec<(&str, Vec<&str>)> = self
+            .reviewers
+            .iter()
+            .map(|reviewer| {
+                let prs = self
+                    .entries
+                    .iter()
+                    .filter(|row| {
+                        !row.merged && row.reviews.get(reviewer) == Some(&Review::Unreviewed)
+                    })
+                    .map(|row| row.pull_request_id.as_str())
+                    .collect();
+                (reviewer.as_str(), prs)
+            })
+            .filter(|(_, prs): &(&str, Vec<&str>)| !prs.is_empty())
+            .collect();
+        waiting.sort_by_key(|(_, prs)| std::cmp::Reverse(prs.len()));
+        waiting
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        let mut header = vec!["ENTRY".to_string(), "PR".to_string()];
+        header.extend(self.reviewers.iter().cloned());
+        let mut rows = vec![header];
+        for row in &self.entries {
+            let mut cells = vec![
+                format!("{}. {}", row.entry, row.branch),
+                if row.merged {
+                    format!("#{} merged", row.pull_request_id)
+                } else {
+                    format!("#{}", row.pull_request_id)
+                },
+            ];
+            cells.extend(self.reviewers.iter().map(|reviewer| {
+                row.reviews
+                    .get(reviewer)
+                    .map(|review| review.label().to_string())
+                    .unwrap_or_default()
+            }));
+            rows.push(cells);
+        }
+        rows
+    }
+}
+
+/// Show who has approved, asked for changes on or not yet reviewed each pull request of
+/// a stack (the active one by default)
+pub async fn run(name: Option<String>, json: bool) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let manager = StackManager::new(&repo_root)?;
+    let stack: &Stack = match &name {
+        Some(name) => manager
+            .get_stack_by_name(name)
+            .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?,
+        None => manager
+            .get_active_stack()
+            .ok_or_else(CascadeError::no_active_stack)?,
+    };
+
+    let submitted: Vec<(usize, &crate::stack::StackEntry, u64)> = stack
+        .entries
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let pr_id = entry.pull_request_id.as_deref()?.parse().ok()?;
+            Some((index + 1, entry, pr_id))
+        })
+        .collect();
+    if submitted.is_empty() {
+        if json {
+            Output::data(serde_json::to_string_pretty(&ApprovalMatrix::build(
+                &stack.name,
+                Vec::new(),
+            ))?);
+            return Ok(());
+        }
+        Output::info(format!("Stack '{}' has no pull requests yet", stack.name));
+        Output::tip("Submit entries with 'ca submit'");
+        return Ok(());
+    }
+
+    let config_dir = crate::config::get_repo_config_dir(&repo_root)?;
+    let settings = crate::config::Settings::load_from_file(&config_dir.join("config.json"))?;
+    let pr_manager = PullRequestManager::from_settings(&settings, &repo_root)?;
+
+    let spinner =
+        (!json).then(|| crate::utils::spinner::Spinner::new("Fetching reviewers...".to_string()));
+    let mut rows = Vec::with_capacity(submitted.len());
+    for (position, entry, pr_id) in submitted {
+        let participants = pr_manager.get_pull_request_participants(pr_id).await?;
+        rows.push(SubmittedEntry {
+            entry: position,
+            branch: entry.branch.clone(),
+            pull_request_id: pr_id.to_string(),
+            merged: entry.is_merged,
+            participants,
+        });
+    }
+    if let Some(spinner) = spinner {
+        spinner.stop();
+    }
+
+    let matrix = ApprovalMatrix::build(&stack.name, rows);
+    if json {
+        Output::data(serde_json::to_string_pretty(&matrix)?);
+        return Ok(());
+    }
+
+    Output::section(format!("Reviews of stack '{}'", matrix.stack));
+    if matrix.reviewers.is_empty() {
+        Output::info("None of the pull requests has reviewers yet");
+        return Ok(());
+    }
+    Output::table(&matrix.rows());
+
+    let waiting = matrix.waiting_on();
+    Output::spacing();
+    if waiting.is_empty() {
+        Output::success("Every reviewer has looked at every open pull request");
+    } else {
+        Output::section("Waiting on");
+        for (reviewer, prs) in waiting {
+            let prs: Vec<String> = prs.iter().map(|id| format!("#{id}")).collect();
+            Output::bullet(format!("{reviewer}: {}", prs.join(", ")));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitbucket::pull_request::User;
+
+    fn participant(name: &str, role: ParticipantRole, status: ParticipantStatus) -> Participant {
+        Participant {
+            user: User {
+                name: name.to_string(),
+                display_name: None,
+                email_address: None,
+                active: true,
+                slug: None,
+            },
+            role,
+            approved: status == ParticipantStatus::Approved,
+            status,
+        }
+    }
+
+    fn submitted(
+        entry: usize,
+        pull_request_id: &str,
+        merged: bool,
+        participants: Vec<Participant>,
+    ) -> SubmittedEntry {
+        SubmittedEntry {
+            entry,
+            branch: format!("auth-{entry}"),
+            pull_request_id: pull_request_id.to_string(),
+            merged,
+            participants,
+        }
+    }
+
+    #[test]
+    fn test_matrix_columns_cells_and_reviewers_to_nudge() {
+        use ParticipantRole::*;
+        use ParticipantStatus::*;
+
+        let matrix = ApprovalMatrix::build(
+            "auth",
+            vec![
+                submitted(
+                    1,
+                    "10",
+                    false,
+                    vec![
+                        participant("me", Author, Unapproved),
+                        participant("alice", Reviewer, Approved),
+                        participant("bob", Reviewer, Unapproved),
+                        // Commented without reviewing: not a column
+                        participant("carol", Participant, Unapproved),
+                    ],
+                ),
+                submitted(
+                    2,
+                    "11",
+                    false,
+                    vec![
+                        participant("bob", Reviewer, NeedsWork),
+                        participant("dave", Participant, Approved),
+                        participant("alice", Reviewer, Unapproved),
+                    ],
+                ),
+                submitted(
+                    3,
+                    "12",
+                    true,
+                    vec![participant("bob", Reviewer, Unapproved)],
+                ),
+            ],
+        );
+
+        assert_eq!(matrix.reviewers, ["alice", "bob", "dave"]);
+        assert_eq!(
+            matrix.rows(),
+            vec![
+                vec!["ENTRY", "PR", "alice", "bob", "dave"],
+                vec!["1. auth-1", "#10", "approved", "-", ""],
+                vec!["2. auth-2", "#11", "-", "needs work", "approved"],
+                vec!["3. auth-3", "#12 merged", "", "-", ""],
+            ]
+        );
+        // Merged pull requests don't need anyone anymore
+        assert_eq!(
+            matrix.waiting_on(),
+            vec![("alice", vec!["11"]), ("bob", vec!["10"])]
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use crate::bitbucket::auth::{AuthPreflight, TokenProblem};
+use crate::bitbucket::oauth::OAuthClient;
+use crate::bitbucket::BitbucketClient;
+use crate::cli::output::Output;
+use crate::config::{get_repo_config_dir, BitbucketConfig, ProviderKind, Settings};
+use crate::errors::{CascadeError, Result};
+use crate::git::find_repository_root;
+use crate::utils::spinner::Spinner;
+use chrono::{DateTime, Utc};
+use std::env;
+use std::path::PathBuf;
+
+/// Log in with the OAuth device flow and keep the refresh token in the credential helper
+pub async fn login() -> Result<()> {
+    let (_, settings) = load_settings()?;
+    let config = &settings.bitbucket;
+    let oauth = oauth_client(config)?;
+    let client = BitbucketClient::new(config)?;
+
+    let authorization = oauth.start_device_authorization(client.http()).await?;
+    Output::section("Log in to Bitbucket");
+    Output::info(format!(
+        "Open {} and enter the code: {}",
+        authorization.verification_uri, authorization.user_code
+    ));
+    let browser_url = authorization
+        .verification_uri_complete
+        .as_deref()
+        .unwrap_or(&authorization.verification_uri);
+    if open::that(browser_url).is_err() {
+        Output::sub_item("Could not open a browser; open the link above yourself");
+    }
+
+    let spinner = Spinner::new("Waiting for approval...".to_string());
+    let tokens = oauth.poll_device_token(client.http(), &authorization).await;
+    spinner.stop();
+    let tokens = tokens?;
+
+    let refresh_token = tokens.refresh_token.ok_or_else(|| {
+        CascadeError::auth(
+            "The server did not issue a refresh token; check that the OAuth client allows \
+             the refresh_token grant",
+        )
+    })?;
+    oauth.store_refresh_token(&refresh_token)?;
+
+    match BitbucketClient::new(config)?.auth_status().await {
+        Ok(status) => Output::success(format!("Logged in as {}", status.user.name)),
+        Err(e) => Output::warning(format!(
+            "Logged in, but the server rejected the new token: {e}"
+        )),
+    }
+    if config.token.is_some() {
+        Output::tip(
+            "bitbucket.token is no longer used while oauth.client_id is set; remove it with \
+             'ca config unset bitbucket.token'",
+        );
+    }
+    Ok(())
+}
+
+/// Forget the refresh token kept by `ca auth login`
+pub async fn logout() -> Result<()> {
+    let (_, settings) = load_settings()?;
+    oauth_client(&settings.bitbucket)?.forget();
+    Output::success("Logged out; the refresh token was removed from the credential helper");
+    Ok(())
+}
+
+/// Show who the configured token authenticates as, its permissions and expiry
+pub async fn status() -> Result<()> {
+    let (repo_root, settings) = load_settings()?;
+    let config = &settings.bitbucket;
+
+    if config.provider == ProviderKind::Mock {
+        Output::info("bitbucket.provider is 'mock' - no credentials are used");
+        return Ok(());
+    }
+
+    Output::section("Bitbucket Authentication");
+    Output::sub_item(format!("Server: {}", config.url));
+    let client = BitbucketClient::new(config)?;
+    if let Some(client_id) = &config.oauth.client_id {
+        Output::sub_item(format!("Method: OAuth (client {client_id})"));
+    } else if let Some(username) = client.credential_helper_user() {
+        let fallback = if config.token.is_some() {
+            ", falling back to bitbucket.token"
+        } else {
+            ""
+        };
+        Output::sub_item(format!(
+            "Method: git credential helper ({username}){fallback}"
+        ));
+    }
+
+    let status = client.auth_status().await.map_err(|e| {
+        let fix = if config.oauth.client_id.is_some() {
+            "Log in again with 'ca auth login'"
+        } else {
+            "Check the token with 'ca config set bitbucket.token <token>'"
+        };
+        CascadeError::auth(format!("{e}\n{fix}, or run 'ca diagnose --network'"))
+    })?;
+    if let Some(preflight) = AuthPreflight::new(config, &repo_root) {
+        preflight.remember(&status);
+    }
+
+    let user = &status.user;
+    let mut identity = match &user.display_name {
+        Some(display_name) => format!("{display_name} ({})", user.name),
+        None => user.name.clone(),
+    };
+    if let Some(email) = &user.email {
+        identity.push_str(&format!(" <{email}>"));
+    }
+    Output::sub_item(format!("User: {identity}"));
+
+    let now = Utc::now();
+    match &status.token {
+        Some(token) => {
+            Output::sub_item(format!("Token: {}", token.name));
+            Output::sub_item(format!("Scopes: {}", token.permissions.join(", ")));
+            if let Some(created) = token.created {
+                Output::sub_item(format!("Created: {}", date(created)));
+            }
+            Output::sub_item(match token.expires {
+                Some(expires) if expires > now => format!(
+                    "Expires: {} (in {} days)",
+                    date(expires),
+                    (expires - now).num_days()
+                ),
+                Some(expires) => format!("Expires: {} (expired)", date(expires)),
+                None => "Expires: never".to_string(),
+            });
+            if let Some(last_used) = token.last_used {
+                Output::sub_item(format!("Last used: {}", date(last_used)));
+            }
+        }
+        None => Output::sub_item(
+            "Token details are not available; the server doesn't list access tokens for \
+             these credentials",
+        ),
+    }
+    Output::spacing();
+
+    let problems = status.problems(now, config.token_warning_days);
+    if problems.is_empty() {
+        Output::success("Credentials are valid");
+        return Ok(());
+    }
+    for problem in &problems {
+        Output::warning(problem);
+    }
+    Output::tip(
+        "Create a new personal access token with repository write permission and run \
+         'ca config set bitbucket.token <token>'",
+    );
+    if problems
+        .iter()
+        .any(|p| matches!(p, TokenProblem::Expired { .. }))
+    {
+        return Err(CascadeError::auth("The Bitbucket token has expired"));
+    }
+    Ok(())
+}
+
+fn load_settings() -> Result<(PathBuf, Settings)> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)?;
+    let config_file = get_repo_config_dir(&repo_root)?.join("config.json");
+    let settings = Settings::load_from_file(&config_file)?;
+    Ok((repo_root, settings))
+}
+
+fn oauth_client(config: &BitbucketConfig) -> Result<OAuthClient> {
+    if config.provider == ProviderKind::Mock {
+        return Err(CascadeError::config(
+            "bitbucket.provider is 'mock' - no credentials are used",
+        ));
+    }
+    OAuthClient::from_config(config)?.ok_or_else(|| {
+        CascadeError::config(
+            "OAuth is not configured; set oauth.client_id and oauth.device_url first, e.g.\n  \
+             ca config set oauth.client_id <client-id>\n  \
+             ca config set oauth.device_url https://auth.example.com/oauth/device/code",
+        )
+    })
+}
+
+fn date(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d").to_string()
+}
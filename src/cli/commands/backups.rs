@@ -0,0 +1,121 @@
+use crate::cli::output::Output;
+use crate::errors::{CascadeError, Result};
+use crate::git::backups::BackupRef;
+use crate::git::GitRepository;
+use chrono::{DateTime, Utc};
+use std::env;
+
+/// List backup refs, optionally only those of one branch
+pub async fn list(branch: Option<String>) -> Result<()> {
+    let git_repo = open_repository()?;
+
+    let backups: Vec<BackupRef> = git_repo
+        .list_backups()?
+        .into_iter()
+        .filter(|backup| branch.as_deref().is_none_or(|b| backup.branch == b))
+        .collect();
+
+    if backups.is_empty() {
+        match branch {
+            Some(branch) => Output::info(format!("No backups found for '{branch}'")),
+            None => Output::info("No backups found"),
+        }
+        return Ok(());
+    }
+
+    Output::section(format!("💾 Backups ({})", backups.len()));
+
+    let mut current_branch: Option<&str> = None;
+    for backup in &backups {
+        if current_branch != Some(backup.branch.as_str()) {
+            Output::info(&backup.branch);
+            current_branch = Some(backup.branch.as_str());
+        }
+        Output::sub_item(format!(
+            "{}  {}  {}",
+            backup.timestamp(),
+            &backup.commit_hash[..8],
+            describe_backup(&git_repo, backup)
+        ));
+    }
+
+    Output::spacing();
+    Output::tip("Restore with: ca backups restore <branch> [--at <timestamp>]");
+    Ok(())
+}
+
+/// Reset a branch to its most recent backup, or the one taken at `at`
+pub async fn restore(branch: String, at: Option<String>) -> Result<()> {
+    let git_repo = open_repository()?;
+
+    let backups: Vec<BackupRef> = git_repo
+        .list_backups()?
+        .into_iter()
+        .filter(|backup| backup.branch == branch)
+        .collect();
+
+    // list_backups returns the newest backup of each branch first
+    let backup = match &at {
+        Some(timestamp) => backups.iter().find(|backup| backup.timestamp() == *timestamp),
+        None => backups.first(),
+    }
+    .ok_or_else(|| match &at {
+        Some(timestamp) => CascadeError::branch(format!(
+            "No backup of '{branch}' taken at {timestamp}. Run 'ca backups list {branch}' to see available backups."
+        )),
+        None => CascadeError::branch(format!("No backups found for '{branch}'")),
+    })?;
+
+    match git_repo.restore_backup(backup)? {
+        Some(previous) => {
+            Output::success(format!(
+                "Restored '{}' to {} (backup from {})",
+                branch,
+                &backup.commit_hash[..8],
+                backup.timestamp()
+            ));
+            Output::sub_item(format!("Previous head saved as: {previous}"));
+        }
+        None => {
+            Output::info(format!(
+                "'{}' already points at {}",
+                branch,
+                &backup.commit_hash[..8]
+            ));
+            return Ok(());
+        }
+    }
+
+    Output::tip("Run 'ca validate' to check the stack still matches its branches");
+    Ok(())
+}
+
+fn open_repository() -> Result<GitRepository> {
+    let repo_path = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Failed to get current directory: {e}")))?;
+
+    GitRepository::open(&repo_path)
+}
+
+/// Commit summary and age of a backup for listings
+fn describe_backup(git_repo: &GitRepository, backup: &BackupRef) -> String {
+    let summary = git_repo
+        .get_commit(&backup.commit_hash)
+        .ok()
+        .and_then(|commit| commit.summary().map(str::to_string))
+        .unwrap_or_default();
+
+    format!("{summary} ({})", format_age(backup.created_at))
+}
+
+/// How long ago `at` was, e.g. `3 hours ago`
+pub(crate) fn format_age(at: DateTime<Utc>) -> String {
+    let age = Utc::now().signed_duration_since(at);
+    if age.num_days() > 0 {
+        format!("{} days ago", age.num_days())
+    } else if age.num_hours() > 0 {
+        format!("{} hours ago", age.num_hours())
+    } else {
+        format!("{} minutes ago", age.num_minutes())
+    }
+}
@@ -0,0 +1,179 @@
+use crate::cli::output::Output;
+use crate::config::{ProviderKind, Settings};
+use crate::errors::{CascadeError, Result};
+use crate::git::{find_repository_root, GitRepository};
+use crate::stack::{Stack, StackManager};
+use std::env;
+use std::path::PathBuf;
+
+/// Open the pull request of one entry of the active stack (the entry you're on by default)
+pub async fn open_entry(entry_num: Option<usize>, print: bool) -> Result<()> {
+    let (repo_root, settings) = load()?;
+    let manager = StackManager::new(&repo_root)?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
+
+    let entry_num = match entry_num {
+        Some(num) => num,
+        None => {
+            let current_branch = GitRepository::open(&repo_root)?.get_current_branch()?;
+            current_entry_number(stack, &current_branch).ok_or_else(|| {
+                CascadeError::config(format!(
+                    "Branch '{current_branch}' is not a stack entry; pass an entry number"
+                ))
+            })?
+        }
+    };
+    if entry_num == 0 || entry_num > stack.entries.len() {
+        return Err(CascadeError::config(format!(
+            "Invalid entry number: {}. Stack has {} entries",
+            entry_num,
+            stack.entries.len()
+        )));
+    }
+
+    let entry = &stack.entries[entry_num - 1];
+    let pr_id = entry.pull_request_id.as_deref().ok_or_else(|| {
+        CascadeError::config(format!(
+            "Entry {entry_num} has no pull request yet. Submit it with 'ca submit {entry_num}'"
+        ))
+    })?;
+
+    open_urls(
+        &settings,
+        &[settings.bitbucket.pull_request_web_url(pr_id)],
+        print,
+    );
+    Ok(())
+}
+
+/// Open every pull request of a stack, the repository's pull request list, or the repository
+pub async fn open_stack(name: Option<String>, list: bool, repo: bool, print: bool) -> Result<()> {
+    let (repo_root, settings) = load()?;
+    let bitbucket = &settings.bitbucket;
+
+    if repo {
+        open_urls(&settings, &[bitbucket.repository_web_url()], print);
+        return Ok(());
+    }
+    if list {
+        open_urls(
+            &settings,
+            &[format!("{}/pull-requests", bitbucket.repository_web_url())],
+            print,
+        );
+        return Ok(());
+    }
+
+    let manager = StackManager::new(&repo_root)?;
+    let stack = match &name {
+        Some(name) => manager
+            .get_stack_by_name(name)
+            .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?,
+        None => manager.get_active_stack().ok_or_else(|| {
+            CascadeError::config("No active stack. Pass a stack name or switch to a stack first")
+        })?,
+    };
+
+    let urls = stack_pull_request_urls(stack, &settings);
+    if urls.is_empty() {
+        Output::info(format!("Stack '{}' has no pull requests yet", stack.name));
+        Output::tip(
+            "Submit entries with 'ca submit', or open the repository with 'ca stack open --repo'",
+        );
+        return Ok(());
+    }
+    open_urls(&settings, &urls, print);
+    Ok(())
+}
+
+/// Open one pull request by ID
+pub fn open_pull_request(pr_id: &str, print: bool) -> Result<()> {
+    let (_, settings) = load()?;
+    open_urls(
+        &settings,
+        &[settings.bitbucket.pull_request_web_url(pr_id)],
+        print,
+    );
+    Ok(())
+}
+
+fn load() -> Result<(PathBuf, Settings)> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+    let config_dir = crate::config::get_repo_config_dir(&repo_root)?;
+    let settings = Settings::load_from_file(&config_dir.join("config.json"))?;
+    Ok((repo_root, settings))
+}
+
+/// 1-based position of the entry whose branch is checked out
+pub(crate) fn current_entry_number(stack: &Stack, current_branch: &str) -> Option<usize> {
+    stack
+        .entries
+        .iter()
+        .position(|entry| entry.branch == current_branch)
+        .map(|index| index + 1)
+}
+
+/// Pull request pages of a stack's entries, bottom to top
+fn stack_pull_request_urls(stack: &Stack, settings: &Settings) -> Vec<String> {
+    stack
+        .entries
+        .iter()
+        .filter_map(|entry| entry.pull_request_id.as_deref())
+        .map(|pr_id| settings.bitbucket.pull_request_web_url(pr_id))
+        .collect()
+}
+
+/// Open each URL in the default browser; print them instead with `--print` or when the mock
+/// provider is in use (its pages don't exist)
+fn open_urls(settings: &Settings, urls: &[String], print: bool) {
+    if print || settings.bitbucket.provider == ProviderKind::Mock {
+        for url in urls {
+            Output::data(url);
+        }
+        return;
+    }
+
+    for url in urls {
+        match open::that(url) {
+            Ok(()) => Output::success(format!("Opened {url}")),
+            Err(e) => {
+                Output::warning(format!("Could not open browser: {e}"));
+                Output::tip(format!("Open manually: {url}"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_urls_skip_unsubmitted_entries() {
+        let mut stack = Stack::new("feature".to_string(), "main".to_string(), None);
+        for (i, branch) in ["a", "b", "c"].iter().enumerate() {
+            stack.push_entry(
+                branch.to_string(),
+                format!("hash{i}"),
+                format!("commit {i}"),
+            );
+        }
+        let ids: Vec<_> = stack.entries.iter().map(|e| e.id).collect();
+        stack.mark_entry_submitted(&ids[0], "7".to_string());
+        stack.mark_entry_submitted(&ids[2], "9".to_string());
+
+        let settings = Settings::default();
+        let urls = stack_pull_request_urls(&stack, &settings);
+        assert_eq!(urls.len(), 2);
+        assert!(urls[0].ends_with("/pull-requests/7"));
+        assert!(urls[1].ends_with("/pull-requests/9"));
+
+        assert_eq!(current_entry_number(&stack, "b"), Some(2));
+        assert_eq!(current_entry_number(&stack, "main"), None);
+    }
+}
@@ -35,7 +35,7 @@ pub async fn run(execute: bool, force: bool) -> Result<()> {
         Output::sub_item(format!("  {} {}", branch_name, branch_info));
     }
 
-    println!(); // Blank line
+    Output::spacing(); // Blank line
 
     if !execute {
         Output::warning("🔍 DRY RUN MODE - No branches will be deleted");
@@ -71,7 +71,7 @@ pub async fn run(execute: bool, force: bool) -> Result<()> {
         }
     }
 
-    println!(); // Blank line
+    Output::spacing(); // Blank line
 
     if deleted > 0 {
         Output::success(format!("✓ Successfully deleted {} branches", deleted));
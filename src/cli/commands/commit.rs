@@ -0,0 +1,178 @@
+use crate::cli::commands::push::PushSpec;
+use crate::cli::output::Output;
+use crate::errors::{CascadeError, Result};
+use crate::git::{find_repository_root, GitRepository};
+use crate::stack::StackManager;
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+/// Options for `ca commit`
+#[derive(Debug, Clone, Default)]
+pub struct CommitOptions {
+    /// Commit message (opens your editor if not provided)
+    pub message: Option<String>,
+    /// Stage modified and deleted tracked files first (like 'git commit -a')
+    pub all: bool,
+    /// Amend the top entry instead of adding a new one
+    pub amend: bool,
+    /// Branch name for the new entry
+    pub branch: Option<String>,
+    /// Force-push the amended entry if it has a PR
+    pub push: bool,
+    /// Skip confirmation prompts
+    pub yes: bool,
+}
+
+/// Commit and add the commit to the active stack in one step
+pub async fn run(options: CommitOptions) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let manager = StackManager::new(&repo_root)?;
+    let repo = GitRepository::open(&repo_root)?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
+
+    // Check before committing: a commit on the base branch can't be pushed to the stack.
+    // Once the stack's base follows its working branch, committing there is the normal flow
+    let current_branch = repo.get_current_branch()?;
+    let on_working_branch = stack.working_branch.as_deref() == Some(current_branch.as_str());
+    if current_branch == stack.base_branch && !on_working_branch {
+        return Err(CascadeError::config(format!(
+            "You're on the base branch '{current_branch}'. Switch to a feature branch first, \
+             or use 'ca push --auto-branch' to move your work onto one"
+        )));
+    }
+
+    if options.amend {
+        return amend_top_entry(&repo_root, options).await;
+    }
+
+    git_commit(&repo_root, &options, false)?;
+    let commit_hash = repo.get_head_commit()?.id().to_string();
+
+    // The post-commit hook may already have added it
+    let manager = StackManager::new(&repo_root)?;
+    let already_tracked = manager
+        .get_active_stack()
+        .is_some_and(|stack| stack.entries.iter().any(|e| e.commit_hash == commit_hash));
+    if already_tracked {
+        return Ok(());
+    }
+
+    super::stack::push(
+        PushSpec::new()
+            .commit(commit_hash)
+            .branch(options.branch)
+            .allow_base_branch(on_working_branch)
+            .yes(options.yes),
+    )
+    .await
+}
+
+/// Amend HEAD, which must be the top entry's commit, and move the entry (and the working
+/// branch) to the amended commit
+async fn amend_top_entry(repo_root: &Path, options: CommitOptions) -> Result<()> {
+    let mut manager = StackManager::new(repo_root)?;
+    let repo = GitRepository::open(repo_root)?;
+
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
+    let top = stack
+        .entries
+        .last()
+        .ok_or_else(|| CascadeError::config("Stack is empty. Use 'ca commit' without --amend"))?
+        .clone();
+    let stack_id = stack.id;
+    let working_branch = stack.working_branch.clone();
+    let position = stack.entries.len();
+
+    if top.is_merged {
+        return Err(CascadeError::config(format!(
+            "Entry {position} is already merged and cannot be amended"
+        )));
+    }
+    let head = repo.get_head_commit()?.id().to_string();
+    if head != top.commit_hash {
+        return Err(CascadeError::config(format!(
+            "HEAD is not the top entry's commit ({}). Use 'ca entry amend' to amend another entry",
+            top.short_hash()
+        )));
+    }
+
+    git_commit(repo_root, &options, true)?;
+    let new_hash = repo.get_head_commit()?.id().to_string();
+
+    let current_branch = repo.get_current_branch()?;
+    if top.branch != current_branch {
+        repo.update_branch_to_commit(&top.branch, &new_hash)?;
+    }
+    if let Some(working) = working_branch.filter(|w| *w != current_branch) {
+        repo.update_branch_to_commit(&working, &new_hash)?;
+    }
+    manager
+        .get_stack_mut(&stack_id)
+        .ok_or_else(|| CascadeError::config("Stack not found"))?
+        .update_entry_commit_hash(&top.id, new_hash.clone())
+        .map_err(CascadeError::config)?;
+    manager.save_to_disk()?;
+
+    Output::success(format!(
+        "Amended entry #{position} ({} → {})",
+        top.short_hash(),
+        &new_hash[..8]
+    ));
+
+    if top.pull_request_id.is_none() {
+        return Ok(());
+    }
+    if options.push {
+        // Skip the force-push confirmation; --push already asked for it
+        std::env::set_var("FORCE_PUSH_NO_CONFIRM", "1");
+        repo.force_push_branch_with_lease(
+            &top.branch,
+            &top.branch,
+            top.remote_commit_hash.as_deref(),
+        )?;
+        manager.set_entry_remote_commit(&stack_id, &top.id, new_hash)?;
+        Output::success(format!("Force-pushed '{}'; the PR will update", top.branch));
+    } else {
+        Output::tip("Use 'ca commit --amend --push' to update the entry's PR");
+    }
+    Ok(())
+}
+
+/// Run `git commit` with the user's hooks, editor and terminal
+fn git_commit(repo_root: &Path, options: &CommitOptions, amend: bool) -> Result<()> {
+    let mut args = vec!["commit"];
+    if options.all {
+        args.push("-a");
+    }
+    if amend {
+        args.push("--amend");
+    }
+    match &options.message {
+        Some(message) => args.extend(["-m", message]),
+        None if amend => args.push("--no-edit"),
+        None => {}
+    }
+
+    let status = Command::new("git")
+        .args(&args)
+        .current_dir(repo_root)
+        .status()
+        .map_err(CascadeError::Io)?;
+    if !status.success() {
+        return Err(CascadeError::branch(if amend {
+            "git commit --amend failed".to_string()
+        } else {
+            "git commit failed (stage changes with 'git add' or pass -a)".to_string()
+        }));
+    }
+    Ok(())
+}
@@ -1,23 +1,74 @@
+use crate::cli::commands::hooks::HookType;
 use crate::cli::output::Output;
 use crate::cli::Cli;
+use crate::config::CONFIG_KEYS;
 use crate::errors::{CascadeError, Result};
-use clap::CommandFactory;
+use crate::stack::StackManager;
+use clap::{Command, CommandFactory, ValueEnum};
 use clap_complete::{generate, Shell};
 use std::fs;
-use std::io;
 use std::path::PathBuf;
 
+/// Shells `ca completions` can generate and install scripts for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    #[value(name = "powershell", alias = "pwsh")]
+    PowerShell,
+    #[value(name = "nushell", alias = "nu")]
+    Nushell,
+    Elvish,
+}
+
+impl CompletionShell {
+    /// The clap_complete generator for this shell; Nushell has none and is generated here
+    fn clap_shell(self) -> Option<Shell> {
+        match self {
+            CompletionShell::Bash => Some(Shell::Bash),
+            CompletionShell::Zsh => Some(Shell::Zsh),
+            CompletionShell::Fish => Some(Shell::Fish),
+            CompletionShell::PowerShell => Some(Shell::PowerShell),
+            CompletionShell::Elvish => Some(Shell::Elvish),
+            CompletionShell::Nushell => None,
+        }
+    }
+}
+
 /// Generate shell completions for the specified shell
-pub fn generate_completions(shell: Shell) -> Result<()> {
+pub fn generate_completions(shell: CompletionShell) -> Result<()> {
+    Output::data(completion_script(shell).trim_end());
+    Ok(())
+}
+
+/// Full completion script for `shell`, including the dynamic value completers
+fn completion_script(shell: CompletionShell) -> String {
     let mut cmd = Cli::command();
-    let bin_name = "ca";
 
-    generate(shell, &mut cmd, bin_name, &mut io::stdout());
-    Ok(())
+    let Some(clap_shell) = shell.clap_shell() else {
+        return generate_nushell_completion(&mut cmd);
+    };
+
+    let mut content = Vec::new();
+    generate(clap_shell, &mut cmd, "ca", &mut content);
+    let mut script = String::from_utf8_lossy(&content).into_owned();
+
+    if shell == CompletionShell::PowerShell {
+        // Keep clap's completer as the fallback and register one that knows about dynamic values
+        script = script.replacen(
+            "Register-ArgumentCompleter -Native -CommandName 'ca' -ScriptBlock {",
+            "$_caStaticCompleter = {",
+            1,
+        );
+    }
+
+    script.push_str(&generate_custom_completion(shell));
+    script
 }
 
 /// Install shell completions to the system
-pub fn install_completions(shell: Option<Shell>) -> Result<()> {
+pub fn install_completions(shell: Option<CompletionShell>) -> Result<()> {
     let shells_to_install = if let Some(shell) = shell {
         vec![shell]
     } else {
@@ -46,37 +97,50 @@ pub fn install_completions(shell: Option<Shell>) -> Result<()> {
             Output::sub_item(format!("{:?}: {}", shell, path.display()));
         }
 
-        println!();
+        Output::spacing();
         Output::tip("Next steps:");
 
         // Provide shell-specific setup instructions
         for (shell, path) in &installed {
             let path_str = path.to_string_lossy();
             match shell {
-                Shell::Zsh if path_str.contains(".zsh/completions") => {
-                    println!();
+                CompletionShell::Zsh if path_str.contains(".zsh/completions") => {
+                    Output::spacing();
                     Output::warning("⚠️  Zsh requires additional setup:");
                     Output::bullet("Add this to your ~/.zshrc:");
-                    println!("      fpath=(~/.zsh/completions $fpath)");
-                    println!("      autoload -Uz compinit && compinit");
+                    Output::line("      fpath=(~/.zsh/completions $fpath)");
+                    Output::line("      autoload -Uz compinit && compinit");
                     Output::bullet("Then reload: source ~/.zshrc");
                 }
-                Shell::Bash if path_str.contains(".bash_completion.d") => {
-                    println!();
+                CompletionShell::Bash if path_str.contains(".bash_completion.d") => {
+                    Output::spacing();
                     Output::info("For bash completions to work:");
                     Output::bullet("Ensure bash-completion is installed");
                     Output::bullet("Then reload: source ~/.bashrc");
                 }
+                CompletionShell::PowerShell => {
+                    Output::spacing();
+                    Output::info("For PowerShell completions to work:");
+                    Output::bullet("Add this line to your $PROFILE:");
+                    Output::line(format!("      . '{path_str}'"));
+                    Output::bullet("Then restart PowerShell");
+                }
+                CompletionShell::Nushell => {
+                    Output::spacing();
+                    Output::info("Nushell 0.101+ loads files in autoload/ automatically");
+                    Output::bullet("On older versions add this line to your config.nu:");
+                    Output::line(format!("      source '{path_str}'"));
+                }
                 _ => {}
             }
         }
 
-        println!();
+        Output::spacing();
         Output::bullet("Try: ca <TAB><TAB>");
     }
 
     if !errors.is_empty() {
-        println!();
+        Output::spacing();
         Output::warning("Some installations failed:");
         for (shell, error) in errors {
             Output::sub_item(format!("{shell:?}: {error}"));
@@ -87,7 +151,7 @@ pub fn install_completions(shell: Option<Shell>) -> Result<()> {
 }
 
 /// Detect current shell first, then fall back to available shells
-fn detect_current_and_available_shells() -> Vec<Shell> {
+fn detect_current_and_available_shells() -> Vec<CompletionShell> {
     let mut shells = Vec::new();
 
     // First, try to detect the current shell from SHELL environment variable
@@ -103,40 +167,63 @@ fn detect_current_and_available_shells() -> Vec<Shell> {
 }
 
 /// Detect the current shell from the SHELL environment variable
-fn detect_current_shell() -> Option<Shell> {
-    let shell_path = std::env::var("SHELL").ok()?;
-    let shell_name = std::path::Path::new(&shell_path).file_name()?.to_str()?;
+///
+/// Nushell and PowerShell often run without SHELL pointing at them (always on
+/// Windows), so when it is unset their own environment variables are checked instead.
+fn detect_current_shell() -> Option<CompletionShell> {
+    let Ok(shell_path) = std::env::var("SHELL") else {
+        if std::env::var_os("NU_VERSION").is_some() {
+            return Some(CompletionShell::Nushell);
+        }
+        if std::env::var_os("PSModulePath").is_some() {
+            return Some(CompletionShell::PowerShell);
+        }
+        return None;
+    };
+    let shell_name = std::path::Path::new(&shell_path).file_stem()?.to_str()?;
 
     match shell_name {
-        "bash" => Some(Shell::Bash),
-        "zsh" => Some(Shell::Zsh),
-        "fish" => Some(Shell::Fish),
+        "bash" => Some(CompletionShell::Bash),
+        "zsh" => Some(CompletionShell::Zsh),
+        "fish" => Some(CompletionShell::Fish),
+        "pwsh" | "powershell" => Some(CompletionShell::PowerShell),
+        "nu" => Some(CompletionShell::Nushell),
         _ => None,
     }
 }
 
 /// Detect which shells are available on the system
-fn detect_available_shells() -> Vec<Shell> {
+fn detect_available_shells() -> Vec<CompletionShell> {
     let mut shells = Vec::new();
 
     // Check for bash
     if which_shell("bash").is_some() {
-        shells.push(Shell::Bash);
+        shells.push(CompletionShell::Bash);
     }
 
     // Check for zsh
     if which_shell("zsh").is_some() {
-        shells.push(Shell::Zsh);
+        shells.push(CompletionShell::Zsh);
     }
 
     // Check for fish
     if which_shell("fish").is_some() {
-        shells.push(Shell::Fish);
+        shells.push(CompletionShell::Fish);
+    }
+
+    // Check for PowerShell (pwsh is PowerShell 7+, powershell the Windows built-in)
+    if which_shell("pwsh").is_some() || which_shell("powershell").is_some() {
+        shells.push(CompletionShell::PowerShell);
+    }
+
+    // Check for nushell
+    if which_shell("nu").is_some() {
+        shells.push(CompletionShell::Nushell);
     }
 
     // Default to bash if nothing found
     if shells.is_empty() {
-        shells.push(Shell::Bash);
+        shells.push(CompletionShell::Bash);
     }
 
     shells
@@ -159,12 +246,12 @@ fn which_shell(shell: &str) -> Option<PathBuf> {
 }
 
 /// Install completion for a specific shell
-fn install_completion_for_shell(shell: Shell) -> Result<PathBuf> {
+fn install_completion_for_shell(shell: CompletionShell) -> Result<PathBuf> {
     // Get platform-specific completion directories
     let completion_dirs = crate::utils::platform::shell_completion_dirs();
 
     let (completion_dir, filename) = match shell {
-        Shell::Bash => {
+        CompletionShell::Bash => {
             // Prioritize user directories over system directories
             let bash_dirs: Vec<_> = completion_dirs
                 .iter()
@@ -201,7 +288,7 @@ fn install_completion_for_shell(shell: Shell) -> Result<PathBuf> {
 
             (dir, "ca")
         }
-        Shell::Zsh => {
+        CompletionShell::Zsh => {
             // Prioritize user directories over system directories
             let zsh_dirs: Vec<_> = completion_dirs
                 .iter()
@@ -238,7 +325,7 @@ fn install_completion_for_shell(shell: Shell) -> Result<PathBuf> {
 
             (dir, "_ca")
         }
-        Shell::Fish => {
+        CompletionShell::Fish => {
             // Prioritize user directories over system directories
             let fish_dirs: Vec<_> = completion_dirs
                 .iter()
@@ -275,7 +362,19 @@ fn install_completion_for_shell(shell: Shell) -> Result<PathBuf> {
 
             (dir, "ca.fish")
         }
-        _ => {
+        CompletionShell::PowerShell => {
+            let dir = powershell_completion_dir().ok_or_else(|| {
+                CascadeError::config("Could not find suitable PowerShell completion directory")
+            })?;
+            (dir, "ca.ps1")
+        }
+        CompletionShell::Nushell => {
+            let dir = nushell_autoload_dir().ok_or_else(|| {
+                CascadeError::config("Could not find suitable nushell autoload directory")
+            })?;
+            (dir, "ca.nu")
+        }
+        CompletionShell::Elvish => {
             return Err(CascadeError::config(format!(
                 "Unsupported shell: {shell:?}"
             )));
@@ -293,16 +392,8 @@ fn install_completion_for_shell(shell: Shell) -> Result<PathBuf> {
     // Validate the completion file path for security
     crate::utils::path_validation::validate_config_path(&completion_file, &completion_dir)?;
 
-    // Generate completion content
-    let mut cmd = Cli::command();
-    let mut content = Vec::new();
-    generate(shell, &mut cmd, "ca", &mut content);
-
-    // Add custom completion logic for stack names
-    let custom_completion = generate_custom_completion(shell);
-    if !custom_completion.is_empty() {
-        content.extend_from_slice(custom_completion.as_bytes());
-    }
+    // Generate completion content, including the dynamic value completers
+    let content = completion_script(shell).into_bytes();
 
     // Write to file atomically, with fallback for lock failures
     match crate::utils::atomic_file::write_bytes(&completion_file, &content) {
@@ -328,6 +419,20 @@ fn install_completion_for_shell(shell: Shell) -> Result<PathBuf> {
     Ok(completion_file)
 }
 
+/// Directory for PowerShell completion scripts, dot-sourced from `$PROFILE`
+fn powershell_completion_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        dirs::document_dir().map(|d| d.join("PowerShell").join("Completions"))
+    } else {
+        dirs::config_dir().map(|d| d.join("powershell").join("Completions"))
+    }
+}
+
+/// Nushell's user autoload directory, sourced automatically at startup (0.101+)
+fn nushell_autoload_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("nushell").join("autoload"))
+}
+
 /// Show installation status and guidance
 pub fn show_completions_status() -> Result<()> {
     Output::section("Shell Completions Status");
@@ -349,17 +454,17 @@ pub fn show_completions_status() -> Result<()> {
         .all(|s| check_completion_installed(*s));
 
     if !all_installed {
-        println!();
+        Output::spacing();
         Output::tip("To install completions:");
         Output::command_example("ca completions install");
         Output::command_example("ca completions install --shell bash  # for specific shell");
     } else {
-        println!();
+        Output::spacing();
         Output::success("All available shells have completions installed!");
 
         // Check if zsh is available and provide setup instructions
-        if available_shells.contains(&Shell::Zsh) {
-            println!();
+        if available_shells.contains(&CompletionShell::Zsh) {
+            Output::spacing();
 
             // Check if zsh is already configured
             let zshrc_path = dirs::home_dir()
@@ -398,29 +503,29 @@ pub fn show_completions_status() -> Result<()> {
 
             if needs_fpath || needs_compinit {
                 Output::warning("Zsh requires additional setup for completions to work");
-                println!();
+                Output::spacing();
 
                 if using_omz {
                     Output::sub_item("Detected Oh-My-Zsh - special setup required:");
-                    println!();
+                    Output::spacing();
                     if let Some(line_num) = omz_line {
                         Output::info(format!("Oh-My-Zsh loads at line {} in ~/.zshrc", line_num));
                         Output::sub_item("The fpath MUST be set BEFORE Oh-My-Zsh loads");
                         Output::sub_item(
                             "Oh-My-Zsh calls compinit internally, so DON'T add compinit yourself",
                         );
-                        println!();
+                        Output::spacing();
                     }
 
                     Output::sub_item("Option 1: Manual edit (recommended)");
                     Output::bullet("Open ~/.zshrc in an editor");
                     Output::bullet("Find the line: source $ZSH/oh-my-zsh.sh");
                     Output::bullet("Add this line BEFORE it:");
-                    println!("      fpath=(~/.zsh/completions $fpath)");
+                    Output::line("      fpath=(~/.zsh/completions $fpath)");
                     Output::bullet("Make sure there's NO 'compinit' line at the end of ~/.zshrc");
                     Output::bullet("Save, then clear Oh-My-Zsh cache and reload:");
-                    println!("      rm -f ~/.zcompdump && exec zsh");
-                    println!();
+                    Output::line("      rm -f ~/.zcompdump && exec zsh");
+                    Output::spacing();
 
                     Output::sub_item("Option 2: Automatic (requires sed)");
                     if let Some(line_num) = omz_line {
@@ -433,7 +538,7 @@ pub fn show_completions_status() -> Result<()> {
                     }
                 } else {
                     Output::sub_item("Run these commands to complete setup:");
-                    println!();
+                    Output::spacing();
 
                     if needs_fpath {
                         Output::command_example(
@@ -451,7 +556,7 @@ pub fn show_completions_status() -> Result<()> {
                 Output::success("Zsh is properly configured for completions!");
 
                 if using_omz {
-                    println!();
+                    Output::spacing();
                     Output::tip("If completions aren't working, clear Oh-My-Zsh cache:");
                     Output::command_example("rm -f ~/.zcompdump && exec zsh");
                 }
@@ -459,79 +564,415 @@ pub fn show_completions_status() -> Result<()> {
         }
     }
 
-    println!();
+    Output::spacing();
     Output::section("Manual installation");
     Output::command_example("ca completions generate bash > ~/.bash_completion.d/ca");
     Output::command_example("ca completions generate zsh > ~/.zsh/completions/_ca");
     Output::command_example("ca completions generate fish > ~/.config/fish/completions/ca.fish");
+    Output::command_example("ca completions generate powershell >> $PROFILE");
+    Output::command_example("ca completions generate nushell | save -f ($nu.default-config-dir | path join autoload ca.nu)");
 
     Ok(())
 }
 
 /// Check if completion is installed for a shell
-fn check_completion_installed(shell: Shell) -> bool {
+fn check_completion_installed(shell: CompletionShell) -> bool {
     let home_dir = match dirs::home_dir() {
         Some(dir) => dir,
         None => return false,
     };
 
     let possible_paths = match shell {
-        Shell::Bash => vec![
+        CompletionShell::Bash => vec![
             home_dir.join(".bash_completion.d/ca"),
             PathBuf::from("/usr/local/etc/bash_completion.d/ca"),
             PathBuf::from("/etc/bash_completion.d/ca"),
         ],
-        Shell::Zsh => vec![
+        CompletionShell::Zsh => vec![
             home_dir.join(".oh-my-zsh/completions/_ca"),
             home_dir.join(".zsh/completions/_ca"),
             PathBuf::from("/usr/local/share/zsh/site-functions/_ca"),
         ],
-        Shell::Fish => vec![home_dir.join(".config/fish/completions/ca.fish")],
-        _ => return false,
+        CompletionShell::Fish => vec![home_dir.join(".config/fish/completions/ca.fish")],
+        CompletionShell::PowerShell => powershell_completion_dir()
+            .map(|d| d.join("ca.ps1"))
+            .into_iter()
+            .collect(),
+        CompletionShell::Nushell => nushell_autoload_dir()
+            .map(|d| d.join("ca.nu"))
+            .into_iter()
+            .collect(),
+        CompletionShell::Elvish => return false,
     };
 
     possible_paths.iter().any(|path| path.exists())
 }
 
+/// Positional arguments completed from `ca completion-helper <source>`, keyed by the
+/// subcommand path that takes them
+const DYNAMIC_ARGS: &[(&str, &str)] = &[
+    ("switch", "stack-names"),
+    ("stacks switch", "stack-names"),
+    ("stacks status", "stack-names"),
+    ("stacks config", "stack-names"),
+    ("viz stack", "stack-names"),
+    ("viz timeline", "stack-names"),
+    ("submit", "entries"),
+    ("land", "entries"),
+    ("drop", "entries"),
+    ("stacks submit", "entries"),
+    ("entry checkout", "entries"),
+    ("entry amend", "entries"),
+    ("entry move", "entries"),
+    ("entry reword", "entries"),
+    ("backups list", "branches"),
+    ("backups restore", "branches"),
+    ("snapshots restore", "snapshots"),
+    ("hooks add", "hooks"),
+    ("hooks remove", "hooks"),
+    ("config set", "config-keys"),
+    ("config get", "config-keys"),
+    ("config unset", "config-keys"),
+];
+
+/// Stack names for `ca completion-helper stack-names`
+pub fn stack_name_values() -> Vec<String> {
+    open_stack_manager()
+        .map(|manager| {
+            manager
+                .list_stacks()
+                .into_iter()
+                .map(|(_, name, _, _, _)| name.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Active stack entries as `<number>\t<message>` for `ca completion-helper entries`
+pub fn entry_values() -> Vec<String> {
+    let Some(manager) = open_stack_manager() else {
+        return Vec::new();
+    };
+    manager
+        .get_active_stack()
+        .map(|stack| {
+            stack
+                .entries
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| format!("{}\t{}", i + 1, entry.short_message(50)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Local branch names for `ca completion-helper branches`
+pub fn branch_values() -> Vec<String> {
+    std::env::current_dir()
+        .ok()
+        .and_then(|dir| crate::git::GitRepository::open(&dir).ok())
+        .and_then(|repo| repo.list_branches().ok())
+        .unwrap_or_default()
+}
+
+/// Hook names as `<name>\t<description>` for `ca completion-helper hooks`
+pub fn hook_values() -> Vec<String> {
+    HookType::ALL
+        .iter()
+        .map(|hook| format!("{}\t{}", hook.name(), hook.description()))
+        .collect()
+}
+
+/// Snapshot ids as `<id>\tbefore <operation>` for `ca completion-helper snapshots`
+pub fn snapshot_values() -> Vec<String> {
+    std::env::current_dir()
+        .map_err(|e| crate::errors::CascadeError::config(e.to_string()))
+        .and_then(|dir| crate::git::find_repository_root(&dir))
+        .and_then(|root| crate::config::get_repo_config_dir(&root))
+        .and_then(|config_dir| crate::stack::snapshots::list_snapshots(&config_dir))
+        .map(|snapshots| {
+            snapshots
+                .into_iter()
+                .map(|snapshot| format!("{}\tbefore {}", snapshot.id, snapshot.manifest.operation))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Known configuration keys for `ca completion-helper config-keys`
+pub fn config_key_values() -> Vec<String> {
+    CONFIG_KEYS.iter().map(|key| key.to_string()).collect()
+}
+
+fn open_stack_manager() -> Option<StackManager> {
+    let current_dir = std::env::current_dir().ok()?;
+    let repo_root = crate::git::find_repository_root(&current_dir).ok()?;
+    StackManager::new(&repo_root).ok()
+}
+
+/// Shell `case` function mapping a subcommand path to its completion-helper source
+fn dynamic_source_function() -> String {
+    let mut function = String::from("_ca_dynamic_source() {\n    case \"$1\" in\n");
+    for (path, source) in DYNAMIC_ARGS {
+        function.push_str(&format!("        \"{path}\") echo {source} ;;\n"));
+    }
+    function.push_str("    esac\n}\n");
+    function
+}
+
 /// Generate custom completion logic for dynamic values
-fn generate_custom_completion(shell: Shell) -> String {
+fn generate_custom_completion(shell: CompletionShell) -> String {
     match shell {
-        Shell::Bash => {
-            r#"
-# Custom completion for ca switch command
-_ca_switch_completion() {
+        CompletionShell::Bash => {
+            let mut script = String::from(
+                "\n# Dynamic values (stacks, entries, branches, hooks, config keys)\n",
+            );
+            script.push_str(&dynamic_source_function());
+            script.push_str(
+                r#"
+_ca_with_dynamic() {
     local cur="${COMP_WORDS[COMP_CWORD]}"
-    local stacks=$(ca completion-helper stack-names 2>/dev/null)
-    COMPREPLY=($(compgen -W "$stacks" -- "$cur"))
+    local path="" i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        [[ "${COMP_WORDS[i]}" == -* ]] && continue
+        path="${path:+$path }${COMP_WORDS[i]}"
+    done
+
+    local source
+    source=$(_ca_dynamic_source "$path")
+    if [[ -n "$source" && "$cur" != -* ]]; then
+        local values
+        values=$(ca completion-helper "$source" 2>/dev/null | cut -f1)
+        COMPREPLY=($(compgen -W "$values" -- "$cur"))
+        return
+    fi
+    _ca "$@"
 }
 
-# Replace the default completion for 'ca switch' with our custom function
-complete -F _ca_switch_completion ca
-"#.to_string()
+complete -F _ca_with_dynamic -o nosort -o bashdefault -o default ca
+"#,
+            );
+            script
         }
-        Shell::Zsh => {
-            r#"
-# Custom completion for ca switch command
-_ca_switch_completion() {
-    local stacks=($(ca completion-helper stack-names 2>/dev/null))
-    _describe 'stacks' stacks
+        CompletionShell::Zsh => {
+            let mut script = String::from(
+                "\n# Dynamic values (stacks, entries, branches, hooks, config keys)\n",
+            );
+            script.push_str(&dynamic_source_function());
+            script.push_str(
+                r#"
+_ca_with_dynamic() {
+    local -a path_words
+    local word
+    for word in "${(@)words[2,CURRENT-1]}"; do
+        [[ "$word" == -* ]] || path_words+=("$word")
+    done
+
+    local source
+    source=$(_ca_dynamic_source "${(j: :)path_words}")
+    if [[ -n "$source" && "$PREFIX" != -* ]]; then
+        local -a values
+        values=("${(@f)$(ca completion-helper $source 2>/dev/null)}")
+        values=("${(@)values//:/\\:}")
+        values=("${(@)values//$'\t'/:}")
+        _describe "$source" values
+        return
+    fi
+    _ca "$@"
 }
 
-# Override the switch completion
-compdef _ca_switch_completion ca switch
+# Bind explicitly so Oh-My-Zsh or other plugins can't replace it
+compdef _ca_with_dynamic ca
+"#,
+            );
+            script
+        }
+        CompletionShell::Fish => {
+            let mut script = String::from(
+                "\n# Dynamic values (stacks, entries, branches, hooks, config keys)\n",
+            );
+            for (path, source) in DYNAMIC_ARGS {
+                let condition = path
+                    .split(' ')
+                    .map(|word| format!("__fish_seen_subcommand_from {word}"))
+                    .collect::<Vec<_>>()
+                    .join("; and ");
+                script.push_str(&format!(
+                    "complete -c ca -f -n '{condition}' -a '(ca completion-helper {source} 2>/dev/null)'\n"
+                ));
+            }
+            script
+        }
+        CompletionShell::PowerShell => {
+            let mut script = String::from(
+                "\n# Dynamic values (stacks, entries, branches, hooks, config keys)\n$_caDynamicSources = @{\n",
+            );
+            for (path, source) in DYNAMIC_ARGS {
+                script.push_str(&format!("    '{path}' = '{source}'\n"));
+            }
+            script.push_str(
+                r#"}
+
+$_caCompleter = {
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $path = @(
+        $commandAst.CommandElements |
+            Select-Object -Skip 1 |
+            Where-Object { $_.Extent.EndOffset -lt $cursorPosition -and -not $_.ToString().StartsWith('-') } |
+            ForEach-Object { $_.ToString() }
+    ) -join ' '
+
+    $source = $_caDynamicSources[$path]
+    if ($source -and -not $wordToComplete.StartsWith('-')) {
+        ca completion-helper $source 2>$null | ForEach-Object {
+            $value, $description = $_ -split "`t", 2
+            if (-not $description) { $description = $value }
+            if ($value -like "$wordToComplete*") {
+                [System.Management.Automation.CompletionResult]::new($value, $value, 'ParameterValue', $description)
+            }
+        }
+        return
+    }
+
+    & $_caStaticCompleter $wordToComplete $commandAst $cursorPosition
+}.GetNewClosure()
 
-# Explicitly bind the main completion function to 'ca'
-# This ensures the completion works even if Oh-My-Zsh or other plugins interfere
-compdef _ca ca
-"#.to_string()
+Register-ArgumentCompleter -Native -CommandName 'ca' -ScriptBlock $_caCompleter
+"#,
+            );
+            script
         }
-        Shell::Fish => {
+        CompletionShell::Nushell | CompletionShell::Elvish => String::new(),
+    }
+}
+
+/// Generate a Nushell module declaring every `ca` subcommand as an extern
+///
+/// clap_complete has no Nushell generator, so the command tree is walked directly.
+/// Dynamic positionals complete through `ca completion-helper`, value enums through
+/// their possible values.
+fn generate_nushell_completion(cmd: &mut Command) -> String {
+    cmd.build();
+
+    let mut script =
+        String::from("# Nushell completions for ca (Cascade CLI)\n\nmodule completions {\n");
+
+    let mut sources: Vec<&str> = DYNAMIC_ARGS.iter().map(|(_, source)| *source).collect();
+    sources.sort_unstable();
+    sources.dedup();
+    for source in sources {
+        script.push_str(&format!(
             r#"
-# Custom completion for ca switch command
-complete -c ca -f -n '__fish_seen_subcommand_from switch' -a '(ca completion-helper stack-names 2>/dev/null)'
-"#.to_string()
+  def "nu-complete ca {source}" [] {{
+    ^ca completion-helper {source}
+    | lines
+    | each {{|line|
+        let parts = ($line | split row "\t")
+        if ($parts | length) > 1 {{
+          {{ value: ($parts | get 0), description: ($parts | get 1) }}
+        }} else {{
+          {{ value: ($parts | get 0) }}
+        }}
+      }}
+  }}
+"#
+        ));
+    }
+
+    nushell_externs(cmd, "ca", &mut script);
+    script.push_str("}\n\nexport use completions *\n");
+    script
+}
+
+fn nushell_externs(cmd: &Command, path: &str, script: &mut String) {
+    let subcommand_path = path.strip_prefix("ca").unwrap_or(path).trim_start();
+    let dynamic_source = DYNAMIC_ARGS
+        .iter()
+        .find(|(p, _)| *p == subcommand_path)
+        .map(|(_, source)| *source);
+
+    let mut params = Vec::new();
+    let mut first_positional = true;
+    for arg in cmd.get_arguments().filter(|arg| !arg.is_hide_set()) {
+        let id = arg.get_id().as_str();
+        let help = arg
+            .get_help()
+            .map(|help| help.to_string())
+            .and_then(|help| help.lines().next().map(str::to_string))
+            .unwrap_or_default();
+
+        let possible_values: Vec<String> = arg
+            .get_possible_values()
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| value.get_name().to_string())
+            .collect();
+        let completer = if arg.is_positional() && first_positional && dynamic_source.is_some() {
+            format!("@\"nu-complete ca {}\"", dynamic_source.unwrap_or_default())
+        } else if !possible_values.is_empty() && arg.get_action().takes_values() {
+            let name = format!("nu-complete {path} {id}");
+            let values = possible_values
+                .iter()
+                .map(|value| format!("\"{value}\""))
+                .collect::<Vec<_>>()
+                .join(" ");
+            script.push_str(&format!(
+                "\n  def \"{name}\" [] {{\n    [ {values} ]\n  }}\n"
+            ));
+            format!("@\"{name}\"")
+        } else {
+            String::new()
+        };
+
+        let param = if arg.is_positional() {
+            first_positional = false;
+            let multiple = arg
+                .get_num_args()
+                .is_some_and(|range| range.max_values() > 1);
+            if multiple {
+                format!("...{id}: string{completer}")
+            } else if arg.is_required_set() {
+                format!("{id}: string{completer}")
+            } else {
+                format!("{id}?: string{completer}")
+            }
+        } else {
+            let mut flag = match (arg.get_long(), arg.get_short()) {
+                (Some(long), Some(short)) => format!("--{long}(-{short})"),
+                (Some(long), None) => format!("--{long}"),
+                (None, Some(short)) => format!("-{short}"),
+                (None, None) => continue,
+            };
+            if arg.get_action().takes_values() {
+                flag.push_str(&format!(": string{completer}"));
+            }
+            flag
+        };
+
+        if help.is_empty() {
+            params.push(format!("    {param}"));
+        } else {
+            params.push(format!("    {param:<40} # {help}"));
         }
-        _ => String::new(),
+    }
+
+    if let Some(about) = cmd.get_about() {
+        if let Some(line) = about.to_string().lines().next() {
+            script.push_str(&format!("\n  # {line}"));
+        }
+    }
+    script.push_str(&format!(
+        "\n  export extern \"{path}\" [\n{}\n  ]\n",
+        params.join("\n")
+    ));
+
+    for subcommand in cmd.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        nushell_externs(
+            subcommand,
+            &format!("{path} {}", subcommand.get_name()),
+            script,
+        );
     }
 }
 
@@ -539,6 +980,19 @@ complete -c ca -f -n '__fish_seen_subcommand_from switch' -a '(ca completion-hel
 mod tests {
     use super::*;
 
+    /// Full completion script, built on a thread with room for clap's command tree.
+    ///
+    /// Unoptimized builds of the derived `Cli::command()` need more than the 2 MiB that test
+    /// threads get by default.
+    fn script_for(shell: CompletionShell) -> String {
+        std::thread::Builder::new()
+            .stack_size(16 * 1024 * 1024)
+            .spawn(move || completion_script(shell))
+            .unwrap()
+            .join()
+            .unwrap()
+    }
+
     #[test]
     fn test_detect_shells() {
         let shells = detect_available_shells();
@@ -547,8 +1001,48 @@ mod tests {
 
     #[test]
     fn test_generate_bash_completion() {
-        let result = generate_completions(Shell::Bash);
-        assert!(result.is_ok());
+        let script = script_for(CompletionShell::Bash);
+        assert!(script.contains("complete -F _ca_with_dynamic"));
+    }
+
+    #[test]
+    fn test_custom_completion_covers_dynamic_args() {
+        let bash = generate_custom_completion(CompletionShell::Bash);
+        assert!(bash.contains("\"entry checkout\") echo entries ;;"));
+        assert!(bash.contains("\"config set\") echo config-keys ;;"));
+        assert!(bash.contains("complete -F _ca_with_dynamic"));
+
+        let zsh = generate_custom_completion(CompletionShell::Zsh);
+        assert!(zsh.contains("compdef _ca_with_dynamic ca"));
+
+        let fish = generate_custom_completion(CompletionShell::Fish);
+        assert!(fish.contains(
+            "-n '__fish_seen_subcommand_from hooks; and __fish_seen_subcommand_from add' -a '(ca completion-helper hooks 2>/dev/null)'"
+        ));
+
+        assert!(hook_values().iter().any(|v| v.starts_with("pre-push\t")));
+        assert!(config_key_values().contains(&"cascade.auto_stash".to_string()));
+    }
+
+    #[test]
+    fn test_powershell_completion_wraps_static_completer() {
+        let script = script_for(CompletionShell::PowerShell);
+        assert!(script.contains("$_caStaticCompleter = {"));
+        assert!(script.contains("'entry checkout' = 'entries'"));
+        assert_eq!(script.matches("Register-ArgumentCompleter").count(), 1);
+        assert!(script.contains("-ScriptBlock $_caCompleter"));
+    }
+
+    #[test]
+    fn test_nushell_completion_declares_externs() {
+        let script = script_for(CompletionShell::Nushell);
+        assert!(script.contains("export extern \"ca\" ["));
+        assert!(script.contains("export extern \"ca viz stack\" ["));
+        assert!(script.contains("def \"nu-complete ca stack-names\" []"));
+        assert!(script.contains("string@\"nu-complete ca config-keys\""));
+        assert!(script.contains("export use completions *"));
+        // Hidden helper commands stay out of the completions
+        assert!(!script.contains("\"ca completion-helper\""));
     }
 
     #[test]
@@ -556,15 +1050,23 @@ mod tests {
         // Test with a mocked SHELL environment variable
         std::env::set_var("SHELL", "/bin/zsh");
         let shell = detect_current_shell();
-        assert_eq!(shell, Some(Shell::Zsh));
+        assert_eq!(shell, Some(CompletionShell::Zsh));
 
         std::env::set_var("SHELL", "/usr/bin/bash");
         let shell = detect_current_shell();
-        assert_eq!(shell, Some(Shell::Bash));
+        assert_eq!(shell, Some(CompletionShell::Bash));
 
         std::env::set_var("SHELL", "/usr/local/bin/fish");
         let shell = detect_current_shell();
-        assert_eq!(shell, Some(Shell::Fish));
+        assert_eq!(shell, Some(CompletionShell::Fish));
+
+        std::env::set_var("SHELL", "/usr/bin/pwsh");
+        let shell = detect_current_shell();
+        assert_eq!(shell, Some(CompletionShell::PowerShell));
+
+        std::env::set_var("SHELL", "/opt/homebrew/bin/nu");
+        let shell = detect_current_shell();
+        assert_eq!(shell, Some(CompletionShell::Nushell));
 
         std::env::set_var("SHELL", "/bin/unknown");
         let shell = detect_current_shell();
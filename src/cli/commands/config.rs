@@ -1,6 +1,6 @@
 use crate::cli::output::Output;
 use crate::cli::ConfigAction;
-use crate::config::{get_repo_config_dir, is_repo_initialized, Settings};
+use crate::config::{get_repo_config_dir, is_repo_initialized, proxy, Settings, TEMPLATE_FIELDS};
 use crate::errors::{CascadeError, Result};
 use crate::git::find_repository_root;
 use std::env;
@@ -33,6 +33,16 @@ pub async fn run(action: ConfigAction) -> Result<()> {
 
 async fn set_config_value(config_file: &std::path::Path, key: &str, value: &str) -> Result<()> {
     let mut settings = Settings::load_from_file(config_file)?;
+
+    // Proxy passwords go to the credential helper; only the URL is saved
+    let stored;
+    let value = if key == "network.proxy" {
+        stored = proxy::store_credentials(value)?;
+        stored.as_str()
+    } else {
+        value
+    };
+
     settings.set_value(key, value)?;
     settings.validate()?;
     settings.save_to_file(config_file)?;
@@ -66,6 +76,26 @@ async fn set_config_value(config_file: &std::path::Path, key: &str, value: &str)
             Output::sub_item("This affects both API calls and git operations");
             Output::sub_item("Make sure the file exists and contains valid PEM certificates");
         }
+        "ssh.host_key_checking" if value == "off" => {
+            Output::warning("SSH host keys are no longer verified - this is insecure!");
+        }
+        _ if key.starts_with("ssh.") => {
+            Output::tip("Check which keys and host keys SSH remotes will use with: ca diagnose");
+        }
+        _ if key.starts_with("credentials.") => {
+            Output::tip("Fetch and push to remotes on this host now use these credentials");
+            Output::sub_item("Other hosts keep using git's credential helper");
+        }
+        "network.proxy" => {
+            if value == proxy::DIRECT {
+                Output::tip(
+                    "API calls and git operations will ignore HTTPS_PROXY and connect directly",
+                );
+            } else {
+                Output::tip("API calls and git operations now go through this proxy");
+                Output::sub_item("Check connectivity with: ca diagnose --network");
+            }
+        }
         _ => {}
     }
 
@@ -77,7 +107,7 @@ async fn get_config_value(config_file: &std::path::Path, key: &str) -> Result<()
     let value = settings.get_value(key)?;
 
     // Mask sensitive values
-    let display_value = if key.contains("token") || key.contains("password") {
+    let display_value = if is_secret_key(key) {
         if value.is_empty() {
             "(not set)".to_string()
         } else {
@@ -97,7 +127,7 @@ async fn list_config_values(config_file: &std::path::Path) -> Result<()> {
     let settings = Settings::load_from_file(config_file)?;
 
     Output::section("Cascade Configuration");
-    println!();
+    Output::spacing();
 
     // Bitbucket configuration
     Output::section("Bitbucket Server");
@@ -105,7 +135,44 @@ async fn list_config_values(config_file: &std::path::Path) -> Result<()> {
     print_config_value(&settings, "  bitbucket.project")?;
     print_config_value(&settings, "  bitbucket.repo")?;
     print_config_value(&settings, "  bitbucket.token")?;
-    println!();
+    print_config_value(&settings, "  bitbucket.token_warning_days")?;
+    print_config_value(&settings, "  bitbucket.use_credential_helper")?;
+    print_config_value(&settings, "  bitbucket.admin_contact")?;
+    Output::spacing();
+
+    // Network configuration
+    Output::section("Network");
+    print_config_value(&settings, "  network.proxy")?;
+    Output::spacing();
+
+    // Per-host git remote credentials
+    if !settings.credentials.is_empty() {
+        Output::section("Remote Credentials");
+        for host in settings.credentials.keys() {
+            print_config_value(&settings, &format!("  credentials.{host}.username"))?;
+            print_config_value(&settings, &format!("  credentials.{host}.token"))?;
+        }
+        Output::spacing();
+    }
+
+    // Stack templates
+    if !settings.templates.is_empty() {
+        Output::section("Stack Templates");
+        for name in settings.templates.keys() {
+            for field in TEMPLATE_FIELDS {
+                print_config_value(&settings, &format!("  templates.{name}.{field}"))?;
+            }
+        }
+        Output::spacing();
+    }
+
+    // OAuth configuration
+    Output::section("OAuth");
+    print_config_value(&settings, "  oauth.client_id")?;
+    print_config_value(&settings, "  oauth.device_url")?;
+    print_config_value(&settings, "  oauth.token_url")?;
+    print_config_value(&settings, "  oauth.scopes")?;
+    Output::spacing();
 
     // Git configuration
     Output::section("Git");
@@ -114,7 +181,17 @@ async fn list_config_values(config_file: &std::path::Path) -> Result<()> {
     print_config_value(&settings, "  git.author_email")?;
     print_config_value(&settings, "  git.auto_cleanup_merged")?;
     print_config_value(&settings, "  git.prefer_rebase")?;
-    println!();
+    print_config_value(&settings, "  git.branch_template")?;
+    print_config_value(&settings, "  git.protected_branches")?;
+    Output::spacing();
+
+    // SSH remotes
+    Output::section("SSH");
+    print_config_value(&settings, "  ssh.key_path")?;
+    print_config_value(&settings, "  ssh.use_agent")?;
+    print_config_value(&settings, "  ssh.host_key_checking")?;
+    print_config_value(&settings, "  ssh.known_hosts")?;
+    Output::spacing();
 
     // Cascade configuration
     Output::section("Cascade");
@@ -122,6 +199,32 @@ async fn list_config_values(config_file: &std::path::Path) -> Result<()> {
     print_config_value(&settings, "  cascade.auto_cleanup")?;
     print_config_value(&settings, "  cascade.max_stack_size")?;
     print_config_value(&settings, "  cascade.enable_notifications")?;
+    print_config_value(&settings, "  cascade.land_mode")?;
+    print_config_value(&settings, "  cascade.auto_stash")?;
+    print_config_value(&settings, "  cascade.squash_message")?;
+    print_config_value(&settings, "  cascade.removed_pr_action")?;
+    print_config_value(&settings, "  cascade.locale")?;
+    Output::spacing();
+
+    // External CI configuration
+    Output::section("Builds");
+    print_config_value(&settings, "  build.status_url")?;
+    print_config_value(&settings, "  build.token")?;
+    Output::spacing();
+
+    // Backup retention
+    Output::section("Backups");
+    print_config_value(&settings, "  backups.keep_per_branch")?;
+    print_config_value(&settings, "  backups.max_age_days")?;
+    Output::spacing();
+
+    // Retention of snapshots and caches
+    Output::section("Garbage Collection");
+    print_config_value(&settings, "  gc.keep_snapshots")?;
+    print_config_value(&settings, "  gc.snapshot_max_age_days")?;
+    print_config_value(&settings, "  gc.cache_max_age_days")?;
+    print_config_value(&settings, "  gc.max_size_mb")?;
+    print_config_value(&settings, "  gc.auto_after_operations")?;
 
     Ok(())
 }
@@ -131,23 +234,27 @@ fn print_config_value(settings: &Settings, key: &str) -> Result<()> {
     let value = settings.get_value(key_without_spaces)?;
 
     // Mask sensitive values
-    let display_value =
-        if key_without_spaces.contains("token") || key_without_spaces.contains("password") {
-            if value.is_empty() {
-                "(not set)".to_string()
-            } else {
-                format!("{}***", &value[..std::cmp::min(4, value.len())])
-            }
-        } else if value.is_empty() {
+    let display_value = if is_secret_key(key_without_spaces) {
+        if value.is_empty() {
             "(not set)".to_string()
         } else {
-            value
-        };
+            format!("{}***", &value[..std::cmp::min(4, value.len())])
+        }
+    } else if value.is_empty() {
+        "(not set)".to_string()
+    } else {
+        value
+    };
 
     Output::sub_item(format!("{key} = {display_value}"));
     Ok(())
 }
 
+/// Whether a key holds a credential whose value should be masked
+fn is_secret_key(key: &str) -> bool {
+    key.ends_with("token") || key.contains("password")
+}
+
 async fn unset_config_value(config_file: &std::path::Path, key: &str) -> Result<()> {
     let mut settings = Settings::load_from_file(config_file)?;
 
@@ -227,4 +334,24 @@ mod tests {
         // List all configuration values
         list_config_values(&config_file).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_config_set_proxy_none() {
+        let (_temp_dir, repo_path) = create_initialized_repo().await;
+        let config_file = crate::config::get_repo_config_dir(&repo_path)
+            .unwrap()
+            .join("config.json");
+
+        set_config_value(&config_file, "network.proxy", proxy::DIRECT)
+            .await
+            .unwrap();
+        let settings = Settings::load_from_file(&config_file).unwrap();
+        assert_eq!(settings.get_value("network.proxy").unwrap(), proxy::DIRECT);
+
+        set_config_value(&config_file, "network.proxy", "")
+            .await
+            .unwrap();
+        let settings = Settings::load_from_file(&config_file).unwrap();
+        assert_eq!(settings.get_value("network.proxy").unwrap(), "");
+    }
 }
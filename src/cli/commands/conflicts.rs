@@ -117,6 +117,7 @@ pub async fn run(args: ConflictsArgs) -> Result<()> {
                     ConflictType::Structural => "Structural",
                     ConflictType::ContentOverlap => "Content Overlap",
                     ConflictType::Complex => "Complex",
+                    ConflictType::SubmodulePointer => "Submodule Pointer",
                 };
                 type_summary.push(format!("{type_name}: {count}"));
             }
@@ -135,6 +136,7 @@ pub async fn run(args: ConflictsArgs) -> Result<()> {
                     ConflictType::Structural => "🏗️  Structural",
                     ConflictType::ContentOverlap => "🔄 Overlap",
                     ConflictType::Complex => "🔍 Complex",
+                    ConflictType::SubmodulePointer => "🔗 Submodule",
                 };
 
                 let strategy_desc = match &conflict.suggested_strategy {
@@ -147,14 +149,18 @@ pub async fn run(args: ConflictsArgs) -> Result<()> {
                     }
                 };
 
-                Output::sub_item(format!(
-                    "{}. {} (lines {}-{}) - {}",
-                    i + 1,
-                    conflict_type,
-                    conflict.start_line,
-                    conflict.end_line,
-                    strategy_desc
-                ));
+                if conflict.conflict_type == ConflictType::SubmodulePointer {
+                    Output::sub_item(format!("{}. {} - {}", i + 1, conflict_type, strategy_desc));
+                } else {
+                    Output::sub_item(format!(
+                        "{}. {} (lines {}-{}) - {}",
+                        i + 1,
+                        conflict_type,
+                        conflict.start_line,
+                        conflict.end_line,
+                        strategy_desc
+                    ));
+                }
 
                 if !conflict.context.is_empty() {
                     Output::sub_item(format!("   Context: {}", conflict.context));
@@ -211,6 +217,7 @@ pub fn display_conflict_stats(type_counts: &HashMap<ConflictType, usize>) {
             ConflictType::Structural => ("🏗️", "Code structure changes"),
             ConflictType::ContentOverlap => ("🔄", "Overlapping content changes"),
             ConflictType::Complex => ("🔍", "Complex conflicts"),
+            ConflictType::SubmodulePointer => ("🔗", "Submodule pointer conflicts"),
         };
 
         Output::sub_item(format!("{icon} {description} - {count} conflicts"));
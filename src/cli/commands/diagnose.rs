@@ -1,10 +1,32 @@
+use crate::bitbucket::BitbucketClient;
 use crate::cli::output::Output;
-use crate::errors::Result;
-use crate::git::GitRepository;
+use crate::config::{
+    get_repo_config_dir, proxy, BitbucketConfig, HostKeyChecking, ProviderKind, Settings,
+    SshSettings,
+};
+use crate::errors::{CascadeError, Result};
+use crate::git::{get_current_repository, ssh, GitRepository};
+use std::net::SocketAddr;
 use std::path::Path;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// How long each network step may take before it counts as failed
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Authenticated requests timed for the latency step
+const LATENCY_SAMPLES: usize = 3;
+
+/// Median latency above which the API counts as slow
+const SLOW_API_MS: u128 = 1000;
+
+/// Run diagnostic checks for git2 TLS/SSH support, and optionally the network path
+/// to the configured provider
+pub async fn run(network: bool) -> Result<()> {
+    if network {
+        return run_network().await;
+    }
 
-/// Run diagnostic checks for git2 TLS/SSH support
-pub async fn run() -> Result<()> {
     Output::section("Cascade CLI - Git2 Diagnostics");
 
     // Check git2 features
@@ -30,7 +52,7 @@ pub async fn run() -> Result<()> {
         libgit2_version.0, libgit2_version.1, libgit2_version.2
     ));
 
-    println!();
+    Output::spacing();
 
     // Check current repository if we're in one
     if let Ok(repo) = GitRepository::open(Path::new(".")) {
@@ -47,12 +69,17 @@ pub async fn run() -> Result<()> {
                 } else {
                     Output::error("HTTPS remote but NO TLS support - will fallback to git CLI");
                 }
-            } else if remote_url.starts_with("git@") || remote_url.starts_with("ssh://") {
+            } else if ssh::is_ssh_url(&remote_url) {
                 if version.ssh() {
                     Output::success("SSH remote with SSH support - should work!");
                 } else {
                     Output::error("SSH remote but NO SSH support - will fallback to git CLI");
                 }
+                let ssh_settings = get_repo_config_dir(repo.path())
+                    .and_then(|dir| Settings::load_from_file(&dir.join("config.json")))
+                    .map(|settings| settings.git.ssh)
+                    .unwrap_or_default();
+                diagnose_ssh(&remote_url, &ssh_settings);
             }
         }
     } else {
@@ -66,10 +93,10 @@ pub async fn run() -> Result<()> {
         Output::error("MISSING FEATURES DETECTED:");
         Output::sub_item("Your git2 is missing TLS/SSH support.");
         Output::sub_item("This causes performance issues due to git CLI fallbacks.");
-        println!();
+        Output::spacing();
         Output::tip("TO FIX: Update Cargo.toml git2 dependency:");
         Output::command_example("git2 = { version = \"0.20.2\", features = [\"vendored-libgit2\", \"https\", \"ssh\"] }");
-        println!();
+        Output::spacing();
         Output::success("BENEFITS: Direct git2 operations (faster, more reliable)");
     } else {
         Output::success("git2 has full TLS/SSH support!");
@@ -82,3 +109,556 @@ pub async fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// Report which SSH keys will be offered and whether the remote's host key is trusted
+fn diagnose_ssh(remote_url: &str, settings: &SshSettings) {
+    Output::section("SSH Authentication");
+
+    let agent_keys = if !settings.use_agent {
+        Output::sub_item("Agent: not used (ssh.use_agent = false)");
+        0
+    } else if !ssh::agent_available() {
+        Output::sub_item("Agent: not running (SSH_AUTH_SOCK is not set)");
+        0
+    } else {
+        let forwarded = if std::env::var_os("SSH_CONNECTION").is_some() {
+            " (forwarded from your SSH session)"
+        } else {
+            ""
+        };
+        match agent_identities() {
+            Some(0) => {
+                Output::warning(format!("Agent{forwarded} holds no keys"));
+                Output::sub_item("Add one with: ssh-add ~/.ssh/id_ed25519");
+                0
+            }
+            Some(count) => {
+                Output::success(format!("Agent{forwarded}: {count} key(s) loaded"));
+                count
+            }
+            None => {
+                Output::warning("SSH_AUTH_SOCK is set but the agent didn't answer");
+                0
+            }
+        }
+    };
+
+    let keys = ssh::identity_files(settings);
+    for key in &keys {
+        if !key.is_file() {
+            Output::error(format!("Key file not found: {}", key.display()));
+            Output::sub_item("Fix it with: ca config set ssh.key_path <path to private key>");
+            continue;
+        }
+        if ssh::key_is_encrypted(key) {
+            Output::sub_item(format!(
+                "Key: {} (passphrase protected; asked once per run, or read from {})",
+                key.display(),
+                ssh::PASSPHRASE_ENV
+            ));
+        } else {
+            Output::sub_item(format!("Key: {}", key.display()));
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(key).map_or(0, |m| m.permissions().mode());
+            if mode & 0o077 != 0 {
+                Output::warning(format!(
+                    "{} is readable by other users; the git CLI's ssh will refuse it. Run: chmod 600 {}",
+                    key.display(),
+                    key.display()
+                ));
+            }
+        }
+    }
+    if agent_keys == 0 && keys.is_empty() {
+        Output::error("No SSH keys to offer");
+        Output::sub_item(
+            "Load a key into the agent with 'ssh-add' or run 'ca config set ssh.key_path <key>'",
+        );
+    }
+
+    let (Some((host, port)), Some(known_hosts)) = (
+        ssh::remote_host(remote_url),
+        ssh::known_hosts_path(settings),
+    ) else {
+        return;
+    };
+    let name = ssh::known_host_name(&host, port);
+    if ssh::host_is_known(&known_hosts, &name) {
+        Output::success(format!("{name} is in {}", known_hosts.display()));
+        return;
+    }
+    match settings.host_key_checking {
+        HostKeyChecking::Strict => {
+            Output::warning(format!(
+                "{name} is not in {}; fetch and push will be refused",
+                known_hosts.display()
+            ));
+            Output::sub_item(format!(
+                "Verify the server's fingerprint, then connect once with: ssh -T {}{host}",
+                port.map(|port| format!("-p {port} ")).unwrap_or_default()
+            ));
+            Output::sub_item(
+                "Or trust it on first use: ca config set ssh.host_key_checking accept-new",
+            );
+        }
+        HostKeyChecking::AcceptNew => Output::info(format!(
+            "{name} will be added to {} on first connection",
+            known_hosts.display()
+        )),
+        HostKeyChecking::Off => Output::warning(
+            "Host key verification is off (ssh.host_key_checking = off) - this is insecure!",
+        ),
+    }
+}
+
+/// Number of keys the SSH agent holds, or `None` when it can't be asked
+fn agent_identities() -> Option<usize> {
+    let output = std::process::Command::new("ssh-add")
+        .arg("-l")
+        .output()
+        .ok()?;
+    match output.status.code() {
+        Some(0) => Some(String::from_utf8_lossy(&output.stdout).lines().count()),
+        // Exit status 1: the agent has no identities
+        Some(1) => Some(0),
+        _ => None,
+    }
+}
+
+/// Outcome of one network probe step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// Not run because an earlier step failed
+    Skipped,
+}
+
+/// One step of `ca diagnose --network`
+#[derive(Debug, Clone)]
+pub struct ProbeStep {
+    pub name: String,
+    pub status: ProbeStatus,
+    pub detail: String,
+    /// What to try when the step didn't pass
+    pub hint: Option<String>,
+}
+
+impl ProbeStep {
+    fn new(name: impl Into<String>, status: ProbeStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, ProbeStatus::Pass, detail)
+    }
+
+    fn warn(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self::new(name, ProbeStatus::Warn, detail).with_hint(hint)
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self::new(name, ProbeStatus::Fail, detail).with_hint(hint)
+    }
+
+    fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+async fn run_network() -> Result<()> {
+    Output::section("Cascade CLI - Network Diagnostics");
+
+    let git_repo = get_current_repository()?;
+    let config_file = get_repo_config_dir(git_repo.path())?.join("config.json");
+    let settings = Settings::load_from_file(&config_file)?;
+
+    if settings.bitbucket.provider == ProviderKind::Mock {
+        Output::info("bitbucket.provider is 'mock' - no network is used, nothing to probe");
+        return Ok(());
+    }
+
+    Output::sub_item(format!("Provider URL: {}", settings.bitbucket.url));
+    Output::spacing();
+
+    let steps = probe_network(&settings.bitbucket).await;
+    for step in &steps {
+        let line = format!("{}: {}", step.name, step.detail);
+        match step.status {
+            ProbeStatus::Pass => Output::success(line),
+            ProbeStatus::Warn => Output::warning(line),
+            ProbeStatus::Fail => Output::error(line),
+            ProbeStatus::Skipped => Output::sub_item(line),
+        }
+        if let Some(hint) = &step.hint {
+            Output::solution(hint);
+        }
+    }
+
+    let failed = steps
+        .iter()
+        .filter(|s| s.status == ProbeStatus::Fail)
+        .count();
+    Output::spacing();
+    if failed == 0 {
+        Output::success("The provider is reachable and accepts your credentials");
+        Ok(())
+    } else {
+        Err(CascadeError::bitbucket(format!(
+            "{failed} network check(s) failed"
+        )))
+    }
+}
+
+/// Walk the network path to the provider one layer at a time: DNS, TCP, TLS with
+/// each trusted CA source, authentication and API latency.
+///
+/// Steps after the first failure are reported as skipped, since their results would
+/// only repeat it.
+pub async fn probe_network(config: &BitbucketConfig) -> Vec<ProbeStep> {
+    let mut steps = Vec::new();
+
+    let url = match Url::parse(&config.url) {
+        Ok(url) if url.host_str().is_some() => url,
+        _ => {
+            steps.push(ProbeStep::fail(
+                "Configuration",
+                format!("'{}' is not a valid provider URL", config.url),
+                "ca config set bitbucket.url https://bitbucket.example.com",
+            ));
+            return steps;
+        }
+    };
+    let host = url.host_str().unwrap_or_default().to_string();
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    let proxy = proxy::proxy_for(&config.network, &config.url);
+    let (connect_host, connect_port) = match &proxy {
+        Some(proxy) => {
+            steps.push(ProbeStep::pass(
+                "Proxy",
+                format!(
+                    "requests go through {} (from {})",
+                    proxy.display_url(),
+                    proxy.origin()
+                ),
+            ));
+            match Url::parse(&proxy.url) {
+                Ok(proxy_url) if proxy_url.host_str().is_some() => (
+                    proxy_url.host_str().unwrap_or_default().to_string(),
+                    proxy_url.port_or_known_default().unwrap_or(8080),
+                ),
+                _ => {
+                    steps.push(ProbeStep::fail(
+                        "Proxy",
+                        format!("'{}' is not a valid proxy URL", proxy.display_url()),
+                        format!("Fix {}, e.g. http://proxy.example.com:8080", proxy.origin()),
+                    ));
+                    return steps;
+                }
+            }
+        }
+        None => {
+            let detail = if config.network.proxy.as_deref() == Some(proxy::DIRECT) {
+                "none (network.proxy = none), connecting directly"
+            } else {
+                "none, connecting directly"
+            };
+            steps.push(ProbeStep::pass("Proxy", detail));
+            (host.clone(), port)
+        }
+    };
+    let proxy_url = proxy.as_ref().map(proxy::ProxyChoice::authenticated_url);
+
+    let address = match resolve(&connect_host, connect_port).await {
+        Ok((address, elapsed)) => {
+            steps.push(ProbeStep::pass(
+                "DNS",
+                format!("{connect_host} resolves to {} ({elapsed} ms)", address.ip()),
+            ));
+            address
+        }
+        Err(e) => {
+            steps.push(ProbeStep::fail(
+                "DNS",
+                format!("could not resolve {connect_host}: {e}"),
+                "Check the host name, and that you are on the VPN if the server is internal",
+            ));
+            return with_skipped(steps, &["TCP", "TLS", "Authentication", "Latency"]);
+        }
+    };
+
+    let started = Instant::now();
+    match tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(address)).await {
+        Ok(Ok(_)) => steps.push(ProbeStep::pass(
+            "TCP",
+            format!(
+                "connected to {address} ({} ms)",
+                started.elapsed().as_millis()
+            ),
+        )),
+        Ok(Err(e)) => {
+            steps.push(ProbeStep::fail(
+                "TCP",
+                format!("could not connect to {address}: {e}"),
+                format!("A firewall may block port {connect_port}; set network.proxy or HTTPS_PROXY if your network requires a proxy"),
+            ));
+            return with_skipped(steps, &["TLS", "Authentication", "Latency"]);
+        }
+        Err(_) => {
+            steps.push(ProbeStep::fail(
+                "TCP",
+                format!("no answer from {address} within {}s", PROBE_TIMEOUT.as_secs()),
+                format!("A firewall may silently drop port {connect_port}; set network.proxy or HTTPS_PROXY if your network requires a proxy"),
+            ));
+            return with_skipped(steps, &["TLS", "Authentication", "Latency"]);
+        }
+    }
+
+    if url.scheme() == "https" {
+        let tls_steps = probe_tls(config, proxy_url.as_deref()).await;
+        let trusted = tls_steps.iter().any(|s| s.status == ProbeStatus::Pass);
+        steps.extend(tls_steps);
+        if !trusted {
+            return with_skipped(steps, &["Authentication", "Latency"]);
+        }
+    } else {
+        steps.push(ProbeStep::warn(
+            "TLS",
+            "plain HTTP, so your token travels unencrypted",
+            "Use the https:// URL of the server if it has one",
+        ));
+    }
+
+    if config.token.as_ref().is_none_or(|t| t.is_empty()) {
+        steps.push(ProbeStep::fail(
+            "Authentication",
+            "no token configured",
+            "ca config set bitbucket.token <personal-access-token>",
+        ));
+        return with_skipped(steps, &["Latency"]);
+    }
+    let client = match BitbucketClient::new(config) {
+        Ok(client) => client,
+        Err(e) => {
+            steps.push(ProbeStep::fail(
+                "Authentication",
+                e.to_string(),
+                "ca setup --force",
+            ));
+            return with_skipped(steps, &["Latency"]);
+        }
+    };
+
+    let mut samples = Vec::with_capacity(LATENCY_SAMPLES);
+    let started = Instant::now();
+    match client.test_connection().await {
+        Ok(()) => {
+            samples.push(started.elapsed().as_millis());
+            steps.push(ProbeStep::pass(
+                "Authentication",
+                format!("token accepted for {}/{}", config.project, config.repo),
+            ));
+        }
+        Err(e) => {
+            let message = e.to_string();
+            let hint = if message.contains("401") {
+                "The token was rejected; create a new personal access token and run 'ca config set bitbucket.token <token>'"
+            } else if message.contains("403") {
+                "The token lacks permission; it needs at least repository read and write"
+            } else if message.contains("404") {
+                "Check bitbucket.project and bitbucket.repo match the repository URL on the server"
+            } else {
+                "Run 'ca logs --tail 20' for the full error"
+            };
+            steps.push(ProbeStep::fail("Authentication", message, hint));
+            return with_skipped(steps, &["Latency"]);
+        }
+    }
+
+    while samples.len() < LATENCY_SAMPLES {
+        let started = Instant::now();
+        if client.test_connection().await.is_err() {
+            break;
+        }
+        samples.push(started.elapsed().as_millis());
+    }
+    samples.sort_unstable();
+    let median = samples[samples.len() / 2];
+    let detail = format!("median {median} ms over {} request(s)", samples.len());
+    steps.push(if median > SLOW_API_MS {
+        ProbeStep::warn(
+            "Latency",
+            detail,
+            "The API is slow; submit and land will take longer than usual",
+        )
+    } else {
+        ProbeStep::pass("Latency", detail)
+    });
+
+    steps
+}
+
+/// TLS handshake once with the built-in roots and once with each configured bundle,
+/// so a failure shows which trust source is missing the server's issuer
+async fn probe_tls(config: &BitbucketConfig, proxy: Option<&str>) -> Vec<ProbeStep> {
+    let mut steps = Vec::new();
+    if config.accept_invalid_certs == Some(true) {
+        steps.push(ProbeStep::warn(
+            "TLS",
+            "certificate verification is disabled (bitbucket.accept_invalid_certs)",
+            "Configure bitbucket.ca_bundle_path with your company's root CA instead",
+        ));
+    }
+
+    let mut sources: Vec<(String, Option<&str>)> = vec![("built-in roots".to_string(), None)];
+    if let Some(bundle) = &config.ca_bundle_path {
+        sources.push((format!("CA bundle {bundle}"), Some(bundle.as_str())));
+    }
+
+    let mut results = Vec::new();
+    for (label, bundle) in &sources {
+        results.push((label.clone(), handshake(&config.url, *bundle, proxy).await));
+    }
+    let any_trusted = results.iter().any(|(_, r)| r.is_ok());
+    for (label, result) in results {
+        steps.push(match result {
+            Ok(elapsed) => ProbeStep::pass(
+                "TLS",
+                format!("handshake verified with {label} ({elapsed} ms)"),
+            ),
+            // A bundle that can't verify the server is fine while another source can
+            Err(e) if any_trusted => ProbeStep::new(
+                "TLS",
+                ProbeStatus::Warn,
+                format!("{label} does not verify the server: {e}"),
+            ),
+            Err(e) => ProbeStep::fail(
+                "TLS",
+                format!("{label} does not verify the server: {e}"),
+                "If your network inspects TLS, export its root CA as PEM and run 'ca config set bitbucket.ca_bundle_path <file>'",
+            ),
+        });
+    }
+    steps
+}
+
+/// Milliseconds until the server answers over TLS trusting only `bundle` (or only the
+/// built-in roots); any HTTP status counts, as only the handshake is under test
+async fn handshake(
+    url: &str,
+    bundle: Option<&str>,
+    proxy: Option<&str>,
+) -> std::result::Result<u128, String> {
+    let mut builder = reqwest::Client::builder().timeout(PROBE_TIMEOUT).no_proxy();
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy).map_err(|e| e.to_string())?);
+    }
+    if let Some(bundle) = bundle {
+        let pem = std::fs::read(bundle).map_err(|e| format!("cannot read it: {e}"))?;
+        let cert = reqwest::Certificate::from_pem(&pem)
+            .map_err(|e| format!("not a PEM certificate: {e}"))?;
+        builder = builder
+            .tls_built_in_root_certs(false)
+            .add_root_certificate(cert);
+    }
+    let client = builder.build().map_err(|e| e.to_string())?;
+
+    let started = Instant::now();
+    client
+        .get(url)
+        .send()
+        .await
+        .map(|_| started.elapsed().as_millis())
+        .map_err(|e| error_chain(&e))
+}
+
+/// First address of `host`, and how long resolving took
+async fn resolve(host: &str, port: u16) -> std::result::Result<(SocketAddr, u128), String> {
+    let started = Instant::now();
+    let lookup = tokio::time::timeout(PROBE_TIMEOUT, tokio::net::lookup_host((host, port)))
+        .await
+        .map_err(|_| format!("timed out after {}s", PROBE_TIMEOUT.as_secs()))?
+        .map_err(|e| e.to_string())?;
+    let address = lookup
+        .into_iter()
+        .next()
+        .ok_or_else(|| "no addresses returned".to_string())?;
+    Ok((address, started.elapsed().as_millis()))
+}
+
+fn with_skipped(mut steps: Vec<ProbeStep>, names: &[&str]) -> Vec<ProbeStep> {
+    steps.extend(
+        names
+            .iter()
+            .map(|name| ProbeStep::new(*name, ProbeStatus::Skipped, "skipped")),
+    );
+    steps
+}
+
+/// Error with its causes, which is where reqwest keeps the TLS details
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(&format!(": {cause}"));
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_probe_reports_each_step() {
+        let mut server = mockito::Server::new_async().await;
+        let _repo = server
+            .mock("GET", "/rest/api/1.0/projects/TEST/repos/app")
+            .with_status(200)
+            .with_body("{}")
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("GET", "/rest/api/1.0/projects/TEST/repos/missing")
+            .with_status(404)
+            .create_async()
+            .await;
+        let config = BitbucketConfig {
+            url: server.url(),
+            project: "TEST".to_string(),
+            repo: "app".to_string(),
+            token: Some("token".to_string()),
+            ..Default::default()
+        };
+
+        let steps = probe_network(&config).await;
+        let status = |name: &str| steps.iter().find(|s| s.name == name).map(|s| s.status);
+        assert_eq!(status("DNS"), Some(ProbeStatus::Pass));
+        assert_eq!(status("TCP"), Some(ProbeStatus::Pass));
+        assert_eq!(status("TLS"), Some(ProbeStatus::Warn));
+        assert_eq!(status("Authentication"), Some(ProbeStatus::Pass));
+        assert_eq!(status("Latency"), Some(ProbeStatus::Pass));
+
+        let rejected = BitbucketConfig {
+            repo: "missing".to_string(),
+            ..config
+        };
+        let steps = probe_network(&rejected).await;
+        let auth = steps.iter().find(|s| s.name == "Authentication").unwrap();
+        assert_eq!(auth.status, ProbeStatus::Fail);
+        assert!(auth.hint.as_deref().unwrap().contains("bitbucket.project"));
+        assert_eq!(steps.last().unwrap().status, ProbeStatus::Skipped);
+    }
+}
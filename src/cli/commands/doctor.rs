@@ -1,14 +1,14 @@
 use crate::cli::output::Output;
 use crate::config::{get_repo_config_dir, is_repo_initialized, Settings};
 use crate::errors::{CascadeError, Result};
-use crate::git::{get_current_repository, is_git_repository};
+use crate::git::{get_current_repository, is_git_repository, GitRepository, RepoFeatures};
 use std::env;
 
 /// Check repository health and configuration
 pub async fn run() -> Result<()> {
     Output::section("Cascade Doctor");
     Output::info("Diagnosing repository health and configuration...");
-    println!();
+    Output::spacing();
 
     let mut issues_found = 0;
     let mut warnings_found = 0;
@@ -30,6 +30,12 @@ pub async fn run() -> Result<()> {
     // Check 4: Git configuration
     warnings_found += check_git_configuration().await?;
 
+    // Check 5: Git LFS
+    warnings_found += check_git_lfs()?;
+
+    // Check 6: Sparse checkout, partial and shallow clones
+    warnings_found += check_repository_layout()?;
+
     // Summary
     print_summary(issues_found, warnings_found);
 
@@ -243,12 +249,82 @@ async fn check_git_configuration() -> Result<u32> {
     Ok(warnings)
 }
 
+fn check_git_lfs() -> Result<u32> {
+    let git_repo = get_current_repository()?;
+    if !git_repo.uses_lfs() {
+        return Ok(0);
+    }
+
+    Output::check_start("Checking Git LFS");
+
+    if !GitRepository::lfs_available() {
+        Output::warning("Repository uses Git LFS but git-lfs is not installed");
+        Output::solution("Install Git LFS (https://git-lfs.com), then run 'git lfs install'");
+        return Ok(1);
+    }
+
+    // `git lfs install` registers the filters; without them checkouts leave pointer files
+    let filters_configured = git2::Repository::open(git_repo.path())?
+        .config()
+        .and_then(|config| config.get_string("filter.lfs.smudge"))
+        .is_ok();
+    if !filters_configured {
+        Output::warning("Git LFS filters are not configured");
+        Output::solution("git lfs install");
+        return Ok(1);
+    }
+
+    Output::success("Git LFS installed and configured");
+    Ok(0)
+}
+
+fn check_repository_layout() -> Result<u32> {
+    let git_repo = get_current_repository()?;
+    let features = git_repo.features();
+    if features == RepoFeatures::default() {
+        return Ok(0);
+    }
+
+    Output::check_start("Checking repository layout");
+    let mut warnings = 0;
+
+    if features.sparse_checkout {
+        Output::info("Sparse checkout enabled");
+    }
+    if features.partial_clone {
+        Output::info("Partial clone: missing objects are fetched on demand");
+
+        // Cascade fetches from origin; blobs missing from another promisor can't be filled in
+        let promisors = git_repo.promisor_remotes();
+        if !promisors.iter().any(|remote| remote == "origin") {
+            Output::warning(format!(
+                "Partial clone objects come from '{}', but Cascade fetches from 'origin'",
+                promisors.join(", ")
+            ));
+            Output::solution("Clone with --filter from origin, or fetch the missing objects with 'git fetch --refetch'");
+            warnings += 1;
+        }
+    }
+    if features.needs_git_cli() {
+        Output::success("Checkouts, cherry-picks and fetches run through the git CLI");
+    }
+
+    if features.shallow {
+        // Merge bases below the shallow boundary are missing, so rebases can replay too much
+        Output::warning("Shallow clone: stacks based on truncated history may fail to rebase");
+        Output::solution("git fetch --unshallow");
+        warnings += 1;
+    }
+
+    Ok(warnings)
+}
+
 fn print_summary(issues: u32, warnings: u32) {
     Output::section("Summary");
 
     if issues == 0 && warnings == 0 {
         Output::success("All checks passed! Your repository is ready for Cascade.");
-        println!();
+        Output::spacing();
         Output::tip("Next steps:");
         Output::bullet("Create your first stack: ca create \"Add new feature\"");
         Output::bullet("Submit for review: ca submit");
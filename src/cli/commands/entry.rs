@@ -1,4 +1,6 @@
+use crate::bitbucket::BitbucketIntegration;
 use crate::cli::output::Output;
+use crate::cli::prompt::{self, prompter, Prompter};
 use crate::errors::{CascadeError, Result};
 use crate::git::{find_repository_root, GitRepository};
 use crate::stack::{StackEntry, StackManager};
@@ -8,7 +10,6 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use dialoguer::{theme::ColorfulTheme, Confirm};
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Alignment, Constraint, Direction, Layout},
@@ -91,9 +92,6 @@ pub enum EntryAction {
         /// Skip interactive picker and use entry number directly
         #[arg(long)]
         direct: bool,
-        /// Skip confirmation prompts
-        #[arg(long, short)]
-        yes: bool,
     },
     /// Show current edit mode status
     Status {
@@ -108,16 +106,17 @@ pub enum EntryAction {
         verbose: bool,
     },
     /// Clear/exit edit mode (useful for recovering from corrupted state)
-    Clear {
-        /// Skip confirmation prompt
-        #[arg(long, short)]
-        yes: bool,
-    },
+    Clear,
     /// Amend the current stack entry commit and automatically restack dependent entries
     ///
     /// Automatically includes all modified tracked files (like 'git commit -a --amend')
-    /// and rebases all dependent entries onto the amended commit
+    /// and rebases all dependent entries onto the amended commit.
+    ///
+    /// With an entry number, folds the currently staged changes into that entry instead
+    /// (no checkout needed), restacks the entries above it and force-pushes them
     Amend {
+        /// Stack entry number to fold staged changes into (defaults to the current entry)
+        entry: Option<usize>,
         /// New commit message (optional, uses git editor if not provided)
         #[arg(long, short)]
         message: Option<String>,
@@ -127,6 +126,9 @@ pub enum EntryAction {
         /// Automatically force-push after amending (if PR exists)
         #[arg(long)]
         push: bool,
+        /// Post a comment on the entry's PR noting the update (with an entry number)
+        #[arg(long, requires = "entry")]
+        comment: bool,
     },
     /// Continue restacking after resolving cherry-pick conflicts
     ///
@@ -136,6 +138,63 @@ pub enum EntryAction {
     ///
     /// Safely aborts the cherry-pick and cleans up any partial restack state
     Abort,
+    /// Move an entry (commit, branch and PR) from the active stack to another stack
+    ///
+    /// The entry is rebased onto the target stack's top and the remaining
+    /// entries of the active stack are restacked over the gap
+    Move {
+        /// Stack entry number to move
+        entry: usize,
+        /// Name of the stack to move the entry to
+        #[arg(long)]
+        to: String,
+    },
+    /// Change an entry's commit message and update its PR title/description to match
+    ///
+    /// Descendant entries are restacked and rewritten branches with PRs are force-pushed
+    Reword {
+        /// Stack entry number to reword
+        entry: usize,
+        /// New commit message (optional, opens your editor if not provided)
+        #[arg(long, short)]
+        message: Option<String>,
+    },
+    /// Open an entry's pull request in the browser
+    Open {
+        /// Stack entry number (defaults to the entry you're on)
+        entry: Option<usize>,
+        /// Print the URL instead of opening it
+        #[arg(long)]
+        print: bool,
+    },
+    /// List or resolve the tasks on an entry's pull request
+    ///
+    /// Tasks are the checklist reviewers leave on a PR; 'ca land --auto' waits until
+    /// all of them are resolved
+    Tasks {
+        #[command(subcommand)]
+        action: EntryTaskAction,
+    },
+}
+
+/// Actions of `ca entry tasks`
+#[derive(Debug, Subcommand)]
+pub enum EntryTaskAction {
+    /// Show the open tasks
+    List {
+        /// Stack entry number (defaults to the entry you're on)
+        #[arg(long, value_name = "N")]
+        entry: Option<usize>,
+    },
+    /// Mark tasks as done
+    Resolve {
+        /// Task IDs, as shown by 'ca entry tasks list'
+        #[arg(required = true)]
+        ids: Vec<u64>,
+        /// Stack entry number (defaults to the entry you're on)
+        #[arg(long, value_name = "N")]
+        entry: Option<usize>,
+    },
 }
 
 pub async fn run(action: EntryAction) -> Result<()> {
@@ -143,13 +202,34 @@ pub async fn run(action: EntryAction) -> Result<()> {
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
 
     match action {
-        EntryAction::Checkout { entry, direct, yes } => checkout_entry(entry, direct, yes).await,
+        EntryAction::Checkout { entry, direct } => {
+            checkout_entry(entry, direct, prompt::assume_yes()).await
+        }
         EntryAction::Status { quiet } => show_edit_status(quiet).await,
         EntryAction::List { verbose } => list_entries(verbose).await,
-        EntryAction::Clear { yes } => clear_edit_mode(yes).await,
-        EntryAction::Amend { message, all, push } => amend_entry(message, all, push).await,
+        EntryAction::Clear => clear_edit_mode(prompt::assume_yes()).await,
+        EntryAction::Amend {
+            entry: Some(entry),
+            message,
+            comment,
+            ..
+        } => amend_entry_at(entry, message, comment).await,
+        EntryAction::Amend {
+            entry: None,
+            message,
+            all,
+            push,
+            ..
+        } => amend_entry(message, all, push).await,
         EntryAction::Continue => continue_restack().await,
         EntryAction::Abort => abort_restack().await,
+        EntryAction::Move { entry, to } => move_entry(entry, to).await,
+        EntryAction::Reword { entry, message } => reword_entry(entry, message).await,
+        EntryAction::Open { entry, print } => super::browse::open_entry(entry, print).await,
+        EntryAction::Tasks { action } => match action {
+            EntryTaskAction::List { entry } => list_tasks(entry).await,
+            EntryTaskAction::Resolve { ids, entry } => resolve_tasks(entry, ids).await,
+        },
     }
 }
 
@@ -168,9 +248,9 @@ async fn checkout_entry(
     let mut manager = StackManager::new(&repo_root)?;
 
     // Get active stack
-    let active_stack = manager.get_active_stack().ok_or_else(|| {
-        CascadeError::config("No active stack. Create a stack first with 'ca stack create'")
-    })?;
+    let active_stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
 
     if active_stack.entries.is_empty() {
         return Err(CascadeError::config(
@@ -249,13 +329,8 @@ async fn checkout_entry(
             ));
 
             // Interactive confirmation to exit current edit mode
-            let should_exit_edit_mode = Confirm::with_theme(&ColorfulTheme::default())
-                .with_prompt("Exit current edit mode and start a new one?")
-                .default(false)
-                .interact()
-                .map_err(|e| {
-                    CascadeError::config(format!("Failed to get user confirmation: {e}"))
-                })?;
+            let should_exit_edit_mode =
+                prompter().confirm("Exit current edit mode and start a new one?", false)?;
 
             if !should_exit_edit_mode {
                 return Err(CascadeError::config(
@@ -292,11 +367,7 @@ async fn checkout_entry(
         Output::info("Any changes you make can be amended to this commit or create new entries.");
 
         // Interactive confirmation to proceed with checkout
-        let should_continue = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Continue with checkout?")
-            .default(false)
-            .interact()
-            .map_err(|e| CascadeError::config(format!("Failed to get user confirmation: {e}")))?;
+        let should_continue = prompter().confirm("Continue with checkout?", false)?;
 
         if !should_continue {
             return Err(CascadeError::config("Entry checkout cancelled"));
@@ -494,7 +565,7 @@ async fn show_edit_status(quiet: bool) -> Result<()> {
 
     if !manager.is_in_edit_mode() {
         if quiet {
-            println!("inactive");
+            Output::line("inactive");
         } else {
             Output::info("Not in edit mode");
             Output::sub_item("Use 'ca entry checkout' to start editing a stack entry");
@@ -505,7 +576,7 @@ async fn show_edit_status(quiet: bool) -> Result<()> {
     let edit_info = manager.get_edit_mode_info().unwrap();
 
     if quiet {
-        println!("active:{:?}", edit_info.target_entry_id);
+        Output::line(format!("active:{:?}", edit_info.target_entry_id));
         return Ok(());
     }
 
@@ -622,11 +693,9 @@ async fn list_entries(verbose: bool) -> Result<()> {
         .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
     let manager = StackManager::new(&repo_root)?;
 
-    let active_stack = manager.get_active_stack().ok_or_else(|| {
-        CascadeError::config(
-            "No active stack. Create a stack first with 'ca stack create'".to_string(),
-        )
-    })?;
+    let active_stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
 
     if active_stack.entries.is_empty() {
         Output::info(format!(
@@ -790,12 +859,8 @@ async fn clear_edit_mode(skip_confirmation: bool) -> Result<()> {
 
     // Confirm before clearing
     if !skip_confirmation {
-        println!();
-        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Clear edit mode state?")
-            .default(true)
-            .interact()
-            .map_err(|e| CascadeError::config(format!("Failed to get user confirmation: {e}")))?;
+        Output::spacing();
+        let confirmed = prompter().confirm("Clear edit mode state?", true)?;
 
         if !confirmed {
             return Err(CascadeError::config("Operation cancelled."));
@@ -826,9 +891,9 @@ async fn amend_entry(message: Option<String>, _all: bool, push: bool) -> Result<
 
     // Get active stack info we need (clone to avoid borrow issues)
     let (stack_id, entry_index, entry_id, entry_branch, working_branch, has_dependents, has_pr) = {
-        let active_stack = manager.get_active_stack().ok_or_else(|| {
-            CascadeError::config("No active stack. Create a stack first with 'ca stack create'")
-        })?;
+        let active_stack = manager
+            .get_active_stack()
+            .ok_or_else(CascadeError::no_active_stack)?;
 
         // Find which entry we're amending (must be on a stack branch)
         let mut found_entry = None;
@@ -958,7 +1023,7 @@ async fn amend_entry(message: Option<String>, _all: bool, push: bool) -> Result<
 
     // 5. Auto-push if requested and entry has a PR
     if push {
-        println!();
+        Output::spacing();
 
         if has_pr {
             Output::section("Force-pushing to remote");
@@ -966,7 +1031,12 @@ async fn amend_entry(message: Option<String>, _all: bool, push: bool) -> Result<
             // Set env var to skip force-push confirmation
             std::env::set_var("FORCE_PUSH_NO_CONFIRM", "1");
 
-            repo.force_push_branch(&current_branch, &current_branch)?;
+            let lease = manager
+                .get_stack(&stack_id)
+                .and_then(|s| s.get_entry(&entry_id))
+                .and_then(|e| e.remote_commit_hash.clone());
+            repo.force_push_branch_with_lease(&current_branch, &current_branch, lease.as_deref())?;
+            manager.set_entry_remote_commit(&stack_id, &entry_id, new_commit_hash.clone())?;
             Output::success(format!("Force-pushed '{}' to remote", current_branch));
             Output::sub_item("PR will be automatically updated");
         } else {
@@ -976,7 +1046,7 @@ async fn amend_entry(message: Option<String>, _all: bool, push: bool) -> Result<
     }
 
     // Summary
-    println!();
+    Output::spacing();
     Output::section("Summary");
     Output::bullet(format!(
         "Amended entry #{} on branch '{}'",
@@ -992,7 +1062,7 @@ async fn amend_entry(message: Option<String>, _all: bool, push: bool) -> Result<
 
     // Automatically restack dependent entries (no flag needed - always required)
     if has_dependents {
-        println!();
+        Output::spacing();
         let dependent_count = {
             let stack = manager
                 .get_stack(&stack_id)
@@ -1026,15 +1096,15 @@ async fn amend_entry(message: Option<String>, _all: bool, push: bool) -> Result<
                 ));
             }
             Err(e) => {
-                println!();
+                Output::spacing();
                 Output::error(format!("Failed to restack dependent entries: {}", e));
-                println!();
+                Output::spacing();
                 Output::section("Recovery Steps");
                 Output::bullet("Resolve any conflicts in your editor");
                 Output::bullet("Stage resolved files: git add <files>");
                 Output::bullet("Continue: ca entry continue");
                 Output::bullet("Or abort: ca entry abort");
-                println!();
+                Output::spacing();
                 return Err(CascadeError::validation(
                     "Restack failed - resolve conflicts and run 'ca entry continue'",
                 ));
@@ -1044,7 +1114,7 @@ async fn amend_entry(message: Option<String>, _all: bool, push: bool) -> Result<
 
     // Tip about --push flag
     if !push && !has_dependents {
-        println!();
+        Output::spacing();
         Output::tip("Use --push to automatically force-push after amending");
     }
 
@@ -1174,7 +1244,7 @@ async fn restack_dependent_entries(
         git_repo.checkout_branch_silent(&temp_branch)?;
 
         // Cherry-pick the entry's commit
-        match git_repo.cherry_pick(&entry.commit_hash) {
+        match cherry_pick_entry(&git_repo, entry) {
             Ok(new_commit_hash) => {
                 // Update the entry's branch to point to the new commit
                 git_repo.update_branch_to_commit(&entry.branch, &new_commit_hash)?;
@@ -1201,24 +1271,24 @@ async fn restack_dependent_entries(
                 // CRITICAL: DO NOT checkout or delete temp branch!
                 // The user needs CHERRY_PICK_HEAD and conflict state to resolve/abort
 
-                println!();
+                Output::spacing();
                 Output::error(format!(
                     "Failed to restack entry #{} ({}): {}",
                     entry_num, entry.branch, e
                 ));
-                println!();
+                Output::spacing();
                 Output::section("Recovery Options");
-                println!();
+                Output::spacing();
                 Output::sub_item("To continue after resolving conflicts:");
                 Output::bullet("1. Check for conflicts: git status");
                 Output::bullet("2. Resolve conflicts in your editor");
                 Output::bullet("3. Stage resolved files: git add <files>");
                 Output::bullet("4. Continue restack: ca entry continue");
-                println!();
+                Output::spacing();
                 Output::sub_item("To abort and undo the restack:");
                 Output::bullet("→ Run: ca entry abort");
                 Output::bullet("→ Then check: ca validate");
-                println!();
+                Output::spacing();
                 Output::tip("Both commands bypass hooks to avoid edit-mode detection");
 
                 return Err(CascadeError::validation(format!(
@@ -1254,6 +1324,15 @@ async fn restack_dependent_entries(
     Ok(())
 }
 
+/// Cherry-pick all of an entry's commits onto the current branch, returning the new tip
+fn cherry_pick_entry(git_repo: &GitRepository, entry: &StackEntry) -> Result<String> {
+    let mut tip = String::new();
+    for commit in git_repo.get_first_parent_chain(&entry.commit_hash, entry.commit_count)? {
+        tip = git_repo.cherry_pick(&commit)?;
+    }
+    Ok(tip)
+}
+
 /// Continue restacking after resolving cherry-pick conflicts
 /// This completes the cherry-pick (skipping hooks) and updates metadata
 async fn continue_restack() -> Result<()> {
@@ -1299,6 +1378,9 @@ async fn continue_restack() -> Result<()> {
         }
     }
 
+    // Remember this resolution so the same conflict resolves itself next time
+    git_repo.record_conflict_resolutions()?;
+
     // Complete the cherry-pick with CASCADE_SKIP_HOOKS to bypass pre-commit hook
     let output = std::process::Command::new("git")
         .args(["cherry-pick", "--continue"])
@@ -1346,7 +1428,7 @@ async fn continue_restack() -> Result<()> {
         // No restack state - this is a standalone continue, use active stack
         stack_manager
             .get_active_stack()
-            .ok_or_else(|| CascadeError::config("No active stack"))?
+            .ok_or_else(CascadeError::no_active_stack)?
             .id
     };
 
@@ -1392,12 +1474,12 @@ async fn continue_restack() -> Result<()> {
     // Continue with restack if there are remaining entries
     if let Some(state) = restack_state {
         if !state.remaining_entries.is_empty() {
-            println!();
+            Output::spacing();
             Output::info(format!(
                 "Continuing restack: {} remaining entries",
                 state.remaining_entries.len()
             ));
-            println!();
+            Output::spacing();
 
             // Continue restacking remaining entries
             // Use the new commit as the base for the next entry
@@ -1447,7 +1529,7 @@ async fn continue_restack() -> Result<()> {
                 git_repo.create_branch(&temp_branch, Some(&current_base_commit))?;
                 git_repo.checkout_branch_silent(&temp_branch)?;
 
-                match git_repo.cherry_pick(&entry.commit_hash) {
+                match cherry_pick_entry(&git_repo, entry) {
                     Ok(new_hash) => {
                         // Update branch and metadata
                         git_repo.update_branch_to_commit(&entry.branch, &new_hash)?;
@@ -1474,22 +1556,22 @@ async fn continue_restack() -> Result<()> {
                     }
                     Err(e) => {
                         // Cherry-pick failed - leave state intact for next continue
-                        println!();
+                        Output::spacing();
                         Output::error(format!(
                             "Failed to restack entry #{} ({}): {}",
                             entry_num, entry.branch, e
                         ));
-                        println!();
+                        Output::spacing();
                         Output::section("Recovery Options");
-                        println!();
+                        Output::spacing();
                         Output::sub_item("To continue after resolving conflicts:");
                         Output::bullet("1. Check for conflicts: git status");
                         Output::bullet("2. Resolve conflicts in your editor");
                         Output::bullet("3. Continue restack: ca entry continue");
-                        println!();
+                        Output::spacing();
                         Output::sub_item("To abort:");
                         Output::bullet("→ Run: ca entry abort");
-                        println!();
+                        Output::spacing();
 
                         return Err(CascadeError::validation(format!(
                             "Restack paused at entry #{} - resolve conflicts or abort",
@@ -1519,11 +1601,11 @@ async fn continue_restack() -> Result<()> {
             // Checkout back to the amended branch (where we started)
             git_repo.checkout_branch_unsafe(&state.amended_branch)?;
 
-            println!();
+            Output::spacing();
             Output::success("Restack completed successfully!");
             Output::sub_item("All dependent entries have been rebased");
             Output::sub_item("Working branch updated");
-            println!();
+            Output::spacing();
         } else {
             // No remaining entries - this was the last one!
             // Update working branch to point to the newly resolved commit
@@ -1550,16 +1632,16 @@ async fn continue_restack() -> Result<()> {
             // Checkout back to the amended branch (where we started)
             git_repo.checkout_branch_unsafe(&state.amended_branch)?;
 
-            println!();
+            Output::spacing();
             Output::success("Restack completed!");
             Output::sub_item("All dependent entries have been rebased");
-            println!();
+            Output::spacing();
         }
     } else {
         // No state file - this was a standalone continue (not part of restack)
-        println!();
+        Output::spacing();
         Output::success("Cherry-pick completed!");
-        println!();
+        Output::spacing();
     }
 
     Ok(())
@@ -1639,14 +1721,594 @@ async fn abort_restack() -> Result<()> {
     // Delete restack state file - operation was aborted
     RestackState::delete(&repo_root)?;
 
-    println!();
+    Output::spacing();
     Output::warning("Restack was aborted - stack may be in inconsistent state");
-    println!();
+    Output::spacing();
     Output::section("Next Steps");
     Output::bullet("Check stack state: ca validate");
     Output::bullet("If needed, fix issues with: ca validate (choose 'Incorporate' or 'Reset')");
     Output::bullet("Or try restack again: ca sync");
-    println!();
+    Output::spacing();
+
+    Ok(())
+}
+
+/// Move an entry from the active stack to the top of another stack
+async fn move_entry(entry_num: usize, target_name: String) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let mut manager = StackManager::new(&repo_root)?;
+
+    let (source_id, source_name, entry) = {
+        let active_stack = manager
+            .get_active_stack()
+            .ok_or_else(CascadeError::no_active_stack)?;
+
+        if entry_num == 0 || entry_num > active_stack.entries.len() {
+            return Err(CascadeError::config(format!(
+                "Invalid entry number: {}. Stack has {} entries",
+                entry_num,
+                active_stack.entries.len()
+            )));
+        }
+
+        (
+            active_stack.id,
+            active_stack.name.clone(),
+            active_stack.entries[entry_num - 1].clone(),
+        )
+    };
+
+    let target_id = manager
+        .get_stack_by_name(&target_name)
+        .map(|stack| stack.id)
+        .ok_or_else(|| CascadeError::config(format!("Stack '{target_name}' not found")))?;
+
+    Output::section(format!(
+        "Moving entry #{} from '{}' to '{}'",
+        entry_num, source_name, target_name
+    ));
+
+    let restacked = manager.move_entry(&source_id, entry_num - 1, &target_id)?;
+
+    Output::success(format!(
+        "Moved {} {} (branch: {})",
+        entry.short_hash(),
+        entry.short_message(40),
+        entry.branch
+    ));
+    for branch in &restacked {
+        Output::sub_item(format!("Restacked {branch}"));
+    }
+
+    // Stacks built on either stack now have a different base
+    for stack_id in [source_id, target_id] {
+        for (name, old_base, new_base) in manager.retarget_dependent_stacks(&stack_id)? {
+            Output::sub_item(format!(
+                "Retargeted dependent stack '{name}': {old_base} → {new_base}"
+            ));
+        }
+    }
+
+    // Force-push rewritten branches that have PRs and point them at their new parents
+    refresh_pull_requests(&repo_root, &target_id, std::slice::from_ref(&entry.branch)).await;
+    refresh_pull_requests(&repo_root, &source_id, &restacked).await;
+
+    if entry.pull_request_id.is_none() {
+        Output::tip(format!(
+            "Switch to '{target_name}' and run 'ca submit' to open a PR for the moved entry"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Force-push `branches` of a stack and retarget their open PRs after their commits were rewritten
+pub(crate) async fn refresh_pull_requests(repo_root: &Path, stack_id: &Uuid, branches: &[String]) {
+    let has_prs = StackManager::new(repo_root)
+        .ok()
+        .and_then(|manager| {
+            manager.get_stack(stack_id).map(|stack| {
+                stack
+                    .entries
+                    .iter()
+                    .any(|e| e.pull_request_id.is_some() && branches.contains(&e.branch))
+            })
+        })
+        .unwrap_or(false);
+    if !has_prs {
+        return;
+    }
+
+    let result = async {
+        let cascade_config = load_cascade_config(repo_root)?;
+        let branch_mapping = branches
+            .iter()
+            .map(|branch| (branch.clone(), branch.clone()))
+            .collect();
+
+        let mut integration =
+            BitbucketIntegration::new(StackManager::new(repo_root)?, cascade_config)?;
+        integration
+            .update_prs_after_rebase(stack_id, &branch_mapping)
+            .await
+    }
+    .await;
+
+    match result {
+        Ok(updated_prs) => {
+            for pr_update in updated_prs {
+                Output::sub_item(format!("Updated {pr_update}"));
+            }
+        }
+        Err(e) => {
+            Output::warning(format!("Failed to update pull requests: {e}"));
+            Output::sub_item("You may need to force-push and retarget PRs manually");
+        }
+    }
+}
+
+/// Load the repository settings as a full config for Bitbucket integration
+fn load_cascade_config(repo_root: &Path) -> Result<crate::config::CascadeConfig> {
+    let config_dir = crate::config::get_repo_config_dir(repo_root)?;
+    let settings = crate::config::Settings::load_from_file(&config_dir.join("config.json"))?;
+    Ok(crate::config::CascadeConfig {
+        bitbucket: Some(settings.bitbucket.clone()),
+        git: settings.git.clone(),
+        auth: crate::config::AuthConfig::default(),
+        cascade: settings.cascade.clone(),
+    })
+}
+
+/// Reword an entry's commit, restack its descendants and sync the PR title/description
+async fn reword_entry(entry_num: usize, message: Option<String>) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    reword_entry_at(&repo_root, entry_num, message).await
+}
+
+/// [`reword_entry`] for the repository at `repo_root`
+async fn reword_entry_at(
+    repo_root: &Path,
+    entry_num: usize,
+    message: Option<String>,
+) -> Result<()> {
+    use crate::stack::{EditAction, EditStep};
+
+    let manager = StackManager::new(repo_root)?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?
+        .clone();
+
+    if entry_num == 0 || entry_num > stack.entries.len() {
+        return Err(CascadeError::config(format!(
+            "Invalid entry number: {}. Stack has {} entries",
+            entry_num,
+            stack.entries.len()
+        )));
+    }
+    let entry = stack.entries[entry_num - 1].clone();
+    if entry.is_merged {
+        return Err(CascadeError::config(format!(
+            "Entry {entry_num} is already merged and cannot be reworded"
+        )));
+    }
+
+    let new_message = match message {
+        Some(message) => message,
+        None => {
+            let template = format!(
+                "{}\n\n# Enter the new message for entry #{} ({}).\n\
+                 # Lines starting with '#' are ignored; an empty message cancels.\n",
+                entry.message, entry_num, entry.branch
+            );
+            match dialoguer::Editor::new().extension(".txt").edit(&template) {
+                Ok(Some(edited)) => edited
+                    .lines()
+                    .filter(|line| !line.starts_with('#'))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Ok(None) => String::new(),
+                Err(e) => return Err(CascadeError::config(format!("Failed to open editor: {e}"))),
+            }
+        }
+    };
+    let new_message = new_message.trim().to_string();
+
+    if new_message.is_empty() {
+        Output::info("Reword cancelled (empty message).");
+        return Ok(());
+    }
+    if new_message == entry.message.trim() {
+        Output::info("Message unchanged.");
+        return Ok(());
+    }
+
+    let steps: Vec<EditStep> = stack
+        .entries
+        .iter()
+        .filter(|e| !e.is_merged)
+        .map(|e| EditStep {
+            entry_id: e.id,
+            action: if e.id == entry.id {
+                EditAction::Reword(new_message.clone())
+            } else {
+                EditAction::Pick
+            },
+        })
+        .collect();
+
+    let git_repo = GitRepository::open(repo_root)?;
+    let mut rebase_manager =
+        crate::stack::RebaseManager::new(manager, git_repo, crate::stack::RebaseOptions::default());
+    let result = rebase_manager.apply_edit_plan(&stack.id, &steps)?;
+
+    Output::success(format!(
+        "Reworded entry #{}: {}",
+        entry_num,
+        new_message.lines().next().unwrap_or("")
+    ));
+    let restacked = result.branch_mapping.len().saturating_sub(1);
+    if restacked > 0 {
+        Output::sub_item(format!("Restacked {restacked} dependent entries"));
+    }
+
+    // Push rewritten branches that have PRs, then bring the PR text in line with the commit
+    let rewritten: Vec<String> = result.branch_mapping.keys().cloned().collect();
+    refresh_pull_requests(repo_root, &stack.id, &rewritten).await;
+
+    if entry.pull_request_id.is_some() {
+        let updated = async {
+            let integration = BitbucketIntegration::new(
+                StackManager::new(repo_root)?,
+                load_cascade_config(repo_root)?,
+            )?;
+            integration
+                .update_pr_from_commit_message(&stack.id, &entry.id)
+                .await
+        }
+        .await;
+
+        match updated {
+            Ok(Some(pr_id)) => {
+                Output::sub_item(format!("Updated PR #{pr_id} title and description"))
+            }
+            Ok(None) => {}
+            Err(e) => Output::warning(format!("Failed to update PR title/description: {e}")),
+        }
+    }
+
+    Ok(())
+}
+
+/// Fold the staged changes into entry `entry_num` without checking it out
+async fn amend_entry_at(entry_num: usize, message: Option<String>, comment: bool) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let manager = StackManager::new(&repo_root)?;
+    let repo = GitRepository::open(&repo_root)?;
+
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?
+        .clone();
+
+    if entry_num == 0 || entry_num > stack.entries.len() {
+        return Err(CascadeError::config(format!(
+            "Invalid entry number: {}. Stack has {} entries",
+            entry_num,
+            stack.entries.len()
+        )));
+    }
+    let entry = stack.entries[entry_num - 1].clone();
+    if entry.is_merged {
+        return Err(CascadeError::config(format!(
+            "Entry {entry_num} is already merged and cannot be amended"
+        )));
+    }
+
+    // The working tree is reset to the rewritten branch afterwards, so only staged
+    // changes (which end up in the commit) may be pending
+    if repo.get_status_summary()?.has_unstaged_changes() {
+        return Err(CascadeError::branch(
+            "You have unstaged changes. Stage the changes to fold in and stash the rest first.",
+        ));
+    }
+
+    let fixup = repo
+        .snapshot_staged_changes(&format!("fixup! {}", entry.short_message(60)))?
+        .ok_or_else(|| {
+            CascadeError::validation(
+                "No staged changes. Stage the changes to fold in with 'git add'",
+            )
+        })?;
+
+    Output::section(format!("Amending stack entry #{entry_num}"));
+
+    let mut rebase_manager =
+        crate::stack::RebaseManager::new(manager, repo, crate::stack::RebaseOptions::default());
+    let result = rebase_manager.amend_entry(&stack.id, &entry.id, &fixup, message)?;
+    let manager = rebase_manager.into_stack_manager();
+
+    let current_branch_rewritten = {
+        let mut branches: Vec<&str> = result.branch_mapping.keys().map(String::as_str).collect();
+        branches.extend(stack.working_branch.as_deref());
+        manager.touches_current_branch(&branches)
+    };
+
+    Output::success(format!(
+        "Folded staged changes into entry #{} ({})",
+        entry_num, entry.branch
+    ));
+    let restacked = result.branch_mapping.len().saturating_sub(1);
+    if restacked > 0 {
+        Output::sub_item(format!("Restacked {restacked} dependent entries"));
+    }
+    if !current_branch_rewritten {
+        Output::warning("The changes are still staged on the current branch");
+        Output::tip("Run 'git restore --staged .' and discard them if you no longer need them");
+    }
+
+    let rewritten: Vec<String> = result.branch_mapping.keys().cloned().collect();
+    refresh_pull_requests(&repo_root, &stack.id, &rewritten).await;
+
+    if comment {
+        if let Some(pr_id) = entry
+            .pull_request_id
+            .as_deref()
+            .and_then(|id| id.parse::<u64>().ok())
+        {
+            let posted = async {
+                let config = load_cascade_config(&repo_root)?;
+                let bitbucket = config
+                    .bitbucket
+                    .as_ref()
+                    .ok_or_else(|| CascadeError::config("Bitbucket is not configured"))?;
+                let new_head = manager
+                    .get_stack(&stack.id)
+                    .and_then(|s| s.get_entry(&entry.id))
+                    .map(|e| e.short_hash())
+                    .unwrap_or_default();
+                crate::bitbucket::PullRequestManager::from_config(
+                    bitbucket,
+                    &config.cascade.build,
+                    &repo_root,
+                )?
+                .add_comment(
+                    pr_id,
+                    &format!(
+                        "🔧 **Entry amended**\n\n\
+                             Follow-up changes were folded into this entry (now `{new_head}`).\n\
+                             {restacked} dependent entr{} restacked.",
+                        if restacked == 1 { "y was" } else { "ies were" }
+                    ),
+                )
+                .await
+            }
+            .await;
+
+            match posted {
+                Ok(_) => Output::sub_item(format!("Posted update comment on PR #{pr_id}")),
+                Err(e) => Output::warning(format!("Failed to comment on PR #{pr_id}: {e}")),
+            }
+        } else {
+            Output::info("Entry has no PR - skipping comment");
+        }
+    }
 
     Ok(())
 }
+
+/// The pull request of an entry of the active stack (the entry you're on by default):
+/// its entry number, PR number and a manager to reach it
+fn entry_pull_request(
+    entry_num: Option<usize>,
+) -> Result<(usize, u64, crate::bitbucket::PullRequestManager)> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let manager = StackManager::new(&repo_root)?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
+
+    let entry_num = match entry_num {
+        Some(num) => num,
+        None => {
+            let current_branch = GitRepository::open(&repo_root)?.get_current_branch()?;
+            super::browse::current_entry_number(stack, &current_branch).ok_or_else(|| {
+                CascadeError::config(format!(
+                    "Branch '{current_branch}' is not a stack entry; pass --entry <N>"
+                ))
+            })?
+        }
+    };
+    if entry_num == 0 || entry_num > stack.entries.len() {
+        return Err(CascadeError::config(format!(
+            "Invalid entry number: {}. Stack has {} entries",
+            entry_num,
+            stack.entries.len()
+        )));
+    }
+
+    let pr_id = stack.entries[entry_num - 1]
+        .pull_request_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .ok_or_else(|| {
+            CascadeError::config(format!(
+                "Entry {entry_num} has no pull request yet. Submit it with 'ca submit {entry_num}'"
+            ))
+        })?;
+
+    let config = load_cascade_config(&repo_root)?;
+    let bitbucket = config
+        .bitbucket
+        .as_ref()
+        .ok_or_else(|| CascadeError::config("Bitbucket is not configured"))?;
+    let pr_manager = crate::bitbucket::PullRequestManager::from_config(
+        bitbucket,
+        &config.cascade.build,
+        &repo_root,
+    )?;
+    Ok((entry_num, pr_id, pr_manager))
+}
+
+/// Show the open tasks on an entry's pull request
+async fn list_tasks(entry_num: Option<usize>) -> Result<()> {
+    let (entry_num, pr_id, pr_manager) = entry_pull_request(entry_num)?;
+    let tasks = pr_manager.get_open_tasks(pr_id).await?;
+
+    if tasks.is_empty() {
+        Output::success(format!("PR #{pr_id} has no open tasks"));
+        return Ok(());
+    }
+
+    Output::section(format!("Open tasks on PR #{pr_id} (entry {entry_num})"));
+    for task in &tasks {
+        let author = task
+            .author
+            .as_ref()
+            .map(|user| format!(" - {}", user.display_name.as_deref().unwrap_or(&user.name)))
+            .unwrap_or_default();
+        Output::bullet(format!(
+            "[{}] {}{author}",
+            task.id,
+            task.text.lines().next().unwrap_or_default()
+        ));
+    }
+    Output::spacing();
+    Output::tip(format!(
+        "Resolve them with 'ca entry tasks resolve <ID>... --entry {entry_num}'"
+    ));
+    Ok(())
+}
+
+/// Mark tasks on an entry's pull request as done
+async fn resolve_tasks(entry_num: Option<usize>, ids: Vec<u64>) -> Result<()> {
+    let (_, pr_id, pr_manager) = entry_pull_request(entry_num)?;
+
+    let mut failed = 0;
+    for id in &ids {
+        match pr_manager.resolve_task(pr_id, *id).await {
+            Ok(task) => Output::success(format!(
+                "Resolved task {id}: {}",
+                task.text.lines().next().unwrap_or_default()
+            )),
+            Err(e) => {
+                Output::error(format!("Could not resolve task {id}: {e}"));
+                failed += 1;
+            }
+        }
+    }
+
+    let remaining = pr_manager.get_open_tasks(pr_id).await?.len();
+    if remaining == 0 {
+        Output::info(format!("PR #{pr_id} has no open tasks left"));
+    } else {
+        Output::info(format!(
+            "PR #{pr_id} still has {remaining} open task{}",
+            if remaining == 1 { "" } else { "s" }
+        ));
+    }
+
+    if failed > 0 {
+        return Err(CascadeError::validation(format!(
+            "{failed} of {} tasks could not be resolved",
+            ids.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_reword_first_entry_rewrites_descendants() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path().to_path_buf();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        git(&["init"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["config", "user.email", "test@test.com"]);
+        std::fs::write(repo_path.join("README.md"), "# Test").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-m", "Initial"]);
+        crate::config::initialize_repo(&repo_path, Some("https://test.bitbucket.com".to_string()))
+            .unwrap();
+
+        let base = git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("reword".to_string(), Some(base.clone()), None)
+            .unwrap();
+        let mut before = Vec::new();
+        for name in ["a", "b", "c"] {
+            git(&["checkout", "-b", &format!("reword-{name}")]);
+            std::fs::write(repo_path.join(format!("{name}.txt")), name).unwrap();
+            git(&["add", &format!("{name}.txt")]);
+            git(&["commit", "-m", &format!("Add {name}")]);
+            let hash = git(&["rev-parse", "HEAD"]);
+            manager.get_stack_mut(&stack_id).unwrap().push_entry(
+                format!("reword-{name}"),
+                hash.clone(),
+                format!("Add {name}"),
+            );
+            before.push(hash);
+        }
+        manager.save_to_disk().unwrap();
+
+        reword_entry_at(&repo_path, 1, Some("Add a, reworded".to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            git(&["log", "-1", "--format=%s", "reword-a"]),
+            "Add a, reworded"
+        );
+        assert_eq!(git(&["rev-parse", "reword-a^"]), git(&["rev-parse", &base]));
+        assert_eq!(
+            git(&["rev-parse", "reword-b^"]),
+            git(&["rev-parse", "reword-a"])
+        );
+        assert_eq!(
+            git(&["rev-parse", "reword-c^"]),
+            git(&["rev-parse", "reword-b"])
+        );
+        assert_eq!(git(&["log", "-1", "--format=%s", "reword-c"]), "Add c");
+
+        let manager = StackManager::new(&repo_path).unwrap();
+        let stack = manager.get_stack(&stack_id).unwrap();
+        assert_eq!(stack.entries[0].message, "Add a, reworded");
+        for (entry, old_hash) in stack.entries.iter().zip(&before) {
+            assert_ne!(&entry.commit_hash, old_hash);
+            assert_eq!(entry.commit_hash, git(&["rev-parse", &entry.branch]));
+        }
+    }
+}
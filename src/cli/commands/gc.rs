@@ -0,0 +1,161 @@
+use crate::cli::commands::backups::format_age;
+use crate::cli::output::Output;
+use crate::config::{get_repo_config_dir, Settings};
+use crate::errors::{CascadeError, Result};
+use crate::git::backups::prunable_backups;
+use crate::git::{find_repository_root, GitRepository};
+use crate::stack::gc::{prunable_files, remove_files, state_files, GcState, StateKind};
+use chrono::Utc;
+use std::env;
+use std::process::{Command, Stdio};
+
+/// Remove snapshots, cache files and backups outside the retention policy.
+///
+/// With `dry_run` only report what would go. `auto` is set for the background run and
+/// suppresses all output.
+pub async fn run(dry_run: bool, auto: bool) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Failed to get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)?;
+    let config_dir = get_repo_config_dir(&repo_root)?;
+    let settings = Settings::load_from_file(&config_dir.join("config.json"))?;
+    let git_repo = GitRepository::open(&repo_root)?;
+    let now = Utc::now();
+
+    let files = state_files(&config_dir)?;
+    let prunable = prunable_files(&files, &settings.cascade.gc, now);
+    let backups = git_repo.list_backups()?;
+    let prunable_refs = prunable_backups(&backups, &settings.cascade.backups, now);
+
+    if !auto {
+        if prunable.is_empty() && prunable_refs.is_empty() {
+            Output::success("Nothing to clean up");
+            return Ok(());
+        }
+
+        Output::section(if dry_run {
+            "🧹 Would remove"
+        } else {
+            "🧹 Removing"
+        });
+        for (kind, title) in [
+            (StateKind::Snapshot, "Snapshots"),
+            (StateKind::Cache, "Cache files"),
+        ] {
+            let of_kind: Vec<_> = prunable
+                .iter()
+                .filter(|(file, _)| file.kind == kind)
+                .collect();
+            if of_kind.is_empty() {
+                continue;
+            }
+            let size = of_kind.iter().map(|(file, _)| file.size).sum();
+            Output::info(format!(
+                "{title} ({}, {})",
+                of_kind.len(),
+                format_size(size)
+            ));
+            for (file, reason) in of_kind {
+                Output::sub_item(format!(
+                    "{}  {}, {} ({})",
+                    file.label,
+                    format_size(file.size),
+                    reason,
+                    format_age(file.modified)
+                ));
+            }
+        }
+        if !prunable_refs.is_empty() {
+            Output::info(format!("Backups ({})", prunable_refs.len()));
+            for backup in &prunable_refs {
+                Output::sub_item(format!(
+                    "{} @ {} ({})",
+                    backup.branch,
+                    backup.timestamp(),
+                    format_age(backup.created_at)
+                ));
+            }
+        }
+    }
+
+    if dry_run {
+        Output::spacing();
+        Output::tip("Run 'ca gc' to remove them");
+        return Ok(());
+    }
+
+    let files: Vec<_> = prunable.iter().map(|(file, _)| *file).collect();
+    let freed = remove_files(&files)?;
+    let pruned_refs = git_repo.prune_backups()?;
+
+    let mut state = GcState::load(&config_dir);
+    state.operations = 0;
+    state.last_run = Some(now);
+    state.save(&config_dir)?;
+
+    if !auto {
+        Output::spacing();
+        Output::success(format!(
+            "Removed {} file(s) and {} backup(s), freed {}",
+            files.len(),
+            pruned_refs,
+            format_size(freed)
+        ));
+    }
+    Ok(())
+}
+
+/// Count a finished command and start `ca gc --auto` in the background once
+/// `gc.auto_after_operations` commands have run. Never fails the command it follows.
+pub fn run_if_due() {
+    let Some(config_dir) = env::current_dir()
+        .ok()
+        .and_then(|dir| find_repository_root(&dir).ok())
+        .and_then(|root| get_repo_config_dir(&root).ok())
+        .filter(|dir| dir.join("config.json").exists())
+    else {
+        return;
+    };
+    let auto_after_operations = Settings::load_from_file(&config_dir.join("config.json"))
+        .map(|settings| settings.cascade.gc.auto_after_operations)
+        .unwrap_or_default();
+
+    let mut state = GcState::load(&config_dir);
+    let due = state.record_operation(auto_after_operations);
+    if let Err(e) = state.save(&config_dir) {
+        tracing::debug!("Failed to record gc state: {}", e);
+        return;
+    }
+    if !due {
+        return;
+    }
+
+    let spawned = env::current_exe().and_then(|exe| {
+        Command::new(exe)
+            .args(["gc", "--auto"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+    });
+    match spawned {
+        Ok(_) => tracing::debug!("Started background gc"),
+        Err(e) => tracing::debug!("Failed to start background gc: {}", e),
+    }
+}
+
+/// Human readable size, e.g. `1.5 MB`
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
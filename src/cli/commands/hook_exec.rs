@@ -0,0 +1,709 @@
+use crate::cli::commands::entry::{self, EntryAction};
+use crate::cli::commands::hooks::{self, CommitTrackingDecision, HookType, HooksManager};
+use crate::cli::commands::push::PushSpec;
+use crate::cli::output::Output;
+use crate::config::is_repo_initialized;
+use crate::errors::{CascadeError, Result};
+use crate::git::{find_repository_root, GitRepository};
+use crate::stack::StackManager;
+use crate::utils::platform;
+use std::env;
+use std::fs;
+use std::io::{BufRead, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tracing::debug;
+
+/// Shortest commit message the commit-msg hook accepts
+const MIN_MESSAGE_LENGTH: usize = 10;
+
+/// Subjects of commits git or rebase generate, which the commit-msg hook leaves alone
+const GENERATED_SUBJECTS: [&str; 4] = ["Merge", "Revert", "fixup!", "squash!"];
+
+/// Start of the section `git commit --verbose` appends and strips again
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+/// What git should do once Cascade's part of a hook has run
+#[derive(Debug, Clone, PartialEq)]
+enum HookOutcome {
+    /// Carry on, running the original hook next
+    Continue,
+    /// Stop the git operation
+    Block,
+}
+
+/// Run an installed Git hook: Cascade's checks first, then the hook Cascade replaced when it
+/// took over `core.hooksPath`. The exit status is what git acts on.
+pub async fn run(hook: String, args: Vec<String>) -> Result<()> {
+    let hook_type: HookType = hook.parse()?;
+    let repo_root = repo_root()?;
+
+    // Git passes pre-push the refs being pushed on stdin; the original hook needs them too
+    let stdin = match hook_type {
+        HookType::PrePush => Some(read_stdin()?),
+        _ => None,
+    };
+
+    execute(&repo_root, &hook_type, &args, stdin, false).await
+}
+
+/// Run a hook by hand (`ca hooks run`), optionally only reporting what it would do.
+///
+/// Without refs on stdin, pre-push checks a push of the current branch to origin, and
+/// commit-msg without a message file checks the HEAD commit's message.
+pub async fn run_manually(hook: &str, args: Vec<String>, dry_run: bool) -> Result<()> {
+    let hook_type: HookType = hook.parse()?;
+    let repo_root = repo_root()?;
+
+    let manager = HooksManager::new(&repo_root)?;
+    if !manager.is_installed(&hook_type)? {
+        Output::warning(format!(
+            "The {} hook isn't installed, so git doesn't run it (install with 'ca hooks add {}')",
+            hook_type.name(),
+            hook_type.name()
+        ));
+    }
+
+    let stdin = match hook_type {
+        HookType::PrePush if std::io::stdin().is_terminal() => {
+            Some(current_branch_refs(&repo_root)?.into_bytes())
+        }
+        HookType::PrePush => Some(read_stdin()?),
+        _ => None,
+    };
+
+    if matches!(hook_type, HookType::CommitMsg) && args.is_empty() {
+        let temp_dir = platform::secure_temp_dir().map_err(CascadeError::Io)?;
+        let message_file = write_head_message(&repo_root, &temp_dir)?;
+        let args = vec![message_file.to_string_lossy().to_string()];
+        let result = execute(&repo_root, &hook_type, &args, stdin, dry_run).await;
+        fs::remove_dir_all(temp_dir).ok();
+        return result;
+    }
+
+    execute(&repo_root, &hook_type, &args, stdin, dry_run).await
+}
+
+async fn execute(
+    repo_root: &Path,
+    hook_type: &HookType,
+    args: &[String],
+    stdin: Option<Vec<u8>>,
+    dry_run: bool,
+) -> Result<()> {
+    let outcome = if is_repo_initialized(repo_root) {
+        match hook_type {
+            HookType::PostCommit => post_commit(repo_root, dry_run).await,
+            HookType::PrePush => pre_push(repo_root, stdin.as_deref().unwrap_or_default())?,
+            HookType::CommitMsg => commit_msg(args)?,
+            HookType::PreCommit => pre_commit(repo_root, dry_run).await?,
+            HookType::PrepareCommitMsg => prepare_commit_msg(repo_root, args, dry_run)?,
+        }
+    } else {
+        if matches!(hook_type, HookType::PostCommit) {
+            Output::info("Cascade not initialized, skipping stack management");
+            Output::sub_item("Run 'ca init' to start using stacked diffs");
+        }
+        HookOutcome::Continue
+    };
+
+    let original = HooksManager::new(repo_root)?.original_hook(hook_type)?;
+
+    if dry_run {
+        Output::spacing();
+        match (&outcome, &original) {
+            (HookOutcome::Block, _) => {
+                Output::warning(format!("Dry run: {} would stop git", hook_type.name()))
+            }
+            (HookOutcome::Continue, Some(original)) => Output::info(format!(
+                "Dry run: {} would pass, then run the original hook {}",
+                hook_type.name(),
+                original.display()
+            )),
+            (HookOutcome::Continue, None) => {
+                Output::info(format!("Dry run: {} would pass", hook_type.name()))
+            }
+        }
+        return Ok(());
+    }
+
+    if outcome == HookOutcome::Block {
+        std::process::exit(1);
+    }
+
+    let Some(original) = original else {
+        return Ok(());
+    };
+    debug!("Chaining to original hook {}", original.display());
+    let status = run_original_hook(&original, args, stdin.as_deref())?;
+    if status != 0 {
+        std::process::exit(status);
+    }
+    Ok(())
+}
+
+fn repo_root() -> Result<PathBuf> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))
+}
+
+fn read_stdin() -> Result<Vec<u8>> {
+    let mut input = Vec::new();
+    std::io::stdin()
+        .read_to_end(&mut input)
+        .map_err(|e| CascadeError::config(format!("Could not read pushed refs: {e}")))?;
+    Ok(input)
+}
+
+/// The pre-push ref line git would send for pushing the current branch to origin
+fn current_branch_refs(repo_root: &Path) -> Result<String> {
+    let repo = GitRepository::open(repo_root)?;
+    let branch = repo.get_current_branch()?;
+    let local = repo.get_branch_head(&branch)?;
+    let remote = repo
+        .get_remote_branch_head(&branch)
+        .unwrap_or_else(|_| "0".repeat(local.len()));
+    Output::info(format!("Checking a push of '{branch}' to origin"));
+    Ok(format!(
+        "refs/heads/{branch} {local} refs/heads/{branch} {remote}\n"
+    ))
+}
+
+/// Write the HEAD commit's message into `dir`, standing in for git's message file
+fn write_head_message(repo_root: &Path, dir: &Path) -> Result<PathBuf> {
+    let repo = GitRepository::open(repo_root)?;
+    let message = repo
+        .get_head_commit()?
+        .message()
+        .unwrap_or_default()
+        .to_string();
+    let path = dir.join("COMMIT_EDITMSG");
+    fs::write(&path, message).map_err(CascadeError::Io)?;
+    Ok(path)
+}
+
+/// Add a new commit to the active stack. Git ignores post-commit's exit status, so
+/// problems are reported rather than raised.
+async fn post_commit(repo_root: &Path, dry_run: bool) -> HookOutcome {
+    let head = GitRepository::open(repo_root).and_then(|repo| {
+        let commit = repo.get_head_commit()?;
+        Ok((
+            commit.id().to_string(),
+            commit.summary().unwrap_or_default().to_string(),
+        ))
+    });
+    let (commit_hash, summary) = match head {
+        Ok(head) => head,
+        Err(e) => {
+            debug!("Could not read the new commit: {}", e);
+            return HookOutcome::Continue;
+        }
+    };
+
+    match hooks::decide_commit_tracking(repo_root, &commit_hash) {
+        Ok(CommitTrackingDecision::Track) if dry_run => {
+            Output::info(format!(
+                "Would add commit {} ({summary}) to the active stack",
+                &commit_hash[..8]
+            ));
+        }
+        Ok(CommitTrackingDecision::Track) => {
+            Output::progress("Adding commit to active stack");
+            Output::sub_item(format!("Commit: {summary}"));
+            let result = super::stack::push(PushSpec::new().yes(true)).await;
+            match result {
+                Ok(()) => {
+                    Output::success("Commit added to stack successfully");
+                    Output::sub_item("Next: 'ca submit' to create PRs when ready");
+                }
+                Err(e) => {
+                    Output::warning(format!("Failed to add commit to stack: {e}"));
+                    Output::tip(format!(
+                        "You can manually add it with: ca push --commit {commit_hash}"
+                    ));
+                }
+            }
+        }
+        Ok(CommitTrackingDecision::NoActiveStack) => {
+            Output::info("No active stack found, commit will not be added to any stack");
+            Output::tip("Use 'ca stack create <name>' to create a stack for this commit");
+        }
+        Ok(CommitTrackingDecision::Skip(reason)) if dry_run => {
+            Output::info(format!("Would leave the commit alone: {reason}"));
+        }
+        Ok(CommitTrackingDecision::Skip(reason)) => {
+            debug!("Not tracking commit {}: {}", commit_hash, reason);
+        }
+        Err(e) => debug!("Could not decide whether to track {}: {}", commit_hash, e),
+    }
+
+    HookOutcome::Continue
+}
+
+/// Stop force pushes and pushes of stack branches that no longer match the stack
+fn pre_push(repo_root: &Path, refs: &[u8]) -> Result<HookOutcome> {
+    // Cascade's own force pushes (ca sync, ca submit, ...) leave a marker in the git dir
+    let repo = GitRepository::open(repo_root)?;
+    if repo.git_dir().join(".cascade-internal-push").exists() {
+        debug!("Cascade's own push is in progress");
+        return Ok(HookOutcome::Continue);
+    }
+
+    let refs = String::from_utf8_lossy(refs);
+    let forced = hooks::find_forced_pushes(repo_root, &refs)?;
+    if !forced.is_empty() {
+        Output::error(format!("Force push detected: {}", forced.join(", ")));
+        Output::sub_item("Cascade CLI uses stacked diffs - force pushes can break stack integrity");
+        Output::section("Instead, try these commands");
+        Output::command_example("ca sync      # Sync with remote changes (handles rebasing)");
+        Output::command_example("ca push      # Push all unpushed commits");
+        Output::command_example("ca submit    # Submit all entries for review");
+        Output::command_example("ca autoland  # Auto-merge when approved + builds pass");
+        Output::tip(
+            "If you really need to force push, skip this check with \
+             'git push --no-verify --force-with-lease [remote] [branch]' \
+             (but consider if this will affect other stack entries)",
+        );
+        return Ok(HookOutcome::Block);
+    }
+
+    if let Err(e) = hooks::check_pushed_refs(repo_root, &refs) {
+        Output::error(format!(
+            "Stack validation failed - fix with 'ca validate --fix' before pushing: {e}"
+        ));
+        return Ok(HookOutcome::Block);
+    }
+
+    // Validate the active stack only; unrelated stacks shouldn't block a push
+    let validated = env::current_exe()
+        .and_then(|cascade_cli| {
+            Command::new(cascade_cli)
+                .args(["validate", "--current"])
+                .current_dir(repo_root)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+        })
+        .map(|status| status.success())
+        .unwrap_or(false);
+    if !validated {
+        Output::error("Stack validation failed - run 'ca validate' for details");
+        return Ok(HookOutcome::Block);
+    }
+
+    Ok(HookOutcome::Continue)
+}
+
+/// Reject commit messages too short to describe a stack entry
+fn commit_msg(args: &[String]) -> Result<HookOutcome> {
+    let Some(message_file) = args.first() else {
+        return Err(CascadeError::config("No commit message file provided"));
+    };
+    let message = fs::read_to_string(message_file)
+        .map_err(|e| CascadeError::config(format!("Could not read commit message: {e}")))?;
+
+    match commit_message_problem(&message) {
+        Some(problem) => {
+            Output::error(problem);
+            Output::tip("Write a descriptive commit message for better stack management");
+            Ok(HookOutcome::Block)
+        }
+        None => Ok(HookOutcome::Continue),
+    }
+}
+
+/// Why a commit message would be rejected, judged on the text git keeps
+fn commit_message_problem(message: &str) -> Option<String> {
+    let text = message
+        .lines()
+        .take_while(|line| !line.starts_with(SCISSORS))
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim();
+
+    if GENERATED_SUBJECTS
+        .iter()
+        .any(|subject| text.starts_with(subject))
+    {
+        return None;
+    }
+    (text.chars().count() < MIN_MESSAGE_LENGTH)
+        .then(|| format!("Commit message too short (minimum {MIN_MESSAGE_LENGTH} characters)"))
+}
+
+/// In edit mode, ask whether a commit on the entry's branch should amend the entry instead.
+/// Outside it, ask where a commit on the branch of an entry below the stack top should go.
+async fn pre_commit(repo_root: &Path, dry_run: bool) -> Result<HookOutcome> {
+    // `ca entry amend` commits with this set so it isn't asked about its own commit
+    if env::var("CASCADE_SKIP_HOOKS").as_deref() == Ok("1") {
+        return Ok(HookOutcome::Continue);
+    }
+
+    {
+        let manager = StackManager::new(repo_root)?;
+        let repo = manager.git_repo();
+        if repo.has_operation_in_progress() {
+            return Ok(HookOutcome::Continue);
+        }
+        if !manager.is_in_edit_mode() {
+            return commit_on_lower_entry(&manager, dry_run).await;
+        }
+        // Edit mode for an entry on another branch doesn't concern this commit
+        let Ok(current_branch) = repo.get_current_branch() else {
+            return Ok(HookOutcome::Continue);
+        };
+        let on_entry_branch = manager
+            .get_all_stacks()
+            .iter()
+            .any(|stack| stack.entries.iter().any(|e| e.branch == current_branch));
+        if !on_entry_branch {
+            return Ok(HookOutcome::Continue);
+        }
+    }
+
+    if dry_run {
+        Output::info(
+            "Would ask whether to amend the entry being edited, add a new entry or cancel",
+        );
+        return Ok(HookOutcome::Continue);
+    }
+
+    Output::section("You're in EDIT MODE for a stack entry");
+    Output::sub_item("Choose your action:");
+    Output::sub_item("  [a] amend: Modify the current entry (default)");
+    Output::sub_item("  [n] new:   Create new entry on top");
+    Output::sub_item("  [c] cancel: Stop and think about it");
+
+    let choice = ask_terminal("Your choice (a/n/c): ").unwrap_or_default();
+    match choice.trim().to_lowercase().as_str() {
+        "" | "a" => {
+            // The staged changes go into the entry, so the commit git was about to make
+            // would duplicate it
+            entry::run(EntryAction::Amend {
+                entry: None,
+                message: None,
+                all: false,
+                push: false,
+                comment: false,
+            })
+            .await?;
+            Output::info("Amend applied - skipping git commit to avoid duplicate entry.");
+            Output::sub_item("Your commit was updated by Cascade; no further action needed.");
+            Ok(HookOutcome::Block)
+        }
+        "n" => {
+            Output::info("Creating new stack entry...");
+            Output::sub_item(
+                "The commit will proceed and post-commit hook will add it to your stack",
+            );
+            Ok(HookOutcome::Continue)
+        }
+        "c" => {
+            Output::info("Commit cancelled");
+            Ok(HookOutcome::Block)
+        }
+        _ => {
+            Output::error("Invalid choice. Please choose A, n, or c");
+            Ok(HookOutcome::Block)
+        }
+    }
+}
+
+/// A commit on the branch of an entry with others stacked on top would be missing from those
+/// entries. Offer to put it on top of the stack instead (post-commit, via `ca push`, moves it
+/// there as a new entry) or to fold it into the entry.
+async fn commit_on_lower_entry(manager: &StackManager, dry_run: bool) -> Result<HookOutcome> {
+    let Some(stack) = manager.get_active_stack() else {
+        return Ok(HookOutcome::Continue);
+    };
+    let Ok(current_branch) = manager.git_repo().get_current_branch() else {
+        return Ok(HookOutcome::Continue);
+    };
+    let Some(index) = stack.lower_entry_index(&current_branch) else {
+        return Ok(HookOutcome::Continue);
+    };
+    // `ca push` moves the commit onto the working branch, where new entries come from
+    let Some(working_branch) = stack
+        .working_branch
+        .as_deref()
+        .filter(|working| *working != current_branch)
+    else {
+        return Ok(HookOutcome::Continue);
+    };
+    let entry_number = index + 1;
+
+    if dry_run {
+        Output::info(format!(
+            "Would warn that '{current_branch}' is entry {entry_number} of {}, below the stack \
+             top, and ask whether to move the commit on top of the stack, amend the entry or cancel",
+            stack.entries.len()
+        ));
+        return Ok(HookOutcome::Continue);
+    }
+
+    Output::section(format!(
+        "You're committing on '{current_branch}', entry {entry_number} of {} in stack '{}'",
+        stack.entries.len(),
+        stack.name
+    ));
+    Output::sub_item("The entries stacked above it won't contain this commit.");
+    Output::sub_item("Choose your action:");
+    Output::sub_item(format!(
+        "  [t] top:    Move the commit onto '{working_branch}' as a new entry (default)"
+    ));
+    Output::sub_item(format!(
+        "  [a] amend:  Fold the changes into entry {entry_number}"
+    ));
+    Output::sub_item("  [c] cancel: Stop and think about it");
+
+    let choice = ask_terminal("Your choice (t/a/c): ").unwrap_or_default();
+    match choice.trim().to_lowercase().as_str() {
+        "" | "t" => {
+            if HooksManager::new(manager.repo_path())?.is_installed(&HookType::PostCommit)? {
+                Output::sub_item(
+                    "The post-commit hook will move the commit to the top of the stack",
+                );
+            } else {
+                Output::sub_item(
+                    "Run 'ca push' after committing to move it to the top of the stack",
+                );
+            }
+            Ok(HookOutcome::Continue)
+        }
+        "a" => {
+            entry::run(EntryAction::Amend {
+                entry: Some(entry_number),
+                message: None,
+                all: false,
+                push: false,
+                comment: false,
+            })
+            .await?;
+            Output::info("Amend applied - skipping git commit to avoid a duplicate commit.");
+            Ok(HookOutcome::Block)
+        }
+        "c" => {
+            Output::info("Commit cancelled");
+            Ok(HookOutcome::Block)
+        }
+        _ => {
+            Output::error("Invalid choice. Please choose T, a, or c");
+            Ok(HookOutcome::Block)
+        }
+    }
+}
+
+/// Read an answer from the terminal; git doesn't give hooks a usable stdin
+fn ask_terminal(question: &str) -> Option<String> {
+    #[cfg(windows)]
+    const TERMINAL: &str = "CONIN$";
+    #[cfg(not(windows))]
+    const TERMINAL: &str = "/dev/tty";
+
+    let terminal = fs::File::open(TERMINAL).ok()?;
+    eprint!("{question}");
+    std::io::stderr().flush().ok();
+    let mut answer = String::new();
+    std::io::BufReader::new(terminal)
+        .read_line(&mut answer)
+        .ok()?;
+    Some(answer)
+}
+
+/// Note the active stack in the message of a commit made from the editor, starting it with
+/// the team's commit template when `.cascade/commit_template` exists
+fn prepare_commit_msg(repo_root: &Path, args: &[String], dry_run: bool) -> Result<HookOutcome> {
+    // A source means the message came from -m, a template, a merge or an amend
+    let (Some(message_file), None) = (args.first(), args.get(1).filter(|s| !s.is_empty())) else {
+        return Ok(HookOutcome::Continue);
+    };
+    let Ok(manager) = StackManager::new(repo_root) else {
+        return Ok(HookOutcome::Continue);
+    };
+    let Some(stack) = manager.get_active_stack() else {
+        return Ok(HookOutcome::Continue);
+    };
+
+    let template = match fs::read_to_string(commit_template_path(repo_root)?) {
+        Ok(template) => {
+            let branch = manager.git_repo().get_current_branch().unwrap_or_default();
+            let issue = find_issue_key(&branch)
+                .or_else(|| find_issue_key(&stack.name))
+                .unwrap_or_else(|| ISSUE_PLACEHOLDER.to_string());
+            Some(render_commit_template(
+                &template,
+                &stack.name,
+                stack.entries.len() + 1,
+                &issue,
+            ))
+        }
+        Err(_) => None,
+    };
+
+    let message = fs::read_to_string(message_file)
+        .map_err(|e| CascadeError::config(format!("Could not read commit message: {e}")))?;
+    let updated = with_stack_context(&message, &stack.name, template.as_deref());
+    if dry_run {
+        match (&updated, &template) {
+            (None, _) => Output::info("The message already notes its stack"),
+            (Some(_), Some(template)) => {
+                Output::info("Would start the message with the commit template:");
+                for line in template.lines() {
+                    Output::sub_item(line);
+                }
+            }
+            (Some(_), None) => {
+                Output::info(format!("Would note stack '{}' in the message", stack.name))
+            }
+        }
+        return Ok(HookOutcome::Continue);
+    }
+    if let Some(message) = updated {
+        fs::write(message_file, message)
+            .map_err(|e| CascadeError::config(format!("Could not write commit message: {e}")))?;
+    }
+    Ok(HookOutcome::Continue)
+}
+
+/// The message with stack context comments added, unless it already has them. A rendered
+/// commit template becomes the start of the message.
+fn with_stack_context(message: &str, stack_name: &str, template: Option<&str>) -> Option<String> {
+    if message.contains("[stack:") || message.contains("# Stack: ") {
+        return None;
+    }
+    Some(format!(
+        "{}\n# Stack: {stack_name}\n\
+         # This commit will be added to the active stack automatically.\n\
+         # Use 'ca stack' to see the current stack state.\n\
+         {message}",
+        template.map(str::trim_end).unwrap_or_default()
+    ))
+}
+
+/// Team commit message template, shared like the rest of `.cascade/`
+fn commit_template_path(repo_root: &Path) -> Result<PathBuf> {
+    Ok(crate::config::get_repo_config_dir(repo_root)?.join("commit_template"))
+}
+
+/// Left in place of `{issue}` when neither the branch nor the stack name has an issue key
+const ISSUE_PLACEHOLDER: &str = "ISSUE-KEY";
+
+/// Fill in a commit template: `{stack}` is the active stack's name, `{position}` the entry
+/// number the commit will get and `{issue}` the issue key
+fn render_commit_template(template: &str, stack: &str, position: usize, issue: &str) -> String {
+    template
+        .replace("{stack}", stack)
+        .replace("{position}", &position.to_string())
+        .replace("{issue}", issue)
+}
+
+/// First issue key (e.g. `PROJ-123`) in a branch or stack name
+fn find_issue_key(text: &str) -> Option<String> {
+    text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .flat_map(|word| {
+            let parts: Vec<&str> = word.split('-').collect();
+            (1..parts.len())
+                .map(|i| (parts[i - 1], parts[i]))
+                .collect::<Vec<_>>()
+        })
+        .find(|(project, number)| {
+            project.len() >= 2
+                && project.starts_with(|c: char| c.is_ascii_uppercase())
+                && project
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                && !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit())
+        })
+        .map(|(project, number)| format!("{project}-{number}"))
+}
+
+/// Run the original hook with git's arguments (and stdin, when git sent any)
+fn run_original_hook(hook: &Path, args: &[String], stdin: Option<&[u8]>) -> Result<i32> {
+    // Git for Windows runs hooks with its bundled sh; so must we
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("sh");
+        command.arg(hook);
+        command
+    };
+    #[cfg(not(windows))]
+    let mut command = Command::new(hook);
+
+    command.args(args);
+    if stdin.is_some() {
+        command.stdin(Stdio::piped());
+    }
+    let mut child = command.spawn().map_err(|e| {
+        CascadeError::config(format!("Could not run hook '{}': {e}", hook.display()))
+    })?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        // A hook that exits without reading its input closes the pipe; that's fine
+        pipe.write_all(input).ok();
+    }
+    let status = child.wait().map_err(|e| {
+        CascadeError::config(format!("Could not run hook '{}': {e}", hook.display()))
+    })?;
+    Ok(status.code().unwrap_or(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_message_problem() {
+        assert!(commit_message_problem("Add login endpoint\n").is_none());
+        assert!(commit_message_problem("wip\n").is_some());
+        // Comment lines and the verbose diff don't count towards the length
+        assert!(commit_message_problem(
+            "wip\n# Please enter the commit message for your changes.\n# On branch main\n"
+        )
+        .is_some());
+        assert!(commit_message_problem(&format!(
+            "fix\n{SCISSORS}\ndiff --git a/src/lib.rs b/src/lib.rs\n"
+        ))
+        .is_some());
+        assert!(commit_message_problem("fixup! x").is_none());
+        assert!(commit_message_problem("Merge branch 'main'").is_none());
+    }
+
+    #[test]
+    fn test_with_stack_context() {
+        let message =
+            with_stack_context("\n# Please enter the commit message\n", "auth", None).unwrap();
+        assert!(message.starts_with("\n# Stack: auth\n"));
+        assert!(message.ends_with("\n# Please enter the commit message\n"));
+        // Running twice (e.g. after a failed commit) doesn't stack up the comments
+        assert!(with_stack_context(&message, "auth", None).is_none());
+
+        let message =
+            with_stack_context("\n# Please enter\n", "auth", Some("[PROJ-1] \n\n")).unwrap();
+        assert!(message.starts_with("[PROJ-1]\n# Stack: auth\n"));
+    }
+
+    #[test]
+    fn test_commit_template() {
+        assert_eq!(
+            render_commit_template(
+                "[{issue}] \n\nPart {position} of {stack}",
+                "auth",
+                3,
+                "PROJ-7"
+            ),
+            "[PROJ-7] \n\nPart 3 of auth"
+        );
+        assert_eq!(
+            find_issue_key("feature/PROJ-123-login").as_deref(),
+            Some("PROJ-123")
+        );
+        assert_eq!(find_issue_key("AB2-9_fix").as_deref(), Some("AB2-9"));
+        assert_eq!(find_issue_key("fix-2-bugs"), None);
+        assert_eq!(find_issue_key("main"), None);
+    }
+}
@@ -1,8 +1,9 @@
 use crate::cli::output::Output;
+use crate::cli::prompt::{prompter, Prompter};
 use crate::config::Settings;
 use crate::errors::{CascadeError, Result};
 use crate::git::find_repository_root;
-use dialoguer::{theme::ColorfulTheme, Confirm};
+use crate::utils::platform;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -69,22 +70,35 @@ pub enum HookType {
 }
 
 impl HookType {
-    fn filename(&self) -> String {
-        let base_name = match self {
+    /// Every hook Cascade can install
+    pub const ALL: [HookType; 5] = [
+        HookType::PostCommit,
+        HookType::PrePush,
+        HookType::CommitMsg,
+        HookType::PreCommit,
+        HookType::PrepareCommitMsg,
+    ];
+
+    /// Git hook name, as accepted by `ca hooks add/remove`
+    pub fn name(&self) -> &'static str {
+        match self {
             HookType::PostCommit => "post-commit",
             HookType::PrePush => "pre-push",
             HookType::CommitMsg => "commit-msg",
             HookType::PreCommit => "pre-commit",
             HookType::PrepareCommitMsg => "prepare-commit-msg",
-        };
+        }
+    }
+
+    fn filename(&self) -> String {
         format!(
             "{}{}",
-            base_name,
+            self.name(),
             crate::utils::platform::git_hook_extension()
         )
     }
 
-    fn description(&self) -> &'static str {
+    pub fn description(&self) -> &'static str {
         match self {
             HookType::PostCommit => "Auto-add new commits to active stack",
             HookType::PrePush => "Prevent force pushes and validate stack state",
@@ -95,6 +109,70 @@ impl HookType {
     }
 }
 
+impl std::str::FromStr for HookType {
+    type Err = CascadeError;
+
+    fn from_str(name: &str) -> Result<Self> {
+        HookType::ALL
+            .into_iter()
+            .find(|hook| hook.name() == name)
+            .ok_or_else(|| CascadeError::config(format!("Unknown hook type: {name}")))
+    }
+}
+
+/// Launcher script for a hook: everything past locating `ca` happens in `ca hook-exec`
+fn hook_launcher(hook_name: &str, cascade_cli: &str) -> String {
+    let version = env!("CARGO_PKG_VERSION");
+
+    #[cfg(windows)]
+    {
+        format!(
+            "@echo off\n\
+             rem Cascade CLI Hook - {hook_name}\n\
+             rem Generated by 'ca hooks install' (Cascade {version}); the logic runs in 'ca hook-exec'\n\
+             if not exist \"{cascade_cli}\" (\n\
+                 echo Cascade hook skipped: {cascade_cli} not found ^(run 'ca hooks install' to update^) 1>&2\n\
+                 exit /b 0\n\
+             )\n\
+             \"{cascade_cli}\" hook-exec {hook_name} %*\n\
+             exit /b %ERRORLEVEL%\n"
+        )
+    }
+
+    #[cfg(not(windows))]
+    {
+        format!(
+            "#!/bin/sh\n\
+             # Cascade CLI Hook - {hook_name}\n\
+             # Generated by 'ca hooks install' (Cascade {version}); the logic runs in 'ca hook-exec'\n\n\
+             CA=\"{cascade_cli}\"\n\
+             if [ ! -x \"$CA\" ]; then\n\
+             \x20   echo \"Cascade hook skipped: $CA not found (run 'ca hooks install' to update)\" >&2\n\
+             \x20   exit 0\n\
+             fi\n\
+             exec \"$CA\" hook-exec {hook_name} \"$@\"\n"
+        )
+    }
+}
+
+/// Write a hook so Git never sees it half-written or not yet executable
+fn write_hook_file(path: &Path, content: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let staged = path.with_file_name(format!(".{file_name}.tmp"));
+
+    fs::write(&staged, content)
+        .map_err(|e| CascadeError::config(format!("Failed to write hook file: {e}")))?;
+    platform::make_executable(&staged)
+        .map_err(|e| CascadeError::config(format!("Failed to make hook executable: {e}")))?;
+    fs::rename(&staged, path).map_err(|e| {
+        fs::remove_file(&staged).ok();
+        CascadeError::config(format!("Failed to install hook file: {e}"))
+    })
+}
+
 impl HooksManager {
     pub fn new(repo_path: &Path) -> Result<Self> {
         // Verify this is a git repository
@@ -399,17 +477,24 @@ impl HooksManager {
             CascadeError::config(format!("Failed to create cascade hooks directory: {e}"))
         })?;
 
-        // Generate hook that chains to original
-        let hook_content = self.generate_chaining_hook_script(hook_type)?;
+        // The launcher hands over to `ca hook-exec`, which also chains to the original hook
+        let hook_content = self.generate_hook_script(hook_type)?;
         let hook_path = cascade_hooks_dir.join(hook_type.filename());
 
-        // Write the hook
-        fs::write(&hook_path, hook_content)
-            .map_err(|e| CascadeError::config(format!("Failed to write hook file: {e}")))?;
+        #[cfg(windows)]
+        let hook_content = platform::to_crlf(&hook_content);
+        write_hook_file(&hook_path, &hook_content)?;
 
-        // Make executable (platform-specific)
-        crate::utils::platform::make_executable(&hook_path)
-            .map_err(|e| CascadeError::config(format!("Failed to make hook executable: {e}")))?;
+        // Git only runs the extension-less hook, so point it at the .cmd file
+        #[cfg(windows)]
+        {
+            write_hook_file(
+                &cascade_hooks_dir.join(hook_type.name()),
+                &platform::windows_hook_shim(hook_type.name()),
+            )?;
+            // Hooks from older versions were .bat files that Git never ran
+            fs::remove_file(cascade_hooks_dir.join(format!("{}.bat", hook_type.name()))).ok();
+        }
 
         // Set core.hooksPath to our cascade directory
         self.set_cascade_hooks_path()?;
@@ -422,7 +507,7 @@ impl HooksManager {
     fn set_cascade_hooks_path(&self) -> Result<()> {
         use std::process::Command;
 
-        let cascade_hooks_dir = self.get_cascade_hooks_dir()?;
+        let cascade_hooks_dir = platform::strip_verbatim(&self.get_cascade_hooks_dir()?);
         let hooks_path_str = cascade_hooks_dir.to_string_lossy();
 
         let output = Command::new("git")
@@ -512,6 +597,8 @@ impl HooksManager {
         if hook_path.exists() {
             fs::remove_file(&hook_path)
                 .map_err(|e| CascadeError::config(format!("Failed to remove hook file: {e}")))?;
+            #[cfg(windows)]
+            fs::remove_file(cascade_hooks_dir.join(hook_type.name())).ok();
             Output::success(format!("Removed {} hook", hook_type.filename()));
 
             // If no more hooks in cascade directory, restore original hooks path
@@ -573,25 +660,39 @@ impl HooksManager {
                     Output::info("  Chaining to original hooks: .git/hooks");
                 }
             }
-            println!();
+            Output::spacing();
         } else {
             Output::warning("Cascade hooks are NOT installed in this repository");
-            println!();
+            Output::spacing();
             Output::sub_item("To install Cascade hooks:");
             Output::command_example("ca hooks install            # recommended: 4 essential hooks");
             Output::command_example(
                 "ca hooks install --all      # all 5 hooks + post-commit auto-add",
             );
-            println!();
+            Output::spacing();
             Output::sub_item("Both options preserve existing hooks by chaining to them");
-            println!();
+            Output::spacing();
         }
 
+        let mut outdated = false;
         for hook in hooks {
             let cascade_hook_path = cascade_hooks_dir.join(hook.filename());
 
             if using_cascade_hooks && cascade_hook_path.exists() {
-                Output::success(format!("{}: {} ✓", hook.filename(), hook.description()));
+                // Hooks from before 'ca hook-exec' carry their own copy of the logic
+                let current = fs::read_to_string(&cascade_hook_path)
+                    .map(|content| content.contains("hook-exec"))
+                    .unwrap_or(false);
+                if current {
+                    Output::success(format!("{}: {} ✓", hook.filename(), hook.description()));
+                } else {
+                    outdated = true;
+                    Output::warning(format!(
+                        "{}: {} (outdated script)",
+                        hook.filename(),
+                        hook.description()
+                    ));
+                }
             } else {
                 // Check default location
                 let default_hook_path = {
@@ -616,6 +717,11 @@ impl HooksManager {
             }
         }
 
+        if outdated {
+            Output::spacing();
+            Output::tip("Run 'ca hooks install' to replace outdated hook scripts");
+        }
+
         Ok(())
     }
 
@@ -641,638 +747,60 @@ impl HooksManager {
         }
     }
 
-    /// Generate hook script content
+    /// Generate the launcher Git runs for a hook. It only hands over to `ca hook-exec`, which
+    /// holds the hook logic and chains to the original hook, so upgrading Cascade updates
+    /// every installed hook at once.
     pub fn generate_hook_script(&self, hook_type: &HookType) -> Result<String> {
-        let cascade_cli = env::current_exe()
-            .map_err(|e| {
-                CascadeError::config(format!("Failed to get current executable path: {e}"))
-            })?
-            .to_string_lossy()
-            .to_string();
-
-        let script = match hook_type {
-            HookType::PostCommit => self.generate_post_commit_hook(&cascade_cli),
-            HookType::PrePush => self.generate_pre_push_hook(&cascade_cli),
-            HookType::CommitMsg => self.generate_commit_msg_hook(&cascade_cli),
-            HookType::PreCommit => self.generate_pre_commit_hook(&cascade_cli),
-            HookType::PrepareCommitMsg => self.generate_prepare_commit_msg_hook(&cascade_cli),
-        };
-
-        Ok(script)
-    }
-
-    /// Generate hook script that chains to original hooks
-    pub fn generate_chaining_hook_script(&self, hook_type: &HookType) -> Result<String> {
-        let cascade_cli = env::current_exe()
-            .map_err(|e| {
-                CascadeError::config(format!("Failed to get current executable path: {e}"))
-            })?
-            .to_string_lossy()
-            .to_string();
-
-        let config_dir = self.get_cascade_config_dir()?;
-        let hook_name = match hook_type {
-            HookType::PostCommit => "post-commit",
-            HookType::PrePush => "pre-push",
-            HookType::CommitMsg => "commit-msg",
-            HookType::PreCommit => "pre-commit",
-            HookType::PrepareCommitMsg => "prepare-commit-msg",
-        };
-
-        // Generate the cascade-specific hook logic
-        let cascade_logic = match hook_type {
-            HookType::PostCommit => self.generate_post_commit_hook(&cascade_cli),
-            HookType::PrePush => self.generate_pre_push_hook(&cascade_cli),
-            HookType::CommitMsg => self.generate_commit_msg_hook(&cascade_cli),
-            HookType::PreCommit => self.generate_pre_commit_hook(&cascade_cli),
-            HookType::PrepareCommitMsg => self.generate_prepare_commit_msg_hook(&cascade_cli),
-        };
-
-        // Create wrapper that chains to original
-        #[cfg(windows)]
-        return Ok(format!(
-                "@echo off\n\
-                 rem Cascade CLI Hook Wrapper - {}\n\
-                 rem This hook runs Cascade logic first, then chains to original hooks\n\n\
-                 rem Run Cascade logic first\n\
-                 call :cascade_logic %*\n\
-                 set CASCADE_RESULT=%ERRORLEVEL%\n\
-                 if %CASCADE_RESULT% neq 0 exit /b %CASCADE_RESULT%\n\n\
-                 rem Check for original hook\n\
-                 set ORIGINAL_HOOKS_PATH=\n\
-                 if exist \"{}\\original-hooks-path\" (\n\
-                     set /p ORIGINAL_HOOKS_PATH=<\"{}\\original-hooks-path\"\n\
-                 )\n\n\
-                 if \"%ORIGINAL_HOOKS_PATH%\"==\"\" (\n\
-                     rem Default location\n\
-                     for /f \"tokens=*\" %%i in ('git rev-parse --git-dir 2^>nul') do set GIT_DIR=%%i\n\
-                     if exist \"%GIT_DIR%\\hooks\\{}\" (\n\
-                         call \"%GIT_DIR%\\hooks\\{}\" %*\n\
-                         exit /b %ERRORLEVEL%\n\
-                     )\n\
-                 ) else (\n\
-                     rem Custom hooks path\n\
-                     if exist \"%ORIGINAL_HOOKS_PATH%\\{}\" (\n\
-                         call \"%ORIGINAL_HOOKS_PATH%\\{}\" %*\n\
-                         exit /b %ERRORLEVEL%\n\
-                     )\n\
-                 )\n\n\
-                 exit /b 0\n\n\
-                 :cascade_logic\n\
-                 {}\n\
-                 exit /b %ERRORLEVEL%\n",
-                hook_name,
-                config_dir.to_string_lossy(),
-                config_dir.to_string_lossy(),
-                hook_name,
-                hook_name,
-                hook_name,
-                hook_name,
-                cascade_logic
-            ));
-
-        #[cfg(not(windows))]
-        {
-            // Build the wrapper using string concatenation to avoid double-escaping issues
-            let trimmed_logic = cascade_logic
-                .trim_start_matches("#!/bin/sh\n")
-                .trim_start_matches("set -e\n");
-
-            let wrapper = format!(
-                "#!/bin/sh\n\
-                 # Cascade CLI Hook Wrapper - {}\n\
-                 # This hook runs Cascade logic first, then chains to original hooks\n\n\
-                 set -e\n\n\
-                 # Function to run Cascade logic\n\
-                 cascade_logic() {{\n",
-                hook_name
-            );
-
-            let chaining_logic = format!(
-                "\n\
-                 }}\n\n\
-                 # Run Cascade logic first\n\
-                 cascade_logic \"$@\"\n\
-                 CASCADE_RESULT=$?\n\
-                 if [ $CASCADE_RESULT -ne 0 ]; then\n\
-                     exit $CASCADE_RESULT\n\
-                 fi\n\n\
-                 # Check for original hook\n\
-                 ORIGINAL_HOOKS_PATH=\"\"\n\
-                 if [ -f \"{}/original-hooks-path\" ]; then\n\
-                     ORIGINAL_HOOKS_PATH=$(cat \"{}/original-hooks-path\" 2>/dev/null || echo \"\")\n\
-                 fi\n\n\
-                 if [ -z \"$ORIGINAL_HOOKS_PATH\" ]; then\n\
-                     # Default location\n\
-                     GIT_DIR=$(git rev-parse --git-dir 2>/dev/null || echo \".git\")\n\
-                     ORIGINAL_HOOK=\"$GIT_DIR/hooks/{}\"\n\
-                 else\n\
-                     # Custom hooks path\n\
-                     ORIGINAL_HOOK=\"$ORIGINAL_HOOKS_PATH/{}\"\n\
-                 fi\n\n\
-                 # Run original hook if it exists and is executable\n\
-                 if [ -x \"$ORIGINAL_HOOK\" ]; then\n\
-                     \"$ORIGINAL_HOOK\" \"$@\"\n\
-                     exit $?\n\
-                 fi\n\n\
-                 exit 0\n",
-                config_dir.to_string_lossy(),
-                config_dir.to_string_lossy(),
-                hook_name,
-                hook_name
-            );
-
-            Ok(format!("{}{}{}", wrapper, trimmed_logic, chaining_logic))
-        }
-    }
-
-    fn generate_post_commit_hook(&self, cascade_cli: &str) -> String {
-        #[cfg(windows)]
-        {
-            format!(
-                "@echo off\n\
-                 rem Cascade CLI Hook - Post Commit\n\
-                 rem Automatically adds new commits to the active stack\n\n\
-                 rem Get the commit hash and message\n\
-                 for /f \"tokens=*\" %%i in ('git rev-parse HEAD') do set COMMIT_HASH=%%i\n\
-                 for /f \"tokens=*\" %%i in ('git log --format=%%s -n 1 HEAD') do set COMMIT_MSG=%%i\n\n\
-                 rem Find repository root and check if Cascade is initialized\n\
-                 for /f \"tokens=*\" %%i in ('git rev-parse --show-toplevel 2^>nul') do set REPO_ROOT=%%i\n\
-                 if \"%REPO_ROOT%\"==\"\" set REPO_ROOT=.\n\
-                 if not exist \"%REPO_ROOT%\\.cascade\" (\n\
-                     echo \"Cascade not initialized, skipping stack management\"\n\
-                     echo \"Run 'ca init' to start using stacked diffs\"\n\
-                     exit /b 0\n\
-                 )\n\n\
-                 rem Check if there's an active stack\n\
-                 \"{cascade_cli}\" stack list --active >nul 2>&1\n\
-                 if %ERRORLEVEL% neq 0 (\n\
-                     echo \"No active stack found, commit will not be added to any stack\"\n\
-                     echo \"Tip: Use 'ca stack create ^<name^>' to create a stack for this commit\"\n\
-                     exit /b 0\n\
-                 )\n\n\
-                 rem Add commit to active stack\n\
-                 echo \"Adding commit to active stack...\"\n\
-                 echo \"Commit: %COMMIT_MSG%\"\n\
-                 \"{cascade_cli}\" stack push --commit \"%COMMIT_HASH%\" --message \"%COMMIT_MSG%\"\n\
-                 if %ERRORLEVEL% equ 0 (\n\
-                     echo \"Commit added to stack successfully\"\n\
-                     echo \"Next: 'ca submit' to create PRs when ready\"\n\
-                 ) else (\n\
-                     echo \"Failed to add commit to stack\"\n\
-                     echo \"Tip: You can manually add it with: ca push --commit %COMMIT_HASH%\"\n\
-                 )\n"
-            )
-        }
+        let cascade_cli = platform::strip_verbatim(&env::current_exe().map_err(|e| {
+            CascadeError::config(format!("Failed to get current executable path: {e}"))
+        })?)
+        .to_string_lossy()
+        .to_string();
 
-        #[cfg(not(windows))]
-        {
-            format!(
-                "#!/bin/sh\n\
-                 # Cascade CLI Hook - Post Commit\n\
-                 # Automatically adds new commits to the active stack\n\n\
-                 set -e\n\n\
-                 # Get the commit hash and message\n\
-                 COMMIT_HASH=$(git rev-parse HEAD)\n\
-                 COMMIT_MSG=$(git log --format=%s -n 1 HEAD)\n\n\
-                 # Find repository root and check if Cascade is initialized\n\
-                 REPO_ROOT=$(git rev-parse --show-toplevel 2>/dev/null || echo \".\")\n\
-                 if [ ! -d \"$REPO_ROOT/.cascade\" ]; then\n\
-                     echo \"Cascade not initialized, skipping stack management\"\n\
-                     echo \"Run 'ca init' to start using stacked diffs\"\n\
-                     exit 0\n\
-                 fi\n\n\
-                 # Check if there's an active stack\n\
-                 if ! \"{cascade_cli}\" stack list --active > /dev/null 2>&1; then\n\
-                     echo \"No active stack found, commit will not be added to any stack\"\n\
-                     echo \"Tip: Use 'ca stack create <name>' to create a stack for this commit\"\n\
-                     exit 0\n\
-                 fi\n\n\
-                 # Add commit to active stack (using specific commit targeting)\n\
-                 echo \"Adding commit to active stack...\"\n\
-                 echo \"Commit: $COMMIT_MSG\"\n\
-                 if \"{cascade_cli}\" stack push --commit \"$COMMIT_HASH\" --message \"$COMMIT_MSG\"; then\n\
-                     echo \"Commit added to stack successfully\"\n\
-                     echo \"Next: 'ca submit' to create PRs when ready\"\n\
-                 else\n\
-                     echo \"Failed to add commit to stack\"\n\
-                     echo \"Tip: You can manually add it with: ca push --commit $COMMIT_HASH\"\n\
-                 fi\n"
-            )
-        }
-    }
-
-    fn generate_pre_push_hook(&self, cascade_cli: &str) -> String {
-        #[cfg(windows)]
-        {
-            format!(
-                "@echo off\n\
-                 rem Cascade CLI Hook - Pre Push\n\
-                 rem Prevents force pushes and validates stack state\n\n\
-                 rem Allow force pushes from Cascade internal commands (ca sync, ca submit, etc.)\n\
-                 rem Check for marker file (Git hooks don't inherit env vars)\n\
-                 for /f \"tokens=*\" %%i in ('git rev-parse --git-dir 2^>nul') do set GIT_DIR=%%i\n\
-                 if \"%GIT_DIR%\"==\"\" set GIT_DIR=.git\n\
-                 if exist \"%GIT_DIR%\\.cascade-internal-push\" (\n\
-                     exit /b 0\n\
-                 )\n\n\
-                 rem Check for force push from user\n\
-                 echo %* | findstr /C:\"--force\" /C:\"--force-with-lease\" /C:\"-f\" >nul\n\
-                 if %ERRORLEVEL% equ 0 (\n\
-                     echo ERROR: Force push detected\n\
-                     echo Cascade CLI uses stacked diffs - force pushes can break stack integrity\n\
-                     echo.\n\
-                     echo Instead, try these commands:\n\
-                     echo   ca sync      - Sync with remote changes ^(handles rebasing^)\n\
-                     echo   ca push      - Push all unpushed commits\n\
-                     echo   ca submit    - Submit all entries for review\n\
-                     echo   ca autoland  - Auto-merge when approved + builds pass\n\
-                     echo.\n\
-                     echo If you really need to force push:\n\
-                     echo   git push --force-with-lease [remote] [branch]\n\
-                     echo   ^(But consider if this will affect other stack entries^)\n\
-                     exit /b 1\n\
-                 )\n\n\
-                 rem Find repository root and check if Cascade is initialized\n\
-                 for /f \"tokens=*\" %%i in ('git rev-parse --show-toplevel 2^>nul') do set REPO_ROOT=%%i\n\
-                 if \"%REPO_ROOT%\"==\"\" set REPO_ROOT=.\n\
-                 if not exist \"%REPO_ROOT%\\.cascade\" (\n\
-                     exit /b 0\n\
-                 )\n\n\
-                 rem Validate active stack only (don't block pushes for unrelated stacks)\n\
-                 \"{cascade_cli}\" validate --current >nul 2>&1\n\
-                 if %ERRORLEVEL% neq 0 (\n\
-                     echo Stack validation failed - run 'ca validate' for details\n\
-                     exit /b 1\n\
-                 )\n"
-            )
-        }
-
-        #[cfg(not(windows))]
-        {
-            format!(
-                "#!/bin/sh\n\
-                 # Cascade CLI Hook - Pre Push\n\
-                 # Prevents force pushes and validates stack state\n\n\
-                 set -e\n\n\
-                 # Allow force pushes from Cascade internal commands (ca sync, ca submit, etc.)\n\
-                 # Check for marker file (Git hooks don't inherit env vars)\n\
-                 GIT_DIR=$(git rev-parse --git-dir 2>/dev/null || echo \".git\")\n\
-                 if [ -f \"$GIT_DIR/.cascade-internal-push\" ]; then\n\
-                     exit 0\n\
-                 fi\n\n\
-                 # Check for force push from user\n\
-                 if echo \"$*\" | grep -q -- \"--force\\|--force-with-lease\\|-f\"; then\n\
-                     echo \"ERROR: Force push detected\"\n\
-                     echo \"Cascade CLI uses stacked diffs - force pushes can break stack integrity\"\n\
-                     echo \"\"\n\
-                     echo \"Instead, try these commands:\"\n\
-                     echo \"  ca sync      - Sync with remote changes (handles rebasing)\"\n\
-                     echo \"  ca push      - Push all unpushed commits\"\n\
-                     echo \"  ca submit    - Submit all entries for review\"\n\
-                     echo \"  ca autoland  - Auto-merge when approved + builds pass\"\n\
-                     echo \"\"\n\
-                     echo \"If you really need to force push:\"\n\
-                     echo \"  git push --force-with-lease [remote] [branch]\"\n\
-                     echo \"  (But consider if this will affect other stack entries)\"\n\
-                     exit 1\n\
-                 fi\n\n\
-                 # Find repository root and check if Cascade is initialized\n\
-                 REPO_ROOT=$(git rev-parse --show-toplevel 2>/dev/null || echo \".\")\n\
-                 if [ ! -d \"$REPO_ROOT/.cascade\" ]; then\n\
-                     exit 0\n\
-                 fi\n\n\
-                 # Validate active stack only (don't block pushes for unrelated stacks)\n\
-                 if ! \"{cascade_cli}\" validate --current > /dev/null 2>&1; then\n\
-                     echo \"Stack validation failed - run 'ca validate' for details\"\n\
-                     exit 1\n\
-                     fi\n"
-            )
-        }
+        Ok(hook_launcher(hook_type.name(), &cascade_cli))
     }
 
-    fn generate_commit_msg_hook(&self, _cascade_cli: &str) -> String {
-        #[cfg(windows)]
-        {
-            r#"@echo off
-rem Cascade CLI Hook - Commit Message
-rem Validates commit message format
-
-set COMMIT_MSG_FILE=%1
-if "%COMMIT_MSG_FILE%"=="" (
-    echo ERROR: No commit message file provided
-    exit /b 1
-)
-
-rem Read commit message (Windows batch is limited, but this covers basic cases)
-for /f "delims=" %%i in ('type "%COMMIT_MSG_FILE%"') do set COMMIT_MSG=%%i
-
-rem Skip validation for merge commits, fixup commits, etc.
-echo %COMMIT_MSG% | findstr /B /C:"Merge" /C:"Revert" /C:"fixup!" /C:"squash!" >nul
-if %ERRORLEVEL% equ 0 exit /b 0
-
-rem Find repository root and check if Cascade is initialized
-for /f "tokens=*" %%i in ('git rev-parse --show-toplevel 2^>nul') do set REPO_ROOT=%%i
-if "%REPO_ROOT%"=="" set REPO_ROOT=.
-if not exist "%REPO_ROOT%\.cascade" exit /b 0
-
-rem Basic commit message validation
-echo %COMMIT_MSG% | findstr /R "^..........*" >nul
-if %ERRORLEVEL% neq 0 (
-    echo ERROR: Commit message too short (minimum 10 characters)
-    echo TIP: Write a descriptive commit message for better stack management
-    exit /b 1
-)
-
-rem Validation passed (silent success)
-exit /b 0
-"#
-            .to_string()
-        }
-
-        #[cfg(not(windows))]
-        {
-            r#"#!/bin/sh
-# Cascade CLI Hook - Commit Message
-# Validates commit message format
-
-set -e
-
-COMMIT_MSG_FILE="$1"
-COMMIT_MSG=$(cat "$COMMIT_MSG_FILE")
-
-# Skip validation for merge commits, fixup commits, etc.
-if echo "$COMMIT_MSG" | grep -E "^(Merge|Revert|fixup!|squash!)" > /dev/null; then
-    exit 0
-fi
-
-# Find repository root and check if Cascade is initialized
-REPO_ROOT=$(git rev-parse --show-toplevel 2>/dev/null || echo ".")
-if [ ! -d "$REPO_ROOT/.cascade" ]; then
-    exit 0
-fi
-
-# Basic commit message validation
-if [ ${#COMMIT_MSG} -lt 10 ]; then
-    echo "ERROR: Commit message too short (minimum 10 characters)"
-    echo "TIP: Write a descriptive commit message for better stack management"
-    exit 1
-fi
-
-# Validation passed (silent success)
-exit 0
-"#
-            .to_string()
-        }
+    /// Whether git runs Cascade's version of this hook
+    pub fn is_installed(&self, hook_type: &HookType) -> Result<bool> {
+        let cascade_hooks_dir = self.get_cascade_hooks_dir()?;
+        let active =
+            self.get_current_hooks_path()? == Some(cascade_hooks_dir.to_string_lossy().to_string());
+        Ok(active && cascade_hooks_dir.join(hook_type.filename()).exists())
     }
 
-    #[allow(clippy::uninlined_format_args)]
-    fn generate_pre_commit_hook(&self, cascade_cli: &str) -> String {
-        #[cfg(windows)]
-        {
-            format!(
-                "@echo off\n\
-                 rem Cascade CLI Hook - Pre Commit\n\
-                 rem Smart edit mode guidance for better UX\n\n\
-                 rem Check if Cascade is initialized\n\
-                 for /f \\\"tokens=*\\\" %%i in ('git rev-parse --show-toplevel 2^>nul') do set REPO_ROOT=%%i\n\
-                 if \\\"%REPO_ROOT%\\\"==\\\"\\\" set REPO_ROOT=.\n\
-                 if not exist \\\"%REPO_ROOT%\\.cascade\\\" exit /b 0\n\n\
-                 rem Skip hook if called from ca entry amend ^(avoid infinite loop^)\n\
-                 if \\\"%CASCADE_SKIP_HOOKS%\\\"==\\\"1\\\" exit /b 0\n\n\
-                 rem Skip hook during cherry-pick/rebase/merge operations\n\
-                 for /f \\\"tokens=*\\\" %%i in ('git rev-parse --git-dir 2^>nul') do set GIT_DIR=%%i\n\
-                 if \\\"%GIT_DIR%\\\"==\\\"\\\" set GIT_DIR=.git\n\
-                 if exist \\\"%GIT_DIR%\\CHERRY_PICK_HEAD\\\" exit /b 0\n\
-                 if exist \\\"%GIT_DIR%\\REBASE_HEAD\\\" exit /b 0\n\
-                 if exist \\\"%GIT_DIR%\\MERGE_HEAD\\\" exit /b 0\n\n\
-                 rem Get edit status\n\
-                 for /f \\\"tokens=*\\\" %%i in ('\\\"{0}\\\" entry status --quiet 2^>nul') do set EDIT_STATUS=%%i\n\
-                 if \\\"%EDIT_STATUS%\\\"==\\\"\\\" set EDIT_STATUS=inactive\n\n\
-                 rem Check if edit status is active\n\
-                 echo %EDIT_STATUS% | findstr /b \\\"active:\\\" >nul\n\
-                 if %ERRORLEVEL% equ 0 (\n\
-                     echo You're in EDIT MODE for a stack entry\n\
-                     echo.\n\
-                     echo Choose your action:\n\
-                     echo   [a] amend: Modify the current entry ^(default^)\n\
-                     echo   [n] new:   Create new entry on top\n\
-                     echo   [c] cancel: Stop and think about it\n\
-                     echo.\n\
-                     set /p choice=\\\"Your choice (a/n/c): \\\" <CON\n\
-                     if \\\"%choice%\\\"==\\\"\\\" set choice=a\n\
-                     \n\
-                    if /i \\\"%choice%\\\"==\\\"A\\\" (\n\
-                        rem Use ca entry amend to update entry ^(ignore any -m flag^)\n\
-                        rem Changes are already staged by git commit; --restack updates dependents\n\
-                        \\\"{0}\\\" entry amend --restack\n\
-                        set amend_error=%ERRORLEVEL%\n\
-                        if %amend_error% EQU 0 (\n\
-                            echo Amend applied - skipping git commit to avoid duplicate entry.\n\
-                            echo Your commit was updated by Cascade; no further action needed.\n\
-                            exit /b 1\n\
-                        ) else (\n\
-                            exit /b %amend_error%\n\
-                        )\n\
-                    ) else if /i \\\"%choice%\\\"==\\\"N\\\" (\n\
-                         echo Creating new stack entry...\n\
-                         echo The commit will proceed and post-commit hook will add it to your stack\n\
-                         rem Let commit proceed ^(Git will use -m flag or open editor^)\n\
-                         exit /b 0\n\
-                     ) else if /i \\\"%choice%\\\"==\\\"C\\\" (\n\
-                         echo Commit cancelled\n\
-                         exit /b 1\n\
-                     ) else (\n\
-                         echo Invalid choice. Please choose A, n, or c\n\
-                         exit /b 1\n\
-                     )\n\
-                 )\n\n\
-                 rem Not in edit mode, proceed normally\n\
-                 exit /b 0\n",
-                cascade_cli
-            )
-        }
+    /// The hook that was in effect before Cascade took over `core.hooksPath`, if there is
+    /// one Git would have run
+    pub fn original_hook(&self, hook_type: &HookType) -> Result<Option<PathBuf>> {
+        let original_path_file = self.get_cascade_config_dir()?.join("original-hooks-path");
+        let original_path = fs::read_to_string(original_path_file).unwrap_or_default();
+        let original_path = original_path.trim();
 
-        #[cfg(not(windows))]
-        {
-            // Use string building to avoid escaping issues with format! macros
-            // Check the OUTPUT of entry status, not just exit code
-            let status_check = format!(
-                "EDIT_STATUS=$(\"{}\" entry status --quiet 2>/dev/null || echo \"inactive\")",
-                cascade_cli
-            );
-            // When called from pre-commit hook during 'git commit', changes are already staged
-            // So we DON'T use --all flag, just amend with what's staged
-            let amend_line = format!("           \"{}\" entry amend --restack", cascade_cli);
-
-            vec![
-                "#!/bin/sh".to_string(),
-                "# Cascade CLI Hook - Pre Commit".to_string(),
-                "# Smart edit mode guidance for better UX".to_string(),
-                "".to_string(),
-                "set -e".to_string(),
-                "".to_string(),
-                "# Check if Cascade is initialized".to_string(),
-                r#"REPO_ROOT=$(git rev-parse --show-toplevel 2>/dev/null || echo ".")"#.to_string(),
-                r#"if [ ! -d "$REPO_ROOT/.cascade" ]; then"#.to_string(),
-                "    exit 0".to_string(),
-                "fi".to_string(),
-                "".to_string(),
-                "# Skip hook if called from ca entry amend (avoid infinite loop)".to_string(),
-                r#"if [ "$CASCADE_SKIP_HOOKS" = "1" ]; then"#.to_string(),
-                "    exit 0".to_string(),
-                "fi".to_string(),
-                "".to_string(),
-                "# Skip hook during cherry-pick/rebase/merge operations".to_string(),
-                r#"GIT_DIR=$(git rev-parse --git-dir 2>/dev/null || echo ".git")"#.to_string(),
-                r#"if [ -f "$GIT_DIR/CHERRY_PICK_HEAD" ] || [ -f "$GIT_DIR/REBASE_HEAD" ] || [ -f "$GIT_DIR/MERGE_HEAD" ]; then"#.to_string(),
-                "    exit 0".to_string(),
-                "fi".to_string(),
-                "".to_string(),
-                "# Check if we're in edit mode".to_string(),
-                r#"CURRENT_BRANCH=$(git branch --show-current 2>/dev/null)"#.to_string(),
-                status_check,
-                "".to_string(),
-                "# If in edit mode, check if we're on a stack entry branch".to_string(),
-                r#"if echo "$EDIT_STATUS" | grep -q "^active:"; then"#.to_string(),
-                "        # Check if current branch is a stack entry branch".to_string(),
-                format!(r#"        if ! "{}" stacks list --format=json 2>/dev/null | grep -q "\"branch_name\": \"$CURRENT_BRANCH\""; then"#, cascade_cli),
-                r#"                # Not on a stack entry branch - edit mode is for a different branch"#.to_string(),
-                r#"                # Silently proceed with normal commit"#.to_string(),
-                "                exit 0".to_string(),
-                "        fi".to_string(),
-                "        ".to_string(),
-                "        # Proper edit mode - prompt user".to_string(),
-                r#"        echo "You're in EDIT MODE for a stack entry""#.to_string(),
-                r#"        echo """#.to_string(),
-                r#"        echo "Choose your action:""#.to_string(),
-                r#"        echo "  [a] amend: Modify the current entry (default)""#.to_string(),
-                r#"        echo "  [n] new:   Create new entry on top""#.to_string(),
-                r#"        echo "  [c] cancel: Stop and think about it""#.to_string(),
-                r#"        echo """#.to_string(),
-                "        ".to_string(),
-                "        # Read user choice with default to amend".to_string(),
-                r#"        read -p "Your choice (a/n/c): " choice < /dev/tty"#.to_string(),
-                "        choice=${choice:-a}".to_string(),
-                "        ".to_string(),
-                "        ".to_string(),
-                r#"        case "$choice" in"#.to_string(),
-                "            [Aa])".to_string(),
-                "                # Use ca entry amend to properly update entry + working branch (ignore any -m flag)"
-                    .to_string(),
-                "                # Changes are already staged by 'git commit', so no --all flag needed".to_string(),
-                amend_line.replace("           ", "                "),
-                "                amend_rc=$?".to_string(),
-                r#"                if [ $amend_rc -eq 0 ]; then"#.to_string(),
-                r#"                    echo "Amend applied - skipping git commit to avoid duplicate entry.""#
-                    .to_string(),
-                r#"                    echo "Your commit was updated by Cascade; no further action needed.""#
-                    .to_string(),
-                "                    exit 1".to_string(),
-                "                else".to_string(),
-                "                    exit $amend_rc".to_string(),
-                "                fi".to_string(),
-                "                ;;".to_string(),
-                "            [Nn])".to_string(),
-                r#"                echo "Creating new stack entry...""#.to_string(),
-                r#"                echo "The commit will proceed and post-commit hook will add it to your stack""#.to_string(),
-                "                # Let the commit proceed normally (Git will use -m flag or open editor)"
-                    .to_string(),
-                "                exit 0".to_string(),
-                "                ;;".to_string(),
-                "            [Cc])".to_string(),
-                r#"                echo "Commit cancelled""#.to_string(),
-                "                exit 1".to_string(),
-                "                ;;".to_string(),
-                "            *)".to_string(),
-                r#"                echo "Invalid choice. Please choose A, n, or c""#.to_string(),
-                "                exit 1".to_string(),
-                "                ;;".to_string(),
-                "        esac".to_string(),
-                "fi".to_string(),
-                "".to_string(),
-                "# Not in edit mode, proceed normally".to_string(),
-                "exit 0".to_string(),
-            ]
-            .join("\n")
-        }
-    }
+        let hooks_dir = if original_path.is_empty() {
+            let repo = git2::Repository::discover(&self.repo_path).ok();
+            repo.map(|r| r.commondir().join("hooks"))
+                .unwrap_or_else(|| self.repo_path.join(".git").join("hooks"))
+        } else if let Some(rest) = original_path.strip_prefix("~/") {
+            dirs::home_dir()
+                .ok_or_else(|| CascadeError::config("Could not find home directory".to_string()))?
+                .join(rest)
+        } else {
+            // Relative paths are relative to the repo root, as for git
+            self.repo_path.join(original_path)
+        };
 
-    fn generate_prepare_commit_msg_hook(&self, cascade_cli: &str) -> String {
-        #[cfg(windows)]
-        {
-            format!(
-                "@echo off\n\
-                 rem Cascade CLI Hook - Prepare Commit Message\n\
-                 rem Adds stack context to commit messages\n\n\
-                 set COMMIT_MSG_FILE=%1\n\
-                 set COMMIT_SOURCE=%2\n\
-                 set COMMIT_SHA=%3\n\n\
-                 rem Skip if user provided message via -m flag, merge commit, etc.\n\
-                 if not \"%COMMIT_SOURCE%\"==\"\" exit /b 0\n\n\
-                 rem Find repository root and check if Cascade is initialized\n\
-                 for /f \"tokens=*\" %%i in ('git rev-parse --show-toplevel 2^>nul') do set REPO_ROOT=%%i\n\
-                 if \"%REPO_ROOT%\"==\"\" set REPO_ROOT=.\n\
-                 if not exist \"%REPO_ROOT%\\.cascade\" exit /b 0\n\n\
-                 rem Check for active stack\n\
-                 for /f \"tokens=*\" %%i in ('\"{cascade_cli}\" stack list --active --format=name 2^>nul') do set ACTIVE_STACK=%%i\n\n\
-                 if not \"%ACTIVE_STACK%\"==\"\" (\n\
-                     rem Get current commit message\n\
-                     set /p CURRENT_MSG=<%COMMIT_MSG_FILE%\n\n\
-                     rem Skip if message already has stack context\n\
-                     echo !CURRENT_MSG! | findstr \"[stack:\" >nul\n\
-                     if %ERRORLEVEL% equ 0 exit /b 0\n\n\
-                     rem Add stack context to commit message\n\
-                     echo.\n\
-                     echo # Stack: %ACTIVE_STACK%\n\
-                     echo # This commit will be added to the active stack automatically.\n\
-                     echo # Use 'ca stack status' to see the current stack state.\n\
-                     type \"%COMMIT_MSG_FILE%\"\n\
-                 ) > \"%COMMIT_MSG_FILE%.tmp\"\n\
-                 move \"%COMMIT_MSG_FILE%.tmp\" \"%COMMIT_MSG_FILE%\"\n"
-            )
+        // Never chain back into ourselves
+        if hooks_dir == self.get_cascade_hooks_dir()? {
+            return Ok(None);
         }
 
-        #[cfg(not(windows))]
-        {
-            format!(
-                "#!/bin/sh\n\
-                 # Cascade CLI Hook - Prepare Commit Message\n\
-                 # Adds stack context to commit messages\n\n\
-                 set -e\n\n\
-                 COMMIT_MSG_FILE=\"$1\"\n\
-                 COMMIT_SOURCE=\"$2\"\n\
-                 COMMIT_SHA=\"$3\"\n\n\
-                 # Skip if user provided message via -m flag, merge commit, etc.\n\
-                 if [ \"$COMMIT_SOURCE\" != \"\" ]; then\n\
-                     exit 0\n\
-                 fi\n\n\
-                 # Find repository root and check if Cascade is initialized\n\
-                 REPO_ROOT=$(git rev-parse --show-toplevel 2>/dev/null || echo \".\")\n\
-                 if [ ! -d \"$REPO_ROOT/.cascade\" ]; then\n\
-                     exit 0\n\
-                 fi\n\n\
-                 # Check for active stack\n\
-                 ACTIVE_STACK=$(\"{cascade_cli}\" stack list --active --format=name 2>/dev/null || echo \"\")\n\
-                 \n\
-                 if [ -n \"$ACTIVE_STACK\" ]; then\n\
-                     # Get current commit message\n\
-                     CURRENT_MSG=$(cat \"$COMMIT_MSG_FILE\")\n\
-                     \n\
-                     # Skip if message already has stack context\n\
-                     if echo \"$CURRENT_MSG\" | grep -q \"\\[stack:\"; then\n\
-                         exit 0\n\
-                     fi\n\
-                     \n\
-                     # Add stack context to commit message\n\
-                     echo \"\n\
-                 # Stack: $ACTIVE_STACK\n\
-                 # This commit will be added to the active stack automatically.\n\
-                 # Use 'ca stack status' to see the current stack state.\n\
-                 $CURRENT_MSG\" > \"$COMMIT_MSG_FILE\"\n\
-                 fi\n"
-            )
-        }
+        let hook = hooks_dir.join(hook_type.name());
+        // Git for Windows runs extension-less hooks through sh, so any file counts there
+        let runnable = if cfg!(windows) {
+            hook.is_file()
+        } else {
+            platform::is_executable(&hook)
+        };
+        Ok(runnable.then_some(hook))
     }
 
     /// Detect repository type from remote URLs
@@ -1450,14 +978,14 @@ exit 0
             Output::sub_item(format!("{}: {}", hook.filename(), hook.description()));
         }
 
-        println!();
+        Output::spacing();
         Output::section("These hooks will automatically");
         Output::bullet("Add commits to your active stack");
         Output::bullet("Validate commit messages");
         Output::bullet("Prevent force pushes that break stack integrity");
         Output::bullet("Add stack context to commit messages");
 
-        println!();
+        Output::spacing();
         Output::section("With hooks + new defaults, your workflow becomes");
         Output::sub_item("git commit       → Auto-added to stack");
         Output::sub_item("ca push          → Pushes all by default");
@@ -1465,11 +993,7 @@ exit 0
         Output::sub_item("ca autoland      → Auto-merges when ready");
 
         // Interactive confirmation to proceed with installation
-        let should_install = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Install Cascade hooks?")
-            .default(true)
-            .interact()
-            .map_err(|e| CascadeError::config(format!("Failed to get user confirmation: {e}")))?;
+        let should_install = prompter().confirm("Install Cascade hooks?", true)?;
 
         if should_install {
             Output::success("Proceeding with installation");
@@ -1561,18 +1085,7 @@ pub async fn install_hook_with_options(
 
     let hooks_manager = HooksManager::new(&repo_root)?;
 
-    let hook_type = match hook_name {
-        "post-commit" => HookType::PostCommit,
-        "pre-push" => HookType::PrePush,
-        "commit-msg" => HookType::CommitMsg,
-        "pre-commit" => HookType::PreCommit,
-        "prepare-commit-msg" => HookType::PrepareCommitMsg,
-        _ => {
-            return Err(CascadeError::config(format!(
-                "Unknown hook type: {hook_name}"
-            )))
-        }
-    };
+    let hook_type: HookType = hook_name.parse()?;
 
     // Run basic validation if not skipped
     if !skip_checks && !force {
@@ -1591,20 +1104,251 @@ pub async fn uninstall_hook(hook_name: &str) -> Result<()> {
 
     let hooks_manager = HooksManager::new(&repo_root)?;
 
-    let hook_type = match hook_name {
-        "post-commit" => HookType::PostCommit,
-        "pre-push" => HookType::PrePush,
-        "commit-msg" => HookType::CommitMsg,
-        "pre-commit" => HookType::PreCommit,
-        "prepare-commit-msg" => HookType::PrepareCommitMsg,
-        _ => {
-            return Err(CascadeError::config(format!(
-                "Unknown hook type: {hook_name}"
-            )))
+    let hook_type: HookType = hook_name.parse()?;
+
+    hooks_manager.uninstall_hook(&hook_type)
+}
+
+/// What the post-commit hook should do with a freshly created commit
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommitTrackingDecision {
+    /// Append the commit (and any earlier unpushed commits) to the active stack
+    Track,
+    /// There is no active stack to append to
+    NoActiveStack,
+    /// Leave the commit alone
+    Skip(String),
+}
+
+impl CommitTrackingDecision {
+    /// Token printed for the hook script to branch on
+    pub fn as_token(&self) -> &'static str {
+        match self {
+            CommitTrackingDecision::Track => "track",
+            CommitTrackingDecision::NoActiveStack => "no-active-stack",
+            CommitTrackingDecision::Skip(_) => "skip",
         }
+    }
+}
+
+/// Decide whether a new commit should be appended to the active stack automatically.
+///
+/// Only commits made on the active stack's working branch that build on top of its
+/// last entry are tracked; base branch commits, amended/rewritten history, and commits
+/// made while an entry is being edited or a git operation is in progress are skipped.
+pub fn decide_commit_tracking(repo_root: &Path, commit: &str) -> Result<CommitTrackingDecision> {
+    let config_path = crate::config::get_repo_config_dir(repo_root)?.join("config.json");
+    let enabled = Settings::load_from_file(&config_path)
+        .map(|settings| settings.cascade.auto_track_commits)
+        .unwrap_or(true);
+    if !enabled {
+        return Ok(CommitTrackingDecision::Skip(
+            "cascade.auto_track_commits is disabled".to_string(),
+        ));
+    }
+
+    let manager = crate::stack::StackManager::new(repo_root)?;
+    let repo = manager.git_repo();
+
+    if repo.has_operation_in_progress() {
+        return Ok(CommitTrackingDecision::Skip(
+            "a merge, rebase or cherry-pick is in progress".to_string(),
+        ));
+    }
+    if manager.is_in_edit_mode() {
+        return Ok(CommitTrackingDecision::Skip(
+            "an entry is being edited".to_string(),
+        ));
+    }
+
+    let Some(stack) = manager.get_active_stack() else {
+        return Ok(CommitTrackingDecision::NoActiveStack);
     };
 
-    hooks_manager.uninstall_hook(&hook_type)
+    let Ok(current_branch) = repo.get_current_branch() else {
+        return Ok(CommitTrackingDecision::Skip("HEAD is detached".to_string()));
+    };
+    if current_branch == stack.base_branch {
+        return Ok(CommitTrackingDecision::Skip(format!(
+            "'{current_branch}' is the stack's base branch"
+        )));
+    }
+    // A commit on the branch of an entry below the top gets moved onto the working branch
+    // by `ca push`
+    if stack.lower_entry_index(&current_branch).is_some()
+        && stack
+            .working_branch
+            .as_deref()
+            .is_some_and(|working| working != current_branch)
+    {
+        return Ok(CommitTrackingDecision::Track);
+    }
+    if stack.working_branch.as_deref() != Some(current_branch.as_str()) {
+        return Ok(CommitTrackingDecision::Skip(format!(
+            "'{current_branch}' is not the working branch of stack '{}'",
+            stack.name
+        )));
+    }
+
+    if stack
+        .entries
+        .iter()
+        .any(|entry| entry.commit_hash == commit)
+    {
+        return Ok(CommitTrackingDecision::Skip(
+            "commit is already in the stack".to_string(),
+        ));
+    }
+    if let Some(last) = stack.entries.last() {
+        if !repo.is_descendant_of(commit, &last.commit_hash)? {
+            return Ok(CommitTrackingDecision::Skip(
+                "commit does not build on the top of the stack".to_string(),
+            ));
+        }
+    }
+
+    Ok(CommitTrackingDecision::Track)
+}
+
+/// Print the tracking decision for a commit (used by the post-commit hook)
+pub async fn should_track(commit: &str) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let decision = decide_commit_tracking(&repo_root, commit)?;
+    if let CommitTrackingDecision::Skip(reason) = &decision {
+        debug!("Not tracking commit {}: {}", commit, reason);
+    }
+    Output::data(decision.as_token());
+    Ok(())
+}
+
+/// A pushed stack branch whose tip no longer matches the stack metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct DivergedPush {
+    pub stack_name: String,
+    pub branch: String,
+    pub expected_commit: String,
+    pub pushed_commit: String,
+}
+
+/// Find stack-managed branches in a pre-push ref list that have diverged from their entries.
+///
+/// `refs` is the pre-push hook's stdin: one `<local ref> <local sha> <remote ref> <remote sha>`
+/// line per ref being pushed. Deletions and branches that don't belong to a stack are ignored.
+pub fn find_diverged_pushes(repo_root: &Path, refs: &str) -> Result<Vec<DivergedPush>> {
+    let manager = crate::stack::StackManager::new(repo_root)?;
+    let mut diverged = Vec::new();
+
+    for line in refs.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(local_ref), Some(local_sha)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let Some(branch) = local_ref.strip_prefix("refs/heads/") else {
+            continue;
+        };
+        if local_sha.chars().all(|c| c == '0') {
+            continue;
+        }
+
+        for stack in manager.get_all_stacks() {
+            let Some(entry) = stack
+                .entries
+                .iter()
+                .find(|entry| entry.branch == branch && !entry.is_merged)
+            else {
+                continue;
+            };
+            if entry.commit_hash != local_sha {
+                diverged.push(DivergedPush {
+                    stack_name: stack.name.clone(),
+                    branch: branch.to_string(),
+                    expected_commit: entry.commit_hash.clone(),
+                    pushed_commit: local_sha.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(diverged)
+}
+
+/// Branches in a pre-push ref list whose remote tip is not an ancestor of what's being
+/// pushed, i.e. pushes that only succeed when forced.
+///
+/// Remote tips that aren't available locally can't be checked and are left to the server,
+/// which rejects them unless the push is forced.
+pub fn find_forced_pushes(repo_root: &Path, refs: &str) -> Result<Vec<String>> {
+    let repo = crate::git::GitRepository::open(repo_root)?;
+    let mut forced = Vec::new();
+
+    for line in refs.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [_, local_sha, remote_ref, remote_sha] = fields[..] else {
+            continue;
+        };
+        let is_zero = |sha: &str| sha.chars().all(|c| c == '0');
+        // New branches and deletions rewrite nothing
+        if is_zero(local_sha) || is_zero(remote_sha) || local_sha == remote_sha {
+            continue;
+        }
+        if repo.get_commit(remote_sha).is_err() {
+            continue;
+        }
+        if !repo.is_descendant_of(local_sha, remote_sha)? {
+            let branch = remote_ref.strip_prefix("refs/heads/").unwrap_or(remote_ref);
+            forced.push(branch.to_string());
+        }
+    }
+
+    Ok(forced)
+}
+
+/// Block pushes of stack branches that have diverged from stack metadata (used by the pre-push hook)
+pub async fn check_push() -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let refs = std::io::read_to_string(std::io::stdin())
+        .map_err(|e| CascadeError::config(format!("Could not read pushed refs: {e}")))?;
+
+    check_pushed_refs(&repo_root, &refs)
+}
+
+/// Report and reject stack branches in a pre-push ref list that diverged from stack metadata
+pub fn check_pushed_refs(repo_root: &Path, refs: &str) -> Result<()> {
+    let diverged = find_diverged_pushes(repo_root, refs)?;
+    if diverged.is_empty() {
+        return Ok(());
+    }
+
+    Output::error("Push blocked: stack branches have diverged from stack metadata");
+    for push in &diverged {
+        Output::sub_item(format!(
+            "'{}' (stack '{}'): expected {}, pushing {}",
+            push.branch,
+            push.stack_name,
+            &push.expected_commit[..push.expected_commit.len().min(8)],
+            &push.pushed_commit[..push.pushed_commit.len().min(8)]
+        ));
+    }
+    Output::tip(
+        "This usually means the branch was rebased or amended outside Cascade. \
+         Run 'ca validate <stack> --fix incorporate' to adopt the new commits \
+         (or '--fix reset' to discard them), then push again",
+    );
+
+    Err(CascadeError::validation(format!(
+        "{} stack branch(es) diverged from stack metadata",
+        diverged.len()
+    )))
 }
 
 #[cfg(test)]
@@ -1753,9 +1497,14 @@ mod tests {
 
         #[cfg(windows)]
         {
-            // On Windows, verify it has .bat extension and file exists
-            assert!(hook_filename.ends_with(".bat"));
-            assert!(hook_path.exists());
+            // Git runs the extension-less shim, which hands over to the CRLF .cmd hook
+            assert!(hook_filename.ends_with(".cmd"));
+            let content = std::fs::read_to_string(&hook_path).unwrap();
+            assert!(content.contains("\r\n"));
+            assert!(!content.replace("\r\n", "").contains('\n'));
+            let shim = std::fs::read_to_string(cascade_hooks_dir.join(hook_type.name())).unwrap();
+            assert!(shim.starts_with("#!/bin/sh\n"));
+            assert!(!shim.contains('\r'));
         }
     }
 
@@ -1821,63 +1570,45 @@ mod tests {
         let (_temp_dir, repo_path) = create_test_repo();
         let manager = HooksManager::new(&repo_path).unwrap();
 
-        // Use a known binary name for testing
-        let binary_name = "cascade-cli";
-
-        // Test post-commit hook generation
-        let post_commit_content = manager.generate_post_commit_hook(binary_name);
-        #[cfg(windows)]
-        {
-            assert!(post_commit_content.contains("@echo off"));
-            assert!(post_commit_content.contains("rem Cascade CLI Hook"));
-        }
-        #[cfg(not(windows))]
-        {
-            assert!(post_commit_content.contains("#!/bin/sh"));
-            assert!(post_commit_content.contains("# Cascade CLI Hook"));
+        for hook_type in HookType::ALL {
+            let content = manager.generate_hook_script(&hook_type).unwrap();
+            #[cfg(windows)]
+            {
+                assert!(content.starts_with("@echo off"));
+                assert!(content.contains(&format!("hook-exec {} %*", hook_type.name())));
+            }
+            #[cfg(not(windows))]
+            {
+                assert!(content.starts_with("#!/bin/sh\n"));
+                assert!(content.contains(&format!("hook-exec {} \"$@\"", hook_type.name())));
+                assert!(!content.contains("bash"));
+            }
+            assert!(content.contains(env!("CARGO_PKG_VERSION")));
         }
-        assert!(post_commit_content.contains(binary_name));
 
-        // Test pre-push hook generation
-        let pre_push_content = manager.generate_pre_push_hook(binary_name);
-        #[cfg(windows)]
-        {
-            assert!(pre_push_content.contains("@echo off"));
-            assert!(pre_push_content.contains("rem Cascade CLI Hook"));
-        }
-        #[cfg(not(windows))]
-        {
-            assert!(pre_push_content.contains("#!/bin/sh"));
-            assert!(pre_push_content.contains("# Cascade CLI Hook"));
+        // Hook names round-trip, so `ca hook-exec <name>` reaches the right logic
+        for hook_type in HookType::ALL {
+            let parsed: HookType = hook_type.name().parse().unwrap();
+            assert_eq!(parsed.name(), hook_type.name());
         }
-        assert!(pre_push_content.contains(binary_name));
+        assert!("post-merge".parse::<HookType>().is_err());
+    }
 
-        // Test commit-msg hook generation (doesn't use binary, just validates)
-        let commit_msg_content = manager.generate_commit_msg_hook(binary_name);
-        #[cfg(windows)]
-        {
-            assert!(commit_msg_content.contains("@echo off"));
-            assert!(commit_msg_content.contains("rem Cascade CLI Hook"));
-        }
-        #[cfg(not(windows))]
-        {
-            assert!(commit_msg_content.contains("#!/bin/sh"));
-            assert!(commit_msg_content.contains("# Cascade CLI Hook"));
-        }
+    #[test]
+    fn test_original_hook_lookup() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let manager = HooksManager::new(&repo_path).unwrap();
+        let hook_path = repo_path.join(".git/hooks/pre-push");
+        std::fs::write(&hook_path, "#!/bin/sh\nexit 0\n").unwrap();
+        crate::utils::platform::make_executable(&hook_path).unwrap();
 
-        // Test prepare-commit-msg hook generation (does use binary)
-        let prepare_commit_content = manager.generate_prepare_commit_msg_hook(binary_name);
-        #[cfg(windows)]
-        {
-            assert!(prepare_commit_content.contains("@echo off"));
-            assert!(prepare_commit_content.contains("rem Cascade CLI Hook"));
-        }
-        #[cfg(not(windows))]
-        {
-            assert!(prepare_commit_content.contains("#!/bin/sh"));
-            assert!(prepare_commit_content.contains("# Cascade CLI Hook"));
-        }
-        assert!(prepare_commit_content.contains(binary_name));
+        manager.install_hook(&HookType::PrePush).unwrap();
+        assert_eq!(
+            manager.original_hook(&HookType::PrePush).unwrap(),
+            Some(hook_path)
+        );
+        assert_eq!(manager.original_hook(&HookType::CommitMsg).unwrap(), None);
+        manager.uninstall_all().unwrap();
     }
 
     #[test]
@@ -1932,8 +1663,163 @@ mod tests {
         let original_content = std::fs::read_to_string(&hook_path).unwrap();
         assert!(original_content.contains("existing hook"));
 
-        // Cascade hook should contain cascade logic
+        // Cascade hook should hand over to the Cascade binary
         let cascade_content = std::fs::read_to_string(&cascade_hook_path).unwrap();
-        assert!(cascade_content.contains("cascade-cli") || cascade_content.contains("ca"));
+        assert!(cascade_content.contains("hook-exec post-commit"));
+    }
+
+    #[test]
+    fn test_decide_commit_tracking() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let commit_file = |name: &str| {
+            std::fs::write(repo_path.join(name), name).unwrap();
+            git(&["add", name]);
+            git(&["commit", "-m", name]);
+            git(&["rev-parse", "HEAD"])
+        };
+
+        let base_branch = git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+        let base_commit = git(&["rev-parse", "HEAD"]);
+        assert_eq!(
+            decide_commit_tracking(&repo_path, &base_commit).unwrap(),
+            CommitTrackingDecision::NoActiveStack
+        );
+
+        git(&["checkout", "-b", "feature-work"]);
+        let mut stack_manager = crate::stack::StackManager::new(&repo_path).unwrap();
+        stack_manager
+            .create_stack("feature".to_string(), Some(base_branch.clone()), None)
+            .unwrap();
+
+        let commit = commit_file("a.txt");
+        assert_eq!(
+            decide_commit_tracking(&repo_path, &commit).unwrap(),
+            CommitTrackingDecision::Track
+        );
+
+        // Commits on the base branch are never picked up
+        git(&["checkout", &base_branch]);
+        let base_only = commit_file("b.txt");
+        assert_ne!(
+            decide_commit_tracking(&repo_path, &base_only).unwrap(),
+            CommitTrackingDecision::Track
+        );
+
+        // The setting turns tracking off entirely
+        git(&["checkout", "feature-work"]);
+        let config_path = crate::config::get_repo_config_dir(&repo_path)
+            .unwrap()
+            .join("config.json");
+        let mut settings = Settings::load_from_file(&config_path).unwrap();
+        settings
+            .set_value("cascade.auto_track_commits", "false")
+            .unwrap();
+        settings.save_to_file(&config_path).unwrap();
+        assert!(matches!(
+            decide_commit_tracking(&repo_path, &commit).unwrap(),
+            CommitTrackingDecision::Skip(_)
+        ));
+    }
+
+    #[test]
+    fn test_find_diverged_pushes() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        let base_branch = git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+        git(&["checkout", "-b", "feature-work"]);
+        std::fs::write(repo_path.join("a.txt"), "a").unwrap();
+        git(&["add", "a.txt"]);
+        git(&["commit", "-m", "Add a"]);
+        let entry_commit = git(&["rev-parse", "HEAD"]);
+        git(&["branch", "entry-a"]);
+
+        let mut stack_manager = crate::stack::StackManager::new(&repo_path).unwrap();
+        stack_manager
+            .create_stack("feature".to_string(), Some(base_branch), None)
+            .unwrap();
+        stack_manager
+            .push_to_stack(
+                "entry-a".to_string(),
+                entry_commit.clone(),
+                "Add a".to_string(),
+                "feature-work".to_string(),
+            )
+            .unwrap();
+
+        let zero = "0".repeat(40);
+        let other = "1".repeat(40);
+
+        // Matching tips, unrelated branches and deletions pass
+        let refs = format!(
+            "refs/heads/entry-a {entry_commit} refs/heads/entry-a {zero}\n\
+             refs/heads/unrelated {other} refs/heads/unrelated {zero}\n\
+             (delete) {zero} refs/heads/entry-a {entry_commit}\n"
+        );
+        assert!(find_diverged_pushes(&repo_path, &refs).unwrap().is_empty());
+
+        // A rewritten entry branch is reported
+        let refs = format!("refs/heads/entry-a {other} refs/heads/entry-a {entry_commit}\n");
+        let diverged = find_diverged_pushes(&repo_path, &refs).unwrap();
+        assert_eq!(diverged.len(), 1);
+        assert_eq!(diverged[0].branch, "entry-a");
+        assert_eq!(diverged[0].stack_name, "feature");
+        assert_eq!(diverged[0].expected_commit, entry_commit);
+        assert_eq!(diverged[0].pushed_commit, other);
+    }
+
+    #[test]
+    fn test_find_forced_pushes() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        let base = git(&["rev-parse", "HEAD"]);
+        git(&["commit", "--allow-empty", "-m", "Second"]);
+        let second = git(&["rev-parse", "HEAD"]);
+        git(&[
+            "commit",
+            "--amend",
+            "--allow-empty",
+            "-m",
+            "Second, reworded",
+        ]);
+        let rewritten = git(&["rev-parse", "HEAD"]);
+        let zero = "0".repeat(40);
+        let unknown = "1".repeat(40);
+
+        // Fast-forwards, new branches, deletions and unknown remote tips pass
+        let refs = format!(
+            "refs/heads/a {second} refs/heads/a {base}\n\
+             refs/heads/b {second} refs/heads/b {zero}\n\
+             (delete) {zero} refs/heads/c {second}\n\
+             refs/heads/d {second} refs/heads/d {unknown}\n"
+        );
+        assert!(find_forced_pushes(&repo_path, &refs).unwrap().is_empty());
+
+        // Replacing the remote tip with a rewritten commit needs a force push
+        let refs = format!("refs/heads/a {rewritten} refs/heads/a {second}\n");
+        assert_eq!(find_forced_pushes(&repo_path, &refs).unwrap(), vec!["a"]);
     }
 }
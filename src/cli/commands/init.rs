@@ -44,7 +44,7 @@ pub async fn run(bitbucket_url: Option<String>, force: bool) -> Result<()> {
         Output::sub_item(format!("Bitbucket Server URL: {url}"));
     }
 
-    println!();
+    Output::spacing();
     Output::section("Next steps");
     Output::bullet("Configure Bitbucket Server settings:");
     if bitbucket_url.is_none() {
@@ -0,0 +1,124 @@
+use crate::cli::output::Output;
+use crate::config::Settings;
+use crate::errors::{CascadeError, Result};
+use crate::git::find_repository_root;
+use crate::utils::logging;
+use chrono::Utc;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::env;
+use std::path::PathBuf;
+
+/// Print log entries, oldest first
+pub async fn show(tail: Option<usize>, since: Option<String>) -> Result<()> {
+    let log_dir = logging::log_dir(&repo_root()?)?;
+    let since = since
+        .map(|since| logging::parse_since(&since, Utc::now()))
+        .transpose()?;
+
+    let records = logging::read_records(&log_dir, since)?;
+    if records.is_empty() {
+        Output::info(format!("No log entries in {}", log_dir.display()));
+        return Ok(());
+    }
+
+    let skip = tail.map_or(0, |tail| records.len().saturating_sub(tail));
+    for record in &records[skip..] {
+        Output::line(record.render());
+    }
+    Ok(())
+}
+
+/// Write a redacted support archive of the logs, config and environment
+pub async fn bundle(output: Option<PathBuf>) -> Result<()> {
+    let repo_root = repo_root()?;
+    let log_dir = logging::log_dir(&repo_root)?;
+
+    let output = output.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "cascade-support-{}.tar.gz",
+            Utc::now().format("%Y%m%d-%H%M%S")
+        ))
+    });
+    let file = std::fs::File::create(&output)
+        .map_err(|e| CascadeError::config(format!("Failed to create {}: {e}", output.display())))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let log_files = logging::log_files(&log_dir);
+    for path in &log_files {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            CascadeError::config(format!("Failed to read log {}: {e}", path.display()))
+        })?;
+        let redacted: String = contents
+            .lines()
+            .map(|line| logging::redact_line(line) + "\n")
+            .collect();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        append(&mut archive, &format!("logs/{name}"), &redacted)?;
+    }
+
+    let config_path = crate::config::get_repo_config_dir(&repo_root)?.join("config.json");
+    if let Ok(settings) = Settings::load_from_file(&config_path) {
+        let mut config = serde_json::to_value(&settings)
+            .map_err(|e| CascadeError::config(format!("Failed to serialize config: {e}")))?;
+        logging::redact_value(&mut config);
+        let config = serde_json::to_string_pretty(&config)
+            .map_err(|e| CascadeError::config(format!("Failed to serialize config: {e}")))?;
+        append(&mut archive, "config.json", &config)?;
+    }
+
+    append(&mut archive, "environment.txt", &environment_report())?;
+
+    archive
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| CascadeError::config(format!("Failed to write {}: {e}", output.display())))?;
+
+    Output::success(format!("Support bundle written to {}", output.display()));
+    Output::sub_item(format!(
+        "{} log file(s), config and environment; tokens and passwords are redacted",
+        log_files.len()
+    ));
+    Output::tip("Review the archive before sharing it");
+    Ok(())
+}
+
+fn repo_root() -> Result<PathBuf> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))
+}
+
+fn append<W: std::io::Write>(
+    archive: &mut tar::Builder<W>,
+    name: &str,
+    contents: &str,
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    archive
+        .append_data(&mut header, name, contents.as_bytes())
+        .map_err(|e| CascadeError::config(format!("Failed to add {name} to bundle: {e}")))
+}
+
+fn environment_report() -> String {
+    let git_version = std::process::Command::new("git")
+        .arg("--version")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unavailable".to_string());
+
+    format!(
+        "cascade: {}\nos: {}\narch: {}\ngit: {}\ncreated: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        git_version,
+        Utc::now().to_rfc3339()
+    )
+}
@@ -1,18 +1,41 @@
+pub mod approvals;
+pub mod auth;
+pub mod backups;
+pub mod browse;
 pub mod cleanup;
+pub mod commit;
 pub mod completions;
 pub mod config;
 pub mod conflicts;
 pub mod diagnose;
 pub mod doctor;
 pub mod entry;
+pub mod gc;
+pub mod hook_exec;
 pub mod hooks;
 pub mod init;
+pub mod logs;
+pub mod nudge;
+pub mod perf;
+pub mod pick;
+pub mod plugin;
+pub mod prompt;
+pub mod prs;
+pub mod push;
+pub mod query;
+pub mod recover;
+pub mod search;
 pub mod setup;
+pub mod snapshots;
 pub mod stack;
+pub mod stats;
 pub mod status;
 pub mod tui;
+pub mod tutorial;
+pub mod update;
 pub mod version;
 pub mod viz;
+pub mod whoami;
 
 // Re-export commonly used types for CLI
 pub use stack::{MergeStrategyArg, RebaseStrategyArg};
@@ -0,0 +1,257 @@
+use crate::bitbucket::pull_request::{ParticipantStatus, PullRequest, PullRequestManager};
+use crate::cli::output::Output;
+use crate::config::Settings;
+use crate::errors::{CascadeError, Result};
+use crate::git::find_repository_root;
+use crate::stack::StackManager;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+const STATE_FILE: &str = "CASCADE_NUDGES";
+
+/// When each pull request was last nudged, so reminders keep a polite distance
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct NudgeLog {
+    /// By pull request ID
+    nudged: HashMap<String, DateTime<Utc>>,
+}
+
+impl NudgeLog {
+    fn state_path(repo_root: &Path) -> Result<PathBuf> {
+        Ok(crate::git::resolve_git_dir(repo_root)?.join(STATE_FILE))
+    }
+
+    /// Load the log; a missing or unreadable file is treated as empty
+    fn load(repo_root: &Path) -> Self {
+        Self::state_path(repo_root)
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, repo_root: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| CascadeError::config(format!("Failed to serialize nudge log: {e}")))?;
+        std::fs::write(Self::state_path(repo_root)?, json)
+            .map_err(|e| CascadeError::config(format!("Failed to write nudge log: {e}")))
+    }
+
+    /// When the pull request was nudged, if that was less than `cooldown` before `now`
+    fn cooling_down(
+        &self,
+        pr_id: &str,
+        now: DateTime<Utc>,
+        cooldown: Duration,
+    ) -> Option<DateTime<Utc>> {
+        self.nudged
+            .get(pr_id)
+            .copied()
+            .filter(|at| now - *at < cooldown)
+    }
+}
+
+/// The reminder comment, with the template's placeholders filled in
+fn render_message(
+    template: &str,
+    reviewers: &[String],
+    idle: Duration,
+    title: &str,
+    stack: &str,
+) -> String {
+    let mentions: Vec<String> = reviewers.iter().map(|name| format!("@{name}")).collect();
+    template
+        .replace("{reviewers}", &mentions.join(", "))
+        .replace("{age}", &super::viz::format_span(idle))
+        .replace("{title}", title)
+        .replace("{stack}", stack)
+}
+
+/// Reviewers who haven't reviewed yet; those who asked for changes wait on the author
+fn pending_reviewers(pr: &PullRequest) -> Vec<String> {
+    pr.reviewers
+        .iter()
+        .filter(|r| r.status == ParticipantStatus::Unapproved)
+        .map(|r| r.user.name.clone())
+        .collect()
+}
+
+/// Remind the reviewers of stack pull requests that have had no activity for a while,
+/// with a comment or by asking them to review again
+pub async fn run(
+    older_than: Option<String>,
+    all: bool,
+    rerequest: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+    let config_dir = crate::config::get_repo_config_dir(&repo_root)?;
+    let settings = Settings::load_from_file(&config_dir.join("config.json"))?;
+    let nudge = &settings.cascade.nudge;
+
+    let older_than = older_than.unwrap_or_else(|| nudge.older_than.clone());
+    let threshold = crate::utils::logging::parse_duration(&older_than).ok_or_else(|| {
+        CascadeError::validation(format!("Invalid --older-than '{older_than}'"))
+            .with_hint("Use e.g. 36h, 2d or 1w")
+    })?;
+    let cooldown = Duration::hours(nudge.cooldown_hours.into());
+
+    let manager = StackManager::new(&repo_root)?;
+    let stacks = if all {
+        manager.get_all_stacks_objects()?
+    } else {
+        vec![manager
+            .get_active_stack()
+            .cloned()
+            .ok_or_else(CascadeError::no_active_stack)?]
+    };
+    // (stack, entry position, PR ID) of every unmerged submitted entry
+    let submitted: Vec<(String, usize, u64)> = stacks
+        .iter()
+        .flat_map(|stack| {
+            stack
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| !entry.is_merged)
+                .filter_map(|(index, entry)| {
+                    let pr_id = entry.pull_request_id.as_deref()?.parse().ok()?;
+                    Some((stack.name.clone(), index + 1, pr_id))
+                })
+        })
+        .collect();
+    if submitted.is_empty() {
+        Output::info("No open pull requests to nudge");
+        return Ok(());
+    }
+
+    let pr_manager = PullRequestManager::from_settings(&settings, &repo_root)?;
+    let mut log = NudgeLog::load(&repo_root);
+    let now = Utc::now();
+    let mut nudged = 0;
+    let mut stale = 0;
+    let mut cooling = 0;
+
+    for (stack, position, pr_id) in submitted {
+        let pr = pr_manager.get_pull_request(pr_id).await?;
+        if !pr.is_open() {
+            continue;
+        }
+        // Comments and approvals count as activity too, not only updates to the PR
+        let last_activity = pr_manager
+            .get_pull_request_activities(pr_id)
+            .await
+            .ok()
+            .and_then(|activities| activities.last().map(|a| a.created_at()))
+            .map_or(pr.updated_at(), |at| at.max(pr.updated_at()));
+        let idle = now - last_activity;
+        if idle < threshold {
+            continue;
+        }
+        stale += 1;
+
+        let label = format!(
+            "PR #{pr_id} ({stack} #{position}), quiet for {}",
+            super::viz::format_span(idle)
+        );
+        let reviewers = pending_reviewers(&pr);
+        if reviewers.is_empty() {
+            Output::sub_item(format!("{label}: no reviews pending, skipped"));
+            continue;
+        }
+        if let Some(at) = log.cooling_down(&pr_id.to_string(), now, cooldown) {
+            Output::sub_item(format!(
+                "{label}: nudged {} ago, skipped",
+                super::viz::format_span(now - at)
+            ));
+            cooling += 1;
+            continue;
+        }
+
+        let mentions = reviewers.join(", ");
+        if dry_run {
+            Output::sub_item(format!("{label}: would remind {mentions}"));
+            continue;
+        }
+        let result = if rerequest {
+            pr_manager
+                .rerequest_review(pr_id, &reviewers)
+                .await
+                .map(|_| ())
+        } else {
+            let message = render_message(nudge.template(), &reviewers, idle, &pr.title, &stack);
+            pr_manager.add_comment(pr_id, &message).await
+        };
+        match result {
+            Ok(()) => {
+                Output::success(format!("{label}: reminded {mentions}"));
+                log.nudged.insert(pr_id.to_string(), now);
+                nudged += 1;
+            }
+            Err(e) => Output::warning(format!("{label}: could not nudge: {e}")),
+        }
+    }
+
+    if nudged > 0 {
+        log.save(&repo_root)?;
+    }
+    Output::spacing();
+    if stale == 0 {
+        Output::info(format!(
+            "No open pull request has been quiet for {older_than}"
+        ));
+    } else if !dry_run {
+        Output::info(format!(
+            "Nudged {nudged} of {stale} quiet pull request{}",
+            if stale == 1 { "" } else { "s" }
+        ));
+        if cooling > 0 {
+            Output::tip(format!(
+                "Each pull request is nudged at most once every {}h (nudge.cooldown_hours)",
+                nudge.cooldown_hours
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NudgeSettings;
+
+    #[test]
+    fn test_render_message_and_cooldown() {
+        let message = render_message(
+            NudgeSettings::DEFAULT_TEMPLATE,
+            &["alice".to_string(), "bob".to_string()],
+            Duration::hours(51),
+            "Add login",
+            "auth",
+        );
+        assert!(message.contains("no activity for 2d 3h"));
+        assert!(message.contains("@alice, @bob, could you"));
+
+        let custom = render_message(
+            "{stack}: '{title}' needs {reviewers}",
+            &["carol".to_string()],
+            Duration::days(3),
+            "Add login",
+            "auth",
+        );
+        assert_eq!(custom, "auth: 'Add login' needs @carol");
+
+        let now = Utc::now();
+        let mut log = NudgeLog::default();
+        log.nudged.insert("7".to_string(), now - Duration::hours(5));
+        assert!(log.cooling_down("7", now, Duration::hours(24)).is_some());
+        assert!(log.cooling_down("7", now, Duration::hours(4)).is_none());
+        assert!(log.cooling_down("8", now, Duration::hours(24)).is_none());
+    }
+}
@@ -0,0 +1,228 @@
+use crate::bitbucket::{PullRequestManager, PullRequestState};
+use crate::cli::output::Output;
+use crate::config::{get_repo_config_dir, ProviderKind, Settings};
+use crate::errors::{CascadeError, Result};
+use crate::git::{find_repository_root, GitRepository};
+use crate::stack::StackManager;
+use serde::Serialize;
+use std::env;
+use std::path::Path;
+use std::time::Instant;
+
+/// Everything `ca perf` reports, also printed as-is with `--json`
+#[derive(Debug, Serialize)]
+struct PerfReport {
+    version: &'static str,
+    os: &'static str,
+    /// Files tracked in the index
+    tracked_files: usize,
+    stacks: usize,
+    entries: usize,
+    iterations: usize,
+    timings: Vec<Timing>,
+}
+
+/// Wall-clock times of one operation over all iterations, in milliseconds
+#[derive(Debug, Serialize)]
+struct Timing {
+    name: &'static str,
+    median_ms: f64,
+    min_ms: f64,
+    max_ms: f64,
+    /// Why the operation wasn't timed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skipped: Option<String>,
+}
+
+impl Timing {
+    fn from_samples(name: &'static str, mut samples: Vec<f64>) -> Self {
+        samples.sort_by(f64::total_cmp);
+        Self {
+            name,
+            median_ms: samples[samples.len() / 2],
+            min_ms: samples[0],
+            max_ms: samples[samples.len() - 1],
+            skipped: None,
+        }
+    }
+
+    fn skipped(name: &'static str, reason: impl Into<String>) -> Self {
+        Self {
+            name,
+            median_ms: 0.0,
+            min_ms: 0.0,
+            max_ms: 0.0,
+            skipped: Some(reason.into()),
+        }
+    }
+}
+
+/// Time `operation` `iterations` times
+fn time<T>(
+    name: &'static str,
+    iterations: usize,
+    mut operation: impl FnMut() -> Result<T>,
+) -> Result<Timing> {
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let started = Instant::now();
+        operation()?;
+        samples.push(started.elapsed().as_secs_f64() * 1000.0);
+    }
+    Ok(Timing::from_samples(name, samples))
+}
+
+/// Time the operations most commands are made of, on this repository, and print a report
+/// to attach to performance bug reports. Nothing is sent anywhere.
+pub async fn run(iterations: usize, offline: bool, json: bool) -> Result<()> {
+    if iterations == 0 {
+        return Err(CascadeError::validation("--iterations must be at least 1"));
+    }
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    if !json {
+        Output::info(format!(
+            "Timing {iterations} iteration(s) of each operation..."
+        ));
+    }
+
+    let mut timings = vec![
+        time("Open repository", iterations, || {
+            GitRepository::open(&repo_root)
+        })?,
+        time("Load stacks", iterations, || StackManager::new(&repo_root))?,
+    ];
+
+    let repo = GitRepository::open(&repo_root)?;
+    timings.push(time("Status scan", iterations, || repo.is_dirty())?);
+    timings.push(time("Untracked files", iterations, || {
+        repo.get_untracked_files()
+    })?);
+
+    let manager = StackManager::new(&repo_root)?;
+    timings.push(match manager.get_active_stack() {
+        Some(stack) => match stack.entries.last() {
+            Some(top) => time("Stack commit walk", iterations, || {
+                repo.get_commits_between(&stack.base_branch, &top.commit_hash)
+                    .map(|commits| commits.len())
+            })?,
+            None => Timing::skipped("Stack commit walk", "active stack is empty"),
+        },
+        None => Timing::skipped("Stack commit walk", "no active stack"),
+    });
+
+    timings.push(time_provider(&repo_root, iterations, offline).await?);
+
+    let stacks = manager.get_all_stacks_objects()?;
+    let report = PerfReport {
+        version: env!("CARGO_PKG_VERSION"),
+        os: env::consts::OS,
+        tracked_files: git2::Repository::open(&repo_root)
+            .and_then(|repo| repo.index())
+            .map(|index| index.len())
+            .unwrap_or(0),
+        stacks: stacks.len(),
+        entries: stacks.iter().map(|stack| stack.entries.len()).sum(),
+        iterations,
+        timings,
+    };
+
+    if json {
+        Output::data(serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+/// One pull request listing per iteration against the configured provider
+async fn time_provider(repo_root: &Path, iterations: usize, offline: bool) -> Result<Timing> {
+    const NAME: &str = "Provider round-trip";
+    if offline {
+        return Ok(Timing::skipped(NAME, "--offline"));
+    }
+    let settings = match get_repo_config_dir(repo_root)
+        .and_then(|dir| Settings::load_from_file(&dir.join("config.json")))
+    {
+        Ok(settings) => settings,
+        Err(_) => return Ok(Timing::skipped(NAME, "Cascade is not initialized")),
+    };
+    if settings.bitbucket.provider == ProviderKind::Mock {
+        return Ok(Timing::skipped(NAME, "mock provider"));
+    }
+    let manager = match PullRequestManager::from_settings(&settings, repo_root) {
+        Ok(manager) => manager,
+        Err(e) => return Ok(Timing::skipped(NAME, e.to_string())),
+    };
+
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let started = Instant::now();
+        if let Err(e) = manager
+            .list_pull_requests(Some(PullRequestState::Open))
+            .await
+        {
+            return Ok(Timing::skipped(NAME, e.to_string()));
+        }
+        samples.push(started.elapsed().as_secs_f64() * 1000.0);
+    }
+    Ok(Timing::from_samples(NAME, samples))
+}
+
+fn print_report(report: &PerfReport) {
+    Output::section("Cascade performance report");
+    Output::sub_item(format!("Version: {} ({})", report.version, report.os));
+    Output::sub_item(format!(
+        "Repository: {} tracked files, {} stacks, {} entries",
+        report.tracked_files, report.stacks, report.entries
+    ));
+    Output::spacing();
+
+    let mut rows = vec![vec![
+        "Operation".to_string(),
+        "Median".to_string(),
+        "Min".to_string(),
+        "Max".to_string(),
+    ]];
+    for timing in &report.timings {
+        rows.push(match &timing.skipped {
+            Some(reason) => vec![
+                timing.name.to_string(),
+                "skipped".to_string(),
+                String::new(),
+                String::new(),
+                format!("({reason})"),
+            ],
+            None => vec![
+                timing.name.to_string(),
+                format!("{:.1} ms", timing.median_ms),
+                format!("{:.1} ms", timing.min_ms),
+                format!("{:.1} ms", timing.max_ms),
+            ],
+        });
+    }
+    Output::table(&rows);
+    Output::spacing();
+    Output::tip("Attach 'ca perf --json' to performance bug reports");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_summarizes_samples() {
+        let timing = Timing::from_samples("op", vec![30.0, 10.0, 20.0, 50.0, 40.0]);
+        assert_eq!(timing.median_ms, 30.0);
+        assert_eq!(timing.min_ms, 10.0);
+        assert_eq!(timing.max_ms, 50.0);
+
+        let json = serde_json::to_value(&timing).unwrap();
+        assert!(json.get("skipped").is_none());
+        let json = serde_json::to_value(Timing::skipped("op", "--offline")).unwrap();
+        assert_eq!(json["skipped"], "--offline");
+    }
+}
@@ -0,0 +1,275 @@
+use crate::cli::commands::push::PushSpec;
+use crate::cli::output::Output;
+use crate::errors::{CascadeError, Result};
+use crate::git::{find_repository_root, GitRepository};
+use crate::stack::{PickState, StackManager};
+use std::env;
+use std::path::Path;
+use std::process::Command;
+use uuid::Uuid;
+
+/// Options for `ca pick`
+#[derive(Debug, Clone, Default)]
+pub struct PickOptions {
+    /// Commit to cherry-pick (hash, branch or any other revision)
+    pub commit: Option<String>,
+    /// Branch name for the new entry
+    pub branch: Option<String>,
+    /// Finish a pick paused on conflicts
+    pub continue_pick: bool,
+    /// Give up a pick paused on conflicts
+    pub abort: bool,
+    /// Skip confirmation prompts
+    pub yes: bool,
+}
+
+/// Cherry-pick a commit onto the top of the active stack and add it as a new entry
+pub async fn run(options: PickOptions) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    if options.abort {
+        return abort_pick(&repo_root);
+    }
+    if options.continue_pick {
+        return continue_pick(&repo_root, options.yes).await;
+    }
+
+    let manager = StackManager::new(&repo_root)?;
+    let repo = GitRepository::open(&repo_root)?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
+
+    if PickState::load(&repo_root)?.is_some() {
+        return Err(CascadeError::validation(
+            "A pick is already in progress. Finish it with 'ca pick --continue' or 'ca pick --abort'",
+        ));
+    }
+    if repo.is_dirty()? {
+        return Err(CascadeError::branch(
+            "Working tree has uncommitted changes. Commit or stash them before picking",
+        ));
+    }
+    // Same rule as 'ca commit': the pick lands on the current branch, which must not be the
+    // stack's base unless the base follows the working branch
+    let current_branch = repo.get_current_branch()?;
+    let on_working_branch = stack.working_branch.as_deref() == Some(current_branch.as_str());
+    if current_branch == stack.base_branch && !on_working_branch {
+        return Err(CascadeError::config(format!(
+            "You're on the base branch '{current_branch}'. Switch to the stack's working branch first"
+        )));
+    }
+
+    let reference = options
+        .commit
+        .as_deref()
+        .ok_or_else(|| CascadeError::config("Commit to pick required"))?;
+    let source = repo.resolve_reference(reference)?;
+    let source_commit = source.id().to_string();
+    if source.parent_count() > 1 {
+        return Err(CascadeError::validation(format!(
+            "{} is a merge commit. Pick the commits it merged instead",
+            &source_commit[..8]
+        )));
+    }
+    if let Some(position) = stack
+        .entries
+        .iter()
+        .position(|e| e.commit_hash == source_commit)
+    {
+        return Err(CascadeError::validation(format!(
+            "{} is already entry {} of the stack",
+            &source_commit[..8],
+            position + 1
+        )));
+    }
+
+    Output::info(format!(
+        "Picking {} {}",
+        &source_commit[..8],
+        source.summary().unwrap_or("")
+    ));
+
+    let output = Command::new("git")
+        .args(["cherry-pick", &source_commit])
+        .env("CASCADE_SKIP_HOOKS", "1")
+        .current_dir(&repo_root)
+        .output()
+        .map_err(CascadeError::Io)?;
+
+    if !output.status.success() {
+        let conflicts = repo.get_conflicted_files()?;
+        if conflicts.is_empty() {
+            let _ = Command::new("git")
+                .args(["cherry-pick", "--abort"])
+                .env("CASCADE_SKIP_HOOKS", "1")
+                .current_dir(&repo_root)
+                .output();
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let reason = if stderr.contains("empty") || stdout.contains("empty") {
+                "its changes are already on the stack".to_string()
+            } else {
+                stderr.trim().to_string()
+            };
+            return Err(CascadeError::branch(format!(
+                "Could not pick {}: {reason}",
+                &source_commit[..8]
+            )));
+        }
+
+        PickState {
+            stack_id: stack.id.to_string(),
+            source_commit: source_commit.clone(),
+            branch: options.branch,
+        }
+        .save(&repo_root)?;
+
+        Output::error(format!("Conflicts picking {}", &source_commit[..8]));
+        for file in &conflicts {
+            Output::sub_item(file);
+        }
+        Output::spacing();
+        Output::tip("To resolve:");
+        Output::numbered_item(1, "Fix conflicts in your editor");
+        Output::numbered_item(2, "Continue: ca pick --continue");
+        Output::numbered_item(3, "Or give up: ca pick --abort");
+        return Err(CascadeError::validation(format!(
+            "Pick of {} stopped on conflicts",
+            &source_commit[..8]
+        )));
+    }
+
+    add_picked_entry(
+        &repo_root,
+        &stack.id,
+        &source_commit,
+        options.branch,
+        options.yes,
+    )
+    .await
+}
+
+/// Commit the resolved pick and add it to the stack
+async fn continue_pick(repo_root: &Path, yes: bool) -> Result<()> {
+    let state = PickState::load(repo_root)?.ok_or_else(|| {
+        CascadeError::config("No pick in progress. Start one with 'ca pick <commit>'")
+    })?;
+    let stack_id = Uuid::parse_str(&state.stack_id)
+        .map_err(|e| CascadeError::config(format!("Invalid stack ID in pick state: {e}")))?;
+
+    let repo = GitRepository::open(repo_root)?;
+    Output::info("Staging resolved files");
+    repo.stage_all()?;
+    repo.record_conflict_resolutions()?;
+
+    if crate::git::resolve_git_dir(repo_root)?
+        .join("CHERRY_PICK_HEAD")
+        .exists()
+    {
+        let output = Command::new("git")
+            .args(["cherry-pick", "--continue"])
+            .env("CASCADE_SKIP_HOOKS", "1")
+            .env("GIT_EDITOR", "true")
+            .current_dir(repo_root)
+            .output()
+            .map_err(CascadeError::Io)?;
+        if !output.status.success() {
+            return Err(CascadeError::branch(format!(
+                "Failed to continue the pick: {}\n\nMake sure all conflicts are resolved.",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    }
+    PickState::delete(repo_root)?;
+
+    add_picked_entry(
+        repo_root,
+        &stack_id,
+        &state.source_commit,
+        state.branch,
+        yes,
+    )
+    .await
+}
+
+fn abort_pick(repo_root: &Path) -> Result<()> {
+    if PickState::load(repo_root)?.is_none() {
+        return Err(CascadeError::config(
+            "No pick in progress. Nothing to abort.",
+        ));
+    }
+
+    if crate::git::resolve_git_dir(repo_root)?
+        .join("CHERRY_PICK_HEAD")
+        .exists()
+    {
+        let output = Command::new("git")
+            .args(["cherry-pick", "--abort"])
+            .env("CASCADE_SKIP_HOOKS", "1")
+            .current_dir(repo_root)
+            .output()
+            .map_err(CascadeError::Io)?;
+        if !output.status.success() {
+            return Err(CascadeError::branch(format!(
+                "Failed to abort the pick: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    }
+    PickState::delete(repo_root)?;
+
+    Output::success("Pick aborted");
+    Ok(())
+}
+
+/// Push HEAD, the picked commit, as a new entry and remember where it came from
+async fn add_picked_entry(
+    repo_root: &Path,
+    stack_id: &Uuid,
+    source_commit: &str,
+    branch: Option<String>,
+    yes: bool,
+) -> Result<()> {
+    let repo = GitRepository::open(repo_root)?;
+    let commit_hash = repo.get_head_commit()?.id().to_string();
+    let on_working_branch = StackManager::new(repo_root)?
+        .get_stack(stack_id)
+        .and_then(|stack| stack.working_branch.clone())
+        .is_some_and(|working| repo.get_current_branch().ok() == Some(working));
+
+    super::stack::push(
+        PushSpec::new()
+            .commit(commit_hash.clone())
+            .branch(branch)
+            .allow_base_branch(on_working_branch)
+            .yes(yes),
+    )
+    .await?;
+
+    let mut manager = StackManager::new(repo_root)?;
+    let stack = manager
+        .get_stack_mut(stack_id)
+        .ok_or_else(|| CascadeError::config("Stack not found"))?;
+    let Some((position, entry_id)) = stack
+        .entries
+        .iter()
+        .enumerate()
+        .find(|(_, e)| e.commit_hash == commit_hash)
+        .map(|(i, e)| (i + 1, e.id))
+    else {
+        return Ok(());
+    };
+    stack.set_entry_picked_from(&entry_id, source_commit.to_string());
+    manager.save_to_disk()?;
+
+    Output::success(format!(
+        "Picked {} as entry {position} ({})",
+        &source_commit[..8],
+        &commit_hash[..8]
+    ));
+    Ok(())
+}
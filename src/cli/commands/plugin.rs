@@ -0,0 +1,157 @@
+use crate::config::{get_repo_config_dir, Settings};
+use crate::errors::{CascadeError, Result};
+use crate::git::find_repository_root;
+use crate::stack::{Stack, StackManager};
+use crate::utils::{logging, platform};
+use serde::Serialize;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Environment variable holding the JSON context passed to plugins
+pub const CONTEXT_ENV: &str = "CASCADE_CONTEXT";
+
+/// What a plugin learns about the invocation, as JSON in `CASCADE_CONTEXT`
+#[derive(Debug, Serialize)]
+struct PluginContext {
+    version: &'static str,
+    /// The `ca` binary, so plugins can call back into Cascade
+    cascade_bin: Option<PathBuf>,
+    repo_root: Option<PathBuf>,
+    active_stack: Option<StackContext>,
+    /// Repository configuration with tokens and passwords redacted
+    config: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct StackContext {
+    id: String,
+    name: String,
+    base_branch: String,
+    working_branch: Option<String>,
+    entries: Vec<EntryContext>,
+}
+
+#[derive(Debug, Serialize)]
+struct EntryContext {
+    branch: String,
+    commit: String,
+    message: String,
+    pull_request_id: Option<String>,
+    merged: bool,
+}
+
+impl From<&Stack> for StackContext {
+    fn from(stack: &Stack) -> Self {
+        Self {
+            id: stack.id.to_string(),
+            name: stack.name.clone(),
+            base_branch: stack.base_branch.clone(),
+            working_branch: stack.working_branch.clone(),
+            entries: stack
+                .entries
+                .iter()
+                .map(|entry| EntryContext {
+                    branch: entry.branch.clone(),
+                    commit: entry.commit_hash.clone(),
+                    message: entry.message.clone(),
+                    pull_request_id: entry.pull_request_id.clone(),
+                    merged: entry.is_merged,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Run `ca <name> [args...]` as the external `ca-<name>` executable from PATH, the way git
+/// runs `git-<name>`. The plugin inherits the terminal; its exit code becomes ours.
+pub async fn run(args: Vec<String>) -> Result<()> {
+    let Some((name, plugin_args)) = args.split_first() else {
+        return Err(CascadeError::config("No command given. See 'ca --help'"));
+    };
+    let executable = format!("ca-{name}");
+    let path = platform::find_in_path(&executable).ok_or_else(|| {
+        CascadeError::config(format!(
+            "Unknown command '{name}'. See 'ca --help', or install a '{executable}' plugin on your PATH"
+        ))
+    })?;
+
+    let repo_root = env::current_dir()
+        .ok()
+        .and_then(|dir| find_repository_root(&dir).ok());
+    let context = build_context(repo_root.as_deref());
+    let context_json = serde_json::to_string(&context)?;
+
+    tracing::debug!("Running plugin {}", path.display());
+    let mut command = Command::new(&path);
+    command
+        .args(plugin_args)
+        .env(CONTEXT_ENV, context_json)
+        .env("CASCADE_VERSION", context.version);
+    if let Some(root) = &context.repo_root {
+        command.env("CASCADE_REPO_ROOT", root);
+    }
+    if let Some(stack) = &context.active_stack {
+        command.env("CASCADE_STACK", &stack.name);
+    }
+    if let Some(bin) = &context.cascade_bin {
+        command.env("CASCADE_BIN", bin);
+    }
+
+    let status = command.status().map_err(|e| {
+        CascadeError::config(format!("Could not run plugin '{}': {e}", path.display()))
+    })?;
+    if !status.success() {
+        std::process::exit(status.code().unwrap_or(1));
+    }
+    Ok(())
+}
+
+fn build_context(repo_root: Option<&Path>) -> PluginContext {
+    let active_stack = repo_root
+        .and_then(|root| StackManager::new(root).ok())
+        .and_then(|manager| manager.get_active_stack().map(StackContext::from));
+    let config = repo_root
+        .and_then(|root| get_repo_config_dir(root).ok())
+        .and_then(|dir| Settings::load_from_file(&dir.join("config.json")).ok())
+        .and_then(|settings| serde_json::to_value(settings).ok())
+        .map(|mut value| {
+            logging::redact_value(&mut value);
+            value
+        });
+
+    PluginContext {
+        version: env!("CARGO_PKG_VERSION"),
+        cascade_bin: env::current_exe().ok(),
+        repo_root: repo_root.map(Path::to_path_buf),
+        active_stack,
+        config,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_redacts_config_secrets() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_path = temp_dir.path();
+        git2::Repository::init(repo_path).unwrap();
+        crate::config::initialize_repo(repo_path, None).unwrap();
+        let config_path = get_repo_config_dir(repo_path).unwrap().join("config.json");
+        let mut settings = Settings::load_from_file(&config_path).unwrap();
+        settings.bitbucket.token = Some("super-secret".to_string());
+        settings.save_to_file(&config_path).unwrap();
+
+        let context = build_context(Some(repo_path));
+        let json = serde_json::to_string(&context).unwrap();
+        assert!(!json.contains("super-secret"));
+        assert_eq!(context.repo_root.as_deref(), Some(repo_path));
+        assert!(context.active_stack.is_none());
+        assert_eq!(
+            context.config.unwrap()["bitbucket"]["url"],
+            settings.bitbucket.url
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use crate::cli::output::Output;
+use crate::errors::Result;
+use crate::git::{find_repository_root, GitRepository};
+use crate::stack::{PrSummaryCache, Stack, StackManager, StackStatus};
+use std::env;
+
+/// What `ca prompt` knows about the active stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptSegment {
+    /// Active stack name
+    pub stack: String,
+    /// 1-based position of the checked-out entry, if HEAD is on an entry branch
+    pub entry: Option<usize>,
+    /// Number of entries in the stack
+    pub total: usize,
+    /// PRs ready to land as of the last Bitbucket check
+    pub ready_to_land: usize,
+    /// Whether the base branch has moved past the bottom of the stack
+    pub needs_sync: bool,
+}
+
+impl PromptSegment {
+    /// Compact form, e.g. `auth 2/5 ✓1 ↻`
+    pub fn render(&self) -> String {
+        let mut segment = match self.entry {
+            Some(entry) => format!("{} {}/{}", self.stack, entry, self.total),
+            None => format!("{} {}", self.stack, self.total),
+        };
+        if self.ready_to_land > 0 {
+            segment.push_str(&format!(" ✓{}", self.ready_to_land));
+        }
+        if self.needs_sync {
+            segment.push_str(" ↻");
+        }
+        segment
+    }
+
+    /// Fill in `{stack}`, `{entry}`, `{total}`, `{ready}` and `{sync}` placeholders
+    pub fn render_template(&self, template: &str) -> String {
+        template
+            .replace("{stack}", &self.stack)
+            .replace(
+                "{entry}",
+                &self.entry.map(|e| e.to_string()).unwrap_or_default(),
+            )
+            .replace("{total}", &self.total.to_string())
+            .replace("{ready}", &self.ready_to_land.to_string())
+            .replace("{sync}", if self.needs_sync { "↻" } else { "" })
+    }
+}
+
+/// Print the prompt segment for the active stack.
+///
+/// Runs on every prompt, so it only reads local metadata and prints nothing at all
+/// outside a Cascade repository or when no stack is active.
+pub async fn run(format: Option<String>) -> Result<()> {
+    let Some(segment) = current_segment() else {
+        return Ok(());
+    };
+
+    match format {
+        Some(template) => Output::data(segment.render_template(&template)),
+        None => Output::data(segment.render()),
+    }
+    Ok(())
+}
+
+fn current_segment() -> Option<PromptSegment> {
+    let current_dir = env::current_dir().ok()?;
+    let repo_root = find_repository_root(&current_dir).ok()?;
+    if !crate::config::is_repo_initialized(&repo_root) {
+        return None;
+    }
+
+    let manager = StackManager::new(&repo_root).ok()?;
+    let stack = manager.get_active_stack()?;
+    let git_repo = manager.git_repo();
+
+    let current_branch = git_repo.get_current_branch().ok();
+    let entry = stack
+        .entries
+        .iter()
+        .position(|entry| Some(&entry.branch) == current_branch.as_ref())
+        .map(|index| index + 1);
+
+    let ready_to_land = PrSummaryCache::load(&repo_root)
+        .get(&stack.id)
+        .map(|summary| summary.ready_to_land)
+        .unwrap_or(0);
+
+    Some(PromptSegment {
+        stack: stack.name.clone(),
+        entry,
+        total: stack.entries.len(),
+        ready_to_land,
+        needs_sync: needs_sync(git_repo, stack),
+    })
+}
+
+/// Whether the base branch head is missing from the history below the bottom
+/// unmerged entry
+pub(crate) fn needs_sync(git_repo: &GitRepository, stack: &Stack) -> bool {
+    if stack.status == StackStatus::NeedsSync {
+        return true;
+    }
+
+    let Some(bottom) = stack.entries.iter().find(|entry| !entry.is_merged) else {
+        return false;
+    };
+    let Ok(base_head) = git_repo.get_branch_head(&stack.base_branch) else {
+        return false;
+    };
+
+    bottom.commit_hash != base_head
+        && git_repo
+            .is_descendant_of(&bottom.commit_hash, &base_head)
+            .is_ok_and(|on_base| !on_base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment() -> PromptSegment {
+        PromptSegment {
+            stack: "auth".to_string(),
+            entry: Some(2),
+            total: 5,
+            ready_to_land: 1,
+            needs_sync: true,
+        }
+    }
+
+    #[test]
+    fn test_render_segment() {
+        assert_eq!(segment().render(), "auth 2/5 ✓1 ↻");
+
+        let quiet = PromptSegment {
+            entry: None,
+            ready_to_land: 0,
+            needs_sync: false,
+            ..segment()
+        };
+        assert_eq!(quiet.render(), "auth 5");
+    }
+
+    #[test]
+    fn test_needs_sync_once_base_moves_on_a_long_history() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_path = temp_dir.path();
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .args(args)
+                .current_dir(repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        git(&["init"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["config", "user.email", "test@test.com"]);
+        for i in 0..80 {
+            git(&["commit", "--allow-empty", "-m", &format!("Commit {i}")]);
+        }
+        crate::config::initialize_repo(repo_path, Some("https://test.bitbucket.com".to_string()))
+            .unwrap();
+
+        let base = git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+        let mut manager = StackManager::new(repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("deep".to_string(), Some(base.clone()), None)
+            .unwrap();
+        git(&["checkout", "-b", "deep-1"]);
+        git(&["commit", "--allow-empty", "-m", "Entry"]);
+        manager.get_stack_mut(&stack_id).unwrap().push_entry(
+            "deep-1".to_string(),
+            git(&["rev-parse", "HEAD"]),
+            "Entry".to_string(),
+        );
+
+        let git_repo = GitRepository::open(repo_path).unwrap();
+        assert!(!needs_sync(
+            &git_repo,
+            manager.get_stack(&stack_id).unwrap()
+        ));
+
+        git(&["checkout", &base]);
+        git(&["commit", "--allow-empty", "-m", "Base moves on"]);
+        assert!(needs_sync(&git_repo, manager.get_stack(&stack_id).unwrap()));
+    }
+
+    #[test]
+    fn test_render_template() {
+        assert_eq!(
+            segment().render_template("[{stack} {entry}/{total}] {ready} ready {sync}"),
+            "[auth 2/5] 1 ready ↻"
+        );
+    }
+}
@@ -0,0 +1,341 @@
+use crate::bitbucket::pull_request::{BuildState, ParticipantStatus};
+use crate::bitbucket::{PullRequest, PullRequestManager, PullRequestQuery, PullRequestState};
+use crate::cli::output::Output;
+use crate::errors::{CascadeError, Result};
+use crate::git::find_repository_root;
+use crate::stack::StackManager;
+use console::style;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+
+/// Filters for `ca prs`
+#[derive(Debug, Clone, Default)]
+pub struct PrFilters {
+    /// open, merged or declined (default: open)
+    pub state: Option<String>,
+    /// Only pull requests I authored
+    pub mine: bool,
+    /// Only pull requests waiting for my approval
+    pub needs_review: bool,
+    /// Only pull requests by this user
+    pub author: Option<String>,
+    pub verbose: bool,
+}
+
+/// Where a pull request stands from the point of view of `me`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Triage {
+    /// I'm a reviewer and haven't approved
+    AwaitingMyReview,
+    /// Someone else has to approve
+    AwaitingOthers,
+    /// A reviewer asked for changes
+    NeedsWork,
+    /// Every reviewer approved
+    Approved,
+}
+
+impl Triage {
+    fn of(pr: &PullRequest, me: Option<&str>) -> Self {
+        let mine = |name: &str| me == Some(name);
+        if pr
+            .reviewers
+            .iter()
+            .any(|r| mine(&r.user.name) && !r.approved)
+            && !mine(&pr.author.user.name)
+        {
+            return Self::AwaitingMyReview;
+        }
+        if pr
+            .reviewers
+            .iter()
+            .any(|r| r.status == ParticipantStatus::NeedsWork)
+        {
+            return Self::NeedsWork;
+        }
+        if !pr.reviewers.is_empty() && pr.reviewers.iter().all(|r| r.approved) {
+            return Self::Approved;
+        }
+        Self::AwaitingOthers
+    }
+
+    fn label(self) -> String {
+        match self {
+            Self::AwaitingMyReview => style("needs your review").yellow().bold().to_string(),
+            Self::AwaitingOthers => style("awaiting review").dim().to_string(),
+            Self::NeedsWork => style("needs work").red().to_string(),
+            Self::Approved => style("approved").green().to_string(),
+        }
+    }
+}
+
+/// Heading for pull requests whose branch isn't a stack entry
+const OUTSIDE_STACKS: &str = "Not in a stack";
+
+pub async fn run(filters: PrFilters) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let config_dir = crate::config::get_repo_config_dir(&repo_root)?;
+    let settings = crate::config::Settings::load_from_file(&config_dir.join("config.json"))?;
+    let me = settings.bitbucket.username.clone();
+
+    let query = build_query(&filters, me.as_deref())?;
+    let pr_manager = PullRequestManager::from_settings(&settings, &repo_root)?;
+    let prs = pr_manager.search_pull_requests(&query).await?.values;
+
+    if let Err(e) = crate::stack::PrSummaryCache::record_titles(
+        &repo_root,
+        prs.iter().map(|pr| (pr.id.to_string(), pr.title.clone())),
+    ) {
+        tracing::debug!("Failed to record PR titles: {}", e);
+    }
+
+    if prs.is_empty() {
+        Output::info("No pull requests match.");
+        return Ok(());
+    }
+
+    // Branch → stack name, so PRs can be grouped by the stack they belong to
+    let manager = StackManager::new(&repo_root)?;
+    let stack_of: HashMap<String, String> = manager
+        .get_all_stacks_objects()?
+        .into_iter()
+        .flat_map(|stack| {
+            let name = stack.name.clone();
+            stack
+                .entries
+                .into_iter()
+                .map(move |entry| (entry.branch, name.clone()))
+        })
+        .collect();
+
+    let mut groups: BTreeMap<&str, Vec<&PullRequest>> = BTreeMap::new();
+    for pr in &prs {
+        let stack = stack_of
+            .get(&pr.from_ref.display_id)
+            .map(String::as_str)
+            .unwrap_or(OUTSIDE_STACKS);
+        groups.entry(stack).or_default().push(pr);
+    }
+    // Stacks first, loose PRs last
+    let mut ordered: Vec<_> = groups.into_iter().collect();
+    ordered.sort_by_key(|(name, _)| *name == OUTSIDE_STACKS);
+
+    let mut awaiting_me = 0;
+    let mut blocked = 0;
+    for (stack, prs) in ordered {
+        Output::section(stack);
+        for pr in prs {
+            let mut tags = Vec::new();
+            if pr.state == PullRequestState::Open {
+                let triage = Triage::of(pr, me.as_deref());
+                if triage == Triage::AwaitingMyReview {
+                    awaiting_me += 1;
+                }
+                tags.push(triage.label());
+
+                let build = pr_manager.get_build_status(pr.id).await.ok();
+                match build.map(|b| b.state) {
+                    Some(BuildState::Failed) => {
+                        blocked += 1;
+                        tags.push(style("build failed").red().to_string());
+                    }
+                    Some(BuildState::InProgress) => {
+                        blocked += 1;
+                        tags.push(style("build running").yellow().to_string());
+                    }
+                    _ => {}
+                }
+            } else {
+                tags.push(style(pr.state.as_str().to_lowercase()).dim().to_string());
+            }
+
+            Output::bullet(format!(
+                "{} {} [{}]",
+                style(format!("#{}", pr.id)).dim(),
+                pr.title,
+                tags.join(", ")
+            ));
+            if filters.verbose {
+                Output::sub_item(format!(
+                    "{} → {} by {}",
+                    pr.from_ref.display_id,
+                    pr.to_ref.display_id,
+                    pr.author
+                        .user
+                        .display_name
+                        .as_deref()
+                        .unwrap_or(&pr.author.user.name)
+                ));
+                if let Some(url) = pr.web_url() {
+                    Output::sub_item(style(url).cyan().underlined());
+                }
+            }
+        }
+    }
+
+    Output::spacing();
+    Output::info(format!(
+        "{} pull request{}: {awaiting_me} awaiting your review, {blocked} blocked on builds",
+        prs.len(),
+        if prs.len() == 1 { "" } else { "s" }
+    ));
+    if me.is_none() {
+        Output::tip("Set 'bitbucket.username' to see which pull requests are waiting for you");
+    }
+    Ok(())
+}
+
+fn build_query(filters: &PrFilters, me: Option<&str>) -> Result<PullRequestQuery> {
+    let state = match filters.state.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("open") => Some(PullRequestState::Open),
+        Some("merged") => Some(PullRequestState::Merged),
+        Some("declined") => Some(PullRequestState::Declined),
+        Some("all") => None,
+        Some(other) => {
+            return Err(CascadeError::config(format!(
+                "Invalid state '{other}'. Use: open, merged, declined, all"
+            )))
+        }
+    };
+
+    let require_me = || {
+        me.map(str::to_string).ok_or_else(|| {
+            CascadeError::config(
+                "--mine and --needs-review need your username: ca config set bitbucket.username <user>",
+            )
+        })
+    };
+    let author = if filters.mine {
+        Some(require_me()?)
+    } else {
+        filters.author.clone()
+    };
+    let reviewer = if filters.needs_review {
+        Some(require_me()?)
+    } else {
+        None
+    };
+
+    Ok(PullRequestQuery {
+        state,
+        author,
+        reviewer,
+        unapproved: filters.needs_review,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitbucket::pull_request::{Participant, ParticipantRole};
+    use crate::bitbucket::User;
+
+    fn participant(name: &str, role: ParticipantRole, status: ParticipantStatus) -> Participant {
+        Participant {
+            user: User {
+                name: name.to_string(),
+                display_name: None,
+                email_address: None,
+                active: true,
+                slug: None,
+            },
+            role,
+            approved: status == ParticipantStatus::Approved,
+            status,
+        }
+    }
+
+    fn pr(author: &str, reviewers: Vec<Participant>) -> PullRequest {
+        let repository = serde_json::json!({
+            "id": 1, "name": "r", "slug": "r", "scmId": "git", "state": "AVAILABLE",
+            "forkable": true, "public": false,
+            "project": {"id": 1, "key": "P", "name": "P", "public": false, "type": "NORMAL"}
+        });
+        let mut pr: PullRequest = serde_json::from_value(serde_json::json!({
+            "id": 1, "version": 0, "title": "Change", "state": "OPEN",
+            "open": true, "closed": false, "createdDate": 0, "updatedDate": 0,
+            "fromRef": {"id": "refs/heads/a", "displayId": "a", "latestCommit": "abc",
+                "repository": repository},
+            "toRef": {"id": "refs/heads/main", "displayId": "main", "latestCommit": "def",
+                "repository": repository},
+            "locked": false,
+            "author": {"user": {"name": author, "active": true}, "role": "AUTHOR",
+                "approved": false, "status": "UNAPPROVED"},
+            "links": {"self": []}
+        }))
+        .unwrap();
+        pr.reviewers = reviewers;
+        pr
+    }
+
+    #[test]
+    fn test_triage_from_my_point_of_view() {
+        use ParticipantRole::Reviewer;
+        use ParticipantStatus::*;
+
+        let waiting_on_me = pr(
+            "alice",
+            vec![
+                participant("me", Reviewer, Unapproved),
+                participant("bob", Reviewer, Approved),
+            ],
+        );
+        assert_eq!(
+            Triage::of(&waiting_on_me, Some("me")),
+            Triage::AwaitingMyReview
+        );
+        assert_eq!(
+            Triage::of(&waiting_on_me, Some("bob")),
+            Triage::AwaitingOthers
+        );
+
+        let needs_work = pr("me", vec![participant("bob", Reviewer, NeedsWork)]);
+        assert_eq!(Triage::of(&needs_work, Some("me")), Triage::NeedsWork);
+
+        let approved = pr("me", vec![participant("bob", Reviewer, Approved)]);
+        assert_eq!(Triage::of(&approved, Some("me")), Triage::Approved);
+        assert_eq!(Triage::of(&pr("me", vec![]), None), Triage::AwaitingOthers);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let filters = PrFilters {
+            needs_review: true,
+            author: Some("alice".to_string()),
+            ..Default::default()
+        };
+        let query = build_query(&filters, Some("me")).unwrap();
+        assert_eq!(query.state, Some(PullRequestState::Open));
+        assert_eq!(query.author.as_deref(), Some("alice"));
+        assert_eq!(query.reviewer.as_deref(), Some("me"));
+
+        let mine = PrFilters {
+            mine: true,
+            ..Default::default()
+        };
+        assert!(build_query(&mine, None).is_err());
+
+        let reviewed = pr(
+            "alice",
+            vec![participant(
+                "me",
+                ParticipantRole::Reviewer,
+                ParticipantStatus::Approved,
+            )],
+        );
+        assert!(!query.matches(&reviewed));
+        let pending = pr(
+            "alice",
+            vec![participant(
+                "me",
+                ParticipantRole::Reviewer,
+                ParticipantStatus::Unapproved,
+            )],
+        );
+        assert!(query.matches(&pending));
+    }
+}
@@ -0,0 +1,352 @@
+use crate::cli::commands::stack::relative_age;
+use crate::cli::output::Output;
+use crate::cli::prompt::{self, Prompter, TerminalPrompter};
+use crate::errors::{CascadeError, Result};
+use crate::git::GitRepository;
+use crate::stack::CommitSelector;
+use chrono::{DateTime, Utc};
+use clap::Args;
+use std::io::IsTerminal;
+
+/// Arguments shared by `ca push` and `ca stacks push`
+#[derive(Debug, Clone, Default, Args)]
+pub struct PushArgs {
+    /// Branch name for this commit
+    #[arg(long, short)]
+    pub branch: Option<String>,
+    /// Commit message (if creating a new commit)
+    #[arg(long, short)]
+    pub message: Option<String>,
+    /// Use specific commit hash instead of HEAD
+    #[arg(long)]
+    pub commit: Option<String>,
+    /// Push commits since this reference (e.g., HEAD~3)
+    #[arg(long)]
+    pub since: Option<String>,
+    /// Push multiple specific commits (comma-separated)
+    #[arg(long)]
+    pub commits: Option<String>,
+    /// Pick the commits to push from a list of unpushed commits
+    #[arg(long, short)]
+    pub interactive: bool,
+    /// Squash unpushed commits before pushing (optional: specify count)
+    #[arg(long, num_args = 0..=1, default_missing_value = "0", conflicts_with = "squash_since")]
+    pub squash: Option<usize>,
+    /// Squash all commits since this reference (e.g., HEAD~5)
+    #[arg(long)]
+    pub squash_since: Option<String>,
+    /// Open $EDITOR on the squashed commit's message before committing it
+    #[arg(long)]
+    pub edit_message: bool,
+    /// Put N consecutive commits into each entry instead of one
+    #[arg(long, value_name = "N")]
+    pub group: Option<usize>,
+    /// Auto-create feature branch when pushing from base branch
+    #[arg(long)]
+    pub auto_branch: bool,
+    /// Allow pushing commits from base branch (not recommended)
+    #[arg(long)]
+    pub allow_base_branch: bool,
+    /// Show what would be pushed without actually pushing
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Squashing to do before the commits are pushed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Squash {
+    /// All unpushed commits
+    Unpushed,
+    /// The last N commits
+    Last(usize),
+    /// Every commit after this reference
+    Since(String),
+}
+
+/// A parsed `ca push`: which commits to add and how
+#[derive(Debug, Clone, Default)]
+pub struct PushSpec {
+    /// Branch name for the first pushed commit
+    pub branch: Option<String>,
+    /// Entry message for the first pushed commit
+    pub message: Option<String>,
+    pub selector: CommitSelector,
+    pub squash: Option<Squash>,
+    /// Edit the squashed commit's message in $EDITOR
+    pub edit_message: bool,
+    /// Consecutive commits per entry
+    pub group: Option<usize>,
+    pub auto_branch: bool,
+    pub allow_base_branch: bool,
+    pub dry_run: bool,
+    pub yes: bool,
+}
+
+impl PushSpec {
+    /// Push the unpushed commits with the default behaviour
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push exactly this commit
+    pub fn commit(mut self, commit: impl Into<String>) -> Self {
+        self.selector = CommitSelector::Commit(commit.into());
+        self
+    }
+
+    pub fn branch(mut self, branch: Option<String>) -> Self {
+        self.branch = branch;
+        self
+    }
+
+    pub fn allow_base_branch(mut self, allow: bool) -> Self {
+        self.allow_base_branch = allow;
+        self
+    }
+
+    pub fn yes(mut self, yes: bool) -> Self {
+        self.yes = yes;
+        self
+    }
+}
+
+impl TryFrom<PushArgs> for PushSpec {
+    type Error = CascadeError;
+
+    fn try_from(args: PushArgs) -> Result<Self> {
+        let selector =
+            CommitSelector::from_flags(args.commit, args.since, args.commits, args.interactive)?;
+        let squash = match (args.squash, args.squash_since) {
+            (Some(0), _) => Some(Squash::Unpushed),
+            (Some(count), _) => Some(Squash::Last(count)),
+            (None, Some(since)) => Some(Squash::Since(since)),
+            (None, None) => None,
+        };
+
+        if args.edit_message && squash.is_none() && selector != CommitSelector::Interactive {
+            return Err(CascadeError::validation(
+                "--edit-message only applies with --squash, --squash-since or --interactive",
+            ));
+        }
+        if args.group == Some(0) {
+            return Err(CascadeError::validation(
+                "--group needs at least 1 commit per entry",
+            ));
+        }
+
+        Ok(Self {
+            branch: args.branch,
+            message: args.message,
+            selector,
+            squash,
+            edit_message: args.edit_message,
+            group: args.group,
+            auto_branch: args.auto_branch,
+            allow_base_branch: args.allow_base_branch,
+            dry_run: args.dry_run,
+            yes: prompt::assume_yes(),
+        })
+    }
+}
+
+/// How the commits picked with `--interactive` become stack entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    /// One entry per commit
+    PerCommit,
+    /// A single entry for all of them, squashed into one commit
+    Squashed,
+    /// A single entry for all of them, keeping the commits
+    Grouped,
+}
+
+/// Commits chosen in the `--interactive` picker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickedCommits {
+    pub commits: Vec<String>,
+    pub grouping: Grouping,
+}
+
+/// Let the user tick which of the candidate commits to push, keeping their order, and choose
+/// whether they become separate entries or one squashed entry
+pub fn pick_commits(repo: &GitRepository, candidates: Vec<String>) -> Result<PickedCommits> {
+    if candidates.len() < 2 {
+        return Ok(PickedCommits {
+            commits: candidates,
+            grouping: Grouping::PerCommit,
+        });
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(CascadeError::config(
+            "--interactive needs a terminal. Use --commit, --commits or --since instead",
+        ));
+    }
+
+    let now = Utc::now();
+    let items = candidates
+        .iter()
+        .map(|hash| describe_commit(repo, hash, now))
+        .collect::<Result<Vec<_>>>()?;
+
+    // The picks are the point of --interactive, so it asks even with --yes
+    let picker = TerminalPrompter::new(false);
+    let chosen = picker.multi_select(
+        "Commits to push (space to toggle, enter to confirm)",
+        &items,
+        &[],
+    )?;
+    let commits: Vec<String> = chosen
+        .into_iter()
+        .map(|index| candidates[index].clone())
+        .collect();
+
+    if commits.len() < 2 {
+        return Ok(PickedCommits {
+            commits,
+            grouping: Grouping::PerCommit,
+        });
+    }
+    // Squashing resets HEAD back over the picked commits, so they have to be the newest ones
+    let head = repo.get_head_commit()?.id().to_string();
+    if !is_newest_run(&candidates, &commits, &head) {
+        Output::info("Each picked commit becomes its own entry");
+        Output::sub_item("Only a run of commits ending at HEAD can share one entry");
+        return Ok(PickedCommits {
+            commits,
+            grouping: Grouping::PerCommit,
+        });
+    }
+
+    let choice = picker.select(
+        &format!("Push {} commits as", commits.len()),
+        &[
+            "One entry per commit",
+            "One squashed entry",
+            "One entry keeping the commits",
+        ]
+        .map(String::from),
+        0,
+    )?;
+
+    Ok(PickedCommits {
+        commits,
+        grouping: match choice {
+            1 => Grouping::Squashed,
+            2 => Grouping::Grouped,
+            _ => Grouping::PerCommit,
+        },
+    })
+}
+
+/// One line of the picker: hash, message, age and the files touched
+fn describe_commit(repo: &GitRepository, hash: &str, now: DateTime<Utc>) -> Result<String> {
+    let commit = repo.get_commit(hash)?;
+    let summary = commit.summary().unwrap_or("(no message)");
+    let age = DateTime::from_timestamp(commit.time().seconds(), 0)
+        .map(|time| relative_age(time, now))
+        .unwrap_or_default();
+    let files = summarize_files(&repo.get_commit_files(hash)?);
+
+    Ok(format!("{} {summary} · {age} · {files}", &hash[..8]))
+}
+
+/// `2 files (src/a.rs, src/b.rs)`, listing at most three of them
+fn summarize_files(files: &[String]) -> String {
+    const SHOWN: usize = 3;
+    let count = match files.len() {
+        0 => return "no files".to_string(),
+        1 => "1 file".to_string(),
+        n => format!("{n} files"),
+    };
+    let mut shown = files.iter().take(SHOWN).cloned().collect::<Vec<_>>();
+    if files.len() > SHOWN {
+        shown.push(format!("+{}", files.len() - SHOWN));
+    }
+    format!("{count} ({})", shown.join(", "))
+}
+
+/// Whether `picked` is the tail of `candidates` and that tail ends at HEAD
+fn is_newest_run(candidates: &[String], picked: &[String], head: &str) -> bool {
+    candidates.last().is_some_and(|last| last == head) && candidates.ends_with(picked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_spec_from_args() {
+        let spec = PushSpec::try_from(PushArgs {
+            since: Some("HEAD~3".to_string()),
+            squash: Some(0),
+            ..PushArgs::default()
+        })
+        .unwrap();
+        assert_eq!(spec.selector, CommitSelector::Since("HEAD~3".to_string()));
+        assert_eq!(spec.squash, Some(Squash::Unpushed));
+
+        let spec = PushSpec::try_from(PushArgs {
+            squash_since: Some("main".to_string()),
+            ..PushArgs::default()
+        })
+        .unwrap();
+        assert_eq!(spec.selector, CommitSelector::Unpushed);
+        assert_eq!(spec.squash, Some(Squash::Since("main".to_string())));
+
+        assert!(PushSpec::try_from(PushArgs {
+            commit: Some("abc".to_string()),
+            interactive: true,
+            ..PushArgs::default()
+        })
+        .is_err());
+        assert!(PushSpec::try_from(PushArgs {
+            edit_message: true,
+            ..PushArgs::default()
+        })
+        .is_err());
+        assert!(
+            PushSpec::try_from(PushArgs {
+                squash: Some(2),
+                edit_message: true,
+                ..PushArgs::default()
+            })
+            .unwrap()
+            .edit_message
+        );
+        assert!(PushSpec::try_from(PushArgs {
+            group: Some(0),
+            ..PushArgs::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_push_spec_builder() {
+        let spec = PushSpec::new()
+            .commit("abc123")
+            .branch(Some("part-one".to_string()))
+            .allow_base_branch(true)
+            .yes(true);
+        assert_eq!(spec.selector, CommitSelector::Commit("abc123".to_string()));
+        assert_eq!(spec.branch.as_deref(), Some("part-one"));
+        assert!(spec.allow_base_branch && spec.yes && !spec.dry_run);
+    }
+
+    #[test]
+    fn test_summarize_files() {
+        assert_eq!(summarize_files(&[]), "no files");
+        assert_eq!(summarize_files(&["a.rs".to_string()]), "1 file (a.rs)");
+        let files = ["a", "b", "c", "d", "e"].map(str::to_string);
+        assert_eq!(summarize_files(&files), "5 files (a, b, c, +2)");
+    }
+
+    #[test]
+    fn test_is_newest_run() {
+        let candidates = ["c1", "c2", "c3"].map(str::to_string);
+        let picked = |ids: &[&str]| ids.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(is_newest_run(&candidates, &picked(&["c2", "c3"]), "c3"));
+        assert!(!is_newest_run(&candidates, &picked(&["c1", "c3"]), "c3"));
+        assert!(!is_newest_run(&candidates, &picked(&["c1", "c2"]), "c3"));
+        assert!(!is_newest_run(&candidates, &picked(&["c2", "c3"]), "c4"));
+    }
+}
@@ -0,0 +1,407 @@
+use crate::cli::output::Output;
+use crate::errors::{CascadeError, Result};
+use crate::git::find_repository_root;
+use crate::stack::{Stack, StackEntry, StackManager};
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::env;
+
+/// One step of a selector like `stacks[active].entries[?submitted==false].branch`
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    /// `.name`: a field of each object; on arrays, of each element
+    Field(String),
+    /// `[2]`, `[-1]`: an array element, counting from 0 (negative counts from the end)
+    Index(i64),
+    /// `[]` or `[*]`: every element
+    All,
+    /// `[?field==value]` or `[field]`: the elements passing a test
+    Filter(Filter),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Filter {
+    /// Dotted path of the field tested, relative to each element
+    field: Vec<String>,
+    /// Without a comparison the field only has to be truthy
+    comparison: Option<(Op, Value)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    /// Operator tokens as written in filters
+    const ALL: [(&'static str, Op); 6] = [
+        ("==", Op::Eq),
+        ("!=", Op::Ne),
+        ("<=", Op::Le),
+        (">=", Op::Ge),
+        ("<", Op::Lt),
+        (">", Op::Gt),
+    ];
+
+    fn apply(self, left: &Value, right: &Value) -> bool {
+        match self {
+            Op::Eq => values_equal(left, right),
+            Op::Ne => !values_equal(left, right),
+            _ => match compare(left, right) {
+                Some(ordering) => match self {
+                    Op::Lt => ordering == Ordering::Less,
+                    Op::Le => ordering != Ordering::Greater,
+                    Op::Gt => ordering == Ordering::Greater,
+                    _ => ordering != Ordering::Less,
+                },
+                None => false,
+            },
+        }
+    }
+}
+
+/// Print what `selector` picks out of the stack data, one result per line (strings bare,
+/// everything else as JSON), or all results as a JSON array with `--json`
+pub async fn run(selector: String, json: bool) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let steps = parse(&selector)?;
+    let manager = StackManager::new(&repo_root)?;
+    let document = document(&manager.get_all_stacks_objects()?);
+    let results = evaluate(&steps, document);
+
+    if json {
+        Output::data(serde_json::to_string_pretty(&results)?);
+    } else {
+        for result in results {
+            match result {
+                Value::String(text) => Output::data(text),
+                other => Output::data(other),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The data selectors run against: every stack, plus the active one under `active`
+fn document(stacks: &[Stack]) -> Value {
+    let stacks: Vec<Value> = stacks
+        .iter()
+        .map(|stack| stack_value(stack, stacks))
+        .collect();
+    let active = stacks
+        .iter()
+        .find(|stack| stack["active"] == Value::Bool(true))
+        .cloned()
+        .unwrap_or(Value::Null);
+    json!({ "stacks": stacks, "active": active })
+}
+
+fn stack_value(stack: &Stack, all: &[Stack]) -> Value {
+    let depends_on = stack
+        .depends_on
+        .and_then(|id| all.iter().find(|other| other.id == id))
+        .map(|other| other.name.clone());
+    json!({
+        "id": stack.id.to_string(),
+        "name": stack.name,
+        "description": stack.description,
+        "base_branch": stack.base_branch,
+        "working_branch": stack.working_branch,
+        "active": stack.is_active,
+        "status": stack.status,
+        "depends_on": depends_on,
+        "entries": stack
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| entry_value(index + 1, entry))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn entry_value(position: usize, entry: &StackEntry) -> Value {
+    json!({
+        "position": position,
+        "branch": entry.branch,
+        "commit": entry.commit_hash,
+        "short_commit": entry.short_hash(),
+        "title": entry.message.lines().next().unwrap_or_default(),
+        "message": entry.message,
+        "pull_request_id": entry.pull_request_id,
+        "submitted": entry.pull_request_id.is_some(),
+        "merged": entry.is_merged,
+        "pushed_commit": entry.remote_commit_hash,
+    })
+}
+
+fn parse(selector: &str) -> Result<Vec<Step>> {
+    let invalid = |at: usize, why: &str| {
+        CascadeError::validation(format!(
+            "Invalid selector '{selector}' at position {}: {why}",
+            at + 1
+        ))
+    };
+
+    let chars: Vec<char> = selector.trim().chars().collect();
+    let mut steps = Vec::new();
+    let mut i = 0;
+    // A leading `.` is allowed, as in jq
+    if chars.first() == Some(&'.') {
+        i = 1;
+    }
+    while i < chars.len() {
+        match chars[i] {
+            '[' => {
+                let start = i + 1;
+                let mut quote = None;
+                let mut end = None;
+                for (offset, &c) in chars[start..].iter().enumerate() {
+                    match (quote, c) {
+                        (Some(q), c) if c == q => quote = None,
+                        (Some(_), _) => {}
+                        (None, '\'' | '"') => quote = Some(c),
+                        (None, ']') => {
+                            end = Some(start + offset);
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                let end = end.ok_or_else(|| invalid(i, "missing ']'"))?;
+                let inner: String = chars[start..end].iter().collect();
+                steps.push(parse_bracket(inner.trim()).map_err(|why| invalid(start, &why))?);
+                i = end + 1;
+            }
+            '.' if !steps.is_empty() => {
+                i += 1;
+                if !chars
+                    .get(i)
+                    .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+                {
+                    return Err(invalid(i, "expected a field name after '.'"));
+                }
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || "_-".contains(chars[i])) {
+                    i += 1;
+                }
+                steps.push(Step::Field(chars[start..i].iter().collect()));
+                if i < chars.len() && !".[".contains(chars[i]) {
+                    return Err(invalid(i, "expected '.', '[' or the end"));
+                }
+            }
+            _ => return Err(invalid(i, "expected a field name or '['")),
+        }
+    }
+    Ok(steps)
+}
+
+fn parse_bracket(inner: &str) -> std::result::Result<Step, String> {
+    if inner.is_empty() || inner == "*" {
+        return Ok(Step::All);
+    }
+    if let Ok(index) = inner.parse::<i64>() {
+        return Ok(Step::Index(index));
+    }
+
+    let expression = inner.strip_prefix('?').unwrap_or(inner).trim();
+    // The leftmost operator wins, and the longer one where two start together (`<=`, `<`)
+    let split = Op::ALL
+        .iter()
+        .filter_map(|(token, op)| expression.find(token).map(|at| (at, token.len(), *op)))
+        .min_by_key(|(at, len, _)| (*at, std::cmp::Reverse(*len)));
+
+    let (field, comparison) = match split {
+        Some((at, len, op)) => (
+            expression[..at].trim(),
+            Some((op, parse_literal(expression[at + len..].trim()))),
+        ),
+        None => (expression, None),
+    };
+    if field.is_empty() {
+        return Err("expected a field name before the comparison".to_string());
+    }
+    if !field
+        .chars()
+        .all(|c| c.is_alphanumeric() || "_-.".contains(c))
+    {
+        return Err(format!("'{field}' is not a field name"));
+    }
+    Ok(Step::Filter(Filter {
+        field: field.split('.').map(str::to_string).collect(),
+        comparison,
+    }))
+}
+
+/// `'text'`, `"text"`, `true`, `false`, `null`, numbers; anything else is a bare string
+fn parse_literal(text: &str) -> Value {
+    for quote in ['\'', '"'] {
+        if let Some(inner) = text
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return Value::String(inner.to_string());
+        }
+    }
+    match text {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        "null" => Value::Null,
+        _ => text
+            .parse::<f64>()
+            .ok()
+            .and_then(|number| serde_json::Number::from_f64(number).map(Value::Number))
+            .unwrap_or_else(|| Value::String(text.to_string())),
+    }
+}
+
+fn evaluate(steps: &[Step], document: Value) -> Vec<Value> {
+    let mut current = vec![document];
+    for step in steps {
+        current = current
+            .into_iter()
+            .flat_map(|value| apply(step, value))
+            .collect();
+    }
+    current
+}
+
+fn apply(step: &Step, value: Value) -> Vec<Value> {
+    match (step, value) {
+        (Step::Field(name), Value::Object(mut map)) => map.remove(name).into_iter().collect(),
+        (Step::Field(_), Value::Array(values)) => values
+            .into_iter()
+            .flat_map(|value| apply(step, value))
+            .collect(),
+        (Step::Index(index), Value::Array(mut values)) => {
+            let len = values.len() as i64;
+            let index = if *index < 0 { len + index } else { *index };
+            if (0..len).contains(&index) {
+                vec![values.swap_remove(index as usize)]
+            } else {
+                Vec::new()
+            }
+        }
+        (Step::All, Value::Array(values)) => values,
+        (Step::All, Value::Object(map)) => map.into_iter().map(|(_, value)| value).collect(),
+        (Step::Filter(filter), Value::Array(values)) => values
+            .into_iter()
+            .filter(|value| filter.matches(value))
+            .collect(),
+        (Step::Filter(filter), value) if filter.matches(&value) => vec![value],
+        _ => Vec::new(),
+    }
+}
+
+impl Filter {
+    fn matches(&self, value: &Value) -> bool {
+        let field = self
+            .field
+            .iter()
+            .try_fold(value, |value, name| value.get(name))
+            .unwrap_or(&Value::Null);
+        match &self.comparison {
+            Some((op, expected)) => op.apply(field, expected),
+            None => truthy(field),
+        }
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Bool(false) => false,
+        Value::String(text) => !text.is_empty(),
+        Value::Array(values) => !values.is_empty(),
+        _ => true,
+    }
+}
+
+fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left.as_f64(), right.as_f64()) {
+        (Some(left), Some(right)) => left == right,
+        _ => left == right,
+    }
+}
+
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+        _ => left.as_f64()?.partial_cmp(&right.as_f64()?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Value {
+        let mut auth = Stack::new("auth".to_string(), "main".to_string(), None);
+        for branch in ["auth-1", "auth-2", "auth-3"] {
+            auth.push_entry(
+                branch.to_string(),
+                format!("{branch}-hash"),
+                format!("Add {branch}\n\nDetails"),
+            );
+        }
+        let first = auth.entries[0].id;
+        auth.mark_entry_submitted(&first, "12".to_string());
+        auth.is_active = true;
+        let billing = Stack::new("billing".to_string(), "develop".to_string(), None);
+        document(&[auth, billing])
+    }
+
+    fn query(selector: &str) -> Vec<Value> {
+        evaluate(&parse(selector).unwrap(), sample())
+    }
+
+    #[test]
+    fn test_selects_fields_through_filters() {
+        assert_eq!(
+            query("stacks[active].entries[?submitted==false].branch"),
+            vec![json!("auth-2"), json!("auth-3")]
+        );
+        assert_eq!(query("stacks.name"), vec![json!("auth"), json!("billing")]);
+        assert_eq!(
+            query("stacks[?base_branch=='develop'].name"),
+            vec![json!("billing")]
+        );
+        assert_eq!(query("active.entries[-1].title"), vec![json!("Add auth-3")]);
+        assert_eq!(
+            query("active.entries[?position>=2].position"),
+            vec![json!(2), json!(3)]
+        );
+        assert_eq!(
+            query("active.entries[submitted].pull_request_id"),
+            vec![json!("12")]
+        );
+        assert_eq!(query(".active.name"), vec![json!("auth")]);
+        assert!(query("stacks[5].name").is_empty());
+        assert_eq!(query("stacks[*]").len(), 2);
+    }
+
+    #[test]
+    fn test_rejects_malformed_selectors() {
+        for selector in [
+            "stacks[active",
+            "stacks..name",
+            "stacks[==1]",
+            "stacks name",
+        ] {
+            let err = parse(selector).unwrap_err();
+            assert!(
+                err.to_string().contains("Invalid selector"),
+                "{selector}: {err}"
+            );
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::cli::commands::backups::format_age;
+use crate::cli::output::Output;
+use crate::cli::prompt::{prompter, Prompter};
+use crate::errors::{CascadeError, Result};
+use crate::git::{find_repository_root, GitRepository};
+use crate::stack::{AutoStash, OperationState, StackManager, SyncState};
+use crate::t;
+use std::env;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Point at an operation an earlier run left unfinished. Starting another operation
+/// (`starting`) on top of it is refused; anything else only warns.
+pub fn check_interrupted(repo_root: &Path, starting: Option<&str>) -> Result<()> {
+    let Some(state) = OperationState::load(repo_root)? else {
+        return Ok(());
+    };
+
+    explain(&state);
+    match starting {
+        Some(operation) => Err(CascadeError::validation(t!(
+            "recover-blocked",
+            operation = operation,
+            command = state.command_line()
+        ))
+        .with_hint(t!("recover-blocked-hint"))),
+        None => {
+            Output::tip(t!("recover-tip"));
+            Output::spacing();
+            Ok(())
+        }
+    }
+}
+
+/// Resume or abort the operation an earlier run left unfinished
+pub async fn run(resume_operation: bool, abort_operation: bool, yes: bool) -> Result<()> {
+    let repo_root = repo_root()?;
+    let Some(state) = OperationState::load(&repo_root)? else {
+        Output::info(t!("recover-none"));
+        return Ok(());
+    };
+
+    explain(&state);
+    Output::spacing();
+
+    if resume_operation {
+        return resume(&repo_root, &state).await;
+    }
+    if abort_operation {
+        return abort(&repo_root, &state, yes);
+    }
+
+    if !std::io::stdin().is_terminal() {
+        Output::tip(t!("recover-resume-tip"));
+        Output::tip(t!("recover-abort-tip"));
+        return Ok(());
+    }
+    let choice = prompter().select(
+        &t!("recover-prompt"),
+        &[
+            t!("recover-choice-resume", command = state.command_line()),
+            t!("recover-choice-abort"),
+            t!("recover-choice-leave"),
+        ],
+        0,
+    )?;
+    match choice {
+        0 => resume(&repo_root, &state).await,
+        1 => abort(&repo_root, &state, true),
+        _ => Ok(()),
+    }
+}
+
+fn explain(state: &OperationState) {
+    Output::warning(t!(
+        "recover-interrupted",
+        command = state.command_line(),
+        age = format_age(state.started_at)
+    ));
+    if let Some(branch) = &state.original_branch {
+        Output::sub_item(t!("recover-started-on", branch = branch.as_str()));
+    }
+    Output::sub_item(t!("recover-process", pid = state.pid.to_string()));
+}
+
+/// Carry on from where the operation stopped: a paused sync or rebase is continued,
+/// anything else is run again from a clean state
+async fn resume(repo_root: &Path, state: &OperationState) -> Result<()> {
+    let git_dir = crate::git::resolve_git_dir(repo_root)?;
+    if SyncState::exists(repo_root) && git_dir.join("CHERRY_PICK_HEAD").exists() {
+        OperationState::delete(repo_root)?;
+        return super::stack::continue_sync().await;
+    }
+    if rebase_in_progress(&git_dir) {
+        OperationState::delete(repo_root)?;
+        return super::stack::continue_rebase().await;
+    }
+
+    clean_up_partial_step(repo_root, state)?;
+    if let Some(stash) = AutoStash::load(repo_root)? {
+        stash.restore(repo_root)?;
+    }
+    OperationState::delete(repo_root)?;
+
+    Output::info(t!("recover-running-again", command = state.command_line()));
+    let executable = env::current_exe()
+        .map_err(|e| CascadeError::config(format!("Could not locate the ca executable: {e}")))?;
+    let status = Command::new(executable)
+        .args(&state.args)
+        .current_dir(repo_root)
+        .status()
+        .map_err(CascadeError::Io)?;
+    if !status.success() {
+        return Err(CascadeError::config(t!(
+            "recover-failed-again",
+            command = state.command_line()
+        )));
+    }
+    Ok(())
+}
+
+/// Undo the operation: stop any half-applied git step, put the stacks back as the
+/// snapshot taken at its start recorded them and return to the original branch
+fn abort(repo_root: &Path, state: &OperationState, yes: bool) -> Result<()> {
+    clean_up_partial_step(repo_root, state)?;
+
+    let config_dir = crate::config::get_repo_config_dir(repo_root)?;
+    match state.snapshot(&config_dir)? {
+        Some(snapshot) => {
+            let confirmed = yes
+                || prompter().confirm(
+                    &t!(
+                        "recover-restore-prompt",
+                        snapshot = snapshot.id.as_str(),
+                        operation = snapshot.manifest.operation.as_str()
+                    ),
+                    true,
+                )?;
+            if confirmed {
+                let mut manager = StackManager::new(repo_root)?;
+                match manager.restore_snapshot(&snapshot) {
+                    Ok(result) => Output::success(t!(
+                        "recover-restored",
+                        snapshot = snapshot.id.as_str(),
+                        count = result.restored.len()
+                    )),
+                    Err(e) => {
+                        Output::warning(t!(
+                            "recover-restore-failed",
+                            snapshot = snapshot.id.as_str(),
+                            error = e.to_string()
+                        ));
+                        Output::tip(t!("recover-restore-later", snapshot = snapshot.id.as_str()));
+                    }
+                }
+            }
+        }
+        None if state.operation == "pop" => {
+            Output::tip(t!("recover-pop-tip"));
+        }
+        None => Output::info(t!("recover-no-snapshot")),
+    }
+
+    if let Some(stash) = AutoStash::load(repo_root)? {
+        stash.restore(repo_root)?;
+    }
+    OperationState::delete(repo_root)?;
+    Output::success(t!("recover-aborted", command = state.command_line()));
+    Output::tip(t!("recover-validate-tip"));
+    Ok(())
+}
+
+/// Stop the git step the operation died in, return to the branch it started on and
+/// remove the temporary branches of an unfinished sync
+fn clean_up_partial_step(repo_root: &Path, state: &OperationState) -> Result<()> {
+    let git_dir = crate::git::resolve_git_dir(repo_root)?;
+    let git_repo = GitRepository::open(repo_root)?;
+
+    if rebase_in_progress(&git_dir) {
+        Output::info(t!("recover-aborting-rebase"));
+        run_git(repo_root, &["rebase", "--abort"])?;
+    } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+        Output::info(t!("recover-aborting-cherry-pick"));
+        run_git(repo_root, &["cherry-pick", "--abort"])?;
+    } else if git_repo.has_operation_in_progress() {
+        git_repo.cleanup_state()?;
+    }
+
+    if let Some(branch) = &state.original_branch {
+        let current = git_repo.get_current_branch().ok();
+        if current.as_ref() != Some(branch) && git_repo.branch_exists(branch) {
+            Output::info(t!("recover-returning", branch = branch.as_str()));
+            if let Err(e) = git_repo.checkout_branch_unsafe(branch) {
+                Output::warning(t!(
+                    "recover-checkout-failed",
+                    branch = branch.as_str(),
+                    error = e.to_string()
+                ));
+            }
+        }
+    }
+
+    if let Ok(sync_state) = SyncState::load(repo_root) {
+        for temp_branch in &sync_state.temp_branches {
+            if let Err(e) = git_repo.delete_branch_unsafe(temp_branch) {
+                tracing::warn!("Could not delete temp branch '{}': {}", temp_branch, e);
+            }
+        }
+        SyncState::delete(repo_root)?;
+    }
+    Ok(())
+}
+
+fn rebase_in_progress(git_dir: &Path) -> bool {
+    git_dir.join("REBASE_HEAD").exists()
+        || git_dir.join("rebase-merge").exists()
+        || git_dir.join("rebase-apply").exists()
+}
+
+fn run_git(repo_root: &Path, args: &[&str]) -> Result<()> {
+    let output = Command::new("git")
+        .args(args)
+        .env("CASCADE_SKIP_HOOKS", "1")
+        .current_dir(repo_root)
+        .output()
+        .map_err(CascadeError::Io)?;
+    if !output.status.success() {
+        return Err(CascadeError::branch(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+fn repo_root() -> Result<PathBuf> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))
+}
@@ -0,0 +1,355 @@
+use crate::cli::output::Output;
+use crate::cli::prompt::{prompter, Prompter};
+use crate::errors::{CascadeError, Result};
+use crate::git::find_repository_root;
+use crate::stack::{PrSummaryCache, Stack, StackManager};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::env;
+
+/// Characters of a matched line shown as context
+const CONTEXT_WIDTH: usize = 72;
+
+/// What a query matched on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Field {
+    StackName,
+    Description,
+    Label,
+    Branch,
+    Message,
+    PrTitle,
+}
+
+impl Field {
+    fn label(self) -> &'static str {
+        match self {
+            Self::StackName => "stack name",
+            Self::Description => "description",
+            Self::Label => "label",
+            Self::Branch => "branch",
+            Self::Message => "commit message",
+            Self::PrTitle => "PR title",
+        }
+    }
+}
+
+/// A stack, or an entry of one, that matched the query
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct SearchMatch {
+    stack: String,
+    active: bool,
+    /// 1-based position of the entry; `None` when the stack itself matched
+    entry: Option<usize>,
+    branch: Option<String>,
+    /// First line of the entry's commit message
+    summary: Option<String>,
+    pull_request_id: Option<String>,
+    merged: bool,
+    field: Field,
+    /// The line that matched, shortened around the match
+    context: String,
+}
+
+/// Find stacks, entries and pull requests whose names, branches, commit messages or
+/// cached PR titles contain `query`, then optionally switch to one or open its PR
+pub async fn run(query: String, switch: bool, open: bool, json: bool) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(CascadeError::validation("The search text can't be empty"));
+    }
+
+    let manager = StackManager::new(&repo_root)?;
+    let stacks = manager.get_all_stacks_objects()?;
+    let titles = PrSummaryCache::load(&repo_root).titles;
+    let matches = find_matches(&stacks, &titles, query);
+
+    if json {
+        Output::data(serde_json::to_string_pretty(&matches)?);
+        return Ok(());
+    }
+    if matches.is_empty() {
+        Output::info(format!("Nothing matches '{query}'"));
+        Output::tip(
+            "PR titles are searched as of the last 'ca prs' or 'ca repo --refresh'; \
+             run one of them to refresh",
+        );
+        return Ok(());
+    }
+
+    if switch {
+        return switch_to_match(&matches).await;
+    }
+    if open {
+        return open_match(&matches);
+    }
+
+    show_matches(&matches);
+    Output::spacing();
+    Output::tip(format!(
+        "Switch with 'ca search \"{query}\" --switch', or open a pull request with --open"
+    ));
+    Ok(())
+}
+
+/// Matches of `query` (ignoring case), active stack first, then by stack name and position.
+/// Each stack and entry is reported once, for the first field it matched on.
+fn find_matches(
+    stacks: &[Stack],
+    titles: &HashMap<String, String>,
+    query: &str,
+) -> Vec<SearchMatch> {
+    let query = query.to_lowercase();
+    let mut ordered: Vec<&Stack> = stacks.iter().collect();
+    ordered.sort_by_key(|stack| (!stack.is_active, stack.name.to_lowercase()));
+
+    let mut matches = Vec::new();
+    for stack in ordered {
+        let stack_match = [
+            (Field::StackName, stack.name.as_str()),
+            (
+                Field::Description,
+                stack.description.as_deref().unwrap_or(""),
+            ),
+        ]
+        .into_iter()
+        .chain(stack.labels().iter().map(|l| (Field::Label, l.as_str())))
+        .find_map(|(field, text)| Some((field, find_in(text, &query)?)));
+        if let Some((field, context)) = stack_match {
+            matches.push(SearchMatch {
+                stack: stack.name.clone(),
+                active: stack.is_active,
+                entry: None,
+                branch: None,
+                summary: None,
+                pull_request_id: None,
+                merged: false,
+                field,
+                context,
+            });
+        }
+
+        for (index, entry) in stack.entries.iter().enumerate() {
+            let title = entry
+                .pull_request_id
+                .as_ref()
+                .and_then(|id| titles.get(id))
+                .map(String::as_str)
+                .unwrap_or("");
+            let entry_match = [
+                (Field::Branch, entry.branch.as_str()),
+                (Field::Message, entry.message.as_str()),
+                (Field::PrTitle, title),
+            ]
+            .into_iter()
+            .chain(entry.labels.iter().map(|l| (Field::Label, l.as_str())))
+            .find_map(|(field, text)| Some((field, find_in(text, &query)?)));
+            if let Some((field, context)) = entry_match {
+                matches.push(SearchMatch {
+                    stack: stack.name.clone(),
+                    active: stack.is_active,
+                    entry: Some(index + 1),
+                    branch: Some(entry.branch.clone()),
+                    summary: entry.message.lines().next().map(str::to_string),
+                    pull_request_id: entry.pull_request_id.clone(),
+                    merged: entry.is_merged,
+                    field,
+                    context,
+                });
+            }
+        }
+    }
+    matches
+}
+
+/// The first line of `text` containing `query` (already lowercase), shortened around it
+fn find_in(text: &str, query: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let lower = line.to_lowercase();
+        let position = lower.find(query)?;
+        // Lowercasing can change byte lengths outside ASCII; fall back to the line start
+        let position = if lower.len() == line.len() && line.is_char_boundary(position) {
+            position
+        } else {
+            0
+        };
+        Some(snippet(line.trim_end(), position))
+    })
+}
+
+/// `line`, cut to [`CONTEXT_WIDTH`] characters with the byte offset `position` in view
+fn snippet(line: &str, position: usize) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() <= CONTEXT_WIDTH {
+        return line.to_string();
+    }
+    let at = line[..position.min(line.len())].chars().count();
+    let start = at
+        .saturating_sub(CONTEXT_WIDTH / 3)
+        .min(chars.len() - CONTEXT_WIDTH);
+    let end = start + CONTEXT_WIDTH;
+    format!(
+        "{}{}{}",
+        if start > 0 { "…" } else { "" },
+        chars[start..end].iter().collect::<String>(),
+        if end < chars.len() { "…" } else { "" }
+    )
+}
+
+/// An entry match as one line: branch, commit summary and pull request
+fn entry_line(found: &SearchMatch) -> String {
+    let mut line = found.branch.clone().unwrap_or_default();
+    if let Some(summary) = &found.summary {
+        line.push_str(&format!(" - {summary}"));
+    }
+    if let Some(pr_id) = &found.pull_request_id {
+        line.push_str(&format!(" · PR #{pr_id}"));
+    }
+    if found.merged {
+        line.push_str(" · merged");
+    }
+    line
+}
+
+fn show_matches(matches: &[SearchMatch]) {
+    let mut current_stack: Option<&str> = None;
+    for found in matches {
+        if current_stack != Some(found.stack.as_str()) {
+            current_stack = Some(&found.stack);
+            let marker = if found.active { " (active)" } else { "" };
+            Output::section(format!("Stack '{}'{marker}", found.stack));
+        }
+        let context = format!("{}: {}", found.field.label(), found.context);
+        match found.entry {
+            Some(position) => {
+                Output::numbered_item(position, entry_line(found));
+                // Branches and one-line messages are already on the line above
+                let shown = found.field == Field::Branch
+                    || found.summary.as_deref() == Some(found.context.as_str());
+                if !shown {
+                    Output::sub_item(context);
+                }
+            }
+            None => Output::sub_item(context),
+        }
+    }
+}
+
+async fn switch_to_match(matches: &[SearchMatch]) -> Result<()> {
+    let mut stacks: Vec<&SearchMatch> = Vec::new();
+    for found in matches {
+        if !stacks.iter().any(|s| s.stack == found.stack) {
+            stacks.push(found);
+        }
+    }
+    let chosen = if stacks.len() == 1 {
+        stacks[0]
+    } else {
+        let names: Vec<String> = stacks.iter().map(|s| s.stack.clone()).collect();
+        // The active stack is listed first, so suggest the next one
+        let default = stacks.iter().position(|s| !s.active).unwrap_or(0);
+        stacks[prompter().select("Switch to which stack?", &names, default)?]
+    };
+    if chosen.active {
+        Output::info(format!("Stack '{}' is already active", chosen.stack));
+        return Ok(());
+    }
+    super::stack::switch(chosen.stack.clone()).await
+}
+
+fn open_match(matches: &[SearchMatch]) -> Result<()> {
+    let candidates: Vec<&SearchMatch> = matches
+        .iter()
+        .filter(|found| found.pull_request_id.is_some())
+        .collect();
+    if candidates.is_empty() {
+        return Err(
+            CascadeError::validation("None of the matching entries has a pull request")
+                .with_hint("Submit entries with 'ca submit'"),
+        );
+    }
+    let chosen = if candidates.len() == 1 {
+        candidates[0]
+    } else {
+        let items: Vec<String> = candidates
+            .iter()
+            .map(|found| {
+                format!(
+                    "{} #{}: {}",
+                    found.stack,
+                    found.entry.unwrap_or_default(),
+                    entry_line(found)
+                )
+            })
+            .collect();
+        candidates[prompter().select("Open which pull request?", &items, 0)?]
+    };
+    let pr_id = chosen.pull_request_id.as_deref().unwrap_or_default();
+    super::browse::open_pull_request(pr_id, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_matches_in_names_messages_and_titles() {
+        let mut auth = Stack::new("auth-rework".into(), "main".into(), None);
+        auth.push_entry(
+            "auth-1".into(),
+            "a".repeat(40),
+            "Add token refresh\n\nRetries the OAuth handshake".into(),
+        );
+        let login_id = auth.push_entry("auth-2".into(), "b".repeat(40), "Login page".into());
+        auth.mark_entry_submitted(&login_id, "42".into());
+
+        let mut billing = Stack::new("billing".into(), "main".into(), None);
+        billing.is_active = true;
+        billing.push_entry("invoices".into(), "c".repeat(40), "Oauth scopes".into());
+
+        let titles = HashMap::from([("42".to_string(), "Redesign the OAUTH login".to_string())]);
+        let matches = find_matches(&[auth, billing], &titles, "oauth");
+
+        let found: Vec<(&str, Option<usize>, Field)> = matches
+            .iter()
+            .map(|m| (m.stack.as_str(), m.entry, m.field))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                // The active stack comes first
+                ("billing", Some(1), Field::Message),
+                ("auth-rework", Some(1), Field::Message),
+                ("auth-rework", Some(2), Field::PrTitle),
+            ]
+        );
+        assert_eq!(matches[1].context, "Retries the OAuth handshake");
+        assert_eq!(matches[2].pull_request_id.as_deref(), Some("42"));
+
+        let by_name = find_matches(
+            &[Stack::new("Billing".into(), "main".into(), None)],
+            &titles,
+            "bill",
+        );
+        assert_eq!(by_name[0].field, Field::StackName);
+        assert_eq!(by_name[0].entry, None);
+    }
+
+    #[test]
+    fn test_snippet_keeps_match_in_view() {
+        let line = format!("{}needle{}", "x".repeat(100), "y".repeat(100));
+        let context = find_in(&line, "needle").unwrap();
+        assert!(context.contains("needle"));
+        assert!(context.starts_with('…') && context.ends_with('…'));
+        assert_eq!(context.chars().count(), CONTEXT_WIDTH + 2);
+
+        assert_eq!(find_in("short line", "line").unwrap(), "short line");
+        assert_eq!(find_in("nothing here", "needle"), None);
+    }
+}
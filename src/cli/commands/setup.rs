@@ -1,8 +1,8 @@
 use crate::cli::output::Output;
+use crate::cli::prompt::{prompter, Prompter};
 use crate::config::{get_repo_config_dir, initialize_repo, Settings};
 use crate::errors::{CascadeError, Result};
 use crate::git::{find_repository_root, GitRepository};
-use dialoguer::{theme::ColorfulTheme, Confirm, Input};
 use std::env;
 use tracing::{info, warn};
 
@@ -11,7 +11,7 @@ pub async fn run(force: bool) -> Result<()> {
     Output::section("Welcome to Cascade CLI Setup!");
     Output::divider();
     Output::info("This wizard will help you configure Cascade for your repository.");
-    println!();
+    Output::spacing();
 
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
@@ -31,11 +31,10 @@ pub async fn run(force: bool) -> Result<()> {
     // Step 2: Check if already initialized
     let config_dir = get_repo_config_dir(&repo_root)?;
     if config_dir.exists() && !force {
-        let reinitialize = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Cascade is already initialized. Do you want to reconfigure?")
-            .default(false)
-            .interact()
-            .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+        let reinitialize = prompter().confirm(
+            "Cascade is already initialized. Do you want to reconfigure?",
+            false,
+        )?;
 
         if !reinitialize {
             Output::success("Setup cancelled. Run with --force to reconfigure.");
@@ -101,11 +100,8 @@ pub async fn run(force: bool) -> Result<()> {
 
     // Step 9: Setup completions (optional)
     Output::progress("Step 7: Shell completions");
-    let install_completions = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Would you like to install shell completions?")
-        .default(true)
-        .interact()
-        .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+    let install_completions =
+        prompter().confirm("Would you like to install shell completions?", true)?;
 
     if install_completions {
         match crate::cli::commands::completions::install_completions(None) {
@@ -121,11 +117,10 @@ pub async fn run(force: bool) -> Result<()> {
 
     // Step 10: Install Git hooks (recommended)
     Output::progress("Step 8: Git hooks");
-    let install_hooks = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Would you like to install Git hooks for enhanced workflow?")
-        .default(true)
-        .interact()
-        .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+    let install_hooks = prompter().confirm(
+        "Would you like to install Git hooks for enhanced workflow?",
+        true,
+    )?;
 
     if install_hooks {
         match crate::cli::commands::hooks::install_essential().await {
@@ -141,8 +136,8 @@ pub async fn run(force: bool) -> Result<()> {
                 warn!("   ⚠️  Failed to install hooks: {}", e);
                 if e.to_string().contains("Git hooks directory not found") {
                     Output::tip("This doesn't appear to be a Git repository.");
-                    println!("      Please ensure you're running this command from within a Git repository.");
-                    println!("      You can initialize git with: git init");
+                    Output::line("      Please ensure you're running this command from within a Git repository.");
+                    Output::line("      You can initialize git with: git init");
                 } else {
                     Output::tip("You can install them later with: ca hooks install");
                 }
@@ -152,13 +147,10 @@ pub async fn run(force: bool) -> Result<()> {
 
     // Step 11: Configure PR description template (optional)
     Output::progress("Step 9: PR Description Template");
-    let setup_template = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt(
-            "Would you like to configure a PR description template? (will be used for ALL PRs)",
-        )
-        .default(false)
-        .interact()
-        .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+    let setup_template = prompter().confirm(
+        "Would you like to configure a PR description template? (will be used for ALL PRs)",
+        false,
+    )?;
 
     if setup_template {
         configure_pr_template(&config_path).await?;
@@ -170,19 +162,19 @@ pub async fn run(force: bool) -> Result<()> {
     // Success summary
     Output::section("Setup Complete!");
     Output::success("Cascade CLI is now fully configured for your repository.");
-    println!();
+    Output::spacing();
     Output::info("Configuration includes:");
     Output::bullet("✅ Git user settings (name and email)");
     Output::bullet("✅ Bitbucket Server integration");
     Output::bullet("✅ Essential Git hooks for enhanced workflow");
     Output::bullet("✅ Shell completions (if selected)");
-    println!();
+    Output::spacing();
     Output::tip("Next steps:");
     Output::bullet("Create your first stack: ca stack create \"My Feature\"");
     Output::bullet("Push commits to the stack: ca push");
     Output::bullet("Submit for review: ca submit");
     Output::bullet("Check status: ca status");
-    println!();
+    Output::spacing();
     Output::tip("Learn more:");
     Output::bullet("Run 'ca --help' for all commands");
     Output::bullet("Run 'ca doctor' to verify your setup");
@@ -199,8 +191,6 @@ pub async fn run(force: bool) -> Result<()> {
 
 /// Configure Git user settings (name and email)
 async fn configure_git_user(git_repo: &GitRepository) -> Result<()> {
-    let theme = ColorfulTheme::default();
-
     // Check current git configuration
     let repo_path = git_repo.path();
     let git_repo_inner = git2::Repository::open(repo_path)
@@ -231,11 +221,7 @@ async fn configure_git_user(git_repo: &GitRepository) -> Result<()> {
             Output::sub_item(format!("Name: {name}"));
             Output::sub_item(format!("Email: {email}"));
 
-            let keep_current = Confirm::with_theme(&theme)
-                .with_prompt("Keep current Git user settings?")
-                .default(true)
-                .interact()
-                .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+            let keep_current = prompter().confirm("Keep current Git user settings?", true)?;
 
             if keep_current {
                 Output::success("Using existing Git user configuration");
@@ -265,26 +251,20 @@ async fn configure_git_user(git_repo: &GitRepository) -> Result<()> {
     }
 
     // Prompt for user information
-    println!("\n👤 Git User Configuration");
-    println!("   This information will be used for all git commits and Cascade operations.");
+    Output::line("\n👤 Git User Configuration");
+    Output::line("   This information will be used for all git commits and Cascade operations.");
 
-    let name: String = Input::with_theme(&theme)
-        .with_prompt("Your name")
-        .with_initial_text(current_name.unwrap_or_default())
-        .validate_with(|input: &String| -> std::result::Result<(), &str> {
+    let name: String =
+        prompter().input("Your name", &current_name.unwrap_or_default(), |input| {
             if input.trim().is_empty() {
                 Err("Name cannot be empty")
             } else {
                 Ok(())
             }
-        })
-        .interact_text()
-        .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
-
-    let email: String = Input::with_theme(&theme)
-        .with_prompt("Your email")
-        .with_initial_text(current_email.unwrap_or_default())
-        .validate_with(|input: &String| -> std::result::Result<(), &str> {
+        })?;
+
+    let email: String =
+        prompter().input("Your email", &current_email.unwrap_or_default(), |input| {
             if input.trim().is_empty() {
                 Err("Email cannot be empty")
             } else if !input.contains('@') {
@@ -292,16 +272,13 @@ async fn configure_git_user(git_repo: &GitRepository) -> Result<()> {
             } else {
                 Ok(())
             }
-        })
-        .interact_text()
-        .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+        })?;
 
     // Ask about scope (global vs local)
-    let use_global = Confirm::with_theme(&theme)
-        .with_prompt("Set globally for all Git repositories? (otherwise only for this repository)")
-        .default(true)
-        .interact()
-        .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+    let use_global = prompter().confirm(
+        "Set globally for all Git repositories? (otherwise only for this repository)",
+        true,
+    )?;
 
     // Set the configuration using git commands for reliability
     let scope_flag = if use_global { "--global" } else { "--local" };
@@ -430,79 +407,61 @@ fn parse_bitbucket_url(url: &str) -> Option<(String, String, String)> {
 async fn configure_bitbucket_interactive(
     auto_config: Option<(String, String, String)>,
 ) -> Result<BitbucketConfig> {
-    let theme = ColorfulTheme::default();
-
     // Server URL
     let default_url = auto_config
         .as_ref()
         .map(|(url, _, _)| url.as_str())
         .unwrap_or("");
-    let url: String = Input::with_theme(&theme)
-        .with_prompt("Bitbucket Server URL")
-        .with_initial_text(default_url)
-        .validate_with(|input: &String| -> std::result::Result<(), &str> {
-            if input.starts_with("http://") || input.starts_with("https://") {
-                Ok(())
-            } else {
-                Err("URL must start with http:// or https://")
-            }
-        })
-        .interact_text()
-        .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+    let url: String = prompter().input("Bitbucket Server URL", default_url, |input| {
+        if input.starts_with("http://") || input.starts_with("https://") {
+            Ok(())
+        } else {
+            Err("URL must start with http:// or https://")
+        }
+    })?;
 
     // Project key
     let default_project = auto_config
         .as_ref()
         .map(|(_, project, _)| project.as_str())
         .unwrap_or("");
-    let project: String = Input::with_theme(&theme)
-        .with_prompt("Project key (usually uppercase)")
-        .with_initial_text(default_project)
-        .validate_with(|input: &String| -> std::result::Result<(), &str> {
+    let project: String = prompter().input(
+        "Project key (usually uppercase)",
+        default_project,
+        |input| {
             if input.trim().is_empty() {
                 Err("Project key cannot be empty")
             } else {
                 Ok(())
             }
-        })
-        .interact_text()
-        .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+        },
+    )?;
 
     // Repository slug
     let default_repo = auto_config
         .as_ref()
         .map(|(_, _, repo)| repo.as_str())
         .unwrap_or("");
-    let repo: String = Input::with_theme(&theme)
-        .with_prompt("Repository slug")
-        .with_initial_text(default_repo)
-        .validate_with(|input: &String| -> std::result::Result<(), &str> {
-            if input.trim().is_empty() {
-                Err("Repository slug cannot be empty")
-            } else {
-                Ok(())
-            }
-        })
-        .interact_text()
-        .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+    let repo: String = prompter().input("Repository slug", default_repo, |input| {
+        if input.trim().is_empty() {
+            Err("Repository slug cannot be empty")
+        } else {
+            Ok(())
+        }
+    })?;
 
     // Authentication token
-    println!("\n🔐 Authentication Setup");
-    println!("   Cascade needs a Personal Access Token to interact with Bitbucket.");
-    println!("   You can create one at: {url}/plugins/servlet/access-tokens/manage");
-    println!("   Required permissions: Repository Read, Repository Write");
+    Output::line("\n🔐 Authentication Setup");
+    Output::line("   Cascade needs a Personal Access Token to interact with Bitbucket.");
+    Output::line(format!(
+        "   You can create one at: {url}/plugins/servlet/access-tokens/manage"
+    ));
+    Output::line("   Required permissions: Repository Read, Repository Write");
 
-    let configure_token = Confirm::with_theme(&theme)
-        .with_prompt("Configure authentication token now?")
-        .default(true)
-        .interact()
-        .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+    let configure_token = prompter().confirm("Configure authentication token now?", true)?;
 
     let token = if configure_token {
-        let token: String = Input::with_theme(&theme)
-            .with_prompt("Personal Access Token")
-            .interact_text()
-            .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+        let token: String = prompter().input("Personal Access Token", "", |_| Ok(()))?;
 
         if token.trim().is_empty() {
             None
@@ -543,34 +502,28 @@ async fn test_bitbucket_connection(settings: &Settings) -> Result<()> {
 
 /// Configure PR description template interactively
 async fn configure_pr_template(config_path: &std::path::Path) -> Result<()> {
-    let theme = ColorfulTheme::default();
-
-    println!("   Configure a markdown template for PR descriptions.");
-    println!("   This template will be used for ALL PRs (overrides --description).");
-    println!("   You can use markdown formatting, variables, etc.");
-    println!("   ");
-    println!("   Example template:");
-    println!("   ## Summary");
-    println!("   Brief description of changes");
-    println!("   ");
-    println!("   ## Testing");
-    println!("   - [ ] Unit tests pass");
-    println!("   - [ ] Manual testing completed");
-
-    let use_example = Confirm::with_theme(&theme)
-        .with_prompt("Use the example template above?")
-        .default(true)
-        .interact()
-        .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+    Output::line("   Configure a markdown template for PR descriptions.");
+    Output::line("   This template will be used for ALL PRs (overrides --description).");
+    Output::line("   You can use markdown formatting, variables, etc.");
+    Output::line("   ");
+    Output::line("   Example template:");
+    Output::line("   ## Summary");
+    Output::line("   Brief description of changes");
+    Output::line("   ");
+    Output::line("   ## Testing");
+    Output::line("   - [ ] Unit tests pass");
+    Output::line("   - [ ] Manual testing completed");
+
+    let use_example = prompter().confirm("Use the example template above?", true)?;
 
     let template = if use_example {
         Some("## Summary\nBrief description of changes\n\n## Testing\n- [ ] Unit tests pass\n- [ ] Manual testing completed\n\n## Checklist\n- [ ] Code review completed\n- [ ] Documentation updated".to_string())
     } else {
-        let custom_template: String = Input::with_theme(&theme)
-            .with_prompt("Enter your PR description template (use \\n for line breaks)")
-            .allow_empty(true)
-            .interact_text()
-            .map_err(|e| CascadeError::config(format!("Input error: {e}")))?;
+        let custom_template: String = prompter().input(
+            "Enter your PR description template (use \\n for line breaks)",
+            "",
+            |_| Ok(()),
+        )?;
 
         if custom_template.trim().is_empty() {
             None
@@ -0,0 +1,105 @@
+use crate::cli::commands::backups::format_age;
+use crate::cli::output::Output;
+use crate::cli::prompt::{prompter, Prompter};
+use crate::errors::{CascadeError, Result};
+use crate::git::find_repository_root;
+use crate::stack::snapshots::{find_snapshot, list_snapshots};
+use crate::stack::StackManager;
+use std::env;
+use std::path::PathBuf;
+
+/// List snapshots, newest first
+pub async fn list() -> Result<()> {
+    let config_dir = crate::config::get_repo_config_dir(&repo_root()?)?;
+    let snapshots = list_snapshots(&config_dir)?;
+
+    if snapshots.is_empty() {
+        Output::info("No snapshots found");
+        return Ok(());
+    }
+
+    Output::section(format!("📸 Snapshots ({})", snapshots.len()));
+    for snapshot in &snapshots {
+        let manifest = &snapshot.manifest;
+        Output::sub_item(format!(
+            "{}  before {}, {} branch(es), on {} ({})",
+            snapshot.id,
+            manifest.operation,
+            manifest.branches.len(),
+            manifest
+                .current_branch
+                .as_deref()
+                .unwrap_or("detached HEAD"),
+            format_age(manifest.created_at)
+        ));
+    }
+
+    Output::spacing();
+    Output::tip("Restore with: ca snapshots restore [<id>]");
+    Ok(())
+}
+
+/// Put the stacks and their branches back the way the snapshot `id` (or the newest one)
+/// recorded them
+pub async fn restore(id: Option<String>, yes: bool) -> Result<()> {
+    let repo_root = repo_root()?;
+    let mut manager = StackManager::new(&repo_root)?;
+    let config_dir = crate::config::get_repo_config_dir(&repo_root)?;
+    let snapshot = find_snapshot(&config_dir, id.as_deref())?;
+
+    // Branches the restore would move
+    let moves: Vec<(&String, &String)> = snapshot
+        .manifest
+        .branches
+        .iter()
+        .filter(|(branch, commit)| {
+            manager.git_repo().get_branch_head(branch).ok().as_ref() != Some(*commit)
+        })
+        .collect();
+
+    Output::section(format!(
+        "Snapshot {} (before {}, {})",
+        snapshot.id,
+        snapshot.manifest.operation,
+        format_age(snapshot.manifest.created_at)
+    ));
+    if moves.is_empty() {
+        Output::sub_item("Every branch already matches; only the stack metadata is restored");
+    }
+    for (branch, commit) in &moves {
+        Output::sub_item(format!("{branch} → {}", &commit[..8]));
+    }
+
+    if !yes {
+        let confirmed = prompter().confirm("Restore this snapshot?", false)?;
+        if !confirmed {
+            Output::info("Restore cancelled");
+            return Ok(());
+        }
+    }
+
+    let result = manager.restore_snapshot(&snapshot)?;
+    Output::success(format!(
+        "Restored snapshot {} ({} branch(es) moved)",
+        snapshot.id,
+        result.restored.len()
+    ));
+    for branch in &result.missing {
+        Output::warning(format!(
+            "'{branch}' was not restored: its snapshotted commit no longer exists"
+        ));
+    }
+    Output::sub_item(format!(
+        "State before the restore saved as: {}",
+        result.previous.id
+    ));
+    Output::tip("Run 'ca validate' to check the stacks match their branches");
+    Ok(())
+}
+
+fn repo_root() -> Result<PathBuf> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))
+}
@@ -1,12 +1,20 @@
-use crate::bitbucket::BitbucketIntegration;
+use crate::bitbucket::{BitbucketIntegration, PullRequestRecovery};
+use crate::cli::commands::push::{pick_commits, Grouping, PushArgs, PushSpec, Squash};
 use crate::cli::output::Output;
+use crate::cli::prompt::{self, prompter, Prompter};
+use crate::config::{RemovedPrAction, SquashMessage};
 use crate::errors::{CascadeError, Result};
 use crate::git::{find_repository_root, GitRepository};
-use crate::stack::{CleanupManager, CleanupOptions, CleanupResult, StackManager, StackStatus};
-use clap::{Subcommand, ValueEnum};
-use dialoguer::{theme::ColorfulTheme, Confirm};
+use crate::stack::commit_selector::unpushed_commits;
+use crate::stack::{
+    AutoStash, CleanupManager, CleanupOptions, CleanupResult, ClosedPrs, CommitSelector,
+    LifecycleHook, PoppedEntries, Stack, StackManager, StackStatus,
+};
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand, ValueEnum};
 // Progress bars removed - using professional Output module instead
 use std::env;
+use std::io::IsTerminal;
 use tracing::{debug, warn};
 use uuid::Uuid;
 
@@ -39,6 +47,102 @@ impl From<MergeStrategyArg> for crate::bitbucket::pull_request::MergeStrategy {
     }
 }
 
+/// Output format for `ca stacks list`
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListFormat {
+    /// Aligned table (see --columns)
+    #[default]
+    Table,
+    /// Full stack details as JSON
+    Json,
+    /// One stack name per line
+    Name,
+    /// One stack ID per line
+    Id,
+    /// `name: Status` per line
+    Status,
+}
+
+/// Filters for `ca stacks list`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StackFilter {
+    /// The base branch has moved past the bottom of the stack
+    NeedsSync,
+    /// The stack has unresolved conflicts
+    Conflicted,
+    /// At least one entry has an open pull request
+    HasOpenPrs,
+}
+
+/// Sort order for `ca stacks list`
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListSort {
+    /// Alphabetically by name
+    #[default]
+    Name,
+    /// Most recent activity first
+    Recent,
+    /// Oldest stack first
+    Created,
+    /// Most entries first
+    Size,
+}
+
+/// Table columns for `ca stacks list`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListColumn {
+    Name,
+    Status,
+    Entries,
+    Prs,
+    Base,
+    Branches,
+    Updated,
+    Created,
+    Id,
+    Labels,
+}
+
+const DEFAULT_LIST_COLUMNS: &[ListColumn] = &[
+    ListColumn::Name,
+    ListColumn::Status,
+    ListColumn::Entries,
+    ListColumn::Prs,
+    ListColumn::Updated,
+    ListColumn::Branches,
+];
+
+/// Options for `ca stacks list` beyond --verbose and --active
+#[derive(Debug, Default)]
+pub struct ListOptions {
+    pub format: Option<ListFormat>,
+    pub filters: Vec<StackFilter>,
+    /// Labels the stack or one of its entries must carry
+    pub labels: Vec<String>,
+    pub sort: ListSort,
+    pub columns: Vec<ListColumn>,
+}
+
+/// Arguments shared by `ca pop` and `ca stacks pop`
+#[derive(Debug, Clone, Default, Args)]
+pub struct PopArgs {
+    /// Keep the branch (don't delete it)
+    #[arg(long)]
+    pub keep_branch: bool,
+    /// Pop every entry above entry N (0 pops them all)
+    #[arg(long, value_name = "N", conflicts_with = "restore_last")]
+    pub to: Option<usize>,
+    /// Leave the popped entries' pull requests open
+    #[arg(long, conflicts_with = "restore_last")]
+    pub keep_pr: bool,
+    /// Put back the entries removed by the last pop
+    #[arg(long, conflicts_with = "keep_branch")]
+    pub restore_last: bool,
+    /// Show what the pop would do without changing anything
+    #[arg(long, conflicts_with = "restore_last")]
+    pub dry_run: bool,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum StackAction {
     /// Create a new stack
@@ -51,6 +155,9 @@ pub enum StackAction {
         /// Description of the stack
         #[arg(long, short)]
         description: Option<String>,
+        /// Start from a template defined under `templates.<name>` in the configuration
+        #[arg(long, short)]
+        template: Option<String>,
     },
 
     /// List all stacks
@@ -61,9 +168,22 @@ pub enum StackAction {
         /// Show only active stack
         #[arg(long)]
         active: bool,
-        /// Output format (name, id, status)
-        #[arg(long)]
-        format: Option<String>,
+        /// Output format
+        #[arg(long, value_enum)]
+        format: Option<ListFormat>,
+        /// Only show stacks matching this filter (repeat to require several)
+        #[arg(long = "filter", value_enum)]
+        filters: Vec<StackFilter>,
+        /// Only show stacks where the stack or one of its entries has this label (repeat
+        /// to require several)
+        #[arg(long = "label", value_name = "LABEL")]
+        labels: Vec<String>,
+        /// Sort order
+        #[arg(long, value_enum, default_value_t = ListSort::Name)]
+        sort: ListSort,
+        /// Table columns to show, comma-separated (e.g. name,status,prs,updated)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        columns: Vec<ListColumn>,
     },
 
     /// Switch to a different stack
@@ -89,49 +209,51 @@ pub enum StackAction {
         mergeable: bool,
     },
 
-    /// Push current commit to the top of the stack
-    Push {
-        /// Branch name for this commit
-        #[arg(long, short)]
-        branch: Option<String>,
-        /// Commit message (if creating a new commit)
-        #[arg(long, short)]
-        message: Option<String>,
-        /// Use specific commit hash instead of HEAD
-        #[arg(long)]
-        commit: Option<String>,
-        /// Push commits since this reference (e.g., HEAD~3)
-        #[arg(long)]
-        since: Option<String>,
-        /// Push multiple specific commits (comma-separated)
-        #[arg(long)]
-        commits: Option<String>,
-        /// Squash unpushed commits before pushing (optional: specify count)
-        #[arg(long, num_args = 0..=1, default_missing_value = "0")]
-        squash: Option<usize>,
-        /// Squash all commits since this reference (e.g., HEAD~5)
-        #[arg(long)]
-        squash_since: Option<String>,
-        /// Auto-create feature branch when pushing from base branch
+    /// List the files each entry touches, marking files more than one entry changes
+    Files {
+        /// Only this entry (1-based position)
+        entry: Option<usize>,
+    },
+
+    /// Suggest regrouping the stack's commits so each entry has one set of code owners
+    ///
+    /// Reads CODEOWNERS (.github/, .bitbucket/, the root or docs/) and proposes entries
+    /// whose pull requests need as few reviewers as possible. Nothing is changed; apply
+    /// the suggestion with 'ca stacks edit'.
+    SuggestSplit,
+
+    /// Show which reviewers approved, asked for changes on or haven't reviewed each PR
+    ///
+    /// A matrix of the stack's submitted entries by reviewer, followed by the reviewers
+    /// still owing a review, to see at a glance whom to nudge.
+    Approvals {
+        /// Name of the stack (defaults to the active stack)
+        name: Option<String>,
+        /// Print the matrix as JSON
         #[arg(long)]
-        auto_branch: bool,
-        /// Allow pushing commits from base branch (not recommended)
+        json: bool,
+    },
+
+    /// Open the stack's pull requests in the browser
+    Open {
+        /// Stack name (defaults to the active stack)
+        name: Option<String>,
+        /// Open the repository's pull request list instead
+        #[arg(long, conflicts_with = "repo")]
+        list: bool,
+        /// Open the repository instead
         #[arg(long)]
-        allow_base_branch: bool,
-        /// Show what would be pushed without actually pushing
+        repo: bool,
+        /// Print the URLs instead of opening them
         #[arg(long)]
-        dry_run: bool,
-        /// Skip confirmation prompts
-        #[arg(long, short)]
-        yes: bool,
+        print: bool,
     },
 
+    /// Push current commit to the top of the stack
+    Push(PushArgs),
+
     /// Pop the top commit from the stack
-    Pop {
-        /// Keep the branch (don't delete it)
-        #[arg(long)]
-        keep_branch: bool,
-    },
+    Pop(PopArgs),
 
     /// Submit a stack entry for review
     Submit {
@@ -146,12 +268,21 @@ pub enum StackAction {
         /// Submit range of entries (e.g., "1-3" or "2,4,6")
         #[arg(long)]
         range: Option<String>,
-        /// Create draft pull requests (default: true, use --no-draft to create ready PRs)
-        #[arg(long, default_value_t = true)]
+        /// Create draft pull requests (the default unless cascade.submit_policy.draft_by_default is false)
+        #[arg(long, conflicts_with = "no_draft")]
         draft: bool,
+        /// Create ready (non-draft) pull requests
+        #[arg(long)]
+        no_draft: bool,
         /// Open the PR(s) in your default browser after submission (default: true, use --no-open to disable)
         #[arg(long, default_value_t = true)]
         open: bool,
+        /// Also refresh already submitted entries: force-push, update title/description and reviewers
+        #[arg(long)]
+        update: bool,
+        /// Edit every PR title and description in your editor before submitting
+        #[arg(long)]
+        edit: bool,
     },
 
     /// Check status of all pull requests in a stack
@@ -162,9 +293,18 @@ pub enum StackAction {
 
     /// List all pull requests for the repository
     Prs {
-        /// Filter by state (open, merged, declined)
+        /// Filter by state (open, merged, declined, all; default: open)
         #[arg(long)]
         state: Option<String>,
+        /// Only pull requests you authored
+        #[arg(long, conflicts_with = "author")]
+        mine: bool,
+        /// Only pull requests waiting for your approval
+        #[arg(long)]
+        needs_review: bool,
+        /// Only pull requests by this user
+        #[arg(long)]
+        author: Option<String>,
         /// Show detailed information
         #[arg(long, short)]
         verbose: bool,
@@ -175,6 +315,9 @@ pub enum StackAction {
         /// Force check even if there are issues
         #[arg(long)]
         force: bool,
+        /// Predict which entries would conflict with the updated base, without rebasing
+        #[arg(long)]
+        conflicts: bool,
     },
 
     /// Sync stack with remote repository (pull + rebase + cleanup)
@@ -188,6 +331,9 @@ pub enum StackAction {
         /// Interactive mode for conflict resolution
         #[arg(long, short)]
         interactive: bool,
+        /// Show what the sync would do without changing anything
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Rebase stack on updated base branch
@@ -201,6 +347,13 @@ pub enum StackAction {
         /// Rebase strategy to use
         #[arg(long, value_enum)]
         strategy: Option<RebaseStrategyArg>,
+        /// Drop obsolete entries (position, range or list, e.g. "3" or "2,4"), close
+        /// their PRs and restack the rest
+        #[arg(long, value_name = "N")]
+        skip_entry: Option<String>,
+        /// Show what the rebase would do without changing anything
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Continue an in-progress rebase after resolving conflicts
@@ -221,6 +374,59 @@ pub enum StackAction {
         force: bool,
     },
 
+    /// Make the active stack build on top of another stack
+    ///
+    /// The active stack's base becomes the other stack's top entry branch.
+    /// Syncing or landing the parent stack retargets this stack automatically.
+    DependOn {
+        /// Name of the stack to depend on
+        #[arg(required_unless_present = "clear")]
+        name: Option<String>,
+        /// Remove the dependency and fall back to the parent's base branch
+        #[arg(long, conflicts_with = "name")]
+        clear: bool,
+    },
+
+    /// Show or change settings a stack uses instead of the repository configuration
+    ///
+    /// Keys: base, merge_strategy (merge, squash, fast-forward), reviewers
+    /// (comma-separated), draft (true/false), branch_prefix, labels (comma-separated)
+    /// and pr_description_template.
+    Config {
+        /// Name of the stack
+        name: String,
+        #[command(subcommand)]
+        action: Option<StackConfigAction>,
+    },
+
+    /// Add, remove or show labels of a stack or one of its entries
+    ///
+    /// Labels are listed in the description of the pull requests they apply to (a stack's
+    /// labels on all of its pull requests) and can be filtered on with
+    /// 'ca stacks list --label'.
+    Label {
+        #[command(subcommand)]
+        action: StackLabelAction,
+    },
+
+    /// Move the active stack onto another base branch, e.g. a release branch
+    ///
+    /// Changes the stack's base, rebases every entry onto it and points the open pull
+    /// requests at the new base. Conflicts pause the rebase like 'ca sync': resolve them
+    /// and run 'ca sync continue', or 'ca sync abort' to keep the old base.
+    Retarget {
+        /// New base branch
+        #[arg(long)]
+        onto: String,
+    },
+
+    /// Restructure the active stack in your editor (like 'git rebase -i')
+    ///
+    /// Opens a todo list with one line per entry. Reorder lines or change the verb:
+    /// pick, reword, squash, drop or move. The plan is applied in one go and
+    /// pull requests are updated afterwards.
+    Edit,
+
     /// Validate stack integrity and handle branch modifications
     ///
     /// Checks that stack branches match their expected commit hashes.
@@ -232,6 +438,9 @@ pub enum StackAction {
     /// • reset: Remove extra commits (DESTRUCTIVE - loses work)
     ///
     /// Without --fix, runs interactively asking for each modification.
+    ///
+    /// --deep also fetches and checks each entry branch against origin, and each open
+    /// pull request's source and target branches against the stack's order.
     Validate {
         /// Name of the stack (defaults to active stack)
         name: Option<String>,
@@ -241,6 +450,12 @@ pub enum StackAction {
         /// Only validate the active stack (exits 0 if no active stack)
         #[arg(long)]
         current: bool,
+        /// Also compare entry branches with origin and pull request branches with the stack
+        #[arg(long)]
+        deep: bool,
+        /// With --deep, point pull requests whose target branch drifted back at the stack
+        #[arg(long, requires = "deep")]
+        retarget: bool,
     },
 
     /// Land (merge) approved stack entries
@@ -288,9 +503,18 @@ pub enum StackAction {
 
     /// List pull requests from Bitbucket
     ListPrs {
-        /// Filter by state (open, merged, declined)
+        /// Filter by state (open, merged, declined, all; default: open)
         #[arg(short, long)]
         state: Option<String>,
+        /// Only pull requests you authored
+        #[arg(long, conflicts_with = "author")]
+        mine: bool,
+        /// Only pull requests waiting for your approval
+        #[arg(long)]
+        needs_review: bool,
+        /// Only pull requests by this user
+        #[arg(long)]
+        author: Option<String>,
         /// Show detailed information
         #[arg(short, long)]
         verbose: bool,
@@ -346,9 +570,62 @@ pub enum StackAction {
         /// Skip all confirmation prompts
         #[arg(long, short)]
         force: bool,
-        /// Skip confirmation prompts
-        #[arg(long, short)]
-        yes: bool,
+    },
+}
+
+/// Actions of `ca stacks config <name>`
+#[derive(Debug, Subcommand)]
+pub enum StackConfigAction {
+    /// Show the stack's settings and the defaults they replace (the default action)
+    List,
+    /// Print one setting
+    Get {
+        /// Setting key
+        key: String,
+    },
+    /// Override a setting for this stack
+    Set {
+        /// Setting key
+        key: String,
+        /// New value
+        value: String,
+    },
+    /// Go back to the repository default
+    Unset {
+        /// Setting key
+        key: String,
+    },
+}
+
+/// Actions of `ca stacks label`
+#[derive(Debug, Subcommand)]
+pub enum StackLabelAction {
+    /// Attach labels
+    Add {
+        /// Name of the stack
+        name: String,
+        /// Labels to add
+        #[arg(required = true)]
+        labels: Vec<String>,
+        /// Label this entry (1-based position) instead of the whole stack
+        #[arg(long, value_name = "N")]
+        entry: Option<usize>,
+    },
+    /// Detach labels
+    Remove {
+        /// Name of the stack
+        name: String,
+        /// Labels to remove
+        #[arg(required = true)]
+        labels: Vec<String>,
+        /// Remove them from this entry (1-based position) instead of the whole stack
+        #[arg(long, value_name = "N")]
+        entry: Option<usize>,
+    },
+    /// Show the labels of a stack and its entries
+    List {
+        /// Name of the stack (defaults to the active stack)
+        name: Option<String>,
     },
 }
 
@@ -358,70 +635,107 @@ pub async fn run(action: StackAction) -> Result<()> {
             name,
             base,
             description,
-        } => create_stack(name, base, description).await,
+            template,
+        } => create_stack(name, base, description, template).await,
         StackAction::List {
             verbose,
             active,
             format,
-        } => list_stacks(verbose, active, format).await,
+            filters,
+            labels,
+            sort,
+            columns,
+        } => {
+            let options = ListOptions {
+                format,
+                filters,
+                labels,
+                sort,
+                columns,
+            };
+            list_stacks(verbose, active, options).await
+        }
         StackAction::Switch { name } => switch_stack(name).await,
         StackAction::Deactivate { force } => deactivate_stack(force).await,
         StackAction::Show { verbose, mergeable } => show_stack(verbose, mergeable).await,
-        StackAction::Push {
-            branch,
-            message,
-            commit,
-            since,
-            commits,
-            squash,
-            squash_since,
-            auto_branch,
-            allow_base_branch,
-            dry_run,
-            yes,
-        } => {
-            push_to_stack(
-                branch,
-                message,
-                commit,
-                since,
-                commits,
-                squash,
-                squash_since,
-                auto_branch,
-                allow_base_branch,
-                dry_run,
-                yes,
-            )
-            .await
-        }
-        StackAction::Pop { keep_branch } => pop_from_stack(keep_branch).await,
+        StackAction::Files { entry } => show_stack_files(entry),
+        StackAction::SuggestSplit => suggest_split(),
+        StackAction::Approvals { name, json } => super::approvals::run(name, json).await,
+        StackAction::Open {
+            name,
+            list,
+            repo,
+            print,
+        } => super::browse::open_stack(name, list, repo, print).await,
+        StackAction::Push(args) => push_to_stack(args.try_into()?).await,
+        StackAction::Pop(args) => pop_from_stack(args).await,
         StackAction::Submit {
             entry,
             title,
             description,
             range,
             draft,
+            no_draft,
             open,
-        } => submit_entry(entry, title, description, range, draft, open).await,
+            update,
+            edit,
+        } => {
+            // Neither flag leaves the choice to the submit policy
+            let draft = if draft {
+                Some(true)
+            } else if no_draft {
+                Some(false)
+            } else {
+                None
+            };
+            submit_entry(entry, title, description, range, draft, open, update, edit).await
+        }
         StackAction::Status { name } => check_stack_status(name).await,
-        StackAction::Prs { state, verbose } => list_pull_requests(state, verbose).await,
-        StackAction::Check { force } => check_stack(force).await,
+        StackAction::Prs {
+            state,
+            mine,
+            needs_review,
+            author,
+            verbose,
+        } => {
+            super::prs::run(super::prs::PrFilters {
+                state,
+                mine,
+                needs_review,
+                author,
+                verbose,
+            })
+            .await
+        }
+        StackAction::Check { force, conflicts } => check_stack(force, conflicts).await,
         StackAction::Sync {
             force,
             cleanup,
             interactive,
-        } => sync_stack(force, cleanup, interactive).await,
+            dry_run,
+        } => sync(force, cleanup, interactive, dry_run).await,
         StackAction::Rebase {
             interactive,
             onto,
             strategy,
-        } => rebase_stack(interactive, onto, strategy).await,
+            skip_entry,
+            dry_run,
+        } => rebase(interactive, onto, strategy, skip_entry, dry_run).await,
         StackAction::ContinueRebase => continue_rebase().await,
         StackAction::AbortRebase => abort_rebase().await,
         StackAction::RebaseStatus => rebase_status().await,
         StackAction::Delete { name, force } => delete_stack(name, force).await,
-        StackAction::Validate { name, fix, current } => validate_stack(name, fix, current).await,
+        StackAction::DependOn { name, clear } => depend_on_stack(name, clear).await,
+        StackAction::Config { name, action } => stack_config(name, action).await,
+        StackAction::Label { action } => stack_label(action).await,
+        StackAction::Retarget { onto } => retarget_stack(onto).await,
+        StackAction::Validate {
+            name,
+            fix,
+            current,
+            deep,
+            retarget,
+        } => validate_stack(name, fix, current, deep, retarget).await,
         StackAction::Land {
             entry,
             force,
@@ -449,7 +763,22 @@ pub async fn run(action: StackAction) -> Result<()> {
             strategy,
             build_timeout,
         } => auto_land_stack(force, dry_run, wait_for_builds, strategy, build_timeout).await,
-        StackAction::ListPrs { state, verbose } => list_pull_requests(state, verbose).await,
+        StackAction::ListPrs {
+            state,
+            mine,
+            needs_review,
+            author,
+            verbose,
+        } => {
+            super::prs::run(super::prs::PrFilters {
+                state,
+                mine,
+                needs_review,
+                author,
+                verbose,
+            })
+            .await
+        }
         StackAction::ContinueLand => continue_land().await,
         StackAction::AbortLand => abort_land().await,
         StackAction::LandStatus => land_status().await,
@@ -479,8 +808,8 @@ pub async fn run(action: StackAction) -> Result<()> {
             keep_branch,
             keep_pr,
             force,
-            yes,
-        } => drop_entries(entry, keep_branch, keep_pr, force, yes).await,
+        } => drop_entries(entry, keep_branch, keep_pr, force, prompt::assume_yes()).await,
+        StackAction::Edit => edit_stack(prompt::assume_yes()).await,
     }
 }
 
@@ -489,38 +818,12 @@ pub async fn show(verbose: bool, mergeable: bool) -> Result<()> {
     show_stack(verbose, mergeable).await
 }
 
-#[allow(clippy::too_many_arguments)]
-pub async fn push(
-    branch: Option<String>,
-    message: Option<String>,
-    commit: Option<String>,
-    since: Option<String>,
-    commits: Option<String>,
-    squash: Option<usize>,
-    squash_since: Option<String>,
-    auto_branch: bool,
-    allow_base_branch: bool,
-    dry_run: bool,
-    yes: bool,
-) -> Result<()> {
-    push_to_stack(
-        branch,
-        message,
-        commit,
-        since,
-        commits,
-        squash,
-        squash_since,
-        auto_branch,
-        allow_base_branch,
-        dry_run,
-        yes,
-    )
-    .await
+pub async fn push(spec: PushSpec) -> Result<()> {
+    push_to_stack(spec).await
 }
 
-pub async fn pop(keep_branch: bool) -> Result<()> {
-    pop_from_stack(keep_branch).await
+pub async fn pop(args: PopArgs) -> Result<()> {
+    pop_from_stack(args).await
 }
 
 pub async fn drop(
@@ -564,7 +867,10 @@ pub async fn autoland(
     auto_land_stack(force, dry_run, wait_for_builds, strategy, build_timeout).await
 }
 
-pub async fn sync(force: bool, skip_cleanup: bool, interactive: bool) -> Result<()> {
+pub async fn sync(force: bool, skip_cleanup: bool, interactive: bool, dry_run: bool) -> Result<()> {
+    if dry_run {
+        return plan_sync(skip_cleanup).await;
+    }
     sync_stack(force, skip_cleanup, interactive).await
 }
 
@@ -572,8 +878,13 @@ pub async fn rebase(
     interactive: bool,
     onto: Option<String>,
     strategy: Option<RebaseStrategyArg>,
+    skip_entry: Option<String>,
+    dry_run: bool,
 ) -> Result<()> {
-    rebase_stack(interactive, onto, strategy).await
+    if dry_run {
+        return plan_rebase(onto, skip_entry).await;
+    }
+    rebase_stack(interactive, onto, strategy, skip_entry).await
 }
 
 pub async fn deactivate(force: bool) -> Result<()> {
@@ -584,10 +895,22 @@ pub async fn switch(name: String) -> Result<()> {
     switch_stack(name).await
 }
 
+pub async fn feature(
+    name: String,
+    base: Option<String>,
+    branch: Option<String>,
+    description: Option<String>,
+    template: Option<String>,
+    no_push: bool,
+) -> Result<()> {
+    start_feature(name, base, branch, description, template, no_push).await
+}
+
 async fn create_stack(
     name: String,
     base: Option<String>,
     description: Option<String>,
+    template: Option<String>,
 ) -> Result<()> {
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
@@ -595,8 +918,14 @@ async fn create_stack(
     let repo_root = find_repository_root(&current_dir)
         .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
 
+    let stack_template = stack_template(&repo_root, template.as_deref())?;
     let mut manager = StackManager::new(&repo_root)?;
-    let stack_id = manager.create_stack(name.clone(), base.clone(), description.clone())?;
+    let stack_id = manager.create_stack_from_template(
+        name.clone(),
+        base.clone(),
+        description.clone(),
+        &stack_template,
+    )?;
 
     // Get the created stack to check its working branch
     let stack = manager
@@ -615,6 +944,11 @@ async fn create_stack(
     if let Some(desc) = description {
         Output::sub_item(format!("Description: {desc}"));
     }
+    if let Some(template) = template {
+        Output::sub_item(format!(
+            "Template: {template} (see 'ca stacks config {name}')"
+        ));
+    }
 
     // Provide helpful guidance based on the working branch situation
     if stack.working_branch.is_none() {
@@ -638,204 +972,150 @@ async fn create_stack(
     Ok(())
 }
 
-async fn list_stacks(verbose: bool, active_only: bool, format: Option<String>) -> Result<()> {
+/// Create a stack together with its working branch and switch to it in one step
+async fn start_feature(
+    name: String,
+    base: Option<String>,
+    branch: Option<String>,
+    description: Option<String>,
+    template: Option<String>,
+    no_push: bool,
+) -> Result<()> {
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
 
     let repo_root = find_repository_root(&current_dir)
         .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
 
-    let manager = StackManager::new(&repo_root)?;
-    let mut stacks = manager.list_stacks();
-
-    if active_only {
-        stacks.retain(|(_, _, _, _, active_marker)| active_marker.is_some());
-    }
-
-    if let Some(ref format) = format {
-        match format.as_str() {
-            "json" => {
-                let mut json_stacks = Vec::new();
-
-                for (stack_id, name, status, entry_count, active_marker) in &stacks {
-                    let (entries_json, working_branch, base_branch) =
-                        if let Some(stack_obj) = manager.get_stack(stack_id) {
-                            let entries_json = stack_obj
-                                .entries
-                                .iter()
-                                .enumerate()
-                                .map(|(idx, entry)| {
-                                    serde_json::json!({
-                                        "position": idx + 1,
-                                        "entry_id": entry.id.to_string(),
-                                        "branch_name": entry.branch.clone(),
-                                        "commit_hash": entry.commit_hash.clone(),
-                                        "short_hash": entry.short_hash(),
-                                        "is_submitted": entry.is_submitted,
-                                        "is_merged": entry.is_merged,
-                                        "pull_request_id": entry.pull_request_id.clone(),
-                                    })
-                                })
-                                .collect::<Vec<_>>();
-
-                            (
-                                entries_json,
-                                stack_obj.working_branch.clone(),
-                                Some(stack_obj.base_branch.clone()),
-                            )
-                        } else {
-                            (Vec::new(), None, None)
-                        };
-
-                    let status_label = format!("{status:?}");
-
-                    json_stacks.push(serde_json::json!({
-                        "id": stack_id.to_string(),
-                        "name": name,
-                        "status": status_label,
-                        "entry_count": entry_count,
-                        "is_active": active_marker.is_some(),
-                        "base_branch": base_branch,
-                        "working_branch": working_branch,
-                        "entries": entries_json,
-                    }));
-                }
+    let mut manager = StackManager::new(&repo_root)?;
 
-                let json_output = serde_json::json!({ "stacks": json_stacks });
-                let serialized = serde_json::to_string_pretty(&json_output)?;
-                println!("{serialized}");
-                return Ok(());
-            }
-            "name" => {
-                for (_, name, _, _, _) in &stacks {
-                    println!("{name}");
-                }
-                return Ok(());
-            }
-            "id" => {
-                for (stack_id, _, _, _, _) in &stacks {
-                    println!("{}", stack_id);
-                }
-                return Ok(());
-            }
-            "status" => {
-                for (_, name, status, _, active_marker) in &stacks {
-                    let status_label = format!("{status:?}");
-                    let marker = if active_marker.is_some() {
-                        " (active)"
-                    } else {
-                        ""
-                    };
-                    println!("{name}: {status_label}{marker}");
-                }
-                return Ok(());
-            }
-            other => {
-                return Err(CascadeError::config(format!(
-                    "Unsupported format '{}'. Supported formats: name, id, status, json",
-                    other
-                )));
-            }
-        }
+    // Fail before touching any branches so a name clash leaves the repo untouched
+    if manager.get_stack_by_name(&name).is_some() {
+        return Err(CascadeError::config(format!(
+            "Stack '{name}' already exists. Use 'ca switch {name}' to work on it"
+        )));
     }
 
-    if stacks.is_empty() {
-        if active_only {
-            Output::info("No active stack. Activate one with 'ca stack switch <name>'");
-        } else {
-            Output::info("No stacks found. Create one with: ca stack create <name>");
-        }
-        return Ok(());
+    let template = stack_template(&repo_root, template.as_deref())?;
+    let base_branch = base.or_else(|| template.base.clone()).unwrap_or_else(|| {
+        manager
+            .get_repository_metadata()
+            .default_base_branch
+            .clone()
+    });
+    if !manager.git_repo().branch_exists_or_fetch(&base_branch)? {
+        return Err(CascadeError::branch(format!(
+            "Base branch '{base_branch}' does not exist locally or remotely"
+        )));
     }
 
-    println!("Stacks:");
-    for (stack_id, name, status, entry_count, active_marker) in stacks {
-        let status_icon = match status {
-            StackStatus::Clean => "✓",
-            StackStatus::Dirty => "~",
-            StackStatus::OutOfSync => "!",
-            StackStatus::Conflicted => "✗",
-            StackStatus::Rebasing => "↔",
-            StackStatus::NeedsSync => "~",
-            StackStatus::Corrupted => "✗",
-        };
-
-        let active_indicator = if active_marker.is_some() {
-            " (active)"
-        } else {
-            ""
-        };
-
-        // Get the actual stack object to access branch information
-        let stack = manager.get_stack(&stack_id);
-
-        if verbose {
-            println!("  {status_icon} {name} [{entry_count}]{active_indicator}");
-            println!("    ID: {stack_id}");
-            if let Some(stack_meta) = manager.get_stack_metadata(&stack_id) {
-                println!("    Base: {}", stack_meta.base_branch);
-                if let Some(desc) = &stack_meta.description {
-                    println!("    Description: {desc}");
-                }
-                println!(
-                    "    Commits: {} total, {} submitted",
-                    stack_meta.total_commits, stack_meta.submitted_commits
-                );
-                if stack_meta.has_conflicts {
-                    Output::warning("    Has conflicts");
-                }
+    let branch_name = match branch {
+        Some(branch) => {
+            if manager.git_repo().branch_exists(&branch) {
+                return Err(CascadeError::branch(format!(
+                    "Branch '{branch}' already exists"
+                )));
             }
+            branch
+        }
+        None => branch_manager_for(&repo_root)?.generate_branch_name_for(
+            &name,
+            &crate::git::BranchNameContext {
+                stack: Some(name.clone()),
+                entry_number: 1,
+                prefix: template.branch_prefix.clone(),
+            },
+        ),
+    };
 
-            // Show branch information in verbose mode
-            if let Some(stack_obj) = stack {
-                if !stack_obj.entries.is_empty() {
-                    println!("    Branches:");
-                    for (i, entry) in stack_obj.entries.iter().enumerate() {
-                        let entry_num = i + 1;
-                        let submitted_indicator = if entry.is_submitted {
-                            "[submitted]"
-                        } else {
-                            ""
-                        };
-                        let branch_name = &entry.branch;
-                        let short_message = if entry.message.len() > 40 {
-                            format!("{}...", &entry.message[..37])
-                        } else {
-                            entry.message.clone()
-                        };
-                        println!("      {entry_num}. {submitted_indicator} {branch_name} - {short_message}");
-                    }
-                }
-            }
-            println!();
-        } else {
-            // Show compact branch info in non-verbose mode
-            let branch_info = if let Some(stack_obj) = stack {
-                if stack_obj.entries.is_empty() {
-                    String::new()
-                } else if stack_obj.entries.len() == 1 {
-                    format!(" → {}", stack_obj.entries[0].branch)
-                } else {
-                    let first_branch = &stack_obj.entries[0].branch;
-                    let last_branch = &stack_obj.entries.last().unwrap().branch;
-                    format!(" → {first_branch} … {last_branch}")
-                }
-            } else {
-                String::new()
-            };
+    // Create and check out the working branch first so the new stack picks it up
+    let repo = GitRepository::open(&repo_root)?;
+    repo.create_branch(&branch_name, Some(&base_branch))?;
+    repo.checkout_branch_silent(&branch_name)?;
+
+    let stack_id = manager.create_stack_from_template(
+        name.clone(),
+        Some(base_branch.clone()),
+        description,
+        &template,
+    )?;
+    let stack = manager
+        .get_stack(&stack_id)
+        .ok_or_else(|| CascadeError::config("Failed to get created stack"))?;
+
+    Output::stack_info(
+        &name,
+        &stack_id.to_string(),
+        &stack.base_branch,
+        stack.working_branch.as_deref(),
+        true, // is_active
+    );
 
-            println!("  {status_icon} {name} [{entry_count}]{branch_info}{active_indicator}");
+    // Publish the branch so `git push`/`git pull` work without extra flags
+    if no_push {
+        Output::sub_item("Skipped pushing branch (--no-push)");
+    } else if repo.get_remote_url("origin").is_err() {
+        Output::sub_item("No 'origin' remote configured, skipping upstream setup");
+    } else {
+        match repo
+            .push(&branch_name)
+            .and_then(|_| repo.set_upstream(&branch_name, "origin", &branch_name))
+        {
+            Ok(()) => Output::sub_item(format!("Tracking origin/{branch_name}")),
+            Err(e) => Output::warning(format!(
+                "Could not set upstream for '{branch_name}': {e}\n   \
+                 Run 'git push -u origin {branch_name}' once you're ready"
+            )),
         }
     }
 
-    if !verbose {
-        println!("\nUse --verbose for more details");
-    }
+    Output::next_steps(&[
+        "Make changes and commit them",
+        "Run 'ca push' to add commits to this stack",
+        "Use 'ca submit' when ready to create pull requests",
+    ]);
 
     Ok(())
 }
 
-async fn switch_stack(name: String) -> Result<()> {
+/// The rows `ca stacks list` shows: the stacks passing the filters, in `options.sort` order
+fn stack_rows(
+    manager: &StackManager,
+    active_only: bool,
+    options: &ListOptions,
+) -> Result<Vec<StackRow>> {
+    let mut rows: Vec<StackRow> = manager
+        .get_all_stacks_objects()?
+        .into_iter()
+        .filter(|stack| !active_only || stack.is_active)
+        .map(|stack| {
+            let needs_sync = super::prompt::needs_sync(manager.git_repo(), &stack);
+            let conflicted = stack.status == StackStatus::Conflicted
+                || manager
+                    .get_stack_metadata(&stack.id)
+                    .is_some_and(|meta| meta.has_conflicts);
+            StackRow::new(stack, needs_sync, conflicted)
+        })
+        .filter(|row| options.filters.iter().all(|filter| row.matches(*filter)))
+        .filter(|row| {
+            options
+                .labels
+                .iter()
+                .all(|label| row.stack.has_label(label))
+        })
+        .collect();
+    sort_stack_rows(&mut rows, options.sort);
+    Ok(rows)
+}
+
+async fn list_stacks(verbose: bool, active_only: bool, options: ListOptions) -> Result<()> {
+    let format = options.format.unwrap_or_default();
+    if !options.columns.is_empty() && (format != ListFormat::Table || verbose) {
+        return Err(CascadeError::config(
+            "--columns only applies to the table format (not --verbose or other --format values)",
+        ));
+    }
+
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
 
@@ -843,12 +1123,364 @@ async fn switch_stack(name: String) -> Result<()> {
         .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
 
     let manager = StackManager::new(&repo_root)?;
-    let repo = GitRepository::open(&repo_root)?;
-
-    // Get stack information before switching
-    let stack = manager
-        .get_stack_by_name(&name)
-        .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?;
+    let total = manager.list_stacks().len();
+    let rows = stack_rows(&manager, active_only, &options)?;
+
+    match format {
+        ListFormat::Json => {
+            let json_stacks = rows.iter().map(StackRow::to_json).collect::<Vec<_>>();
+            let json_output = serde_json::json!({ "stacks": json_stacks });
+            let serialized = serde_json::to_string_pretty(&json_output)?;
+            Output::data(serialized);
+            return Ok(());
+        }
+        ListFormat::Name => {
+            for row in &rows {
+                Output::data(&row.stack.name);
+            }
+            return Ok(());
+        }
+        ListFormat::Id => {
+            for row in &rows {
+                Output::data(row.stack.id);
+            }
+            return Ok(());
+        }
+        ListFormat::Status => {
+            for row in &rows {
+                let marker = if row.stack.is_active { " (active)" } else { "" };
+                Output::data(format!(
+                    "{}: {:?}{marker}",
+                    row.stack.name, row.stack.status
+                ));
+            }
+            return Ok(());
+        }
+        ListFormat::Table => {}
+    }
+
+    if rows.is_empty() {
+        if (!options.filters.is_empty() || !options.labels.is_empty()) && total > 0 {
+            Output::info(format!(
+                "No stacks match the given filters ({total} in total)"
+            ));
+        } else if active_only {
+            Output::info("No active stack. Activate one with 'ca stack switch <name>'");
+        } else {
+            Output::info("No stacks found. Create one with: ca stack create <name>");
+        }
+        return Ok(());
+    }
+
+    if !verbose {
+        let columns = if options.columns.is_empty() {
+            DEFAULT_LIST_COLUMNS
+        } else {
+            &options.columns
+        };
+        for line in render_stack_table(&rows, columns, Utc::now()) {
+            Output::line(line);
+        }
+        Output::line("\nUse --verbose for more details");
+        return Ok(());
+    }
+
+    Output::line("Stacks:");
+    for row in &rows {
+        let stack = &row.stack;
+        let active_indicator = if stack.is_active { " (active)" } else { "" };
+        Output::line(format!(
+            "  {} {} [{}]{active_indicator}",
+            status_icon(&stack.status),
+            stack.name,
+            stack.entries.len()
+        ));
+        Output::line(format!("    ID: {}", stack.id));
+        if let Some(stack_meta) = manager.get_stack_metadata(&stack.id) {
+            Output::line(format!("    Base: {}", stack_meta.base_branch));
+            if let Some(desc) = &stack_meta.description {
+                Output::line(format!("    Description: {desc}"));
+            }
+            Output::line(format!(
+                "    Commits: {} total, {} submitted",
+                stack_meta.total_commits, stack_meta.submitted_commits
+            ));
+            if stack_meta.has_conflicts {
+                Output::warning("    Has conflicts");
+            }
+        }
+        if row.needs_sync {
+            Output::line(format!("    Needs sync with {}", stack.base_branch));
+        }
+        if row.open_prs > 0 {
+            Output::line(format!("    Open PRs: {}", row.open_prs));
+        }
+        if !stack.labels().is_empty() {
+            Output::line(format!("    Labels: {}", stack.labels().join(", ")));
+        }
+
+        if !stack.entries.is_empty() {
+            Output::line("    Branches:");
+            for (i, entry) in stack.entries.iter().enumerate() {
+                let entry_num = i + 1;
+                let submitted_indicator = if entry.is_submitted {
+                    "[submitted]"
+                } else {
+                    ""
+                };
+                let branch_name = &entry.branch;
+                let summary = entry.message.lines().next().unwrap_or_default();
+                let short_message = if summary.len() > 40 {
+                    format!("{}...", &summary[..37])
+                } else {
+                    summary.to_string()
+                };
+                let labels = if entry.labels.is_empty() {
+                    String::new()
+                } else {
+                    format!(" [{}]", entry.labels.join(", "))
+                };
+                Output::line(format!(
+                    "      {entry_num}. {submitted_indicator} {branch_name} - {short_message}{labels}"
+                ));
+            }
+        }
+        Output::spacing();
+    }
+
+    Ok(())
+}
+
+fn status_icon(status: &StackStatus) -> &'static str {
+    match status {
+        StackStatus::Clean => "✓",
+        StackStatus::Dirty => "~",
+        StackStatus::OutOfSync => "!",
+        StackStatus::Conflicted => "✗",
+        StackStatus::Rebasing => "↔",
+        StackStatus::NeedsSync => "~",
+        StackStatus::Corrupted => "✗",
+    }
+}
+
+/// A stack plus the derived state `ca stacks list` filters and sorts on
+struct StackRow {
+    stack: Stack,
+    needs_sync: bool,
+    conflicted: bool,
+    open_prs: usize,
+    last_activity: DateTime<Utc>,
+}
+
+impl StackRow {
+    fn new(stack: Stack, needs_sync: bool, conflicted: bool) -> Self {
+        let open_prs = stack
+            .entries
+            .iter()
+            .filter(|entry| entry.pull_request_id.is_some() && !entry.is_merged)
+            .count();
+        let last_activity = stack
+            .entries
+            .iter()
+            .map(|entry| entry.updated_at)
+            .fold(stack.updated_at, DateTime::max);
+        Self {
+            stack,
+            needs_sync,
+            conflicted,
+            open_prs,
+            last_activity,
+        }
+    }
+
+    fn matches(&self, filter: StackFilter) -> bool {
+        match filter {
+            StackFilter::NeedsSync => self.needs_sync,
+            StackFilter::Conflicted => self.conflicted,
+            StackFilter::HasOpenPrs => self.open_prs > 0,
+        }
+    }
+
+    fn status_label(&self) -> &'static str {
+        if self.conflicted {
+            return "conflicted";
+        }
+        match self.stack.status {
+            StackStatus::Clean if self.needs_sync => "needs sync",
+            StackStatus::Clean => "clean",
+            StackStatus::Dirty => "dirty",
+            StackStatus::OutOfSync => "out of sync",
+            StackStatus::Conflicted => "conflicted",
+            StackStatus::Rebasing => "rebasing",
+            StackStatus::NeedsSync => "needs sync",
+            StackStatus::Corrupted => "corrupted",
+        }
+    }
+
+    fn cell(&self, column: ListColumn, now: DateTime<Utc>) -> String {
+        let stack = &self.stack;
+        match column {
+            ListColumn::Name => {
+                let marker = if stack.is_active { "* " } else { "  " };
+                format!("{marker}{}", stack.name)
+            }
+            ListColumn::Status => self.status_label().to_string(),
+            ListColumn::Entries => stack.entries.len().to_string(),
+            ListColumn::Prs => self.open_prs.to_string(),
+            ListColumn::Base => stack.base_branch.clone(),
+            ListColumn::Branches => match stack.entries.as_slice() {
+                [] => "-".to_string(),
+                [only] => only.branch.clone(),
+                [first, .., last] => format!("{} … {}", first.branch, last.branch),
+            },
+            ListColumn::Updated => relative_age(self.last_activity, now),
+            ListColumn::Created => stack.created_at.format("%Y-%m-%d").to_string(),
+            ListColumn::Id => stack.id.to_string(),
+            ListColumn::Labels => {
+                let mut labels: Vec<&String> = stack.labels().iter().collect();
+                for label in stack.entries.iter().flat_map(|entry| &entry.labels) {
+                    if !labels.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+                        labels.push(label);
+                    }
+                }
+                if labels.is_empty() {
+                    "-".to_string()
+                } else {
+                    labels
+                        .iter()
+                        .map(|label| label.as_str())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                }
+            }
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let stack = &self.stack;
+        let entries_json = stack
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(idx, entry)| {
+                serde_json::json!({
+                    "position": idx + 1,
+                    "entry_id": entry.id.to_string(),
+                    "branch_name": entry.branch.clone(),
+                    "commit_hash": entry.commit_hash.clone(),
+                    "short_hash": entry.short_hash(),
+                    "is_submitted": entry.is_submitted,
+                    "is_merged": entry.is_merged,
+                    "pull_request_id": entry.pull_request_id.clone(),
+                    "labels": entry.labels.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::json!({
+            "id": stack.id.to_string(),
+            "name": stack.name,
+            "status": format!("{:?}", stack.status),
+            "entry_count": stack.entries.len(),
+            "is_active": stack.is_active,
+            "base_branch": stack.base_branch,
+            "working_branch": stack.working_branch,
+            "needs_sync": self.needs_sync,
+            "conflicted": self.conflicted,
+            "open_prs": self.open_prs,
+            "labels": stack.labels(),
+            "created_at": stack.created_at.to_rfc3339(),
+            "last_activity": self.last_activity.to_rfc3339(),
+            "entries": entries_json,
+        })
+    }
+}
+
+fn sort_stack_rows(rows: &mut [StackRow], sort: ListSort) {
+    let by_name = |a: &StackRow, b: &StackRow| {
+        a.stack
+            .name
+            .to_lowercase()
+            .cmp(&b.stack.name.to_lowercase())
+    };
+    match sort {
+        ListSort::Name => rows.sort_by(by_name),
+        ListSort::Recent => {
+            rows.sort_by(|a, b| b.last_activity.cmp(&a.last_activity).then(by_name(a, b)))
+        }
+        ListSort::Created => rows.sort_by(|a, b| {
+            a.stack
+                .created_at
+                .cmp(&b.stack.created_at)
+                .then(by_name(a, b))
+        }),
+        ListSort::Size => rows.sort_by(|a, b| {
+            b.stack
+                .entries
+                .len()
+                .cmp(&a.stack.entries.len())
+                .then(by_name(a, b))
+        }),
+    }
+}
+
+fn render_stack_table(
+    rows: &[StackRow],
+    columns: &[ListColumn],
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let header = |column: &ListColumn| match column {
+        ListColumn::Name => "  NAME",
+        ListColumn::Status => "STATUS",
+        ListColumn::Entries => "ENTRIES",
+        ListColumn::Prs => "PRS",
+        ListColumn::Base => "BASE",
+        ListColumn::Branches => "BRANCHES",
+        ListColumn::Updated => "UPDATED",
+        ListColumn::Created => "CREATED",
+        ListColumn::Id => "ID",
+        ListColumn::Labels => "LABELS",
+    };
+    let mut table = vec![columns
+        .iter()
+        .map(|c| header(c).to_string())
+        .collect::<Vec<_>>()];
+    table.extend(
+        rows.iter()
+            .map(|row| columns.iter().map(|c| row.cell(*c, now)).collect()),
+    );
+    Output::table_lines(&table)
+}
+
+pub(crate) fn relative_age(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let age = now - time;
+    if age.num_days() >= 30 {
+        time.format("%Y-%m-%d").to_string()
+    } else if age.num_days() > 0 {
+        format!("{}d ago", age.num_days())
+    } else if age.num_hours() > 0 {
+        format!("{}h ago", age.num_hours())
+    } else if age.num_minutes() > 0 {
+        format!("{}m ago", age.num_minutes())
+    } else {
+        "just now".to_string()
+    }
+}
+
+async fn switch_stack(name: String) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let manager = StackManager::new(&repo_root)?;
+    let repo = GitRepository::open(&repo_root)?;
+
+    // Get stack information before switching
+    let stack = manager
+        .get_stack_by_name(&name)
+        .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?;
 
     // Determine the target branch and provide appropriate messaging
     if let Some(working_branch) = &stack.working_branch {
@@ -862,6 +1494,8 @@ async fn switch_stack(name: String) -> Result<()> {
 
             // Check if target branch exists
             if repo.branch_exists(working_branch) {
+                // Auto-stashed changes follow the user onto the new branch
+                AutoStash::stash_if_enabled(&repo_root, &repo, "switch")?;
                 match repo.checkout_branch(working_branch) {
                     Ok(_) => {
                         Output::success(format!("Checked out branch: {working_branch}"));
@@ -874,6 +1508,7 @@ async fn switch_stack(name: String) -> Result<()> {
                         ));
                     }
                 }
+                AutoStash::finish(&repo_root, Ok(()))?;
             } else {
                 Output::warning(format!(
                     "Stack working branch '{working_branch}' doesn't exist locally"
@@ -914,6 +1549,283 @@ async fn deactivate_stack(_force: bool) -> Result<()> {
     Ok(())
 }
 
+/// List the files touched by each entry of the active stack (or only `entry`), noting
+/// the other unmerged entries that change the same file
+fn show_stack_files(entry: Option<usize>) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let manager = StackManager::new(&repo_root)?;
+    let git_repo = GitRepository::open(&repo_root)?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
+    if stack.entries.is_empty() {
+        Output::info("Stack has no entries yet");
+        return Ok(());
+    }
+    if let Some(position) = entry {
+        if position == 0 || position > stack.entries.len() {
+            return Err(CascadeError::validation(format!(
+                "Entry {position} doesn't exist: the stack has {} entries",
+                stack.entries.len()
+            )));
+        }
+    }
+
+    let mut index = crate::stack::ChangedFilesIndex::load(&repo_root);
+    let mut files = Vec::with_capacity(stack.entries.len());
+    for stack_entry in &stack.entries {
+        files.push(index.files(&git_repo, stack_entry)?.to_vec());
+    }
+    if let Err(e) = index.save(&repo_root, manager.get_all_stacks()) {
+        debug!("Could not save the changed-files index: {}", e);
+    }
+
+    Output::section(format!("Files changed by stack '{}'", stack.name));
+    for (i, stack_entry) in stack.entries.iter().enumerate() {
+        if entry.is_some_and(|position| position != i + 1) {
+            continue;
+        }
+        let merged = if stack_entry.is_merged {
+            ", merged"
+        } else {
+            ""
+        };
+        Output::numbered_item(
+            i + 1,
+            format!(
+                "{} ({} file{}{merged})",
+                stack_entry.branch,
+                files[i].len(),
+                if files[i].len() == 1 { "" } else { "s" }
+            ),
+        );
+        for file in &files[i] {
+            let shared: Vec<String> = stack
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|(j, other)| {
+                    *j != i
+                        && !other.is_merged
+                        && !stack_entry.is_merged
+                        && files[*j].contains(file)
+                })
+                .map(|(j, _)| (j + 1).to_string())
+                .collect();
+            if shared.is_empty() {
+                Output::sub_item(file);
+            } else {
+                Output::sub_item(format!("{file} (also entry {})", shared.join(", ")));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Owners as shown next to an entry
+fn owners_label(owners: &std::collections::BTreeSet<String>) -> String {
+    if owners.is_empty() {
+        "no owners".to_string()
+    } else {
+        owners.iter().cloned().collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// Propose entries for the active stack's commits that follow CODEOWNERS boundaries
+fn suggest_split() -> Result<()> {
+    use crate::stack::ownership::{owner_groups, suggest_entries, OwnedCommit};
+
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let codeowners = crate::git::CodeOwners::load(&repo_root)?.ok_or_else(|| {
+        CascadeError::config("This repository has no CODEOWNERS file").with_hint(format!(
+            "Cascade looks for {}",
+            crate::git::codeowners::CODEOWNERS_PATHS.join(", ")
+        ))
+    })?;
+    let manager = StackManager::new(&repo_root)?;
+    let git_repo = GitRepository::open(&repo_root)?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
+    let entries: Vec<(usize, &crate::stack::StackEntry)> = stack
+        .entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| !entry.is_merged)
+        .collect();
+    if entries.is_empty() {
+        Output::info("The stack has no unmerged entries to split");
+        return Ok(());
+    }
+
+    let mut index = crate::stack::ChangedFilesIndex::load(&repo_root);
+    Output::section(format!(
+        "Owners of stack '{}' (from {})",
+        stack.name,
+        codeowners.source.display()
+    ));
+    let mut commits = Vec::new();
+    let mut current = Vec::new();
+    for (position, entry) in &entries {
+        let owners = index
+            .files(&git_repo, entry)?
+            .iter()
+            .flat_map(|file| codeowners.owners(file).iter().cloned())
+            .collect();
+        Output::numbered_item(
+            position + 1,
+            format!("{}: {}", entry.branch, owners_label(&owners)),
+        );
+
+        let mut group = Vec::new();
+        for hash in git_repo.get_first_parent_chain(&entry.commit_hash, entry.commit_count)? {
+            let summary = git_repo
+                .get_commit(&hash)?
+                .summary()
+                .unwrap_or_default()
+                .to_string();
+            let files = git_repo.get_commit_files(&hash)?;
+            group.push(commits.len());
+            commits.push(OwnedCommit::new(hash, summary, files, &codeowners));
+        }
+        current.push(group);
+    }
+    if let Err(e) = index.save(&repo_root, manager.get_all_stacks()) {
+        debug!("Could not save the changed-files index: {}", e);
+    }
+
+    if commits.iter().all(|commit| commit.owners.is_empty()) {
+        Output::spacing();
+        Output::info(format!(
+            "None of the stack's files have owners in {}",
+            codeowners.source.display()
+        ));
+        return Ok(());
+    }
+
+    let suggested = suggest_entries(&commits);
+    let unchanged = suggested
+        .iter()
+        .map(|entry| &entry.commits)
+        .eq(current.iter());
+    if unchanged {
+        Output::spacing();
+        Output::success("Entries already follow ownership boundaries");
+    } else {
+        Output::section("Suggested entries");
+        for (number, entry) in suggested.iter().enumerate() {
+            Output::numbered_item(number + 1, owners_label(&entry.owners));
+            for &commit in &entry.commits {
+                Output::sub_item(format!(
+                    "{} {}",
+                    &commits[commit].hash[..8],
+                    commits[commit].summary
+                ));
+            }
+        }
+    }
+
+    let spanning: Vec<_> = commits
+        .iter()
+        .map(|commit| (commit, owner_groups(&commit.files, &codeowners)))
+        .filter(|(_, groups)| !groups.is_empty())
+        .collect();
+    if !spanning.is_empty() {
+        Output::section("Commits touching files of several owners");
+        for (commit, groups) in spanning {
+            Output::bullet(format!("{} {}", &commit.hash[..8], commit.summary));
+            for (owners, files) in groups {
+                let owners = if owners.is_empty() {
+                    "no owners".to_string()
+                } else {
+                    owners.join(", ")
+                };
+                Output::sub_item(format!("{owners}: {}", files.join(", ")));
+            }
+        }
+        Output::tip("Splitting these commits by owner would narrow their reviewer sets further");
+    }
+
+    if !unchanged {
+        Output::spacing();
+        Output::tip("Reorder and squash entries to match with 'ca stacks edit'");
+    }
+    Ok(())
+}
+
+/// Warn when entries just pushed touch files that earlier unmerged entries change too:
+/// landing or reordering one of them is then likely to conflict with the other
+fn warn_file_overlaps(repo_root: &std::path::Path, manager: &StackManager, pushed: &[Uuid]) {
+    let Some(stack) = manager.get_active_stack() else {
+        return;
+    };
+    let Ok(git_repo) = GitRepository::open(repo_root) else {
+        return;
+    };
+    let mut index = crate::stack::ChangedFilesIndex::load(repo_root);
+
+    let mut warned = false;
+    for entry_id in pushed {
+        let overlaps = match index.overlaps(&git_repo, stack, entry_id) {
+            Ok(overlaps) => overlaps,
+            Err(e) => {
+                debug!(
+                    "Could not check entry {} for overlapping files: {}",
+                    entry_id, e
+                );
+                continue;
+            }
+        };
+        let Some(position) = stack.entries.iter().position(|e| e.id == *entry_id) else {
+            continue;
+        };
+        if overlaps.is_empty() {
+            continue;
+        }
+        warned = true;
+        Output::warning(format!(
+            "Entry {} touches files that earlier entries also change (conflict risk):",
+            position + 1
+        ));
+        for overlap in &overlaps {
+            let shown: Vec<&str> = overlap.files.iter().take(3).map(String::as_str).collect();
+            let more = overlap.files.len().saturating_sub(shown.len());
+            Output::sub_item(format!(
+                "entry {} ({}): {}{}",
+                overlap.position,
+                overlap.branch,
+                shown.join(", "),
+                if more > 0 {
+                    format!(" and {more} more")
+                } else {
+                    String::new()
+                }
+            ));
+        }
+        let nearest = overlaps[0].position;
+        if nearest < position {
+            Output::tip(format!(
+                "Move it right above entry {nearest} with 'ca stacks edit', or squash the two, \
+                 so the shared changes sit together"
+            ));
+        }
+    }
+    if warned {
+        Output::spacing();
+    }
+    if let Err(e) = index.save(repo_root, manager.get_all_stacks()) {
+        debug!("Could not save the changed-files index: {}", e);
+    }
+}
+
 async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
@@ -924,7 +1836,7 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
     let stack_manager = StackManager::new(&repo_root)?;
 
     // Get stack information first to avoid borrow conflicts
-    let (stack_id, stack_name, stack_base, stack_working, stack_entries) = {
+    let (stack_id, stack_name, stack_base, stack_working, stack_entries, stack_superseded) = {
         let active_stack = stack_manager.get_active_stack().ok_or_else(|| {
             CascadeError::config(
                 "No active stack. Use 'ca stacks create' or 'ca stacks switch' to select a stack"
@@ -938,6 +1850,7 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
             active_stack.base_branch.clone(),
             active_stack.working_branch.clone(),
             active_stack.entries.clone(),
+            active_stack.superseded.clone(),
         )
     };
 
@@ -1041,9 +1954,15 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
         // Use colored status: pending (yellow), submitted (muted green), merged (bright green)
         let status_colored = Output::entry_status(entry.is_submitted, entry.is_merged);
 
+        let group_info = if entry.is_grouped() {
+            format!(" [{} commits]", entry.commit_count)
+        } else {
+            String::new()
+        };
+
         Output::numbered_item(
             entry_num,
-            format!("{short_hash} {status_colored} {short_msg}{source_branch_info}"),
+            format!("{short_hash} {status_colored} {short_msg}{group_info}{source_branch_info}"),
         );
 
         if verbose {
@@ -1055,6 +1974,9 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
             if let Some(pr_id) = &entry.pull_request_id {
                 Output::sub_item(format!("PR: #{pr_id}"));
             }
+            if let Some(source) = &entry.picked_from {
+                Output::sub_item(format!("Picked from: {}", &source[..source.len().min(8)]));
+            }
 
             // Display full commit message
             Output::sub_item("Commit Message:");
@@ -1065,6 +1987,23 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
         }
     }
 
+    if verbose && !stack_superseded.is_empty() {
+        Output::section("Superseded Entries");
+        for superseded in &stack_superseded {
+            let pr = superseded
+                .pull_request_id
+                .as_deref()
+                .map(|id| format!(" (PR #{id} closed)"))
+                .unwrap_or_default();
+            Output::bullet(format!(
+                "{} {}{pr} on {}",
+                &superseded.commit_hash[..superseded.commit_hash.len().min(8)],
+                superseded.message.lines().next().unwrap_or(""),
+                superseded.superseded_at.format("%Y-%m-%d")
+            ));
+        }
+    }
+
     // Enhanced PR status if requested and available
     if show_mergeable {
         Output::section("Mergeability Status");
@@ -1180,7 +2119,7 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
                                     style("Unknown").dim().to_string()
                                 }
                             };
-                            println!("      Builds: {}", build_display);
+                            Output::line(format!("      Builds: {}", build_display));
 
                             // Review status
                             let review_display = if enhanced.review_status.can_merge {
@@ -1200,10 +2139,30 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
                             } else {
                                 style("Pending").yellow().to_string()
                             };
-                            println!("      Reviews: {}", review_display);
-
-                            // Merge status
-                            if !enhanced.mergeable.unwrap_or(false) {
+                            Output::line(format!("      Reviews: {}", review_display));
+
+                            if !enhanced.open_tasks.is_empty() {
+                                let count = enhanced.open_tasks.len();
+                                Output::line(format!(
+                                    "      Tasks: {}",
+                                    style(format!(
+                                        "{count} open task{}",
+                                        if count == 1 { "" } else { "s" }
+                                    ))
+                                    .yellow()
+                                ));
+                                if verbose {
+                                    for task in &enhanced.open_tasks {
+                                        Output::line(format!(
+                                            "        [{}] {}",
+                                            task.id, task.text
+                                        ));
+                                    }
+                                }
+                            }
+
+                            // Merge status
+                            if !enhanced.mergeable.unwrap_or(false) {
                                 // Show simplified blocking reason (just the first/most important one)
                                 let blocking = enhanced.get_blocking_reasons();
                                 if !blocking.is_empty() {
@@ -1226,29 +2185,35 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
                                         "Blocked by repository policy"
                                     };
 
-                                    println!("      Merge: {}", style(simplified).red());
+                                    Output::line(format!(
+                                        "      Merge: {}",
+                                        style(simplified).red()
+                                    ));
                                 }
                             } else if enhanced.is_ready_to_land() {
-                                println!("      Merge: {}", style("Ready").green());
+                                Output::line(format!("      Merge: {}", style("Ready").green()));
                             }
                         }
 
                         if verbose {
-                            println!(
+                            Output::line(format!(
                                 "      {} -> {}",
                                 enhanced.pr.from_ref.display_id, enhanced.pr.to_ref.display_id
-                            );
+                            ));
 
                             // Show blocking reasons if not ready
                             if !enhanced.is_ready_to_land() {
                                 let blocking = enhanced.get_blocking_reasons();
                                 if !blocking.is_empty() {
-                                    println!("      Blocking: {}", blocking.join(", "));
+                                    Output::line(format!(
+                                        "      Blocking: {}",
+                                        blocking.join(", ")
+                                    ));
                                 }
                             }
 
                             // Show review details (actual count from Bitbucket)
-                            println!(
+                            Output::line(format!(
                                 "      Reviews: {} approval{}",
                                 enhanced.review_status.current_approvals,
                                 if enhanced.review_status.current_approvals == 1 {
@@ -1256,13 +2221,13 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
                                 } else {
                                     "s"
                                 }
-                            );
+                            ));
 
                             if enhanced.review_status.needs_work_count > 0 {
-                                println!(
+                                Output::line(format!(
                                     "      {} reviewers requested changes",
                                     enhanced.review_status.needs_work_count
-                                );
+                                ));
                             }
 
                             // Show build status
@@ -1273,22 +2238,35 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
                                     crate::bitbucket::pull_request::BuildState::InProgress => "~",
                                     _ => "○",
                                 };
-                                println!("      Build: {} {:?}", build_icon, build.state);
+                                Output::line(format!(
+                                    "      Build: {} {:?}",
+                                    build_icon, build.state
+                                ));
                             }
 
                             if let Some(url) = enhanced.pr.web_url() {
-                                println!("      URL: {url}");
+                                Output::line(format!("      URL: {url}"));
                             }
-                            println!();
+                            Output::spacing();
                         }
                     }
 
+                    if status
+                        .enhanced_statuses
+                        .iter()
+                        .any(|enhanced| enhanced.pr.is_open() && !enhanced.open_tasks.is_empty())
+                    {
+                        Output::tip(
+                            "'ca land --auto' waits for open tasks; see them with 'ca entry tasks list'",
+                        );
+                    }
+
                     if ready_to_land > 0 {
-                        println!(
+                        Output::line(format!(
                             "\n🎯 {} PR{} ready to land! Use 'ca land' to land them all.",
                             ready_to_land,
                             if ready_to_land == 1 { " is" } else { "s are" }
-                        );
+                        ));
                     }
                 }
             }
@@ -1316,16 +2294,19 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
 
         match integration.check_stack_status(&stack_id).await {
             Ok(status) => {
-                println!("\nPull Request Status:");
-                println!("   Total entries: {}", status.total_entries);
-                println!("   Submitted: {}", status.submitted_entries);
-                println!("   Open PRs: {}", status.open_prs);
-                println!("   Merged PRs: {}", status.merged_prs);
-                println!("   Declined PRs: {}", status.declined_prs);
-                println!("   Completion: {:.1}%", status.completion_percentage());
+                Output::line("\nPull Request Status:");
+                Output::line(format!("   Total entries: {}", status.total_entries));
+                Output::line(format!("   Submitted: {}", status.submitted_entries));
+                Output::line(format!("   Open PRs: {}", status.open_prs));
+                Output::line(format!("   Merged PRs: {}", status.merged_prs));
+                Output::line(format!("   Declined PRs: {}", status.declined_prs));
+                Output::line(format!(
+                    "   Completion: {:.1}%",
+                    status.completion_percentage()
+                ));
 
                 if !status.pull_requests.is_empty() {
-                    println!("\nPull Requests:");
+                    Output::line("\nPull Requests:");
                     for pr in &status.pull_requests {
                         use console::style;
 
@@ -1344,7 +2325,7 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
 
                         // Format: icon PR #123: title (from -> to)
                         // Dim the PR number and branch arrows for less visual noise
-                        println!(
+                        Output::line(format!(
                             "   {} PR {}: {} ({} {} {})",
                             state_icon,
                             style(format!("#{}", pr.id)).dim(),
@@ -1352,16 +2333,16 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
                             style(&pr.from_ref.display_id).dim(),
                             style("→").dim(),
                             style(&pr.to_ref.display_id).dim()
-                        );
+                        ));
 
                         // Make URL stand out with cyan/blue hyperlink color
                         if let Some(url) = pr.web_url() {
-                            println!("      URL: {}", style(url).cyan().underlined());
+                            Output::line(format!("      URL: {}", style(url).cyan().underlined()));
                         }
                     }
                 }
 
-                println!();
+                Output::spacing();
                 Output::tip("Use 'ca stack --mergeable' to see detailed status including build and review information");
             }
             Err(e) => {
@@ -1373,20 +2354,192 @@ async fn show_stack(verbose: bool, show_mergeable: bool) -> Result<()> {
     Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
-async fn push_to_stack(
-    branch: Option<String>,
-    message: Option<String>,
-    commit: Option<String>,
-    since: Option<String>,
-    commits: Option<String>,
-    squash: Option<usize>,
-    squash_since: Option<String>,
-    auto_branch: bool,
-    allow_base_branch: bool,
+/// Create a stack named after the current branch when `cascade.auto_create_stack` is enabled.
+///
+/// Does nothing (leaving the usual "no active stack" error to the caller) when the setting
+/// is off, we're on the default base branch, or a stack with that name already exists.
+fn offer_stack_for_branch(
+    manager: &mut StackManager,
+    repo: &GitRepository,
+    repo_root: &std::path::Path,
+    yes: bool,
+) -> Result<()> {
+    let config_path = crate::config::get_repo_config_dir(repo_root)?.join("config.json");
+    let auto_create = crate::config::Settings::load_from_file(&config_path)
+        .map(|settings| settings.cascade.auto_create_stack)
+        .unwrap_or(false);
+    if !auto_create {
+        return Ok(());
+    }
+
+    let current_branch = repo.get_current_branch()?;
+    if current_branch == manager.get_repository_metadata().default_base_branch
+        || manager.get_stack_by_name(&current_branch).is_some()
+    {
+        return Ok(());
+    }
+
+    if !yes {
+        let should_create = prompter().confirm(
+            &format!("No active stack. Create stack '{current_branch}' for this branch?"),
+            true,
+        )?;
+
+        if !should_create {
+            return Ok(());
+        }
+    }
+
+    let stack_id = manager.create_stack(current_branch.clone(), None, None)?;
+    let base_branch = manager
+        .get_stack(&stack_id)
+        .map(|stack| stack.base_branch.clone())
+        .unwrap_or_default();
+    Output::success(format!(
+        "Created stack '{current_branch}' (base: {base_branch})"
+    ));
+
+    Ok(())
+}
+
+/// Commits made on the branch of an entry with others stacked on top are missing from those
+/// entries, so pushing them where they are would fork the stack. Offer to move them on top of
+/// the stack, where they're pushed as new entries.
+fn move_commits_off_lower_entry(
+    manager: &StackManager,
+    repo: &GitRepository,
     dry_run: bool,
     yes: bool,
 ) -> Result<()> {
+    let Some(stack) = manager.get_active_stack() else {
+        return Ok(());
+    };
+    let current_branch = repo.get_current_branch()?;
+    let Some(index) = stack.lower_entry_index(&current_branch) else {
+        return Ok(());
+    };
+    // New entries are cut from the commits on the working branch
+    let Some(working_branch) = stack
+        .working_branch
+        .clone()
+        .filter(|working| *working != current_branch && repo.branch_exists(working))
+    else {
+        return Ok(());
+    };
+    let entry = &stack.entries[index];
+    let head = repo.get_head_commit_hash()?;
+    // An entry amended in place isn't an ancestor of HEAD; that's the edit flows' business
+    if head == entry.commit_hash || !repo.is_descendant_of(&head, &entry.commit_hash)? {
+        return Ok(());
+    }
+
+    let mut new_commits: Vec<String> = repo
+        .get_commits_between(&entry.commit_hash, &head)?
+        .iter()
+        .map(|c| c.id().to_string())
+        .collect();
+    new_commits.reverse();
+
+    Output::warning(format!(
+        "You're on '{current_branch}', the branch of entry {} of {} in stack '{}'",
+        index + 1,
+        stack.entries.len(),
+        stack.name
+    ));
+    Output::sub_item(format!(
+        "{} commit(s) made here are missing from the {} entries stacked above it",
+        new_commits.len(),
+        stack.entries.len() - index - 1
+    ));
+
+    if dry_run {
+        Output::info(format!(
+            "Would move them on top of '{working_branch}' and push them as new entries"
+        ));
+        return Ok(());
+    }
+
+    if !yes {
+        let should_move = prompter().confirm(
+            &format!("Move them to the top of the stack ('{working_branch}')?"),
+            true,
+        )?;
+
+        if !should_move {
+            Output::line("\n   Options:");
+            Output::line(format!(
+                "   1. Fold the changes into entry {} instead:",
+                index + 1
+            ));
+            Output::line(format!("      git reset --soft {}", entry.short_hash()));
+            Output::line(format!("      ca entry amend {}", index + 1));
+            Output::line("\n   2. Move them to the top of the stack:");
+            Output::line("      ca push --yes");
+
+            return Err(CascadeError::config(format!(
+                "Refusing to push commits from '{current_branch}', which has entries stacked on top"
+            )));
+        }
+    }
+
+    move_commits_to_top(
+        repo,
+        &current_branch,
+        &entry.commit_hash,
+        &working_branch,
+        &new_commits,
+    )?;
+    Output::success(format!(
+        "Moved {} commit(s) to '{working_branch}' and reset '{current_branch}' to entry {}",
+        new_commits.len(),
+        index + 1
+    ));
+    Ok(())
+}
+
+/// Replay `commits` on top of `working_branch` and switch to it, then reset `entry_branch` to the
+/// entry's own commit. Nothing changes if a commit doesn't apply cleanly.
+fn move_commits_to_top(
+    repo: &GitRepository,
+    entry_branch: &str,
+    entry_commit: &str,
+    working_branch: &str,
+    commits: &[String],
+) -> Result<()> {
+    let old_tip = repo.get_branch_head(working_branch)?;
+    let mut new_tip = old_tip.clone();
+    for commit in commits {
+        new_tip = repo.cherry_pick_onto(commit, &new_tip, None).map_err(|e| {
+            CascadeError::branch(format!(
+                "Could not move commit {} on top of '{working_branch}': {e}",
+                &commit[..commit.len().min(8)]
+            ))
+        })?;
+    }
+
+    repo.update_branch_to_commit(working_branch, &new_tip)?;
+    if let Err(e) = repo.checkout_branch_silent(working_branch) {
+        repo.update_branch_to_commit(working_branch, &old_tip)?;
+        return Err(e);
+    }
+    repo.update_branch_to_commit(entry_branch, entry_commit)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn push_to_stack(spec: PushSpec) -> Result<()> {
+    let PushSpec {
+        branch,
+        message,
+        selector,
+        squash,
+        edit_message,
+        group,
+        auto_branch,
+        allow_base_branch,
+        dry_run,
+        yes,
+    } = spec;
+
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
 
@@ -1396,10 +2549,14 @@ async fn push_to_stack(
     let mut manager = StackManager::new(&repo_root)?;
     let repo = GitRepository::open(&repo_root)?;
 
+    if manager.get_active_stack().is_none() {
+        offer_stack_for_branch(&mut manager, &repo, &repo_root, yes)?;
+    }
+
     // Get the active stack to check base branch
-    let active_stack = manager.get_active_stack().ok_or_else(|| {
-        CascadeError::config("No active stack. Create a stack first with 'ca stack create'")
-    })?;
+    let active_stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
 
     // 🛡️ BASE BRANCH PROTECTION
     let current_branch = repo.get_current_branch()?;
@@ -1431,19 +2588,19 @@ async fn push_to_stack(
                     repo.checkout_branch(&feature_branch)?;
 
                     Output::success(format!("Created and switched to '{feature_branch}'"));
-                    println!("   You can now commit and push your changes safely");
+                    Output::line("   You can now commit and push your changes safely");
 
                     // Continue with normal flow
                 } else {
-                    println!("\nYou have uncommitted changes. Here are your options:");
-                    println!("   1. Create a feature branch first:");
-                    println!("      git checkout -b feature/my-work");
-                    println!("      git commit -am \"your work\"");
-                    println!("      ca push");
-                    println!("\n   2. Auto-create a branch (recommended):");
-                    println!("      ca push --auto-branch");
-                    println!("\n   3. Force push to base branch (dangerous):");
-                    println!("      ca push --allow-base-branch");
+                    Output::line("\nYou have uncommitted changes. Here are your options:");
+                    Output::line("   1. Create a feature branch first:");
+                    Output::line("      git checkout -b feature/my-work");
+                    Output::line("      git commit -am \"your work\"");
+                    Output::line("      ca push");
+                    Output::line("\n   2. Auto-create a branch (recommended):");
+                    Output::line("      ca push --auto-branch");
+                    Output::line("\n   3. Force push to base branch (dangerous):");
+                    Output::line("      ca push --allow-base-branch");
 
                     return Err(CascadeError::config(
                         "Refusing to push uncommitted changes from base branch. Use one of the options above."
@@ -1451,49 +2608,7 @@ async fn push_to_stack(
                 }
             } else {
                 // Check if there are existing commits to push
-                let commits_to_check = if let Some(commits_str) = &commits {
-                    commits_str
-                        .split(',')
-                        .map(|s| s.trim().to_string())
-                        .collect::<Vec<String>>()
-                } else if let Some(since_ref) = &since {
-                    let since_commit = repo.resolve_reference(since_ref)?;
-                    let head_commit = repo.get_head_commit()?;
-                    let commits = repo.get_commits_between(
-                        &since_commit.id().to_string(),
-                        &head_commit.id().to_string(),
-                    )?;
-                    commits.into_iter().map(|c| c.id().to_string()).collect()
-                } else if commit.is_none() {
-                    let mut unpushed = Vec::new();
-                    let head_commit = repo.get_head_commit()?;
-                    let mut current_commit = head_commit;
-
-                    loop {
-                        let commit_hash = current_commit.id().to_string();
-                        let already_in_stack = active_stack
-                            .entries
-                            .iter()
-                            .any(|entry| entry.commit_hash == commit_hash);
-
-                        if already_in_stack {
-                            break;
-                        }
-
-                        unpushed.push(commit_hash);
-
-                        if let Some(parent) = current_commit.parents().next() {
-                            current_commit = parent;
-                        } else {
-                            break;
-                        }
-                    }
-
-                    unpushed.reverse();
-                    unpushed
-                } else {
-                    vec![repo.get_head_commit()?.id().to_string()]
-                };
+                let commits_to_check = selector.resolve(&repo, active_stack)?;
 
                 if !commits_to_check.is_empty() {
                     if auto_branch {
@@ -1507,13 +2622,15 @@ async fn push_to_stack(
                         repo.checkout_branch(&feature_branch)?;
 
                         // Cherry-pick the commits to the new branch
-                        println!(
+                        Output::line(format!(
                             "🍒 Cherry-picking {} commit(s) to new branch...",
                             commits_to_check.len()
-                        );
+                        ));
                         for commit_hash in &commits_to_check {
                             match repo.cherry_pick(commit_hash) {
-                                Ok(_) => println!("   ✅ Cherry-picked {}", &commit_hash[..8]),
+                                Ok(_) => {
+                                    Output::success(format!("Cherry-picked {}", &commit_hash[..8]))
+                                }
                                 Err(e) => {
                                     Output::error(format!(
                                         "Failed to cherry-pick {}: {}",
@@ -1528,29 +2645,29 @@ async fn push_to_stack(
                             }
                         }
 
-                        println!(
+                        Output::line(format!(
                             "✅ Successfully moved {} commit(s) to '{feature_branch}'",
                             commits_to_check.len()
-                        );
-                        println!(
-                            "   You're now on the feature branch and can continue with 'ca push'"
+                        ));
+                        Output::line(
+                            "   You're now on the feature branch and can continue with 'ca push'",
                         );
 
                         // Continue with normal flow
                     } else {
-                        println!(
+                        Output::line(format!(
                             "\n💡 Found {} commit(s) to push from base branch '{base_branch}'",
                             commits_to_check.len()
-                        );
-                        println!("   These commits are currently ON the base branch, which may not be intended.");
-                        println!("\n   Options:");
-                        println!("   1. Auto-create feature branch and cherry-pick commits:");
-                        println!("      ca push --auto-branch");
-                        println!("\n   2. Manually create branch and move commits:");
-                        println!("      git checkout -b feature/my-work");
-                        println!("      ca push");
-                        println!("\n   3. Force push from base branch (not recommended):");
-                        println!("      ca push --allow-base-branch");
+                        ));
+                        Output::line("   These commits are currently ON the base branch, which may not be intended.");
+                        Output::line("\n   Options:");
+                        Output::line("   1. Auto-create feature branch and cherry-pick commits:");
+                        Output::line("      ca push --auto-branch");
+                        Output::line("\n   2. Manually create branch and move commits:");
+                        Output::line("      git checkout -b feature/my-work");
+                        Output::line("      ca push");
+                        Output::line("\n   3. Force push from base branch (not recommended):");
+                        Output::line("      ca push --allow-base-branch");
 
                         return Err(CascadeError::config(
                             "Refusing to push commits from base branch. Use --auto-branch or create a feature branch manually."
@@ -1561,46 +2678,70 @@ async fn push_to_stack(
         }
     }
 
+    // 🛡️ LOWER ENTRY PROTECTION
+    if !selector.is_explicit() && !manager.is_in_edit_mode() {
+        move_commits_off_lower_entry(&manager, &repo, dry_run, yes)?;
+    }
+
+    let squash_message = crate::config::get_repo_config_dir(&repo_root)
+        .and_then(|dir| crate::config::Settings::load_from_file(&dir.join("config.json")))
+        .map(|settings| settings.cascade.squash_message)
+        .unwrap_or_default();
+
     // Handle squash operations first
-    if let Some(squash_count) = squash {
-        if squash_count == 0 {
+    match squash {
+        Some(Squash::Unpushed) => {
             // User used --squash without specifying count, auto-detect unpushed commits
-            let active_stack = manager.get_active_stack().ok_or_else(|| {
-                CascadeError::config(
-                    "No active stack. Create a stack first with 'ca stacks create'",
-                )
-            })?;
+            let active_stack = manager
+                .get_active_stack()
+                .ok_or_else(CascadeError::no_active_stack)?;
 
-            let unpushed_count = get_unpushed_commits(&repo, active_stack)?.len();
+            let unpushed_count = unpushed_commits(&repo, active_stack)?.len();
 
             if unpushed_count == 0 {
                 Output::info("  No unpushed commits to squash");
             } else if unpushed_count == 1 {
                 Output::info("  Only 1 unpushed commit, no squashing needed");
             } else {
-                println!(" Auto-detected {unpushed_count} unpushed commits, squashing...");
-                squash_commits(&repo, unpushed_count, None).await?;
-                Output::success(" Squashed {unpushed_count} unpushed commits into one");
+                Output::line(format!(
+                    " Auto-detected {unpushed_count} unpushed commits, squashing..."
+                ));
+                squash_commits(&repo, unpushed_count, None, &squash_message, edit_message).await?;
+                Output::success(format!(
+                    " Squashed {unpushed_count} unpushed commits into one"
+                ));
             }
-        } else {
-            println!(" Squashing last {squash_count} commits...");
-            squash_commits(&repo, squash_count, None).await?;
-            Output::success(" Squashed {squash_count} commits into one");
         }
-    } else if let Some(since_ref) = squash_since {
-        println!(" Squashing commits since {since_ref}...");
-        let since_commit = repo.resolve_reference(&since_ref)?;
-        let commits_count = count_commits_since(&repo, &since_commit.id().to_string())?;
-        squash_commits(&repo, commits_count, Some(since_ref.clone())).await?;
-        Output::success(" Squashed {commits_count} commits since {since_ref} into one");
+        Some(Squash::Last(squash_count)) => {
+            Output::line(format!(" Squashing last {squash_count} commits..."));
+            squash_commits(&repo, squash_count, None, &squash_message, edit_message).await?;
+            Output::success(format!(" Squashed {squash_count} commits into one"));
+        }
+        Some(Squash::Since(since_ref)) => {
+            Output::line(format!(" Squashing commits since {since_ref}..."));
+            let since_commit = repo.resolve_reference(&since_ref)?;
+            let commits_count = count_commits_since(&repo, &since_commit.id().to_string())?;
+            squash_commits(
+                &repo,
+                commits_count,
+                Some(since_ref.clone()),
+                &squash_message,
+                edit_message,
+            )
+            .await?;
+            Output::success(format!(
+                " Squashed {commits_count} commits since {since_ref} into one"
+            ));
+        }
+        None => {}
     }
 
     // 🛡️ STALE BASE DETECTION
     // Only check when user didn't specify explicit commits
-    if commits.is_none() && since.is_none() && commit.is_none() {
-        let active_stack_for_stale = manager.get_active_stack().ok_or_else(|| {
-            CascadeError::config("No active stack. Create a stack first with 'ca stacks create'")
-        })?;
+    if !selector.is_explicit() {
+        let active_stack_for_stale = manager
+            .get_active_stack()
+            .ok_or_else(CascadeError::no_active_stack)?;
         let stale_base = &active_stack_for_stale.base_branch;
         let stale_current = repo.get_current_branch()?;
 
@@ -1616,15 +2757,7 @@ async fn push_to_stack(
                     Output::tip("Run 'ca sync' or 'ca stacks rebase' to rebase first.");
 
                     if !dry_run && !yes {
-                        let should_rebase = Confirm::with_theme(&ColorfulTheme::default())
-                            .with_prompt("Rebase before pushing?")
-                            .default(true)
-                            .interact()
-                            .map_err(|e| {
-                                CascadeError::config(format!(
-                                    "Failed to get user confirmation: {e}"
-                                ))
-                            })?;
+                        let should_rebase = prompter().confirm("Rebase before pushing?", true)?;
 
                         if should_rebase {
                             Output::info(
@@ -1640,93 +2773,16 @@ async fn push_to_stack(
     }
 
     // Determine which commits to push
-    let commits_to_push = if let Some(commits_str) = commits {
-        // Parse comma-separated commit hashes
-        commits_str
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .collect::<Vec<String>>()
-    } else if let Some(since_ref) = since {
-        // Get commits since the specified reference
-        let since_commit = repo.resolve_reference(&since_ref)?;
-        let head_commit = repo.get_head_commit()?;
-
-        // Get commits between since_ref and HEAD
-        let commits = repo.get_commits_between(
-            &since_commit.id().to_string(),
-            &head_commit.id().to_string(),
-        )?;
-        commits.into_iter().map(|c| c.id().to_string()).collect()
-    } else if let Some(hash) = commit {
-        // Single specific commit
-        vec![hash]
-    } else {
-        // Default: Get all unpushed commits (commits on current branch but not on base branch)
-        let active_stack = manager.get_active_stack().ok_or_else(|| {
-            CascadeError::config("No active stack. Create a stack first with 'ca stacks create'")
-        })?;
-
-        // Get commits that are on current branch but not on the base branch
-        let base_branch = &active_stack.base_branch;
-        let current_branch = repo.get_current_branch()?;
-
-        // If we're on the base branch, only include commits that aren't already in the stack
-        if current_branch == *base_branch {
-            let mut unpushed = Vec::new();
-            let head_commit = repo.get_head_commit()?;
-            let mut current_commit = head_commit;
-
-            // Walk back from HEAD until we find a commit that's already in the stack
-            loop {
-                let commit_hash = current_commit.id().to_string();
-                let already_in_stack = active_stack
-                    .entries
-                    .iter()
-                    .any(|entry| entry.commit_hash == commit_hash);
-
-                if already_in_stack {
-                    break;
-                }
-
-                unpushed.push(commit_hash);
-
-                // Move to parent commit
-                if let Some(parent) = current_commit.parents().next() {
-                    current_commit = parent;
-                } else {
-                    break;
-                }
-            }
-
-            unpushed.reverse(); // Reverse to get chronological order
-            unpushed
-        } else {
-            // Use git's commit range calculation to find commits on current branch but not on base
-            match repo.get_commits_between(base_branch, &current_branch) {
-                Ok(commits) => {
-                    let mut unpushed: Vec<String> =
-                        commits.into_iter().map(|c| c.id().to_string()).collect();
-
-                    // Filter out commits that are already in the stack
-                    unpushed.retain(|commit_hash| {
-                        !active_stack
-                            .entries
-                            .iter()
-                            .any(|entry| entry.commit_hash == *commit_hash)
-                    });
-
-                    unpushed.reverse(); // Reverse to get chronological order (oldest first)
-                    unpushed
-                }
-                Err(e) => {
-                    return Err(CascadeError::branch(format!(
-                            "Failed to calculate commits between '{base_branch}' and '{current_branch}': {e}. \
-                             This usually means the branches have diverged or don't share common history."
-                        )));
-                }
-            }
-        }
-    };
+    let active_stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
+    let mut commits_to_push = selector.resolve(&repo, active_stack)?;
+    let mut grouping = Grouping::PerCommit;
+    if selector == CommitSelector::Interactive {
+        let picked = pick_commits(&repo, commits_to_push)?;
+        commits_to_push = picked.commits;
+        grouping = picked.grouping;
+    }
 
     if commits_to_push.is_empty() {
         Output::info("  No commits to push to stack");
@@ -1787,6 +2843,31 @@ async fn push_to_stack(
         ));
     }
 
+    // How many consecutive commits go into each entry
+    let group_size = match grouping {
+        Grouping::Grouped => commits_to_push.len(),
+        _ => group.unwrap_or(1),
+    };
+    if group_size > 1 {
+        for commits in commits_to_push.chunks(group_size) {
+            ensure_consecutive(&repo, commits)?;
+        }
+    }
+
+    match grouping {
+        Grouping::Squashed => Output::info("These commits will be squashed into one entry"),
+        _ if group_size > 1 => Output::info(format!(
+            "These commits will be pushed as {} entr{} of up to {group_size} commits",
+            commits_to_push.len().div_ceil(group_size),
+            if commits_to_push.len() > group_size {
+                "ies"
+            } else {
+                "y"
+            }
+        )),
+        _ => {}
+    }
+
     // Early return for dry run mode
     if dry_run {
         Output::tip("Run without --dry-run to actually push these commits.");
@@ -1796,14 +2877,10 @@ async fn push_to_stack(
     // Confirmation prompt (unless --yes)
     if !yes {
         let default_confirm = !has_foreign_commits;
-        let should_continue = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(format!(
-                "Push {} commit(s) to stack?",
-                commits_to_push.len()
-            ))
-            .default(default_confirm)
-            .interact()
-            .map_err(|e| CascadeError::config(format!("Failed to get user confirmation: {e}")))?;
+        let should_continue = prompter().confirm(
+            &format!("Push {} commit(s) to stack?", commits_to_push.len()),
+            default_confirm,
+        )?;
 
         if !should_continue {
             Output::info("Push cancelled.");
@@ -1814,12 +2891,26 @@ async fn push_to_stack(
     // 🛡️ SAFEGUARDS: Analyze commits for merge commits and age checks
     analyze_commits_for_safeguards(&commits_to_push, &repo, dry_run).await?;
 
-    // Push each commit to the stack
+    if grouping == Grouping::Squashed {
+        squash_commits(
+            &repo,
+            commits_to_push.len(),
+            None,
+            &squash_message,
+            edit_message,
+        )
+        .await?;
+        commits_to_push = vec![repo.get_head_commit()?.id().to_string()];
+    }
+
+    // Push each group of commits (usually just one) to the stack as an entry
     let mut pushed_count = 0;
+    let mut pushed_entries = Vec::new();
     let mut source_branches = std::collections::HashSet::new();
 
-    for (i, commit_hash) in commits_to_push.iter().enumerate() {
-        let commit_obj = repo.get_commit(commit_hash)?;
+    for (i, commits) in commits_to_push.chunks(group_size).enumerate() {
+        let commit_hash = commits.last().expect("chunks are never empty");
+        let commit_obj = repo.get_commit(&commits[0])?;
         let commit_msg = commit_obj.message().unwrap_or("").to_string();
 
         // Check which branch this commit belongs to
@@ -1832,10 +2923,16 @@ async fn push_to_stack(
         let branch_name = if i == 0 && branch.is_some() {
             branch.clone().unwrap()
         } else {
-            // Create a temporary GitRepository for branch name generation
-            let temp_repo = GitRepository::open(&repo_root)?;
-            let branch_mgr = crate::git::BranchManager::new(temp_repo);
-            branch_mgr.generate_branch_name(&commit_msg)
+            let context = crate::git::BranchNameContext {
+                stack: manager.get_active_stack().map(|s| s.name.clone()),
+                entry_number: manager
+                    .get_active_stack()
+                    .map_or(1, |s| s.entries.len() + 1),
+                prefix: manager
+                    .get_active_stack()
+                    .and_then(|s| s.overrides.branch_prefix.clone()),
+            };
+            branch_manager_for(&repo_root)?.generate_branch_name_for(&commit_msg, &context)
         };
 
         // Use provided message for first commit, original message for others
@@ -1851,24 +2948,48 @@ async fn push_to_stack(
             final_message.clone(),
             commit_source_branch.clone(),
         )?;
-        pushed_count += 1;
+        if commits.len() > 1 {
+            if let Some(stack) = manager.get_active_stack_mut() {
+                stack.set_entry_commit_count(&entry_id, commits.len());
+            }
+            manager.save_to_disk()?;
+        }
+        pushed_count += commits.len();
+        pushed_entries.push(entry_id);
 
-        Output::success(format!(
-            "Pushed commit {}/{} to stack",
-            i + 1,
-            commits_to_push.len()
-        ));
-        Output::sub_item(format!(
-            "Commit: {} ({})",
-            &commit_hash[..8],
-            commit_msg.split('\n').next().unwrap_or("")
-        ));
+        if commits.len() > 1 {
+            Output::success(format!(
+                "Pushed commits {}-{}/{} to stack as one entry",
+                i * group_size + 1,
+                i * group_size + commits.len(),
+                commits_to_push.len()
+            ));
+            Output::sub_item(format!(
+                "Commits: {}..{} ({})",
+                &commits[0][..8],
+                &commit_hash[..8],
+                commit_msg.split('\n').next().unwrap_or("")
+            ));
+        } else {
+            Output::success(format!(
+                "Pushed commit {}/{} to stack",
+                i * group_size + 1,
+                commits_to_push.len()
+            ));
+            Output::sub_item(format!(
+                "Commit: {} ({})",
+                &commit_hash[..8],
+                commit_msg.split('\n').next().unwrap_or("")
+            ));
+        }
         Output::sub_item(format!("Branch: {branch_name}"));
         Output::sub_item(format!("Source: {commit_source_branch}"));
         Output::sub_item(format!("Entry ID: {entry_id}"));
-        println!();
+        Output::spacing();
     }
 
+    warn_file_overlaps(&repo_root, &manager, &pushed_entries);
+
     // 🚨 SCATTERED COMMIT WARNING
     if source_branches.len() > 1 {
         Output::warning("Scattered Commit Detection");
@@ -1891,7 +3012,7 @@ async fn push_to_stack(
         Output::bullet("Cherry-pick commits in order: git cherry-pick <commit1> <commit2> ...");
         Output::bullet("Delete old scattered branches");
         Output::bullet("Push the consolidated branch to your stack");
-        println!();
+        Output::spacing();
     }
 
     Output::success(format!(
@@ -1903,44 +3024,551 @@ async fn push_to_stack(
     Ok(())
 }
 
-async fn pop_from_stack(keep_branch: bool) -> Result<()> {
+/// Commits grouped into one entry must follow each other, each the first parent of the next
+fn ensure_consecutive(repo: &GitRepository, commits: &[String]) -> Result<()> {
+    for pair in commits.windows(2) {
+        let parent = repo.get_commit(&pair[1])?.parent_id(0).ok();
+        if parent.map(|id| id.to_string()).as_deref() != Some(pair[0].as_str()) {
+            return Err(CascadeError::validation(format!(
+                "Cannot group {} with {}: they aren't consecutive commits",
+                &pair[0][..8],
+                &pair[1][..8]
+            )));
+        }
+    }
+    Ok(())
+}
+
+async fn pop_from_stack(args: PopArgs) -> Result<()> {
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
 
     let repo_root = find_repository_root(&current_dir)
         .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
 
+    if args.restore_last {
+        return restore_last_pop(&repo_root).await;
+    }
+
     let mut manager = StackManager::new(&repo_root)?;
     let repo = GitRepository::open(&repo_root)?;
 
-    let entry = manager.pop_from_stack()?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?
+        .clone();
+    if stack.entries.is_empty() {
+        return Err(CascadeError::config("Stack is empty"));
+    }
+    let keep = match args.to {
+        Some(to) if to >= stack.entries.len() => {
+            return Err(CascadeError::validation(format!(
+                "--to {to} leaves nothing to pop: the stack has {} entries",
+                stack.entries.len()
+            )));
+        }
+        Some(to) => to,
+        None => stack.entries.len() - 1,
+    };
+    let to_pop = &stack.entries[keep..];
 
-    Output::success("Popped commit from stack");
-    Output::sub_item(format!(
-        "Commit: {} ({})",
-        entry.short_hash(),
-        entry.short_message(50)
-    ));
-    Output::sub_item(format!("Branch: {}", entry.branch));
+    if args.dry_run {
+        return plan_pop(&repo_root, &repo, &stack, keep, &args);
+    }
 
-    // Delete branch if requested and it's not the current branch
-    if !keep_branch && entry.branch != repo.get_current_branch()? {
-        match repo.delete_branch(&entry.branch) {
-            Ok(_) => Output::sub_item(format!("Deleted branch: {}", entry.branch)),
-            Err(e) => Output::warning(format!("Could not delete branch {}: {}", entry.branch, e)),
+    if to_pop.len() > 1 {
+        Output::info(format!("Popping {} entries:", to_pop.len()));
+        for (offset, entry) in to_pop.iter().enumerate() {
+            Output::numbered_item(
+                keep + offset + 1,
+                format!("{} {}", entry.short_hash(), entry.short_message(50)),
+            );
+        }
+        if !prompter().confirm(&format!("Pop these {} entries?", to_pop.len()), true)? {
+            Output::info("Pop cancelled.");
+            return Ok(());
         }
     }
 
-    Ok(())
-}
+    // Keep the popped commits on a branch of their own, so they survive the entries' branches
+    let lowest = &to_pop[0];
+    let top = &to_pop[to_pop.len() - 1];
+    let first_commit = repo
+        .get_first_parent_chain(&lowest.commit_hash, lowest.commit_count)?
+        .remove(0);
+    let base_commit = repo
+        .get_commit(&first_commit)?
+        .parent_id(0)
+        .map_err(CascadeError::Git)?
+        .to_string();
+    let popped_at = Utc::now();
+    let stash_branch = PoppedEntries::branch_name(&stack.name, popped_at);
+    repo.create_branch(&stash_branch, Some(&top.commit_hash))?;
+
+    // The working branch follows the stack, so it goes back to where the kept entries end
+    let current_branch = repo.get_current_branch()?;
+    if let Some(working_branch) = &stack.working_branch {
+        let points_at_top = repo
+            .get_branch_commit_hash(working_branch)
+            .is_ok_and(|hash| hash == top.commit_hash);
+        if points_at_top {
+            if *working_branch == current_branch && repo.is_dirty()? {
+                Output::warning(format!(
+                    "'{working_branch}' has uncommitted changes, so it still holds the popped commits"
+                ));
+            } else {
+                repo.update_branch_to_commit(working_branch, &base_commit)?;
+                if *working_branch == current_branch {
+                    repo.reset_to_head()?;
+                }
+            }
+        }
+    }
 
-async fn submit_entry(
-    entry: Option<usize>,
+    let entries = manager.pop_to(keep)?;
+    let config_dir = crate::config::get_repo_config_dir(&repo_root)?;
+    let mut popped = PoppedEntries {
+        stack_id: stack.id.to_string(),
+        branch: stash_branch.clone(),
+        base_commit,
+        entries: entries.clone(),
+        popped_at,
+        closed_prs: None,
+    };
+    popped.record(&config_dir)?;
+
+    if entries.len() == 1 {
+        Output::success("Popped commit from stack");
+        Output::sub_item(format!(
+            "Commit: {} ({})",
+            entries[0].short_hash(),
+            entries[0].short_message(50)
+        ));
+        Output::sub_item(format!("Branch: {}", entries[0].branch));
+    } else {
+        Output::success(format!(
+            "Popped {} entries, leaving {keep} on the stack",
+            entries.len()
+        ));
+    }
+    Output::sub_item(format!("Commits kept on: {stash_branch}"));
+
+    for entry in &entries {
+        // Delete branch if requested and it's not the current branch. Its commits are safe
+        // on the popped branch, so there's no need to check for unpushed ones
+        if !args.keep_branch && entry.branch != current_branch {
+            match repo.delete_branch_unsafe(&entry.branch) {
+                Ok(_) => Output::sub_item(format!("Deleted branch: {}", entry.branch)),
+                Err(e) => {
+                    Output::warning(format!("Could not delete branch {}: {}", entry.branch, e))
+                }
+            }
+        }
+    }
+
+    let override_action = args.keep_pr.then_some(RemovedPrAction::Keep);
+    let closed = handle_removed_prs(
+        &repo_root,
+        &stack.name,
+        &entries,
+        "ca pop",
+        override_action,
+        None,
+    )
+    .await;
+    if !closed.pr_ids.is_empty() {
+        popped.closed_prs = Some(closed);
+        PoppedEntries::forget(&config_dir, &popped.branch)?;
+        popped.record(&config_dir)?;
+    }
+    Output::tip("Put them back with: ca pop --restore-last");
+
+    Ok(())
+}
+
+/// `ca pop --dry-run`: the entries popping from `keep` on would remove, and what would
+/// happen to their branches, their pull requests and the working branch
+fn plan_pop(
+    repo_root: &std::path::Path,
+    repo: &GitRepository,
+    stack: &crate::stack::Stack,
+    keep: usize,
+    args: &PopArgs,
+) -> Result<()> {
+    let to_pop = &stack.entries[keep..];
+    Output::section(format!(
+        "Dry run: pop {} entr{} from stack '{}'",
+        to_pop.len(),
+        if to_pop.len() == 1 { "y" } else { "ies" },
+        stack.name
+    ));
+    for (offset, entry) in to_pop.iter().enumerate() {
+        Output::numbered_item(
+            keep + offset + 1,
+            format!("{} {}", entry.short_hash(), entry.short_message(50)),
+        );
+    }
+
+    let top = &to_pop[to_pop.len() - 1];
+    Output::spacing();
+    Output::sub_item(format!(
+        "Commits kept on: {}",
+        PoppedEntries::branch_name(&stack.name, Utc::now())
+    ));
+
+    let current_branch = repo.get_current_branch()?;
+    if let Some(working_branch) = &stack.working_branch {
+        let points_at_top = repo
+            .get_branch_commit_hash(working_branch)
+            .is_ok_and(|hash| hash == top.commit_hash);
+        if points_at_top {
+            let lowest = &to_pop[0];
+            let first_commit = repo
+                .get_first_parent_chain(&lowest.commit_hash, lowest.commit_count)?
+                .remove(0);
+            let base_commit = repo
+                .get_commit(&first_commit)?
+                .parent_id(0)
+                .map_err(CascadeError::Git)?
+                .to_string();
+            if *working_branch == current_branch && repo.is_dirty()? {
+                Output::sub_item(format!(
+                    "'{working_branch}' has uncommitted changes, so it would stay where it is"
+                ));
+            } else {
+                Output::sub_item(format!(
+                    "Working branch '{working_branch}' moves from {} to {}",
+                    top.short_hash(),
+                    &base_commit[..8]
+                ));
+            }
+        }
+    }
+
+    for entry in to_pop {
+        if args.keep_branch {
+            Output::sub_item(format!("Branch kept: {}", entry.branch));
+        } else if entry.branch == current_branch {
+            Output::sub_item(format!("Branch kept (checked out): {}", entry.branch));
+        } else {
+            Output::sub_item(format!("Branch deleted: {}", entry.branch));
+        }
+    }
+
+    let pr_ids = open_pr_ids(to_pop);
+    if !pr_ids.is_empty() {
+        let action = if args.keep_pr {
+            RemovedPrAction::Keep
+        } else {
+            crate::config::get_repo_config_dir(repo_root)
+                .and_then(|dir| crate::config::Settings::load_from_file(&dir.join("config.json")))
+                .map(|settings| settings.cascade.removed_pr_action)
+                .unwrap_or(RemovedPrAction::Keep)
+        };
+        let action = match action {
+            RemovedPrAction::Prompt if prompt::assume_yes() => RemovedPrAction::Decline,
+            RemovedPrAction::Prompt if !std::io::stdin().is_terminal() => RemovedPrAction::Keep,
+            action => action,
+        };
+        for pr_id in pr_ids {
+            Output::sub_item(match action {
+                RemovedPrAction::Decline => format!("PR #{pr_id} declined"),
+                RemovedPrAction::Draft => format!("PR #{pr_id} converted to a draft"),
+                RemovedPrAction::Keep => format!("PR #{pr_id} left open"),
+                RemovedPrAction::Prompt => {
+                    format!("PR #{pr_id}: you'd be asked whether to decline, draft or keep it")
+                }
+            });
+        }
+    }
+
+    Output::spacing();
+    Output::tip("Run without --dry-run to pop");
+    Ok(())
+}
+
+/// Put back the entries removed by the most recent pop of the active stack, replaying their
+/// commits when the stack has changed since
+async fn restore_last_pop(repo_root: &std::path::Path) -> Result<()> {
+    let mut manager = StackManager::new(repo_root)?;
+    let repo = GitRepository::open(repo_root)?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?
+        .clone();
+
+    let config_dir = crate::config::get_repo_config_dir(repo_root)?;
+    let popped = PoppedEntries::last_for(&config_dir, &stack.id.to_string())?
+        .ok_or_else(|| CascadeError::config(format!("Nothing popped from '{}'", stack.name)))?;
+
+    // The popped entries go on top of the stack as it is now
+    let onto = match stack.entries.last() {
+        Some(entry) => entry.commit_hash.clone(),
+        None => popped.base_commit.clone(),
+    };
+    let current_branch = repo.get_current_branch()?;
+    let commits = if onto == popped.base_commit {
+        popped
+            .entries
+            .iter()
+            .map(|entry| entry.commit_hash.clone())
+            .collect::<Vec<_>>()
+    } else {
+        if let Some(entry) = popped.entries.iter().find(|e| e.branch == current_branch) {
+            return Err(CascadeError::branch(format!(
+                "Switch off '{}' first: its entry has to be replayed onto the stack",
+                entry.branch
+            )));
+        }
+        Output::info(format!(
+            "The stack changed since the pop; replaying the entries onto {}",
+            &onto[..8]
+        ));
+        let mut tip = onto.clone();
+        let mut commits = Vec::with_capacity(popped.entries.len());
+        for entry in &popped.entries {
+            tip = repo
+                .cherry_pick_chain_onto(&entry.commit_hash, entry.commit_count, &tip, None)
+                .map_err(|e| {
+                    CascadeError::branch(format!(
+                        "Could not replay '{}' onto the stack: {e}. The commits are still on '{}'",
+                        entry.branch, popped.branch
+                    ))
+                })?;
+            commits.push(tip.clone());
+        }
+        commits
+    };
+
+    for (entry, commit) in popped.entries.iter().zip(&commits) {
+        if entry.branch != current_branch {
+            repo.update_branch_to_commit(&entry.branch, commit)?;
+        }
+    }
+    let new_top = commits[commits.len() - 1].clone();
+    if let Some(working_branch) = &stack.working_branch {
+        let points_at_onto = repo
+            .get_branch_commit_hash(working_branch)
+            .is_ok_and(|hash| hash == onto);
+        if points_at_onto {
+            if *working_branch == current_branch && repo.is_dirty()? {
+                Output::warning(format!(
+                    "'{working_branch}' has uncommitted changes, so it wasn't moved to the restored entries"
+                ));
+            } else {
+                repo.update_branch_to_commit(working_branch, &new_top)?;
+                if *working_branch == current_branch {
+                    repo.reset_to_head()?;
+                }
+            }
+        }
+    }
+
+    let count = popped.entries.len();
+    manager.restore_entries(&stack.id, popped.entries, &commits)?;
+    PoppedEntries::forget(&config_dir, &popped.branch)?;
+    if let Err(e) = repo.delete_branch_unsafe(&popped.branch) {
+        Output::warning(format!("Could not delete branch {}: {e}", popped.branch));
+    }
+
+    Output::success(format!(
+        "Restored {count} {} to '{}'",
+        if count == 1 { "entry" } else { "entries" },
+        stack.name
+    ));
+    if let Some(closed) = &popped.closed_prs {
+        restore_removed_prs(repo_root, closed).await;
+    }
+    Ok(())
+}
+
+/// PR numbers of the entries whose pull requests haven't merged
+fn open_pr_ids(entries: &[crate::stack::StackEntry]) -> Vec<u64> {
+    entries
+        .iter()
+        .filter(|e| !e.is_merged)
+        .filter_map(|e| e.pull_request_id.as_deref())
+        .filter_map(|id| id.parse().ok())
+        .collect()
+}
+
+/// Decline or draft the open pull requests of entries just removed from `stack_name`, as
+/// `cascade.removed_pr_action` (or `action`, when given) says. `prompt` asks which; it
+/// becomes `unattended` when that's given and `keep` without a terminal. `command` is named
+/// in the comment left on each PR. Returns the PRs that were changed. Failures only warn:
+/// the entries are already gone by now
+async fn handle_removed_prs(
+    repo_root: &std::path::Path,
+    stack_name: &str,
+    entries: &[crate::stack::StackEntry],
+    command: &str,
+    action: Option<RemovedPrAction>,
+    unattended: Option<RemovedPrAction>,
+) -> ClosedPrs {
+    let pr_ids = open_pr_ids(entries);
+    let mut closed = ClosedPrs {
+        action: RemovedPrAction::Keep,
+        pr_ids: Vec::new(),
+    };
+    if pr_ids.is_empty() {
+        return closed;
+    }
+
+    let settings = match crate::config::get_repo_config_dir(repo_root)
+        .and_then(|dir| crate::config::Settings::load_from_file(&dir.join("config.json")))
+    {
+        Ok(settings) => settings,
+        Err(e) => {
+            Output::warning(format!("Could not load settings, leaving PRs open: {e}"));
+            return closed;
+        }
+    };
+
+    let mut action = action.unwrap_or(settings.cascade.removed_pr_action);
+    if action == RemovedPrAction::Prompt {
+        action = if let Some(unattended) = unattended {
+            unattended
+        } else if std::io::stdin().is_terminal() {
+            let choice = prompter()
+                .select(
+                    &format!(
+                        "The removed entries have {} open pull request(s). What should happen to them?",
+                        pr_ids.len()
+                    ),
+                    &["Decline them", "Convert them to drafts", "Leave them open"].map(String::from),
+                    0,
+                )
+                .unwrap_or(2);
+            match choice {
+                0 => RemovedPrAction::Decline,
+                1 => RemovedPrAction::Draft,
+                _ => RemovedPrAction::Keep,
+            }
+        } else {
+            RemovedPrAction::Keep
+        };
+    }
+
+    if action == RemovedPrAction::Keep {
+        for pr_id in &pr_ids {
+            Output::sub_item(format!(
+                "PR #{pr_id} is still open and no longer part of the stack"
+            ));
+        }
+        return closed;
+    }
+    closed.action = action;
+
+    let pr_manager = match crate::bitbucket::PullRequestManager::from_settings(&settings, repo_root)
+    {
+        Ok(pr_manager) => pr_manager,
+        Err(e) => {
+            Output::warning(format!("Could not connect to update the PRs: {e}"));
+            return closed;
+        }
+    };
+
+    for pr_id in pr_ids {
+        let pr = match pr_manager.get_pull_request(pr_id).await {
+            Ok(pr) if pr.is_open() => pr,
+            Ok(_) => continue,
+            Err(e) => {
+                Output::warning(format!("Could not load PR #{pr_id}: {e}"));
+                continue;
+            }
+        };
+
+        let result = if action == RemovedPrAction::Decline {
+            let comment = format!(
+                "Declined: this change was removed from stack '{stack_name}' with `{command}`."
+            );
+            if let Err(e) = pr_manager.add_comment(pr_id, &comment).await {
+                debug!("Could not comment on PR #{}: {}", pr_id, e);
+            }
+            pr_manager
+                .decline_pull_request(pr_id, &comment)
+                .await
+                .map(|_| format!("Declined PR #{pr_id}"))
+        } else {
+            let comment = format!(
+                "Converted to a draft: this change was removed from stack '{stack_name}' with `{command}`."
+            );
+            if let Err(e) = pr_manager.add_comment(pr_id, &comment).await {
+                debug!("Could not comment on PR #{}: {}", pr_id, e);
+            }
+            if pr.title.starts_with("[DRAFT]") {
+                // Already a draft; nothing to undo on restore either
+                continue;
+            }
+            pr_manager
+                .update_pull_request(pr_id, Some(format!("[DRAFT] {}", pr.title)), None)
+                .await
+                .map(|_| format!("Converted PR #{pr_id} to a draft"))
+        };
+        match result {
+            Ok(done) => {
+                Output::sub_item(done);
+                closed.pr_ids.push(pr_id);
+            }
+            Err(e) => Output::warning(format!("Failed to update PR #{pr_id}: {e}")),
+        }
+    }
+
+    closed
+}
+
+/// Undo `handle_removed_prs` for entries put back on the stack: reopen declined PRs and
+/// drop the `[DRAFT]` prefix from drafted ones
+async fn restore_removed_prs(repo_root: &std::path::Path, closed: &ClosedPrs) {
+    if closed.pr_ids.is_empty() {
+        return;
+    }
+    let pr_manager = match crate::config::get_repo_config_dir(repo_root)
+        .and_then(|dir| crate::config::Settings::load_from_file(&dir.join("config.json")))
+        .and_then(|settings| {
+            crate::bitbucket::PullRequestManager::from_settings(&settings, repo_root)
+        }) {
+        Ok(pr_manager) => pr_manager,
+        Err(e) => {
+            Output::warning(format!("Could not connect to restore the PRs: {e}"));
+            return;
+        }
+    };
+
+    for &pr_id in &closed.pr_ids {
+        let result = match closed.action {
+            RemovedPrAction::Decline => pr_manager
+                .reopen_pull_request(pr_id)
+                .await
+                .map(|_| format!("Reopened PR #{pr_id}")),
+            _ => match pr_manager.get_pull_request(pr_id).await {
+                Ok(pr) => match pr.title.strip_prefix("[DRAFT]") {
+                    Some(title) => pr_manager
+                        .update_pull_request(pr_id, Some(title.trim().to_string()), None)
+                        .await
+                        .map(|_| format!("PR #{pr_id} is no longer a draft")),
+                    None => continue,
+                },
+                Err(e) => Err(e),
+            },
+        };
+        match result {
+            Ok(done) => Output::sub_item(done),
+            Err(e) => Output::warning(format!("Could not restore PR #{pr_id}: {e}")),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn submit_entry(
+    entry: Option<usize>,
     title: Option<String>,
     description: Option<String>,
     range: Option<String>,
-    draft: bool,
+    draft: Option<bool>,
     open: bool,
+    update: bool,
+    edit: bool,
 ) -> Result<()> {
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
@@ -1948,6 +3576,8 @@ async fn submit_entry(
     let repo_root = find_repository_root(&current_dir)
         .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
 
+    freshness_preflight(&repo_root, "submitting").await?;
+
     let stack_manager = StackManager::new(&repo_root)?;
 
     // Load configuration first
@@ -1964,11 +3594,20 @@ async fn submit_entry(
     };
 
     // Get the active stack
-    let active_stack = stack_manager.get_active_stack().ok_or_else(|| {
-        CascadeError::config("No active stack. Create a stack first with 'ca stack create'")
-    })?;
+    let active_stack = stack_manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
+    let draft = draft
+        .or(active_stack.overrides.draft)
+        .unwrap_or(settings.cascade.submit_policy.draft_by_default);
     let stack_id = active_stack.id;
 
+    // The integration gets its own StackManager, which sees every change it makes
+    let integration_stack_manager = StackManager::new(&repo_root)?;
+    let mut integration =
+        BitbucketIntegration::new(integration_stack_manager, cascade_config.clone())?;
+    restore_deleted_remote_branches(&mut integration, &stack_id, false).await;
+
     // Determine which entries to submit
     let entries_to_submit = if let Some(range_str) = range {
         // Parse range (e.g., "1-3" or "2,4,6")
@@ -2035,20 +3674,109 @@ async fn submit_entry(
         }
         vec![(entry_num, active_stack.entries[entry_num - 1].clone())]
     } else {
-        // Default: Submit all unsubmitted entries
+        // Default: Submit all unsubmitted entries (and refresh open ones with --update)
         active_stack
             .entries
             .iter()
             .enumerate()
-            .filter(|(_, entry)| !entry.is_submitted)
+            .filter(|(_, entry)| {
+                if update {
+                    !entry.is_merged
+                } else {
+                    !entry.is_submitted
+                }
+            })
             .map(|(i, entry)| (i + 1, entry.clone())) // Convert to 1-based indexing
             .collect::<Vec<(usize, _)>>()
     };
 
+    // Without --update, entries that already have a PR are left alone
+    let (entries_to_submit, already_submitted): (Vec<_>, Vec<_>) = entries_to_submit
+        .into_iter()
+        .partition(|(_, entry)| update || entry.pull_request_id.is_none());
+    for (entry_num, entry) in &already_submitted {
+        Output::info(format!(
+            "Entry {} is already submitted as PR #{}",
+            entry_num,
+            entry.pull_request_id.as_deref().unwrap_or("?")
+        ));
+    }
+    if !already_submitted.is_empty() {
+        Output::tip("Use 'ca submit --update' to push and refresh existing PRs");
+    }
+
     if entries_to_submit.is_empty() {
         Output::info("No entries to submit");
         return Ok(());
     }
+    permission_preflight(&repo_root, &settings, "submitting", &[]).await?;
+
+    // Titles and descriptions written in the editor, by entry number
+    let mut edited_text: std::collections::HashMap<usize, (String, Option<String>)> =
+        std::collections::HashMap::new();
+    let entries_to_submit = if edit {
+        let mut proposals = Vec::new();
+        for (entry_num, entry) in &entries_to_submit {
+            let (proposed_title, proposed_description) =
+                integration.proposed_pr_text(&stack_id, &entry.id).await?;
+            // --title/--description still apply to a single entry
+            let (proposed_title, proposed_description) = if entries_to_submit.len() == 1 {
+                (
+                    title.clone().unwrap_or(proposed_title),
+                    description.clone().or(proposed_description),
+                )
+            } else {
+                (proposed_title, proposed_description)
+            };
+            proposals.push(SubmitDraft {
+                entry_num: *entry_num,
+                label: match &entry.pull_request_id {
+                    Some(pr_id) => format!("{} (PR #{pr_id})", entry.branch),
+                    None => entry.branch.clone(),
+                },
+                title: proposed_title,
+                description: proposed_description,
+            });
+        }
+
+        let document = render_submit_document(&active_stack.name, &proposals);
+        let edited = match dialoguer::Editor::new().extension(".md").edit(&document) {
+            Ok(Some(edited)) => edited,
+            Ok(None) => {
+                Output::info("Submit cancelled.");
+                return Ok(());
+            }
+            Err(e) => return Err(CascadeError::config(format!("Failed to open editor: {e}"))),
+        };
+        let numbers: Vec<usize> = entries_to_submit.iter().map(|(n, _)| *n).collect();
+        for parsed in parse_submit_document(&edited, &numbers)? {
+            edited_text.insert(parsed.entry_num, (parsed.title, parsed.description));
+        }
+        if edited_text.is_empty() {
+            Output::info("Submit cancelled (no entries left).");
+            return Ok(());
+        }
+        // Sections removed in the editor are not submitted
+        entries_to_submit
+            .into_iter()
+            .filter(|(entry_num, _)| edited_text.contains_key(entry_num))
+            .collect()
+    } else {
+        entries_to_submit
+    };
+
+    crate::stack::lifecycle::run_hook(
+        &repo_root,
+        LifecycleHook::PreSubmit,
+        serde_json::json!({
+            "stack": crate::stack::lifecycle::stack_context(active_stack),
+            "entries": entries_to_submit
+                .iter()
+                .map(|(num, entry)| crate::stack::lifecycle::entry_context(*num, entry))
+                .collect::<Vec<_>>(),
+            "draft": draft,
+        }),
+    )?;
 
     // Professional output for submission
     Output::section(format!(
@@ -2060,17 +3788,13 @@ async fn submit_entry(
             "entries"
         }
     ));
-    println!();
-
-    // Create a new StackManager for the integration (since the original was moved)
-    let integration_stack_manager = StackManager::new(&repo_root)?;
-    let mut integration =
-        BitbucketIntegration::new(integration_stack_manager, cascade_config.clone())?;
+    Output::spacing();
 
     // Submit each entry
     let mut submitted_count = 0;
     let mut failed_entries = Vec::new();
     let mut pr_urls = Vec::new(); // Collect URLs to open
+    let mut submitted_prs = Vec::new(); // Reported to the post-submit hook
     let total_entries = entries_to_submit.len();
 
     for (entry_num, entry_to_submit) in &entries_to_submit {
@@ -2082,37 +3806,55 @@ async fn submit_entry(
         } else {
             "├─"
         };
-        print!(
+        Output::inline(format!(
             "   {} Entry {}: {}... ",
             tree_char, entry_num, entry_to_submit.branch
-        );
-        std::io::Write::flush(&mut std::io::stdout()).ok();
+        ));
 
         // Use provided title/description only for first entry or single entry submissions
-        let entry_title = if total_entries == 1 {
-            title.clone()
-        } else {
-            None
+        let (entry_title, entry_description) = match edited_text.remove(entry_num) {
+            Some((title, description)) => (Some(title), description),
+            None if total_entries == 1 => (title.clone(), description.clone()),
+            None => (None, None),
         };
-        let entry_description = if total_entries == 1 {
-            description.clone()
+
+        let is_update = entry_to_submit.pull_request_id.is_some();
+        let result = if is_update {
+            integration
+                .update_submitted_entry(
+                    &stack_id,
+                    &entry_to_submit.id,
+                    entry_title,
+                    entry_description,
+                )
+                .await
         } else {
-            None
+            integration
+                .submit_entry(
+                    &stack_id,
+                    &entry_to_submit.id,
+                    entry_title,
+                    entry_description,
+                    draft,
+                )
+                .await
         };
 
-        match integration
-            .submit_entry(
-                &stack_id,
-                &entry_to_submit.id,
-                entry_title,
-                entry_description,
-                draft,
-            )
-            .await
-        {
+        match result {
             Ok(pr) => {
                 submitted_count += 1;
-                Output::success(format!("PR #{}", pr.id));
+                if is_update {
+                    Output::success(format!("PR #{} updated", pr.id));
+                } else {
+                    Output::success(format!("PR #{}", pr.id));
+                }
+                submitted_prs.push(serde_json::json!({
+                    "position": entry_num,
+                    "entry_id": entry_to_submit.id.to_string(),
+                    "branch": entry_to_submit.branch,
+                    "pull_request_id": pr.id,
+                    "url": pr.web_url(),
+                }));
                 if let Some(url) = pr.web_url() {
                     use console::style;
                     Output::sub_item(format!(
@@ -2122,7 +3864,9 @@ async fn submit_entry(
                         pr.to_ref.display_id
                     ));
                     Output::sub_item(format!("URL: {}", style(url.clone()).cyan().underlined()));
-                    pr_urls.push(url); // Collect for opening later
+                    if !is_update {
+                        pr_urls.push(url); // Collect for opening later
+                    }
                 }
             }
             Err(e) => {
@@ -2149,7 +3893,7 @@ async fn submit_entry(
         }
     }
 
-    println!();
+    Output::spacing();
 
     // Update all PR descriptions in the stack if any PRs were created/exist
     let has_any_prs = active_stack
@@ -2186,6 +3930,10 @@ async fn submit_entry(
         }
     }
 
+    if submitted_count > 0 {
+        push_trailer_notes(&repo_root);
+    }
+
     // Summary
     if failed_entries.is_empty() {
         Output::success(format!(
@@ -2198,13 +3946,13 @@ async fn submit_entry(
             }
         ));
     } else {
-        println!();
+        Output::spacing();
         Output::section("Submission Summary");
         Output::success(format!("Successful: {submitted_count}"));
         Output::error(format!("Failed: {}", failed_entries.len()));
 
         if !failed_entries.is_empty() {
-            println!();
+            Output::spacing();
             Output::tip("Retry failed entries:");
             for (entry_num, _) in &failed_entries {
                 Output::bullet(format!("ca stack submit {entry_num}"));
@@ -2212,9 +3960,23 @@ async fn submit_entry(
         }
     }
 
-    // Open PRs in browser if requested (default: true)
-    if open && !pr_urls.is_empty() {
-        println!();
+    crate::stack::lifecycle::run_hook(
+        &repo_root,
+        LifecycleHook::PostSubmit,
+        serde_json::json!({
+            "stack": crate::stack::lifecycle::stack_context(active_stack),
+            "submitted": submitted_prs,
+            "failed": failed_entries
+                .iter()
+                .map(|(num, error)| serde_json::json!({ "position": num, "error": error }))
+                .collect::<Vec<_>>(),
+        }),
+    )?;
+
+    // Open PRs in browser if requested (default: true); mock PRs have no page to open
+    let has_web_pages = settings.bitbucket.provider != crate::config::ProviderKind::Mock;
+    if open && has_web_pages && !pr_urls.is_empty() {
+        Output::spacing();
         for url in &pr_urls {
             if let Err(e) = open::that(url) {
                 Output::warning(format!("Could not open browser: {}", e));
@@ -2226,6 +3988,108 @@ async fn submit_entry(
     Ok(())
 }
 
+/// One entry's section in the `ca submit --edit` document
+#[derive(Debug, Clone, PartialEq)]
+struct SubmitDraft {
+    entry_num: usize,
+    /// Branch (and PR) shown in the section heading; ignored when parsing
+    label: String,
+    title: String,
+    description: Option<String>,
+}
+
+/// Marks the start of an entry's section in the `ca submit --edit` document
+const SUBMIT_SECTION_MARKER: &str = "=== Entry ";
+
+/// Render the `ca submit --edit` document: one section per entry whose first line is
+/// the PR title and the rest its description
+fn render_submit_document(stack_name: &str, drafts: &[SubmitDraft]) -> String {
+    let mut document = format!(
+        "<!--\n\
+         Pull requests for stack '{stack_name}'.\n\
+         \n\
+         Each section starts with an '{SUBMIT_SECTION_MARKER}N' line. The first line below it is\n\
+         the PR title; everything after that, up to the next section, is the description.\n\
+         Delete a section to leave that entry out. Delete everything to cancel.\n\
+         The stack hierarchy footer is added to every description automatically.\n\
+         -->\n"
+    );
+    for draft in drafts {
+        document.push_str(&format!(
+            "\n{SUBMIT_SECTION_MARKER}{}: {} ===\n{}\n",
+            draft.entry_num, draft.label, draft.title
+        ));
+        if let Some(description) = &draft.description {
+            document.push_str(&format!("\n{}\n", description.trim_end()));
+        }
+    }
+    document
+}
+
+/// Parse an edited `ca submit --edit` document; `entry_nums` are the entries offered
+fn parse_submit_document(document: &str, entry_nums: &[usize]) -> Result<Vec<SubmitDraft>> {
+    // Drop the instructions comment at the top
+    let body = match document.trim_start().strip_prefix("<!--") {
+        Some(rest) => rest.split_once("-->").map(|(_, body)| body).unwrap_or(""),
+        None => document,
+    };
+
+    let mut drafts: Vec<SubmitDraft> = Vec::new();
+    let mut current: Option<(usize, String, Vec<&str>)> = None;
+    let finish = |(entry_num, label, lines): (usize, String, Vec<&str>)| -> Result<SubmitDraft> {
+        let mut lines = lines.into_iter().skip_while(|line| line.trim().is_empty());
+        let title = lines.next().unwrap_or("").trim().to_string();
+        if title.is_empty() {
+            return Err(CascadeError::validation(format!(
+                "Entry {entry_num} has an empty title"
+            )));
+        }
+        let description = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+        Ok(SubmitDraft {
+            entry_num,
+            label,
+            title,
+            description: (!description.is_empty()).then_some(description),
+        })
+    };
+
+    for line in body.lines() {
+        if let Some(heading) = line.strip_prefix(SUBMIT_SECTION_MARKER) {
+            if let Some(section) = current.take() {
+                drafts.push(finish(section)?);
+            }
+            let heading = heading.trim_end().trim_end_matches('=').trim();
+            let (number, label) = heading.split_once(':').unwrap_or((heading, ""));
+            let entry_num: usize = number.trim().parse().map_err(|_| {
+                CascadeError::validation(format!("Invalid section heading: '{}'", line.trim()))
+            })?;
+            if !entry_nums.contains(&entry_num) {
+                return Err(CascadeError::validation(format!(
+                    "Entry {entry_num} is not being submitted"
+                )));
+            }
+            if drafts.iter().any(|d| d.entry_num == entry_num) {
+                return Err(CascadeError::validation(format!(
+                    "Entry {entry_num} appears more than once"
+                )));
+            }
+            current = Some((entry_num, label.trim().to_string(), Vec::new()));
+        } else if let Some((_, _, lines)) = current.as_mut() {
+            lines.push(line);
+        } else if !line.trim().is_empty() {
+            return Err(CascadeError::validation(format!(
+                "Text outside an entry section: '{}'",
+                line.trim()
+            )));
+        }
+    }
+    if let Some(section) = current.take() {
+        drafts.push(finish(section)?);
+    }
+
+    Ok(drafts)
+}
+
 async fn check_stack_status(name: Option<String>) -> Result<()> {
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
@@ -2254,9 +4118,9 @@ async fn check_stack_status(name: Option<String>) -> Result<()> {
             .get_stack_by_name(&name)
             .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?
     } else {
-        stack_manager.get_active_stack().ok_or_else(|| {
-            CascadeError::config("No active stack. Use 'ca stack list' to see available stacks")
-        })?
+        stack_manager
+            .get_active_stack()
+            .ok_or_else(CascadeError::no_active_stack)?
     };
     let stack_id = stack.id;
 
@@ -2269,11 +4133,17 @@ async fn check_stack_status(name: Option<String>) -> Result<()> {
     }
 
     // Create Bitbucket integration (this takes ownership of stack_manager)
-    let integration = crate::bitbucket::BitbucketIntegration::new(stack_manager, cascade_config)?;
-
-    // Check stack status
-    match integration.check_stack_status(&stack_id).await {
+    let mut integration =
+        crate::bitbucket::BitbucketIntegration::new(stack_manager, cascade_config)?;
+
+    // Check stack status, recording PRs merged or declined outside of Cascade
+    match integration.check_enhanced_stack_status(&stack_id).await {
         Ok(status) => {
+            if let Some(stack) = StackManager::new(&repo_root)?.get_stack(&stack_id) {
+                if stack.status == crate::stack::StackStatus::NeedsSync {
+                    Output::warning("Entries below others were merged; run 'ca sync' to restack");
+                }
+            }
             Output::section("Pull Request Status");
             Output::sub_item(format!("Total entries: {}", status.total_entries));
             Output::sub_item(format!("Submitted: {}", status.submitted_entries));
@@ -2315,7 +4185,7 @@ async fn check_stack_status(name: Option<String>) -> Result<()> {
 
                     // Make URL stand out with cyan/blue hyperlink color
                     if let Some(url) = pr.web_url() {
-                        println!("      URL: {}", style(url).cyan().underlined());
+                        Output::line(format!("      URL: {}", style(url).cyan().underlined()));
                     }
                 }
             }
@@ -2329,119 +4199,105 @@ async fn check_stack_status(name: Option<String>) -> Result<()> {
     Ok(())
 }
 
-async fn list_pull_requests(state: Option<String>, verbose: bool) -> Result<()> {
+async fn check_stack(_force: bool, conflicts: bool) -> Result<()> {
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
 
     let repo_root = find_repository_root(&current_dir)
         .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
 
-    let stack_manager = StackManager::new(&repo_root)?;
-
-    // Load configuration
-    let config_dir = crate::config::get_repo_config_dir(&repo_root)?;
-    let config_path = config_dir.join("config.json");
-    let settings = crate::config::Settings::load_from_file(&config_path)?;
-
-    // Create the main config structure
-    let cascade_config = crate::config::CascadeConfig {
-        bitbucket: Some(settings.bitbucket.clone()),
-        git: settings.git.clone(),
-        auth: crate::config::AuthConfig::default(),
-        cascade: settings.cascade.clone(),
-    };
-
-    // Create Bitbucket integration
-    let integration = crate::bitbucket::BitbucketIntegration::new(stack_manager, cascade_config)?;
+    if conflicts {
+        return predict_conflicts(&repo_root);
+    }
 
-    // Parse state filter
-    let pr_state = if let Some(state_str) = state {
-        match state_str.to_lowercase().as_str() {
-            "open" => Some(crate::bitbucket::PullRequestState::Open),
-            "merged" => Some(crate::bitbucket::PullRequestState::Merged),
-            "declined" => Some(crate::bitbucket::PullRequestState::Declined),
-            _ => {
-                return Err(CascadeError::config(format!(
-                    "Invalid state '{state_str}'. Use: open, merged, declined"
-                )))
-            }
-        }
-    } else {
-        None
-    };
+    let mut manager = StackManager::new(&repo_root)?;
 
-    // Get pull requests
-    match integration.list_pull_requests(pr_state).await {
-        Ok(pr_page) => {
-            if pr_page.values.is_empty() {
-                Output::info("No pull requests found.");
-                return Ok(());
-            }
+    let active_stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
+    let stack_id = active_stack.id;
 
-            println!("Pull Requests ({} total):", pr_page.values.len());
-            for pr in &pr_page.values {
-                let state_icon = match pr.state {
-                    crate::bitbucket::PullRequestState::Open => "○",
-                    crate::bitbucket::PullRequestState::Merged => "✓",
-                    crate::bitbucket::PullRequestState::Declined => "✗",
-                };
-                println!("   {} PR #{}: {}", state_icon, pr.id, pr.title);
-                if verbose {
-                    println!(
-                        "      From: {} -> {}",
-                        pr.from_ref.display_id, pr.to_ref.display_id
-                    );
-                    println!(
-                        "      Author: {}",
-                        pr.author
-                            .user
-                            .display_name
-                            .as_deref()
-                            .unwrap_or(&pr.author.user.name)
-                    );
-                    if let Some(url) = pr.web_url() {
-                        println!("      URL: {url}");
-                    }
-                    if let Some(desc) = &pr.description {
-                        if !desc.is_empty() {
-                            println!("      Description: {desc}");
-                        }
-                    }
-                    println!();
-                }
-            }
+    manager.sync_stack(&stack_id)?;
 
-            if !verbose {
-                println!("\nUse --verbose for more details");
-            }
-        }
-        Err(e) => {
-            warn!("Failed to list pull requests: {}", e);
-            return Err(e);
-        }
-    }
+    Output::success("Stack check completed successfully");
 
     Ok(())
 }
 
-async fn check_stack(_force: bool) -> Result<()> {
-    let current_dir = env::current_dir()
-        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
-
-    let repo_root = find_repository_root(&current_dir)
-        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+/// Replay the active stack onto its updated base in memory and list the files each
+/// entry would conflict on, so resolution can be planned before a sync
+fn predict_conflicts(repo_root: &std::path::Path) -> Result<()> {
+    let manager = StackManager::new(repo_root)?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
+    let git_repo = GitRepository::open(repo_root)?;
 
-    let mut manager = StackManager::new(&repo_root)?;
+    // A parent stack's branch is only local; a landed parent's trunk comes from origin
+    let onto = if manager.base_is_parent_branch(&stack.id) {
+        stack.base_branch.clone()
+    } else {
+        if let Err(e) = git_repo.fetch() {
+            Output::warning(format!("Could not fetch, using local refs: {e}"));
+        }
+        let remote_base = format!("origin/{}", stack.base_branch);
+        if git_repo.resolve_reference(&remote_base).is_ok() {
+            remote_base
+        } else {
+            stack.base_branch.clone()
+        }
+    };
 
-    let active_stack = manager
-        .get_active_stack()
-        .ok_or_else(|| CascadeError::config("No active stack"))?;
-    let stack_id = active_stack.id;
+    let entries: Vec<_> = stack.entries.iter().filter(|e| !e.is_merged).collect();
+    if entries.is_empty() {
+        Output::info("No unmerged entries to check");
+        return Ok(());
+    }
 
-    manager.sync_stack(&stack_id)?;
+    let commits: Vec<String> = entries.iter().map(|e| e.commit_hash.clone()).collect();
+    let predictions = git_repo.predict_rebase_conflicts(&commits, &onto)?;
 
-    Output::success("Stack check completed successfully");
+    Output::section(format!(
+        "Conflict prediction for '{}' onto {}",
+        stack.name, onto
+    ));
+    let mut conflicting = 0;
+    for (entry, files) in entries.iter().zip(&predictions) {
+        let position = stack
+            .entries
+            .iter()
+            .position(|e| e.id == entry.id)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        if files.is_empty() {
+            Output::success(format!(
+                "{position}. {} applies cleanly",
+                entry.short_message(50)
+            ));
+        } else {
+            conflicting += 1;
+            Output::warning(format!(
+                "{position}. {} would conflict in {} file{}",
+                entry.short_message(50),
+                files.len(),
+                if files.len() == 1 { "" } else { "s" }
+            ));
+            for file in files {
+                Output::sub_item(file);
+            }
+        }
+    }
 
+    Output::spacing();
+    if conflicting == 0 {
+        Output::success("No conflicts expected; 'ca sync' should apply cleanly");
+    } else {
+        Output::info(format!(
+            "{conflicting} of {} entries are expected to conflict",
+            entries.len()
+        ));
+        Output::tip("Run 'ca sync' when ready, then 'ca sync continue' after resolving each");
+    }
     Ok(())
 }
 
@@ -2452,16 +4308,15 @@ pub async fn continue_sync() -> Result<()> {
     let repo_root = find_repository_root(&current_dir)?;
 
     Output::section("Continuing sync from where it left off");
-    println!();
+    Output::spacing();
 
     // Check if there's an in-progress cherry-pick
     let cherry_pick_head = crate::git::resolve_git_dir(&repo_root)?.join("CHERRY_PICK_HEAD");
     if !cherry_pick_head.exists() {
-        return Err(CascadeError::config(
-            "No in-progress cherry-pick found. Nothing to continue.\n\n\
-             Use 'ca sync' to start a new sync."
-                .to_string(),
-        ));
+        return Err(
+            CascadeError::config("No in-progress cherry-pick found. Nothing to continue.")
+                .with_hint("Run 'ca sync' to start a new sync"),
+        );
     }
 
     Output::info("Staging all resolved files");
@@ -2473,6 +4328,9 @@ pub async fn continue_sync() -> Result<()> {
         .output()
         .map_err(CascadeError::Io)?;
 
+    // Remember this resolution so the same conflict resolves itself on later syncs
+    crate::git::GitRepository::open(&repo_root)?.record_conflict_resolutions()?;
+
     let sync_state = crate::stack::SyncState::load(&repo_root).ok();
 
     Output::info("Continuing cherry-pick");
@@ -2494,7 +4352,33 @@ pub async fn continue_sync() -> Result<()> {
     }
 
     Output::success("Cherry-pick continued successfully");
-    println!();
+    Output::spacing();
+
+    // A grouped entry stopped before its last commit: pick the rest of it
+    if let Some(mut state) = sync_state
+        .clone()
+        .filter(|state| !state.pending_commits.is_empty())
+    {
+        let git_repo = crate::git::GitRepository::open(&repo_root)?;
+        while !state.pending_commits.is_empty() {
+            let commit = state.pending_commits.remove(0);
+            if let Err(e) = git_repo.cherry_pick(&commit) {
+                state.save(&repo_root)?;
+                Output::error(e.to_string());
+                return Err(CascadeError::branch(format!(
+                    "Conflicts in commit {} of entry '{}'\n\n\
+                     To resolve:\n\
+                     1. Fix conflicts in your editor\n\
+                     2. Continue: ca sync continue\n\n\
+                     Or abort:\n\
+                     → Run: ca sync abort",
+                    &commit[..8],
+                    state.current_entry_branch
+                )));
+            }
+        }
+        state.save(&repo_root)?;
+    }
 
     // Now we need to:
     // 1. Figure out which stack branch this temp branch belongs to
@@ -2597,7 +4481,7 @@ pub async fn continue_sync() -> Result<()> {
     } else {
         let active_stack = manager
             .get_active_stack()
-            .ok_or_else(|| CascadeError::config("No active stack found"))?;
+            .ok_or_else(CascadeError::no_active_stack)?;
 
         let entry_id = active_stack
             .entries
@@ -2676,31 +4560,43 @@ pub async fn continue_sync() -> Result<()> {
         crate::stack::SyncState::delete(&repo_root)?;
     }
 
-    println!();
+    Output::spacing();
     Output::info("Resuming sync to complete the rebase...");
-    println!();
+    Output::spacing();
 
     // Continue with the full sync to process remaining entries
-    sync_stack(false, false, false).await
+    let result = sync_stack(false, false, false).await;
+    // A retarget paused on another conflict still has to be abortable
+    if let (Err(_), Some(previous_base)) =
+        (&result, sync_state.and_then(|state| state.previous_base))
+    {
+        crate::stack::SyncState::record_previous_base(&repo_root, &previous_base)?;
+    }
+    result
 }
 
 pub async fn abort_sync() -> Result<()> {
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
 
-    let repo_root = find_repository_root(&current_dir)?;
+    abort_sync_at(&find_repository_root(&current_dir)?).await
+}
+
+/// [`abort_sync`] for the repository at `repo_root`
+async fn abort_sync_at(repo_root: &std::path::Path) -> Result<()> {
+    let repo_root = repo_root.to_path_buf();
 
     Output::section("Aborting sync");
-    println!();
+    Output::spacing();
 
     // Check if there's an in-progress cherry-pick
     let cherry_pick_head = crate::git::resolve_git_dir(&repo_root)?.join("CHERRY_PICK_HEAD");
     if !cherry_pick_head.exists() {
         return Err(CascadeError::config(
             "No in-progress cherry-pick found. Nothing to abort.\n\n\
-             The sync may have already completed or been aborted."
-                .to_string(),
-        ));
+             The sync may have already completed or been aborted.",
+        )
+        .with_hint("Run 'ca sync' to start a new sync"));
     }
 
     Output::info("Aborting cherry-pick");
@@ -2727,7 +4623,7 @@ pub async fn abort_sync() -> Result<()> {
     let git_repo = crate::git::GitRepository::open(&repo_root)?;
 
     if let Ok(state) = crate::stack::SyncState::load(&repo_root) {
-        println!();
+        Output::spacing();
         Output::info("Cleaning up temporary branches");
 
         // Clean up all temp branches
@@ -2752,6 +4648,19 @@ pub async fn abort_sync() -> Result<()> {
 
         // Delete sync state file
         crate::stack::SyncState::delete(&repo_root)?;
+
+        // An aborted retarget leaves the entries on the old base
+        if let Some(previous_base) = &state.previous_base {
+            let stack_id = Uuid::parse_str(&state.stack_id).map_err(|e| {
+                CascadeError::config(format!("Invalid stack ID in sync state: {e}"))
+            })?;
+            StackManager::new(&repo_root)?.set_stack_config(
+                &stack_id,
+                "base",
+                Some(previous_base),
+            )?;
+            Output::info(format!("Restored base branch: {previous_base}"));
+        }
     } else {
         // No state file - try to figure out where to go
         let current_branch = git_repo.get_current_branch()?;
@@ -2772,23 +4681,331 @@ pub async fn abort_sync() -> Result<()> {
         }
     }
 
-    println!();
+    Output::spacing();
     Output::success("Sync aborted");
-    println!();
+    Output::spacing();
     Output::tip("You can start a fresh sync with: ca sync");
 
+    AutoStash::finish(&repo_root, Ok(()))
+}
+
+/// Before `operation` (submit or land), fetch and compare the active stack's base with
+/// origin. When the base moved more than `cascade.auto_sync_threshold` commits past the
+/// bottom entry, sync first, offer to, or warn, per `cascade.auto_sync`.
+async fn freshness_preflight(repo_root: &std::path::Path, operation: &str) -> Result<()> {
+    use crate::config::AutoSync;
+
+    let config_path = crate::config::get_repo_config_dir(repo_root)?.join("config.json");
+    let settings = crate::config::Settings::load_from_file(&config_path)?;
+
+    let stack_manager = StackManager::new(repo_root)?;
+    let Some(stack) = stack_manager.get_active_stack() else {
+        return Ok(());
+    };
+    // While the base is one of the parent stack's local branches there's nothing to fetch
+    if stack_manager.base_is_parent_branch(&stack.id) {
+        return Ok(());
+    }
+    let Some(bottom) = stack.entries.iter().find(|e| !e.is_merged) else {
+        return Ok(());
+    };
+
+    let git_repo = GitRepository::open(repo_root)?;
+    if let Err(e) = git_repo.fetch_async().await {
+        debug!("Skipping freshness check, fetch failed: {}", e);
+        return Ok(());
+    }
+    let remote_base = format!("origin/{}", stack.base_branch);
+    let behind = match git_repo.commits_behind(&bottom.commit_hash, &remote_base) {
+        Ok(behind) => behind,
+        Err(e) => {
+            debug!("Skipping freshness check for {}: {}", remote_base, e);
+            return Ok(());
+        }
+    };
+    if behind <= settings.cascade.auto_sync_threshold {
+        return Ok(());
+    }
+
+    let summary = format!(
+        "'{}' has {behind} new commit{} on the remote since the stack was last synced",
+        stack.base_branch,
+        if behind == 1 { "" } else { "s" }
+    );
+    // Piped and scheduled runs can't answer a prompt, unless --yes answers for them
+    let interactive = std::io::stdin().is_terminal() || crate::cli::prompt::assume_yes();
+    let sync_now = match settings.cascade.auto_sync {
+        AutoSync::Always => true,
+        AutoSync::Never => false,
+        AutoSync::Prompt if !interactive => false,
+        AutoSync::Prompt => {
+            Output::warning(&summary);
+            prompter().confirm(&format!("Sync the stack before {operation}?"), true)?
+        }
+    };
+
+    if sync_now {
+        Output::info(format!("{summary}; syncing before {operation}"));
+        sync_stack(false, false, false).await?;
+        Output::spacing();
+    } else {
+        if settings.cascade.auto_sync != AutoSync::Prompt || !interactive {
+            Output::warning(&summary);
+        }
+        Output::tip("Run 'ca sync' so the pull requests aren't stale on arrival");
+    }
     Ok(())
 }
 
+/// Before `operation` (submit or land), ask the provider whether the credentials can
+/// push and merge into each of `merge_into`, so a missing permission stops the command
+/// before its first change rather than as a 403 partway through. Anything the provider
+/// can't answer is left for the requests themselves to report.
+async fn permission_preflight(
+    repo_root: &std::path::Path,
+    settings: &crate::config::Settings,
+    operation: &str,
+    merge_into: &[String],
+) -> Result<()> {
+    let pr_manager =
+        crate::bitbucket::pull_request::PullRequestManager::from_settings(settings, repo_root)?;
+    let identity = match pr_manager.identity().await {
+        Ok(identity) => identity,
+        Err(e) => {
+            debug!("Skipping permission check: {}", e);
+            return Ok(());
+        }
+    };
+    let mut restrictions = Vec::new();
+    for branch in merge_into {
+        match pr_manager.merge_restrictions(branch).await {
+            Ok(Some(found)) => restrictions.push((branch.clone(), found)),
+            Ok(None) => debug!("Branch restrictions on {} are not visible", branch),
+            Err(e) => debug!("Skipping branch permission check for {}: {}", branch, e),
+        }
+    }
+    match permission_problem(&identity, &settings.bitbucket, operation, &restrictions) {
+        Some(problem) => Err(problem),
+        None => Ok(()),
+    }
+}
+
+/// Why `identity` can't go ahead with `operation`, naming who to ask for access
+fn permission_problem(
+    identity: &crate::bitbucket::provider::ProviderIdentity,
+    config: &crate::config::BitbucketConfig,
+    operation: &str,
+    restrictions: &[(String, Vec<crate::bitbucket::provider::BranchRestriction>)],
+) -> Option<CascadeError> {
+    let user = &identity.user.name;
+    let contact = config
+        .admin_contact
+        .clone()
+        .unwrap_or_else(|| "a repository admin".to_string());
+
+    if identity.permission < Some(crate::bitbucket::provider::RepositoryPermission::Write) {
+        return Some(
+            CascadeError::auth(format!(
+                "You ({user}) lack write permission on {}/{}, so {operation} would fail; \
+                 ask {contact}",
+                config.project, config.repo
+            ))
+            .with_hint("Run 'ca whoami' to see your permissions"),
+        );
+    }
+    if !identity.token_can_write() {
+        return Some(
+            CascadeError::auth(format!(
+                "Your token can't write to repositories, so {operation} would fail"
+            ))
+            .with_hint(
+                "Create a personal access token with repository write permission and run \
+                 'ca config set bitbucket.token <token>'",
+            ),
+        );
+    }
+    for (branch, branch_restrictions) in restrictions {
+        let Some(restriction) = branch_restrictions.iter().find(|r| r.blocks(user)) else {
+            continue;
+        };
+        let ask = if restriction.exempt_users.is_empty() {
+            contact
+        } else {
+            restriction.exempt_users.join(" or ")
+        };
+        return Some(
+            CascadeError::auth(format!(
+                "You ({user}) lack merge permission on {branch}; ask {ask}"
+            ))
+            .with_hint(format!("{branch} is read-only for everyone not exempted")),
+        );
+    }
+    None
+}
+
 async fn sync_stack(force: bool, cleanup: bool, interactive: bool) -> Result<()> {
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
-
     let repo_root = find_repository_root(&current_dir)
         .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
 
+    sync_stack_at(&repo_root, force, cleanup, interactive).await
+}
+
+/// [`sync_stack`] for the repository at `repo_root`
+async fn sync_stack_at(
+    repo_root: &std::path::Path,
+    force: bool,
+    cleanup: bool,
+    interactive: bool,
+) -> Result<()> {
+    AutoStash::stash_if_enabled(repo_root, &GitRepository::open(repo_root)?, "sync")?;
+    let result = sync_stack_inner(repo_root, force, cleanup, interactive).await;
+    AutoStash::finish(repo_root, result)
+}
+
+/// The commit an entry's first commit sits on
+fn entry_base(git_repo: &GitRepository, entry: &crate::stack::StackEntry) -> Option<String> {
+    let chain = git_repo
+        .get_first_parent_chain(&entry.commit_hash, entry.commit_count)
+        .ok()?;
+    let first = git_repo.get_commit(chain.first()?).ok()?;
+    first.parent_id(0).ok().map(|id| id.to_string())
+}
+
+/// Unmerged entries whose change is already on `upstream` under other commits: picked over
+/// commit by commit (same patch-id), or squash-merged on their own or together with the
+/// unmerged entries below them (same patch-id as their combined diff)
+fn upstream_equivalents(
+    git_repo: &GitRepository,
+    stack: &crate::stack::Stack,
+    upstream: &str,
+) -> Vec<Uuid> {
+    let pending: Vec<&crate::stack::StackEntry> =
+        stack.entries.iter().filter(|e| !e.is_merged).collect();
+    let Some(top) = pending.last() else {
+        return Vec::new();
+    };
+
+    let mut landed: Vec<Uuid> = match git_repo.commits_already_upstream(upstream, &top.commit_hash)
+    {
+        // Every commit of a grouped entry has to be there, not just its tip
+        Ok(commits) => pending
+            .iter()
+            .filter(|e| {
+                git_repo
+                    .get_first_parent_chain(&e.commit_hash, e.commit_count)
+                    .is_ok_and(|chain| chain.iter().all(|commit| commits.contains(commit)))
+            })
+            .map(|e| e.id)
+            .collect(),
+        Err(e) => {
+            debug!("Skipping upstream patch detection: {}", e);
+            Vec::new()
+        }
+    };
+
+    // Each entry on its own, and everything from the lowest unmerged entry up to it,
+    // as a collapsed land (`cascade.land_mode`) squashes it
+    let Some(bottom_base) = entry_base(git_repo, pending[0]) else {
+        return landed;
+    };
+    let mut ranges = Vec::new();
+    let mut covered = Vec::new();
+    for (position, entry) in pending.iter().enumerate() {
+        if position > 0 {
+            if let Some(base) = entry_base(git_repo, entry) {
+                ranges.push((base, entry.commit_hash.clone()));
+                covered.push(position..=position);
+            }
+        }
+        ranges.push((bottom_base.clone(), entry.commit_hash.clone()));
+        covered.push(0..=position);
+    }
+    match git_repo.ranges_landed_upstream(upstream, &ranges) {
+        Ok(matched) => {
+            for range in matched {
+                for entry in &pending[covered[range].clone()] {
+                    if !landed.contains(&entry.id) {
+                        landed.push(entry.id);
+                    }
+                }
+            }
+        }
+        Err(e) => debug!("Skipping squash-merge detection: {}", e),
+    }
+    landed
+}
+
+/// Offer to mark entries as landed when an equivalent patch (same patch-id) is already on
+/// `upstream`, so the rebase leaves them out and cleanup can delete their branches
+fn mark_upstream_equivalents(
+    git_repo: &GitRepository,
+    manager: &mut StackManager,
+    stack_id: &uuid::Uuid,
+    upstream: &str,
+) -> Result<()> {
+    let Some(stack) = manager.get_stack(stack_id) else {
+        return Ok(());
+    };
+    let landed = upstream_equivalents(git_repo, stack, upstream);
+    let equivalents: Vec<(uuid::Uuid, String)> = stack
+        .entries
+        .iter()
+        .filter(|e| landed.contains(&e.id))
+        .map(|e| (e.id, e.short_message(50)))
+        .collect();
+    if equivalents.is_empty() {
+        return Ok(());
+    }
+
+    Output::info(format!(
+        "{} entr{} already landed on {upstream} as an equivalent commit:",
+        equivalents.len(),
+        if equivalents.len() == 1 {
+            "y has"
+        } else {
+            "ies have"
+        }
+    ));
+    for (_, message) in &equivalents {
+        Output::sub_item(message);
+    }
+
+    let mark = std::env::var("CI").is_ok()
+        || prompter().confirm(
+            "Mark them as landed and leave them out of the rebase?",
+            true,
+        )?;
+    if !mark {
+        Output::tip("Use 'ca rebase --skip-entry <n>' to drop them later");
+        return Ok(());
+    }
+
+    for (entry_id, _) in &equivalents {
+        manager.set_entry_merged(stack_id, entry_id, true)?;
+    }
+    manager.save_to_disk()?;
+    Output::success("Marked as landed");
+    Ok(())
+}
+
+async fn sync_stack_inner(
+    repo_root: &std::path::Path,
+    force: bool,
+    cleanup: bool,
+    interactive: bool,
+) -> Result<()> {
+    let repo_root = repo_root.to_path_buf();
     let mut stack_manager = StackManager::new(&repo_root)?;
 
+    // Dependent stacks pick up their parent's current top branch before syncing
+    if let Some(stack_id) = stack_manager.get_active_stack().map(|s| s.id) {
+        if stack_manager.refresh_dependency_base(&stack_id)? {
+            debug!("Refreshed base branch from parent stack");
+        }
+    }
+
     // Exit edit mode if active (sync will invalidate commit SHAs)
     // TODO: Add error recovery to restore edit mode if sync fails
     if stack_manager.is_in_edit_mode() {
@@ -2800,18 +5017,22 @@ async fn sync_stack(force: bool, cleanup: bool, interactive: bool) -> Result<()>
 
     if git_repo.is_dirty()? {
         return Err(CascadeError::branch(
-            "Working tree has uncommitted changes. Commit or stash them before running 'ca sync'."
+            "Working tree has uncommitted changes. Commit or stash them before running 'ca sync' \
+             (or enable 'ca config set cascade.auto_stash true')."
                 .to_string(),
         ));
     }
 
     // Get active stack
-    let active_stack = stack_manager.get_active_stack().ok_or_else(|| {
-        CascadeError::config("No active stack. Create a stack first with 'ca stack create'")
-    })?;
+    let active_stack = stack_manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
 
     let base_branch = active_stack.base_branch.clone();
     let _stack_name = active_stack.name.clone();
+    // While the parent stack has unmerged entries the base is one of its local branches,
+    // which is authoritative; once the parent has landed it's the trunk again
+    let base_is_stack_branch = stack_manager.base_is_parent_branch(&active_stack.id);
 
     // Save the original working branch before any checkouts
     let original_branch = git_repo.get_current_branch().ok();
@@ -2822,7 +5043,16 @@ async fn sync_stack(force: bool, cleanup: bool, interactive: bool) -> Result<()>
     // If the base branch is checked out in another worktree, the local ref won't be
     // moved (to avoid desynchronising that worktree). In that case, use origin/<base>
     // as the rebase target instead.
-    match git_repo.update_local_branch_from_remote(&base_branch) {
+    let base_update = if base_is_stack_branch {
+        debug!(
+            "Base '{}' belongs to a parent stack, skipping remote update",
+            base_branch
+        );
+        Ok(())
+    } else {
+        git_repo.update_local_branch_from_remote(&base_branch)
+    };
+    match base_update {
         Ok(_) => {}
         Err(e) => {
             if force {
@@ -2859,7 +5089,7 @@ async fn sync_stack(force: bool, cleanup: bool, interactive: bool) -> Result<()>
         let remote_ref = format!("origin/{base_branch}");
         let remote_head = git_repo.get_branch_head(&remote_ref).ok();
         match (local_head, remote_head) {
-            (Some(local), Some(remote)) if local != remote => {
+            (Some(local), Some(remote)) if local != remote && !base_is_stack_branch => {
                 debug!(
                     "Local '{}' ({}) behind 'origin/{}' ({}), using remote as rebase target",
                     base_branch,
@@ -2934,13 +5164,22 @@ async fn sync_stack(force: bool, cleanup: bool, interactive: bool) -> Result<()>
         }
     }
 
+    // Step 2c: Entries whose change already landed on the base as a different commit
+    // would only replay as empty commits or conflicts
+    mark_upstream_equivalents(
+        &git_repo,
+        &mut updated_stack_manager,
+        &stack_id,
+        &rebase_target,
+    )?;
+
     match updated_stack_manager.sync_stack(&stack_id) {
         Ok(_) => {
             // Check the updated status
             if let Some(updated_stack) = updated_stack_manager.get_stack(&stack_id) {
                 // Check for empty stack first
                 if updated_stack.entries.is_empty() {
-                    println!(); // Spacing
+                    Output::spacing(); // Spacing
                     Output::info("Stack has no entries yet");
                     Output::tip("Use 'ca push' to add commits to this stack");
                     return Ok(());
@@ -2960,7 +5199,7 @@ async fn sync_stack(force: bool, cleanup: bool, interactive: bool) -> Result<()>
                             cascade: settings.cascade.clone(),
                         };
 
-                        println!(); // Spacing
+                        Output::spacing(); // Spacing
 
                         // Use the existing rebase system with force-push strategy
                         // This preserves PR history by force-pushing to original branches
@@ -3114,25 +5353,428 @@ async fn sync_stack(force: bool, cleanup: bool, interactive: bool) -> Result<()>
 
     Output::success("Sync completed successfully!");
 
-    Ok(())
-}
-
-async fn rebase_stack(
-    interactive: bool,
-    onto: Option<String>,
-    strategy: Option<RebaseStrategyArg>,
-) -> Result<()> {
-    let current_dir = env::current_dir()
-        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    retarget_dependent_stacks(&repo_root, &stack_id);
 
-    let repo_root = find_repository_root(&current_dir)
-        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+    let synced_stack = StackManager::new(&repo_root)?
+        .get_stack(&stack_id)
+        .map(crate::stack::lifecycle::stack_context);
+    crate::stack::lifecycle::run_hook(
+        &repo_root,
+        LifecycleHook::PostSync,
+        serde_json::json!({ "stack": synced_stack }),
+    )?;
 
-    let stack_manager = StackManager::new(&repo_root)?;
-    let git_repo = GitRepository::open(&repo_root)?;
+    Ok(())
+}
 
-    // Load configuration for potential Bitbucket integration
-    let config_dir = crate::config::get_repo_config_dir(&repo_root)?;
+/// The commit `base` would be at once a sync or rebase has brought it up to date with
+/// origin. Nothing is moved: the remote-tracking ref is only fetched and read.
+fn planned_base_commit(git_repo: &GitRepository, base: &str, from_remote: bool) -> Result<String> {
+    let local = git_repo.get_branch_head(base).ok();
+    if !from_remote {
+        return local.ok_or_else(|| CascadeError::branch(format!("Branch '{base}' not found")));
+    }
+    if let Err(e) = git_repo.fetch() {
+        Output::warning(format!(
+            "Could not fetch from origin, planning with the refs you have: {e}"
+        ));
+    }
+    match (local, git_repo.get_remote_branch_head(base).ok()) {
+        (Some(local), Some(remote)) if local != remote => {
+            if git_repo.is_descendant_of(&remote, &local)? {
+                Ok(remote)
+            } else {
+                Output::warning(format!(
+                    "'{base}' has diverged from 'origin/{base}'; planning with the local branch"
+                ));
+                Ok(local)
+            }
+        }
+        (Some(local), _) => Ok(local),
+        (None, Some(remote)) => Ok(remote),
+        (None, None) => Err(CascadeError::branch(format!(
+            "Base branch '{base}' does not exist locally or remotely"
+        ))),
+    }
+}
+
+/// Bitbucket access when the stack has pull requests to look at or update
+fn pr_integration(
+    repo_root: &std::path::Path,
+    stack: &crate::stack::Stack,
+) -> Option<BitbucketIntegration> {
+    if stack.entries.iter().all(|e| e.pull_request_id.is_none()) {
+        return None;
+    }
+    let config_dir = crate::config::get_repo_config_dir(repo_root).ok()?;
+    let settings = crate::config::Settings::load_from_file(&config_dir.join("config.json")).ok()?;
+    let cascade_config = crate::config::CascadeConfig {
+        bitbucket: Some(settings.bitbucket.clone()),
+        git: settings.git.clone(),
+        auth: crate::config::AuthConfig::default(),
+        cascade: settings.cascade.clone(),
+    };
+    match BitbucketIntegration::new(StackManager::new(repo_root).ok()?, cascade_config) {
+        Ok(integration) => Some(integration),
+        Err(e) => {
+            debug!("Skipping pull requests: {}", e);
+            None
+        }
+    }
+}
+
+/// Print what a rebase would do to each entry, bottom of the stack first
+fn show_rebase_plan(plan: &crate::stack::RebasePlan) {
+    use crate::stack::PlannedAction;
+
+    for (index, step) in plan.steps.iter().enumerate() {
+        let pr = step
+            .pull_request_id
+            .as_deref()
+            .map(|id| format!(" (PR #{id})"))
+            .unwrap_or_default();
+        match &step.action {
+            PlannedAction::Skip => {
+                Output::numbered_item(index + 1, format!("{}{pr}: left out", step.branch))
+            }
+            PlannedAction::Keep => {
+                Output::numbered_item(index + 1, format!("{}{pr}: stays", step.branch))
+            }
+            PlannedAction::Move {
+                from,
+                onto,
+                commits,
+            } => {
+                Output::numbered_item(
+                    index + 1,
+                    format!("{}{pr}: moves from {} onto {onto}", step.branch, &from[..8]),
+                );
+                for commit in commits {
+                    Output::sub_item(format!(
+                        "cherry-pick {} {}",
+                        &commit.hash[..8],
+                        commit.summary
+                    ));
+                }
+            }
+        }
+    }
+
+    let pushed: Vec<String> = plan
+        .moves()
+        .filter_map(|step| step.pull_request_id.as_deref())
+        .map(|id| format!("#{id}"))
+        .collect();
+    Output::spacing();
+    match plan.moves().count() {
+        0 => Output::success("Every branch is up to date; nothing would move"),
+        moved => Output::info(format!(
+            "{moved} branch{} would move",
+            if moved == 1 { "" } else { "es" }
+        )),
+    }
+    if !pushed.is_empty() {
+        Output::sub_item(format!("Force-pushed to update PR {}", pushed.join(", ")));
+    }
+}
+
+/// Print the pull requests whose target branch doesn't match the stack's chain, which
+/// the retarget pass after a rebase would fix
+async fn show_planned_retargets(integration: &BitbucketIntegration, stack_id: &Uuid) {
+    let checks = match integration.check_pull_request_chain(stack_id).await {
+        Ok(checks) => checks,
+        Err(e) => {
+            Output::warning(format!("Could not check pull request targets: {e}"));
+            return;
+        }
+    };
+    let drifted: Vec<_> = checks.iter().filter(|c| c.target_drifted()).collect();
+    if drifted.is_empty() {
+        return;
+    }
+    Output::section("Pull requests that would be retargeted");
+    for check in drifted {
+        Output::sub_item(format!(
+            "PR #{}: {} -> {}",
+            check.pr_id, check.target, check.expected_target
+        ));
+    }
+}
+
+/// `ca sync --dry-run`: the branches the sync would move, the commits it would
+/// cherry-pick and the pull requests it would retarget. The working tree, branches and
+/// stack metadata are left alone.
+async fn plan_sync(cleanup: bool) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let manager = StackManager::new(&repo_root)?;
+    let git_repo = GitRepository::open(&repo_root)?;
+    let mut stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?
+        .clone();
+    if stack.entries.is_empty() {
+        Output::info("Stack has no entries yet; a sync would have nothing to do");
+        return Ok(());
+    }
+
+    // A dependent stack first picks up its parent's current top branch
+    if let Some(parent) = stack.depends_on.and_then(|id| manager.get_stack(&id)) {
+        stack.base_branch = parent.dependency_base_branch();
+    }
+    let target_commit =
+        planned_base_commit(&git_repo, &stack.base_branch, stack.depends_on.is_none())?;
+
+    // The sync trusts branch heads over the commits recorded for the entries
+    let heads: Vec<(Uuid, String)> = stack
+        .entries
+        .iter()
+        .filter_map(|entry| {
+            let head = git_repo.get_branch_head(&entry.branch).ok()?;
+            (head != entry.commit_hash).then_some((entry.id, head))
+        })
+        .collect();
+    for (entry_id, head) in heads {
+        stack
+            .update_entry_commit_hash(&entry_id, head)
+            .map_err(CascadeError::config)?;
+    }
+
+    // Entries already on the base, as merged pull requests or equivalent commits, are left out
+    let integration = pr_integration(&repo_root, &stack);
+    let mut landed = match &integration {
+        Some(integration) => integration
+            .merged_entries(&stack.id)
+            .await
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    landed.extend(upstream_equivalents(&git_repo, &stack, &target_commit));
+
+    let target = format!("{} ({})", stack.base_branch, &target_commit[..8]);
+    let plan =
+        crate::stack::RebasePlan::build(&git_repo, &stack, &target, &target_commit, &landed)?;
+
+    Output::section(format!(
+        "Dry run: sync of stack '{}' onto {target}",
+        stack.name
+    ));
+    show_rebase_plan(&plan);
+    if let Some(integration) = &integration {
+        show_planned_retargets(integration, &stack.id).await;
+    }
+    if cleanup {
+        perform_simple_cleanup(&manager, &git_repo, true).await?;
+    }
+    Output::spacing();
+    Output::tip("Run without --dry-run to sync");
+    Ok(())
+}
+
+/// `ca rebase --dry-run`: what the rebase would do, like [`plan_sync`], with the entries
+/// `skip_entry` names left out
+async fn plan_rebase(onto: Option<String>, skip_entry: Option<String>) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let manager = StackManager::new(&repo_root)?;
+    let git_repo = GitRepository::open(&repo_root)?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?
+        .clone();
+    if stack.entries.is_empty() {
+        Output::info("Stack is empty. Nothing to rebase.");
+        return Ok(());
+    }
+
+    let mut skipped = Vec::new();
+    if let Some(spec) = &skip_entry {
+        for position in parse_entry_spec(spec, stack.entries.len())? {
+            let entry = &stack.entries[position - 1];
+            if entry.is_merged {
+                return Err(CascadeError::validation(format!(
+                    "Entry {position} ('{}') is already merged and can't be skipped",
+                    entry.short_message(40)
+                )));
+            }
+            skipped.push(entry);
+        }
+    }
+
+    let base = onto.unwrap_or_else(|| stack.base_branch.clone());
+    let target_commit = planned_base_commit(&git_repo, &base, stack.depends_on.is_none())?;
+    let target = format!("{base} ({})", &target_commit[..8]);
+    let skip: Vec<Uuid> = skipped.iter().map(|e| e.id).collect();
+    let plan = crate::stack::RebasePlan::build(&git_repo, &stack, &target, &target_commit, &skip)?;
+
+    Output::section(format!(
+        "Dry run: rebase of stack '{}' onto {target}",
+        stack.name
+    ));
+    show_rebase_plan(&plan);
+    if !skipped.is_empty() {
+        Output::section("Entries that would be dropped as obsolete");
+        for entry in &skipped {
+            let pr = entry
+                .pull_request_id
+                .as_deref()
+                .map(|id| format!(", closing PR #{id}"))
+                .unwrap_or_default();
+            Output::sub_item(format!("{}: branch deleted{pr}", entry.branch));
+        }
+    }
+    if let Some(integration) = pr_integration(&repo_root, &stack) {
+        show_planned_retargets(&integration, &stack.id).await;
+    }
+    Output::spacing();
+    Output::tip("Run without --dry-run to rebase");
+    Ok(())
+}
+
+async fn rebase_stack(
+    interactive: bool,
+    onto: Option<String>,
+    strategy: Option<RebaseStrategyArg>,
+    skip_entry: Option<String>,
+) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    AutoStash::stash_if_enabled(&repo_root, &GitRepository::open(&repo_root)?, "rebase")?;
+    let result = async {
+        if let Some(spec) = &skip_entry {
+            skip_obsolete_entries(&repo_root, spec).await?;
+        }
+        rebase_stack_inner(interactive, onto, strategy).await
+    }
+    .await;
+    AutoStash::finish(&repo_root, result)
+}
+
+/// Take obsolete entries out of the active stack before a rebase: replay the entries
+/// above them without their commits, record them as superseded, and close their PRs
+async fn skip_obsolete_entries(repo_root: &std::path::Path, spec: &str) -> Result<()> {
+    use crate::stack::{EditAction, EditStep};
+
+    let manager = StackManager::new(repo_root)?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?
+        .clone();
+
+    let positions = parse_entry_spec(spec, stack.entries.len())?;
+    let mut skipped = Vec::new();
+    for &position in &positions {
+        let entry = &stack.entries[position - 1];
+        if entry.is_merged {
+            return Err(CascadeError::validation(format!(
+                "Entry {position} ('{}') is already merged and can't be skipped",
+                entry.short_message(40)
+            )));
+        }
+        skipped.push(entry.clone());
+    }
+
+    let steps: Vec<EditStep> = stack
+        .entries
+        .iter()
+        .filter(|e| !e.is_merged && !skipped.iter().any(|s| s.id == e.id))
+        .map(|e| EditStep {
+            entry_id: e.id,
+            action: EditAction::Pick,
+        })
+        .collect();
+
+    Output::section(format!(
+        "Skipping {} obsolete entr{}",
+        skipped.len(),
+        if skipped.len() == 1 { "y" } else { "ies" }
+    ));
+    for (position, entry) in positions.iter().zip(&skipped) {
+        Output::numbered_item(*position, entry.short_message(50));
+    }
+
+    let git_repo = GitRepository::open(repo_root)?;
+    let mut rebase_manager = crate::stack::RebaseManager::new(
+        manager,
+        git_repo,
+        crate::stack::RebaseOptions {
+            strategy: crate::stack::RebaseStrategy::ForcePush,
+            ..Default::default()
+        },
+    );
+    rebase_manager.apply_edit_plan(&stack.id, &steps)?;
+    let mut manager = rebase_manager.into_stack_manager();
+
+    if let Some(stack_mut) = manager.get_stack_mut(&stack.id) {
+        for entry in &skipped {
+            stack_mut.record_superseded(entry);
+        }
+    }
+    manager.save_to_disk()?;
+
+    let repo = GitRepository::open(repo_root)?;
+    let current_branch = repo.get_current_branch().unwrap_or_default();
+    for entry in &skipped {
+        if entry.branch != current_branch {
+            if let Err(e) = repo.delete_branch_unsafe(&entry.branch) {
+                Output::warning(format!("Could not delete branch {}: {e}", entry.branch));
+            }
+        }
+    }
+
+    let pr_ids: Vec<u64> = skipped
+        .iter()
+        .filter_map(|e| e.pull_request_id.as_deref())
+        .filter_map(|id| id.parse().ok())
+        .collect();
+    if !pr_ids.is_empty() {
+        let config_dir = crate::config::get_repo_config_dir(repo_root)?;
+        let settings = crate::config::Settings::load_from_file(&config_dir.join("config.json"))?;
+        let pr_manager = crate::bitbucket::PullRequestManager::from_settings(&settings, repo_root)?;
+        let comment = format!(
+            "Superseded: this change is obsolete and was skipped while rebasing stack '{}'.",
+            stack.name
+        );
+        for pr_id in pr_ids {
+            if let Err(e) = pr_manager.add_comment(pr_id, &comment).await {
+                debug!("Could not comment on PR #{}: {}", pr_id, e);
+            }
+            match pr_manager.decline_pull_request(pr_id, &comment).await {
+                Ok(_) => Output::sub_item(format!("Closed PR #{pr_id} as superseded")),
+                Err(e) => Output::warning(format!("Failed to close PR #{pr_id}: {e}")),
+            }
+        }
+    }
+
+    Output::spacing();
+    Ok(())
+}
+
+async fn rebase_stack_inner(
+    interactive: bool,
+    onto: Option<String>,
+    strategy: Option<RebaseStrategyArg>,
+) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let stack_manager = StackManager::new(&repo_root)?;
+    let git_repo = GitRepository::open(&repo_root)?;
+
+    // Load configuration for potential Bitbucket integration
+    let config_dir = crate::config::get_repo_config_dir(&repo_root)?;
     let config_path = config_dir.join("config.json");
     let settings = crate::config::Settings::load_from_file(&config_path)?;
 
@@ -3145,9 +5787,9 @@ async fn rebase_stack(
     };
 
     // Get active stack
-    let active_stack = stack_manager.get_active_stack().ok_or_else(|| {
-        CascadeError::config("No active stack. Create a stack first with 'ca stack create'")
-    })?;
+    let active_stack = stack_manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
     let stack_id = active_stack.id;
 
     let active_stack = stack_manager
@@ -3179,7 +5821,7 @@ async fn rebase_stack(
     debug!("   Target base: {:?}", onto);
     debug!("   Entries: {}", active_stack.entries.len());
 
-    println!(); // Spacing
+    Output::spacing(); // Spacing
 
     // Start spinner for rebase
     let rebase_spinner = crate::utils::spinner::Spinner::new_with_output_below(format!(
@@ -3218,7 +5860,7 @@ async fn rebase_stack(
 
     // Stop spinner before showing results
     rebase_spinner.stop();
-    println!(); // Spacing
+    Output::spacing(); // Spacing
 
     match rebase_result {
         Ok(result) => {
@@ -3256,9 +5898,9 @@ async fn rebase_stack(
                     {
                         Ok(updated_prs) => {
                             if !updated_prs.is_empty() {
-                                println!("   🔄 Preserved pull request history:");
+                                Output::info("Preserved pull request history:");
                                 for pr_update in updated_prs {
-                                    println!("      ✅ {pr_update}");
+                                    Output::sub_item(pr_update);
                                 }
                             }
                         }
@@ -3277,7 +5919,7 @@ async fn rebase_stack(
 
             // Show next steps
             if matches!(rebase_strategy, crate::stack::RebaseStrategy::ForcePush) {
-                println!();
+                Output::spacing();
                 Output::section("Next steps");
                 if !result.branch_mapping.is_empty() {
                     Output::numbered_item(1, "Branches have been rebased and force-pushed");
@@ -3288,18 +5930,18 @@ async fn rebase_stack(
                     Output::numbered_item(3, "Review the updated PRs in Bitbucket");
                     Output::numbered_item(4, "Test your changes");
                 } else {
-                    println!("   1. Review the rebased stack");
-                    println!("   2. Test your changes");
-                    println!("   3. Submit new pull requests with 'ca stack submit'");
+                    Output::line("   1. Review the rebased stack");
+                    Output::line("   2. Test your changes");
+                    Output::line("   3. Submit new pull requests with 'ca stack submit'");
                 }
             }
         }
         Err(e) => {
             warn!("❌ Rebase failed: {}", e);
             Output::tip(" Tips for resolving rebase issues:");
-            println!("   - Check for uncommitted changes with 'git status'");
-            println!("   - Ensure base branch is up to date");
-            println!("   - Try interactive mode: 'ca stack rebase --interactive'");
+            Output::line("   - Check for uncommitted changes with 'git status'");
+            Output::line("   - Ensure base branch is up to date");
+            Output::line("   - Try interactive mode: 'ca stack rebase --interactive'");
             return Err(e);
         }
     }
@@ -3324,18 +5966,19 @@ pub async fn continue_rebase() -> Result<()> {
         return Ok(());
     }
 
-    println!(" Continuing rebase...");
+    Output::line(" Continuing rebase...");
     match rebase_manager.continue_rebase() {
         Ok(_) => {
             Output::success(" Rebase continued successfully");
-            println!("   Check 'ca stack rebase-status' for current state");
+            Output::line("   Check 'ca stack rebase-status' for current state");
+            AutoStash::finish(&repo_root, Ok(()))?;
         }
         Err(e) => {
             warn!("❌ Failed to continue rebase: {}", e);
             Output::tip(" You may need to resolve conflicts first:");
-            println!("   1. Edit conflicted files");
-            println!("   2. Stage resolved files with 'git add'");
-            println!("   3. Run 'ca stack continue-rebase' again");
+            Output::line("   1. Edit conflicted files");
+            Output::line("   2. Stage resolved files with 'git add'");
+            Output::line("   3. Run 'ca stack continue-rebase' again");
         }
     }
 
@@ -3363,11 +6006,12 @@ pub async fn abort_rebase() -> Result<()> {
     match rebase_manager.abort_rebase() {
         Ok(_) => {
             Output::success(" Rebase aborted successfully");
-            println!("   Repository restored to pre-rebase state");
+            Output::line("   Repository restored to pre-rebase state");
+            AutoStash::finish(&repo_root, Ok(()))?;
         }
         Err(e) => {
             warn!("❌ Failed to abort rebase: {}", e);
-            println!("⚠️  You may need to manually clean up the repository state");
+            Output::warning("You may need to manually clean up the repository state");
         }
     }
 
@@ -3384,7 +6028,7 @@ async fn rebase_status() -> Result<()> {
     let stack_manager = StackManager::new(&repo_root)?;
     let git_repo = crate::git::GitRepository::open(&repo_root)?;
 
-    println!("Rebase Status");
+    Output::line("Rebase Status");
 
     // Check if rebase is in progress by checking git state directly
     let git_dir = git_repo.git_dir();
@@ -3393,14 +6037,14 @@ async fn rebase_status() -> Result<()> {
         || git_dir.join("rebase-apply").exists();
 
     if rebase_in_progress {
-        println!("   Status: 🔄 Rebase in progress");
-        println!(
+        Output::line("   Status: 🔄 Rebase in progress");
+        Output::line(
             "   
-📝 Actions available:"
+📝 Actions available:",
         );
-        println!("     - 'ca stack continue-rebase' to continue");
-        println!("     - 'ca stack abort-rebase' to abort");
-        println!("     - 'git status' to see conflicted files");
+        Output::line("     - 'ca stack continue-rebase' to continue");
+        Output::line("     - 'ca stack abort-rebase' to abort");
+        Output::line("     - 'git status' to see conflicted files");
 
         // Check for conflicts
         match git_repo.get_status() {
@@ -3415,17 +6059,17 @@ async fn rebase_status() -> Result<()> {
                 }
 
                 if !conflicts.is_empty() {
-                    println!("   ⚠️  Conflicts in {} files:", conflicts.len());
+                    Output::line(format!("   ⚠️  Conflicts in {} files:", conflicts.len()));
                     for conflict in conflicts {
-                        println!("      - {conflict}");
+                        Output::line(format!("      - {conflict}"));
                     }
-                    println!(
+                    Output::line(
                         "   
-💡 To resolve conflicts:"
+💡 To resolve conflicts:",
                     );
-                    println!("     1. Edit the conflicted files");
-                    println!("     2. Stage resolved files: git add <file>");
-                    println!("     3. Continue: ca stack continue-rebase");
+                    Output::line("     1. Edit the conflicted files");
+                    Output::line("     2. Stage resolved files: git add <file>");
+                    Output::line("     3. Continue: ca stack continue-rebase");
                 }
             }
             Err(e) => {
@@ -3433,13 +6077,13 @@ async fn rebase_status() -> Result<()> {
             }
         }
     } else {
-        println!("   Status: ✅ No rebase in progress");
+        Output::line("   Status: ✅ No rebase in progress");
 
         // Show stack status instead
         if let Some(active_stack) = stack_manager.get_active_stack() {
-            println!("   Active stack: {}", active_stack.name);
-            println!("   Entries: {}", active_stack.entries.len());
-            println!("   Base branch: {}", active_stack.base_branch);
+            Output::line(format!("   Active stack: {}", active_stack.name));
+            Output::line(format!("   Entries: {}", active_stack.entries.len()));
+            Output::line(format!("   Base branch: {}", active_stack.base_branch));
         }
     }
 
@@ -3478,11 +6122,7 @@ async fn delete_stack(name: String, force: bool) -> Result<()> {
     Ok(())
 }
 
-async fn validate_stack(
-    name: Option<String>,
-    fix_mode: Option<String>,
-    current_only: bool,
-) -> Result<()> {
+async fn depend_on_stack(parent_name: Option<String>, clear: bool) -> Result<()> {
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
 
@@ -3491,65 +6131,709 @@ async fn validate_stack(
 
     let mut manager = StackManager::new(&repo_root)?;
 
-    // --current: only validate the active stack, exit 0 if none exists.
-    // Used by the pre-push hook so unrelated stacks don't block pushes.
-    if current_only {
-        let active = match manager.get_active_stack() {
-            Some(s) => s.clone(),
-            None => return Ok(()), // no active stack → nothing to validate
-        };
+    let (stack_id, stack_name) = {
+        let active_stack = manager.get_active_stack().ok_or_else(|| {
+            CascadeError::config("No active stack. Switch to the dependent stack first")
+        })?;
+        (active_stack.id, active_stack.name.clone())
+    };
 
-        let stack_id = active.id;
-        match active.validate() {
-            Ok(_) => {}
-            Err(e) => {
-                Output::error(format!("Stack '{}' validation failed: {}", active.name, e));
-                return Err(CascadeError::config(e));
-            }
-        }
-        manager.handle_branch_modifications(&stack_id, fix_mode)?;
+    if clear {
+        let new_base = manager.set_stack_dependency(&stack_id, None)?;
+        Output::success(format!(
+            "Stack '{stack_name}' no longer depends on another stack"
+        ));
+        Output::sub_item(format!("Base branch: {new_base}"));
+        Output::tip("Run 'ca sync' to rebase onto the new base");
         return Ok(());
     }
 
-    if let Some(name) = name {
-        // Validate specific stack
-        let stack = manager
-            .get_stack_by_name(&name)
-            .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?;
+    let parent_name =
+        parent_name.ok_or_else(|| CascadeError::config("Stack name required (or use --clear)"))?;
+    let parent_id = manager
+        .get_stack_by_name(&parent_name)
+        .map(|stack| stack.id)
+        .ok_or_else(|| CascadeError::config(format!("Stack '{parent_name}' not found")))?;
 
-        let stack_id = stack.id;
+    let new_base = manager.set_stack_dependency(&stack_id, Some(parent_id))?;
 
-        // Basic structure validation first
-        match stack.validate() {
-            Ok(_message) => {
-                Output::success(format!("Stack '{}' structure validation passed", name));
-            }
-            Err(e) => {
-                Output::error(format!(
-                    "Stack '{}' structure validation failed: {}",
-                    name, e
-                ));
-                return Err(CascadeError::config(e));
-            }
-        }
+    Output::success(format!(
+        "Stack '{stack_name}' now depends on '{parent_name}'"
+    ));
+    Output::sub_item(format!("Base branch: {new_base}"));
+    Output::tip("Run 'ca sync' to rebase onto the parent stack");
 
-        // Handle branch modifications (includes Git integrity checks)
-        manager.handle_branch_modifications(&stack_id, fix_mode)?;
+    Ok(())
+}
 
-        println!();
-        Output::success(format!("Stack '{name}' validation completed"));
-        Ok(())
-    } else {
-        // Validate all stacks
-        Output::section("Validating all stacks");
-        println!();
+async fn stack_config(name: String, action: Option<StackConfigAction>) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
 
-        // Get all stack IDs through public method
-        let all_stacks = manager.get_all_stacks();
-        let stack_ids: Vec<uuid::Uuid> = all_stacks.iter().map(|s| s.id).collect();
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
 
-        if stack_ids.is_empty() {
-            Output::info("No stacks found");
+    let mut manager = StackManager::new(&repo_root)?;
+    let stack = manager
+        .get_stack_by_name(&name)
+        .cloned()
+        .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?;
+
+    match action.unwrap_or(StackConfigAction::List) {
+        StackConfigAction::List => {
+            let config_path = crate::config::get_repo_config_dir(&repo_root)?.join("config.json");
+            let settings = crate::config::Settings::load_from_file(&config_path)?;
+            let reviewers = settings.bitbucket.default_reviewers.join(",");
+            let defaults = [
+                ("merge_strategy", "squash".to_string()),
+                ("reviewers", reviewers),
+                (
+                    "draft",
+                    settings.cascade.submit_policy.draft_by_default.to_string(),
+                ),
+                ("branch_prefix", String::new()),
+                ("labels", String::new()),
+                (
+                    "pr_description_template",
+                    settings.cascade.pr_description_template.unwrap_or_default(),
+                ),
+            ];
+            // Multi-line templates are shown by their first line
+            let summary = |value: &str| match value.split_once('\n') {
+                Some((first, _)) => format!("{first} ..."),
+                None => value.to_string(),
+            };
+
+            Output::section(format!("Stack '{}' settings", stack.name));
+            Output::sub_item(format!("base = {}", stack.base_branch));
+            for (key, default) in defaults {
+                match stack.overrides.get(key)? {
+                    Some(value) => Output::sub_item(format!("{key} = {}", summary(&value))),
+                    None => Output::sub_item(format!(
+                        "{key} = {} (repository default)",
+                        summary(&default)
+                    )),
+                }
+            }
+        }
+        StackConfigAction::Get { key } => {
+            let value = match key.as_str() {
+                "base" => Some(stack.base_branch.clone()),
+                _ => stack.overrides.get(&key)?,
+            };
+            match value {
+                Some(value) => Output::data(value),
+                None => Output::info(format!(
+                    "{key} is not set for '{}'; the repository default applies",
+                    stack.name
+                )),
+            }
+        }
+        StackConfigAction::Set { key, value } => {
+            manager.set_stack_config(&stack.id, &key, Some(&value))?;
+            Output::success(format!("Set {key} = {value} for stack '{}'", stack.name));
+            if key == "base" {
+                Output::tip("Run 'ca sync' to rebase the stack onto its new base");
+            }
+        }
+        StackConfigAction::Unset { key } => {
+            manager.set_stack_config(&stack.id, &key, None)?;
+            Output::success(format!(
+                "Stack '{}' uses the repository default for {key} again",
+                stack.name
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Add or remove labels of a stack or one of its entries, or show them
+async fn stack_label(action: StackLabelAction) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let mut manager = StackManager::new(&repo_root)?;
+    let (name, labels, entry, adding) = match action {
+        StackLabelAction::List { name } => return show_labels(&manager, name),
+        StackLabelAction::Add {
+            name,
+            labels,
+            entry,
+        } => (name, labels, entry, true),
+        StackLabelAction::Remove {
+            name,
+            labels,
+            entry,
+        } => (name, labels, entry, false),
+    };
+    let labels = labels
+        .iter()
+        .map(|label| crate::stack::validate_label(label))
+        .collect::<Result<Vec<_>>>()?;
+    let stack = manager
+        .get_stack_by_name(&name)
+        .cloned()
+        .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?;
+    let target = match entry {
+        Some(position) if position == 0 || position > stack.entries.len() => {
+            return Err(CascadeError::validation(format!(
+                "Entry {position} doesn't exist: the stack has {} entries",
+                stack.entries.len()
+            )));
+        }
+        Some(position) => Some((position, &stack.entries[position - 1])),
+        None => None,
+    };
+
+    let current = match target {
+        Some((_, entry)) => entry.labels.clone(),
+        None => stack.labels().to_vec(),
+    };
+    let mut updated = current.clone();
+    for label in &labels {
+        if !adding {
+            updated.retain(|l| !l.eq_ignore_ascii_case(label));
+        } else if !updated.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+            updated.push(label.clone());
+        }
+    }
+    let subject = match target {
+        Some((position, entry)) => format!(
+            "entry {position} ({}) of stack '{}'",
+            entry.branch, stack.name
+        ),
+        None => format!("stack '{}'", stack.name),
+    };
+    if updated == current {
+        Output::info(if adding {
+            format!("Nothing to add: {subject} already has these labels")
+        } else {
+            format!("Nothing to remove: {subject} has none of these labels")
+        });
+        return Ok(());
+    }
+
+    manager.set_labels(
+        &stack.id,
+        target.map(|(_, entry)| &entry.id),
+        updated.clone(),
+    )?;
+    Output::success(format!(
+        "Labels of {subject}: {}",
+        if updated.is_empty() {
+            "none".to_string()
+        } else {
+            updated.join(", ")
+        }
+    ));
+
+    // Labels are listed in pull request descriptions, so refresh the ones they apply to
+    let has_prs = match target {
+        Some((_, entry)) => entry.pull_request_id.is_some() && !entry.is_merged,
+        None => stack
+            .entries
+            .iter()
+            .any(|entry| entry.pull_request_id.is_some() && !entry.is_merged),
+    };
+    if has_prs {
+        match pr_integration(&repo_root, &stack) {
+            Some(integration) => match integration.update_all_pr_descriptions(&stack.id).await {
+                Ok(prs) if !prs.is_empty() => Output::sub_item(format!(
+                    "Updated {} PR description{}",
+                    prs.len(),
+                    if prs.len() == 1 { "" } else { "s" }
+                )),
+                Ok(_) => {}
+                Err(e) => Output::warning(format!("Could not update PR descriptions: {e}")),
+            },
+            None => Output::tip("PR descriptions pick up the labels on the next 'ca submit'"),
+        }
+    }
+
+    Ok(())
+}
+
+/// List the labels of a stack (the active one by default) and its entries
+fn show_labels(manager: &StackManager, name: Option<String>) -> Result<()> {
+    let stack = match &name {
+        Some(name) => manager
+            .get_stack_by_name(name)
+            .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?,
+        None => manager
+            .get_active_stack()
+            .ok_or_else(CascadeError::no_active_stack)?,
+    };
+
+    let labeled =
+        !stack.labels().is_empty() || stack.entries.iter().any(|entry| !entry.labels.is_empty());
+    if !labeled {
+        Output::info(format!(
+            "Stack '{}' has no labels. Add some with 'ca stacks label add {} <label>'",
+            stack.name, stack.name
+        ));
+        return Ok(());
+    }
+    Output::section(format!("Labels of stack '{}'", stack.name));
+    if !stack.labels().is_empty() {
+        Output::sub_item(format!("Stack: {}", stack.labels().join(", ")));
+    }
+    for (index, entry) in stack.entries.iter().enumerate() {
+        if !entry.labels.is_empty() {
+            Output::numbered_item(
+                index + 1,
+                format!("{}: {}", entry.branch, entry.labels.join(", ")),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Move the active stack onto `onto`: change its base, rebase the entries through the
+/// regular sync and point the open pull requests at the new chain
+async fn retarget_stack(onto: String) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    retarget_stack_at(&repo_root, onto).await
+}
+
+/// [`retarget_stack`] for the repository at `repo_root`
+async fn retarget_stack_at(repo_root: &std::path::Path, onto: String) -> Result<()> {
+    let repo_root = repo_root.to_path_buf();
+    let mut manager = StackManager::new(&repo_root)?;
+    let (stack_id, stack_name, old_base) = {
+        let active_stack = manager.get_active_stack().ok_or_else(|| {
+            CascadeError::config("No active stack. Switch to the stack to retarget first")
+        })?;
+        (
+            active_stack.id,
+            active_stack.name.clone(),
+            active_stack.base_branch.clone(),
+        )
+    };
+
+    if onto == old_base {
+        Output::info(format!("Stack '{stack_name}' is already based on '{onto}'"));
+        return Ok(());
+    }
+    if crate::stack::SyncState::exists(&repo_root) {
+        return Err(CascadeError::validation(
+            "A sync is in progress. Finish it with 'ca sync continue' or 'ca sync abort' first",
+        ));
+    }
+
+    manager.set_stack_config(&stack_id, "base", Some(&onto))?;
+    Output::section(format!(
+        "Retargeting stack '{stack_name}': {old_base} → {onto}"
+    ));
+
+    if let Err(e) = sync_stack_at(&repo_root, false, false, false).await {
+        if crate::stack::SyncState::record_previous_base(&repo_root, &old_base)? {
+            // Paused on a conflict; 'ca sync abort' puts the old base back
+            return Err(e);
+        }
+        StackManager::new(&repo_root)?.set_stack_config(&stack_id, "base", Some(&old_base))?;
+        Output::warning(format!("Base branch left at '{old_base}'"));
+        return Err(e);
+    }
+
+    // The sync only retargets pull requests whose branches it rewrote
+    let has_prs = StackManager::new(&repo_root)?
+        .get_stack(&stack_id)
+        .is_some_and(|stack| stack.entries.iter().any(|e| e.pull_request_id.is_some()));
+    if has_prs {
+        let settings = crate::config::Settings::load_from_file(
+            &crate::config::get_repo_config_dir(&repo_root)?.join("config.json"),
+        )?;
+        let cascade_config = crate::config::CascadeConfig {
+            bitbucket: Some(settings.bitbucket.clone()),
+            git: settings.git.clone(),
+            auth: crate::config::AuthConfig::default(),
+            cascade: settings.cascade.clone(),
+        };
+        let integration =
+            BitbucketIntegration::new(StackManager::new(&repo_root)?, cascade_config)?;
+        for check in integration.check_pull_request_chain(&stack_id).await? {
+            if !check.target_drifted() {
+                continue;
+            }
+            match integration.retarget_to_chain(&check).await {
+                Ok(()) => Output::success(format!(
+                    "Retargeted PR #{} → {}",
+                    check.pr_id, check.expected_target
+                )),
+                Err(e) => Output::warning(format!(
+                    "Failed to retarget PR #{} to {}: {e}",
+                    check.pr_id, check.expected_target
+                )),
+            }
+        }
+    }
+
+    Output::success(format!("Stack '{stack_name}' now builds on '{onto}'"));
+    Ok(())
+}
+
+/// The stack template `name` from the repository configuration; no name gives the empty
+/// template
+fn stack_template(
+    repo_root: &std::path::Path,
+    name: Option<&str>,
+) -> Result<crate::config::StackTemplate> {
+    let Some(name) = name else {
+        return Ok(crate::config::StackTemplate::default());
+    };
+    let config_path = crate::config::get_repo_config_dir(repo_root)?.join("config.json");
+    let settings = crate::config::Settings::load_from_file(&config_path)?;
+    if let Some(template) = settings.templates.get(name) {
+        return Ok(template.clone());
+    }
+
+    let available: Vec<&str> = settings.templates.keys().map(String::as_str).collect();
+    Err(CascadeError::config(if available.is_empty() {
+        format!(
+            "No stack template '{name}'. Define one with: ca config set templates.{name}.base <branch>"
+        )
+    } else {
+        format!(
+            "No stack template '{name}' (available: {})",
+            available.join(", ")
+        )
+    }))
+}
+
+/// Branch manager that names new branches per the repository's `git.branch_template`
+fn branch_manager_for(repo_root: &std::path::Path) -> Result<crate::git::BranchManager> {
+    let config_path = crate::config::get_repo_config_dir(repo_root)?.join("config.json");
+    let template = crate::config::Settings::load_from_file(&config_path)
+        .ok()
+        .and_then(|settings| settings.git.branch_template);
+
+    Ok(
+        crate::git::BranchManager::new(GitRepository::open(repo_root)?)
+            .with_branch_template(template),
+    )
+}
+
+/// Split ready PRs for a collapsed land into the top-most PR (in stack order) and the
+/// PRs below it that it supersedes
+fn collapse_order<'a>(
+    stack: &crate::stack::Stack,
+    prs: &[&'a crate::bitbucket::pull_request::PullRequestStatus],
+) -> Option<(
+    &'a crate::bitbucket::pull_request::PullRequestStatus,
+    Vec<&'a crate::bitbucket::pull_request::PullRequestStatus>,
+)> {
+    let position = |pr_status: &crate::bitbucket::pull_request::PullRequestStatus| {
+        stack
+            .entries
+            .iter()
+            .position(|e| e.branch == pr_status.pr.from_ref.display_id)
+            .unwrap_or(usize::MAX)
+    };
+
+    let mut ordered = prs.to_vec();
+    ordered.sort_by_key(|pr_status| position(pr_status));
+    let top = ordered.pop()?;
+    Some((top, ordered))
+}
+
+const COLLAPSED_STACK_MARKER: &str = "Landed as a single commit for stack";
+
+/// Description for the top PR of a collapsed land, listing the PRs folded into it so the
+/// squash commit message covers the whole stack
+fn collapsed_pr_description(
+    original: Option<&str>,
+    stack_name: &str,
+    included: &[(u64, &str)],
+) -> String {
+    // Re-running land shouldn't stack up duplicate summaries
+    let original = original
+        .and_then(|d| d.split(COLLAPSED_STACK_MARKER).next())
+        .unwrap_or("")
+        .trim_end();
+
+    let mut description = String::new();
+    if !original.is_empty() {
+        description.push_str(original);
+        description.push_str("\n\n");
+    }
+    description.push_str(&format!("{COLLAPSED_STACK_MARKER} '{stack_name}':\n"));
+    for (id, title) in included {
+        description.push_str(&format!("\n- #{id} {title}"));
+    }
+    description
+}
+
+/// Land a stack as one squashed commit: retarget the top PR onto the base branch,
+/// squash-merge it, then decline the PRs below it as superseded
+async fn collapse_stack_prs(
+    pr_manager: &crate::bitbucket::pull_request::PullRequestManager,
+    stack: &crate::stack::Stack,
+    top: &crate::bitbucket::pull_request::PullRequest,
+    superseded: &[&crate::bitbucket::pull_request::PullRequest],
+    auto: bool,
+    conditions: &crate::bitbucket::pull_request::AutoMergeConditions,
+) -> Result<crate::bitbucket::pull_request::PullRequest> {
+    use crate::bitbucket::pull_request::{AutoMergeConditions, AutoMergeResult, MergeStrategy};
+
+    let included: Vec<(u64, &str)> = superseded
+        .iter()
+        .copied()
+        .chain(std::iter::once(top))
+        .map(|pr| (pr.id, pr.title.as_str()))
+        .collect();
+    let description = collapsed_pr_description(top.description.as_deref(), &stack.name, &included);
+    pr_manager
+        .update_pull_request(top.id, None, Some(description))
+        .await?;
+
+    let original_target = top.to_ref.display_id.clone();
+    let retargeted = original_target != stack.base_branch;
+    if retargeted {
+        pr_manager
+            .retarget_pull_request(top.id, &stack.base_branch)
+            .await?;
+    }
+
+    let squash = AutoMergeConditions {
+        merge_strategy: MergeStrategy::Squash,
+        ..conditions.clone()
+    };
+    let result = if auto {
+        pr_manager.auto_merge_if_ready(top.id, &squash).await
+    } else {
+        pr_manager
+            .merge_pull_request(top.id, MergeStrategy::Squash)
+            .await
+            .map(|pr| AutoMergeResult::Merged {
+                pr: Box::new(pr),
+                merge_strategy: MergeStrategy::Squash,
+            })
+    };
+
+    let outcome = match result {
+        Ok(AutoMergeResult::Merged { pr, .. }) => Ok(*pr),
+        Ok(AutoMergeResult::NotReady { blocking_reasons }) => {
+            Err(format!("not ready: {}", blocking_reasons.join(", ")))
+        }
+        Ok(AutoMergeResult::Failed { error }) => Err(error),
+        Err(e) => Err(e.to_string()),
+    };
+    let merged = match outcome {
+        Ok(merged) => merged,
+        Err(failure) => {
+            // Put the PR back where it was so the stack still reviews entry by entry
+            if retargeted {
+                let _ = pr_manager
+                    .retarget_pull_request(top.id, &original_target)
+                    .await;
+            }
+            return Err(CascadeError::bitbucket(format!(
+                "PR #{} could not be merged: {failure}",
+                top.id
+            )));
+        }
+    };
+
+    for pr in superseded {
+        let reason = format!(
+            "Superseded by PR #{}, which landed stack '{}' as a single commit",
+            top.id, stack.name
+        );
+        if let Err(e) = pr_manager.decline_pull_request(pr.id, &reason).await {
+            Output::warning(format!("Could not close superseded PR #{}: {e}", pr.id));
+        }
+    }
+
+    Ok(merged)
+}
+
+/// Note on a landed entry's commit which pull request it went through and, when the
+/// server merged it as another commit (squash or merge commit), the commit it landed as;
+/// that commit gets the pull request noted as well
+fn record_landed_trailers(
+    repo_root: &std::path::Path,
+    stack_id: &Uuid,
+    branch: &str,
+    pr_url: Option<String>,
+    merged: &crate::bitbucket::pull_request::PullRequest,
+) {
+    use crate::git::trailers::{LANDED_AS_TRAILER, PR_TRAILER};
+
+    // Earlier landings may have rebased the entry, so read its current commit
+    let Ok(manager) = StackManager::new(repo_root) else {
+        return;
+    };
+    let Some(entry) = manager
+        .get_stack(stack_id)
+        .and_then(|stack| stack.entries.iter().find(|e| e.branch == branch))
+    else {
+        return;
+    };
+    let mut trailers = Vec::new();
+    if let Some(url) = &pr_url {
+        trailers.push((PR_TRAILER, url.as_str()));
+    }
+    let landed_as = merged
+        .merge_commit()
+        .filter(|sha| *sha != entry.commit_hash);
+    if let Some(sha) = landed_as {
+        trailers.push((LANDED_AS_TRAILER, sha));
+    }
+    if trailers.is_empty() {
+        return;
+    }
+    let git_repo = manager.git_repo();
+    if let Err(e) = git_repo.add_trailers(&entry.commit_hash, &trailers) {
+        tracing::debug!("Failed to record trailers for {}: {}", branch, e);
+    }
+
+    // The commit on the base branch leads back to the review too
+    if let (Some(sha), Some(url)) = (landed_as, &pr_url) {
+        if let Err(e) = git_repo.add_trailers(sha, &[(PR_TRAILER, url)]) {
+            tracing::debug!("Failed to record trailers for {}: {}", sha, e);
+        }
+    }
+}
+
+/// Share the trailer notes with other clones. Like the trailers themselves this is a
+/// convenience, so a failure never fails the command.
+fn push_trailer_notes(repo_root: &std::path::Path) {
+    if let Err(e) = GitRepository::open(repo_root).and_then(|repo| repo.push_trailer_notes()) {
+        tracing::debug!("Failed to push trailer notes: {}", e);
+    }
+}
+
+/// Retarget stacks that depend on `stack_id` after its branches moved (sync/land)
+fn retarget_dependent_stacks(repo_root: &std::path::Path, stack_id: &Uuid) {
+    let result = StackManager::new(repo_root)
+        .and_then(|mut manager| manager.retarget_dependent_stacks(stack_id));
+
+    match result {
+        Ok(retargeted) => {
+            for (name, old_base, new_base) in &retargeted {
+                Output::sub_item(format!(
+                    "Retargeted dependent stack '{name}': {old_base} → {new_base}"
+                ));
+            }
+            if !retargeted.is_empty() {
+                Output::tip("Switch to each dependent stack and run 'ca sync' to rebase it");
+            }
+        }
+        Err(e) => Output::warning(format!("Failed to retarget dependent stacks: {e}")),
+    }
+}
+
+async fn validate_stack(
+    name: Option<String>,
+    fix_mode: Option<String>,
+    current_only: bool,
+    deep: bool,
+    retarget: bool,
+) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let mut manager = StackManager::new(&repo_root)?;
+
+    if deep {
+        if let Err(e) = manager.git_repo().fetch() {
+            Output::warning(format!(
+                "Could not fetch from origin, comparing with the last fetched state: {e}"
+            ));
+        }
+    }
+
+    // --current: only validate the active stack, exit 0 if none exists.
+    // Used by the pre-push hook so unrelated stacks don't block pushes.
+    if current_only {
+        let active = match manager.get_active_stack() {
+            Some(s) => s.clone(),
+            None => return Ok(()), // no active stack → nothing to validate
+        };
+
+        let stack_id = active.id;
+        match active.validate() {
+            Ok(_) => {}
+            Err(e) => {
+                Output::error(format!("Stack '{}' validation failed: {}", active.name, e));
+                return Err(CascadeError::config(e));
+            }
+        }
+        manager.handle_branch_modifications(&stack_id, fix_mode)?;
+        if deep && validate_remote_state(&repo_root, &active, retarget).await? > 0 {
+            return Err(CascadeError::validation(format!(
+                "Stack '{}' has drifted from origin or its pull requests",
+                active.name
+            )));
+        }
+        return Ok(());
+    }
+
+    if let Some(name) = name {
+        // Validate specific stack
+        let stack = manager
+            .get_stack_by_name(&name)
+            .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?;
+
+        let stack_id = stack.id;
+
+        // Basic structure validation first
+        match stack.validate() {
+            Ok(_message) => {
+                Output::success(format!("Stack '{}' structure validation passed", name));
+            }
+            Err(e) => {
+                Output::error(format!(
+                    "Stack '{}' structure validation failed: {}",
+                    name, e
+                ));
+                return Err(CascadeError::config(e));
+            }
+        }
+
+        // Handle branch modifications (includes Git integrity checks)
+        manager.handle_branch_modifications(&stack_id, fix_mode)?;
+
+        if deep {
+            let stack = manager
+                .get_stack(&stack_id)
+                .cloned()
+                .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?;
+            Output::section("Remote branches and pull requests");
+            if validate_remote_state(&repo_root, &stack, retarget).await? > 0 {
+                return Err(CascadeError::validation(format!(
+                    "Stack '{name}' has drifted from origin or its pull requests"
+                )));
+            }
+            Output::success("Remote branches and pull requests match the stack");
+        }
+
+        Output::spacing();
+        Output::success(format!("Stack '{name}' validation completed"));
+        Ok(())
+    } else {
+        // Validate all stacks
+        Output::section("Validating all stacks");
+        Output::spacing();
+
+        // Get all stack IDs through public method
+        let all_stacks = manager.get_all_stacks();
+        let stack_ids: Vec<uuid::Uuid> = all_stacks.iter().map(|s| s.id).collect();
+
+        if stack_ids.is_empty() {
+            Output::info("No stacks found");
             return Ok(());
         }
 
@@ -3558,7 +6842,7 @@ async fn validate_stack(
             let stack = manager.get_stack(&stack_id).unwrap();
             let stack_name = &stack.name;
 
-            println!("Checking stack '{stack_name}':");
+            Output::line(format!("Checking stack '{stack_name}':"));
 
             // Basic structure validation
             match stack.validate() {
@@ -3582,7 +6866,23 @@ async fn validate_stack(
                     all_valid = false;
                 }
             }
-            println!();
+
+            if deep {
+                if let Some(stack) = manager.get_stack(&stack_id).cloned() {
+                    match validate_remote_state(&repo_root, &stack, retarget).await {
+                        Ok(0) => Output::sub_item("Remote state: OK"),
+                        Ok(problems) => {
+                            Output::sub_item(format!("Remote state: {problems} problem(s)"));
+                            all_valid = false;
+                        }
+                        Err(e) => {
+                            Output::sub_item(format!("Remote state: {e}"));
+                            all_valid = false;
+                        }
+                    }
+                }
+            }
+            Output::spacing();
         }
 
         if all_valid {
@@ -3596,37 +6896,182 @@ async fn validate_stack(
     }
 }
 
-/// Get commits that are not yet in any stack entry
-#[allow(dead_code)]
-fn get_unpushed_commits(repo: &GitRepository, stack: &crate::stack::Stack) -> Result<Vec<String>> {
-    let mut unpushed = Vec::new();
-    let head_commit = repo.get_head_commit()?;
-    let mut current_commit = head_commit;
-
-    // Walk back from HEAD until we find a commit that's already in the stack
-    loop {
-        let commit_hash = current_commit.id().to_string();
-        let already_in_stack = stack
-            .entries
-            .iter()
-            .any(|entry| entry.commit_hash == commit_hash);
+/// Push entry branches deleted on origin back before submitting or landing, so the push or
+/// merge doesn't fail on a missing branch, and summarize what was restored. Failures are
+/// reported but don't stop the command.
+async fn restore_deleted_remote_branches(
+    integration: &mut BitbucketIntegration,
+    stack_id: &Uuid,
+    dry_run: bool,
+) {
+    if dry_run {
+        match integration.deleted_remote_branches(stack_id) {
+            Ok(deleted) if !deleted.is_empty() => {
+                Output::section("Branches deleted on origin");
+                for entry in deleted {
+                    Output::sub_item(format!("Would push '{}' back to origin", entry.branch));
+                }
+            }
+            Ok(_) => {}
+            Err(e) => Output::warning(format!("Could not check origin for deleted branches: {e}")),
+        }
+        return;
+    }
 
-        if already_in_stack {
-            break;
+    match integration.restore_deleted_branches(stack_id).await {
+        Ok(restored) if !restored.is_empty() => {
+            Output::section("Restored branches deleted on origin");
+            for branch in restored {
+                let pull_request = match branch.pull_request {
+                    PullRequestRecovery::NoPullRequest => "no pull request yet".to_string(),
+                    PullRequestRecovery::StillOpen(id) => format!("PR #{id} is still open"),
+                    PullRequestRecovery::Reopened(id) => format!("reopened PR #{id}"),
+                    PullRequestRecovery::Replaced { old, new } => {
+                        format!("PR #{old} could not be reopened, opened PR #{new} instead")
+                    }
+                };
+                Output::sub_item(format!("Pushed '{}' again: {pull_request}", branch.branch));
+            }
         }
+        Ok(_) => {}
+        Err(e) => {
+            Output::warning(format!("Could not restore branches deleted on origin: {e}"));
+        }
+    }
+}
 
-        unpushed.push(commit_hash);
+/// `validate --deep`: compare each unmerged entry branch with origin and each open pull
+/// request with the stack's chain, retargeting drifted pull requests when asked. Returns the
+/// number of problems left.
+async fn validate_remote_state(
+    repo_root: &std::path::Path,
+    stack: &Stack,
+    retarget: bool,
+) -> Result<usize> {
+    let repo = GitRepository::open(repo_root)?;
+    let mut problems = 0;
+
+    for (index, entry) in stack.entries.iter().enumerate() {
+        if entry.is_merged {
+            continue;
+        }
+        let number = index + 1;
+        let branch = &entry.branch;
+        let Ok(local) = repo.get_branch_head(branch) else {
+            Output::sub_item(format!(
+                "Entry {number}: branch '{branch}' is missing locally"
+            ));
+            problems += 1;
+            continue;
+        };
+        let Ok(remote) = repo.get_remote_branch_head(branch) else {
+            if entry.is_submitted {
+                Output::sub_item(format!(
+                    "Entry {number}: '{branch}' has a pull request but no branch on origin"
+                ));
+                problems += 1;
+            }
+            continue;
+        };
 
-        // Move to parent commit
-        if let Some(parent) = current_commit.parents().next() {
-            current_commit = parent;
+        if remote == local {
+            continue;
+        }
+        if entry.remote_commit_hash.as_deref() == Some(remote.as_str())
+            || repo.is_descendant_of(&local, &remote)?
+        {
+            Output::sub_item(format!(
+                "Entry {number}: '{branch}' has local changes that aren't pushed yet"
+            ));
+        } else if repo.is_descendant_of(&remote, &local)? {
+            Output::sub_item(format!(
+                "Entry {number}: origin/{branch} has commits the entry doesn't ({} → {})",
+                &local[..8],
+                &remote[..8]
+            ));
+            problems += 1;
         } else {
-            break;
+            Output::sub_item(format!(
+                "Entry {number}: '{branch}' and origin/{branch} have diverged ({} vs {})",
+                &local[..8],
+                &remote[..8]
+            ));
+            problems += 1;
         }
     }
 
-    unpushed.reverse(); // Reverse to get chronological order
-    Ok(unpushed)
+    if stack
+        .entries
+        .iter()
+        .all(|entry| entry.pull_request_id.is_none())
+    {
+        return Ok(problems);
+    }
+
+    let settings = crate::config::Settings::load_from_file(
+        &crate::config::get_repo_config_dir(repo_root)?.join("config.json"),
+    )?;
+    let cascade_config = crate::config::CascadeConfig {
+        bitbucket: Some(settings.bitbucket.clone()),
+        git: settings.git.clone(),
+        auth: crate::config::AuthConfig::default(),
+        cascade: settings.cascade.clone(),
+    };
+    let (integration, checks) =
+        match BitbucketIntegration::new(StackManager::new(repo_root)?, cascade_config) {
+            Ok(integration) => match integration.check_pull_request_chain(&stack.id).await {
+                Ok(checks) => (integration, checks),
+                Err(e) => {
+                    Output::warning(format!("Skipped pull request checks: {e}"));
+                    return Ok(problems);
+                }
+            },
+            Err(e) => {
+                Output::warning(format!("Skipped pull request checks: {e}"));
+                return Ok(problems);
+            }
+        };
+
+    let mut drifted_targets = 0;
+    for check in &checks {
+        let pr_id = check.pr_id;
+        if check.source_drifted() {
+            Output::sub_item(format!(
+                "PR #{pr_id} is opened from '{}' instead of the entry branch '{}'",
+                check.source, check.branch
+            ));
+            problems += 1;
+        }
+        if !check.target_drifted() {
+            continue;
+        }
+        if !retarget {
+            Output::sub_item(format!(
+                "PR #{pr_id} ('{}') targets '{}' instead of '{}'",
+                check.branch, check.target, check.expected_target
+            ));
+            drifted_targets += 1;
+            continue;
+        }
+        match integration.retarget_to_chain(check).await {
+            Ok(()) => Output::sub_item(format!(
+                "Retargeted PR #{pr_id}: {} → {}",
+                check.target, check.expected_target
+            )),
+            Err(e) => {
+                Output::warning(format!(
+                    "Failed to retarget PR #{pr_id} to {}: {e}",
+                    check.expected_target
+                ));
+                problems += 1;
+            }
+        }
+    }
+    if drifted_targets > 0 {
+        Output::tip("Run 'ca validate --deep --retarget' to point them back at the stack");
+    }
+
+    Ok(problems + drifted_targets)
 }
 
 /// Squash the last N commits into a single commit
@@ -3634,6 +7079,8 @@ pub async fn squash_commits(
     repo: &GitRepository,
     count: usize,
     since_ref: Option<String>,
+    strategy: &SquashMessage,
+    edit_message: bool,
 ) -> Result<()> {
     if count <= 1 {
         return Ok(()); // Nothing to squash
@@ -3649,7 +7096,9 @@ pub async fn squash_commits(
         format!("HEAD~{count}")
     };
 
-    println!("   Analyzing {count} commits to create smart squash message...");
+    Output::line(format!(
+        "   Analyzing {count} commits to create the squash message..."
+    ));
 
     // Get the commits that will be squashed to create a smart message
     let head_commit = repo.get_head_commit()?;
@@ -3666,12 +7115,15 @@ pub async fn squash_commits(
         }
     }
 
-    // Generate smart commit message from the squashed commits
-    let smart_message = generate_squash_message(&commits_to_squash)?;
-    println!(
-        "   Smart message: {}",
-        smart_message.lines().next().unwrap_or("")
-    );
+    // Word the squashed commit with the configured strategy, then let the user edit it
+    let mut squash_message = build_squash_message(strategy, &commits_to_squash)?;
+    if edit_message || *strategy == SquashMessage::Editor {
+        squash_message = edit_squash_message(&squash_message, &commits_to_squash)?;
+    }
+    Output::line(format!(
+        "   Message: {}",
+        squash_message.lines().next().unwrap_or("")
+    ));
 
     // Get the commit we want to reset to (the commit before our range)
     let reset_target = if since_ref.is_some() {
@@ -3688,19 +7140,105 @@ pub async fn squash_commits(
     // Stage all changes (they should already be staged from the reset --soft)
     repo.stage_all()?;
 
-    // Create the new commit with the smart message
-    let new_commit_hash = repo.commit(&smart_message)?;
+    // Create the new commit with the chosen message
+    let new_commit_hash = repo.commit(&squash_message)?;
 
-    println!(
+    Output::line(format!(
         "   Created squashed commit: {} ({})",
         &new_commit_hash[..8],
-        smart_message.lines().next().unwrap_or("")
-    );
-    println!("   💡 Tip: Use 'git commit --amend' to edit the commit message if needed");
+        squash_message.lines().next().unwrap_or("")
+    ));
+    if !edit_message {
+        Output::tip("Use --edit-message to edit the message while squashing");
+    }
 
     Ok(())
 }
 
+/// Word the commit replacing `commits` (newest first) with the given strategy. `Editor`
+/// starts from the concatenated messages
+pub fn build_squash_message(strategy: &SquashMessage, commits: &[git2::Commit]) -> Result<String> {
+    if *strategy == SquashMessage::Smart {
+        return generate_squash_message(commits);
+    }
+
+    let messages: Vec<String> = commits
+        .iter()
+        .rev()
+        .map(|c| c.message().unwrap_or("").trim().to_string())
+        .filter(|m| !m.is_empty())
+        .collect();
+    Ok(compose_squash_message(strategy, &messages)
+        .unwrap_or_else(|| "Squashed commits".to_string()))
+}
+
+/// Message for every strategy but `Smart`, from the messages oldest first
+fn compose_squash_message(strategy: &SquashMessage, messages: &[String]) -> Option<String> {
+    let subject = |message: &String| message.lines().next().unwrap_or("").to_string();
+    match strategy {
+        SquashMessage::Concat | SquashMessage::Editor | SquashMessage::Smart => {
+            (!messages.is_empty()).then(|| messages.join("\n\n"))
+        }
+        SquashMessage::First => messages.first().cloned(),
+        SquashMessage::Last => messages.last().cloned(),
+        SquashMessage::Template(template) => {
+            let subjects = messages
+                .iter()
+                .map(|m| format!("- {}", subject(m)))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let message = template
+                .replace("\\n", "\n")
+                .replace(
+                    "{first}",
+                    &messages.first().map(subject).unwrap_or_default(),
+                )
+                .replace("{last}", &messages.last().map(subject).unwrap_or_default())
+                .replace("{count}", &messages.len().to_string())
+                .replace("{subjects}", &subjects);
+            let message = message.trim();
+            (!message.is_empty()).then(|| message.to_string())
+        }
+    }
+}
+
+/// Open $EDITOR on the proposed squash message. Lines starting with '#' are dropped, and an
+/// empty message stops the squash before anything is changed
+fn edit_squash_message(proposed: &str, commits: &[git2::Commit]) -> Result<String> {
+    let mut template = format!(
+        "{proposed}\n\n# Message for the commit squashing these {} commits:\n",
+        commits.len()
+    );
+    for commit in commits.iter().rev() {
+        template.push_str(&format!(
+            "#   {} {}\n",
+            &commit.id().to_string()[..8],
+            commit.summary().unwrap_or("")
+        ));
+    }
+    template
+        .push_str("# Lines starting with '#' are ignored; an empty message cancels the squash.\n");
+
+    let edited = match dialoguer::Editor::new().extension(".txt").edit(&template) {
+        Ok(Some(edited)) => edited
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        // Closed without saving: keep the proposed message
+        Ok(None) => proposed.to_string(),
+        Err(e) => return Err(CascadeError::config(format!("Failed to open editor: {e}"))),
+    };
+
+    let edited = edited.trim().to_string();
+    if edited.is_empty() {
+        return Err(CascadeError::validation(
+            "Squash cancelled: the commit message is empty",
+        ));
+    }
+    Ok(edited)
+}
+
 /// Generate a smart commit message from multiple commits being squashed
 pub fn generate_squash_message(commits: &[git2::Commit]) -> Result<String> {
     if commits.is_empty() {
@@ -3844,6 +7382,10 @@ async fn land_stack(
     let repo_root = find_repository_root(&current_dir)
         .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
 
+    if !dry_run {
+        freshness_preflight(&repo_root, "landing").await?;
+    }
+
     let stack_manager = StackManager::new(&repo_root)?;
 
     // Get stack ID and active stack before moving stack_manager
@@ -3860,7 +7402,7 @@ async fn land_stack(
     let active_stack = stack_manager
         .get_active_stack()
         .cloned()
-        .ok_or_else(|| CascadeError::config("No active stack found".to_string()))?;
+        .ok_or_else(CascadeError::no_active_stack)?;
 
     // Load configuration and create Bitbucket integration
     let config_dir = crate::config::get_repo_config_dir(&repo_root)?;
@@ -3876,6 +7418,7 @@ async fn land_stack(
 
     let mut integration =
         crate::bitbucket::BitbucketIntegration::new(stack_manager, cascade_config)?;
+    restore_deleted_remote_branches(&mut integration, &stack_id, dry_run).await;
 
     // Get enhanced status
     let mut status = integration.check_enhanced_stack_status(&stack_id).await?;
@@ -3893,6 +7436,13 @@ async fn land_stack(
         return Ok(());
     }
 
+    let collapse = settings.cascade.land_mode == crate::config::LandMode::Collapse;
+    if collapse && entry.is_some() {
+        return Err(CascadeError::config(
+            "cascade.land_mode is 'collapse': the whole stack lands as a single commit, so entries can't be landed individually",
+        ));
+    }
+
     // Filter PRs that are ready to land
     let ready_prs: Vec<_> = status
         .enhanced_statuses
@@ -3920,8 +7470,22 @@ async fn land_stack(
         })
         .collect();
 
-    if ready_prs.is_empty() {
-        if let Some(entry_num) = entry {
+    // Collapsing needs every open PR in the stack, not just the ready ones
+    let open_count = status
+        .enhanced_statuses
+        .iter()
+        .filter(|s| s.pr.state == crate::bitbucket::pull_request::PullRequestState::Open)
+        .count();
+    let collapse_blocked = collapse && !force && ready_prs.len() < open_count;
+
+    if ready_prs.is_empty() || collapse_blocked {
+        if collapse_blocked {
+            Output::error(format!(
+                "Only {} of {} PRs are ready; collapse mode lands the whole stack at once",
+                ready_prs.len(),
+                open_count
+            ));
+        } else if let Some(entry_num) = entry {
             Output::error(format!(
                 "Entry {entry_num} is not ready to land or doesn't exist"
             ));
@@ -3930,7 +7494,7 @@ async fn land_stack(
         }
 
         // Show what's blocking them
-        println!();
+        Output::spacing();
         Output::section("Blocking Issues");
         for pr_status in &status.enhanced_statuses {
             if pr_status.pr.state == crate::bitbucket::pull_request::PullRequestState::Open {
@@ -3942,13 +7506,33 @@ async fn land_stack(
         }
 
         if !force {
-            println!();
+            Output::spacing();
             Output::tip("Use --force to land PRs with blocking issues (dangerous!)");
         }
         return Ok(());
     }
 
+    let collapse_plan = if collapse {
+        collapse_order(&active_stack, &ready_prs)
+    } else {
+        None
+    };
+
     if dry_run {
+        if let Some((top, superseded)) = &collapse_plan {
+            Output::section(format!(
+                "Dry Run - Stack would land as a single commit through PR #{}",
+                top.pr.id
+            ));
+            Output::sub_item(format!("PR #{}: {}", top.pr.id, top.pr.title));
+            for pr_status in superseded {
+                Output::sub_item(format!(
+                    "PR #{}: {} (closed as superseded)",
+                    pr_status.pr.id, pr_status.pr.title
+                ));
+            }
+            return Ok(());
+        }
         if let Some(entry_num) = entry {
             Output::section(format!("Dry Run - Entry {entry_num} that would be landed"));
         } else {
@@ -3976,34 +7560,139 @@ async fn land_stack(
         }
     }
 
+    // Every PR ends up merged into its target, the lower entries' PRs into the base
+    let mut merge_targets: Vec<String> = ready_prs
+        .iter()
+        .map(|s| s.pr.to_ref.display_id.clone())
+        .chain(std::iter::once(active_stack.base_branch.clone()))
+        .collect();
+    merge_targets.sort();
+    merge_targets.dedup();
+    permission_preflight(&repo_root, &settings, "landing", &merge_targets).await?;
+
+    StackManager::new(&repo_root)?.snapshot_before("land");
+
     // Setup auto-merge conditions
-    let merge_strategy: crate::bitbucket::pull_request::MergeStrategy =
-        strategy.unwrap_or(MergeStrategyArg::Squash).into();
+    let merge_strategy = strategy
+        .map(crate::bitbucket::pull_request::MergeStrategy::from)
+        .or_else(|| active_stack.overrides.merge_strategy.clone())
+        .unwrap_or(crate::bitbucket::pull_request::MergeStrategy::Squash);
     let auto_merge_conditions = crate::bitbucket::pull_request::AutoMergeConditions {
         merge_strategy: merge_strategy.clone(),
         wait_for_builds,
         build_timeout: std::time::Duration::from_secs(build_timeout),
         allowed_authors: None, // Allow all authors for now
     };
+    if collapse
+        && !matches!(
+            merge_strategy,
+            crate::bitbucket::pull_request::MergeStrategy::Squash
+        )
+    {
+        Output::warning("cascade.land_mode is 'collapse', which always squash-merges");
+    }
+
+    let pr_context = |pr: &crate::bitbucket::pull_request::PullRequest| {
+        serde_json::json!({
+            "id": pr.id,
+            "title": pr.title,
+            "branch": pr.from_ref.display_id,
+            "target": pr.to_ref.display_id,
+        })
+    };
+    crate::stack::lifecycle::run_hook(
+        &repo_root,
+        LifecycleHook::PreLand,
+        serde_json::json!({
+            "stack": crate::stack::lifecycle::stack_context(&active_stack),
+            "pull_requests": ready_prs.iter().map(|s| pr_context(&s.pr)).collect::<Vec<_>>(),
+            "strategy": format!("{merge_strategy:?}"),
+        }),
+    )?;
 
     // Land the PRs
-    println!();
+    Output::spacing();
     Output::section(format!(
         "Landing {} PR{}",
         ready_prs.len(),
         if ready_prs.len() == 1 { "" } else { "s" }
     ));
 
-    let pr_manager = crate::bitbucket::pull_request::PullRequestManager::new(
-        crate::bitbucket::BitbucketClient::new(&settings.bitbucket)?,
-    );
+    let pr_manager =
+        crate::bitbucket::pull_request::PullRequestManager::from_settings(&settings, &repo_root)?;
 
     // Land PRs in dependency order
+    let mut landed_prs = Vec::new(); // Reported to the post-land hook
     let mut landed_count = 0;
     let mut failed_count = 0;
     let total_ready_prs = ready_prs.len();
 
-    for pr_status in ready_prs {
+    // Collapse mode lands everything through the top PR, leaving nothing to merge one by one
+    let per_entry_prs = match &collapse_plan {
+        Some((top, superseded)) => {
+            Output::progress(format!(
+                "Collapsing {} PR{} into PR #{}: {}",
+                total_ready_prs,
+                if total_ready_prs == 1 { "" } else { "s" },
+                top.pr.id,
+                top.pr.title
+            ));
+            let superseded_prs: Vec<_> = superseded.iter().map(|s| &s.pr).collect();
+            match collapse_stack_prs(
+                &pr_manager,
+                &active_stack,
+                &top.pr,
+                &superseded_prs,
+                auto,
+                &auto_merge_conditions,
+            )
+            .await
+            {
+                Ok(merged) => {
+                    Output::success_inline();
+                    landed_count = total_ready_prs;
+                    // Every collapsed entry landed as the one squash commit
+                    for pr in superseded_prs
+                        .iter()
+                        .copied()
+                        .chain(std::iter::once(&top.pr))
+                    {
+                        record_landed_trailers(
+                            &repo_root,
+                            &stack_id,
+                            &pr.from_ref.display_id,
+                            pr.web_url(),
+                            &merged,
+                        );
+                    }
+                    landed_prs.extend(superseded_prs.iter().map(|pr| pr_context(pr)));
+                    landed_prs.push(pr_context(&top.pr));
+
+                    // Everything up to the top entry is now on the base branch
+                    let mut mark_manager = StackManager::new(&repo_root)?;
+                    if let Some(top_index) = active_stack
+                        .entries
+                        .iter()
+                        .position(|e| e.branch == top.pr.from_ref.display_id)
+                    {
+                        for landed_entry in &active_stack.entries[..=top_index] {
+                            let _ =
+                                mark_manager.set_entry_merged(&stack_id, &landed_entry.id, true);
+                        }
+                    }
+                }
+                Err(e) => {
+                    Output::error_inline("");
+                    Output::error(format!("Failed to collapse stack: {e}"));
+                    failed_count += 1;
+                }
+            }
+            Vec::new()
+        }
+        None => ready_prs,
+    };
+
+    for pr_status in per_entry_prs {
         let pr_id = pr_status.pr.id;
 
         Output::progress(format!("Landing PR #{}: {}", pr_id, pr_status.pr.title));
@@ -4027,9 +7716,17 @@ async fn land_stack(
         };
 
         match land_result {
-            Ok(crate::bitbucket::pull_request::AutoMergeResult::Merged { .. }) => {
+            Ok(crate::bitbucket::pull_request::AutoMergeResult::Merged { pr: merged, .. }) => {
                 Output::success_inline();
                 landed_count += 1;
+                landed_prs.push(pr_context(&pr_status.pr));
+                record_landed_trailers(
+                    &repo_root,
+                    &stack_id,
+                    &pr_status.pr.from_ref.display_id,
+                    pr_status.pr.web_url(),
+                    &merged,
+                );
 
                 // Mark the landed entry as merged so the retarget rebase skips it
                 let merged_branch = &pr_status.pr.from_ref.display_id;
@@ -4081,7 +7778,7 @@ async fn land_stack(
                     let entry_count = stack_for_count.entries.len();
                     let plural = if entry_count == 1 { "entry" } else { "entries" };
 
-                    println!(); // Spacing
+                    Output::spacing(); // Spacing
                     let rebase_spinner = crate::utils::spinner::Spinner::new(format!(
                         "Retargeting {} {}",
                         entry_count, plural
@@ -4100,7 +7797,7 @@ async fn land_stack(
                     let rebase_result = rebase_manager.rebase_stack(&stack_id);
 
                     rebase_spinner.stop();
-                    println!(); // Spacing
+                    Output::spacing(); // Spacing
 
                     match rebase_result {
                         Ok(rebase_result) => {
@@ -4143,15 +7840,15 @@ async fn land_stack(
                         }
                         Err(e) => {
                             // CONFLICTS DETECTED - Give clear next steps
-                            println!();
+                            Output::spacing();
                             Output::error("Rebase conflict while retargeting remaining PRs");
-                            println!();
+                            Output::spacing();
                             Output::section("To resolve and continue");
                             Output::numbered_item(1, "Resolve conflicts in the affected files");
                             Output::numbered_item(2, "Stage resolved files: git add <files>");
                             Output::numbered_item(3, "Finish the rebase: ca sync continue");
                             Output::numbered_item(4, "Or abort the rebase: ca sync abort");
-                            println!();
+                            Output::spacing();
                             Output::tip(
                                 "Once the rebase is complete, re-run 'ca land' to continue merging remaining PRs",
                             );
@@ -4190,7 +7887,7 @@ async fn land_stack(
     }
 
     // Show summary
-    println!();
+    Output::spacing();
     Output::section("Landing Summary");
     Output::sub_item(format!("Successfully landed: {landed_count}"));
     if failed_count > 0 {
@@ -4200,6 +7897,22 @@ async fn land_stack(
     if landed_count > 0 {
         Output::success("Landing operation completed!");
 
+        push_trailer_notes(&repo_root);
+        retarget_dependent_stacks(&repo_root, &stack_id);
+
+        let landed_stack = StackManager::new(&repo_root)?
+            .get_stack(&stack_id)
+            .map(crate::stack::lifecycle::stack_context);
+        crate::stack::lifecycle::run_hook(
+            &repo_root,
+            LifecycleHook::PostLand,
+            serde_json::json!({
+                "stack": landed_stack,
+                "landed": landed_prs,
+                "failed_count": failed_count,
+            }),
+        )?;
+
         // Check if all entries in the stack are now merged
         let final_stack_manager = StackManager::new(&repo_root)?;
         if let Some(final_stack) = final_stack_manager.get_stack(&stack_id) {
@@ -4260,7 +7973,7 @@ async fn land_stack(
                     "entries"
                 };
 
-                println!();
+                Output::spacing();
                 let rebase_spinner = crate::utils::spinner::Spinner::new(format!(
                     "Retargeting {remaining_count} {plural}"
                 ));
@@ -4278,7 +7991,7 @@ async fn land_stack(
                 let rebase_result = rebase_manager.rebase_stack(&stack_id);
 
                 rebase_spinner.stop();
-                println!();
+                Output::spacing();
 
                 match rebase_result {
                     Ok(rebase_result) => {
@@ -4315,15 +8028,15 @@ async fn land_stack(
                         }
                     }
                     Err(e) => {
-                        println!();
+                        Output::spacing();
                         Output::error("Rebase conflict while retargeting remaining PRs");
-                        println!();
+                        Output::spacing();
                         Output::section("To resolve and continue");
                         Output::numbered_item(1, "Resolve conflicts in the affected files");
                         Output::numbered_item(2, "Stage resolved files: git add <files>");
                         Output::numbered_item(3, "Finish the rebase: ca sync continue");
                         Output::numbered_item(4, "Or abort the rebase: ca sync abort");
-                        println!();
+                        Output::spacing();
                         Output::tip(
                             "Once resolved, re-run 'ca land' to continue merging remaining PRs",
                         );
@@ -4333,9 +8046,9 @@ async fn land_stack(
             }
 
             if all_merged {
-                println!();
+                Output::spacing();
                 Output::success("All PRs in stack merged!");
-                println!();
+                Output::spacing();
 
                 // Switch back to base branch so no stack is active
                 let base_branch = active_stack.base_branch.clone();
@@ -4353,10 +8066,8 @@ async fn land_stack(
 
                 // Prompt to clean up merged branches
                 if !dry_run {
-                    let should_cleanup = Confirm::with_theme(&ColorfulTheme::default())
-                        .with_prompt("Clean up merged branches?")
-                        .default(true)
-                        .interact()
+                    let should_cleanup = prompter()
+                        .confirm("Clean up merged branches?", true)
                         .unwrap_or(false);
 
                     if should_cleanup {
@@ -4413,10 +8124,8 @@ async fn land_stack(
                     }
 
                     // Prompt to delete the stack metadata
-                    let should_delete_stack = Confirm::with_theme(&ColorfulTheme::default())
-                        .with_prompt(format!("Delete stack '{}'?", final_stack.name))
-                        .default(true)
-                        .interact()
+                    let should_delete_stack = prompter()
+                        .confirm(&format!("Delete stack '{}'?", final_stack.name), true)
                         .unwrap_or(false);
 
                     if should_delete_stack {
@@ -4466,7 +8175,7 @@ async fn continue_land() -> Result<()> {
 
     Output::warning("'ca stack continue-land' is deprecated");
     Output::tip("Use 'ca sync continue' to finish the rebase, then 'ca land' to continue merging");
-    println!();
+    Output::spacing();
 
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
@@ -4489,7 +8198,7 @@ async fn continue_land() -> Result<()> {
     }
 
     Output::section("Continuing land operation");
-    println!();
+    Output::spacing();
 
     // Step 1: Complete the cherry-pick/rebase
     Output::info("Completing conflict resolution...");
@@ -4511,7 +8220,7 @@ async fn continue_land() -> Result<()> {
         }
     }
 
-    println!();
+    Output::spacing();
 
     // Step 2: Get the active stack
     let stack_manager = StackManager::new(&repo_root)?;
@@ -4523,7 +8232,7 @@ async fn continue_land() -> Result<()> {
 
     // Step 3: Rebase remaining stack entries (restack children)
     Output::info("Rebasing remaining stack entries...");
-    println!();
+    Output::spacing();
 
     let git_repo_for_rebase = crate::git::GitRepository::open(&repo_root)?;
     let mut rebase_manager = crate::stack::RebaseManager::new(
@@ -4541,17 +8250,17 @@ async fn continue_land() -> Result<()> {
     if !rebase_result.success {
         // Check if this is a conflict that needs resolution
         if !rebase_result.conflicts.is_empty() {
-            println!();
+            Output::spacing();
             Output::error("Additional conflicts detected during rebase");
-            println!();
+            Output::spacing();
             Output::tip("To resolve and continue:");
             Output::bullet("Resolve conflicts in your editor");
             Output::bullet("Stage resolved files: git add <files>");
             Output::bullet("Finish the rebase: ca sync continue");
-            println!();
+            Output::spacing();
             Output::tip("Or abort the rebase:");
             Output::bullet("ca sync abort");
-            println!();
+            Output::spacing();
             Output::tip("Once the rebase is complete, re-run 'ca land' to continue merging");
 
             // Leave state intact for user to continue
@@ -4568,7 +8277,7 @@ async fn continue_land() -> Result<()> {
         ));
     }
 
-    println!();
+    Output::spacing();
     Output::success(format!(
         "Rebased {} remaining entries",
         rebase_result.branch_mapping.len()
@@ -4580,7 +8289,7 @@ async fn continue_land() -> Result<()> {
 
     if let Ok(settings) = crate::config::Settings::load_from_file(&config_path) {
         if !rebase_result.branch_mapping.is_empty() {
-            println!();
+            Output::spacing();
             Output::info("Updating pull requests...");
 
             let cascade_config = crate::config::CascadeConfig {
@@ -4612,9 +8321,9 @@ async fn continue_land() -> Result<()> {
         }
     }
 
-    println!();
+    Output::spacing();
     Output::success("Land operation continued successfully");
-    println!();
+    Output::spacing();
     Output::tip("Next steps:");
     Output::bullet("Wait for builds to pass on rebased PRs");
     Output::bullet("Once builds are green, run: ca land");
@@ -4625,7 +8334,7 @@ async fn continue_land() -> Result<()> {
 async fn abort_land() -> Result<()> {
     Output::warning("'ca stack abort-land' is deprecated");
     Output::tip("Use 'ca sync abort' to abort the rebase");
-    println!();
+    Output::spacing();
 
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
@@ -4643,7 +8352,7 @@ async fn abort_land() -> Result<()> {
         return Ok(());
     }
 
-    println!("⚠️  Aborting rebase...");
+    Output::warning("Aborting rebase...");
     match rebase_manager.abort_rebase() {
         Ok(_) => {
             Output::success("Rebase aborted successfully");
@@ -4651,7 +8360,7 @@ async fn abort_land() -> Result<()> {
         }
         Err(e) => {
             warn!("❌ Failed to abort rebase: {}", e);
-            println!("⚠️  You may need to manually clean up the repository state");
+            Output::warning("You may need to manually clean up the repository state");
         }
     }
 
@@ -4668,7 +8377,7 @@ async fn land_status() -> Result<()> {
     let stack_manager = StackManager::new(&repo_root)?;
     let git_repo = crate::git::GitRepository::open(&repo_root)?;
 
-    println!("Land Status");
+    Output::line("Land Status");
 
     // Check if land operation is in progress by checking git state directly
     let git_dir = git_repo.git_dir();
@@ -4677,15 +8386,15 @@ async fn land_status() -> Result<()> {
         || git_dir.join("rebase-apply").exists();
 
     if land_in_progress {
-        println!("   Status: 🔄 Land operation in progress");
-        println!(
+        Output::line("   Status: 🔄 Land operation in progress");
+        Output::line(
             "   
-📝 Actions available:"
+📝 Actions available:",
         );
-        println!("     - 'ca sync continue' to finish the rebase");
-        println!("     - 'ca sync abort' to abort the rebase");
-        println!("     - 'ca land' to continue merging (after rebase is complete)");
-        println!("     - 'git status' to see conflicted files");
+        Output::line("     - 'ca sync continue' to finish the rebase");
+        Output::line("     - 'ca sync abort' to abort the rebase");
+        Output::line("     - 'ca land' to continue merging (after rebase is complete)");
+        Output::line("     - 'git status' to see conflicted files");
 
         // Check for conflicts
         match git_repo.get_status() {
@@ -4700,18 +8409,18 @@ async fn land_status() -> Result<()> {
                 }
 
                 if !conflicts.is_empty() {
-                    println!("   ⚠️  Conflicts in {} files:", conflicts.len());
+                    Output::line(format!("   ⚠️  Conflicts in {} files:", conflicts.len()));
                     for conflict in conflicts {
-                        println!("      - {conflict}");
+                        Output::line(format!("      - {conflict}"));
                     }
-                    println!(
+                    Output::line(
                         "   
-💡 To resolve conflicts:"
+💡 To resolve conflicts:",
                     );
-                    println!("     1. Edit the conflicted files");
-                    println!("     2. Stage resolved files: git add <file>");
-                    println!("     3. Finish the rebase: ca sync continue");
-                    println!("     4. Then re-run: ca land");
+                    Output::line("     1. Edit the conflicted files");
+                    Output::line("     2. Stage resolved files: git add <file>");
+                    Output::line("     3. Finish the rebase: ca sync continue");
+                    Output::line("     4. Then re-run: ca land");
                 }
             }
             Err(e) => {
@@ -4719,13 +8428,13 @@ async fn land_status() -> Result<()> {
             }
         }
     } else {
-        println!("   Status: ✅ No land operation in progress");
+        Output::line("   Status: ✅ No land operation in progress");
 
         // Show stack status instead
         if let Some(active_stack) = stack_manager.get_active_stack() {
-            println!("   Active stack: {}", active_stack.name);
-            println!("   Entries: {}", active_stack.entries.len());
-            println!("   Base branch: {}", active_stack.base_branch);
+            Output::line(format!("   Active stack: {}", active_stack.name));
+            Output::line(format!("   Entries: {}", active_stack.entries.len()));
+            Output::line(format!("   Base branch: {}", active_stack.base_branch));
         }
     }
 
@@ -4741,7 +8450,7 @@ async fn repair_stack_data() -> Result<()> {
 
     let mut stack_manager = StackManager::new(&repo_root)?;
 
-    println!("🔧 Repairing stack data consistency...");
+    Output::info("Repairing stack data consistency...");
 
     stack_manager.repair_all_stacks()?;
 
@@ -4899,19 +8608,18 @@ async fn perform_cleanup(
         // Show first few branch names for context
         let preview_count = 5.min(candidates.len());
         for candidate in candidates.iter().take(preview_count) {
-            println!("  • {}", candidate.branch_name);
+            Output::bullet(&candidate.branch_name);
         }
         if candidates.len() > preview_count {
-            println!("  ... and {} more", candidates.len() - preview_count);
+            Output::line(format!(
+                "  ... and {} more",
+                candidates.len() - preview_count
+            ));
         }
-        println!(); // Spacing before prompt
+        Output::spacing(); // Spacing before prompt
 
         // Interactive confirmation to proceed with cleanup
-        let should_continue = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Continue with branch cleanup?")
-            .default(false)
-            .interact()
-            .map_err(|e| CascadeError::config(format!("Failed to get user confirmation: {e}")))?;
+        let should_continue = prompter().confirm("Continue with branch cleanup?", false)?;
 
         if !should_continue {
             Output::sub_item("Cleanup cancelled");
@@ -4959,12 +8667,12 @@ async fn analyze_commits_for_safeguards(
 
     // 🛡️ SAFEGUARD 1: Large commit count warning
     if commits_to_push.len() > LARGE_COMMIT_THRESHOLD {
-        println!(
+        Output::line(format!(
             "⚠️  Warning: About to push {} commits to stack",
             commits_to_push.len()
-        );
-        println!("   This may indicate a merge commit issue or unexpected commit range.");
-        println!("   Large commit counts often result from merging instead of rebasing.");
+        ));
+        Output::line("   This may indicate a merge commit issue or unexpected commit range.");
+        Output::line("   Large commit counts often result from merging instead of rebasing.");
 
         if !dry_run && !confirm_large_push(commits_to_push.len())? {
             return Err(CascadeError::config("Push cancelled by user"));
@@ -4985,13 +8693,13 @@ async fn analyze_commits_for_safeguards(
         .collect();
 
     if !merge_commits.is_empty() {
-        println!(
+        Output::line(format!(
             "⚠️  Warning: {} merge commits detected in push",
             merge_commits.len()
-        );
-        println!("   This often indicates you merged instead of rebased.");
-        println!("   Consider using 'ca sync' to rebase on the base branch.");
-        println!("   Merge commits in stacks can cause confusion and duplicate work.");
+        ));
+        Output::line("   This often indicates you merged instead of rebased.");
+        Output::line("   Consider using 'ca sync' to rebase on the base branch.");
+        Output::line("   Merge commits in stacks can cause confusion and duplicate work.");
     }
 
     // 🛡️ SAFEGUARD 3: Commit age warning
@@ -5002,27 +8710,30 @@ async fn analyze_commits_for_safeguards(
 
         if time_span > WEEK_IN_SECONDS {
             let days = time_span / (24 * 3600);
-            println!("⚠️  Warning: Commits span {days} days");
-            println!("   This may indicate merged history rather than new work.");
-            println!("   Recent work should typically span hours or days, not weeks.");
+            Output::warning(format!("Commits span {days} days"));
+            Output::line("   This may indicate merged history rather than new work.");
+            Output::line("   Recent work should typically span hours or days, not weeks.");
         }
     }
 
     // 🛡️ SAFEGUARD 4: Better range detection suggestions
     if commits_to_push.len() > 5 {
         Output::tip(" Tip: If you only want recent commits, use:");
-        println!(
+        Output::line(format!(
             "   ca push --since HEAD~{}  # pushes last {} commits",
             std::cmp::min(commits_to_push.len(), 5),
             std::cmp::min(commits_to_push.len(), 5)
-        );
-        println!("   ca push --commits <hash1>,<hash2>  # pushes specific commits");
-        println!("   ca push --dry-run  # preview what would be pushed");
+        ));
+        Output::line("   ca push --commits <hash1>,<hash2>  # pushes specific commits");
+        Output::line("   ca push --dry-run  # preview what would be pushed");
     }
 
     // 🛡️ SAFEGUARD 5: Dry run mode
     if dry_run {
-        println!("🔍 DRY RUN: Would push {} commits:", commits_to_push.len());
+        Output::info(format!(
+            "DRY RUN: Would push {} commits:",
+            commits_to_push.len()
+        ));
         for (i, (commit_hash, commit_obj)) in commits_to_push
             .iter()
             .zip(commit_objects.iter())
@@ -5030,7 +8741,7 @@ async fn analyze_commits_for_safeguards(
         {
             let summary = commit_obj.summary().unwrap_or("(no message)");
             let short_hash = &commit_hash[..std::cmp::min(commit_hash.len(), 7)];
-            println!("  {}: {} ({})", i + 1, summary, short_hash);
+            Output::line(format!("  {}: {} ({})", i + 1, summary, short_hash));
         }
         Output::tip(" Run without --dry-run to actually push these commits.");
     }
@@ -5041,11 +8752,8 @@ async fn analyze_commits_for_safeguards(
 /// Prompt user for confirmation when pushing large number of commits
 fn confirm_large_push(count: usize) -> Result<bool> {
     // Interactive confirmation for large push
-    let should_continue = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt(format!("Continue pushing {count} commits?"))
-        .default(false)
-        .interact()
-        .map_err(|e| CascadeError::config(format!("Failed to get user confirmation: {e}")))?;
+    let should_continue =
+        prompter().confirm(&format!("Continue pushing {count} commits?"), false)?;
 
     Ok(should_continue)
 }
@@ -5143,9 +8851,9 @@ async fn drop_entries(
     let mut manager = StackManager::new(&repo_root)?;
     let repo = GitRepository::open(&repo_root)?;
 
-    let active_stack = manager.get_active_stack().ok_or_else(|| {
-        CascadeError::config("No active stack. Create a stack first with 'ca stacks create'")
-    })?;
+    let active_stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?;
     let stack_id = active_stack.id;
     let entry_count = active_stack.entries.len();
 
@@ -5154,158 +8862,381 @@ async fn drop_entries(
         return Ok(());
     }
 
-    let indices = parse_entry_spec(&entry_spec, entry_count)?;
-
-    // Validate: refuse entries that are merged
-    for &idx in &indices {
-        let entry = &active_stack.entries[idx - 1];
-        if entry.is_merged {
-            return Err(CascadeError::config(format!(
-                "Entry {} ('{}') is already merged. Use 'ca stacks cleanup' to remove merged entries.",
-                idx,
-                entry.short_message(40)
-            )));
+    let indices = parse_entry_spec(&entry_spec, entry_count)?;
+
+    // Validate: refuse entries that are merged
+    for &idx in &indices {
+        let entry = &active_stack.entries[idx - 1];
+        if entry.is_merged {
+            return Err(CascadeError::config(format!(
+                "Entry {} ('{}') is already merged. Use 'ca stacks cleanup' to remove merged entries.",
+                idx,
+                entry.short_message(40)
+            )));
+        }
+    }
+
+    // Display entries to drop
+    let has_submitted = indices
+        .iter()
+        .any(|&idx| active_stack.entries[idx - 1].is_submitted);
+
+    Output::section(format!("Entries to drop ({})", indices.len()));
+    for &idx in &indices {
+        let entry = &active_stack.entries[idx - 1];
+        let pr_status = if entry.is_submitted {
+            format!(" [PR #{}]", entry.pull_request_id.as_deref().unwrap_or("?"))
+        } else {
+            String::new()
+        };
+        Output::numbered_item(
+            idx,
+            format!(
+                "{} {} (branch: {}){}",
+                entry.short_hash(),
+                entry.short_message(40),
+                entry.branch,
+                pr_status
+            ),
+        );
+    }
+
+    // Confirmation
+    if !force && !yes {
+        if has_submitted {
+            Output::warning("Some entries have associated pull requests.");
+        }
+
+        let default_confirm = !has_submitted;
+        let should_continue = prompter().confirm(
+            &format!("Drop {} entry/entries from stack?", indices.len()),
+            default_confirm,
+        )?;
+
+        if !should_continue {
+            Output::info("Drop cancelled.");
+            return Ok(());
+        }
+    }
+
+    // Collect branch names before removal
+    let entries_info: Vec<String> = indices
+        .iter()
+        .map(|&idx| active_stack.entries[idx - 1].branch.clone())
+        .collect();
+
+    let current_branch = repo.get_current_branch()?;
+    let stack_name = active_stack.name.clone();
+    let mut dropped = Vec::with_capacity(indices.len());
+
+    // Remove entries in reverse index order to preserve indices
+    for (i, &idx) in indices.iter().enumerate().rev() {
+        let zero_idx = idx - 1;
+        match manager.remove_stack_entry_at(&stack_id, zero_idx)? {
+            Some(removed) => {
+                Output::success(format!(
+                    "Dropped entry {}: {} {}",
+                    idx,
+                    removed.short_hash(),
+                    removed.short_message(40)
+                ));
+                dropped.push(removed);
+            }
+            None => {
+                Output::warning(format!("Could not remove entry {idx}"));
+                continue;
+            }
+        }
+
+        let branch_name = &entries_info[i];
+
+        // Delete branch unless --keep-branch or it's the current branch
+        if !keep_branch && *branch_name != current_branch {
+            match repo.delete_branch(branch_name) {
+                Ok(_) => Output::sub_item(format!("Deleted branch: {branch_name}")),
+                Err(e) => Output::warning(format!("Could not delete branch {branch_name}: {e}")),
+            }
+        }
+    }
+
+    // --force skips the question, declining as it always has
+    handle_removed_prs(
+        &repo_root,
+        &stack_name,
+        &dropped,
+        "ca drop",
+        keep_pr.then_some(RemovedPrAction::Keep),
+        force.then_some(RemovedPrAction::Decline),
+    )
+    .await;
+
+    Output::success(format!(
+        "Dropped {} entry/entries from stack",
+        indices.len()
+    ));
+
+    Ok(())
+}
+
+/// Render the `ca stacks edit` todo list for a stack's unmerged entries
+fn render_edit_todo(stack: &crate::stack::Stack) -> String {
+    let mut todo = String::new();
+    for entry in stack.entries.iter().filter(|e| !e.is_merged) {
+        todo.push_str(&format!(
+            "pick {} {}\n",
+            entry.short_hash(),
+            entry.message.lines().next().unwrap_or("")
+        ));
+    }
+    todo.push_str(&format!(
+        "\n# Edit stack '{}' (bottom of the stack first)\n\
+         #\n\
+         # Commands:\n\
+         # p, pick <hash> = keep entry\n\
+         # r, reword <hash> <message> = keep entry, using the rest of the line as its message\n\
+         # s, squash <hash> = fold entry into the one above it (keeps that message)\n\
+         # d, drop <hash> = remove entry\n\
+         # m, move <hash> <stack> = move entry to the top of another stack\n\
+         #\n\
+         # Lines can be reordered. Removing a line drops the entry.\n\
+         # Removing everything cancels the edit.\n",
+        stack.name
+    ));
+    todo
+}
+
+/// Parse an edited todo list into plan steps
+fn parse_edit_todo(todo: &str, stack: &crate::stack::Stack) -> Result<Vec<crate::stack::EditStep>> {
+    use crate::stack::{EditAction, EditStep};
+
+    let candidates: Vec<_> = stack.entries.iter().filter(|e| !e.is_merged).collect();
+    let mut steps = Vec::new();
+
+    for (line_no, line) in todo.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.splitn(3, char::is_whitespace);
+        let verb = parts.next().unwrap_or_default();
+        let hash = parts.next().ok_or_else(|| {
+            CascadeError::validation(format!("Line {}: missing commit hash", line_no + 1))
+        })?;
+        let rest = parts.next().unwrap_or("").trim();
+
+        let matches: Vec<_> = candidates
+            .iter()
+            .filter(|e| hash.len() >= 4 && e.commit_hash.starts_with(hash))
+            .collect();
+        let entry = match matches.as_slice() {
+            [entry] => entry,
+            [] => {
+                return Err(CascadeError::validation(format!(
+                    "Line {}: '{hash}' does not match an unmerged entry",
+                    line_no + 1
+                )))
+            }
+            _ => {
+                return Err(CascadeError::validation(format!(
+                    "Line {}: '{hash}' is ambiguous",
+                    line_no + 1
+                )))
+            }
+        };
+
+        let action = match verb {
+            "p" | "pick" => EditAction::Pick,
+            "s" | "squash" => EditAction::Squash,
+            "d" | "drop" => EditAction::Drop,
+            "r" | "reword" => {
+                if rest.is_empty() {
+                    return Err(CascadeError::validation(format!(
+                        "Line {}: reword needs a new message",
+                        line_no + 1
+                    )));
+                }
+                EditAction::Reword(rest.to_string())
+            }
+            "m" | "move" => {
+                let target = rest.split_whitespace().next().ok_or_else(|| {
+                    CascadeError::validation(format!(
+                        "Line {}: move needs a target stack name",
+                        line_no + 1
+                    ))
+                })?;
+                EditAction::Move(target.to_string())
+            }
+            other => {
+                return Err(CascadeError::validation(format!(
+                    "Line {}: unknown command '{other}'",
+                    line_no + 1
+                )))
+            }
+        };
+
+        steps.push(EditStep {
+            entry_id: entry.id,
+            action,
+        });
+    }
+
+    Ok(steps)
+}
+
+/// Restructure the active stack through an editor todo list
+async fn edit_stack(yes: bool) -> Result<()> {
+    use crate::stack::EditAction;
+
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let manager = StackManager::new(&repo_root)?;
+    let stack = manager
+        .get_active_stack()
+        .ok_or_else(CascadeError::no_active_stack)?
+        .clone();
+
+    if stack.entries.iter().all(|e| e.is_merged) {
+        Output::info("Stack has no unmerged entries to edit.");
+        return Ok(());
+    }
+
+    let todo = render_edit_todo(&stack);
+    let edited = match dialoguer::Editor::new().extension(".txt").edit(&todo) {
+        Ok(Some(edited)) => edited,
+        Ok(None) => {
+            Output::info("Edit cancelled.");
+            return Ok(());
         }
+        Err(e) => return Err(CascadeError::config(format!("Failed to open editor: {e}"))),
+    };
+
+    let steps = parse_edit_todo(&edited, &stack)?;
+    if steps.is_empty() {
+        Output::info("Edit cancelled (empty plan).");
+        return Ok(());
     }
 
-    // Display entries to drop
-    let has_submitted = indices
+    let unmerged: Vec<_> = stack.entries.iter().filter(|e| !e.is_merged).collect();
+    let unchanged = steps.len() == unmerged.len()
+        && steps
+            .iter()
+            .zip(&unmerged)
+            .all(|(step, entry)| step.entry_id == entry.id && step.action == EditAction::Pick);
+    if unchanged {
+        Output::info("No changes to apply.");
+        return Ok(());
+    }
+
+    // Entries left out of the plan are dropped; squashed ones lose their branch too
+    let mut removed: Vec<&crate::stack::StackEntry> = unmerged
         .iter()
-        .any(|&idx| active_stack.entries[idx - 1].is_submitted);
+        .filter(|entry| !steps.iter().any(|step| step.entry_id == entry.id))
+        .copied()
+        .collect();
 
-    Output::section(format!("Entries to drop ({})", indices.len()));
-    for &idx in &indices {
-        let entry = &active_stack.entries[idx - 1];
-        let pr_status = if entry.is_submitted {
-            format!(" [PR #{}]", entry.pull_request_id.as_deref().unwrap_or("?"))
-        } else {
-            String::new()
+    Output::section(format!("Edit plan for '{}'", stack.name));
+    for (i, step) in steps.iter().enumerate() {
+        let entry = &stack.entry_map[&step.entry_id];
+        let description = match &step.action {
+            EditAction::Pick => format!("pick   {}", entry.short_message(50)),
+            EditAction::Reword(message) => format!("reword {} → {message}", entry.short_hash()),
+            EditAction::Squash => format!("squash {}", entry.short_message(50)),
+            EditAction::Drop => format!("drop   {}", entry.short_message(50)),
+            EditAction::Move(target) => {
+                format!("move   {} → {target}", entry.short_message(50))
+            }
         };
-        Output::numbered_item(
-            idx,
-            format!(
-                "{} {} (branch: {}){}",
-                entry.short_hash(),
-                entry.short_message(40),
-                entry.branch,
-                pr_status
-            ),
-        );
-    }
-
-    // Confirmation
-    if !force && !yes {
-        if has_submitted {
-            Output::warning("Some entries have associated pull requests.");
+        Output::numbered_item(i + 1, description);
+        if matches!(step.action, EditAction::Squash | EditAction::Drop) {
+            removed.push(entry);
         }
+    }
+    for entry in unmerged
+        .iter()
+        .filter(|entry| !steps.iter().any(|step| step.entry_id == entry.id))
+    {
+        Output::bullet(format!("drop   {} (line removed)", entry.short_message(50)));
+    }
 
-        let default_confirm = !has_submitted;
-        let should_continue = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(format!("Drop {} entry/entries from stack?", indices.len()))
-            .default(default_confirm)
-            .interact()
-            .map_err(|e| CascadeError::config(format!("Failed to get user confirmation: {e}")))?;
+    if !yes {
+        let should_continue = prompter().confirm("Apply this plan?", true)?;
 
         if !should_continue {
-            Output::info("Drop cancelled.");
+            Output::info("Edit cancelled.");
             return Ok(());
         }
     }
 
-    // Collect entry info before removal (we need branch names and PR info)
-    let entries_info: Vec<(String, Option<String>, bool)> = indices
-        .iter()
-        .map(|&idx| {
-            let entry = &active_stack.entries[idx - 1];
-            (
-                entry.branch.clone(),
-                entry.pull_request_id.clone(),
-                entry.is_submitted,
-            )
-        })
-        .collect();
-
-    let current_branch = repo.get_current_branch()?;
-
-    // Lazily construct Bitbucket client only if needed
-    let mut pr_manager = None;
-
-    // Remove entries in reverse index order to preserve indices
-    for (i, &idx) in indices.iter().enumerate().rev() {
-        let zero_idx = idx - 1;
-        match manager.remove_stack_entry_at(&stack_id, zero_idx)? {
-            Some(removed) => {
-                Output::success(format!(
-                    "Dropped entry {}: {} {}",
-                    idx,
-                    removed.short_hash(),
-                    removed.short_message(40)
-                ));
-            }
-            None => {
-                Output::warning(format!("Could not remove entry {idx}"));
-                continue;
-            }
-        }
+    let git_repo = GitRepository::open(&repo_root)?;
+    let mut rebase_manager = crate::stack::RebaseManager::new(
+        manager,
+        git_repo,
+        crate::stack::RebaseOptions {
+            strategy: crate::stack::RebaseStrategy::ForcePush,
+            ..Default::default()
+        },
+    );
+    let result = rebase_manager.apply_edit_plan(&stack.id, &steps)?;
+    let mut manager = rebase_manager.into_stack_manager();
 
-        let (ref branch_name, ref pr_id, is_submitted) = entries_info[i];
+    Output::success("Stack edited");
+    Output::sub_item(result.get_summary());
 
-        // Delete branch unless --keep-branch or it's the current branch
-        if !keep_branch && *branch_name != current_branch {
-            match repo.delete_branch(branch_name) {
-                Ok(_) => Output::sub_item(format!("Deleted branch: {branch_name}")),
-                Err(e) => Output::warning(format!("Could not delete branch {branch_name}: {e}")),
+    // Clean up branches of removed entries (the checked-out branch is left alone)
+    let repo = GitRepository::open(&repo_root)?;
+    let current_branch = repo.get_current_branch().unwrap_or_default();
+    for entry in &removed {
+        if entry.branch != current_branch {
+            match repo.delete_branch_unsafe(&entry.branch) {
+                Ok(_) => Output::sub_item(format!("Deleted branch: {}", entry.branch)),
+                Err(e) => Output::warning(format!("Could not delete branch {}: {e}", entry.branch)),
             }
         }
+    }
 
-        // Decline PR if entry was submitted (unless --keep-pr)
-        if is_submitted && !keep_pr {
-            if let Some(pr_id_str) = pr_id {
-                if let Ok(pr_id_num) = pr_id_str.parse::<u64>() {
-                    let should_decline = if force {
-                        true
-                    } else {
-                        Confirm::with_theme(&ColorfulTheme::default())
-                            .with_prompt(format!("Decline PR #{pr_id_num} on Bitbucket?"))
-                            .default(true)
-                            .interact()
-                            .unwrap_or(false)
-                    };
+    // Close PRs whose entries no longer exist; --yes declines them as it always has
+    let removed: Vec<_> = removed.into_iter().cloned().collect();
+    handle_removed_prs(
+        &repo_root,
+        &stack.name,
+        &removed,
+        "ca stacks edit",
+        None,
+        yes.then_some(RemovedPrAction::Decline),
+    )
+    .await;
 
-                    if should_decline {
-                        // Lazily create PR manager
-                        if pr_manager.is_none() {
-                            let config_dir = crate::config::get_repo_config_dir(&repo_root)?;
-                            let config_path = config_dir.join("config.json");
-                            let settings = crate::config::Settings::load_from_file(&config_path)?;
-                            let client =
-                                crate::bitbucket::BitbucketClient::new(&settings.bitbucket)?;
-                            pr_manager = Some(crate::bitbucket::PullRequestManager::new(client));
-                        }
+    // Update PRs: rewritten branches here, moved branches in their new stacks
+    let rewritten: Vec<String> = result.branch_mapping.keys().cloned().collect();
+    crate::cli::commands::entry::refresh_pull_requests(&repo_root, &stack.id, &rewritten).await;
 
-                        if let Some(ref mgr) = pr_manager {
-                            match mgr
-                                .decline_pull_request(pr_id_num, "Dropped from stack")
-                                .await
-                            {
-                                Ok(_) => Output::sub_item(format!(
-                                    "Declined PR #{pr_id_num} on Bitbucket"
-                                )),
-                                Err(e) => Output::warning(format!(
-                                    "Failed to decline PR #{pr_id_num}: {e}"
-                                )),
-                            }
-                        }
-                    }
-                }
+    let mut touched_stacks = vec![stack.id];
+    for step in &steps {
+        if let EditAction::Move(target_name) = &step.action {
+            let Some(target_id) = manager.get_stack_by_name(target_name).map(|s| s.id) else {
+                continue;
+            };
+            let branch = stack.entry_map[&step.entry_id].branch.clone();
+            crate::cli::commands::entry::refresh_pull_requests(&repo_root, &target_id, &[branch])
+                .await;
+            if !touched_stacks.contains(&target_id) {
+                touched_stacks.push(target_id);
             }
         }
     }
 
-    Output::success(format!(
-        "Dropped {} entry/entries from stack",
-        indices.len()
-    ));
+    for stack_id in &touched_stacks {
+        for (name, old_base, new_base) in manager.retarget_dependent_stacks(stack_id)? {
+            Output::sub_item(format!(
+                "Retargeted dependent stack '{name}': {old_base} → {new_base}"
+            ));
+        }
+    }
 
     Ok(())
 }
@@ -5385,7 +9316,7 @@ mod tests {
         let (temp_dir, repo_path) = match create_test_repo() {
             Ok(repo) => repo,
             Err(_) => {
-                println!("Skipping test due to git environment setup failure");
+                Output::line("Skipping test due to git environment setup failure");
                 return;
             }
         };
@@ -5402,6 +9333,7 @@ mod tests {
                     "test-stack".to_string(),
                     None, // Use default branch
                     Some("Test description".to_string()),
+                    None,
                 )
                 .await;
 
@@ -5417,7 +9349,7 @@ mod tests {
             }
             Err(_) => {
                 // Skip test if we can't change directories (CI environment issue)
-                println!("Skipping test due to directory access restrictions");
+                Output::line("Skipping test due to directory access restrictions");
             }
         }
     }
@@ -5427,7 +9359,7 @@ mod tests {
         let (temp_dir, repo_path) = match create_test_repo() {
             Ok(repo) => repo,
             Err(_) => {
-                println!("Skipping test due to git environment setup failure");
+                Output::line("Skipping test due to git environment setup failure");
                 return;
             }
         };
@@ -5440,7 +9372,7 @@ mod tests {
         let original_dir = env::current_dir().map_err(|_| "Failed to get current dir");
         match env::set_current_dir(&repo_path) {
             Ok(_) => {
-                let result = list_stacks(false, false, None).await;
+                let result = list_stacks(false, false, ListOptions::default()).await;
 
                 // Restore original directory (best effort)
                 if let Ok(orig) = original_dir {
@@ -5454,7 +9386,7 @@ mod tests {
             }
             Err(_) => {
                 // Skip test if we can't change directories (CI environment issue)
-                println!("Skipping test due to directory access restrictions");
+                Output::line("Skipping test due to directory access restrictions");
             }
         }
     }
@@ -5492,6 +9424,32 @@ mod tests {
         assert!(result.contains("Implement") || result.contains("Squashed") || result.len() > 5);
     }
 
+    #[test]
+    fn test_compose_squash_message() {
+        let messages = vec![
+            "Add parser\n\nHandles quoted strings".to_string(),
+            "Fix parser edge case".to_string(),
+        ];
+        assert_eq!(
+            compose_squash_message(&SquashMessage::Concat, &messages).unwrap(),
+            "Add parser\n\nHandles quoted strings\n\nFix parser edge case"
+        );
+        assert_eq!(
+            compose_squash_message(&SquashMessage::First, &messages).unwrap(),
+            messages[0]
+        );
+        assert_eq!(
+            compose_squash_message(&SquashMessage::Last, &messages).unwrap(),
+            "Fix parser edge case"
+        );
+        let template = SquashMessage::Template("{first} ({count} commits)\\n\\n{subjects}".into());
+        assert_eq!(
+            compose_squash_message(&template, &messages).unwrap(),
+            "Add parser (2 commits)\n\n- Add parser\n- Fix parser edge case"
+        );
+        assert_eq!(compose_squash_message(&SquashMessage::Last, &[]), None);
+    }
+
     #[test]
     fn test_extract_feature_from_wip_empty() {
         let messages = vec![];
@@ -5600,7 +9558,7 @@ mod tests {
         let (temp_dir, repo_path) = match create_test_repo() {
             Ok(repo) => repo,
             Err(_) => {
-                println!("Skipping test due to git environment setup failure");
+                Output::line("Skipping test due to git environment setup failure");
                 return;
             }
         };
@@ -5619,6 +9577,7 @@ mod tests {
                     "test-stack".to_string(),
                     None,
                     Some("Test stack for auto-land".to_string()),
+                    None,
                 )
                 .await;
 
@@ -5634,7 +9593,7 @@ mod tests {
                 );
             }
             Err(_) => {
-                println!("Skipping test due to directory access restrictions");
+                Output::line("Skipping test due to directory access restrictions");
             }
         }
     }
@@ -5778,7 +9737,7 @@ mod tests {
         let (temp_dir, repo_path) = match create_test_repo() {
             Ok(repo) => repo,
             Err(_) => {
-                println!("Skipping test due to git environment setup failure");
+                Output::line("Skipping test due to git environment setup failure");
                 return;
             }
         };
@@ -5787,7 +9746,7 @@ mod tests {
 
         // Verify directory exists before changing to it
         if !repo_path.exists() {
-            println!("Skipping test due to temporary directory creation issue");
+            Output::line("Skipping test due to temporary directory creation issue");
             return;
         }
 
@@ -5797,20 +9756,7 @@ mod tests {
         match env::set_current_dir(&repo_path) {
             Ok(_) => {
                 // Test that push_to_stack properly handles the case when no stack is active
-                let result = push_to_stack(
-                    None,  // branch
-                    None,  // message
-                    None,  // commit
-                    None,  // since
-                    None,  // commits
-                    None,  // squash
-                    None,  // squash_since
-                    false, // auto_branch
-                    false, // allow_base_branch
-                    false, // dry_run
-                    true,  // yes (skip prompts in test)
-                )
-                .await;
+                let result = push_to_stack(PushSpec::new().yes(true)).await;
 
                 // Restore original directory (best effort)
                 if let Ok(orig) = original_dir {
@@ -5833,48 +9779,29 @@ mod tests {
                     }
                     Ok(_) => {
                         // If it somehow succeeds, that's also fine (e.g., if environment is set up differently)
-                        println!(
-                            "Push succeeded unexpectedly - test environment may have active stack"
+                        Output::line(
+                            "Push succeeded unexpectedly - test environment may have active stack",
                         );
                     }
                 }
             }
             Err(_) => {
                 // Skip test if we can't change directories (CI environment issue)
-                println!("Skipping test due to directory access restrictions");
+                Output::line("Skipping test due to directory access restrictions");
             }
         }
 
         // Verify we can construct the command structure correctly
-        let push_action = StackAction::Push {
-            branch: None,
-            message: None,
-            commit: None,
-            since: None,
-            commits: None,
-            squash: None,
-            squash_since: None,
-            auto_branch: false,
-            allow_base_branch: false,
-            dry_run: false,
-            yes: false,
-        };
+        let push_action = StackAction::Push(PushArgs::default());
 
         assert!(matches!(
             push_action,
-            StackAction::Push {
+            StackAction::Push(PushArgs {
                 branch: None,
-                message: None,
                 commit: None,
-                since: None,
-                commits: None,
-                squash: None,
-                squash_since: None,
-                auto_branch: false,
-                allow_base_branch: false,
-                dry_run: false,
-                yes: false
-            }
+                interactive: false,
+                ..
+            })
         ));
     }
 
@@ -5884,7 +9811,7 @@ mod tests {
         let (temp_dir, repo_path) = match create_test_repo() {
             Ok(repo) => repo,
             Err(_) => {
-                println!("Skipping test due to git environment setup failure");
+                Output::line("Skipping test due to git environment setup failure");
                 return;
             }
         };
@@ -5893,7 +9820,7 @@ mod tests {
 
         // Verify directory exists before changing to it
         if !repo_path.exists() {
-            println!("Skipping test due to temporary directory creation issue");
+            Output::line("Skipping test due to temporary directory creation issue");
             return;
         }
 
@@ -5901,7 +9828,7 @@ mod tests {
         let original_dir = match env::current_dir() {
             Ok(dir) => dir,
             Err(_) => {
-                println!("Skipping test due to current directory access restrictions");
+                Output::line("Skipping test due to current directory access restrictions");
                 return;
             }
         };
@@ -5914,8 +9841,10 @@ mod tests {
                     None,  // title
                     None,  // description
                     None,  // range
-                    false, // draft
+                    None,  // draft
                     true,  // open
+                    false, // update
+                    false, // edit
                 )
                 .await;
 
@@ -5938,13 +9867,13 @@ mod tests {
                     }
                     Ok(_) => {
                         // If it somehow succeeds, that's also fine (e.g., if environment is set up differently)
-                        println!("Submit succeeded unexpectedly - test environment may have active stack");
+                        Output::line("Submit succeeded unexpectedly - test environment may have active stack");
                     }
                 }
             }
             Err(_) => {
                 // Skip test if we can't change directories (CI environment issue)
-                println!("Skipping test due to directory access restrictions");
+                Output::line("Skipping test due to directory access restrictions");
             }
         }
 
@@ -5955,7 +9884,10 @@ mod tests {
             description: None,
             range: None,
             draft: true, // Default changed to true
+            no_draft: false,
             open: true,
+            update: false,
+            edit: false,
         };
 
         assert!(matches!(
@@ -5966,7 +9898,10 @@ mod tests {
                 description: None,
                 range: None,
                 draft: true, // Default changed to true
-                open: true
+                no_draft: false,
+                open: true,
+                update: false,
+                edit: false
             }
         ));
     }
@@ -5999,20 +9934,8 @@ mod tests {
     fn test_command_flow_logic() {
         // These just test the command structure exists
         assert!(matches!(
-            StackAction::Push {
-                branch: None,
-                message: None,
-                commit: None,
-                since: None,
-                commits: None,
-                squash: None,
-                squash_since: None,
-                auto_branch: false,
-                allow_base_branch: false,
-                dry_run: false,
-                yes: false
-            },
-            StackAction::Push { .. }
+            StackAction::Push(PushArgs::default()),
+            StackAction::Push(_)
         ));
 
         assert!(matches!(
@@ -6022,7 +9945,10 @@ mod tests {
                 description: None,
                 range: None,
                 draft: false,
-                open: true
+                no_draft: false,
+                open: true,
+                update: false,
+                edit: false
             },
             StackAction::Submit { .. }
         ));
@@ -6037,4 +9963,647 @@ mod tests {
             StackAction::Deactivate { force: false }
         ));
     }
+
+    #[test]
+    fn test_submit_document_round_trip() {
+        let drafts = vec![
+            SubmitDraft {
+                entry_num: 1,
+                label: "auth (PR #12)".to_string(),
+                title: "Add auth".to_string(),
+                description: Some("## Summary\nAdds auth.".to_string()),
+            },
+            SubmitDraft {
+                entry_num: 2,
+                label: "login".to_string(),
+                title: "Add login".to_string(),
+                description: None,
+            },
+        ];
+
+        let document = render_submit_document("feature", &drafts);
+        assert_eq!(parse_submit_document(&document, &[1, 2]).unwrap(), drafts);
+
+        // Editing the title and removing a section
+        let edited = document
+            .replace("Add auth", "Add OAuth")
+            .split("\n=== Entry 2")
+            .next()
+            .unwrap()
+            .to_string();
+        let parsed = parse_submit_document(&edited, &[1, 2]).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].title, "Add OAuth");
+        assert_eq!(
+            parsed[0].description.as_deref(),
+            Some("## Summary\nAdds auth.")
+        );
+    }
+
+    #[test]
+    fn test_submit_document_rejects_bad_sections() {
+        assert!(parse_submit_document("=== Entry 3: x ===\nTitle\n", &[1, 2]).is_err());
+        assert!(parse_submit_document("=== Entry 1: x ===\n\n", &[1]).is_err());
+        assert!(
+            parse_submit_document("=== Entry 1: x ===\nA\n=== Entry 1: x ===\nB\n", &[1]).is_err()
+        );
+        assert!(parse_submit_document("stray text\n", &[1]).is_err());
+        assert!(parse_submit_document("", &[1]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_edit_todo() {
+        use crate::stack::EditAction;
+
+        let mut stack = crate::stack::Stack::new("edit".to_string(), "main".to_string(), None);
+        let first = stack.push_entry(
+            "edit-1".to_string(),
+            "aaaa1111aaaa1111aaaa1111aaaa1111aaaa1111".to_string(),
+            "First".to_string(),
+        );
+        let second = stack.push_entry(
+            "edit-2".to_string(),
+            "bbbb2222bbbb2222bbbb2222bbbb2222bbbb2222".to_string(),
+            "Second".to_string(),
+        );
+
+        // The rendered todo round-trips to an all-pick plan
+        let steps = parse_edit_todo(&render_edit_todo(&stack), &stack).unwrap();
+        assert_eq!(steps.len(), 2);
+        assert!(steps.iter().all(|s| s.action == EditAction::Pick));
+
+        let steps = parse_edit_todo(
+            "r bbbb2222 Better second\n# comment\nm aaaa1111 other-stack\n",
+            &stack,
+        )
+        .unwrap();
+        assert_eq!(steps[0].entry_id, second);
+        assert_eq!(
+            steps[0].action,
+            EditAction::Reword("Better second".to_string())
+        );
+        assert_eq!(steps[1].entry_id, first);
+        assert_eq!(steps[1].action, EditAction::Move("other-stack".to_string()));
+
+        assert!(parse_edit_todo("pick cccc3333", &stack).is_err());
+        assert!(parse_edit_todo("fixup aaaa1111", &stack).is_err());
+        assert!(parse_edit_todo("reword aaaa1111", &stack).is_err());
+    }
+
+    #[test]
+    fn test_collapsed_pr_description() {
+        let included = [(11, "Add parser"), (12, "Use parser in CLI")];
+
+        let description = collapsed_pr_description(Some("Wires it up"), "parser", &included);
+        assert!(description.starts_with("Wires it up\n\n"));
+        assert!(description.contains("Landed as a single commit for stack 'parser'"));
+        assert!(description.contains("- #11 Add parser"));
+        assert!(description.ends_with("- #12 Use parser in CLI"));
+
+        // Landing again replaces the summary instead of appending another one
+        let again = collapsed_pr_description(Some(&description), "parser", &included);
+        assert_eq!(again, description);
+
+        let bare = collapsed_pr_description(None, "parser", &included);
+        assert!(bare.starts_with("Landed as a single commit"));
+    }
+
+    #[test]
+    fn test_stack_list_rows_filter_sort_and_render() {
+        let now = Utc::now();
+        let row = |name: &str, entries: usize, hours_ago: i64, needs_sync: bool| {
+            let mut stack = Stack::new(name.to_string(), "main".to_string(), None);
+            for i in 0..entries {
+                stack.push_entry(
+                    format!("{name}-{i}"),
+                    format!("{i:040}"),
+                    format!("Commit {i}"),
+                );
+            }
+            stack.updated_at = now - chrono::Duration::days(10);
+            for entry in &mut stack.entries {
+                entry.updated_at = now - chrono::Duration::hours(hours_ago);
+            }
+            StackRow::new(stack, needs_sync, false)
+        };
+
+        let mut with_pr = row("beta", 2, 1, false);
+        with_pr.stack.entries[0].pull_request_id = Some("7".to_string());
+        with_pr.stack.entries[1].pull_request_id = Some("8".to_string());
+        with_pr.stack.entries[1].is_merged = true;
+        let with_pr = StackRow::new(with_pr.stack, false, false);
+        assert_eq!(with_pr.open_prs, 1);
+
+        let mut rows = vec![
+            with_pr,
+            row("Alpha", 1, 30, true),
+            row("gamma", 0, 0, false),
+        ];
+        assert!(rows[0].matches(StackFilter::HasOpenPrs));
+        assert!(!rows[1].matches(StackFilter::HasOpenPrs));
+        assert!(rows[1].matches(StackFilter::NeedsSync));
+        assert_eq!(rows[1].status_label(), "needs sync");
+        // An empty stack falls back to its own update time
+        assert_eq!(rows[2].last_activity, rows[2].stack.updated_at);
+
+        let names = |rows: &[StackRow]| {
+            rows.iter()
+                .map(|r| r.stack.name.clone())
+                .collect::<Vec<_>>()
+        };
+        sort_stack_rows(&mut rows, ListSort::Name);
+        assert_eq!(names(&rows), ["Alpha", "beta", "gamma"]);
+        sort_stack_rows(&mut rows, ListSort::Recent);
+        assert_eq!(names(&rows), ["beta", "Alpha", "gamma"]);
+        sort_stack_rows(&mut rows, ListSort::Size);
+        assert_eq!(names(&rows), ["beta", "Alpha", "gamma"]);
+
+        rows[0].stack.is_active = true;
+        let lines = render_stack_table(
+            &rows,
+            &[
+                ListColumn::Name,
+                ListColumn::Prs,
+                ListColumn::Updated,
+                ListColumn::Branches,
+            ],
+            now,
+        );
+        assert_eq!(lines[0], "  NAME   PRS  UPDATED  BRANCHES");
+        assert_eq!(lines[1], "* beta   1    1h ago   beta-0 … beta-1");
+        assert_eq!(lines[2], "  Alpha  0    1d ago   Alpha-0");
+        assert_eq!(lines[3], "  gamma  0    10d ago  -");
+    }
+
+    #[test]
+    fn test_stack_list_needs_sync_filter_after_base_moves() {
+        let (_temp_dir, repo_path) = create_test_repo().unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let base = git(&["branch", "--show-current"]);
+        git(&["checkout", "-b", "release"]);
+        git(&["checkout", &base]);
+
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        for (name, stack_base) in [("behind", &base), ("current", &"release".to_string())] {
+            let stack_id = manager
+                .create_stack(name.to_string(), Some(stack_base.clone()), None)
+                .unwrap();
+            git(&["checkout", "-b", &format!("{name}-1"), stack_base]);
+            git(&["commit", "--allow-empty", "-m", name]);
+            manager.get_stack_mut(&stack_id).unwrap().push_entry(
+                format!("{name}-1"),
+                git(&["rev-parse", "HEAD"]),
+                name.to_string(),
+            );
+        }
+        manager.save_to_disk().unwrap();
+        git(&["checkout", &base]);
+        git(&["commit", "--allow-empty", "-m", "Base moves on"]);
+
+        let manager = StackManager::new(&repo_path).unwrap();
+        let options = ListOptions {
+            filters: vec![StackFilter::NeedsSync],
+            ..ListOptions::default()
+        };
+        let rows = stack_rows(&manager, false, &options).unwrap();
+        let names: Vec<_> = rows.iter().map(|row| row.stack.name.as_str()).collect();
+        assert_eq!(names, ["behind"]);
+    }
+
+    #[test]
+    fn test_landed_trailers_reach_the_commit_on_the_trunk() {
+        let (_temp_dir, repo_path) = create_test_repo().unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let base = git(&["branch", "--show-current"]);
+        std::fs::write(repo_path.join(".git/info/exclude"), ".cascade/\n").unwrap();
+        let origin_dir = TempDir::new().unwrap();
+        let origin = origin_dir.path().to_str().unwrap();
+        Command::new("git")
+            .args(["init", "--bare", origin])
+            .output()
+            .unwrap();
+        git(&["remote", "add", "origin", origin]);
+
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("notes".to_string(), Some(base.clone()), None)
+            .unwrap();
+        git(&["checkout", "-b", "notes-1"]);
+        std::fs::write(repo_path.join("a.txt"), "a").unwrap();
+        git(&["add", "a.txt"]);
+        git(&["commit", "-m", "Add a"]);
+        let entry_commit = git(&["rev-parse", "HEAD"]);
+        manager.get_stack_mut(&stack_id).unwrap().push_entry(
+            "notes-1".to_string(),
+            entry_commit.clone(),
+            "Add a".to_string(),
+        );
+        manager.save_to_disk().unwrap();
+
+        // The server squashes the entry onto the trunk
+        git(&["checkout", &base]);
+        git(&["merge", "--squash", "notes-1"]);
+        git(&["commit", "-m", "Add a (#7)"]);
+        let trunk_commit = git(&["rev-parse", "HEAD"]);
+        git(&["push", "origin", &base]);
+
+        let url = "https://bitbucket.example.com/projects/P/repos/r/pull-requests/7";
+        let repository = serde_json::json!({
+            "id": 1, "name": "r", "slug": "r", "scmId": "git", "state": "AVAILABLE",
+            "forkable": true, "public": false,
+            "project": {"id": 1, "key": "P", "name": "P", "public": false, "type": "NORMAL"}
+        });
+        let merged: crate::bitbucket::pull_request::PullRequest =
+            serde_json::from_value(serde_json::json!({
+                "id": 7, "version": 1, "title": "Add a", "state": "MERGED",
+                "open": false, "closed": true, "createdDate": 0, "updatedDate": 0,
+                "fromRef": {"id": "refs/heads/notes-1", "displayId": "notes-1",
+                    "latestCommit": entry_commit, "repository": repository},
+                "toRef": {"id": format!("refs/heads/{base}"), "displayId": base,
+                    "latestCommit": trunk_commit, "repository": repository},
+                "locked": false,
+                "author": {"user": {"name": "jdoe", "active": true}, "role": "AUTHOR",
+                    "approved": false, "status": "UNAPPROVED"},
+                "links": {"self": [{"href": url}]},
+                "properties": {"mergeCommit": {"id": trunk_commit}}
+            }))
+            .unwrap();
+
+        record_landed_trailers(&repo_path, &stack_id, "notes-1", merged.web_url(), &merged);
+        push_trailer_notes(&repo_path);
+
+        let git_repo = GitRepository::open(&repo_path).unwrap();
+        let entry_note = git_repo.get_trailers(&entry_commit).unwrap();
+        assert!(entry_note.contains(&format!("Landed-as: {trunk_commit}")));
+
+        // Another clone reads the review from the trunk's history
+        let clone_dir = TempDir::new().unwrap();
+        let clone = clone_dir.path().join("clone");
+        Command::new("git")
+            .args(["clone", origin, clone.to_str().unwrap()])
+            .output()
+            .unwrap();
+        let note = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&clone)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        note(&["fetch", "origin", "refs/notes/cascade:refs/notes/cascade"]);
+        assert_eq!(
+            note(&["notes", "--ref", "cascade", "show", &base]),
+            format!("PR: {url}")
+        );
+
+        // Notes the other clone published first are kept by the next push
+        note(&["config", "user.name", "Other"]);
+        note(&["config", "user.email", "other@example.com"]);
+        note(&[
+            "notes",
+            "--ref",
+            "cascade",
+            "add",
+            "-m",
+            "Reviewed-by: alice",
+            "HEAD^",
+        ]);
+        note(&["push", "origin", "refs/notes/cascade"]);
+        git_repo
+            .add_trailers(&trunk_commit, &[("Deployed", "yes")])
+            .unwrap();
+        push_trailer_notes(&repo_path);
+        note(&["fetch", "origin", "+refs/notes/cascade:refs/notes/cascade"]);
+        assert_eq!(
+            note(&["notes", "--ref", "cascade", "show", "HEAD^"]),
+            "Reviewed-by: alice"
+        );
+        assert!(note(&["notes", "--ref", "cascade", "show", &base]).contains("Deployed: yes"));
+    }
+
+    #[test]
+    fn test_permission_problem_names_who_to_ask() {
+        use crate::bitbucket::auth::AuthenticatedUser;
+        use crate::bitbucket::provider::{
+            BranchRestriction, ProviderIdentity, RepositoryPermission,
+        };
+
+        let identity = |permission| ProviderIdentity {
+            user: AuthenticatedUser {
+                name: "jdoe".to_string(),
+                display_name: None,
+                email: None,
+            },
+            permission: Some(permission),
+            token: None,
+        };
+        let mut config = crate::config::BitbucketConfig::default();
+        let locked = vec![(
+            "main".to_string(),
+            vec![BranchRestriction {
+                exempt_users: vec!["alice".to_string(), "bob".to_string()],
+                exempt_groups: Vec::new(),
+            }],
+        )];
+
+        let problem = permission_problem(
+            &identity(RepositoryPermission::Read),
+            &config,
+            "landing",
+            &[],
+        )
+        .unwrap();
+        assert!(problem.to_string().contains(
+            "lack write permission on PROJECT/repo, so landing would fail; ask a repository admin"
+        ));
+
+        let problem = permission_problem(
+            &identity(RepositoryPermission::Write),
+            &config,
+            "landing",
+            &locked,
+        )
+        .unwrap();
+        assert_eq!(
+            problem.to_string(),
+            "You (jdoe) lack merge permission on main; ask alice or bob"
+        );
+
+        config.admin_contact = Some("#platform-help".to_string());
+        let unexempted = vec![("main".to_string(), vec![BranchRestriction::default()])];
+        let problem = permission_problem(
+            &identity(RepositoryPermission::Admin),
+            &config,
+            "landing",
+            &unexempted,
+        )
+        .unwrap();
+        assert!(problem.to_string().ends_with("ask #platform-help"));
+
+        assert!(permission_problem(
+            &identity(RepositoryPermission::Write),
+            &config,
+            "submitting",
+            &[("main".to_string(), Vec::new())],
+        )
+        .is_none());
+    }
+
+    /// Point the repository at the mock provider so syncs don't reach for a server
+    fn use_mock_provider(repo_path: &std::path::Path) {
+        let config_path = crate::config::get_repo_config_dir(repo_path)
+            .unwrap()
+            .join("config.json");
+        let mut settings = crate::config::Settings::load_from_file(&config_path).unwrap();
+        settings.bitbucket.provider = crate::config::ProviderKind::Mock;
+        settings.save_to_file(&config_path).unwrap();
+    }
+
+    /// A repository with an `origin`, a two-entry stack on the default branch whose first
+    /// entry rewrites `README.md`, and a `develop` branch whose extra commit writes
+    /// `develop_file`; the top entry's branch is checked out
+    fn create_retarget_repo(develop_file: &str) -> (TempDir, TempDir, std::path::PathBuf, Uuid) {
+        let (temp_dir, repo_path) = create_test_repo().unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let base = git(&["branch", "--show-current"]);
+        std::fs::write(repo_path.join(".git/info/exclude"), ".cascade/\n").unwrap();
+
+        let origin_dir = TempDir::new().unwrap();
+        let origin = origin_dir.path();
+        Command::new("git")
+            .args(["init", "--bare", origin.to_str().unwrap()])
+            .output()
+            .unwrap();
+        git(&["remote", "add", "origin", origin.to_str().unwrap()]);
+
+        git(&["checkout", "-b", "develop"]);
+        std::fs::write(repo_path.join(develop_file), "develop\n").unwrap();
+        git(&["add", develop_file]);
+        git(&["commit", "-m", "Develop work"]);
+        git(&["push", "origin", &base, "develop"]);
+        git(&["checkout", &base]);
+
+        use_mock_provider(&repo_path);
+
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("feature".to_string(), Some(base.clone()), None)
+            .unwrap();
+        for (i, file) in ["README.md", "b.txt"].iter().enumerate() {
+            let branch = format!("feature-{}", i + 1);
+            git(&["checkout", "-b", &branch]);
+            std::fs::write(repo_path.join(file), "feature\n").unwrap();
+            git(&["add", file]);
+            git(&["commit", "-m", &format!("Add {file}")]);
+            let hash = git(&["rev-parse", "HEAD"]);
+            manager.get_stack_mut(&stack_id).unwrap().push_entry(
+                branch,
+                hash,
+                format!("Add {file}"),
+            );
+        }
+        manager.save_to_disk().unwrap();
+
+        (temp_dir, origin_dir, repo_path, stack_id)
+    }
+
+    #[tokio::test]
+    async fn test_retarget_onto_moves_entries_to_new_base() {
+        let (_temp_dir, _origin_dir, repo_path, stack_id) = create_retarget_repo("develop.txt");
+
+        retarget_stack_at(&repo_path, "develop".to_string())
+            .await
+            .unwrap();
+
+        let manager = StackManager::new(&repo_path).unwrap();
+        let stack = manager.get_stack(&stack_id).unwrap();
+        assert_eq!(stack.base_branch, "develop");
+
+        let git_repo = GitRepository::open(&repo_path).unwrap();
+        let develop = git_repo.get_branch_head("develop").unwrap();
+        for entry in &stack.entries {
+            let head = git_repo.get_branch_head(&entry.branch).unwrap();
+            assert!(git_repo.is_descendant_of(&head, &develop).unwrap());
+        }
+        assert!(!crate::stack::SyncState::exists(&repo_path));
+    }
+
+    #[tokio::test]
+    async fn test_sync_abort_after_conflicted_retarget_restores_base() {
+        // develop rewrites the same line as the first entry, so replaying it conflicts
+        let (_temp_dir, _origin_dir, repo_path, stack_id) = create_retarget_repo("README.md");
+        let base = StackManager::new(&repo_path)
+            .unwrap()
+            .get_stack(&stack_id)
+            .unwrap()
+            .base_branch
+            .clone();
+
+        assert!(retarget_stack_at(&repo_path, "develop".to_string())
+            .await
+            .is_err());
+        let state = crate::stack::SyncState::load(&repo_path).unwrap();
+        assert_eq!(state.previous_base.as_deref(), Some(base.as_str()));
+
+        abort_sync_at(&repo_path).await.unwrap();
+
+        let manager = StackManager::new(&repo_path).unwrap();
+        assert_eq!(manager.get_stack(&stack_id).unwrap().base_branch, base);
+        assert!(!crate::stack::SyncState::exists(&repo_path));
+    }
+
+    #[tokio::test]
+    async fn test_sync_dependent_stack_after_parent_landed_uses_remote_trunk() {
+        let (_temp_dir, repo_path) = create_test_repo().unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let commit = |file: &str, message: &str| {
+            std::fs::write(repo_path.join(file), message).unwrap();
+            git(&["add", file]);
+            git(&["commit", "-m", message]);
+        };
+        let base = git(&["branch", "--show-current"]);
+        let initial = git(&["rev-parse", "HEAD"]);
+        std::fs::write(repo_path.join(".git/info/exclude"), ".cascade/\n").unwrap();
+        let origin_dir = TempDir::new().unwrap();
+        let origin = origin_dir.path();
+        Command::new("git")
+            .args(["init", "--bare", origin.to_str().unwrap()])
+            .output()
+            .unwrap();
+        git(&["remote", "add", "origin", origin.to_str().unwrap()]);
+        git(&["push", "origin", &base]);
+        use_mock_provider(&repo_path);
+
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let parent_id = manager
+            .create_stack("parent".to_string(), Some(base.clone()), None)
+            .unwrap();
+        git(&["checkout", "-b", "parent-1"]);
+        commit("parent.txt", "Add parent");
+        let parent_entry = manager.get_stack_mut(&parent_id).unwrap().push_entry(
+            "parent-1".to_string(),
+            git(&["rev-parse", "HEAD"]),
+            "Add parent".to_string(),
+        );
+        let child_id = manager
+            .create_stack("child".to_string(), Some(base.clone()), None)
+            .unwrap();
+        manager
+            .set_stack_dependency(&child_id, Some(parent_id))
+            .unwrap();
+        git(&["checkout", "-b", "child-1"]);
+        commit("child.txt", "Add child");
+        manager.get_stack_mut(&child_id).unwrap().push_entry(
+            "child-1".to_string(),
+            git(&["rev-parse", "HEAD"]),
+            "Add child".to_string(),
+        );
+        manager.save_to_disk().unwrap();
+
+        // The parent lands and the trunk moves on, but only on the remote
+        git(&["checkout", &base]);
+        git(&["merge", "--ff-only", "parent-1"]);
+        commit("other.txt", "Other work");
+        git(&["push", "origin", &base]);
+        git(&["reset", "--hard", &initial]);
+        manager
+            .set_entry_merged(&parent_id, &parent_entry, true)
+            .unwrap();
+        git(&["checkout", "child-1"]);
+
+        sync_stack_at(&repo_path, false, false, false)
+            .await
+            .unwrap();
+
+        let manager = StackManager::new(&repo_path).unwrap();
+        assert_eq!(manager.get_stack(&child_id).unwrap().base_branch, base);
+        assert!(!manager.base_is_parent_branch(&child_id));
+        let remote_trunk = git(&["rev-parse", &format!("origin/{base}")]);
+        let on_trunk = Command::new("git")
+            .args(["merge-base", "--is-ancestor", &remote_trunk, "child-1"])
+            .current_dir(&repo_path)
+            .status()
+            .unwrap();
+        assert!(on_trunk.success());
+    }
+
+    #[test]
+    fn test_grouped_entry_lands_only_once_every_commit_is_upstream() {
+        let (_temp_dir, repo_path) = create_test_repo().unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let commit = |file: &str, message: &str| {
+            std::fs::write(repo_path.join(file), message).unwrap();
+            git(&["add", file]);
+            git(&["commit", "-m", message]);
+            git(&["rev-parse", "HEAD"])
+        };
+        let base = git(&["branch", "--show-current"]);
+        std::fs::write(repo_path.join(".git/info/exclude"), ".cascade/\n").unwrap();
+
+        // One entry grouping two commits
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("grouped".to_string(), Some(base.clone()), None)
+            .unwrap();
+        git(&["checkout", "-b", "grouped-1"]);
+        let first = commit("first.txt", "First half");
+        let second = commit("second.txt", "Second half");
+        let stack = manager.get_stack_mut(&stack_id).unwrap();
+        let entry_id = stack.push_entry(
+            "grouped-1".to_string(),
+            second.clone(),
+            "Second half".to_string(),
+        );
+        stack.set_entry_commit_count(&entry_id, 2);
+        let git_repo = GitRepository::open(&repo_path).unwrap();
+
+        // Only the tip commit was picked over to the trunk
+        git(&["checkout", &base]);
+        git(&["cherry-pick", &second]);
+        let stack = manager.get_stack(&stack_id).unwrap();
+        assert!(upstream_equivalents(&git_repo, stack, &base).is_empty());
+
+        // Once the rest follows, the whole entry is there
+        git(&["cherry-pick", &first]);
+        assert_eq!(
+            upstream_equivalents(&git_repo, stack, &base),
+            vec![entry_id]
+        );
+    }
 }
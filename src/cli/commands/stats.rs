@@ -0,0 +1,492 @@
+use crate::bitbucket::pull_request::{ActivityAction, PullRequestActivity};
+use crate::cli::output::Output;
+use crate::errors::{CascadeError, Result};
+use crate::git::find_repository_root;
+use crate::stack::{Stack, StackManager};
+use crate::utils::logging::{self, LogRecord, OPS_TARGET};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+
+/// Everything `ca stats` reports, also printed as-is with `--json`
+#[derive(Debug, Serialize)]
+struct StatsReport {
+    /// Start of the period covered (`--since`)
+    since: Option<DateTime<Utc>>,
+    /// Oldest event in `.cascade/logs`; syncs, conflicts and force-pushes before it
+    /// are not counted
+    log_start: Option<DateTime<Utc>>,
+    stacks: Vec<StackStats>,
+    pull_requests: Vec<PrStats>,
+    summary: StatsSummary,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct StackStats {
+    name: String,
+    entries: usize,
+    submitted: usize,
+    landed: usize,
+    syncs: usize,
+    conflicts: usize,
+    force_pushes: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct PrStats {
+    id: String,
+    stack: String,
+    branch: String,
+    submitted_at: Option<DateTime<Utc>>,
+    landed_at: Option<DateTime<Utc>>,
+    hours_to_land: Option<f64>,
+    /// Times Cascade force-pushed the PR branch (rebases, amends, syncs)
+    force_pushes: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct StatsSummary {
+    stacks: usize,
+    avg_entries_per_stack: Option<f64>,
+    pull_requests: usize,
+    landed: usize,
+    avg_hours_to_land: Option<f64>,
+    median_hours_to_land: Option<f64>,
+    avg_force_pushes_per_pr: Option<f64>,
+    syncs: usize,
+    conflicts: usize,
+    /// Conflicts per sync
+    conflict_rate: Option<f64>,
+}
+
+/// Operation events from the log, counted by stack name (syncs, conflicts) or branch
+/// (force-pushes)
+#[derive(Debug, Default)]
+struct OpCounts {
+    syncs: HashMap<String, usize>,
+    conflicts: HashMap<String, usize>,
+    force_pushes: HashMap<String, usize>,
+}
+
+impl OpCounts {
+    fn from_records(records: &[LogRecord]) -> Self {
+        let mut counts = Self::default();
+        for record in records.iter().filter(|r| r.target == OPS_TARGET) {
+            let field = |name: &str| record.fields.get(name).and_then(Value::as_str);
+            let (map, key) = match field("op") {
+                Some("sync") => (&mut counts.syncs, field("stack")),
+                Some("conflict") => (&mut counts.conflicts, field("stack")),
+                Some("force_push") => (&mut counts.force_pushes, field("branch")),
+                _ => continue,
+            };
+            if let Some(key) = key {
+                *map.entry(key.to_string()).or_default() += 1;
+            }
+        }
+        counts
+    }
+}
+
+/// Show cycle-time, rework and conflict metrics for the repository's stacks
+pub async fn run(since: Option<String>, json: bool) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let since = since
+        .map(|since| logging::parse_since(&since, Utc::now()))
+        .transpose()?;
+
+    let manager = StackManager::new(&repo_root)?;
+    let stacks = manager.get_all_stacks_objects()?;
+
+    let records = logging::read_records(&logging::log_dir(&repo_root)?, since)?;
+    let log_start = records.first().map(|record| record.timestamp);
+    let ops = OpCounts::from_records(&records);
+
+    let mut activities = HashMap::new();
+    for stack in stacks
+        .iter()
+        .filter(|stack| stack.entries.iter().any(|e| e.pull_request_id.is_some()))
+    {
+        match super::viz::fetch_pr_activities(&repo_root, stack).await {
+            Ok(stack_activities) => activities.extend(stack_activities),
+            Err(e) => {
+                tracing::debug!("Failed to fetch PR activity: {}", e);
+                if !json {
+                    Output::warning(
+                        "Could not fetch PR events; cycle times are missing from these stats",
+                    );
+                }
+                break;
+            }
+        }
+    }
+
+    let report = build_report(&stacks, &activities, &ops, since, log_start);
+    if json {
+        Output::data(serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+fn build_report(
+    stacks: &[Stack],
+    activities: &HashMap<String, Vec<PullRequestActivity>>,
+    ops: &OpCounts,
+    since: Option<DateTime<Utc>>,
+    log_start: Option<DateTime<Utc>>,
+) -> StatsReport {
+    let count = |map: &HashMap<String, usize>, key: &str| map.get(key).copied().unwrap_or(0);
+
+    let mut stack_stats = Vec::new();
+    let mut pull_requests = Vec::new();
+    for stack in stacks {
+        let mut stats = StackStats {
+            name: stack.name.clone(),
+            entries: stack.entries.len(),
+            syncs: count(&ops.syncs, &stack.name),
+            conflicts: count(&ops.conflicts, &stack.name),
+            ..Default::default()
+        };
+
+        for entry in &stack.entries {
+            let force_pushes = count(&ops.force_pushes, &entry.branch);
+            stats.force_pushes += force_pushes;
+            let Some(pr_id) = &entry.pull_request_id else {
+                continue;
+            };
+            stats.submitted += 1;
+            if entry.is_merged {
+                stats.landed += 1;
+            }
+
+            let pr_activities = activities.get(pr_id).map(Vec::as_slice).unwrap_or(&[]);
+            let first = |action: ActivityAction| {
+                pr_activities
+                    .iter()
+                    .find(|activity| activity.action == action)
+                    .map(PullRequestActivity::created_at)
+            };
+            let submitted_at = first(ActivityAction::Opened);
+            let landed_at = first(ActivityAction::Merged);
+            let in_period = since.is_none_or(|since| {
+                submitted_at.is_some_and(|at| at >= since)
+                    || landed_at.is_some_and(|at| at >= since)
+            });
+            if !in_period {
+                continue;
+            }
+
+            pull_requests.push(PrStats {
+                id: pr_id.clone(),
+                stack: stack.name.clone(),
+                branch: entry.branch.clone(),
+                submitted_at,
+                landed_at,
+                hours_to_land: submitted_at
+                    .zip(landed_at)
+                    .map(|(submitted, landed)| hours(landed - submitted)),
+                force_pushes,
+            });
+        }
+        stack_stats.push(stats);
+    }
+
+    let mut land_hours: Vec<f64> = pull_requests
+        .iter()
+        .filter_map(|pr| pr.hours_to_land)
+        .collect();
+    land_hours.sort_by(f64::total_cmp);
+    let syncs: usize = stack_stats.iter().map(|s| s.syncs).sum();
+    let conflicts: usize = stack_stats.iter().map(|s| s.conflicts).sum();
+
+    let summary = StatsSummary {
+        stacks: stack_stats.len(),
+        avg_entries_per_stack: average(stack_stats.iter().map(|s| s.entries as f64)),
+        pull_requests: pull_requests.len(),
+        landed: pull_requests
+            .iter()
+            .filter(|pr| pr.landed_at.is_some())
+            .count(),
+        avg_hours_to_land: average(land_hours.iter().copied()),
+        median_hours_to_land: median(&land_hours),
+        avg_force_pushes_per_pr: average(pull_requests.iter().map(|pr| pr.force_pushes as f64)),
+        syncs,
+        conflicts,
+        conflict_rate: (syncs > 0).then(|| conflicts as f64 / syncs as f64),
+    };
+
+    StatsReport {
+        since,
+        log_start,
+        stacks: stack_stats,
+        pull_requests,
+        summary,
+    }
+}
+
+fn hours(span: Duration) -> f64 {
+    (span.num_minutes() as f64 / 6.0).round() / 10.0
+}
+
+fn average(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Median of sorted values
+fn median(sorted: &[f64]) -> Option<f64> {
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+        _ => Some(sorted[mid]),
+    }
+}
+
+fn span_from_hours(hours: f64) -> String {
+    super::viz::format_span(Duration::minutes((hours * 60.0).round() as i64))
+}
+
+fn print_report(report: &StatsReport) {
+    let summary = &report.summary;
+
+    Output::section("Stacks");
+    if report.stacks.is_empty() {
+        Output::sub_item("No stacks found");
+    } else {
+        let mut rows = vec![[
+            "STACK",
+            "ENTRIES",
+            "SUBMITTED",
+            "LANDED",
+            "SYNCS",
+            "CONFLICTS",
+            "FORCE-PUSHES",
+        ]
+        .map(String::from)
+        .to_vec()];
+        for stack in &report.stacks {
+            rows.push(vec![
+                stack.name.clone(),
+                stack.entries.to_string(),
+                stack.submitted.to_string(),
+                stack.landed.to_string(),
+                stack.syncs.to_string(),
+                stack.conflicts.to_string(),
+                stack.force_pushes.to_string(),
+            ]);
+        }
+        Output::table(&rows);
+        if let Some(avg) = summary.avg_entries_per_stack {
+            Output::spacing();
+            Output::sub_item(format!(
+                "{} stacks, {avg:.1} entries per stack on average",
+                summary.stacks
+            ));
+        }
+    }
+
+    Output::section("Pull Requests");
+    if report.pull_requests.is_empty() {
+        Output::sub_item("No pull requests in this period");
+    } else {
+        let mut rows = vec![[
+            "PR",
+            "STACK",
+            "BRANCH",
+            "SUBMITTED",
+            "TIME TO LAND",
+            "FORCE-PUSHES",
+        ]
+        .map(String::from)
+        .to_vec()];
+        for pr in &report.pull_requests {
+            rows.push(vec![
+                format!("#{}", pr.id),
+                pr.stack.clone(),
+                pr.branch.clone(),
+                pr.submitted_at
+                    .map_or("-".to_string(), |at| at.format("%Y-%m-%d").to_string()),
+                pr.hours_to_land.map_or("-".to_string(), span_from_hours),
+                pr.force_pushes.to_string(),
+            ]);
+        }
+        Output::table(&rows);
+        Output::spacing();
+        Output::sub_item(format!(
+            "Landed: {} of {}",
+            summary.landed, summary.pull_requests
+        ));
+        if let (Some(avg), Some(median)) = (summary.avg_hours_to_land, summary.median_hours_to_land)
+        {
+            Output::sub_item(format!(
+                "Submit to land: {} on average, {} median",
+                span_from_hours(avg),
+                span_from_hours(median)
+            ));
+        }
+        if let Some(avg) = summary.avg_force_pushes_per_pr {
+            Output::sub_item(format!("Force-pushes per PR: {avg:.1} on average"));
+        }
+    }
+
+    Output::section("Conflicts");
+    Output::sub_item(format!("Syncs: {}", summary.syncs));
+    match summary.conflict_rate {
+        Some(rate) => Output::sub_item(format!(
+            "Conflicts: {} ({:.0}% of syncs)",
+            summary.conflicts,
+            rate * 100.0
+        )),
+        None => Output::sub_item(format!("Conflicts: {}", summary.conflicts)),
+    }
+
+    match (report.since, report.log_start) {
+        (Some(since), _) => Output::tip(format!(
+            "Covering activity since {}",
+            since.format("%Y-%m-%d %H:%M")
+        )),
+        (None, Some(start)) => Output::tip(format!(
+            "Syncs, conflicts and force-pushes are counted from the local log, which starts {}",
+            start.format("%Y-%m-%d")
+        )),
+        (None, None) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(action: &str, at: DateTime<Utc>) -> PullRequestActivity {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "createdDate": at.timestamp_millis(),
+            "user": {"name": "jane", "emailAddress": "jane@example.com", "displayName": "Jane",
+                     "id": 1, "active": true, "slug": "jane", "type": "NORMAL"},
+            "action": action,
+        }))
+        .unwrap()
+    }
+
+    fn op_record(fields: Value) -> LogRecord {
+        LogRecord::parse(
+            &serde_json::json!({
+                "timestamp": "2024-05-01T10:00:00Z",
+                "level": "DEBUG",
+                "target": OPS_TARGET,
+                "fields": fields,
+            })
+            .to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_op_counts_from_records() {
+        let mut records = vec![
+            op_record(serde_json::json!({"message": "x", "op": "sync", "stack": "auth"})),
+            op_record(serde_json::json!({"message": "x", "op": "sync", "stack": "auth"})),
+            op_record(
+                serde_json::json!({"message": "x", "op": "conflict", "stack": "auth", "branch": "a-1"}),
+            ),
+            op_record(serde_json::json!({"message": "x", "op": "force_push", "branch": "a-1"})),
+        ];
+        // Ordinary log lines are ignored, even when they carry an op field
+        let mut other =
+            op_record(serde_json::json!({"message": "x", "op": "sync", "stack": "auth"}));
+        other.target = "cascade_cli::stack::rebase".to_string();
+        records.push(other);
+
+        let ops = OpCounts::from_records(&records);
+        assert_eq!(ops.syncs["auth"], 2);
+        assert_eq!(ops.conflicts["auth"], 1);
+        assert_eq!(ops.force_pushes["a-1"], 1);
+    }
+
+    #[test]
+    fn test_build_report() {
+        let start = DateTime::parse_from_rfc3339("2024-05-01T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut stack = Stack::new("auth".to_string(), "main".to_string(), None);
+        for i in 1..=3 {
+            stack.push_entry(format!("a-{i}"), format!("{i:040}"), format!("Commit {i}"));
+        }
+        stack.entries[0].pull_request_id = Some("11".to_string());
+        stack.entries[0].is_merged = true;
+        stack.entries[1].pull_request_id = Some("12".to_string());
+        stack.entries[1].is_merged = true;
+        stack.entries[2].pull_request_id = Some("13".to_string());
+        let empty = Stack::new("empty".to_string(), "main".to_string(), None);
+
+        let activities = HashMap::from([
+            (
+                "11".to_string(),
+                vec![
+                    activity("OPENED", start),
+                    activity("APPROVED", start + Duration::hours(1)),
+                    activity("MERGED", start + Duration::hours(2)),
+                ],
+            ),
+            (
+                "12".to_string(),
+                vec![
+                    activity("OPENED", start),
+                    activity("MERGED", start + Duration::hours(10)),
+                ],
+            ),
+            ("13".to_string(), vec![activity("OPENED", start)]),
+        ]);
+        let ops = OpCounts {
+            syncs: HashMap::from([("auth".to_string(), 4)]),
+            conflicts: HashMap::from([("auth".to_string(), 1)]),
+            force_pushes: HashMap::from([("a-1".to_string(), 3), ("a-3".to_string(), 1)]),
+        };
+
+        let report = build_report(
+            &[stack.clone(), empty.clone()],
+            &activities,
+            &ops,
+            None,
+            None,
+        );
+        let auth = &report.stacks[0];
+        assert_eq!(
+            (auth.entries, auth.submitted, auth.landed, auth.force_pushes),
+            (3, 3, 2, 4)
+        );
+        assert_eq!(report.pull_requests[0].hours_to_land, Some(2.0));
+        assert_eq!(report.pull_requests[2].hours_to_land, None);
+
+        let summary = &report.summary;
+        assert_eq!(summary.avg_entries_per_stack, Some(1.5));
+        assert_eq!((summary.pull_requests, summary.landed), (3, 2));
+        assert_eq!(summary.avg_hours_to_land, Some(6.0));
+        assert_eq!(summary.median_hours_to_land, Some(6.0));
+        assert_eq!(summary.avg_force_pushes_per_pr, Some(4.0 / 3.0));
+        assert_eq!(summary.conflict_rate, Some(0.25));
+
+        // Only PRs with activity in the period count
+        let later = build_report(
+            &[stack, empty],
+            &activities,
+            &ops,
+            Some(start + Duration::hours(5)),
+            None,
+        );
+        let ids: Vec<_> = later
+            .pull_requests
+            .iter()
+            .map(|pr| pr.id.as_str())
+            .collect();
+        assert_eq!(ids, ["12"]);
+    }
+}
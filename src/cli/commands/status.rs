@@ -1,11 +1,57 @@
 use crate::cli::output::Output;
-use crate::config::{get_repo_config_dir, is_repo_initialized, Settings};
+use crate::config::{get_repo_config_dir, is_repo_initialized, BitbucketConfig, Settings};
 use crate::errors::{CascadeError, Result};
 use crate::git::{get_current_repository, GitRepository};
+use crate::stack::{PrSummary, PrSummaryCache, Stack, StackManager};
+use chrono::{Duration, Utc};
+use serde::Serialize;
 use std::env;
+use std::path::Path;
+
+/// PR counts older than this are flagged as stale in the next actions
+const PR_SUMMARY_MAX_AGE_HOURS: i64 = 24;
+
+/// Everything `ca repo` reports, also printed as-is with `--json`
+#[derive(Debug, Serialize)]
+struct RepoDashboard {
+    current_branch: Option<String>,
+    uncommitted_changes: bool,
+    bitbucket_configured: bool,
+    stacks: Vec<StackReadiness>,
+    next_actions: Vec<String>,
+}
+
+/// How close one stack is to landing
+#[derive(Debug, Serialize)]
+struct StackReadiness {
+    name: String,
+    active: bool,
+    base_branch: String,
+    entries: usize,
+    /// Entries that have no pull request yet
+    unsubmitted: usize,
+    /// Unmerged entries that have to be rebased onto the base branch or the entry below
+    needs_sync: usize,
+    /// Counts from the last Bitbucket check (`ca repo --refresh`, `ca stack --verbose`)
+    pull_requests: Option<PrSummary>,
+}
 
 /// Show repository overview and all stacks status
-pub async fn run() -> Result<()> {
+pub async fn run(json: bool, refresh: bool) -> Result<()> {
+    if json {
+        let git_repo = get_current_repository()?;
+        let repo_root = git_repo.path().to_path_buf();
+        if !is_repo_initialized(&repo_root) {
+            return Err(CascadeError::config(
+                "Repository is not initialized for Cascade. Run 'ca init' first",
+            ));
+        }
+        let settings = load_settings(&repo_root)?;
+        let dashboard = build_dashboard(&git_repo, &settings, refresh, true).await?;
+        Output::data(serde_json::to_string_pretty(&dashboard)?);
+        return Ok(());
+    }
+
     Output::section("Repository Overview");
 
     // Get current directory and repository
@@ -24,7 +70,20 @@ pub async fn run() -> Result<()> {
     show_git_status(&git_repo)?;
 
     // Show Cascade initialization status
-    show_cascade_status(&git_repo)?;
+    let Some(settings) = show_cascade_status(&git_repo)? else {
+        return Ok(());
+    };
+
+    match build_dashboard(&git_repo, &settings, refresh, false).await {
+        Ok(dashboard) => {
+            show_stack_readiness(&dashboard);
+            show_next_actions(&dashboard);
+        }
+        Err(e) => {
+            Output::section("Stacks");
+            Output::sub_item(format!("Unable to load stack information: {e}"));
+        }
+    }
 
     Ok(())
 }
@@ -64,13 +123,16 @@ fn show_git_status(git_repo: &GitRepository) -> Result<()> {
         ));
         if repo_info.untracked_files.len() <= 5 {
             for file in &repo_info.untracked_files {
-                println!("    - {file}");
+                Output::line(format!("    - {file}"));
             }
         } else {
             for file in repo_info.untracked_files.iter().take(3) {
-                println!("    - {file}");
+                Output::line(format!("    - {file}"));
             }
-            println!("    ... and {} more", repo_info.untracked_files.len() - 3);
+            Output::line(format!(
+                "    ... and {} more",
+                repo_info.untracked_files.len() - 3
+            ));
         }
     } else {
         Output::sub_item("Untracked files: None");
@@ -107,7 +169,7 @@ fn show_git_status(git_repo: &GitRepository) -> Result<()> {
     Ok(())
 }
 
-fn show_cascade_status(git_repo: &GitRepository) -> Result<()> {
+fn show_cascade_status(git_repo: &GitRepository) -> Result<Option<Settings>> {
     Output::section("Cascade Status");
 
     let repo_path = git_repo.path();
@@ -115,15 +177,13 @@ fn show_cascade_status(git_repo: &GitRepository) -> Result<()> {
     if !is_repo_initialized(repo_path) {
         Output::error("Status: Not initialized");
         Output::sub_item("Run 'ca init' to initialize this repository for Cascade");
-        return Ok(());
+        return Ok(None);
     }
 
     Output::success("Status: Initialized");
 
     // Load and show configuration
-    let config_dir = get_repo_config_dir(repo_path)?;
-    let config_file = config_dir.join("config.json");
-    let settings = Settings::load_from_file(&config_file)?;
+    let settings = load_settings(repo_path)?;
 
     // Check Bitbucket configuration
     Output::section("Bitbucket Configuration");
@@ -173,67 +233,291 @@ fn show_cascade_status(git_repo: &GitRepository) -> Result<()> {
         Output::sub_item("Run 'ca doctor' for configuration recommendations");
     }
 
-    // Show stack information
-    Output::section("Stacks");
+    Ok(Some(settings))
+}
+
+fn load_settings(repo_root: &Path) -> Result<Settings> {
+    let config_file = get_repo_config_dir(repo_root)?.join("config.json");
+    Settings::load_from_file(&config_file)
+}
 
-    match crate::stack::StackManager::new(repo_path) {
-        Ok(manager) => {
-            let stacks = manager.get_all_stacks();
-            let active_stack = manager.get_active_stack();
+fn is_bitbucket_configured(config: &BitbucketConfig) -> bool {
+    !config.url.is_empty()
+        && !config.project.is_empty()
+        && !config.repo.is_empty()
+        && config.token.as_ref().is_some_and(|token| !token.is_empty())
+}
+
+async fn build_dashboard(
+    git_repo: &GitRepository,
+    settings: &Settings,
+    refresh: bool,
+    quiet: bool,
+) -> Result<RepoDashboard> {
+    let repo_root = git_repo.path();
+    if refresh {
+        refresh_pr_summaries(repo_root, settings, quiet).await;
+    }
+
+    let manager = StackManager::new(repo_root)?;
+    let pr_summaries = PrSummaryCache::load(repo_root);
+    let mut stacks: Vec<StackReadiness> = manager
+        .get_all_stacks_objects()?
+        .iter()
+        .map(|stack| StackReadiness {
+            name: stack.name.clone(),
+            active: stack.is_active,
+            base_branch: stack.base_branch.clone(),
+            entries: stack.entries.len(),
+            unsubmitted: stack
+                .entries
+                .iter()
+                .filter(|entry| !entry.is_submitted && !entry.is_merged)
+                .count(),
+            needs_sync: entries_needing_sync(git_repo, stack),
+            pull_requests: pr_summaries.get(&stack.id).cloned(),
+        })
+        .collect();
+    // Active stack first, the rest by name
+    stacks.sort_by_key(|stack| !stack.active);
+
+    let mut dashboard = RepoDashboard {
+        current_branch: git_repo.get_current_branch().ok(),
+        uncommitted_changes: git_repo.is_dirty().unwrap_or(false),
+        bitbucket_configured: is_bitbucket_configured(&settings.bitbucket),
+        stacks,
+        next_actions: Vec::new(),
+    };
+    dashboard.next_actions = suggested_actions(&dashboard);
+    Ok(dashboard)
+}
 
-            if stacks.is_empty() {
-                Output::sub_item("No stacks created yet");
-                Output::sub_item(
-                    "Run 'ca stacks create \"Stack Name\"' to create your first stack",
-                );
+/// Fetch PR status for every stack with submitted entries, which records fresh counts
+/// in the [`PrSummaryCache`]. Failures only leave the older counts in place.
+async fn refresh_pr_summaries(repo_root: &Path, settings: &Settings, quiet: bool) {
+    let stacks = match StackManager::new(repo_root).and_then(|m| m.get_all_stacks_objects()) {
+        Ok(stacks) => stacks,
+        Err(e) => {
+            tracing::debug!("Could not load stacks for refresh: {}", e);
+            return;
+        }
+    };
+    let submitted: Vec<&Stack> = stacks
+        .iter()
+        .filter(|stack| stack.entries.iter().any(|e| e.pull_request_id.is_some()))
+        .collect();
+    if submitted.is_empty() {
+        return;
+    }
+
+    let cascade_config = crate::config::CascadeConfig {
+        bitbucket: Some(settings.bitbucket.clone()),
+        git: settings.git.clone(),
+        auth: crate::config::AuthConfig::default(),
+        cascade: settings.cascade.clone(),
+    };
+    let integration = StackManager::new(repo_root)
+        .and_then(|manager| crate::bitbucket::BitbucketIntegration::new(manager, cascade_config));
+    let mut integration = match integration {
+        Ok(integration) => integration,
+        Err(e) => {
+            if !quiet {
+                Output::warning(format!("Could not refresh pull request status: {e}"));
+            }
+            return;
+        }
+    };
+
+    let spinner = (!quiet).then(|| {
+        crate::utils::spinner::Spinner::new("Fetching pull request status...".to_string())
+    });
+    let mut failures = Vec::new();
+    for stack in submitted {
+        if let Err(e) = integration.check_enhanced_stack_status(&stack.id).await {
+            failures.push(format!("{}: {e}", stack.name));
+        }
+    }
+    if let Some(spinner) = spinner {
+        spinner.stop();
+    }
+    for failure in failures {
+        if quiet {
+            tracing::warn!("Could not refresh pull request status of {}", failure);
+        } else {
+            Output::warning(format!(
+                "Could not refresh pull request status of {failure}"
+            ));
+        }
+    }
+}
+
+/// Unmerged entries that no longer sit on top of their parent (the base branch for
+/// the bottom one); every entry above the first such one has to be rebased as well
+fn entries_needing_sync(git_repo: &GitRepository, stack: &Stack) -> usize {
+    let unmerged: Vec<_> = stack.entries.iter().filter(|e| !e.is_merged).collect();
+    let Ok(mut parent) = git_repo.get_branch_head(&stack.base_branch) else {
+        return 0;
+    };
+
+    for (index, entry) in unmerged.iter().enumerate() {
+        let on_top = entry.commit_hash == parent
+            || git_repo
+                .is_descendant_of(&entry.commit_hash, &parent)
+                .unwrap_or(true);
+        if !on_top {
+            return unmerged.len() - index;
+        }
+        parent = entry.commit_hash.clone();
+    }
+    0
+}
+
+fn plural(count: usize, one: &str, many: &str) -> String {
+    format!("{count} {}", if count == 1 { one } else { many })
+}
+
+/// Commands worth running next, most urgent first
+fn suggested_actions(dashboard: &RepoDashboard) -> Vec<String> {
+    let mut actions = Vec::new();
+    if !dashboard.bitbucket_configured {
+        actions.push("Finish the Bitbucket configuration with 'ca setup'".to_string());
+    }
+    if dashboard.uncommitted_changes {
+        actions.push("Commit or stash your uncommitted changes".to_string());
+    }
+    if dashboard.stacks.is_empty() {
+        actions.push("Create your first stack with 'ca stacks create <name>'".to_string());
+        return actions;
+    }
+
+    if !dashboard.stacks.iter().any(|stack| stack.active) {
+        actions.push("Activate a stack with 'ca switch <name>'".to_string());
+    }
+
+    let mut stale_prs = false;
+    for stack in &dashboard.stacks {
+        let run = |command: &str| {
+            if stack.active {
+                format!("'{command}'")
             } else {
-                Output::sub_item(format!("Total stacks: {}", stacks.len()));
-
-                // Show each stack with detailed status
-                for stack in &stacks {
-                    let is_active = active_stack
-                        .as_ref()
-                        .map(|a| a.name == stack.name)
-                        .unwrap_or(false);
-                    let active_marker = if is_active { "◉" } else { "◯" };
-
-                    let submitted = stack.entries.iter().filter(|e| e.is_submitted).count();
-
-                    let status_info = if submitted > 0 {
-                        format!("{}/{} submitted", submitted, stack.entries.len())
-                    } else if !stack.entries.is_empty() {
-                        format!("{} entries, none submitted", stack.entries.len())
-                    } else {
-                        "empty".to_string()
-                    };
-
-                    Output::sub_item(format!(
-                        "{} {} - {}",
-                        active_marker, stack.name, status_info
-                    ));
-
-                    // Show additional details for active stack
-                    if is_active && !stack.entries.is_empty() {
-                        let first_branch = stack
-                            .entries
-                            .first()
-                            .map(|e| e.branch.as_str())
-                            .unwrap_or("unknown");
-                        println!("    Base: {} → {}", stack.base_branch, first_branch);
-                    }
-                }
+                format!("'ca switch {}' then '{command}'", stack.name)
+            }
+        };
 
-                if active_stack.is_none() && !stacks.is_empty() {
-                    Output::tip("No active stack. Use 'ca stacks switch <name>' to activate one");
-                }
+        if let Some(prs) = &stack.pull_requests {
+            if prs.failing_builds > 0 {
+                actions.push(format!(
+                    "Fix {} in '{}' (details: {})",
+                    plural(prs.failing_builds, "failing build", "failing builds"),
+                    stack.name,
+                    run("ca stack --verbose")
+                ));
+            }
+            if prs.ready_to_land > 0 {
+                actions.push(format!(
+                    "Land {} in '{}' with {}",
+                    plural(
+                        prs.ready_to_land,
+                        "ready pull request",
+                        "ready pull requests"
+                    ),
+                    stack.name,
+                    run("ca land")
+                ));
             }
+            stale_prs |= Utc::now() - prs.checked_at > Duration::hours(PR_SUMMARY_MAX_AGE_HOURS);
+        } else {
+            stale_prs |= stack.entries > stack.unsubmitted;
         }
-        Err(_) => {
-            Output::sub_item("Unable to load stack information");
+
+        if stack.needs_sync > 0 {
+            actions.push(format!(
+                "Sync '{}' ({} behind {}) with {}",
+                stack.name,
+                plural(stack.needs_sync, "entry", "entries"),
+                stack.base_branch,
+                run("ca sync")
+            ));
+        }
+        if stack.unsubmitted > 0 {
+            actions.push(format!(
+                "Submit {} in '{}' with {}",
+                plural(stack.unsubmitted, "new entry", "new entries"),
+                stack.name,
+                run("ca submit")
+            ));
         }
     }
 
-    Ok(())
+    if stale_prs {
+        actions.push("Run 'ca repo --refresh' for current pull request status".to_string());
+    }
+    actions
+}
+
+fn show_stack_readiness(dashboard: &RepoDashboard) {
+    Output::section("Stacks");
+    if dashboard.stacks.is_empty() {
+        Output::sub_item("No stacks created yet");
+        return;
+    }
+    Output::sub_item(format!("Total stacks: {}", dashboard.stacks.len()));
+
+    for stack in &dashboard.stacks {
+        let active_marker = if stack.active { "◉" } else { "◯" };
+        let mut details = vec![plural(stack.entries, "entry", "entries")];
+        if stack.unsubmitted > 0 {
+            details.push(format!("{} unsubmitted", stack.unsubmitted));
+        }
+        if let Some(prs) = &stack.pull_requests {
+            let mut pr_details = plural(prs.open, "open PR", "open PRs");
+            let mut extra = Vec::new();
+            if prs.ready_to_land > 0 {
+                extra.push(format!("{} ready", prs.ready_to_land));
+            }
+            if prs.failing_builds > 0 {
+                extra.push(plural(
+                    prs.failing_builds,
+                    "failing build",
+                    "failing builds",
+                ));
+            }
+            if !extra.is_empty() {
+                pr_details.push_str(&format!(" ({})", extra.join(", ")));
+            }
+            details.push(pr_details);
+        }
+        if stack.needs_sync > 0 {
+            details.push(format!(
+                "{} to sync",
+                plural(stack.needs_sync, "entry", "entries")
+            ));
+        }
+
+        Output::sub_item(format!(
+            "{} {} - {}",
+            active_marker,
+            stack.name,
+            details.join(", ")
+        ));
+        if let Some(prs) = &stack.pull_requests {
+            Output::line(format!(
+                "    PRs checked: {}",
+                super::stack::relative_age(prs.checked_at, Utc::now())
+            ));
+        }
+    }
+}
+
+fn show_next_actions(dashboard: &RepoDashboard) {
+    Output::section("Next Actions");
+    if dashboard.next_actions.is_empty() {
+        Output::success("Nothing to do - every stack is up to date");
+        return;
+    }
+    for action in &dashboard.next_actions {
+        Output::bullet(action);
+    }
 }
 
 #[cfg(test)]
@@ -280,7 +564,7 @@ mod tests {
         let original_dir = env::current_dir().map_err(|_| "Failed to get current dir");
         match env::set_current_dir(&repo_path) {
             Ok(_) => {
-                let result = run().await;
+                let result = run(false, false).await;
 
                 // Restore original directory (best effort)
                 if let Ok(orig) = original_dir {
@@ -291,7 +575,7 @@ mod tests {
             }
             Err(_) => {
                 // Skip test if we can't change directories (CI environment issue)
-                println!("Skipping test due to directory access restrictions");
+                eprintln!("Skipping test due to directory access restrictions");
             }
         }
     }
@@ -319,7 +603,7 @@ mod tests {
         let original_dir = env::current_dir().map_err(|_| "Failed to get current dir");
         match env::set_current_dir(&repo_path) {
             Ok(_) => {
-                let result = run().await;
+                let result = run(false, false).await;
 
                 // Restore original directory (best effort)
                 if let Ok(orig) = original_dir {
@@ -330,8 +614,66 @@ mod tests {
             }
             Err(_) => {
                 // Skip test if we can't change directories (CI environment issue)
-                println!("Skipping test due to directory access restrictions");
+                eprintln!("Skipping test due to directory access restrictions");
             }
         }
     }
+
+    fn readiness(name: &str, active: bool, prs: Option<(usize, usize, usize)>) -> StackReadiness {
+        StackReadiness {
+            name: name.to_string(),
+            active,
+            base_branch: "main".to_string(),
+            entries: 3,
+            unsubmitted: 1,
+            needs_sync: 0,
+            pull_requests: prs.map(|(open, ready_to_land, failing_builds)| PrSummary {
+                open,
+                ready_to_land,
+                failing_builds,
+                checked_at: Utc::now(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_suggested_actions() {
+        let mut other = readiness("other", false, None);
+        other.needs_sync = 2;
+        other.unsubmitted = 0;
+        let dashboard = RepoDashboard {
+            current_branch: Some("main".to_string()),
+            uncommitted_changes: true,
+            bitbucket_configured: true,
+            stacks: vec![readiness("auth", true, Some((2, 1, 1))), other],
+            next_actions: Vec::new(),
+        };
+
+        assert_eq!(
+            suggested_actions(&dashboard),
+            [
+                "Commit or stash your uncommitted changes",
+                "Fix 1 failing build in 'auth' (details: 'ca stack --verbose')",
+                "Land 1 ready pull request in 'auth' with 'ca land'",
+                "Submit 1 new entry in 'auth' with 'ca submit'",
+                "Sync 'other' (2 entries behind main) with 'ca switch other' then 'ca sync'",
+                "Run 'ca repo --refresh' for current pull request status",
+            ]
+        );
+
+        let empty = RepoDashboard {
+            current_branch: None,
+            uncommitted_changes: false,
+            bitbucket_configured: false,
+            stacks: Vec::new(),
+            next_actions: Vec::new(),
+        };
+        assert_eq!(
+            suggested_actions(&empty),
+            [
+                "Finish the Bitbucket configuration with 'ca setup'",
+                "Create your first stack with 'ca stacks create <name>'",
+            ]
+        );
+    }
 }
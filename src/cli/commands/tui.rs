@@ -1,3 +1,4 @@
+use crate::cli::output::Output;
 use crate::errors::{CascadeError, Result};
 use crate::git::find_repository_root;
 use crate::stack::{StackManager, StackStatus};
@@ -641,6 +642,12 @@ impl TuiApp {
 
 /// Run the TUI application
 pub async fn run() -> Result<()> {
+    if Output::is_plain() {
+        return Err(CascadeError::validation(
+            "The interactive TUI is not available with plain output",
+        )
+        .with_hint("Use 'ca stacks list --verbose' or 'ca viz stack' instead"));
+    }
     let mut app = TuiApp::new()?;
     app.run()
 }
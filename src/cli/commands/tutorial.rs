@@ -0,0 +1,364 @@
+use crate::cli::output::Output;
+use crate::cli::prompt::{prompter, Prompter};
+use crate::errors::{CascadeError, Result};
+use crate::stack::StackManager;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const STACK_NAME: &str = "user-profile";
+
+type Step = fn(&Tutorial) -> Result<()>;
+
+/// Sandbox repositories the tutorial works in
+struct Tutorial {
+    /// Directory holding everything the tutorial creates
+    root: PathBuf,
+    /// Your clone, where the stack is built
+    work: PathBuf,
+    /// A second clone standing in for a teammate pushing to main
+    teammate: PathBuf,
+    /// Path of the running `ca` binary, so the tutorial runs the real commands
+    ca: PathBuf,
+    /// Pause for the user between steps
+    interactive: bool,
+}
+
+/// Walk through create, push, submit, sync and land in a throwaway repository.
+///
+/// Everything runs against a local bare repository with the mock provider standing in
+/// for Bitbucket, so nothing leaves the machine and no credentials are needed.
+pub async fn run(dir: Option<PathBuf>, yes: bool) -> Result<()> {
+    let root = match dir {
+        Some(dir) => dir,
+        None => std::env::temp_dir().join(format!("cascade-tutorial-{}", std::process::id())),
+    };
+    if root.exists() && fs::read_dir(&root)?.next().is_some() {
+        return Err(CascadeError::config(format!(
+            "Tutorial directory {} is not empty; pass --dir with a new or empty directory",
+            root.display()
+        )));
+    }
+
+    let tutorial = Tutorial {
+        work: root.join("my-app"),
+        teammate: root.join("teammate"),
+        root,
+        ca: std::env::current_exe()
+            .map_err(|e| CascadeError::config(format!("Could not locate the ca binary: {e}")))?,
+        interactive: !yes && console::user_attended(),
+    };
+
+    Output::section("🎓 Cascade tutorial");
+    Output::info(
+        "You'll build a small stack of dependent changes and take it all the way to landing.",
+    );
+    Output::sub_item("Every command runs for real in a sandbox; nothing touches your repositories");
+    Output::sub_item("Bitbucket is simulated, so no server or credentials are needed");
+    Output::sub_item(format!("Sandbox: {}", tutorial.root.display()));
+
+    let steps: [(&str, Step); 6] = [
+        ("Set up a sandbox repository", Tutorial::setup),
+        ("Create a stack", Tutorial::create),
+        ("Commit and push entries", Tutorial::push),
+        ("Submit pull requests", Tutorial::submit),
+        ("Sync with a moving main", Tutorial::sync),
+        ("Land the bottom entry", Tutorial::land),
+    ];
+
+    for (number, (title, step)) in steps.iter().enumerate() {
+        Output::section(format!("Step {}/{}: {}", number + 1, steps.len(), title));
+        step(&tutorial)?;
+        if number + 1 < steps.len() && !tutorial.pause()? {
+            Output::info("Tutorial stopped; the sandbox is left as it is for you to explore");
+            Output::sub_item(format!("cd {}", tutorial.work.display()));
+            return Ok(());
+        }
+    }
+
+    tutorial.finish();
+    Ok(())
+}
+
+impl Tutorial {
+    fn setup(&self) -> Result<()> {
+        explain(&[
+            "A stack is a series of small commits, each reviewed as its own pull request.",
+            "Every entry gets its own branch, based on the entry below it.",
+        ]);
+
+        fs::create_dir_all(&self.root)?;
+        let origin = self.root.join("origin.git");
+        git(
+            &self.root,
+            &[
+                "init",
+                "--quiet",
+                "--bare",
+                "--initial-branch=main",
+                path_str(&origin)?,
+            ],
+        )?;
+        git(
+            &self.root,
+            &[
+                "clone",
+                "--quiet",
+                path_str(&origin)?,
+                path_str(&self.work)?,
+            ],
+        )?;
+        configure_identity(&self.work)?;
+
+        fs::write(self.work.join("README.md"), "# My App\n")?;
+        fs::write(self.work.join("profile.txt"), "name\n")?;
+        git(&self.work, &["add", "."])?;
+        git(&self.work, &["commit", "--quiet", "-m", "Initial commit"])?;
+        git(&self.work, &["push", "--quiet", "origin", "main"])?;
+
+        crate::config::initialize_repo(&self.work, None)?;
+        self.use_mock_provider()?;
+
+        checkpoint(
+            "Repository 'my-app' cloned from a local stand-in for Bitbucket and initialized for Cascade",
+            crate::config::is_repo_initialized(&self.work),
+        )
+    }
+
+    fn create(&self) -> Result<()> {
+        explain(&[
+            "'ca feature' creates a stack plus a working branch to commit on.",
+            "The stack remembers its base branch (main) so it can be rebased later.",
+        ]);
+
+        self.ca(&["feature", STACK_NAME])?;
+
+        let manager = StackManager::new(&self.work)?;
+        checkpoint(
+            &format!("Stack '{STACK_NAME}' exists and is active"),
+            manager
+                .get_active_stack()
+                .is_some_and(|stack| stack.name == STACK_NAME),
+        )
+    }
+
+    fn push(&self) -> Result<()> {
+        explain(&[
+            "Commit as usual, one logical change per commit.",
+            "'ca push' turns each new commit into a stack entry with its own branch.",
+        ]);
+
+        self.commit("profile.txt", "name\nemail\n", "Add email to profile")?;
+        self.commit(
+            "profile.txt",
+            "name\nemail\navatar\n",
+            "Add avatar to profile",
+        )?;
+        self.ca(&["push", "--yes"])?;
+        self.ca(&["viz", "stack"])?;
+
+        checkpoint("The stack has two entries", self.entry_count()? == 2)
+    }
+
+    fn submit(&self) -> Result<()> {
+        explain(&[
+            "'ca submit' opens one pull request per entry.",
+            "Each PR targets the branch of the entry below it, so reviewers only see that change.",
+            "(The sandbox uses the mock provider, which keeps its PRs in the repository's .git.)",
+        ]);
+
+        self.ca(&["submit"])?;
+
+        let manager = StackManager::new(&self.work)?;
+        checkpoint(
+            "Every entry has a pull request",
+            manager
+                .get_stack_by_name(STACK_NAME)
+                .is_some_and(|stack| stack.entries.iter().all(|entry| entry.is_submitted)),
+        )
+    }
+
+    fn sync(&self) -> Result<()> {
+        explain(&[
+            "While your PRs are in review, a teammate pushes to main.",
+            "'ca sync' pulls main and rebases every entry onto it, force-pushing the branches",
+            "so the open PRs stay up to date.",
+        ]);
+
+        let origin = self.root.join("origin.git");
+        git(
+            &self.root,
+            &[
+                "clone",
+                "--quiet",
+                path_str(&origin)?,
+                path_str(&self.teammate)?,
+            ],
+        )?;
+        configure_identity(&self.teammate)?;
+        fs::write(
+            self.teammate.join("README.md"),
+            "# My App\n\nNow with docs.\n",
+        )?;
+        git(
+            &self.teammate,
+            &["commit", "--quiet", "-am", "Document the app"],
+        )?;
+        git(&self.teammate, &["push", "--quiet", "origin", "main"])?;
+        Output::info("Teammate pushed 'Document the app' to main");
+
+        self.ca(&["sync"])?;
+
+        let manager = StackManager::new(&self.work)?;
+        let git_repo = crate::git::GitRepository::open(&self.work)?;
+        let main_head = git_repo.get_branch_head("main")?;
+        let on_main = manager
+            .get_stack_by_name(STACK_NAME)
+            .and_then(|stack| stack.entries.first())
+            .and_then(|entry| git_repo.get_commit(&entry.commit_hash).ok())
+            .and_then(|commit| commit.parent_id(0).ok())
+            .is_some_and(|parent| parent.to_string() == main_head);
+        checkpoint(
+            "The bottom entry now sits on top of the teammate's commit",
+            on_main,
+        )
+    }
+
+    fn land(&self) -> Result<()> {
+        explain(&[
+            "Once a PR is approved, 'ca land' merges it and retargets the next PR to main.",
+            "Stacks land bottom-up: entry 1 first, then entry 2 once it's approved.",
+            "(The mock provider reports every PR as approved with a passing build.)",
+        ]);
+
+        self.ca(&["land", "1"])?;
+
+        let manager = StackManager::new(&self.work)?;
+        checkpoint(
+            "Entry 1 is merged and entry 2 is next in line",
+            manager.get_stack_by_name(STACK_NAME).is_some_and(|stack| {
+                stack.entries.first().is_some_and(|entry| entry.is_merged)
+                    && stack.entries.get(1).is_some_and(|entry| !entry.is_merged)
+            }),
+        )
+    }
+
+    fn finish(&self) {
+        Output::section("🎉 Tutorial complete");
+        Output::info("The commands you used:");
+        Output::command_example("ca feature <name>       # start a stack");
+        Output::command_example("ca push                 # add new commits as entries");
+        Output::command_example("ca submit               # open a PR per entry");
+        Output::command_example("ca sync                 # rebase onto the latest base branch");
+        Output::command_example("ca land                 # merge approved PRs bottom-up");
+        Output::spacing();
+        Output::sub_item(format!("Explore the sandbox: cd {}", self.work.display()));
+        Output::sub_item(format!(
+            "Remove it when done: rm -rf {}",
+            self.root.display()
+        ));
+        Output::tip("Run 'ca setup' in your own repository to connect it to Bitbucket");
+    }
+
+    /// Point the sandbox at the mock provider instead of a Bitbucket server
+    fn use_mock_provider(&self) -> Result<()> {
+        let config_path = crate::config::get_repo_config_dir(&self.work)?.join("config.json");
+        let mut settings = crate::config::Settings::load_from_file(&config_path)?;
+        settings.bitbucket.provider = crate::config::ProviderKind::Mock;
+        settings.bitbucket.project = "DEMO".to_string();
+        settings.bitbucket.repo = "my-app".to_string();
+        settings.save_to_file(&config_path)
+    }
+
+    /// Run a real `ca` command in the sandbox, echoing it first
+    fn ca(&self, args: &[&str]) -> Result<()> {
+        Output::spacing();
+        Output::command_example(format!("$ ca {}", args.join(" ")));
+        let status = Command::new(&self.ca)
+            .args(args)
+            .current_dir(&self.work)
+            .status()?;
+        if !status.success() {
+            return Err(CascadeError::config(format!(
+                "'ca {}' failed in the tutorial sandbox ({})",
+                args.join(" "),
+                self.work.display()
+            )));
+        }
+        Ok(())
+    }
+
+    fn commit(&self, file: &str, content: &str, message: &str) -> Result<()> {
+        fs::write(self.work.join(file), content)?;
+        git(&self.work, &["commit", "--quiet", "-am", message])?;
+        Output::command_example(format!("$ git commit -am \"{message}\""));
+        Ok(())
+    }
+
+    fn entry_count(&self) -> Result<usize> {
+        Ok(StackManager::new(&self.work)?
+            .get_stack_by_name(STACK_NAME)
+            .map_or(0, |stack| stack.entries.len()))
+    }
+
+    /// Wait for the user before the next step; `false` stops the tutorial
+    fn pause(&self) -> Result<bool> {
+        if !self.interactive {
+            return Ok(true);
+        }
+        Output::spacing();
+        prompter().confirm("Continue to the next step?", true)
+    }
+}
+
+fn explain(lines: &[&str]) {
+    for line in lines {
+        Output::info(line);
+    }
+}
+
+fn checkpoint(description: &str, passed: bool) -> Result<()> {
+    Output::spacing();
+    if passed {
+        Output::success(format!("Checkpoint: {description}"));
+        Ok(())
+    } else {
+        Err(CascadeError::validation(format!(
+            "Tutorial checkpoint failed: {description}"
+        )))
+    }
+}
+
+/// Use the global git identity when there is one so sandbox commits look familiar
+fn configure_identity(repo: &Path) -> Result<()> {
+    let global = |key: &str| {
+        Command::new("git")
+            .args(["config", "--global", key])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let name = global("user.name").unwrap_or_else(|| "Cascade Tutorial".to_string());
+    let email = global("user.email").unwrap_or_else(|| "tutorial@example.com".to_string());
+    git(repo, &["config", "user.name", &name])?;
+    git(repo, &["config", "user.email", &email])
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let output = Command::new("git").args(args).current_dir(dir).output()?;
+    if !output.status.success() {
+        return Err(CascadeError::config(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+fn path_str(path: &Path) -> Result<&str> {
+    path.to_str()
+        .ok_or_else(|| CascadeError::config(format!("Non UTF-8 path: {}", path.display())))
+}
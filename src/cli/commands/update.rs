@@ -0,0 +1,382 @@
+use crate::cli::output::Output;
+use crate::cli::prompt::{prompter, Prompter};
+use crate::config::{get_config_dir, get_repo_config_dir, Settings, UpdateCheck};
+use crate::errors::{CascadeError, Result};
+use crate::git::find_repository_root;
+use crate::utils::release::{self, InstallChannel, Release, CHECKSUMS_ASSET};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Release lookups made by `ca update` itself
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(15);
+/// Release lookups made in the background of other commands, which must not hold them up
+const PASSIVE_TIMEOUT: Duration = Duration::from_secs(2);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[cfg(windows)]
+const BINARY_NAME: &str = "ca.exe";
+#[cfg(not(windows))]
+const BINARY_NAME: &str = "ca";
+
+/// When the passive check last ran, in `~/.cascade/update-check.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CheckState {
+    last_checked: Option<DateTime<Utc>>,
+}
+
+/// Check for a newer release and install it, or say how to when a package manager owns
+/// the binary
+pub async fn run(check: bool, yes: bool) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    Output::progress("Checking for updates...");
+    let release = match release::latest_release(LOOKUP_TIMEOUT).await {
+        Ok(release) => {
+            Output::success_inline();
+            release
+        }
+        Err(e) => {
+            Output::error_inline("");
+            return Err(e);
+        }
+    };
+    record_check();
+
+    if !release.is_newer_than(current) {
+        Output::success(format!("Cascade {current} is up to date"));
+        return Ok(());
+    }
+    Output::info(format!(
+        "Cascade {} is available (you have {current})",
+        release.version()
+    ));
+    Output::sub_item(&release.html_url);
+    if check {
+        Output::tip("Run 'ca update' to install it");
+        return Ok(());
+    }
+
+    let channel = InstallChannel::detect();
+    if let Some(command) = channel.upgrade_command() {
+        Output::info(format!(
+            "This copy was installed with {}; update it there:",
+            channel.as_str()
+        ));
+        Output::command_example(command);
+        return Ok(());
+    }
+
+    if !yes && std::io::stdin().is_terminal() {
+        let confirmed =
+            prompter().confirm(&format!("Install Cascade {} now?", release.version()), true)?;
+        if !confirmed {
+            Output::info("Update cancelled");
+            return Ok(());
+        }
+    }
+
+    install(&release).await?;
+    Output::success(format!("Updated Cascade to {}", release.version()));
+    Ok(())
+}
+
+/// Download, verify and swap in the release binary for this platform
+async fn install(release: &Release) -> Result<()> {
+    let asset_name = release::platform_asset().ok_or_else(|| {
+        CascadeError::config(format!(
+            "There's no prebuilt binary for {}-{}. Update with 'cargo install cascade-cli --force'",
+            env::consts::OS,
+            env::consts::ARCH
+        ))
+    })?;
+    let asset = release.asset(&asset_name).ok_or_else(|| {
+        CascadeError::config(format!(
+            "Release {} has no {asset_name}. Download it from {}",
+            release.tag_name, release.html_url
+        ))
+    })?;
+    // Never install a binary that can't be verified
+    let checksums = release.asset(CHECKSUMS_ASSET).ok_or_else(|| {
+        CascadeError::config(format!(
+            "Release {} publishes no {CHECKSUMS_ASSET}, so the download can't be verified. \
+             Download it from {}",
+            release.tag_name, release.html_url
+        ))
+    })?;
+    let checksums =
+        String::from_utf8_lossy(&release::download(checksums, LOOKUP_TIMEOUT).await?).into_owned();
+    let expected = release::expected_checksum(&checksums, &asset_name).ok_or_else(|| {
+        CascadeError::config(format!(
+            "{CHECKSUMS_ASSET} of release {} has no entry for {asset_name}",
+            release.tag_name
+        ))
+    })?;
+
+    Output::progress(format!("Downloading {asset_name}..."));
+    let archive = match release::download(asset, DOWNLOAD_TIMEOUT).await {
+        Ok(archive) => {
+            Output::success_inline();
+            archive
+        }
+        Err(e) => {
+            Output::error_inline("");
+            return Err(e);
+        }
+    };
+    let actual = release::sha256_hex(&archive);
+    if actual != expected {
+        return Err(CascadeError::config(format!(
+            "Checksum mismatch for {asset_name} (expected {expected}, got {actual}). \
+             Nothing was installed"
+        )));
+    }
+    Output::sub_item("Checksum verified");
+
+    let binary = extract_binary(&asset_name, &archive)?;
+    let exe = env::current_exe()
+        .and_then(|exe| exe.canonicalize())
+        .map_err(|e| CascadeError::config(format!("Could not locate the running binary: {e}")))?;
+    replace_binary(&exe, &binary)
+}
+
+/// The `ca` executable inside a release archive
+fn extract_binary(asset_name: &str, archive: &[u8]) -> Result<Vec<u8>> {
+    let invalid =
+        |e: std::io::Error| CascadeError::config(format!("Could not unpack {asset_name}: {e}"));
+    if asset_name.ends_with(".zip") {
+        return extract_zip(asset_name, archive);
+    }
+
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    for entry in tar.entries().map_err(invalid)? {
+        let mut entry = entry.map_err(invalid)?;
+        let is_binary = entry
+            .path()
+            .map_err(invalid)?
+            .file_name()
+            .is_some_and(|name| name == BINARY_NAME);
+        if is_binary && entry.header().entry_type().is_file() {
+            let mut binary = Vec::new();
+            entry.read_to_end(&mut binary).map_err(invalid)?;
+            return Ok(binary);
+        }
+    }
+    Err(CascadeError::config(format!(
+        "{asset_name} does not contain '{BINARY_NAME}'"
+    )))
+}
+
+#[cfg(windows)]
+fn extract_zip(asset_name: &str, archive: &[u8]) -> Result<Vec<u8>> {
+    let invalid = |e: zip::result::ZipError| {
+        CascadeError::config(format!("Could not unpack {asset_name}: {e}"))
+    };
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive)).map_err(invalid)?;
+    for index in 0..zip.len() {
+        let mut file = zip.by_index(index).map_err(invalid)?;
+        let is_binary = Path::new(file.name())
+            .file_name()
+            .is_some_and(|name| name == BINARY_NAME);
+        if is_binary && file.is_file() {
+            let mut binary = Vec::new();
+            file.read_to_end(&mut binary)
+                .map_err(|e| CascadeError::config(format!("Could not unpack {asset_name}: {e}")))?;
+            return Ok(binary);
+        }
+    }
+    Err(CascadeError::config(format!(
+        "{asset_name} does not contain '{BINARY_NAME}'"
+    )))
+}
+
+#[cfg(not(windows))]
+fn extract_zip(asset_name: &str, _archive: &[u8]) -> Result<Vec<u8>> {
+    Err(CascadeError::config(format!(
+        "{asset_name} is a Windows release"
+    )))
+}
+
+/// Replace `exe` with `binary`. The new binary is written next to the old one and renamed
+/// over it, so an interrupted update leaves the old binary working.
+fn replace_binary(exe: &Path, binary: &[u8]) -> Result<()> {
+    let dir = exe
+        .parent()
+        .ok_or_else(|| CascadeError::config(format!("Invalid binary path: {}", exe.display())))?;
+    let staged = dir.join(format!(".{BINARY_NAME}.new"));
+    let write_error = |e: std::io::Error| {
+        CascadeError::config(format!(
+            "Could not write to {}: {e}. Re-run with permission to write there (e.g. 'sudo ca update')",
+            dir.display()
+        ))
+    };
+    fs::write(&staged, binary).map_err(write_error)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = fs::set_permissions(&staged, fs::Permissions::from_mode(0o755)) {
+            let _ = fs::remove_file(&staged);
+            return Err(write_error(e));
+        }
+    }
+
+    // Windows can't replace a running executable, but it can rename it out of the way
+    #[cfg(windows)]
+    let previous = {
+        let previous = old_binary_path(exe);
+        let _ = fs::remove_file(&previous);
+        if let Err(e) = fs::rename(exe, &previous) {
+            let _ = fs::remove_file(&staged);
+            return Err(write_error(e));
+        }
+        previous
+    };
+
+    if let Err(e) = fs::rename(&staged, exe) {
+        let _ = fs::remove_file(&staged);
+        #[cfg(windows)]
+        let _ = fs::rename(&previous, exe);
+        return Err(write_error(e));
+    }
+    Ok(())
+}
+
+/// Where Windows keeps the binary an update replaced, until the next update removes it
+#[cfg(windows)]
+fn old_binary_path(exe: &Path) -> PathBuf {
+    exe.with_extension("old.exe")
+}
+
+fn state_path() -> Result<PathBuf> {
+    Ok(get_config_dir()?.join("update-check.json"))
+}
+
+fn record_check() {
+    let Ok(path) = state_path() else {
+        return;
+    };
+    let state = CheckState {
+        last_checked: Some(Utc::now()),
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .map_err(CascadeError::from)
+        .and_then(|_| crate::utils::atomic_file::write_json(&path, &state));
+    if let Err(e) = result {
+        tracing::debug!("Could not record update check: {}", e);
+    }
+}
+
+/// Whether a check made at `last_checked` is stale under `frequency`
+fn check_due(
+    frequency: UpdateCheck,
+    last_checked: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> bool {
+    match (frequency.interval(), last_checked) {
+        (None, _) => false,
+        (Some(_), None) => true,
+        (Some(interval), Some(last)) => now - last >= interval,
+    }
+}
+
+/// Mention a newer release after a command, at most once per `cascade.update_check`
+/// interval. Only runs on a terminal outside CI, gives up quickly, and never fails the
+/// command it follows.
+pub async fn notify_if_due() {
+    if !std::io::stdout().is_terminal() || env::var_os("CI").is_some() {
+        return;
+    }
+    let frequency = env::current_dir()
+        .ok()
+        .and_then(|dir| find_repository_root(&dir).ok())
+        .and_then(|root| get_repo_config_dir(&root).ok())
+        .and_then(|dir| Settings::load_from_file(&dir.join("config.json")).ok())
+        .map(|settings| settings.cascade.update_check)
+        .unwrap_or_default();
+    let last_checked = state_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<CheckState>(&content).ok())
+        .and_then(|state| state.last_checked);
+    if !check_due(frequency, last_checked, Utc::now()) {
+        return;
+    }
+
+    // Recorded even when offline, so a missing network costs one slow command per interval
+    let latest = release::latest_release(PASSIVE_TIMEOUT).await;
+    record_check();
+    match latest {
+        Ok(release) if release.is_newer_than(env!("CARGO_PKG_VERSION")) => {
+            Output::spacing();
+            Output::tip(format!(
+                "Cascade {} is available (you have {}). Run 'ca update'",
+                release.version(),
+                env!("CARGO_PKG_VERSION")
+            ));
+        }
+        Ok(_) => {}
+        Err(e) => tracing::debug!("Update check failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_due_follows_frequency() {
+        let now = Utc::now();
+        let days_ago = |days| Some(now - chrono::Duration::days(days));
+        assert!(check_due(UpdateCheck::Weekly, None, now));
+        assert!(!check_due(UpdateCheck::Weekly, days_ago(3), now));
+        assert!(check_due(UpdateCheck::Weekly, days_ago(8), now));
+        assert!(check_due(UpdateCheck::Daily, days_ago(1), now));
+        assert!(!check_due(UpdateCheck::Never, None, now));
+    }
+
+    #[test]
+    fn test_extracts_binary_from_release_archive() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        for (path, contents) in [
+            ("completions/ca.bash", &b"complete"[..]),
+            (BINARY_NAME, &b"new binary"[..]),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder.append_data(&mut header, path, contents).unwrap();
+        }
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+
+        let binary = extract_binary("ca-linux-x64.tar.gz", &archive).unwrap();
+        assert_eq!(binary, b"new binary");
+        assert!(extract_binary("ca-linux-x64.tar.gz", b"not an archive").is_err());
+    }
+
+    #[test]
+    fn test_replace_binary_swaps_in_place() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let exe = temp_dir.path().join(BINARY_NAME);
+        fs::write(&exe, "old").unwrap();
+
+        replace_binary(&exe, b"new").unwrap();
+        assert_eq!(fs::read(&exe).unwrap(), b"new");
+        assert!(!temp_dir.path().join(format!(".{BINARY_NAME}.new")).exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&exe).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+    }
+}
@@ -1,8 +1,12 @@
 use crate::cli::output::Output;
 use crate::errors::Result;
+use crate::utils::release::{self, InstallChannel};
+use std::time::Duration;
 
-/// Show version information
-pub async fn run() -> Result<()> {
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Show version information, and with `check` how it compares to the latest release
+pub async fn run(check: bool) -> Result<()> {
     Output::section("Cascade CLI");
     Output::sub_item(format!("Version: {}", env!("CARGO_PKG_VERSION")));
     Output::sub_item(format!("Authors: {}", env!("CARGO_PKG_AUTHORS")));
@@ -13,6 +17,8 @@ pub async fn run() -> Result<()> {
     Output::sub_item(format!("Rust version: {}", env!("CARGO_PKG_RUST_VERSION")));
     Output::sub_item(format!("Target: {}", std::env::consts::ARCH));
     Output::sub_item(format!("OS: {}", std::env::consts::OS));
+    let channel = InstallChannel::detect();
+    Output::sub_item(format!("Install channel: {}", channel.as_str()));
 
     #[cfg(debug_assertions)]
     Output::sub_item("Build type: Debug");
@@ -27,15 +33,68 @@ pub async fn run() -> Result<()> {
     Output::sub_item("serde: 1.0+");
 
     Output::section("Links");
-    Output::sub_item("Repository: https://github.com/your-org/cascade-cli");
-    Output::sub_item("Issues: https://github.com/your-org/cascade-cli/issues");
-    Output::sub_item("Documentation: https://github.com/your-org/cascade-cli/wiki");
+    Output::sub_item(format!("Repository: {}", env!("CARGO_PKG_REPOSITORY")));
+    Output::sub_item(format!("Issues: {}/issues", env!("CARGO_PKG_REPOSITORY")));
+    Output::sub_item(format!(
+        "Documentation: {}/blob/main/docs/USER_MANUAL.md",
+        env!("CARGO_PKG_REPOSITORY")
+    ));
 
     Output::section("Quick Start");
     Output::sub_item("Initialize repository: ca init");
     Output::sub_item("Show help: ca --help");
     Output::sub_item("Check status: ca status");
 
+    if check {
+        show_available_updates(channel).await?;
+    }
+    Ok(())
+}
+
+/// Latest release, breaking changes in every release since this one, and how to update
+async fn show_available_updates(channel: InstallChannel) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    Output::section("Updates");
+    let newer = release::releases_since(current, CHECK_TIMEOUT).await?;
+    let Some(latest) = newer.first() else {
+        Output::success(format!("Cascade {current} is the latest release"));
+        return Ok(());
+    };
+
+    let published = latest
+        .published_at
+        .map(|date| format!(", released {}", date.format("%Y-%m-%d")))
+        .unwrap_or_default();
+    Output::info(format!(
+        "Cascade {} is available{published} (you have {current}, {} release{} behind)",
+        latest.version(),
+        newer.len(),
+        if newer.len() == 1 { "" } else { "s" }
+    ));
+    Output::sub_item(&latest.html_url);
+
+    let breaking: Vec<(String, String)> = newer
+        .iter()
+        .flat_map(|release| {
+            release
+                .breaking_changes()
+                .into_iter()
+                .map(|change| (release.version().to_string(), change))
+        })
+        .collect();
+    if !breaking.is_empty() {
+        Output::spacing();
+        Output::warning("Breaking changes since your version:");
+        for (version, change) in &breaking {
+            Output::bullet(format!("{version}: {change}"));
+        }
+    }
+
+    Output::spacing();
+    match channel.upgrade_command() {
+        Some(command) => Output::tip(format!("Update with: {command}")),
+        None => Output::tip("Update with: ca update"),
+    }
     Ok(())
 }
 
@@ -45,7 +104,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_version_command() {
-        let result = run().await;
+        let result = run(false).await;
         assert!(result.is_ok());
     }
 }
@@ -1,8 +1,13 @@
+use crate::bitbucket::pull_request::{
+    ActivityAction, BuildState, PullRequestActivity, PullRequestStatus,
+};
+use crate::bitbucket::{PullRequestManager, PullRequestState};
 use crate::cli::output::Output;
 use crate::errors::{CascadeError, Result};
 use crate::git::find_repository_root;
-use crate::stack::{Stack, StackManager};
-use std::collections::HashMap;
+use crate::stack::{Stack, StackEntry, StackManager};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fs;
 
@@ -17,6 +22,10 @@ pub enum OutputFormat {
     Dot,
     /// PlantUML syntax
     PlantUml,
+    /// Self-contained interactive HTML page
+    Html,
+    /// Standalone SVG image
+    Svg,
 }
 
 impl OutputFormat {
@@ -26,6 +35,11 @@ impl OutputFormat {
             "mermaid" => Ok(OutputFormat::Mermaid),
             "dot" | "graphviz" => Ok(OutputFormat::Dot),
             "plantuml" | "puml" => Ok(OutputFormat::PlantUml),
+            "html" => Ok(OutputFormat::Html),
+            "svg" => Ok(OutputFormat::Svg),
+            "png" => Err(CascadeError::config(
+                "PNG output is not supported; use --format svg and convert the image if needed",
+            )),
             _ => Err(CascadeError::config(format!("Unknown output format: {s}"))),
         }
     }
@@ -53,14 +67,101 @@ impl Default for VisualizationStyle {
     }
 }
 
+/// Pull request state fetched from Bitbucket for `--live` diagrams
+#[derive(Debug, Clone)]
+pub struct LivePrStatus {
+    pub state: PullRequestState,
+    pub approvals: usize,
+    pub required_approvals: usize,
+    pub needs_work: usize,
+    pub build: Option<BuildState>,
+}
+
+impl LivePrStatus {
+    /// One-line summary for node labels, e.g. `open · 1/2 approvals · build ✓`
+    pub fn summary(&self) -> String {
+        let state = match self.state {
+            PullRequestState::Open => "open",
+            PullRequestState::Merged => "merged",
+            PullRequestState::Declined => "declined",
+        };
+        let mut parts = vec![state.to_string()];
+
+        if self.required_approvals > 0 {
+            parts.push(format!(
+                "{}/{} approvals",
+                self.approvals, self.required_approvals
+            ));
+        } else {
+            parts.push(format!(
+                "{} approval{}",
+                self.approvals,
+                if self.approvals == 1 { "" } else { "s" }
+            ));
+        }
+        if self.needs_work > 0 {
+            parts.push("changes requested".to_string());
+        }
+        if let Some(build) = &self.build {
+            let icon = match build {
+                BuildState::Successful => "✓",
+                BuildState::Failed => "✗",
+                BuildState::InProgress => "~",
+                BuildState::Cancelled | BuildState::Unknown => "○",
+            };
+            parts.push(format!("build {icon}"));
+        }
+        parts.join(" · ")
+    }
+}
+
+impl From<&PullRequestStatus> for LivePrStatus {
+    fn from(status: &PullRequestStatus) -> Self {
+        Self {
+            state: status.pr.state.clone(),
+            approvals: status.review_status.current_approvals,
+            required_approvals: status.review_status.required_approvals,
+            needs_work: status.review_status.needs_work_count,
+            build: status.build_status.as_ref().map(|b| b.state.clone()),
+        }
+    }
+}
+
 /// Stack visualizer
 pub struct StackVisualizer {
     style: VisualizationStyle,
+    /// Bitbucket pull request URL prefix, used to link entries in HTML output
+    pr_base_url: Option<String>,
+    /// Live PR status keyed by pull request ID
+    live_status: HashMap<String, LivePrStatus>,
 }
 
 impl StackVisualizer {
     pub fn new(style: VisualizationStyle) -> Self {
-        Self { style }
+        Self {
+            style,
+            pr_base_url: None,
+            live_status: HashMap::new(),
+        }
+    }
+
+    /// Show fetched PR state, approvals and build status on entry nodes
+    pub fn with_live_status(mut self, live_status: HashMap<String, LivePrStatus>) -> Self {
+        self.live_status = live_status;
+        self
+    }
+
+    fn live_status_for(&self, entry: &StackEntry) -> Option<&LivePrStatus> {
+        entry
+            .pull_request_id
+            .as_ref()
+            .and_then(|pr_id| self.live_status.get(pr_id))
+    }
+
+    /// Link entries to their pull requests under `url` (`.../pull-requests`)
+    pub fn with_pr_base_url(mut self, url: Option<String>) -> Self {
+        self.pr_base_url = url;
+        self
     }
 
     /// Generate stack diagram in specified format
@@ -70,6 +171,8 @@ impl StackVisualizer {
             OutputFormat::Mermaid => self.generate_mermaid_diagram(stack),
             OutputFormat::Dot => self.generate_dot_diagram(stack),
             OutputFormat::PlantUml => self.generate_plantuml_diagram(stack),
+            OutputFormat::Html => self.generate_html_diagram(stack),
+            OutputFormat::Svg => Ok(self.stack_graph(stack).render_svg()),
         }
     }
 
@@ -84,6 +187,8 @@ impl StackVisualizer {
             OutputFormat::Mermaid => self.generate_mermaid_dependency_graph(stacks),
             OutputFormat::Dot => self.generate_dot_dependency_graph(stacks),
             OutputFormat::PlantUml => self.generate_plantuml_dependency_graph(stacks),
+            OutputFormat::Html => self.generate_html_dependency_graph(stacks),
+            OutputFormat::Svg => Ok(self.dependency_graph(stacks).render_svg()),
         }
     }
 
@@ -146,6 +251,10 @@ impl StackVisualizer {
                 output.push_str(&format!("│ {} 🌿 {:<50} │\n", vertical, entry.branch));
             }
 
+            if let Some(live) = self.live_status_for(entry) {
+                output.push_str(&format!("│ {} 📊 {:<50} │\n", vertical, live.summary()));
+            }
+
             // Separator for non-compact mode
             if !self.style.compact_mode && !is_last {
                 output.push_str(&format!("│ {} {:<50} │\n", vertical, ""));
@@ -189,7 +298,7 @@ impl StackVisualizer {
                     "📝"
                 };
 
-                let label = if self.style.compact_mode {
+                let mut label = if self.style.compact_mode {
                     format!("{} {}", status_icon, entry.short_message(30))
                 } else {
                     format!(
@@ -200,6 +309,9 @@ impl StackVisualizer {
                         entry.short_hash()
                     )
                 };
+                if let Some(live) = self.live_status_for(entry) {
+                    label.push_str(&format!("\\n📊 {}", live.summary()));
+                }
 
                 output.push_str(&format!("        {node_id}[\"{label}\"]\n"));
                 output.push_str(&format!("        {previous} --> {node_id}\n"));
@@ -270,13 +382,16 @@ impl StackVisualizer {
                     "📝"
                 };
 
-                let label = format!(
+                let mut label = format!(
                     "{} {}\\n🌿 {}\\n📋 {}",
                     status_icon,
                     entry.short_message(25).replace("\"", "\\\""),
                     entry.branch,
                     entry.short_hash()
                 );
+                if let Some(live) = self.live_status_for(entry) {
+                    label.push_str(&format!("\\n📊 {}", live.summary()));
+                }
 
                 let color = if entry.pull_request_id.is_some() {
                     if entry.is_synced {
@@ -350,13 +465,16 @@ impl StackVisualizer {
                     "#FFFFE0"
                 };
 
-                let label = format!(
+                let mut label = format!(
                     "{} {}\\n🌿 {}\\n📋 {}",
                     status_icon,
                     entry.short_message(25),
                     entry.branch,
                     entry.short_hash()
                 );
+                if let Some(live) = self.live_status_for(entry) {
+                    label.push_str(&format!("\\n📊 {}", live.summary()));
+                }
 
                 output.push_str(&format!("rectangle \"{label}\" as {node_id} {color}\n"));
 
@@ -373,6 +491,231 @@ impl StackVisualizer {
         Ok(output)
     }
 
+    fn generate_html_diagram(&self, stack: &Stack) -> Result<String> {
+        let subtitle = format!(
+            "Base {} · {} entries · {:?}",
+            stack.base_branch,
+            stack.entries.len(),
+            stack.status
+        );
+        Ok(self.stack_graph(stack).render_page(
+            &format!("Stack: {}", stack.name),
+            &subtitle,
+            stack.description.as_deref(),
+        ))
+    }
+
+    fn generate_html_dependency_graph(&self, stacks: &[Stack]) -> Result<String> {
+        let subtitle = format!("{} stacks", stacks.len());
+        Ok(self
+            .dependency_graph(stacks)
+            .render_page("Stack Dependencies", &subtitle, None))
+    }
+
+    /// Lay out a stack as its base branch followed by a chain of entries
+    fn stack_graph(&self, stack: &Stack) -> SvgGraph {
+        let mut graph = SvgGraph::default();
+
+        let base = graph.add_node(SvgNode {
+            x: 0.0,
+            y: 0.0,
+            title: format!("🌿 {}", stack.base_branch),
+            detail: "base branch".to_string(),
+            class: "base",
+            href: None,
+            badge: None,
+        });
+
+        if stack.entries.is_empty() {
+            let empty = graph.add_node(SvgNode {
+                x: 0.0,
+                y: ROW_HEIGHT,
+                title: "(empty stack)".to_string(),
+                detail: String::new(),
+                class: "empty",
+                href: None,
+                badge: None,
+            });
+            graph.add_edge(base, empty, false);
+        } else {
+            self.add_entry_column(&mut graph, stack, base, 0.0, ROW_HEIGHT);
+        }
+        graph
+    }
+
+    /// Lay out each stack as a column under its base branch, with dashed edges for
+    /// cross-stack dependencies
+    fn dependency_graph(&self, stacks: &[Stack]) -> SvgGraph {
+        let mut graph = SvgGraph::default();
+
+        // Sorted so the page is stable between runs
+        let mut by_base: BTreeMap<&str, Vec<&Stack>> = BTreeMap::new();
+        for stack in stacks {
+            by_base.entry(&stack.base_branch).or_default().push(stack);
+        }
+
+        let mut headers: HashMap<uuid::Uuid, usize> = HashMap::new();
+        let mut column = 0usize;
+        for (base_branch, mut base_stacks) in by_base {
+            base_stacks.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let first_x = column as f64 * COLUMN_WIDTH;
+            let last_x = (column + base_stacks.len() - 1) as f64 * COLUMN_WIDTH;
+            let base = graph.add_node(SvgNode {
+                x: (first_x + last_x) / 2.0,
+                y: 0.0,
+                title: format!("🌿 {base_branch}"),
+                detail: "base branch".to_string(),
+                class: "base",
+                href: None,
+                badge: None,
+            });
+
+            for stack in base_stacks {
+                let x = column as f64 * COLUMN_WIDTH;
+                let active_marker = if stack.is_active { " 👉" } else { "" };
+                let header = graph.add_node(SvgNode {
+                    x,
+                    y: ROW_HEIGHT,
+                    title: format!("📚 {}{}", stack.name, active_marker),
+                    detail: format!("{} entries", stack.entries.len()),
+                    class: if stack.is_active {
+                        "stack active"
+                    } else {
+                        "stack"
+                    },
+                    href: None,
+                    badge: None,
+                });
+                graph.add_edge(base, header, false);
+                headers.insert(stack.id, header);
+
+                if !self.style.compact_mode {
+                    self.add_entry_column(&mut graph, stack, header, x, 2.0 * ROW_HEIGHT);
+                }
+                column += 1;
+            }
+        }
+
+        // Cross-stack dependencies
+        for stack in stacks {
+            if let Some(parent) = Self::parent_stack(stacks, stack) {
+                if let (Some(&from), Some(&to)) = (headers.get(&parent.id), headers.get(&stack.id))
+                {
+                    graph.add_edge(from, to, true);
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Add a stack's entries as a chain of nodes below `parent`
+    fn add_entry_column(
+        &self,
+        graph: &mut SvgGraph,
+        stack: &Stack,
+        parent: usize,
+        x: f64,
+        top: f64,
+    ) {
+        let mut previous = parent;
+        for (i, entry) in stack.entries.iter().enumerate() {
+            let live = self.live_status_for(entry);
+            let (class, status) = Self::entry_status(entry, live);
+            let detail = if let Some(live) = live {
+                live.summary()
+            } else if self.style.compact_mode {
+                status.to_string()
+            } else {
+                let mut parts = vec![status.to_string()];
+                if self.style.show_branch_names {
+                    parts.push(entry.branch.clone());
+                }
+                if self.style.show_commit_hashes {
+                    parts.push(entry.short_hash());
+                }
+                parts.join(" · ")
+            };
+            let pr_link = entry.pull_request_id.as_ref().and_then(|pr_id| {
+                self.pr_base_url
+                    .as_ref()
+                    .map(|base| format!("{base}/{pr_id}"))
+            });
+            let badge = match &entry.pull_request_id {
+                Some(pr_id) if self.style.show_pr_status => {
+                    let state = match live.map(|l| &l.state) {
+                        Some(PullRequestState::Merged) => "merged",
+                        Some(PullRequestState::Declined) => "declined",
+                        Some(PullRequestState::Open) => "open",
+                        None if entry.is_merged => "merged",
+                        None => "open",
+                    };
+                    Some((format!("PR #{pr_id} {state}"), state))
+                }
+                _ => None,
+            };
+            // Leave room for the badge in the top-right corner
+            let max_len = if badge.is_some() { 24 } else { 40 };
+            let title = format!("{}. {}", i + 1, entry.short_message(max_len));
+
+            let node = graph.add_node(SvgNode {
+                x,
+                y: top + i as f64 * ROW_HEIGHT,
+                title,
+                detail,
+                class,
+                href: pr_link,
+                badge,
+            });
+            graph.add_edge(previous, node, false);
+            previous = node;
+        }
+    }
+
+    /// CSS class and label for an entry's review state, preferring live PR state
+    fn entry_status(
+        entry: &StackEntry,
+        live: Option<&LivePrStatus>,
+    ) -> (&'static str, &'static str) {
+        if let Some(live) = live {
+            if live.state == PullRequestState::Declined {
+                return ("declined", "declined");
+            }
+            if live.build == Some(BuildState::Failed) {
+                return ("failed", "build failed");
+            }
+        }
+        if entry.is_merged || live.is_some_and(|l| l.state == PullRequestState::Merged) {
+            ("merged", "merged")
+        } else if entry.pull_request_id.is_some() {
+            ("submitted", "in review")
+        } else {
+            ("draft", "draft")
+        }
+    }
+
+    /// Find the stack that `stack` declares a dependency on
+    fn parent_stack<'a>(stacks: &'a [Stack], stack: &Stack) -> Option<&'a Stack> {
+        stack
+            .depends_on
+            .and_then(|parent_id| stacks.iter().find(|s| s.id == parent_id))
+    }
+
+    /// Map stack IDs to the node IDs used when grouping stacks by base branch
+    fn dependency_node_ids(
+        by_base: &HashMap<String, Vec<&Stack>>,
+        prefix: &str,
+    ) -> HashMap<uuid::Uuid, String> {
+        let mut node_ids = HashMap::new();
+        for (i, (_, base_stacks)) in by_base.iter().enumerate() {
+            for (j, stack) in base_stacks.iter().enumerate() {
+                node_ids.insert(stack.id, format!("{prefix}{i}_{j}"));
+            }
+        }
+        node_ids
+    }
+
     fn generate_ascii_dependency_graph(&self, stacks: &[Stack]) -> Result<String> {
         let mut output = String::new();
 
@@ -420,6 +763,15 @@ impl StackVisualizer {
                 output.push_str(&" ".repeat(padding));
                 output.push_str("│\n");
 
+                if let Some(parent) = Self::parent_stack(stacks, stack) {
+                    let line = format!("⛓  depends on: {}", parent.name);
+                    let padding = 48usize.saturating_sub(line.chars().count());
+                    output.push_str(&format!(
+                        "│ {stack_vertical} {line}{}│\n",
+                        " ".repeat(padding)
+                    ));
+                }
+
                 // Show entries if not in compact mode
                 if !self.style.compact_mode && !stack.entries.is_empty() {
                     for (j, entry) in stack.entries.iter().enumerate() {
@@ -447,6 +799,16 @@ impl StackVisualizer {
                         let padding = 45usize.saturating_sub(entry.short_message(30).len());
                         output.push_str(&" ".repeat(padding));
                         output.push_str("│\n");
+
+                        if let Some(live) = self.live_status_for(entry) {
+                            let summary = live.summary();
+                            let entry_vertical = if is_last_entry { " " } else { "│" };
+                            let padding = 45usize.saturating_sub(summary.chars().count());
+                            output.push_str(&format!(
+                                "│ {stack_vertical} {entry_vertical}    📊 {summary}{}│\n",
+                                " ".repeat(padding.saturating_sub(3))
+                            ));
+                        }
                     }
                 }
             }
@@ -465,6 +827,20 @@ impl StackVisualizer {
         let active_stacks = stacks.iter().filter(|s| s.is_active).count();
         output.push_str(&format!("  Active stacks: {active_stacks}\n"));
 
+        let dependencies: Vec<_> = stacks
+            .iter()
+            .filter_map(|stack| Self::parent_stack(stacks, stack).map(|p| (p, stack)))
+            .collect();
+        if !dependencies.is_empty() {
+            output.push_str("\n⛓  Stack Dependencies:\n");
+            for (parent, child) in dependencies {
+                output.push_str(&format!(
+                    "  {} → {} (base: {})\n",
+                    parent.name, child.name, child.base_branch
+                ));
+            }
+        }
+
         Ok(output)
     }
 
@@ -509,6 +885,16 @@ impl StackVisualizer {
 
         output.push_str("    end\n");
 
+        // Cross-stack dependencies
+        let node_ids = Self::dependency_node_ids(&by_base, "STACK");
+        for stack in stacks {
+            if let (Some(parent_id), Some(child)) = (stack.depends_on, node_ids.get(&stack.id)) {
+                if let Some(parent) = node_ids.get(&parent_id) {
+                    output.push_str(&format!("    {parent} -.->|depends on| {child}\n"));
+                }
+            }
+        }
+
         // Add styling
         output.push('\n');
         output.push_str("    classDef active fill:#fef3c7,stroke:#d97706,stroke-width:3px\n");
@@ -563,6 +949,18 @@ impl StackVisualizer {
             output.push_str("    }\n");
         }
 
+        // Cross-stack dependencies
+        let node_ids = Self::dependency_node_ids(&by_base, "stack");
+        for stack in stacks {
+            if let (Some(parent_id), Some(child)) = (stack.depends_on, node_ids.get(&stack.id)) {
+                if let Some(parent) = node_ids.get(&parent_id) {
+                    output.push_str(&format!(
+                        "    {parent} -> {child} [style=dashed label=\"depends on\"];\n"
+                    ));
+                }
+            }
+        }
+
         output.push_str("}\n");
 
         Ok(output)
@@ -615,12 +1013,311 @@ impl StackVisualizer {
             }
         }
 
+        // Cross-stack dependencies
+        let node_ids = Self::dependency_node_ids(&by_base, "stack");
+        for stack in stacks {
+            if let (Some(parent_id), Some(child)) = (stack.depends_on, node_ids.get(&stack.id)) {
+                if let Some(parent) = node_ids.get(&parent_id) {
+                    output.push_str(&format!("{parent} ..> {child} : depends on\n"));
+                }
+            }
+        }
+
         output.push_str("\n@enduml\n");
 
         Ok(output)
     }
 }
 
+const NODE_WIDTH: f64 = 300.0;
+const NODE_HEIGHT: f64 = 56.0;
+const ROW_HEIGHT: f64 = 88.0;
+const COLUMN_WIDTH: f64 = 340.0;
+
+/// A box in the graph, positioned by its top-left corner
+struct SvgNode {
+    x: f64,
+    y: f64,
+    title: String,
+    detail: String,
+    class: &'static str,
+    href: Option<String>,
+    /// Pull request badge text and its CSS class (`open`, `merged`)
+    badge: Option<(String, &'static str)>,
+}
+
+/// Nodes and edges laid out without graphviz, rendered as SVG
+#[derive(Default)]
+struct SvgGraph {
+    nodes: Vec<SvgNode>,
+    /// (from, to, dashed)
+    edges: Vec<(usize, usize, bool)>,
+}
+
+impl SvgGraph {
+    fn add_node(&mut self, node: SvgNode) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, dashed: bool) {
+        self.edges.push((from, to, dashed));
+    }
+
+    fn render_svg(&self) -> String {
+        let width = self
+            .nodes
+            .iter()
+            .map(|n| n.x + NODE_WIDTH)
+            .fold(NODE_WIDTH, f64::max)
+            + 40.0;
+        let height = self
+            .nodes
+            .iter()
+            .map(|n| n.y + NODE_HEIGHT)
+            .fold(NODE_HEIGHT, f64::max)
+            + 40.0;
+
+        let mut svg = format!(
+            "<svg id=\"graph\" xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-20 -20 {width} {height}\">\n"
+        );
+        svg.push_str(SVG_STYLE);
+        svg.push_str("<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto\"><path d=\"M0,0 L10,5 L0,10 z\"/></marker></defs>\n");
+
+        for &(from, to, dashed) in &self.edges {
+            let (from, to) = (&self.nodes[from], &self.nodes[to]);
+            let (x1, y1) = (from.x + NODE_WIDTH / 2.0, from.y + NODE_HEIGHT);
+            let (x2, y2) = (to.x + NODE_WIDTH / 2.0, to.y);
+            let mid = (y1 + y2) / 2.0;
+            let class = if dashed { "edge dependency" } else { "edge" };
+            svg.push_str(&format!(
+                "<path class=\"{class}\" d=\"M{x1},{y1} C{x1},{mid} {x2},{mid} {x2},{y2}\" marker-end=\"url(#arrow)\"/>\n"
+            ));
+        }
+
+        for node in &self.nodes {
+            let badge = node
+                .badge
+                .as_ref()
+                .map(|(text, class)| {
+                    // No font metrics without a renderer; approximate 6.5px per character
+                    let width = text.chars().count() as f64 * 6.5 + 16.0;
+                    let x = NODE_WIDTH - width - 8.0;
+                    format!(
+                        "<g class=\"badge {class}\" transform=\"translate({x},8)\">\
+                         <rect width=\"{width}\" height=\"18\" rx=\"9\"/>\
+                         <text x=\"8\" y=\"13\">{}</text></g>",
+                        html_escape(text)
+                    )
+                })
+                .unwrap_or_default();
+            let mut group = format!(
+                "<g class=\"node {}\" transform=\"translate({},{})\">\
+                 <title>{}</title>\
+                 <rect width=\"{NODE_WIDTH}\" height=\"{NODE_HEIGHT}\" rx=\"8\"/>\
+                 <text x=\"12\" y=\"23\" class=\"title\">{}</text>\
+                 <text x=\"12\" y=\"43\" class=\"detail\">{}</text>{badge}</g>",
+                node.class,
+                node.x,
+                node.y,
+                html_escape(&node.title),
+                html_escape(&node.title),
+                html_escape(&node.detail)
+            );
+            if let Some(href) = &node.href {
+                group = format!(
+                    "<a href=\"{}\" target=\"_blank\" rel=\"noopener\">{group}</a>",
+                    html_escape(href)
+                );
+            }
+            svg.push_str(&group);
+            svg.push('\n');
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+
+    fn render_page(&self, title: &str, subtitle: &str, description: Option<&str>) -> String {
+        let description = description
+            .map(|d| format!("<p class=\"description\">{}</p>", html_escape(d)))
+            .unwrap_or_default();
+
+        HTML_TEMPLATE
+            .replace("{{title}}", &html_escape(title))
+            .replace("{{subtitle}}", &html_escape(subtitle))
+            .replace("{{description}}", &description)
+            .replace("{{svg}}", &self.render_svg())
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Node and edge styles, embedded in the SVG so standalone images render the same
+const SVG_STYLE: &str = r#"<style>
+  text { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; fill: #0f172a; }
+  .node > rect { fill: #fff; stroke: #94a3b8; stroke-width: 2; }
+  .node .title { font-size: 14px; font-weight: 600; }
+  .node .detail { font-size: 12px; fill: #475569; }
+  .node.base > rect { fill: #f1f5f9; }
+  .node.stack > rect { fill: #ede9fe; stroke: #7c3aed; }
+  .node.active > rect { stroke-width: 3; }
+  .node.draft > rect { fill: #fef3c7; stroke: #d97706; }
+  .node.submitted > rect { fill: #dbeafe; stroke: #2563eb; }
+  .node.merged > rect { fill: #d1fae5; stroke: #059669; }
+  .node.failed > rect { fill: #fee2e2; stroke: #dc2626; }
+  .node.declined > rect { fill: #f1f5f9; stroke: #64748b; stroke-dasharray: 4 3; }
+  a .node:hover > rect { stroke-width: 3; }
+  .badge text { font-size: 11px; font-weight: 600; fill: #fff; }
+  .badge.open rect { fill: #2563eb; }
+  .badge.merged rect { fill: #059669; }
+  .badge.declined rect { fill: #64748b; }
+  .edge { fill: none; stroke: #64748b; stroke-width: 1.5; }
+  .edge.dependency { stroke: #7c3aed; stroke-dasharray: 6 4; }
+  marker path { fill: #64748b; }
+</style>
+"#;
+
+/// Page shell for HTML output: styles plus a small pan/zoom script, no external assets
+const HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{{title}}</title>
+<style>
+  body { margin: 0; font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; background: #f8fafc; color: #0f172a; }
+  header { padding: 16px 24px; background: #fff; border-bottom: 1px solid #e2e8f0; }
+  h1 { margin: 0; font-size: 20px; }
+  .subtitle, .description { margin: 4px 0 0; color: #475569; font-size: 14px; }
+  .legend span { display: inline-block; margin-right: 12px; font-size: 13px; }
+  .legend i { display: inline-block; width: 12px; height: 12px; margin-right: 4px; border-radius: 3px; vertical-align: -1px; }
+  .toolbar { margin-top: 8px; }
+  .toolbar button { margin-right: 4px; }
+  #viewport { height: calc(100vh - 130px); overflow: hidden; cursor: grab; }
+  #viewport.dragging { cursor: grabbing; }
+  #graph { width: 100%; height: 100%; }
+</style>
+</head>
+<body>
+<header>
+  <h1>{{title}}</h1>
+  <p class="subtitle">{{subtitle}}</p>
+  {{description}}
+  <div class="toolbar legend">
+    <span><i style="background:#fef3c7"></i>draft</span>
+    <span><i style="background:#dbeafe"></i>in review</span>
+    <span><i style="background:#d1fae5"></i>merged</span>
+    <span><i style="background:#fee2e2"></i>build failed</span>
+    <button id="zoom-in">+</button><button id="zoom-out">&minus;</button><button id="zoom-reset">Reset</button>
+  </div>
+</header>
+<div id="viewport">
+{{svg}}
+</div>
+<script>
+(function () {
+  var svg = document.getElementById("graph");
+  var initial = svg.getAttribute("viewBox").split(" ").map(Number);
+  var box = initial.slice();
+  function apply() { svg.setAttribute("viewBox", box.join(" ")); }
+  function zoom(factor, cx, cy) {
+    cx = cx === undefined ? box[0] + box[2] / 2 : cx;
+    cy = cy === undefined ? box[1] + box[3] / 2 : cy;
+    box = [cx - (cx - box[0]) * factor, cy - (cy - box[1]) * factor, box[2] * factor, box[3] * factor];
+    apply();
+  }
+  function toGraph(evt) {
+    var rect = svg.getBoundingClientRect();
+    return [box[0] + (evt.clientX - rect.left) / rect.width * box[2],
+            box[1] + (evt.clientY - rect.top) / rect.height * box[3]];
+  }
+  svg.addEventListener("wheel", function (evt) {
+    evt.preventDefault();
+    var p = toGraph(evt);
+    zoom(evt.deltaY > 0 ? 1.1 : 1 / 1.1, p[0], p[1]);
+  }, { passive: false });
+  var viewport = document.getElementById("viewport");
+  var drag = null;
+  viewport.addEventListener("mousedown", function (evt) {
+    drag = { x: evt.clientX, y: evt.clientY, box: box.slice() };
+    viewport.classList.add("dragging");
+  });
+  window.addEventListener("mousemove", function (evt) {
+    if (!drag) return;
+    var rect = svg.getBoundingClientRect();
+    box[0] = drag.box[0] - (evt.clientX - drag.x) / rect.width * box[2];
+    box[1] = drag.box[1] - (evt.clientY - drag.y) / rect.height * box[3];
+    apply();
+  });
+  window.addEventListener("mouseup", function () { drag = null; viewport.classList.remove("dragging"); });
+  document.getElementById("zoom-in").onclick = function () { zoom(1 / 1.25); };
+  document.getElementById("zoom-out").onclick = function () { zoom(1.25); };
+  document.getElementById("zoom-reset").onclick = function () { box = initial.slice(); apply(); };
+})();
+</script>
+</body>
+</html>
+"#;
+
+/// Pull request URL prefix from the repository's Bitbucket settings
+fn pr_base_url(repo_root: &std::path::Path) -> Option<String> {
+    let config_dir = crate::config::get_repo_config_dir(repo_root).ok()?;
+    let settings = crate::config::Settings::load_from_file(&config_dir.join("config.json")).ok()?;
+    let bitbucket = settings.bitbucket;
+    if bitbucket.url.is_empty() || bitbucket.project.is_empty() || bitbucket.repo.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{}/projects/{}/repos/{}/pull-requests",
+        bitbucket.url.trim_end_matches('/'),
+        bitbucket.project,
+        bitbucket.repo
+    ))
+}
+
+/// Fetch PR state, approvals and build status for every submitted entry
+async fn fetch_live_status(
+    repo_root: &std::path::Path,
+    stacks: &[&Stack],
+) -> Result<HashMap<String, LivePrStatus>> {
+    let config_dir = crate::config::get_repo_config_dir(repo_root)?;
+    let settings = crate::config::Settings::load_from_file(&config_dir.join("config.json"))?;
+    if settings.bitbucket.url.is_empty() {
+        return Err(CascadeError::config(
+            "--live needs Bitbucket settings. Run 'ca setup' first.",
+        ));
+    }
+
+    let pr_manager = PullRequestManager::from_settings(&settings, repo_root)?;
+
+    let spinner = crate::utils::spinner::Spinner::new("Fetching PR status...".to_string());
+    let mut live_status = HashMap::new();
+    for pr_id in stacks
+        .iter()
+        .flat_map(|stack| &stack.entries)
+        .filter_map(|entry| entry.pull_request_id.as_ref())
+    {
+        let Ok(id) = pr_id.parse::<u64>() else {
+            continue;
+        };
+        match pr_manager.get_pull_request_status(id).await {
+            Ok(status) => {
+                live_status.insert(pr_id.clone(), LivePrStatus::from(&status));
+            }
+            Err(e) => tracing::debug!("Failed to get status for PR #{}: {}", pr_id, e),
+        }
+    }
+    spinner.stop();
+
+    Ok(live_status)
+}
+
 /// Visualize a specific stack
 pub async fn show_stack(
     stack_name: Option<String>,
@@ -628,6 +1325,7 @@ pub async fn show_stack(
     output_file: Option<String>,
     compact: bool,
     no_colors: bool,
+    live: bool,
 ) -> Result<()> {
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
@@ -642,9 +1340,9 @@ pub async fn show_stack(
             .get_stack_by_name(&name)
             .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?
     } else {
-        manager.get_active_stack().ok_or_else(|| {
-            CascadeError::config("No active stack. Use 'ca stack list' to see available stacks")
-        })?
+        manager
+            .get_active_stack()
+            .ok_or_else(CascadeError::no_active_stack)?
     };
 
     let output_format = format
@@ -659,7 +1357,15 @@ pub async fn show_stack(
         ..Default::default()
     };
 
-    let visualizer = StackVisualizer::new(style);
+    let live_status = if live {
+        fetch_live_status(&repo_root, &[stack]).await?
+    } else {
+        HashMap::new()
+    };
+
+    let visualizer = StackVisualizer::new(style)
+        .with_pr_base_url(pr_base_url(&repo_root))
+        .with_live_status(live_status);
     let diagram = visualizer.generate_stack_diagram(stack, &output_format)?;
 
     if let Some(file_path) = output_file {
@@ -668,7 +1374,7 @@ pub async fn show_stack(
         })?;
         Output::success(format!("Stack diagram saved to: {file_path}"));
     } else {
-        println!("{diagram}");
+        Output::line(&diagram);
     }
 
     Ok(())
@@ -680,6 +1386,7 @@ pub async fn show_dependencies(
     output_file: Option<String>,
     compact: bool,
     no_colors: bool,
+    live: bool,
 ) -> Result<()> {
     let current_dir = env::current_dir()
         .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
@@ -691,7 +1398,7 @@ pub async fn show_dependencies(
     let stacks = manager.get_all_stacks_objects()?;
 
     if stacks.is_empty() {
-        println!("No stacks found. Create one with: ca stack create <name>");
+        Output::info("No stacks found. Create one with: ca stack create <name>");
         return Ok(());
     }
 
@@ -707,7 +1414,15 @@ pub async fn show_dependencies(
         ..Default::default()
     };
 
-    let visualizer = StackVisualizer::new(style);
+    let live_status = if live {
+        fetch_live_status(&repo_root, &stacks.iter().collect::<Vec<_>>()).await?
+    } else {
+        HashMap::new()
+    };
+
+    let visualizer = StackVisualizer::new(style)
+        .with_pr_base_url(pr_base_url(&repo_root))
+        .with_live_status(live_status);
     let diagram = visualizer.generate_dependency_graph(&stacks, &output_format)?;
 
     if let Some(file_path) = output_file {
@@ -716,8 +1431,509 @@ pub async fn show_dependencies(
         })?;
         Output::success(format!("Dependency graph saved to: {file_path}"));
     } else {
-        println!("{diagram}");
+        Output::line(&diagram);
+    }
+
+    Ok(())
+}
+
+/// What happened to an entry at a point on the timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineEventKind {
+    Created,
+    Submitted,
+    Approved,
+    ChangesRequested,
+    Landed,
+    Declined,
+}
+
+impl TimelineEventKind {
+    fn label(&self) -> &'static str {
+        match self {
+            TimelineEventKind::Created => "📝 created",
+            TimelineEventKind::Submitted => "📤 submitted",
+            TimelineEventKind::Approved => "👍 approved",
+            TimelineEventKind::ChangesRequested => "✋ changes requested",
+            TimelineEventKind::Landed => "🚀 landed",
+            TimelineEventKind::Declined => "❌ declined",
+        }
+    }
+}
+
+/// A dated event for one stack entry
+#[derive(Debug, Clone)]
+pub struct TimelineEvent {
+    pub at: DateTime<Utc>,
+    /// 1-based position of the entry in the stack
+    pub position: usize,
+    pub kind: TimelineEventKind,
+    /// Who approved, merged etc., when known
+    pub actor: Option<String>,
+}
+
+/// Build a stack's timeline from entry creation times and PR activity (keyed by PR ID)
+pub fn build_timeline(
+    stack: &Stack,
+    activities: &HashMap<String, Vec<PullRequestActivity>>,
+) -> Vec<TimelineEvent> {
+    let mut events = Vec::new();
+
+    for (i, entry) in stack.entries.iter().enumerate() {
+        events.push(TimelineEvent {
+            at: entry.created_at,
+            position: i + 1,
+            kind: TimelineEventKind::Created,
+            actor: None,
+        });
+
+        let pr_activities = entry
+            .pull_request_id
+            .as_ref()
+            .and_then(|pr_id| activities.get(pr_id));
+        for activity in pr_activities.into_iter().flatten() {
+            let kind = match activity.action {
+                ActivityAction::Opened => TimelineEventKind::Submitted,
+                ActivityAction::Approved => TimelineEventKind::Approved,
+                ActivityAction::Reviewed => TimelineEventKind::ChangesRequested,
+                ActivityAction::Merged => TimelineEventKind::Landed,
+                ActivityAction::Declined => TimelineEventKind::Declined,
+                _ => continue,
+            };
+            events.push(TimelineEvent {
+                at: activity.created_at(),
+                position: i + 1,
+                kind,
+                actor: activity
+                    .user
+                    .display_name
+                    .clone()
+                    .or_else(|| Some(activity.user.name.clone())),
+            });
+        }
+    }
+
+    events.sort_by_key(|event| (event.at, event.position));
+    events
+}
+
+/// Short human duration, e.g. `45m`, `3h`, `2d 4h`
+pub(crate) fn format_span(span: Duration) -> String {
+    if span.num_days() > 0 {
+        let hours = span.num_hours() % 24;
+        if hours > 0 {
+            format!("{}d {}h", span.num_days(), hours)
+        } else {
+            format!("{}d", span.num_days())
+        }
+    } else if span.num_hours() > 0 {
+        format!("{}h", span.num_hours())
+    } else {
+        format!("{}m", span.num_minutes().max(0))
+    }
+}
+
+/// Per-entry durations and stall warning for the timeline summary
+fn timeline_entry_summary(
+    events: &[TimelineEvent],
+    position: usize,
+    now: DateTime<Utc>,
+    stall_days: i64,
+) -> String {
+    let entry_events: Vec<&TimelineEvent> =
+        events.iter().filter(|e| e.position == position).collect();
+    let Some(created) = entry_events
+        .iter()
+        .find(|e| e.kind == TimelineEventKind::Created)
+    else {
+        return String::new();
+    };
+    let first = |kind| entry_events.iter().find(|e| e.kind == kind);
+
+    let mut parts = Vec::new();
+    if let Some(submitted) = first(TimelineEventKind::Submitted) {
+        parts.push(format!(
+            "submitted after {}",
+            format_span(submitted.at - created.at)
+        ));
+        if let Some(approved) = first(TimelineEventKind::Approved) {
+            parts.push(format!(
+                "first approval after {}",
+                format_span(approved.at - submitted.at)
+            ));
+        }
+    }
+
+    if let Some(landed) = first(TimelineEventKind::Landed) {
+        parts.push(format!(
+            "landed after {}",
+            format_span(landed.at - created.at)
+        ));
+    } else if first(TimelineEventKind::Declined).is_some() {
+        parts.push("declined".to_string());
+    } else {
+        let last = entry_events
+            .iter()
+            .map(|e| e.at)
+            .max()
+            .unwrap_or(created.at);
+        let idle = now - last;
+        if idle.num_days() >= stall_days {
+            let waiting_for = if first(TimelineEventKind::Submitted).is_none() {
+                "not submitted"
+            } else if first(TimelineEventKind::Approved).is_none() {
+                "waiting for review"
+            } else {
+                "waiting to land"
+            };
+            parts.push(format!(
+                "⏸ no activity for {} ({waiting_for})",
+                format_span(idle)
+            ));
+        }
+    }
+
+    parts.join(" · ")
+}
+
+/// Render a stack's timeline as a dated event list followed by per-entry summaries
+pub fn render_timeline_ascii(
+    stack: &Stack,
+    events: &[TimelineEvent],
+    now: DateTime<Utc>,
+    stall_days: i64,
+) -> String {
+    let mut output = String::new();
+    output.push_str(&format!(
+        "🕒 Timeline: {} (base {})\n\n",
+        stack.name, stack.base_branch
+    ));
+
+    if events.is_empty() {
+        output.push_str("   (empty stack)\n");
+        return output;
+    }
+
+    for event in events {
+        let entry = &stack.entries[event.position - 1];
+        output.push_str(&format!(
+            "{}  #{:<2} {:<30} {}",
+            event.at.format("%Y-%m-%d %H:%M"),
+            event.position,
+            entry.branch,
+            event.kind.label()
+        ));
+        if let (Some(actor), TimelineEventKind::Approved | TimelineEventKind::ChangesRequested) =
+            (&event.actor, event.kind)
+        {
+            output.push_str(&format!(" by {actor}"));
+        }
+        output.push('\n');
+    }
+
+    output.push_str("\nPer entry:\n");
+    for (i, entry) in stack.entries.iter().enumerate() {
+        let summary = timeline_entry_summary(events, i + 1, now, stall_days);
+        output.push_str(&format!(
+            "  #{:<2} {:<30} {}\n",
+            i + 1,
+            entry.branch,
+            summary
+        ));
+    }
+
+    output
+}
+
+/// Render a stack's timeline as a Mermaid `timeline` diagram, one period per day
+pub fn render_timeline_mermaid(stack: &Stack, events: &[TimelineEvent]) -> String {
+    let mut output = String::new();
+    output.push_str("timeline\n");
+    output.push_str(&format!("    title Stack: {}\n", stack.name));
+
+    let mut by_day: BTreeMap<String, Vec<&TimelineEvent>> = BTreeMap::new();
+    for event in events {
+        by_day
+            .entry(event.at.format("%Y-%m-%d").to_string())
+            .or_default()
+            .push(event);
+    }
+
+    for (day, day_events) in by_day {
+        let descriptions: Vec<String> = day_events
+            .iter()
+            .map(|event| {
+                // Mermaid uses ':' to separate events
+                let label = event.kind.label().split_once(' ').map_or("", |(_, l)| l);
+                format!(
+                    "#{} {} {}",
+                    event.position,
+                    stack.entries[event.position - 1].branch.replace(':', "-"),
+                    label
+                )
+            })
+            .collect();
+        output.push_str(&format!("    {day} : {}\n", descriptions.join(" : ")));
+    }
+
+    output
+}
+
+/// Show when each entry of a stack was created, submitted, approved and landed
+pub async fn show_timeline(
+    stack_name: Option<String>,
+    format: Option<String>,
+    output_file: Option<String>,
+    stall_days: u32,
+) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+
+    let repo_root = find_repository_root(&current_dir)
+        .map_err(|e| CascadeError::config(format!("Could not find git repository: {e}")))?;
+
+    let manager = StackManager::new(&repo_root)?;
+
+    let stack = if let Some(name) = stack_name {
+        manager
+            .get_stack_by_name(&name)
+            .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?
+    } else {
+        manager
+            .get_active_stack()
+            .ok_or_else(CascadeError::no_active_stack)?
+    };
+
+    let output_format = format
+        .as_ref()
+        .map(|f| OutputFormat::from_str(f))
+        .transpose()?
+        .unwrap_or(OutputFormat::Ascii);
+
+    let activities = match fetch_pr_activities(&repo_root, stack).await {
+        Ok(activities) => activities,
+        Err(e) => {
+            tracing::debug!("Failed to fetch PR activity: {}", e);
+            Output::warning("Could not fetch PR events; showing local history only");
+            HashMap::new()
+        }
+    };
+    let events = build_timeline(stack, &activities);
+
+    let diagram = match output_format {
+        OutputFormat::Ascii => {
+            render_timeline_ascii(stack, &events, Utc::now(), i64::from(stall_days))
+        }
+        OutputFormat::Mermaid => render_timeline_mermaid(stack, &events),
+        other => {
+            return Err(CascadeError::config(format!(
+                "Timeline supports ascii and mermaid output, not {other:?}"
+            )))
+        }
+    };
+
+    if let Some(file_path) = output_file {
+        fs::write(&file_path, diagram).map_err(|e| {
+            CascadeError::config(format!("Failed to write to file '{file_path}': {e}"))
+        })?;
+        Output::success(format!("Timeline saved to: {file_path}"));
+    } else {
+        Output::line(&diagram);
     }
 
     Ok(())
 }
+
+/// Fetch the activity stream of every PR in the stack. Returns nothing when Bitbucket
+/// isn't configured, so the timeline falls back to local entry history.
+pub(crate) async fn fetch_pr_activities(
+    repo_root: &std::path::Path,
+    stack: &Stack,
+) -> Result<HashMap<String, Vec<PullRequestActivity>>> {
+    let config_dir = crate::config::get_repo_config_dir(repo_root)?;
+    let settings = crate::config::Settings::load_from_file(&config_dir.join("config.json"))?;
+    let mut activities = HashMap::new();
+    if settings.bitbucket.url.is_empty() {
+        return Ok(activities);
+    }
+
+    let pr_manager = PullRequestManager::from_settings(&settings, repo_root)?;
+    for pr_id in stack
+        .entries
+        .iter()
+        .filter_map(|entry| entry.pull_request_id.as_ref())
+    {
+        let Ok(id) = pr_id.parse::<u64>() else {
+            continue;
+        };
+        activities.insert(
+            pr_id.clone(),
+            pr_manager.get_pull_request_activities(id).await?,
+        );
+    }
+
+    Ok(activities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_stack() -> Stack {
+        let mut stack = Stack::new("auth".to_string(), "main".to_string(), None);
+        let first = stack.push_entry(
+            "auth-1".to_string(),
+            "a".repeat(40),
+            "Add <login> form".to_string(),
+        );
+        stack.push_entry(
+            "auth-2".to_string(),
+            "b".repeat(40),
+            "Add tokens".to_string(),
+        );
+        stack.mark_entry_submitted(&first, "42".to_string());
+        stack
+    }
+
+    #[test]
+    fn test_html_stack_diagram() {
+        let visualizer = StackVisualizer::new(VisualizationStyle::default()).with_pr_base_url(
+            Some("https://bitbucket.example.com/projects/P/repos/r/pull-requests".to_string()),
+        );
+        let html = visualizer
+            .generate_stack_diagram(&sample_stack(), &OutputFormat::Html)
+            .unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Stack: auth</title>"));
+        assert!(html.contains(
+            "href=\"https://bitbucket.example.com/projects/P/repos/r/pull-requests/42\""
+        ));
+        assert!(html.contains("node submitted"));
+        assert!(html.contains("node draft"));
+        // Commit messages are escaped, and nothing is loaded from elsewhere
+        assert!(html.contains("Add &lt;login&gt; form"));
+        assert!(!html.contains("<script src"));
+    }
+
+    #[test]
+    fn test_svg_stack_diagram_has_pr_badges() {
+        let svg = StackVisualizer::new(VisualizationStyle::default())
+            .generate_stack_diagram(&sample_stack(), &OutputFormat::Svg)
+            .unwrap();
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains("<style>"));
+        assert!(svg.contains("class=\"badge open\""));
+        assert!(svg.contains("PR #42 open"));
+        assert_eq!(svg.matches("class=\"badge").count(), 1);
+
+        assert!(OutputFormat::from_str("png").is_err());
+    }
+
+    #[test]
+    fn test_live_status_on_nodes() {
+        let stack = sample_stack();
+        let live_status = HashMap::from([(
+            "42".to_string(),
+            LivePrStatus {
+                state: PullRequestState::Open,
+                approvals: 1,
+                required_approvals: 2,
+                needs_work: 0,
+                build: Some(BuildState::Failed),
+            },
+        )]);
+        let visualizer =
+            StackVisualizer::new(VisualizationStyle::default()).with_live_status(live_status);
+
+        let summary = "open · 1/2 approvals · build ✗";
+        for format in [
+            OutputFormat::Ascii,
+            OutputFormat::Mermaid,
+            OutputFormat::Svg,
+        ] {
+            let diagram = visualizer.generate_stack_diagram(&stack, &format).unwrap();
+            assert!(
+                diagram.contains(summary),
+                "{format:?} is missing live status"
+            );
+        }
+
+        let svg = visualizer
+            .generate_stack_diagram(&stack, &OutputFormat::Svg)
+            .unwrap();
+        assert!(svg.contains("node failed"));
+    }
+
+    fn activity(action: &str, at: DateTime<Utc>, user: &str) -> PullRequestActivity {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "createdDate": at.timestamp_millis(),
+            "user": {"name": user, "displayName": user, "active": true},
+            "action": action,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_timeline_from_entries_and_pr_activity() {
+        let mut stack = sample_stack();
+        // Bitbucket timestamps have no sub-second part
+        let created =
+            DateTime::from_timestamp((Utc::now() - Duration::days(10)).timestamp(), 0).unwrap();
+        for entry in &mut stack.entries {
+            entry.created_at = created;
+        }
+        let activities = HashMap::from([(
+            "42".to_string(),
+            vec![
+                activity("OPENED", created + Duration::hours(2), "joe"),
+                activity("APPROVED", created + Duration::days(1), "Jane"),
+                activity("COMMENTED", created + Duration::days(1), "Jane"),
+                activity("MERGED", created + Duration::days(2), "joe"),
+            ],
+        )]);
+
+        let events = build_timeline(&stack, &activities);
+        let kinds: Vec<_> = events.iter().map(|e| (e.position, e.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (1, TimelineEventKind::Created),
+                (2, TimelineEventKind::Created),
+                (1, TimelineEventKind::Submitted),
+                (1, TimelineEventKind::Approved),
+                (1, TimelineEventKind::Landed),
+            ]
+        );
+
+        let ascii = render_timeline_ascii(&stack, &events, Utc::now(), 3);
+        assert!(ascii.contains("👍 approved by Jane"));
+        assert!(ascii.contains("submitted after 2h · first approval after 22h · landed after 2d"));
+        // The second entry was never submitted and has been idle for 10 days
+        assert!(ascii.contains("⏸ no activity for 10d (not submitted)"));
+
+        let mermaid = render_timeline_mermaid(&stack, &events);
+        assert!(mermaid.starts_with("timeline\n"));
+        assert!(mermaid.contains("#1 auth-1 landed"));
+    }
+
+    #[test]
+    fn test_html_dependency_graph_links_stacks() {
+        let parent = sample_stack();
+        let mut child = Stack::new("billing".to_string(), "main".to_string(), None);
+        child.depends_on = Some(parent.id);
+
+        let html = StackVisualizer::new(VisualizationStyle::default())
+            .generate_dependency_graph(&[parent, child], &OutputFormat::Html)
+            .unwrap();
+
+        assert!(html.contains("📚 auth"));
+        assert!(html.contains("📚 billing"));
+        assert!(html.contains("edge dependency"));
+        // Without Bitbucket settings entries aren't linked
+        assert!(!html.contains("<a href"));
+    }
+}
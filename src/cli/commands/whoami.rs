@@ -0,0 +1,231 @@
+use crate::bitbucket::provider::{ProviderIdentity, RepositoryPermission};
+use crate::bitbucket::pull_request::PullRequestManager;
+use crate::cli::output::Output;
+use crate::config::{ProviderKind, Settings};
+use crate::errors::{CascadeError, Result};
+use crate::git::{find_repository_root, GitRepository};
+use serde::Serialize;
+use std::env;
+
+/// Everything `ca whoami` reports, also printed as-is with `--json`
+#[derive(Debug, Serialize)]
+struct WhoamiReport {
+    provider: String,
+    server: String,
+    /// `PROJECT/repo`
+    repository: String,
+    user: String,
+    display_name: Option<String>,
+    email: Option<String>,
+    permission: Option<RepositoryPermission>,
+    token_scopes: Option<Vec<String>>,
+    can_push: bool,
+    can_merge: bool,
+    git: GitIdentity,
+}
+
+/// The identity git records on commits
+#[derive(Debug, Serialize)]
+struct GitIdentity {
+    name: Option<String>,
+    email: Option<String>,
+}
+
+impl WhoamiReport {
+    fn new(settings: &Settings, identity: ProviderIdentity, git: GitIdentity) -> Self {
+        let config = &settings.bitbucket;
+        let provider = match config.provider {
+            ProviderKind::Bitbucket => "bitbucket",
+            ProviderKind::Mock => "mock",
+        };
+        Self {
+            provider: provider.to_string(),
+            server: config.url.clone(),
+            repository: format!("{}/{}", config.project, config.repo),
+            can_push: identity.can_push(),
+            can_merge: identity.can_merge(),
+            user: identity.user.name,
+            display_name: identity.user.display_name,
+            email: identity.user.email,
+            permission: identity.permission,
+            token_scopes: identity.token.map(|token| token.permissions),
+            git,
+        }
+    }
+
+    /// Whether git records commits under a different email than the provider knows
+    fn email_mismatch(&self) -> bool {
+        match (&self.email, &self.git.email) {
+            (Some(provider), Some(git)) => !provider.eq_ignore_ascii_case(git),
+            _ => false,
+        }
+    }
+}
+
+/// Show the provider user the credentials authenticate as, what they may do in the
+/// repository, and the git identity commits are made with
+pub async fn run(json: bool) -> Result<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| CascadeError::config(format!("Could not get current directory: {e}")))?;
+    let repo_root = find_repository_root(&current_dir)?;
+    let config_dir = crate::config::get_repo_config_dir(&repo_root)?;
+    let settings = Settings::load_from_file(&config_dir.join("config.json"))?;
+
+    let (name, email) = GitRepository::open(&repo_root)?.get_user_info();
+    let git = GitIdentity { name, email };
+
+    let pr_manager = PullRequestManager::from_settings(&settings, &repo_root)?;
+    let identity = match pr_manager.identity().await {
+        Ok(identity) => identity,
+        Err(e) => {
+            if !json {
+                print_git_identity(&git);
+            }
+            return Err(CascadeError::auth(format!(
+                "Could not look up the authenticated user: {e}"
+            ))
+            .with_hint("Run 'ca auth status' or 'ca diagnose --network' for details"));
+        }
+    };
+    let report = WhoamiReport::new(&settings, identity, git);
+
+    if json {
+        Output::data(serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    Output::section(format!("Provider ({})", report.provider));
+    Output::sub_item(format!("Server: {}", report.server));
+    Output::sub_item(format!("Repository: {}", report.repository));
+    let mut user = match &report.display_name {
+        Some(display_name) if *display_name != report.user => {
+            format!("{display_name} ({})", report.user)
+        }
+        _ => report.user.clone(),
+    };
+    if let Some(email) = &report.email {
+        user.push_str(&format!(" <{email}>"));
+    }
+    Output::sub_item(format!("User: {user}"));
+    Output::sub_item(format!(
+        "Permission: {}",
+        report
+            .permission
+            .map_or("none".to_string(), |permission| permission.to_string())
+    ));
+    if let Some(scopes) = &report.token_scopes {
+        Output::sub_item(format!("Token scopes: {}", scopes.join(", ")));
+    }
+    Output::sub_item(format!("Can push: {}", yes_no(report.can_push)));
+    Output::sub_item(format!("Can merge: {}", yes_no(report.can_merge)));
+    print_git_identity(&report.git);
+    Output::spacing();
+
+    if !report.can_push {
+        if report.permission >= Some(RepositoryPermission::Write) {
+            Output::warning("The token's scopes don't allow writing to repositories");
+            Output::tip(
+                "Create a personal access token with repository write permission and run \
+                 'ca config set bitbucket.token <token>'",
+            );
+        } else {
+            Output::warning(format!(
+                "{} can't push to {}; submitting and landing will fail",
+                report.user, report.repository
+            ));
+            Output::tip("Ask a repository or project admin for write access");
+        }
+    }
+    if report.email_mismatch() {
+        Output::warning(format!(
+            "Commits are authored as {} but the provider knows {} as {}",
+            report.git.email.as_deref().unwrap_or_default(),
+            report.user,
+            report.email.as_deref().unwrap_or_default()
+        ));
+        Output::tip("Set the email the server knows with 'git config user.email <email>'");
+    }
+    if report.git.name.is_none() || report.git.email.is_none() {
+        Output::warning("git has no user.name or user.email; commits will fail");
+    }
+    Ok(())
+}
+
+fn print_git_identity(git: &GitIdentity) {
+    Output::section("Git");
+    Output::sub_item(format!(
+        "user.name: {}",
+        git.name.as_deref().unwrap_or("(not set)")
+    ));
+    Output::sub_item(format!(
+        "user.email: {}",
+        git.email.as_deref().unwrap_or("(not set)")
+    ));
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitbucket::auth::{AuthenticatedUser, TokenDetails};
+
+    fn identity(permission: Option<RepositoryPermission>, scopes: &[&str]) -> ProviderIdentity {
+        ProviderIdentity {
+            user: AuthenticatedUser {
+                name: "jdoe".to_string(),
+                display_name: Some("Jane Doe".to_string()),
+                email: Some("Jane@example.com".to_string()),
+            },
+            permission,
+            token: Some(TokenDetails {
+                name: "cascade".to_string(),
+                permissions: scopes.iter().map(|s| s.to_string()).collect(),
+                created: None,
+                expires: None,
+                last_used: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_report_combines_permission_and_token_scopes() {
+        let settings = Settings::default();
+        let git = || GitIdentity {
+            name: Some("Jane".to_string()),
+            email: Some("jane@example.com".to_string()),
+        };
+
+        let writer = WhoamiReport::new(
+            &settings,
+            identity(Some(RepositoryPermission::Write), &["REPO_WRITE"]),
+            git(),
+        );
+        assert!(writer.can_push && writer.can_merge);
+        assert!(!writer.email_mismatch());
+
+        let read_only_token = WhoamiReport::new(
+            &settings,
+            identity(Some(RepositoryPermission::Admin), &["REPO_READ"]),
+            git(),
+        );
+        assert!(!read_only_token.can_push);
+
+        let reader = WhoamiReport::new(
+            &settings,
+            identity(Some(RepositoryPermission::Read), &[]),
+            GitIdentity {
+                name: None,
+                email: Some("jane@home.example".to_string()),
+            },
+        );
+        assert!(!reader.can_merge);
+        assert!(reader.email_mismatch());
+    }
+}
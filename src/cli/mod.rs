@@ -1,12 +1,14 @@
 pub mod commands;
 pub mod output;
+pub mod prompt;
 
 use crate::errors::Result;
 use clap::{Parser, Subcommand};
-use clap_complete::Shell;
+use commands::completions::CompletionShell;
 use commands::entry::EntryAction;
 use commands::stack::StackAction;
 use commands::{MergeStrategyArg, RebaseStrategyArg};
+use output::Output;
 
 #[derive(Debug, Subcommand)]
 pub enum SyncAction {
@@ -39,6 +41,27 @@ pub struct Cli {
     /// Disable colored output
     #[arg(long, global = true)]
     pub no_color: bool,
+
+    /// Answer yes to every confirmation prompt and take the default for other questions
+    #[arg(long, short = 'y', global = true)]
+    pub yes: bool,
+
+    /// Plain line-oriented output: no color, emoji, box drawing or spinners
+    /// (also CASCADE_PLAIN=1)
+    #[arg(long, global = true)]
+    pub plain: bool,
+
+    /// Allow operating on branches listed in git.protected_branches
+    #[arg(long, global = true)]
+    pub allow_protected: bool,
+
+    /// Fetch from the remote every time a step needs it, instead of once per command
+    #[arg(long, global = true, conflicts_with = "no_fetch")]
+    pub fetch: bool,
+
+    /// Don't fetch from the remote; work with the remote-tracking refs you have
+    #[arg(long, global = true)]
+    pub no_fetch: bool,
 }
 
 /// Commands available in the CLI
@@ -73,17 +96,134 @@ pub enum Commands {
         action: EntryAction,
     },
 
-    /// Show repository overview and all stacks
-    Repo,
+    /// Show repository overview, stack readiness and suggested next actions
+    Repo {
+        /// Print the overview as JSON (for dashboards and scripts)
+        #[arg(long)]
+        json: bool,
+        /// Fetch current pull request status from Bitbucket first
+        #[arg(long)]
+        refresh: bool,
+    },
+
+    /// Show cycle-time, rework and conflict metrics for stacks and pull requests
+    Stats {
+        /// Only count activity since a duration ago (30d, 12h), a date or a timestamp
+        #[arg(long)]
+        since: Option<String>,
+        /// Print the metrics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print stack data picked by a selector, e.g. 'stacks[active].entries[?submitted==false].branch'
+    Query {
+        /// Selector: fields joined by '.', with [N], [*] and [?field==value] on lists
+        selector: String,
+        /// Print the results as a JSON array
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Find stacks, entries and pull requests by name, branch, commit message or PR title
+    Search {
+        /// Text to look for (case-insensitive)
+        query: String,
+        /// Switch to the stack of a match (asks which when several stacks match)
+        #[arg(long, conflicts_with_all = ["open", "json"])]
+        switch: bool,
+        /// Open the pull request of a match (asks which when several have one)
+        #[arg(long, conflicts_with = "json")]
+        open: bool,
+        /// Print the matches as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Remind reviewers of stack pull requests that have had no activity for a while
+    ///
+    /// Posts a comment mentioning the reviewers who haven't reviewed yet (template:
+    /// nudge.template), or asks them to review again with --rerequest. A pull request
+    /// is nudged at most once per nudge.cooldown_hours.
+    Nudge {
+        /// Only pull requests without activity for this long, e.g. 36h, 2d or 1w
+        /// (default: nudge.older_than, 2d)
+        #[arg(long, value_name = "AGE")]
+        older_than: Option<String>,
+        /// Pull requests of every stack, not just the active one
+        #[arg(long)]
+        all: bool,
+        /// Ask reviewers to review again instead of commenting
+        #[arg(long)]
+        rerequest: bool,
+        /// Show who would be reminded without doing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Triage pull requests across all stacks
+    Prs {
+        /// Filter by state (open, merged, declined, all; default: open)
+        #[arg(long)]
+        state: Option<String>,
+        /// Only pull requests you authored
+        #[arg(long, conflicts_with = "author")]
+        mine: bool,
+        /// Only pull requests waiting for your approval
+        #[arg(long)]
+        needs_review: bool,
+        /// Only pull requests by this user
+        #[arg(long)]
+        author: Option<String>,
+        /// Show branches, authors and links
+        #[arg(long, short)]
+        verbose: bool,
+    },
+
+    /// Print a compact stack summary for your shell prompt (PS1, starship)
+    Prompt {
+        /// Template with {stack}, {entry}, {total}, {ready} and {sync} placeholders
+        #[arg(long)]
+        format: Option<String>,
+    },
 
     /// Show version information  
-    Version,
+    Version {
+        /// Compare with the latest release and list breaking changes since this version
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Install the latest release of Cascade
+    Update {
+        /// Only report whether a newer release exists
+        #[arg(long)]
+        check: bool,
+    },
 
     /// Check repository health and configuration
     Doctor,
 
     /// Diagnose git2 TLS/SSH support issues
-    Diagnose,
+    Diagnose {
+        /// Probe DNS, proxy, TLS, authentication and latency to the configured provider
+        #[arg(long)]
+        network: bool,
+    },
+
+    /// Show Cascade's debug log for this repository
+    Logs {
+        #[command(subcommand)]
+        action: Option<LogsAction>,
+
+        /// Show only the last N entries
+        #[arg(long, value_name = "N")]
+        tail: Option<usize>,
+
+        /// Show entries newer than a duration (30m, 2h, 1d), date or RFC 3339 time
+        #[arg(long)]
+        since: Option<String>,
+    },
 
     /// Generate shell completions
     Completions {
@@ -101,6 +241,13 @@ pub enum Commands {
     /// Launch interactive TUI for stack management
     Tui,
 
+    /// Learn stacked diffs in a guided walkthrough using a sandbox repository
+    Tutorial {
+        /// Directory for the sandbox (default: a new temporary directory)
+        #[arg(long)]
+        dir: Option<std::path::PathBuf>,
+    },
+
     /// Git hooks management
     Hooks {
         #[command(subcommand)]
@@ -124,6 +271,51 @@ pub enum Commands {
         force: bool,
     },
 
+    /// Show the provider user, their permissions on the repository and the git identity
+    Whoami {
+        /// Print the identity as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check the Bitbucket credentials
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
+
+    /// List and restore backups taken before force pushes
+    Backups {
+        #[command(subcommand)]
+        action: BackupsAction,
+    },
+
+    /// List and restore snapshots of all stacks taken before rebases, lands and resets
+    Snapshots {
+        #[command(subcommand)]
+        action: SnapshotsAction,
+    },
+
+    /// Resume or abort an operation that an earlier run left unfinished
+    Recover {
+        /// Carry on with the interrupted operation
+        #[arg(long, conflicts_with = "abort")]
+        resume: bool,
+        /// Undo the interrupted operation and restore the stacks from before it started
+        #[arg(long)]
+        abort: bool,
+    },
+
+    /// Remove old snapshots, cache files and backups outside the retention policy
+    Gc {
+        /// Show what would be removed without removing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Run silently, as the automatic background gc does
+        #[arg(long, hide = true)]
+        auto: bool,
+    },
+
     // Stack command shortcuts for commonly used operations
     /// Show current stack details
     Stack {
@@ -136,48 +328,45 @@ pub enum Commands {
     },
 
     /// Push current commit to the top of the stack (shortcut for 'stack push')
-    Push {
-        /// Branch name for this commit
-        #[arg(long, short)]
-        branch: Option<String>,
-        /// Commit message (if creating a new commit)
+    Push(commands::push::PushArgs),
+
+    /// Commit and add the commit to the active stack in one step
+    Commit {
+        /// Commit message (opens your editor if not provided)
         #[arg(long, short)]
         message: Option<String>,
-        /// Use specific commit hash instead of HEAD
+        /// Stage modified and deleted tracked files first (like 'git commit -a')
+        #[arg(long, short)]
+        all: bool,
+        /// Amend the top entry instead of adding a new one
         #[arg(long)]
+        amend: bool,
+        /// Branch name for the new entry
+        #[arg(long, short, conflicts_with = "amend")]
+        branch: Option<String>,
+        /// Force-push the amended entry if it has a PR
+        #[arg(long, requires = "amend")]
+        push: bool,
+    },
+
+    /// Cherry-pick a commit from any branch onto the top of the stack as a new entry
+    Pick {
+        /// Commit to pick (hash, branch or other revision)
+        #[arg(required_unless_present_any = ["continue_pick", "abort"])]
         commit: Option<String>,
-        /// Push commits since this reference (e.g., HEAD~3)
-        #[arg(long)]
-        since: Option<String>,
-        /// Push multiple specific commits (comma-separated)
-        #[arg(long)]
-        commits: Option<String>,
-        /// Squash last N commits into one before pushing
-        #[arg(long)]
-        squash: Option<usize>,
-        /// Squash all commits since this reference (e.g., HEAD~5)
-        #[arg(long)]
-        squash_since: Option<String>,
-        /// Auto-create feature branch when pushing from base branch
-        #[arg(long)]
-        auto_branch: bool,
-        /// Allow pushing commits from base branch (not recommended)
-        #[arg(long)]
-        allow_base_branch: bool,
-        /// Show what would be pushed without actually pushing
-        #[arg(long)]
-        dry_run: bool,
-        /// Skip confirmation prompts
-        #[arg(long, short = 'y')]
-        yes: bool,
+        /// Branch name for the new entry
+        #[arg(long, short)]
+        branch: Option<String>,
+        /// Finish a pick after resolving its conflicts
+        #[arg(long = "continue", conflicts_with_all = ["commit", "abort"])]
+        continue_pick: bool,
+        /// Give up a pick that stopped on conflicts
+        #[arg(long, conflicts_with = "commit")]
+        abort: bool,
     },
 
     /// Pop the top commit from the stack (shortcut for 'stack pop')
-    Pop {
-        /// Keep the branch (don't delete it)
-        #[arg(long)]
-        keep_branch: bool,
-    },
+    Pop(commands::stack::PopArgs),
 
     /// Drop (remove) stack entries by position (shortcut for 'stacks drop')
     Drop {
@@ -192,9 +381,6 @@ pub enum Commands {
         /// Skip all confirmation prompts
         #[arg(long, short)]
         force: bool,
-        /// Skip confirmation prompts
-        #[arg(long, short = 'y')]
-        yes: bool,
     },
 
     /// Land (merge) approved stack entries (shortcut for 'stack land')
@@ -254,6 +440,9 @@ pub enum Commands {
         /// Interactive mode for conflict resolution
         #[arg(long, short, global = true)]
         interactive: bool,
+        /// Show what the sync would do without changing anything
+        #[arg(long, global = true)]
+        dry_run: bool,
     },
 
     /// Rebase operations (shortcut for 'stack rebase')
@@ -270,6 +459,34 @@ pub enum Commands {
         /// Rebase strategy to use
         #[arg(long, value_enum, global = true)]
         strategy: Option<RebaseStrategyArg>,
+        /// Drop obsolete entries (position, range or list, e.g. "3" or "2,4"), close
+        /// their PRs and restack the rest
+        #[arg(long, value_name = "N")]
+        skip_entry: Option<String>,
+        /// Show what the rebase would do without changing anything
+        #[arg(long, global = true)]
+        dry_run: bool,
+    },
+
+    /// Start a new feature: create a stack and its working branch, then switch to it
+    Feature {
+        /// Name of the new stack
+        name: String,
+        /// Base branch for the stack (defaults to the repository's default base branch)
+        #[arg(long, short)]
+        base: Option<String>,
+        /// Working branch name (defaults to a name derived from the stack name)
+        #[arg(long)]
+        branch: Option<String>,
+        /// Description of the stack
+        #[arg(long, short)]
+        description: Option<String>,
+        /// Start from a template defined under `templates.<name>` in the configuration
+        #[arg(long, short)]
+        template: Option<String>,
+        /// Don't push the branch or set its upstream
+        #[arg(long)]
+        no_push: bool,
     },
 
     /// Switch to a different stack (shortcut for 'stacks switch')
@@ -318,12 +535,21 @@ pub enum Commands {
         /// Submit range of entries (e.g., "1-3" or "2,4,6")
         #[arg(long)]
         range: Option<String>,
-        /// Create draft pull requests (default: true, use --no-draft to create ready PRs)
-        #[arg(long, default_value_t = true)]
+        /// Create draft pull requests (the default unless cascade.submit_policy.draft_by_default is false)
+        #[arg(long, conflicts_with = "no_draft")]
         draft: bool,
+        /// Create ready (non-draft) pull requests
+        #[arg(long)]
+        no_draft: bool,
         /// Open the PR(s) in your default browser after submission (default: true, use --no-open to disable)
         #[arg(long, default_value_t = true)]
         open: bool,
+        /// Also refresh already submitted entries: force-push, update title/description and reviewers
+        #[arg(long)]
+        update: bool,
+        /// Edit every PR title and description in your editor before submitting
+        #[arg(long)]
+        edit: bool,
     },
 
     /// Validate stack integrity and handle branch modifications (shortcut for 'stacks validate')
@@ -336,6 +562,36 @@ pub enum Commands {
         /// Only validate the active stack (exits 0 if no active stack)
         #[arg(long)]
         current: bool,
+        /// Also compare entry branches with origin and pull request branches with the stack
+        #[arg(long)]
+        deep: bool,
+        /// With --deep, point pull requests whose target branch drifted back at the stack
+        #[arg(long, requires = "deep")]
+        retarget: bool,
+    },
+
+    /// Check the active stack (shortcut for 'stacks check')
+    Check {
+        /// Force check even if there are issues
+        #[arg(long)]
+        force: bool,
+        /// Predict which entries would conflict with the updated base, without rebasing
+        #[arg(long)]
+        conflicts: bool,
+    },
+
+    /// Time status scans, stack loading and provider round-trips for bug reports (hidden)
+    #[command(hide = true)]
+    Perf {
+        /// Times to run each operation
+        #[arg(long, default_value_t = 5)]
+        iterations: usize,
+        /// Skip provider round-trips
+        #[arg(long)]
+        offline: bool,
+        /// Print the report as JSON (for bug reports and CI)
+        #[arg(long)]
+        json: bool,
     },
 
     /// Internal command for shell completion (hidden)
@@ -344,6 +600,20 @@ pub enum Commands {
         #[command(subcommand)]
         action: CompletionHelperAction,
     },
+
+    /// Run the logic of an installed Git hook (internal, called by the hook launchers)
+    #[command(hide = true)]
+    HookExec {
+        /// Git hook name (post-commit, pre-push, commit-msg, pre-commit, prepare-commit-msg)
+        hook: String,
+        /// Arguments git passed to the hook
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Any other command runs the `ca-<name>` plugin from PATH
+    #[command(external_subcommand)]
+    External(Vec<String>),
 }
 
 /// Git hooks actions
@@ -363,10 +633,6 @@ pub enum HooksAction {
         #[arg(long)]
         allow_main_branch: bool,
 
-        /// Skip confirmation prompt
-        #[arg(long, short)]
-        yes: bool,
-
         /// Force installation even if checks fail (not recommended)
         #[arg(long)]
         force: bool,
@@ -397,6 +663,32 @@ pub enum HooksAction {
         /// Hook name (post-commit, pre-push, commit-msg, prepare-commit-msg)
         hook: String,
     },
+
+    /// Run a hook's Cascade logic by hand, e.g. to debug a hook failure
+    ///
+    /// Pre-push reads refs from stdin like git sends them, and otherwise checks a push of
+    /// the current branch; commit-msg checks the HEAD commit's message unless given a file
+    Run {
+        /// Hook name (post-commit, pre-push, commit-msg, pre-commit, prepare-commit-msg)
+        hook: String,
+        /// Show what the hook would do without changing anything or running the original hook
+        #[arg(long)]
+        dry_run: bool,
+        /// Arguments git would pass to the hook
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Report whether the post-commit hook should add a commit to the stack (internal)
+    #[command(hide = true)]
+    ShouldTrack {
+        /// Commit hash created by the commit
+        commit: String,
+    },
+
+    /// Check refs being pushed against stack metadata (internal, reads pre-push stdin)
+    #[command(hide = true)]
+    CheckPush,
 }
 
 /// Visualization actions
@@ -406,7 +698,7 @@ pub enum VizAction {
     Stack {
         /// Stack name (defaults to active stack)
         name: Option<String>,
-        /// Output format (ascii, mermaid, dot, plantuml)
+        /// Output format (ascii, mermaid, dot, plantuml, html, svg)
         #[arg(long, short)]
         format: Option<String>,
         /// Output file path
@@ -418,11 +710,14 @@ pub enum VizAction {
         /// Disable colors
         #[arg(long)]
         no_colors: bool,
+        /// Fetch PR state, approvals and build status from Bitbucket
+        #[arg(long)]
+        live: bool,
     },
 
     /// Show dependency graph of all stacks
     Deps {
-        /// Output format (ascii, mermaid, dot, plantuml)
+        /// Output format (ascii, mermaid, dot, plantuml, html, svg)
         #[arg(long, short)]
         format: Option<String>,
         /// Output file path
@@ -434,6 +729,78 @@ pub enum VizAction {
         /// Disable colors
         #[arg(long)]
         no_colors: bool,
+        /// Fetch PR state, approvals and build status from Bitbucket
+        #[arg(long)]
+        live: bool,
+    },
+
+    /// Show when entries were created, submitted, approved and landed
+    Timeline {
+        /// Stack name (defaults to active stack)
+        name: Option<String>,
+        /// Output format (ascii, mermaid)
+        #[arg(long, short)]
+        format: Option<String>,
+        /// Output file path
+        #[arg(long, short)]
+        output: Option<String>,
+        /// Flag entries with no activity for this many days
+        #[arg(long, default_value_t = 3)]
+        stall_days: u32,
+    },
+}
+
+/// Authentication actions
+#[derive(Debug, Subcommand)]
+pub enum AuthAction {
+    /// Log in through the OAuth device flow instead of pasting a token
+    Login,
+    /// Remove the refresh token kept by `ca auth login`
+    Logout,
+    /// Show the authenticated user and the token's scopes and expiry
+    Status,
+}
+
+/// Backup actions
+#[derive(Debug, Subcommand)]
+pub enum BackupsAction {
+    /// List backups (newest first per branch)
+    List {
+        /// Only show backups of this branch
+        branch: Option<String>,
+    },
+
+    /// Reset a branch to one of its backups
+    Restore {
+        /// Branch to restore
+        branch: String,
+        /// Backup timestamp to restore (defaults to the most recent, see `ca backups list`)
+        #[arg(long)]
+        at: Option<String>,
+    },
+}
+
+/// Snapshot actions
+#[derive(Debug, Subcommand)]
+pub enum SnapshotsAction {
+    /// List snapshots, newest first
+    List,
+
+    /// Put the stacks and their branches back the way a snapshot recorded them
+    Restore {
+        /// Snapshot to restore (defaults to the most recent, see `ca snapshots list`)
+        id: Option<String>,
+    },
+}
+
+/// Log actions
+#[derive(Debug, Subcommand)]
+pub enum LogsAction {
+    /// Write a redacted support archive of the logs, config and environment
+    Bundle {
+        /// Archive path (default: cascade-support-<timestamp>.tar.gz)
+        #[arg(long, short)]
+        output: Option<std::path::PathBuf>,
     },
 }
 
@@ -444,14 +811,14 @@ pub enum CompletionsAction {
     Generate {
         /// Shell to generate completions for
         #[arg(value_enum)]
-        shell: Shell,
+        shell: CompletionShell,
     },
 
     /// Install completions for available shells
     Install {
         /// Specific shell to install for
         #[arg(long, value_enum)]
-        shell: Option<Shell>,
+        shell: Option<CompletionShell>,
     },
 
     /// Show completion installation status
@@ -463,6 +830,16 @@ pub enum CompletionsAction {
 pub enum CompletionHelperAction {
     /// List available stack names
     StackNames,
+    /// List entry numbers of the active stack with a message preview
+    Entries,
+    /// List local branch names
+    Branches,
+    /// List installable hook names
+    Hooks,
+    /// List known configuration keys
+    ConfigKeys,
+    /// List snapshot ids with the operation they were taken before
+    Snapshots,
 }
 
 #[derive(Debug, Subcommand)]
@@ -491,8 +868,79 @@ pub enum ConfigAction {
     },
 }
 
+impl Commands {
+    /// Name of the operation this command runs if it rewrites branches, recorded while
+    /// it runs so an interrupted run can be recovered. Dry runs and `pop --restore-last`,
+    /// the undo of a pop, aren't tracked so they still run while a recovery is pending
+    fn tracked_operation(&self) -> Option<&'static str> {
+        match self {
+            Commands::Sync {
+                action: None,
+                dry_run: false,
+                ..
+            }
+            | Commands::Stacks {
+                action: StackAction::Sync { dry_run: false, .. },
+            } => Some("sync"),
+            Commands::Rebase {
+                action: None,
+                dry_run: false,
+                ..
+            }
+            | Commands::Stacks {
+                action: StackAction::Rebase { dry_run: false, .. },
+            } => Some("rebase"),
+            Commands::Land { dry_run: false, .. }
+            | Commands::Stacks {
+                action: StackAction::Land { dry_run: false, .. },
+            } => Some("land"),
+            Commands::Autoland { dry_run: false, .. }
+            | Commands::Stacks {
+                action: StackAction::AutoLand { dry_run: false, .. },
+            } => Some("autoland"),
+            Commands::Pop(args)
+            | Commands::Stacks {
+                action: StackAction::Pop(args),
+            } if !args.dry_run && !args.restore_last => Some("pop"),
+            _ => None,
+        }
+    }
+}
+
 impl Cli {
+    /// Whether the command was asked for JSON output, so a failure is reported as JSON too
+    pub fn json_output(&self) -> bool {
+        match &self.command {
+            Commands::Repo { json, .. }
+            | Commands::Stats { json, .. }
+            | Commands::Query { json, .. }
+            | Commands::Search { json, .. }
+            | Commands::Perf { json, .. }
+            | Commands::Whoami { json } => *json,
+            Commands::Stacks {
+                action: StackAction::List { format, .. },
+            } => matches!(format, Some(commands::stack::ListFormat::Json)),
+            Commands::Stacks {
+                action: StackAction::Approvals { json, .. },
+            } => *json,
+            _ => false,
+        }
+    }
+
+    /// Whether output should be plain, from `--plain` or `CASCADE_PLAIN`
+    pub fn plain_output(&self) -> bool {
+        self.plain
+            || std::env::var(crate::cli::output::PLAIN_ENV)
+                .is_ok_and(|value| !value.is_empty() && value != "0")
+    }
+
     pub async fn run(self) -> Result<()> {
+        Output::set_plain(self.plain_output());
+        if self.no_color {
+            console::set_colors_enabled(false);
+            console::set_colors_enabled_stderr(false);
+        }
+
         // Set up logging based on verbosity
         self.setup_logging();
 
@@ -501,7 +949,74 @@ impl Cli {
         // just like git CLI and other modern dev tools (Graphite, Sapling, Phabricator)
         self.init_git2_ssl()?;
 
-        match self.command {
+        // Git operations run deep inside managers that open their own repositories,
+        // so the override travels through the environment
+        if self.allow_protected {
+            std::env::set_var(crate::git::repository::ALLOW_PROTECTED_ENV, "1");
+        }
+        if self.fetch {
+            std::env::set_var(crate::git::repository::FETCH_POLICY_ENV, "always");
+        } else if self.no_fetch {
+            std::env::set_var(crate::git::repository::FETCH_POLICY_ENV, "never");
+        }
+
+        // Long operations (rebases, fetches) defer Ctrl-C to a point where they can stop cleanly
+        crate::utils::interrupt::install();
+
+        // Commands that are scripted, run from hooks or are about versions themselves skip
+        // the passive update check
+        let check_for_updates = !matches!(
+            self.command,
+            Commands::Version { .. }
+                | Commands::Update { .. }
+                | Commands::Prompt { .. }
+                | Commands::Completions { .. }
+                | Commands::CompletionHelper { .. }
+                | Commands::Hooks { .. }
+                | Commands::HookExec { .. }
+                | Commands::Query { .. }
+                | Commands::External(_)
+        );
+        // Every command run by hand counts towards the automatic gc, except gc itself
+        let count_for_gc = check_for_updates && !matches!(self.command, Commands::Gc { .. });
+
+        // Messages follow the repository's `cascade.locale` setting. An operation a crashed
+        // run left behind is pointed out before anything else runs, and operations that
+        // rewrite the repository record themselves while they run.
+        let repo_root = std::env::current_dir()
+            .ok()
+            .and_then(|dir| crate::git::find_repository_root(&dir).ok());
+        crate::i18n::init(
+            repo_root
+                .as_deref()
+                .and_then(|root| crate::config::get_repo_config_dir(root).ok())
+                .and_then(|dir| {
+                    crate::config::Settings::load_from_file(&dir.join("config.json")).ok()
+                })
+                .and_then(|settings| settings.cascade.locale)
+                .as_deref(),
+        );
+        let operation = self.command.tracked_operation();
+        if let Some(repo_root) = &repo_root {
+            if check_for_updates && !matches!(self.command, Commands::Recover { .. }) {
+                commands::recover::check_interrupted(repo_root, operation)?;
+            }
+        }
+        let tracked_root = match (operation, &repo_root) {
+            (Some(operation), Some(repo_root)) => {
+                let original_branch = crate::git::GitRepository::open(repo_root)
+                    .and_then(|repo| repo.get_current_branch())
+                    .ok();
+                let args = std::env::args().skip(1).collect();
+                crate::stack::OperationState::begin(repo_root, operation, args, original_branch)?;
+                Some(repo_root.clone())
+            }
+            _ => None,
+        };
+
+        let yes = self.yes;
+        crate::cli::prompt::set_assume_yes(yes);
+        let result = match self.command {
             Commands::Init {
                 bitbucket_url,
                 force,
@@ -509,10 +1024,56 @@ impl Cli {
             Commands::Config { action } => commands::config::run(action).await,
             Commands::Stacks { action } => commands::stack::run(action).await,
             Commands::Entry { action } => commands::entry::run(action).await,
-            Commands::Repo => commands::status::run().await,
-            Commands::Version => commands::version::run().await,
+            Commands::Repo { json, refresh } => commands::status::run(json, refresh).await,
+            Commands::Stats { since, json } => commands::stats::run(since, json).await,
+            Commands::Query { selector, json } => commands::query::run(selector, json).await,
+            Commands::Search {
+                query,
+                switch,
+                open,
+                json,
+            } => commands::search::run(query, switch, open, json).await,
+            Commands::Nudge {
+                older_than,
+                all,
+                rerequest,
+                dry_run,
+            } => commands::nudge::run(older_than, all, rerequest, dry_run).await,
+            Commands::Prs {
+                state,
+                mine,
+                needs_review,
+                author,
+                verbose,
+            } => {
+                commands::prs::run(commands::prs::PrFilters {
+                    state,
+                    mine,
+                    needs_review,
+                    author,
+                    verbose,
+                })
+                .await
+            }
+            Commands::Prompt { format } => commands::prompt::run(format).await,
+            Commands::Version { check } => commands::version::run(check).await,
+            Commands::Update { check } => commands::update::run(check, yes).await,
             Commands::Doctor => commands::doctor::run().await,
-            Commands::Diagnose => commands::diagnose::run().await,
+            Commands::Perf {
+                iterations,
+                offline,
+                json,
+            } => commands::perf::run(iterations, offline, json).await,
+            Commands::External(args) => commands::plugin::run(args).await,
+            Commands::Diagnose { network } => commands::diagnose::run(network).await,
+            Commands::Logs {
+                action,
+                tail,
+                since,
+            } => match action {
+                Some(LogsAction::Bundle { output }) => commands::logs::bundle(output).await,
+                None => commands::logs::show(tail, since).await,
+            },
 
             Commands::Completions { action } => match action {
                 CompletionsAction::Generate { shell } => {
@@ -527,15 +1088,39 @@ impl Cli {
             Commands::Setup { force } => commands::setup::run(force).await,
 
             Commands::Tui => commands::tui::run().await,
+            Commands::Tutorial { dir } => commands::tutorial::run(dir, yes).await,
 
             Commands::Cleanup { execute, force } => commands::cleanup::run(execute, force).await,
 
+            Commands::Whoami { json } => commands::whoami::run(json).await,
+
+            Commands::Auth { action } => match action {
+                AuthAction::Login => commands::auth::login().await,
+                AuthAction::Logout => commands::auth::logout().await,
+                AuthAction::Status => commands::auth::status().await,
+            },
+
+            Commands::Backups { action } => match action {
+                BackupsAction::List { branch } => commands::backups::list(branch).await,
+                BackupsAction::Restore { branch, at } => {
+                    commands::backups::restore(branch, at).await
+                }
+            },
+
+            Commands::Snapshots { action } => match action {
+                SnapshotsAction::List => commands::snapshots::list().await,
+                SnapshotsAction::Restore { id } => commands::snapshots::restore(id, yes).await,
+            },
+
+            Commands::Recover { resume, abort } => commands::recover::run(resume, abort, yes).await,
+
+            Commands::Gc { dry_run, auto } => commands::gc::run(dry_run, auto).await,
+
             Commands::Hooks { action } => match action {
                 HooksAction::Install {
                     all,
                     skip_checks,
                     allow_main_branch,
-                    yes,
                     force,
                 } => {
                     if all {
@@ -561,6 +1146,13 @@ impl Cli {
                     force,
                 } => commands::hooks::install_hook_with_options(&hook, skip_checks, force).await,
                 HooksAction::Remove { hook } => commands::hooks::uninstall_hook(&hook).await,
+                HooksAction::Run {
+                    hook,
+                    dry_run,
+                    args,
+                } => commands::hook_exec::run_manually(&hook, args, dry_run).await,
+                HooksAction::ShouldTrack { commit } => commands::hooks::should_track(&commit).await,
+                HooksAction::CheckPush => commands::hooks::check_push().await,
             },
 
             Commands::Viz { action } => match action {
@@ -570,6 +1162,7 @@ impl Cli {
                     output,
                     compact,
                     no_colors,
+                    live,
                 } => {
                     commands::viz::show_stack(
                         name.clone(),
@@ -577,6 +1170,7 @@ impl Cli {
                         output.clone(),
                         compact,
                         no_colors,
+                        live,
                     )
                     .await
                 }
@@ -585,12 +1179,28 @@ impl Cli {
                     output,
                     compact,
                     no_colors,
+                    live,
                 } => {
                     commands::viz::show_dependencies(
                         format.clone(),
                         output.clone(),
                         compact,
                         no_colors,
+                        live,
+                    )
+                    .await
+                }
+                VizAction::Timeline {
+                    name,
+                    format,
+                    output,
+                    stall_days,
+                } => {
+                    commands::viz::show_timeline(
+                        name.clone(),
+                        format.clone(),
+                        output.clone(),
+                        stall_days,
                     )
                     .await
                 }
@@ -600,43 +1210,49 @@ impl Cli {
                 commands::stack::show(verbose, mergeable).await
             }
 
-            Commands::Push {
-                branch,
+            Commands::Push(args) => commands::stack::push(args.try_into()?).await,
+
+            Commands::Commit {
                 message,
-                commit,
-                since,
-                commits,
-                squash,
-                squash_since,
-                auto_branch,
-                allow_base_branch,
-                dry_run,
-                yes,
+                all,
+                amend,
+                branch,
+                push,
             } => {
-                commands::stack::push(
-                    branch,
+                commands::commit::run(commands::commit::CommitOptions {
                     message,
+                    all,
+                    amend,
+                    branch,
+                    push,
+                    yes,
+                })
+                .await
+            }
+
+            Commands::Pick {
+                commit,
+                branch,
+                continue_pick,
+                abort,
+            } => {
+                commands::pick::run(commands::pick::PickOptions {
                     commit,
-                    since,
-                    commits,
-                    squash,
-                    squash_since,
-                    auto_branch,
-                    allow_base_branch,
-                    dry_run,
+                    branch,
+                    continue_pick,
+                    abort,
                     yes,
-                )
+                })
                 .await
             }
 
-            Commands::Pop { keep_branch } => commands::stack::pop(keep_branch).await,
+            Commands::Pop(args) => commands::stack::pop(args).await,
 
             Commands::Drop {
                 entry,
                 keep_branch,
                 keep_pr,
                 force,
-                yes,
             } => commands::stack::drop(entry, keep_branch, keep_pr, force, yes).await,
 
             Commands::Land {
@@ -676,10 +1292,11 @@ impl Cli {
                 force,
                 cleanup,
                 interactive,
+                dry_run,
             } => match action {
                 Some(SyncAction::Continue) => commands::stack::continue_sync().await,
                 Some(SyncAction::Abort) => commands::stack::abort_sync().await,
-                None => commands::stack::sync(force, cleanup, interactive).await,
+                None => commands::stack::sync(force, cleanup, interactive, dry_run).await,
             },
 
             Commands::Rebase {
@@ -687,12 +1304,25 @@ impl Cli {
                 interactive,
                 onto,
                 strategy,
+                skip_entry,
+                dry_run,
             } => match action {
                 Some(RebaseAction::Continue) => commands::stack::continue_rebase().await,
                 Some(RebaseAction::Abort) => commands::stack::abort_rebase().await,
-                None => commands::stack::rebase(interactive, onto, strategy).await,
+                None => {
+                    commands::stack::rebase(interactive, onto, strategy, skip_entry, dry_run).await
+                }
             },
 
+            Commands::Feature {
+                name,
+                base,
+                branch,
+                description,
+                template,
+                no_push,
+            } => commands::stack::feature(name, base, branch, description, template, no_push).await,
+
             Commands::Switch { name } => commands::stack::switch(name).await,
 
             Commands::Conflicts {
@@ -718,7 +1348,10 @@ impl Cli {
                 description,
                 range,
                 draft,
+                no_draft,
                 open,
+                update,
+                edit,
             } => {
                 // Delegate to the stacks submit functionality
                 let submit_action = StackAction::Submit {
@@ -727,19 +1360,53 @@ impl Cli {
                     description,
                     range,
                     draft,
+                    no_draft,
                     open,
+                    update,
+                    edit,
                 };
                 commands::stack::run(submit_action).await
             }
 
-            Commands::Validate { name, fix, current } => {
+            Commands::Validate {
+                name,
+                fix,
+                current,
+                deep,
+                retarget,
+            } => {
                 // Delegate to the stacks validate functionality
-                let validate_action = StackAction::Validate { name, fix, current };
+                let validate_action = StackAction::Validate {
+                    name,
+                    fix,
+                    current,
+                    deep,
+                    retarget,
+                };
                 commands::stack::run(validate_action).await
             }
 
+            Commands::Check { force, conflicts } => {
+                commands::stack::run(StackAction::Check { force, conflicts }).await
+            }
+
             Commands::CompletionHelper { action } => handle_completion_helper(action).await,
+            Commands::HookExec { hook, args } => commands::hook_exec::run(hook, args).await,
+        };
+
+        if let Some(repo_root) = &tracked_root {
+            if let Err(e) = crate::stack::OperationState::delete(repo_root) {
+                tracing::warn!("Could not clear the operation record: {}", e);
+            }
         }
+
+        if result.is_ok() && check_for_updates {
+            commands::update::notify_if_due().await;
+        }
+        if result.is_ok() && count_for_gc {
+            commands::gc::run_if_due();
+        }
+        result
     }
 
     /// Initialize git2 to use system certificates by default
@@ -859,44 +1526,97 @@ impl Cli {
     }
 
     fn setup_logging(&self) {
+        use tracing_subscriber::filter::{LevelFilter, Targets};
+        use tracing_subscriber::prelude::*;
+
         let level = if self.verbose {
             tracing::Level::DEBUG
         } else {
             tracing::Level::INFO
         };
 
-        let subscriber = tracing_subscriber::fmt()
-            .with_max_level(level)
+        let terminal = tracing_subscriber::fmt::layer()
             .with_target(false)
-            .without_time();
+            .without_time()
+            .with_ansi(!self.no_color && !Output::is_plain())
+            .with_filter(LevelFilter::from_level(level));
+
+        // Cascade's own DEBUG events always reach the repository log (see `ca logs`);
+        // dependencies only contribute warnings
+        let file = std::env::current_dir()
+            .ok()
+            .and_then(|dir| crate::git::find_repository_root(&dir).ok())
+            .and_then(|root| crate::utils::logging::open_repo_log(&root))
+            .map(|log| {
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_current_span(false)
+                    .with_span_list(false)
+                    .with_writer(std::sync::Mutex::new(log))
+                    .with_filter(
+                        Targets::new()
+                            .with_target("cascade_cli", tracing::Level::DEBUG)
+                            .with_default(tracing::Level::WARN),
+                    )
+            });
 
-        if self.no_color {
-            subscriber.with_ansi(false).init();
-        } else {
-            subscriber.init();
-        }
+        tracing_subscriber::registry()
+            .with(terminal)
+            .with(file)
+            .init();
     }
 }
 
 /// Handle completion helper commands
 async fn handle_completion_helper(action: CompletionHelperAction) -> Result<()> {
-    match action {
-        CompletionHelperAction::StackNames => {
-            use crate::git::find_repository_root;
-            use crate::stack::StackManager;
-            use std::env;
-
-            // Try to get stack names, but silently fail if not in a repository
-            if let Ok(current_dir) = env::current_dir() {
-                if let Ok(repo_root) = find_repository_root(&current_dir) {
-                    if let Ok(manager) = StackManager::new(&repo_root) {
-                        for (_, name, _, _, _) in manager.list_stacks() {
-                            println!("{name}");
-                        }
-                    }
-                }
-            }
-            Ok(())
-        }
+    // Completion must never print errors into the user's prompt, so values that can't be
+    // looked up (e.g. outside a repository) are silently skipped
+    let values = match action {
+        CompletionHelperAction::StackNames => commands::completions::stack_name_values(),
+        CompletionHelperAction::Entries => commands::completions::entry_values(),
+        CompletionHelperAction::Branches => commands::completions::branch_values(),
+        CompletionHelperAction::Hooks => commands::completions::hook_values(),
+        CompletionHelperAction::ConfigKeys => commands::completions::config_key_values(),
+        CompletionHelperAction::Snapshots => commands::completions::snapshot_values(),
+    };
+    for value in values {
+        Output::data(value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The command tree is deep enough that building its parser overflows the default
+    /// test thread stack in debug builds
+    fn tracked(args: &[&str]) -> Option<&'static str> {
+        let args: Vec<String> = std::iter::once("ca")
+            .chain(args.iter().copied())
+            .map(String::from)
+            .collect();
+        std::thread::Builder::new()
+            .stack_size(16 * 1024 * 1024)
+            .spawn(move || {
+                Cli::try_parse_from(args)
+                    .expect("arguments should parse")
+                    .command
+                    .tracked_operation()
+            })
+            .unwrap()
+            .join()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_dry_runs_and_pop_restore_are_not_tracked() {
+        assert_eq!(tracked(&["sync"]), Some("sync"));
+        assert_eq!(tracked(&["pop"]), Some("pop"));
+
+        assert_eq!(tracked(&["sync", "--dry-run"]), None);
+        assert_eq!(tracked(&["stacks", "sync", "--dry-run"]), None);
+        assert_eq!(tracked(&["pop", "--dry-run"]), None);
+        assert_eq!(tracked(&["pop", "--restore-last"]), None);
     }
 }
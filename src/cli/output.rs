@@ -1,5 +1,15 @@
+// The one place output is printed; everything else goes through `Output`
+#![allow(clippy::print_stdout)]
+
+use crate::errors::ErrorReport;
 use console::{style, Color, Emoji, Style};
 use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Environment variable that turns on plain output, like `--plain`
+pub const PLAIN_ENV: &str = "CASCADE_PLAIN";
+
+static PLAIN: AtomicBool = AtomicBool::new(false);
 
 /// Theme configuration for Cascade CLI
 /// Matches the branding: black, gray, green palette
@@ -39,74 +49,244 @@ impl Theme {
 pub struct Output;
 
 impl Output {
+    /// Switch plain output on or off. Plain output has no color, emoji, box drawing or
+    /// animation: every message is one line of text a screen reader or a dumb terminal
+    /// can follow, with words in place of the status symbols.
+    pub fn set_plain(plain: bool) {
+        PLAIN.store(plain, Ordering::Relaxed);
+        if plain {
+            console::set_colors_enabled(false);
+            console::set_colors_enabled_stderr(false);
+        }
+    }
+
+    /// Whether plain output is on
+    pub fn is_plain() -> bool {
+        PLAIN.load(Ordering::Relaxed)
+    }
+
+    /// `symbol`, or the message `word` in plain output
+    fn mark(symbol: &str, word: &str) -> String {
+        if Self::is_plain() {
+            crate::t!(word)
+        } else {
+            symbol.to_string()
+        }
+    }
+
+    /// `message` as text, with emoji and box drawing replaced in plain output
+    fn text<T: Display>(message: T) -> String {
+        let text = message.to_string();
+        if Self::is_plain() {
+            plain_text(&text)
+        } else {
+            text
+        }
+    }
+
+    /// Print a line as it is (emoji and box drawing are replaced in plain output)
+    pub fn line<T: Display>(message: T) {
+        println!("{}", Self::text(message));
+    }
+
+    /// Print machine-readable output (JSON, a URL, a completion script) exactly as
+    /// given, whatever the output mode
+    pub fn data<T: Display>(data: T) {
+        println!("{data}");
+    }
+
+    /// Theme for interactive prompts: colored, or dialoguer's plain one in plain output
+    pub fn prompt_theme() -> Box<dyn dialoguer::theme::Theme> {
+        if Self::is_plain() {
+            Box::new(dialoguer::theme::SimpleTheme)
+        } else {
+            Box::new(dialoguer::theme::ColorfulTheme::default())
+        }
+    }
+
     /// Print a success message with checkmark (bright bold green)
     pub fn success<T: Display>(message: T) {
-        println!("{} {}", Theme::success_style().apply_to("✓"), message);
+        println!(
+            "{} {}",
+            Theme::success_style().apply_to(Self::mark("✓", "output-plain-success")),
+            Self::text(message)
+        );
     }
 
     /// Print an error message with X mark
     pub fn error<T: Display>(message: T) {
-        println!("{} {}", style("✗").fg(Theme::ERROR), message);
+        println!(
+            "{} {}",
+            style(Self::mark("✗", "output-plain-error")).fg(Theme::ERROR),
+            Self::text(message)
+        );
+    }
+
+    /// Print a failed command's error to stderr: code and summary, the rest of the
+    /// message, then the hints. With `json` it goes to stdout as `{"error": {...}}`.
+    pub fn error_report(report: &ErrorReport, json: bool) {
+        if json {
+            #[derive(serde::Serialize)]
+            struct JsonError<'a> {
+                error: &'a ErrorReport,
+            }
+            if let Ok(json) = serde_json::to_string_pretty(&JsonError { error: report }) {
+                println!("{json}");
+            }
+            return;
+        }
+
+        let header = crate::t!("error-header", code = report.code);
+        if Self::is_plain() {
+            eprintln!("{} {}", header, plain_text(&report.summary));
+        } else {
+            eprintln!(
+                "{} {} {}",
+                style("✗").fg(Theme::ERROR),
+                style(header).fg(Theme::ERROR).bold(),
+                report.summary
+            );
+        }
+        if let Some(details) = &report.details {
+            eprintln!();
+            for line in details.lines() {
+                eprintln!("  {}", Self::text(line));
+            }
+        }
+        if !report.hints.is_empty() {
+            eprintln!();
+        }
+        for hint in &report.hints {
+            eprintln!(
+                "{} {}",
+                Theme::tip_style().apply_to(crate::t!("output-tip")),
+                Theme::dim_style().apply_to(Self::text(hint))
+            );
+        }
     }
 
     /// Print a warning message with warning emoji
     pub fn warning<T: Display>(message: T) {
-        println!("{} {}", style("⚠").fg(Theme::WARNING), message);
+        println!(
+            "{} {}",
+            style(Self::mark("⚠", "output-plain-warning")).fg(Theme::WARNING),
+            Self::text(message)
+        );
     }
 
     /// Print an info message with info emoji (muted green)
     pub fn info<T: Display>(message: T) {
-        println!("{} {}", Theme::info_style().apply_to("ℹ"), message);
+        println!(
+            "{} {}",
+            Theme::info_style().apply_to(Self::mark("ℹ", "output-plain-info")),
+            Self::text(message)
+        );
     }
 
     /// Print a sub-item with arrow prefix
     pub fn sub_item<T: Display>(message: T) {
-        println!("  {} {}", Theme::dim_style().apply_to("→"), message);
+        println!(
+            "  {} {}",
+            Theme::dim_style().apply_to(if Self::is_plain() { "-" } else { "→" }),
+            Self::text(message)
+        );
     }
 
     /// Print a bullet point
     pub fn bullet<T: Display>(message: T) {
-        println!("  {} {}", Theme::dim_style().apply_to("•"), message);
+        println!(
+            "  {} {}",
+            Theme::dim_style().apply_to(if Self::is_plain() { "*" } else { "•" }),
+            Self::text(message)
+        );
     }
 
     /// Print a section header
     pub fn section<T: Display>(title: T) {
-        println!("\n{}", style(title).bold().underlined());
+        println!("\n{}", style(Self::text(title)).bold().underlined());
     }
 
     /// Print a tip/suggestion (muted green)
     pub fn tip<T: Display>(message: T) {
         println!(
             "{} {}",
-            Theme::tip_style().apply_to("TIP:"),
-            Theme::dim_style().apply_to(message)
+            Theme::tip_style().apply_to(crate::t!("output-tip")),
+            Theme::dim_style().apply_to(Self::text(message))
         );
     }
 
     /// Print progress indicator (muted green)
     pub fn progress<T: Display>(message: T) {
-        print!("{} {}", Theme::info_style().apply_to("→"), message);
+        print!(
+            "{} {}",
+            Theme::info_style().apply_to(if Self::is_plain() { "-" } else { "→" }),
+            Self::text(message)
+        );
         use std::io::{self, Write};
         io::stdout().flush().unwrap();
     }
 
+    /// Print the start of a line the next message finishes
+    pub fn inline<T: Display>(message: T) {
+        print!("{}", Self::text(message));
+        use std::io::{self, Write};
+        io::stdout().flush().ok();
+    }
+
     /// Print success checkmark inline (for use after progress)
     pub fn success_inline() {
-        println!(" {}", Theme::success_style().apply_to("✓"));
+        println!(
+            " {}",
+            Theme::success_style().apply_to(Self::mark("✓", "output-plain-done"))
+        );
     }
 
     /// Print error cross inline (for use after progress)
     pub fn error_inline<T: Display>(message: T) {
+        let mark = style(Self::mark("✗", "output-plain-failed")).fg(Theme::ERROR);
         if message.to_string().is_empty() {
-            println!(" {}", style("✗").fg(Theme::ERROR));
+            println!(" {mark}");
         } else {
-            println!(" {} {}", style("✗").fg(Theme::ERROR), message);
+            println!(" {} {}", mark, Self::text(message));
+        }
+    }
+
+    /// Lay out rows as left-aligned columns two spaces apart (the first row is the header)
+    pub fn table_lines(rows: &[Vec<String>]) -> Vec<String> {
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|i| {
+                rows.iter()
+                    .filter_map(|cells| cells.get(i))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        rows.iter()
+            .map(|cells| {
+                let line = cells
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{cell:<width$}"))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    /// Print a table laid out by [`Self::table_lines`]
+    pub fn table(rows: &[Vec<String>]) {
+        for line in Self::table_lines(rows) {
+            println!("{}", Self::text(line));
         }
     }
 
     /// Print a divider line
     pub fn divider() {
-        println!("{}", Theme::dim_style().apply_to("─".repeat(50)));
+        let rule = if Self::is_plain() { "-" } else { "─" };
+        println!("{}", Theme::dim_style().apply_to(rule.repeat(50)));
     }
 
     /// Print stack information in a formatted way
@@ -120,26 +300,31 @@ impl Output {
         // Show as info, not success (we're viewing, not creating)
         println!(
             "{} {}",
-            Theme::info_style().apply_to("Stack:"),
-            style(name).bold()
+            Theme::info_style().apply_to(crate::t!("output-stack")),
+            style(Self::text(name)).bold()
         );
-        Self::sub_item(format!("Stack ID: {}", Theme::dim_style().apply_to(id)));
-        Self::sub_item(format!(
-            "Base branch: {}",
-            Theme::info_style().apply_to(base_branch)
+        Self::sub_item(crate::t!(
+            "output-stack-id",
+            id = Theme::dim_style().apply_to(id).to_string()
+        ));
+        Self::sub_item(crate::t!(
+            "output-base-branch",
+            branch = Theme::info_style().apply_to(base_branch).to_string()
         ));
 
         if let Some(working) = working_branch {
-            Self::sub_item(format!(
-                "Working branch: {}",
-                Theme::info_style().apply_to(working)
+            Self::sub_item(crate::t!(
+                "output-working-branch",
+                branch = Theme::info_style().apply_to(working).to_string()
             ));
         }
 
         if is_active {
-            Self::sub_item(format!(
-                "Status: {}",
-                Theme::success_style().apply_to("Active")
+            Self::sub_item(crate::t!(
+                "output-status",
+                status = Theme::success_style()
+                    .apply_to(crate::t!("output-status-active"))
+                    .to_string()
             ));
         }
     }
@@ -147,7 +332,7 @@ impl Output {
     /// Print next steps guidance
     pub fn next_steps(steps: &[&str]) {
         println!();
-        Self::tip("Next steps:");
+        Self::tip(crate::t!("output-next-steps"));
         for step in steps {
             Self::bullet(step);
         }
@@ -155,22 +340,34 @@ impl Output {
 
     /// Print a command example
     pub fn command_example<T: Display>(command: T) {
-        println!("  {}", style(command).fg(Theme::WARNING));
+        println!("  {}", style(Self::text(command)).fg(Theme::WARNING));
     }
 
     /// Print a check start message
     pub fn check_start<T: Display>(message: T) {
-        println!("\n{} {}", style("🔍").bright(), style(message).bold());
+        if Self::is_plain() {
+            println!("\n{}", plain_text(&message.to_string()));
+        } else {
+            println!("\n{} {}", style("🔍").bright(), style(message).bold());
+        }
     }
 
     /// Print a solution message
     pub fn solution<T: Display>(message: T) {
-        println!("     {}: {}", style("Solution").fg(Theme::WARNING), message);
+        println!(
+            "     {}: {}",
+            style(crate::t!("output-solution")).fg(Theme::WARNING),
+            Self::text(message)
+        );
     }
 
     /// Print a numbered item (muted green)
     pub fn numbered_item<T: Display>(number: usize, message: T) {
-        println!("  {}. {}", Theme::info_style().apply_to(number), message);
+        println!(
+            "  {}. {}",
+            Theme::info_style().apply_to(number),
+            Self::text(message)
+        );
     }
 
     /// Print empty line for spacing
@@ -184,13 +381,66 @@ impl Output {
     /// - merged: Bright green (completed!)
     pub fn entry_status(is_submitted: bool, is_merged: bool) -> String {
         if is_merged {
-            format!("{}", Theme::success_style().apply_to("[merged]"))
+            format!(
+                "{}",
+                Theme::success_style().apply_to(crate::t!("entry-status-merged"))
+            )
         } else if is_submitted {
-            format!("{}", Theme::info_style().apply_to("[submitted]"))
+            format!(
+                "{}",
+                Theme::info_style().apply_to(crate::t!("entry-status-submitted"))
+            )
         } else {
-            format!("{}", style("[pending]").fg(Theme::WARNING))
+            format!(
+                "{}",
+                style(crate::t!("entry-status-pending")).fg(Theme::WARNING)
+            )
+        }
+    }
+}
+
+/// `text` without emoji (or the space after one), with box drawing as `-`, `|` and `+`
+/// and arrows and bullets spelled in ASCII
+pub fn plain_text(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '✓' | '✔' => plain.push_str("OK"),
+            '✗' | '✘' => plain.push('X'),
+            '→' | '➜' | '➡' | '⟶' => plain.push_str("->"),
+            '←' | '⟵' => plain.push_str("<-"),
+            '•' => plain.push('*'),
+            '─' | '━' | '┄' | '┅' | '┈' | '┉' | '╌' | '╍' | '═' => {
+                plain.push('-')
+            }
+            '│' | '┃' | '┆' | '┇' | '┊' | '┋' | '╎' | '╏' | '║' => {
+                plain.push('|')
+            }
+            '\u{2500}'..='\u{257F}' => plain.push('+'),
+            '\u{2580}'..='\u{259F}' => plain.push('#'),
+            '\u{FE0F}' | '\u{200D}' => {}
+            c if is_emoji(c) => {
+                while chars
+                    .next_if(|next| matches!(next, ' ' | '\u{FE0F}'))
+                    .is_some()
+                {}
+            }
+            c => plain.push(c),
         }
     }
+    plain
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c,
+        '\u{2139}'
+            | '\u{2300}'..='\u{23FF}'
+            | '\u{2600}'..='\u{27BF}'
+            | '\u{2B00}'..='\u{2BFF}'
+            | '\u{1F000}'..='\u{1FAFF}'
+    )
 }
 
 /// Emojis for different contexts
@@ -207,3 +457,26 @@ impl Emojis {
     pub const UPLOAD: Emoji<'_, '_> = Emoji("📤", "UPLOAD");
     pub const STACK: Emoji<'_, '_> = Emoji("📊", "STACK");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text() {
+        assert_eq!(plain_text("🚀 Landing stack"), "Landing stack");
+        assert_eq!(
+            plain_text("⚠️  Conflicts in 2 files:"),
+            "Conflicts in 2 files:"
+        );
+        assert_eq!(
+            plain_text("   Status: ✅ No rebase in progress"),
+            "   Status: No rebase in progress"
+        );
+        assert_eq!(plain_text("main → feature"), "main -> feature");
+        assert_eq!(plain_text("  • add-b"), "  * add-b");
+        assert_eq!(plain_text("✓ Done"), "OK Done");
+        assert_eq!(plain_text("┌──┐\n│ab│\n└──┘"), "+--+\n|ab|\n+--+");
+        assert_eq!(plain_text("Grüße, naïve café"), "Grüße, naïve café");
+    }
+}
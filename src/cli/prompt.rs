@@ -0,0 +1,230 @@
+//! Questions asked of the user
+//!
+//! Every confirmation, choice and text prompt goes through a [`Prompter`]. The one commands
+//! use, [`prompter()`], asks on the terminal with dialoguer. With `--yes` it answers for the
+//! user instead: confirmations, choices and text fields take their defaults, so a
+//! confirmation that defaults to no (deleting a branch with unpushed commits, say) is still
+//! declined. Without a terminal and without `--yes`, a prompt fails with a hint rather than
+//! waiting for input that can't come.
+
+use crate::cli::output::Output;
+use crate::errors::{CascadeError, Result};
+use dialoguer::{Confirm, Input, MultiSelect, Password, Select};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Check on a line of text, giving the reason it's refused
+pub type Validator = fn(&str) -> std::result::Result<(), &'static str>;
+
+/// Answer every prompt from here on as `--yes` would
+pub fn set_assume_yes(yes: bool) {
+    ASSUME_YES.store(yes, Ordering::Relaxed);
+}
+
+/// Whether `--yes` was given
+pub fn assume_yes() -> bool {
+    ASSUME_YES.load(Ordering::Relaxed)
+}
+
+/// The prompter for this process: the terminal, honoring `--yes`
+pub fn prompter() -> TerminalPrompter {
+    TerminalPrompter::new(assume_yes())
+}
+
+/// Something that can answer questions for a command
+pub trait Prompter {
+    /// A yes/no question
+    fn confirm(&self, prompt: &str, default: bool) -> Result<bool>;
+
+    /// Pick one of `items`
+    fn select(&self, prompt: &str, items: &[String], default: usize) -> Result<usize>;
+
+    /// Pick any number of `items`; `defaults` says which start out picked
+    fn multi_select(&self, prompt: &str, items: &[String], defaults: &[bool])
+        -> Result<Vec<usize>>;
+
+    /// A line of text, starting out as `initial` and accepted once `validate` passes
+    fn input(&self, prompt: &str, initial: &str, validate: Validator) -> Result<String>;
+
+    /// A secret, not echoed, which may be empty. `--yes` can't answer it.
+    fn password(&self, prompt: &str) -> Result<String>;
+}
+
+/// Asks on the terminal, or answers with the defaults under `--yes`
+pub struct TerminalPrompter {
+    assume_yes: bool,
+    interactive: bool,
+}
+
+impl TerminalPrompter {
+    pub fn new(assume_yes: bool) -> Self {
+        Self {
+            assume_yes,
+            interactive: std::io::stdin().is_terminal(),
+        }
+    }
+
+    /// Fail a prompt that nobody is there to answer
+    fn require_terminal(&self, prompt: &str) -> Result<()> {
+        if self.interactive {
+            return Ok(());
+        }
+        Err(CascadeError::validation(format!(
+            "Cannot ask \"{}\" without a terminal",
+            prompt.trim_end_matches(['?', ':'])
+        ))
+        .with_hint("Pass --yes to accept the default answers"))
+    }
+
+    /// Note the answer `--yes` gave, so the output still shows what was decided
+    fn answered(prompt: &str, answer: &str) {
+        Output::sub_item(format!("{prompt} {answer} (--yes)"));
+    }
+}
+
+fn input_error(e: dialoguer::Error) -> CascadeError {
+    CascadeError::config(format!("Failed to get user input: {e}"))
+}
+
+impl Prompter for TerminalPrompter {
+    fn confirm(&self, prompt: &str, default: bool) -> Result<bool> {
+        if self.assume_yes {
+            Self::answered(prompt, if default { "yes" } else { "no" });
+            return Ok(default);
+        }
+        self.require_terminal(prompt)?;
+        Confirm::with_theme(&*Output::prompt_theme())
+            .with_prompt(prompt)
+            .default(default)
+            .interact()
+            .map_err(input_error)
+    }
+
+    fn select(&self, prompt: &str, items: &[String], default: usize) -> Result<usize> {
+        if self.assume_yes {
+            if let Some(item) = items.get(default) {
+                Self::answered(prompt, item);
+            }
+            return Ok(default);
+        }
+        self.require_terminal(prompt)?;
+        Select::with_theme(&*Output::prompt_theme())
+            .with_prompt(prompt)
+            .items(items)
+            .default(default)
+            .interact()
+            .map_err(input_error)
+    }
+
+    fn multi_select(
+        &self,
+        prompt: &str,
+        items: &[String],
+        defaults: &[bool],
+    ) -> Result<Vec<usize>> {
+        if self.assume_yes {
+            let chosen: Vec<usize> = (0..items.len())
+                .filter(|&i| defaults.get(i).copied().unwrap_or(false))
+                .collect();
+            Self::answered(prompt, &format!("{} selected", chosen.len()));
+            return Ok(chosen);
+        }
+        self.require_terminal(prompt)?;
+        MultiSelect::with_theme(&*Output::prompt_theme())
+            .with_prompt(prompt)
+            .items(items)
+            .defaults(defaults)
+            .interact()
+            .map_err(input_error)
+    }
+
+    fn input(&self, prompt: &str, initial: &str, validate: Validator) -> Result<String> {
+        if self.assume_yes && !initial.is_empty() && validate(initial).is_ok() {
+            Self::answered(prompt, initial);
+            return Ok(initial.to_string());
+        }
+        self.require_terminal(prompt)?;
+        Input::with_theme(&*Output::prompt_theme())
+            .with_prompt(prompt)
+            .with_initial_text(initial)
+            .allow_empty(true)
+            .validate_with(|input: &String| validate(input))
+            .interact_text()
+            .map_err(input_error)
+    }
+
+    fn password(&self, prompt: &str) -> Result<String> {
+        self.require_terminal(prompt)?;
+        Password::with_theme(&*Output::prompt_theme())
+            .with_prompt(prompt)
+            .allow_empty_password(true)
+            .interact()
+            .map_err(input_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<String> {
+        vec![
+            "stash".to_string(),
+            "discard".to_string(),
+            "abort".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_assume_yes_answers_with_defaults() {
+        let prompter = TerminalPrompter {
+            assume_yes: true,
+            interactive: false,
+        };
+        assert!(prompter.confirm("Pop these 2 entries?", true).unwrap());
+        assert_eq!(prompter.select("Choose an action", &items(), 2).unwrap(), 2);
+        assert_eq!(
+            prompter
+                .multi_select("Commits to push", &items(), &[true, false, true])
+                .unwrap(),
+            vec![0, 2]
+        );
+        let any = |_: &str| Ok(());
+        assert_eq!(prompter.input("Your name", "Ada", any).unwrap(), "Ada");
+        assert!(prompter.input("Your email", "", any).is_err());
+        let url = |input: &str| match input.starts_with("https://") {
+            true => Ok(()),
+            false => Err("URL must start with https://"),
+        };
+        assert!(prompter.input("Server URL", "bitbucket", url).is_err());
+        assert!(prompter.password("Passphrase").is_err());
+    }
+
+    #[test]
+    fn test_assume_yes_keeps_a_confirmation_that_defaults_to_no() {
+        let prompter = TerminalPrompter {
+            assume_yes: true,
+            interactive: false,
+        };
+        assert!(!prompter
+            .confirm("Do you want to proceed with deleting this branch?", false)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_no_terminal_without_yes_fails_with_hint() {
+        let prompter = TerminalPrompter {
+            assume_yes: false,
+            interactive: false,
+        };
+        let error = prompter.confirm("Pop these 2 entries?", true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Cannot ask \"Pop these 2 entries\" without a terminal"
+        );
+        assert!(error.hints().iter().any(|hint| hint.contains("--yes")));
+        assert!(prompter.select("Choose an action", &items(), 0).is_err());
+    }
+}
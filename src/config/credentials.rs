@@ -0,0 +1,114 @@
+//! Secrets kept by git's credential helper
+//!
+//! Proxy passwords and OAuth refresh tokens never go into `.cascade/config.json`, and
+//! the Bitbucket API reuses whatever the helper already holds for the server.
+//! They are handed to whatever `credential.helper` git uses (macOS Keychain, Windows
+//! Credential Manager, libsecret), keyed by protocol, host and username.
+
+use crate::errors::{CascadeError, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use url::Url;
+
+/// What a secret is kept under
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialKey {
+    pub protocol: String,
+    /// Host name, with `:port` when it isn't the default
+    pub host: String,
+    pub username: Option<String>,
+}
+
+impl CredentialKey {
+    /// Key for the host and username of `url`
+    pub fn from_url(url: &Url) -> Self {
+        let host = url.host_str().unwrap_or_default();
+        Self {
+            protocol: url.scheme().to_string(),
+            host: match url.port() {
+                Some(port) => format!("{host}:{port}"),
+                None => host.to_string(),
+            },
+            username: Some(url.username())
+                .filter(|name| !name.is_empty())
+                .map(str::to_string),
+        }
+    }
+
+    fn request(&self) -> String {
+        let mut request = format!("protocol={}\nhost={}\n", self.protocol, self.host);
+        if let Some(username) = &self.username {
+            request.push_str(&format!("username={username}\n"));
+        }
+        request
+    }
+}
+
+/// Whether git has a credential helper that can keep secrets
+pub fn helper_configured() -> bool {
+    Command::new("git")
+        .args(["config", "--get", "credential.helper"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| !String::from_utf8_lossy(&output.stdout).trim().is_empty())
+}
+
+/// Username and secret the helper holds for `key`, without prompting
+pub fn fill(key: &CredentialKey) -> Option<(String, String)> {
+    let mut request = key.request();
+    request.push('\n');
+    let response = run("fill", &request)?;
+
+    let field = |name: &str| {
+        response
+            .lines()
+            .find_map(|line| line.strip_prefix(&format!("{name}=")))
+            .map(str::to_string)
+    };
+    Some((field("username")?, field("password")?))
+}
+
+/// Keep `secret` under `key` in the helper
+pub fn approve(key: &CredentialKey, secret: &str) -> Result<()> {
+    if !helper_configured() {
+        return Err(CascadeError::config(
+            "No git credential helper is configured to keep secrets.\n\
+             Set one, e.g. 'git config --global credential.helper osxkeychain', 'manager' \
+             or 'libsecret'",
+        ));
+    }
+    let mut request = key.request();
+    request.push_str(&format!("password={secret}\n\n"));
+    run("approve", &request)
+        .map(|_| ())
+        .ok_or_else(|| CascadeError::config("git credential approve failed"))
+}
+
+/// Forget the secret kept under `key`
+pub fn reject(key: &CredentialKey) {
+    let mut request = key.request();
+    request.push('\n');
+    let _ = run("reject", &request);
+}
+
+fn run(action: &str, input: &str) -> Option<String> {
+    // Only stored credentials: no terminal, askpass or credential manager prompts
+    let mut child = Command::new("git")
+        .args(["-c", "core.askPass=", "credential", action])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GCM_INTERACTIVE", "never")
+        .env_remove("GIT_ASKPASS")
+        .env_remove("SSH_ASKPASS")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(input.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
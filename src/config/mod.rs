@@ -1,8 +1,16 @@
 pub mod auth;
+pub mod credentials;
+pub mod proxy;
 pub mod settings;
 
 pub use auth::{AuthConfig, AuthManager};
-pub use settings::{BitbucketConfig, CascadeConfig, CascadeSettings, GitConfig, Settings};
+pub use settings::{
+    AutoSync, BackupSettings, BitbucketConfig, BuildSettings, CascadeConfig, CascadeSettings,
+    GcSettings, GitConfig, HostCredentials, HostKeyChecking, LandMode, MockSettings,
+    NetworkSettings, NudgeSettings, OAuthSettings, ProviderKind, RemovedPrAction, Settings,
+    SquashMessage, SshSettings, StackTemplate, SubmitPolicy, UpdateCheck, CONFIG_KEYS,
+    TEMPLATE_FIELDS,
+};
 
 use crate::errors::{CascadeError, Result};
 use crate::git::GitRepository;
@@ -111,6 +119,17 @@ pub fn initialize_repo(repo_path: &Path, bitbucket_url: Option<String>) -> Resul
 
     settings.save_to_file(&config_dir.join("config.json"))?;
 
+    // Checkouts and rebases of deeply nested files fail past 260 characters on Windows
+    // unless libgit2 and git are told to use long paths
+    #[cfg(windows)]
+    if let Ok(mut config) = git2::Repository::open(repo_path).and_then(|repo| repo.config()) {
+        if config.get_bool("core.longpaths").is_err() {
+            if let Err(e) = config.set_bool("core.longpaths", true) {
+                tracing::debug!("Could not enable core.longpaths: {}", e);
+            }
+        }
+    }
+
     tracing::debug!("Initialized Cascade repository at {}", repo_path.display());
     Ok(())
 }
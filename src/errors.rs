@@ -60,6 +60,33 @@ pub enum CascadeError {
     /// Validation errors
     #[error("{0}")]
     Validation(String),
+
+    /// Bitbucket API errors
+    #[error("{0}")]
+    Bitbucket(String),
+
+    /// An error with the steps that fix it
+    #[error("{error}")]
+    WithHints {
+        error: Box<CascadeError>,
+        hints: Vec<String>,
+    },
+}
+
+/// How an error is shown to the user, as text or as JSON
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ErrorReport {
+    /// Stable code identifying the kind of failure, e.g. `CA303`
+    pub code: &'static str,
+    /// Kind of failure, e.g. `auth`
+    pub kind: &'static str,
+    /// First paragraph of the message
+    pub summary: String,
+    /// Rest of the message, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// What to run or check next
+    pub hints: Vec<String>,
 }
 
 impl CascadeError {
@@ -85,6 +112,14 @@ impl CascadeError {
 
     pub fn not_initialized<S: Into<String>>(msg: S) -> Self {
         CascadeError::config(msg.into())
+            .with_hint("Run 'ca init' to set up Cascade in this repository")
+    }
+
+    /// The command needs an active stack and the current branch isn't part of one
+    pub fn no_active_stack() -> Self {
+        CascadeError::config("No active stack (current branch doesn't belong to any stack)")
+            .with_hint("Switch to a stack with 'ca switch <name>'")
+            .with_hint("Or start a new one with 'ca feature <name>'")
     }
 
     pub fn invalid_operation<S: Into<String>>(msg: S) -> Self {
@@ -96,16 +131,163 @@ impl CascadeError {
     }
 
     pub fn bitbucket_api(status: u16, message: String) -> Self {
-        CascadeError::Conflict(format!("Bitbucket API error: {status} - {message}"))
+        CascadeError::Bitbucket(format!("Bitbucket API error: {status} - {message}"))
     }
 
     pub fn bitbucket<S: Into<String>>(msg: S) -> Self {
-        CascadeError::Conflict(msg.into())
+        CascadeError::Bitbucket(msg.into())
+    }
+
+    /// Attach a step that fixes the error, shown after the message
+    pub fn with_hint<S: Into<String>>(self, hint: S) -> Self {
+        match self {
+            CascadeError::WithHints { error, mut hints } => {
+                hints.push(hint.into());
+                CascadeError::WithHints { error, hints }
+            }
+            error => CascadeError::WithHints {
+                error: Box::new(error),
+                hints: vec![hint.into()],
+            },
+        }
+    }
+
+    /// The error without any attached hints
+    pub fn inner(&self) -> &CascadeError {
+        match self {
+            CascadeError::WithHints { error, .. } => error.inner(),
+            error => error,
+        }
+    }
+
+    /// Stable code for the kind of failure, listed in the user manual
+    pub fn code(&self) -> &'static str {
+        if self.is_lock_error() {
+            return "CA105";
+        }
+        match self.inner() {
+            CascadeError::Git(_) => "CA100",
+            CascadeError::Branch(_) => "CA101",
+            CascadeError::Rebase(_) => "CA102",
+            CascadeError::Conflict(_) => "CA103",
+            CascadeError::Corruption(_) => "CA104",
+            CascadeError::Config(_) => "CA200",
+            CascadeError::Validation(_) => "CA201",
+            CascadeError::MissingDependency(_) => "CA202",
+            CascadeError::Network(_) => "CA300",
+            CascadeError::Http(_) => "CA301",
+            CascadeError::Url(_) => "CA302",
+            CascadeError::Auth(_) => "CA303",
+            CascadeError::RateLimit(_) => "CA304",
+            CascadeError::Bitbucket(_) => "CA305",
+            CascadeError::Io(_) => "CA400",
+            CascadeError::Json(_) => "CA401",
+            CascadeError::WithHints { .. } => unreachable!("inner() unwraps hints"),
+        }
+    }
+
+    /// Short name for the kind of failure
+    pub fn kind(&self) -> &'static str {
+        if self.is_lock_error() {
+            return "lock";
+        }
+        match self.inner() {
+            CascadeError::Git(_) => "git",
+            CascadeError::Branch(_) => "branch",
+            CascadeError::Rebase(_) => "rebase",
+            CascadeError::Conflict(_) => "conflict",
+            CascadeError::Corruption(_) => "corruption",
+            CascadeError::Config(_) => "config",
+            CascadeError::Validation(_) => "validation",
+            CascadeError::MissingDependency(_) => "missing_dependency",
+            CascadeError::Network(_) => "network",
+            CascadeError::Http(_) => "http",
+            CascadeError::Url(_) => "url",
+            CascadeError::Auth(_) => "auth",
+            CascadeError::RateLimit(_) => "rate_limit",
+            CascadeError::Bitbucket(_) => "bitbucket",
+            CascadeError::Io(_) => "io",
+            CascadeError::Json(_) => "json",
+            CascadeError::WithHints { .. } => unreachable!("inner() unwraps hints"),
+        }
+    }
+
+    /// First paragraph of the message
+    pub fn summary(&self) -> String {
+        let message = self.to_string();
+        let (summary, _) = split_message(&message);
+        summary.to_string()
+    }
+
+    /// Attached hints, then the usual fix for this kind of failure
+    pub fn hints(&self) -> Vec<String> {
+        let mut hints = match self {
+            CascadeError::WithHints { hints, .. } => hints.clone(),
+            _ => Vec::new(),
+        };
+        let default = if self.is_lock_error() {
+            Some(
+                "Another git process is using the repository. Wait for it to finish, or \
+                 remove .git/index.lock if none is running",
+            )
+        } else {
+            match self.inner() {
+                CascadeError::Conflict(_) => {
+                    Some("Run 'ca conflicts' to see the conflicting files")
+                }
+                CascadeError::Corruption(_) => Some(
+                    "Run 'ca validate' to check the stacks, or 'ca snapshots restore' to \
+                     put back the last snapshot",
+                ),
+                CascadeError::Rebase(_) => {
+                    Some("Run 'ca stacks rebase-status' to see where the rebase stopped")
+                }
+                CascadeError::MissingDependency(_) => {
+                    Some("Install the missing tool and make sure it's on your PATH")
+                }
+                CascadeError::Network(_) | CascadeError::Http(_) => {
+                    Some("Run 'ca diagnose --network' to check the connection to the server")
+                }
+                CascadeError::Url(_) => {
+                    Some("Check the server URL with 'ca config get bitbucket.url'")
+                }
+                CascadeError::Auth(_) => Some(
+                    "Run 'ca auth status' to check the credentials, or 'ca auth login' to \
+                     set them again",
+                ),
+                CascadeError::RateLimit(_) => {
+                    Some("The server is rate limiting requests; wait a minute and try again")
+                }
+                CascadeError::Bitbucket(_) => {
+                    Some("Run 'ca doctor' to check the Bitbucket configuration")
+                }
+                _ => None,
+            }
+        };
+        if let Some(default) = default {
+            if !hints.iter().any(|hint| hint == default) {
+                hints.push(default.to_string());
+            }
+        }
+        hints
+    }
+
+    /// Everything shown to the user about this error
+    pub fn report(&self) -> ErrorReport {
+        let message = self.to_string();
+        let (summary, details) = split_message(&message);
+        ErrorReport {
+            code: self.code(),
+            kind: self.kind(),
+            summary: summary.to_string(),
+            details: details.map(str::to_string),
+            hints: self.hints(),
+        }
     }
 
     /// Check if this error originated from git index lock contention.
     pub fn is_lock_error(&self) -> bool {
-        match self {
+        match self.inner() {
             CascadeError::Git(e) => crate::utils::git_lock::is_lock_error(e),
             CascadeError::Branch(msg) | CascadeError::Config(msg) | CascadeError::Rebase(msg) => {
                 msg.contains("index is locked") || msg.contains("index.lock")
@@ -116,3 +298,63 @@ impl CascadeError {
 }
 
 pub type Result<T> = std::result::Result<T, CascadeError>;
+
+/// The first paragraph of `message` and the rest, if there is more
+fn split_message(message: &str) -> (&str, Option<&str>) {
+    let message = message.trim();
+    match message.split_once("\n\n") {
+        Some((summary, details)) => (
+            summary.trim(),
+            Some(details.trim()).filter(|d| !d.is_empty()),
+        ),
+        None => (message, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_splits_message_and_adds_hints() {
+        let error = CascadeError::config(
+            "No in-progress sync found.\n\nThe sync may have already completed.",
+        )
+        .with_hint("Run 'ca sync' to start a new sync");
+        let report = error.report();
+        assert_eq!(report.code, "CA200");
+        assert_eq!(report.kind, "config");
+        assert_eq!(report.summary, "No in-progress sync found.");
+        assert_eq!(
+            report.details.as_deref(),
+            Some("The sync may have already completed.")
+        );
+        assert_eq!(report.hints, vec!["Run 'ca sync' to start a new sync"]);
+        assert_eq!(
+            error.to_string(),
+            "No in-progress sync found.\n\nThe sync may have already completed."
+        );
+    }
+
+    #[test]
+    fn test_default_hints_and_codes() {
+        let error = CascadeError::auth("Token rejected");
+        assert_eq!(error.code(), "CA303");
+        assert_eq!(error.hints().len(), 1);
+        assert!(error.hints()[0].contains("ca auth status"));
+
+        let error = CascadeError::bitbucket_api(404, "Not found".into()).with_hint("Check the id");
+        assert_eq!(error.code(), "CA305");
+        assert_eq!(error.hints()[0], "Check the id");
+        assert_eq!(error.hints().len(), 2);
+
+        let error = CascadeError::branch("Unable to create 'x/.git/index.lock': File exists");
+        assert_eq!(error.code(), "CA105");
+        assert!(matches!(
+            error.with_hint("one").with_hint("two").inner(),
+            CascadeError::Branch(_)
+        ));
+
+        assert!(CascadeError::validation("Bad input").hints().is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+//! Backup refs recorded before Cascade overwrites a branch
+//!
+//! Force pushes that would drop remote commits first save the old commit under
+//! `refs/cascade/backup/<branch>/<timestamp>`. Keeping them out of `refs/heads` hides
+//! them from branch listings while `ca backups` can still find and restore them.
+
+use crate::config::BackupSettings;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::collections::HashMap;
+
+/// Namespace holding all backup refs
+pub const BACKUP_REF_PREFIX: &str = "refs/cascade/backup/";
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// A saved copy of a branch head
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupRef {
+    /// Full ref name, e.g. `refs/cascade/backup/feature/auth/20250101T120000Z`
+    pub refname: String,
+    /// Branch the backup was taken of
+    pub branch: String,
+    /// When the backup was taken
+    pub created_at: DateTime<Utc>,
+    /// Commit the backup points at
+    pub commit_hash: String,
+}
+
+impl BackupRef {
+    /// Parse a backup ref; returns `None` for refs outside the backup namespace
+    pub fn parse(refname: &str, commit_hash: String) -> Option<Self> {
+        let rest = refname.strip_prefix(BACKUP_REF_PREFIX)?;
+        let (branch, timestamp) = rest.rsplit_once('/')?;
+        let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+            .ok()?
+            .and_utc();
+
+        Some(Self {
+            refname: refname.to_string(),
+            branch: branch.to_string(),
+            created_at,
+            commit_hash,
+        })
+    }
+
+    /// Timestamp component of the ref name, accepted by `ca backups restore --at`
+    pub fn timestamp(&self) -> String {
+        self.created_at.format(TIMESTAMP_FORMAT).to_string()
+    }
+}
+
+/// Ref name for a new backup of `branch` taken at `at`
+pub fn backup_ref_name(branch: &str, at: DateTime<Utc>) -> String {
+    format!(
+        "{BACKUP_REF_PREFIX}{branch}/{}",
+        at.format(TIMESTAMP_FORMAT)
+    )
+}
+
+/// Backups that fall outside the retention policy: older than `max_age_days` (when
+/// non-zero), or beyond the newest `keep_per_branch` backups of their branch. The newest
+/// backup of a branch is always kept, even with a hand-edited `keep_per_branch` of 0.
+pub fn prunable_backups<'a>(
+    backups: &'a [BackupRef],
+    policy: &BackupSettings,
+    now: DateTime<Utc>,
+) -> Vec<&'a BackupRef> {
+    let mut by_branch: HashMap<&str, Vec<&BackupRef>> = HashMap::new();
+    for backup in backups {
+        by_branch.entry(&backup.branch).or_default().push(backup);
+    }
+
+    let mut prunable = Vec::new();
+    for (_, mut branch_backups) in by_branch {
+        branch_backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+        for (i, backup) in branch_backups.into_iter().enumerate() {
+            let expired = policy.max_age_days > 0
+                && (now - backup.created_at).num_days() >= i64::from(policy.max_age_days);
+            if expired || i >= policy.keep_per_branch.max(1) {
+                prunable.push(backup);
+            }
+        }
+    }
+
+    prunable.sort_by(|a, b| a.refname.cmp(&b.refname));
+    prunable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn backup(branch: &str, at: DateTime<Utc>) -> BackupRef {
+        BackupRef {
+            refname: backup_ref_name(branch, at),
+            branch: branch.to_string(),
+            created_at: at,
+            commit_hash: "0".repeat(40),
+        }
+    }
+
+    #[test]
+    fn test_backup_ref_round_trip() {
+        let at = Utc.with_ymd_and_hms(2025, 3, 14, 9, 26, 53).unwrap();
+        let refname = backup_ref_name("feature/auth", at);
+        assert_eq!(refname, "refs/cascade/backup/feature/auth/20250314T092653Z");
+
+        let parsed = BackupRef::parse(&refname, "abc".to_string()).unwrap();
+        assert_eq!(parsed.branch, "feature/auth");
+        assert_eq!(parsed.created_at, at);
+        assert_eq!(parsed.timestamp(), "20250314T092653Z");
+
+        assert!(BackupRef::parse("refs/heads/feature/auth", String::new()).is_none());
+        assert!(BackupRef::parse("refs/cascade/backup/feature/latest", String::new()).is_none());
+    }
+
+    #[test]
+    fn test_prunable_backups() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+        let backups = vec![
+            backup("a", now - Duration::days(1)),
+            backup("a", now - Duration::days(2)),
+            backup("a", now - Duration::days(3)),
+            backup("b", now - Duration::days(40)),
+            backup("c", now - Duration::hours(1)),
+        ];
+        let policy = BackupSettings {
+            keep_per_branch: 2,
+            max_age_days: 30,
+        };
+
+        let prunable = prunable_backups(&backups, &policy, now);
+        assert_eq!(prunable, vec![&backups[2], &backups[3]]);
+
+        // Age limit of zero keeps backups regardless of age
+        let policy = BackupSettings {
+            keep_per_branch: 5,
+            max_age_days: 0,
+        };
+        assert!(prunable_backups(&backups, &policy, now).is_empty());
+
+        let policy = BackupSettings {
+            keep_per_branch: 0,
+            max_age_days: 0,
+        };
+        assert_eq!(
+            prunable_backups(&backups, &policy, now),
+            vec![&backups[2], &backups[1]]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::errors::Result;
+use crate::errors::{CascadeError, Result};
 use crate::git::GitRepository;
 use serde::{Deserialize, Serialize};
 
@@ -21,15 +21,39 @@ pub struct BranchInfo {
     pub upstream: Option<UpstreamInfo>,
 }
 
+/// Placeholders understood by `git.branch_template`
+pub const BRANCH_TEMPLATE_PLACEHOLDERS: [&str; 4] = ["{user}", "{stack}", "{slug}", "{n}"];
+
+/// Stack details substituted into a branch template
+#[derive(Debug, Clone, Default)]
+pub struct BranchNameContext {
+    /// Name of the stack the branch belongs to
+    pub stack: Option<String>,
+    /// 1-based position of the entry in its stack
+    pub entry_number: usize,
+    /// Put in front of the generated name, e.g. a stack's `hotfix/` prefix
+    pub prefix: Option<String>,
+}
+
 /// Manages branch operations and metadata
 pub struct BranchManager {
     git_repo: GitRepository,
+    branch_template: Option<String>,
 }
 
 impl BranchManager {
     /// Create a new BranchManager
     pub fn new(git_repo: GitRepository) -> Self {
-        Self { git_repo }
+        Self {
+            git_repo,
+            branch_template: None,
+        }
+    }
+
+    /// Generate branch names from a `git.branch_template` instead of the default scheme
+    pub fn with_branch_template(mut self, template: Option<String>) -> Self {
+        self.branch_template = template;
+        self
     }
 
     /// Get information about all branches
@@ -112,34 +136,66 @@ impl BranchManager {
 
     /// Generate a safe branch name from a commit message
     pub fn generate_branch_name(&self, message: &str) -> String {
-        let base_name = message
-            .to_lowercase()
-            .chars()
-            .map(|c| match c {
-                'a'..='z' | '0'..='9' => c,
-                _ => '-',
-            })
-            .collect::<String>()
-            .split('-')
-            .filter(|s| !s.is_empty())
-            .take(5) // Limit to first 5 words
-            .collect::<Vec<_>>()
-            .join("-");
+        let base_name = slugify(message, Some(5)); // Limit to first 5 words
 
         // Ensure the branch name is unique
+        let mut candidate = self.unique_branch_name(&base_name);
+
+        // Ensure it starts with a letter
+        if candidate.chars().next().is_none_or(|c| !c.is_alphabetic()) {
+            candidate = format!("feature-{candidate}");
+        }
+
+        candidate
+    }
+
+    /// Generate a branch name for a stack entry, following `git.branch_template` when set
+    /// and starting with the context's prefix
+    pub fn generate_branch_name_for(&self, message: &str, context: &BranchNameContext) -> String {
+        let prefix = context.prefix.as_deref().unwrap_or_default();
+        let name = match &self.branch_template {
+            None if prefix.is_empty() => return self.generate_branch_name(message),
+            None => slugify(message, Some(5)),
+            Some(template) => {
+                let (name, email) = self.git_repo.get_user_info();
+                let user = email
+                    .as_deref()
+                    .and_then(|email| email.split('@').next())
+                    .or(name.as_deref())
+                    .unwrap_or("");
+
+                render_branch_template(
+                    template,
+                    user,
+                    context.stack.as_deref().unwrap_or(""),
+                    message,
+                    context.entry_number,
+                )
+            }
+        };
+        let rendered = format!("{prefix}{name}");
+
+        if !is_valid_branch_name(&rendered) {
+            tracing::warn!(
+                "Generated branch name '{}' is invalid; using the default scheme",
+                rendered
+            );
+            return self.generate_branch_name(message);
+        }
+
+        self.unique_branch_name(&rendered)
+    }
+
+    /// Append a counter to `base_name` until it doesn't clash with an existing branch
+    fn unique_branch_name(&self, base_name: &str) -> String {
         let mut counter = 1;
-        let mut candidate = base_name.clone();
+        let mut candidate = base_name.to_string();
 
         while self.git_repo.branch_exists(&candidate) {
             candidate = format!("{base_name}-{counter}");
             counter += 1;
         }
 
-        // Ensure it starts with a letter
-        if candidate.chars().next().is_none_or(|c| !c.is_alphabetic()) {
-            candidate = format!("feature-{candidate}");
-        }
-
         candidate
     }
 
@@ -176,6 +232,91 @@ impl BranchManager {
     }
 }
 
+/// Lowercase `text` and replace anything other than ASCII letters and digits with
+/// single dashes, keeping at most `max_words` words
+fn slugify(text: &str, max_words: Option<usize>) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' => c,
+            _ => '-',
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .take(max_words.unwrap_or(usize::MAX))
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Fill in a branch template.
+///
+/// `{user}` and `{stack}` are slugified, `{slug}` is the slugified first five words of
+/// `message` (or `change` when it has none) and `{n}` is the entry number. Path segments
+/// left empty by missing values are dropped.
+pub fn render_branch_template(
+    template: &str,
+    user: &str,
+    stack: &str,
+    message: &str,
+    entry_number: usize,
+) -> String {
+    let slug = match slugify(message, Some(5)) {
+        slug if slug.is_empty() => "change".to_string(),
+        slug => slug,
+    };
+
+    template
+        .replace("{user}", &slugify(user, None))
+        .replace("{stack}", &slugify(stack, None))
+        .replace("{slug}", &slug)
+        .replace("{n}", &entry_number.to_string())
+        .split('/')
+        .map(|segment| segment.trim_matches(|c| c == '-' || c == '.' || c == '_'))
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Check that a branch template only uses known placeholders and yields valid branch names
+pub fn validate_branch_template(template: &str) -> Result<()> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..].find('}').map(|i| start + i).ok_or_else(|| {
+            CascadeError::config(format!(
+                "Unclosed placeholder in branch template: {template}"
+            ))
+        })?;
+        let placeholder = &rest[start..=end];
+        if !BRANCH_TEMPLATE_PLACEHOLDERS.contains(&placeholder) {
+            return Err(CascadeError::config(format!(
+                "Unknown placeholder {placeholder} in branch template (supported: {})",
+                BRANCH_TEMPLATE_PLACEHOLDERS.join(", ")
+            )));
+        }
+        rest = &rest[end + 1..];
+    }
+
+    if !template.contains("{slug}") && !template.contains("{n}") {
+        return Err(CascadeError::config(
+            "Branch template must include {slug} or {n} so each entry gets its own branch",
+        ));
+    }
+
+    let sample = render_branch_template(template, "user", "stack", "sample change", 1);
+    if !is_valid_branch_name(&sample) {
+        return Err(CascadeError::config(format!(
+            "Branch template produces invalid branch names (e.g. '{sample}')"
+        )));
+    }
+
+    Ok(())
+}
+
+fn is_valid_branch_name(name: &str) -> bool {
+    !name.is_empty() && git2::Reference::is_valid_name(&format!("refs/heads/{name}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -293,4 +434,74 @@ mod tests {
         assert_eq!(remote, "origin");
         assert_eq!(branch, "feature-auth");
     }
+
+    #[test]
+    fn test_branch_template_rendering() {
+        assert_eq!(
+            render_branch_template(
+                "{user}/{stack}/{slug}-{n}",
+                "Jane.Doe",
+                "Payments API",
+                "Fix rounding in invoice totals for EU customers",
+                3
+            ),
+            "jane-doe/payments-api/fix-rounding-in-invoice-totals-3"
+        );
+
+        // Missing values drop their path segment
+        assert_eq!(
+            render_branch_template("{user}/{slug}", "", "", "!!!", 1),
+            "change"
+        );
+
+        assert!(validate_branch_template("{user}/{stack}/{slug}-{n}").is_ok());
+        assert!(validate_branch_template("feature/{stack}").is_err());
+        assert!(validate_branch_template("{user}/{ticket}-{slug}").is_err());
+        assert!(validate_branch_template("{slug").is_err());
+        assert!(validate_branch_template("bad..name/{slug}").is_err());
+    }
+
+    #[test]
+    fn test_branch_template_collisions() {
+        let (_temp_dir, branch_manager) = create_test_branch_manager();
+        let branch_manager =
+            branch_manager.with_branch_template(Some("team/{stack}/{slug}".to_string()));
+        let context = BranchNameContext {
+            stack: Some("auth".to_string()),
+            entry_number: 1,
+            prefix: None,
+        };
+
+        let first = branch_manager.generate_branch_name_for("Add login", &context);
+        assert_eq!(first, "team/auth/add-login");
+
+        branch_manager
+            .git_repo()
+            .create_branch(&first, None)
+            .unwrap();
+        assert_eq!(
+            branch_manager.generate_branch_name_for("Add login", &context),
+            "team/auth/add-login-1"
+        );
+    }
+
+    #[test]
+    fn test_branch_name_prefix() {
+        let (_temp_dir, branch_manager) = create_test_branch_manager();
+        let context = BranchNameContext {
+            stack: Some("outage".to_string()),
+            entry_number: 1,
+            prefix: Some("hotfix/".to_string()),
+        };
+        assert_eq!(
+            branch_manager.generate_branch_name_for("Fix login crash", &context),
+            "hotfix/fix-login-crash"
+        );
+
+        let branch_manager = branch_manager.with_branch_template(Some("{stack}-{n}".to_string()));
+        assert_eq!(
+            branch_manager.generate_branch_name_for("Fix login crash", &context),
+            "hotfix/outage-1"
+        );
+    }
 }
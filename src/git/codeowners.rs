@@ -0,0 +1,183 @@
+//! Code owners of the files in a repository, from its CODEOWNERS file
+//!
+//! The file is looked for where GitHub and Bitbucket look: `.github/`, `.bitbucket/`, the
+//! repository root and `docs/`. Each line is a gitignore-style pattern followed by owners;
+//! the last line matching a path decides its owners, and a pattern without owners leaves
+//! the path unowned.
+
+use crate::errors::{CascadeError, Result};
+use std::path::{Path, PathBuf};
+
+/// Places a CODEOWNERS file is read from, first found wins
+pub const CODEOWNERS_PATHS: &[&str] = &[
+    ".github/CODEOWNERS",
+    ".bitbucket/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    /// Path segments to match, `**` standing for any number of them
+    segments: Vec<String>,
+    /// Whether the pattern only matches from the repository root
+    anchored: bool,
+    owners: Vec<String>,
+}
+
+/// The rules of a CODEOWNERS file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+    /// File the rules were read from, relative to the repository root
+    pub source: PathBuf,
+}
+
+impl CodeOwners {
+    /// Read the repository's CODEOWNERS file, if it has one
+    pub fn load(repo_root: &Path) -> Result<Option<Self>> {
+        for relative in CODEOWNERS_PATHS {
+            let path = repo_root.join(relative);
+            if !path.is_file() {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                CascadeError::config(format!("Failed to read {}: {e}", path.display()))
+            })?;
+            let mut owners = Self::parse(&content);
+            owners.source = PathBuf::from(relative);
+            return Ok(Some(owners));
+        }
+        Ok(None)
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            // Bitbucket's `[Section]` headers only group rules
+            .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('['))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let pattern = fields.next()?;
+                let owners = fields
+                    .take_while(|field| !field.starts_with('#'))
+                    .map(str::to_string)
+                    .collect();
+                Some(Rule::new(pattern, owners))
+            })
+            .collect();
+        Self {
+            rules,
+            source: PathBuf::new(),
+        }
+    }
+
+    /// Owners of `path` (relative to the repository root, `/`-separated); empty when no
+    /// rule gives it any
+    pub fn owners(&self, path: &str) -> &[String] {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(&segments))
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+impl Rule {
+    fn new(pattern: &str, owners: Vec<String>) -> Self {
+        let trimmed = pattern.trim_end_matches('/');
+        // As in gitignore, a slash anywhere but at the end ties the pattern to the root
+        let anchored = trimmed.contains('/');
+        let segments = trimmed
+            .trim_start_matches('/')
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        Self {
+            segments,
+            anchored,
+            owners,
+        }
+    }
+
+    /// Whether the rule covers a file: the pattern matches the path itself or one of the
+    /// directories it's in
+    fn matches(&self, path: &[&str]) -> bool {
+        let starts: Vec<usize> = if self.anchored {
+            vec![0]
+        } else {
+            (0..path.len()).collect()
+        };
+        starts
+            .into_iter()
+            .any(|start| match_prefix(&self.segments, &path[start..]))
+    }
+}
+
+/// Whether `pattern` matches `path` or a leading run of its segments
+fn match_prefix(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => true,
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| match_prefix(rest, &path[skip..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, remaining)) => {
+                match_segment(first.as_bytes(), segment.as_bytes()) && match_prefix(rest, remaining)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Match one path segment against a pattern with `*` and `?`
+fn match_segment(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| match_segment(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && match_segment(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && match_segment(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_matching_rule_wins() {
+        let owners = CodeOwners::parse(
+            "# Default owners\n\
+             *                @core\n\
+             *.md             @docs # prose\n\
+             /src/api/        @api-team @alice\n\
+             src/**/tests/    @qa\n\
+             build            @infra\n\
+             /src/api/gen.rs\n",
+        );
+
+        assert_eq!(owners.owners("main.rs"), ["@core"]);
+        assert_eq!(owners.owners("docs/guide.md"), ["@docs"]);
+        assert_eq!(owners.owners("src/api/handler.rs"), ["@api-team", "@alice"]);
+        assert_eq!(owners.owners("src/api/v2/tests/routes.rs"), ["@qa"]);
+        assert_eq!(owners.owners("tools/build/run.sh"), ["@infra"]);
+        assert_eq!(owners.owners("lib/api/handler.rs"), ["@core"]);
+        assert!(owners.owners("src/api/gen.rs").is_empty());
+    }
+
+    #[test]
+    fn test_load_finds_github_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(CodeOwners::load(temp_dir.path()).unwrap(), None);
+
+        std::fs::create_dir(temp_dir.path().join(".github")).unwrap();
+        std::fs::write(temp_dir.path().join(".github/CODEOWNERS"), "* @core\n").unwrap();
+        let owners = CodeOwners::load(temp_dir.path()).unwrap().unwrap();
+        assert_eq!(owners.source, PathBuf::from(".github/CODEOWNERS"));
+        assert_eq!(owners.owners("a.rs"), ["@core"]);
+    }
+}
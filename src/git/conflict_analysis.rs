@@ -19,6 +19,8 @@ pub enum ConflictType {
     ContentOverlap,
     /// Complex conflicts requiring manual resolution
     Complex,
+    /// Both sides moved a submodule to different commits
+    SubmodulePointer,
 }
 
 /// Difficulty level for resolving a conflict
@@ -186,12 +188,20 @@ impl ConflictAnalyzer {
         let mut auto_resolvable_count = 0;
         let mut manual_resolution_files = Vec::new();
 
-        for file_path in conflicted_files {
-            let full_path = repo_path.join(file_path);
-            let content = std::fs::read_to_string(&full_path)
-                .map_err(|e| CascadeError::config(format!("Failed to read {file_path}: {e}")))?;
+        let submodule_conflicts = submodule_conflicts(repo_path);
 
-            let analysis = self.analyze_file(file_path, &content)?;
+        for file_path in conflicted_files {
+            let analysis = match submodule_conflicts.get(file_path) {
+                // A submodule is a directory on disk with no conflict markers to parse
+                Some((ours, theirs)) => self.analyze_submodule_conflict(file_path, ours, theirs),
+                None => {
+                    let full_path = repo_path.join(file_path);
+                    let content = std::fs::read_to_string(&full_path).map_err(|e| {
+                        CascadeError::config(format!("Failed to read {file_path}: {e}"))
+                    })?;
+                    self.analyze_file(file_path, &content)?
+                }
+            };
 
             total_conflicts += analysis.conflicts.len();
             auto_resolvable_count += analysis
@@ -218,6 +228,41 @@ impl ConflictAnalyzer {
         })
     }
 
+    /// Describe a conflicting submodule pointer (`ours`/`theirs` are commit ids, empty
+    /// when that side deleted the submodule)
+    pub fn analyze_submodule_conflict(
+        &self,
+        file_path: &str,
+        ours: &str,
+        theirs: &str,
+    ) -> FileConflictAnalysis {
+        let conflict_type = ConflictType::SubmodulePointer;
+        let conflict = ConflictRegion {
+            file_path: file_path.to_string(),
+            start_pos: 0,
+            end_pos: 0,
+            start_line: 0,
+            end_line: 0,
+            our_content: ours.to_string(),
+            their_content: theirs.to_string(),
+            difficulty: self.assess_difficulty(&conflict_type, ours, theirs),
+            suggested_strategy: ResolutionStrategy::Custom(format!(
+                "Check out the intended commit in {file_path}, then 'git add {file_path}'"
+            )),
+            context: self.generate_context(&conflict_type, ours, theirs),
+            conflict_type,
+        };
+
+        let overall_difficulty = self.assess_overall_difficulty(std::slice::from_ref(&conflict));
+        FileConflictAnalysis {
+            file_path: file_path.to_string(),
+            conflicts: vec![conflict],
+            overall_difficulty,
+            auto_resolvable: false,
+            conflict_summary: HashMap::from([(ConflictType::SubmodulePointer, 1)]),
+        }
+    }
+
     /// Parse conflict markers from file content
     fn parse_conflict_markers(
         &self,
@@ -371,6 +416,7 @@ impl ConflictAnalyzer {
             ConflictType::Structural => ConflictDifficulty::Medium,
             ConflictType::ContentOverlap => ConflictDifficulty::Medium,
             ConflictType::Complex => ConflictDifficulty::Hard,
+            ConflictType::SubmodulePointer => ConflictDifficulty::Medium,
         }
     }
 
@@ -399,6 +445,7 @@ impl ConflictAnalyzer {
             ConflictType::Structural => ResolutionStrategy::Manual,
             ConflictType::ContentOverlap => ResolutionStrategy::Manual,
             ConflictType::Complex => ResolutionStrategy::Manual,
+            ConflictType::SubmodulePointer => ResolutionStrategy::Manual,
         }
     }
 
@@ -425,6 +472,20 @@ impl ConflictAnalyzer {
             }
             ConflictType::ContentOverlap => "Overlapping changes to the same content".to_string(),
             ConflictType::Complex => "Complex conflicts requiring manual review".to_string(),
+            ConflictType::SubmodulePointer => {
+                let short = |id: &str| {
+                    if id.is_empty() {
+                        "deleted".to_string()
+                    } else {
+                        id[..id.len().min(8)].to_string()
+                    }
+                };
+                format!(
+                    "Submodule pointer conflict: ours {} vs theirs {}",
+                    short(our_content),
+                    short(their_content)
+                )
+            }
         }
     }
 
@@ -589,6 +650,11 @@ impl ConflictAnalyzer {
                         "🔍 {count} complex conflicts require manual resolution"
                     ));
                 }
+                ConflictType::SubmodulePointer => {
+                    recommendations.push(format!(
+                        "🔗 {count} submodule pointer conflicts: check out the right commit in each submodule and 'git add' it"
+                    ));
+                }
                 _ => {}
             }
         }
@@ -597,6 +663,48 @@ impl ConflictAnalyzer {
     }
 }
 
+/// Conflicted index entries that are submodules (gitlinks), keyed by path, with the
+/// commit recorded on each side
+fn submodule_conflicts(repo_path: &std::path::Path) -> HashMap<String, (String, String)> {
+    const GITLINK_MODE: u32 = 0o160000;
+
+    let mut conflicts = HashMap::new();
+    let Ok(repo) = git2::Repository::open(repo_path) else {
+        return conflicts;
+    };
+    let Ok(index) = repo.index() else {
+        return conflicts;
+    };
+    let Ok(iter) = index.conflicts() else {
+        return conflicts;
+    };
+
+    for conflict in iter.flatten() {
+        let sides = [&conflict.ancestor, &conflict.our, &conflict.their];
+        if !sides
+            .iter()
+            .any(|side| side.as_ref().is_some_and(|e| e.mode == GITLINK_MODE))
+        {
+            continue;
+        }
+        let Some(path) = sides
+            .iter()
+            .find_map(|side| side.as_ref())
+            .and_then(|entry| std::str::from_utf8(&entry.path).ok())
+        else {
+            continue;
+        };
+        let id = |side: &Option<git2::IndexEntry>| {
+            side.as_ref()
+                .map(|entry| entry.id.to_string())
+                .unwrap_or_default()
+        };
+        conflicts.insert(path.to_string(), (id(&conflict.our), id(&conflict.their)));
+    }
+
+    conflicts
+}
+
 impl Default for ConflictAnalyzer {
     fn default() -> Self {
         Self::new()
@@ -688,4 +796,55 @@ line after conflict
         // Not an import conflict
         assert!(!analyzer.is_import_conflict("main.rs", "fn main() {}", "fn test() {}"));
     }
+
+    #[test]
+    fn test_submodule_pointer_conflict() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path();
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .args(args)
+                .current_dir(path)
+                .output()
+                .unwrap();
+            output.status.success()
+        };
+        let pointer = |id: &str| {
+            assert!(git(&[
+                "update-index",
+                "--add",
+                "--cacheinfo",
+                &format!("160000,{id},libs/vendor"),
+            ]));
+        };
+        let (base, ours, theirs) = ("1".repeat(40), "2".repeat(40), "3".repeat(40));
+
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["config", "user.email", "test@test.com"]);
+        pointer(&base);
+        git(&["commit", "-q", "-m", "Add submodule"]);
+        git(&["checkout", "-q", "-b", "feature"]);
+        pointer(&theirs);
+        git(&["commit", "-q", "-m", "Bump submodule on feature"]);
+        git(&["checkout", "-q", "main"]);
+        pointer(&ours);
+        git(&["commit", "-q", "-m", "Bump submodule on main"]);
+        assert!(!git(&["cherry-pick", "feature"]));
+
+        let analyzer = ConflictAnalyzer::new();
+        let analysis = analyzer
+            .analyze_conflicts(&["libs/vendor".to_string()], path)
+            .unwrap();
+
+        let file = &analysis.files[0];
+        assert!(!file.auto_resolvable);
+        assert_eq!(
+            file.conflicts[0].conflict_type,
+            ConflictType::SubmodulePointer
+        );
+        assert_eq!(file.conflicts[0].our_content, ours);
+        assert_eq!(file.conflicts[0].their_content, theirs);
+        assert_eq!(analysis.manual_resolution_files, vec!["libs/vendor"]);
+    }
 }
@@ -1,10 +1,15 @@
+pub mod backups;
 pub mod branch_manager;
+pub mod codeowners;
 pub mod conflict_analysis;
 pub mod repository;
+pub mod ssh;
+pub mod trailers;
 
-pub use branch_manager::{BranchInfo, BranchManager};
+pub use branch_manager::{BranchInfo, BranchManager, BranchNameContext};
+pub use codeowners::CodeOwners;
 pub use conflict_analysis::{ConflictAnalysis, ConflictAnalyzer, ConflictRegion, ConflictType};
-pub use repository::{GitRepository, GitStatusSummary, RepositoryInfo};
+pub use repository::{GitRepository, GitStatusSummary, RepoFeatures, RepositoryInfo, StashRestore};
 
 use crate::errors::{CascadeError, Result};
 use std::path::{Path, PathBuf};
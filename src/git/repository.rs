@@ -1,9 +1,19 @@
+use super::backups::{self, BackupRef, BACKUP_REF_PREFIX};
+use super::ssh;
+use super::trailers::{self, TRAILERS_NOTES_REF};
 use crate::cli::output::Output;
+use crate::cli::prompt::{prompter, Prompter};
+use crate::config::{proxy, BackupSettings, HostCredentials, NetworkSettings, SshSettings};
 use crate::errors::{CascadeError, Result};
+use crate::utils::interrupt::{self, InterruptGuard};
+use crate::utils::platform;
+use crate::utils::progress::TransferProgress;
 use chrono;
-use dialoguer::{theme::ColorfulTheme, Confirm, Select};
 use git2::{Oid, Repository, Signature};
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::OnceLock;
 use tracing::{debug, info, warn};
 
 /// Repository information
@@ -19,12 +29,40 @@ pub struct RepositoryInfo {
 /// Backup information for force push operations
 #[derive(Debug, Clone)]
 struct ForceBackupInfo {
-    pub backup_branch_name: String,
     pub remote_commit_id: String,
     #[allow(dead_code)] // Used for logging/display purposes
     pub commits_that_would_be_lost: usize,
 }
 
+/// Repository features that libgit2 handles poorly or not at all
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RepoFeatures {
+    /// `core.sparseCheckout` is enabled; libgit2 checkouts would fill in excluded paths
+    pub sparse_checkout: bool,
+    /// Blobs are fetched on demand from a promisor remote, which libgit2 can't do
+    pub partial_clone: bool,
+    /// History is truncated (`.git/shallow` exists)
+    pub shallow: bool,
+}
+
+impl RepoFeatures {
+    /// Whether checkouts, cherry-picks, resets and fetches must go through the git CLI
+    pub fn needs_git_cli(&self) -> bool {
+        self.sparse_checkout || self.partial_clone
+    }
+}
+
+/// Outcome of re-applying a stash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StashRestore {
+    /// Changes applied cleanly and the stash entry was dropped
+    Applied,
+    /// Changes applied with conflicts in these files; the stash entry was kept
+    Conflicted(Vec<String>),
+    /// Git refused to apply the stash (e.g. untracked files in the way); nothing changed
+    Failed(String),
+}
+
 /// Safety information for branch deletion operations
 #[derive(Debug, Clone)]
 struct BranchDeletionSafety {
@@ -102,13 +140,111 @@ pub struct GitRepository {
     repo: Repository,
     path: PathBuf,
     ssl_config: Option<GitSslConfig>,
-    bitbucket_credentials: Option<BitbucketCredentials>,
+    remote_credentials: RemoteCredentials,
+    protected_branches: Vec<String>,
+    backup_policy: BackupSettings,
+    network: NetworkSettings,
+    /// `network.proxy` with its password filled in, looked up on first remote operation
+    configured_proxy: OnceLock<String>,
+    ssh: SshSettings,
+    features: RepoFeatures,
+    /// `git.rerere`: record conflict resolutions and reapply them on later cherry-picks
+    rerere: bool,
+    /// `git.commit_walk_limit`: most commits one history walk visits (0 = unlimited)
+    commit_walk_limit: usize,
 }
 
-#[derive(Debug, Clone)]
-struct BitbucketCredentials {
-    username: Option<String>,
-    token: Option<String>,
+/// Set (by `--allow-protected`) to let push, force-push, create and delete operate on
+/// branches matching `git.protected_branches`
+pub const ALLOW_PROTECTED_ENV: &str = "CASCADE_ALLOW_PROTECTED";
+
+/// Set by `--fetch` (`always`) and `--no-fetch` (`never`) to override fetching once per run
+pub const FETCH_POLICY_ENV: &str = "CASCADE_FETCH";
+
+/// When `fetch` goes to the remote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FetchPolicy {
+    /// The first fetch of a repository in this process; later ones reuse its refs
+    #[default]
+    Once,
+    /// Every fetch
+    Always,
+    /// Never; remote-tracking refs stay as they are
+    Never,
+}
+
+impl FetchPolicy {
+    /// The policy chosen for this run
+    pub fn current() -> Self {
+        match std::env::var(FETCH_POLICY_ENV).as_deref() {
+            Ok("always") => Self::Always,
+            Ok("never") => Self::Never,
+            _ => Self::Once,
+        }
+    }
+}
+
+/// Repositories fetched so far in this process. Managers open their own `GitRepository`,
+/// so the cache can't live on an instance
+static FETCHED_REPOS: std::sync::Mutex<Vec<PathBuf>> = std::sync::Mutex::new(Vec::new());
+
+/// Make the next fetch of `repo_root` go to the remote, after this run changed the remote
+/// server-side (e.g. merged a pull request)
+pub fn forget_fetch(repo_root: &Path) {
+    let key = fetch_cache_key(repo_root);
+    if let Ok(mut repos) = FETCHED_REPOS.lock() {
+        repos.retain(|repo| *repo != key);
+    }
+}
+
+fn fetch_cache_key(repo_root: &Path) -> PathBuf {
+    repo_root
+        .canonicalize()
+        .unwrap_or_else(|_| repo_root.to_path_buf())
+}
+
+/// Credentials for HTTPS remotes, picked by the host git is authenticating against
+#[derive(Debug, Clone, Default)]
+struct RemoteCredentials {
+    /// `credentials.<host>` entries
+    hosts: BTreeMap<String, HostCredentials>,
+    /// Host (and port) of `bitbucket.url`
+    bitbucket_host: Option<String>,
+    /// `bitbucket.username`/`bitbucket.token`
+    bitbucket: Option<HostCredentials>,
+}
+
+impl RemoteCredentials {
+    /// Credentials for a remote URL: a `credentials.<host>` entry first, then the
+    /// Bitbucket credentials when the remote is the Bitbucket server
+    fn for_url(&self, url: &str) -> Option<&HostCredentials> {
+        let Some(host) = remote_host(url) else {
+            return self
+                .bitbucket
+                .as_ref()
+                .filter(|_| url.contains("bitbucket"));
+        };
+        let host_only = host.split(':').next().unwrap_or(&host);
+        if let Some(creds) = self.hosts.get(&host).or_else(|| self.hosts.get(host_only)) {
+            return Some(creds);
+        }
+        let is_bitbucket = match &self.bitbucket_host {
+            Some(bitbucket_host) => *bitbucket_host == host || bitbucket_host == host_only,
+            // No usable bitbucket.url; fall back to guessing from the URL
+            None => url.contains("bitbucket"),
+        };
+        self.bitbucket.as_ref().filter(|_| is_bitbucket)
+    }
+}
+
+/// `host` or `host:port` of an http(s) remote URL
+fn remote_host(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    Some(match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    })
 }
 
 impl GitRepository {
@@ -125,13 +261,31 @@ impl GitRepository {
 
         // Try to load SSL configuration from cascade config
         let ssl_config = Self::load_ssl_config_from_cascade(&workdir);
-        let bitbucket_credentials = Self::load_bitbucket_credentials_from_cascade(&workdir);
+        let remote_credentials = Self::load_remote_credentials_from_cascade(&workdir);
+        let protected_branches = Self::load_protected_branches_from_cascade(&workdir);
+        let backup_policy = Self::load_backup_policy_from_cascade(&workdir);
+        let network = Self::load_network_settings_from_cascade(&workdir);
+        let ssh = Self::load_ssh_settings_from_cascade(&workdir);
+        let rerere = Self::load_rerere_from_cascade(&workdir);
+        let commit_walk_limit = Self::load_commit_walk_limit_from_cascade(&workdir);
+        let features = Self::detect_features(&repo);
+        if features.needs_git_cli() {
+            debug!("Using git CLI for working tree operations ({:?})", features);
+        }
 
         Ok(Self {
             repo,
             path: workdir,
             ssl_config,
-            bitbucket_credentials,
+            remote_credentials,
+            protected_branches,
+            backup_policy,
+            network,
+            configured_proxy: OnceLock::new(),
+            ssh,
+            features,
+            rerere,
+            commit_walk_limit,
         })
     }
 
@@ -155,22 +309,285 @@ impl GitRepository {
         }
     }
 
-    /// Load Bitbucket credentials from cascade config file if it exists
-    fn load_bitbucket_credentials_from_cascade(repo_path: &Path) -> Option<BitbucketCredentials> {
-        // Try to load cascade configuration
-        let config_dir = crate::config::get_repo_config_dir(repo_path).ok()?;
-        let config_path = config_dir.join("config.json");
-        let settings = crate::config::Settings::load_from_file(&config_path).ok()?;
+    /// Load Bitbucket and per-host remote credentials from cascade config file if it exists
+    fn load_remote_credentials_from_cascade(repo_path: &Path) -> RemoteCredentials {
+        let Some(settings) = crate::config::get_repo_config_dir(repo_path)
+            .ok()
+            .and_then(|dir| crate::config::Settings::load_from_file(&dir.join("config.json")).ok())
+        else {
+            return RemoteCredentials::default();
+        };
 
-        // Return credentials if any are configured
-        if settings.bitbucket.username.is_some() || settings.bitbucket.token.is_some() {
-            Some(BitbucketCredentials {
-                username: settings.bitbucket.username.clone(),
-                token: settings.bitbucket.token.clone(),
-            })
-        } else {
-            None
+        let bitbucket = &settings.bitbucket;
+        RemoteCredentials {
+            hosts: settings.credentials,
+            bitbucket_host: remote_host(&bitbucket.url),
+            bitbucket: (bitbucket.username.is_some() || bitbucket.token.is_some()).then(|| {
+                HostCredentials {
+                    username: bitbucket.username.clone(),
+                    token: bitbucket.token.clone(),
+                }
+            }),
+        }
+    }
+
+    /// Load `git.protected_branches` patterns from cascade config file if it exists
+    fn load_protected_branches_from_cascade(repo_path: &Path) -> Vec<String> {
+        crate::config::get_repo_config_dir(repo_path)
+            .ok()
+            .and_then(|dir| crate::config::Settings::load_from_file(&dir.join("config.json")).ok())
+            .map(|settings| settings.git.protected_branches)
+            .unwrap_or_default()
+    }
+
+    /// Detect sparse checkout, partial clone and shallow history
+    fn detect_features(repo: &Repository) -> RepoFeatures {
+        let config = repo.config().ok();
+        let config_bool = |key: &str| {
+            config
+                .as_ref()
+                .and_then(|c| c.get_bool(key).ok())
+                .unwrap_or(false)
+        };
+
+        RepoFeatures {
+            sparse_checkout: config_bool("core.sparseCheckout"),
+            partial_clone: !Self::promisor_remotes_in(repo).is_empty(),
+            shallow: repo.is_shallow(),
+        }
+    }
+
+    /// Remotes that serve missing objects of a partial clone
+    fn promisor_remotes_in(repo: &Repository) -> Vec<String> {
+        let Ok(config) = repo.config() else {
+            return Vec::new();
+        };
+
+        let mut remotes = Vec::new();
+        // Older git records the promisor remote as extensions.partialClone
+        if let Ok(remote) = config.get_string("extensions.partialclone") {
+            remotes.push(remote);
+        }
+        if let Ok(mut entries) = config.entries(Some(r"remote\..*\.promisor")) {
+            while let Some(Ok(entry)) = entries.next() {
+                let is_promisor = entry
+                    .value()
+                    .is_some_and(|v| matches!(v, "true" | "yes" | "on" | "1"));
+                let remote = entry
+                    .name()
+                    .and_then(|name| name.strip_prefix("remote."))
+                    .and_then(|name| name.strip_suffix(".promisor"));
+                if let (true, Some(remote)) = (is_promisor, remote) {
+                    if !remotes.iter().any(|r| r == remote) {
+                        remotes.push(remote.to_string());
+                    }
+                }
+            }
         }
+        remotes
+    }
+
+    /// Sparse checkout, partial clone and shallow history detected at open
+    pub fn features(&self) -> RepoFeatures {
+        self.features
+    }
+
+    /// Remotes that serve missing objects of a partial clone
+    pub fn promisor_remotes(&self) -> Vec<String> {
+        Self::promisor_remotes_in(&self.repo)
+    }
+
+    /// Run a git command that changes the working tree, for repos libgit2 can't check
+    /// out correctly (see [`RepoFeatures::needs_git_cli`])
+    fn run_worktree_git_cli(&self, args: &[&str]) -> Result<std::process::Output> {
+        self.ensure_index_closed()?;
+
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(&self.path)
+            .env("CASCADE_SKIP_HOOKS", "1")
+            .output()
+            .map_err(|e| CascadeError::branch(format!("Failed to run git {}: {e}", args[0])))?;
+
+        // Pick up the index git just wrote
+        self.repo
+            .index()
+            .and_then(|mut index| index.read(true))
+            .map_err(CascadeError::Git)?;
+        Ok(output)
+    }
+
+    /// Load the `backups.*` retention policy from cascade config file if it exists
+    fn load_backup_policy_from_cascade(repo_path: &Path) -> BackupSettings {
+        crate::config::get_repo_config_dir(repo_path)
+            .ok()
+            .and_then(|dir| crate::config::Settings::load_from_file(&dir.join("config.json")).ok())
+            .map(|settings| settings.cascade.backups)
+            .unwrap_or_default()
+    }
+
+    /// Load the `network.*` settings from cascade config file if it exists
+    fn load_network_settings_from_cascade(repo_path: &Path) -> NetworkSettings {
+        crate::config::get_repo_config_dir(repo_path)
+            .ok()
+            .and_then(|dir| crate::config::Settings::load_from_file(&dir.join("config.json")).ok())
+            .map(|settings| settings.bitbucket.network)
+            .unwrap_or_default()
+    }
+
+    /// Load the `ssh.*` settings from cascade config file if it exists
+    fn load_ssh_settings_from_cascade(repo_path: &Path) -> SshSettings {
+        crate::config::get_repo_config_dir(repo_path)
+            .ok()
+            .and_then(|dir| crate::config::Settings::load_from_file(&dir.join("config.json")).ok())
+            .map(|settings| settings.git.ssh)
+            .unwrap_or_default()
+    }
+
+    /// Load `git.rerere` from cascade config file, defaulting to enabled
+    fn load_rerere_from_cascade(repo_path: &Path) -> bool {
+        crate::config::get_repo_config_dir(repo_path)
+            .ok()
+            .and_then(|dir| crate::config::Settings::load_from_file(&dir.join("config.json")).ok())
+            .map(|settings| settings.git.rerere)
+            .unwrap_or(true)
+    }
+
+    /// Load `git.commit_walk_limit` from cascade config file
+    fn load_commit_walk_limit_from_cascade(repo_path: &Path) -> usize {
+        crate::config::get_repo_config_dir(repo_path)
+            .ok()
+            .and_then(|dir| crate::config::Settings::load_from_file(&dir.join("config.json")).ok())
+            .map(|settings| settings.git.commit_walk_limit)
+            .unwrap_or_else(|| crate::config::GitConfig::default().commit_walk_limit)
+    }
+
+    /// Commits one history walk may visit
+    fn walk_limit(&self) -> usize {
+        match self.commit_walk_limit {
+            0 => usize::MAX,
+            limit => limit,
+        }
+    }
+
+    /// Status of the working tree and index, skipping ignored files. Untracked directories
+    /// are only descended into with `recurse_untracked`, and `pathspecs` (when not empty)
+    /// limits the scan to matching paths. Refreshed stat data is written back to the index
+    /// so the next scan can skip unchanged files; if the index is locked the scan runs
+    /// without that.
+    fn scan_status(
+        &self,
+        pathspecs: &[&str],
+        recurse_untracked: bool,
+    ) -> Result<git2::Statuses<'_>> {
+        let options = |update_index: bool| {
+            let mut opts = git2::StatusOptions::new();
+            opts.include_untracked(true)
+                .include_ignored(false)
+                .recurse_untracked_dirs(recurse_untracked)
+                .update_index(update_index);
+            for pathspec in pathspecs {
+                opts.pathspec(pathspec);
+            }
+            opts
+        };
+
+        match self.repo.statuses(Some(&mut options(true))) {
+            Ok(statuses) => Ok(statuses),
+            Err(e) => {
+                debug!("Status with index refresh failed ({}), retrying without", e);
+                self.repo
+                    .statuses(Some(&mut options(false)))
+                    .map_err(CascadeError::Git)
+            }
+        }
+    }
+
+    /// `network.proxy` URL including the password from the credential helper
+    fn configured_proxy_url(&self) -> Option<&str> {
+        let configured = self
+            .network
+            .proxy
+            .as_deref()
+            .filter(|proxy| *proxy != proxy::DIRECT)?;
+        Some(self.configured_proxy.get_or_init(|| {
+            proxy::ProxyChoice {
+                url: configured.to_string(),
+                source: proxy::ProxySource::Config,
+            }
+            .authenticated_url()
+        }))
+    }
+
+    /// Proxy for git2 remote operations: `network.proxy` when set, otherwise git's
+    /// `http.proxy` and the proxy environment variables
+    fn proxy_options(&self) -> git2::ProxyOptions<'_> {
+        let mut options = git2::ProxyOptions::new();
+        match self.configured_proxy_url() {
+            Some(url) => {
+                options.url(url);
+            }
+            None if self.network.proxy.is_none() => {
+                options.auto();
+            }
+            None => {}
+        }
+        options
+    }
+
+    /// `git` command for talking to a remote, routed through `network.proxy` when set
+    fn git_remote_command(&self) -> std::process::Command {
+        let mut command = std::process::Command::new("git");
+        if let Some(url) = self.configured_proxy_url() {
+            command.args(["-c", &format!("http.proxy={url}")]);
+        } else if self.network.proxy.as_deref() == Some(proxy::DIRECT) {
+            for var in ["HTTPS_PROXY", "HTTP_PROXY", "ALL_PROXY"] {
+                command.env_remove(var).env_remove(var.to_lowercase());
+            }
+            command.args(["-c", "http.proxy="]);
+        }
+        if let Some(ssh_command) = ssh::ssh_command(&self.ssh) {
+            command.env("GIT_SSH_COMMAND", ssh_command);
+        }
+        command.current_dir(&self.path);
+        command
+    }
+
+    /// Whether a branch matches one of the `git.protected_branches` patterns. Where refs
+    /// are case-insensitive, `Main` is the same branch as `main` and is protected too.
+    pub fn is_protected_branch(&self, name: &str) -> bool {
+        let ignore_case = platform::case_insensitive_refs();
+        self.protected_branches.iter().any(|pattern| {
+            if ignore_case {
+                branch_pattern_matches(&pattern.to_lowercase(), &name.to_lowercase())
+            } else {
+                branch_pattern_matches(pattern, name)
+            }
+        })
+    }
+
+    /// Existing local branch whose name differs from `name` only in case. On case-insensitive
+    /// ref storage creating `name` would collide with it.
+    fn branch_case_conflict(&self, name: &str) -> Option<String> {
+        if !platform::case_insensitive_refs() {
+            return None;
+        }
+        let branches = self.repo.branches(Some(git2::BranchType::Local)).ok()?;
+        branches
+            .flatten()
+            .filter_map(|(branch, _)| branch.name().ok().flatten().map(str::to_string))
+            .find(|existing| existing != name && platform::same_branch_name(existing, name))
+    }
+
+    /// Refuse to `action` a protected branch unless `--allow-protected` was given
+    fn ensure_not_protected(&self, name: &str, action: &str) -> Result<()> {
+        if self.is_protected_branch(name) && std::env::var(ALLOW_PROTECTED_ENV).is_err() {
+            return Err(CascadeError::branch(format!(
+                "Refusing to {action} protected branch '{name}' (matches git.protected_branches). \
+                 Re-run with --allow-protected to override."
+            )));
+        }
+        Ok(())
     }
 
     /// Get repository information
@@ -224,7 +641,14 @@ impl GitRepository {
     /// Check if the working directory is dirty (has uncommitted changes)
     /// Excludes .cascade/ directory changes as these are internal metadata
     pub fn is_dirty(&self) -> Result<bool> {
-        let statuses = self.repo.statuses(None).map_err(CascadeError::Git)?;
+        self.is_dirty_in(&[])
+    }
+
+    /// Like [`Self::is_dirty`], only looking at paths matching `pathspecs`
+    pub fn is_dirty_in(&self, pathspecs: &[&str]) -> Result<bool> {
+        // One untracked file is enough to be dirty, so untracked directories aren't walked
+        let statuses = self.scan_status(pathspecs, false)?;
+        let sparse_excluded = self.sparse_excluded_paths();
 
         for status in statuses.iter() {
             let flags = status.status();
@@ -234,6 +658,9 @@ impl GitRepository {
                 if path.starts_with(".cascade/") || path == ".cascade" {
                     continue;
                 }
+                if flags == git2::Status::WT_DELETED && sparse_excluded.contains(path) {
+                    continue;
+                }
             }
 
             // Check for any modifications, additions, or deletions
@@ -252,9 +679,31 @@ impl GitRepository {
         Ok(false)
     }
 
+    /// Index paths left out of a sparse checkout. libgit2 ignores the skip-worktree
+    /// bit, so its status reports these as deleted from the working tree.
+    fn sparse_excluded_paths(&self) -> HashSet<String> {
+        if !self.features.sparse_checkout {
+            return HashSet::new();
+        }
+
+        self.repo
+            .index()
+            .map(|index| {
+                index
+                    .iter()
+                    .filter(|entry| {
+                        git2::IndexEntryExtendedFlag::from_bits_truncate(entry.flags_extended)
+                            .is_skip_worktree()
+                    })
+                    .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Get list of untracked files
     pub fn get_untracked_files(&self) -> Result<Vec<String>> {
-        let statuses = self.repo.statuses(None).map_err(CascadeError::Git)?;
+        let statuses = self.scan_status(&[], true)?;
 
         let mut untracked = Vec::new();
         for status in statuses.iter() {
@@ -270,6 +719,14 @@ impl GitRepository {
 
     /// Create a new branch
     pub fn create_branch(&self, name: &str, target: Option<&str>) -> Result<()> {
+        self.ensure_not_protected(name, "create")?;
+        if let Some(existing) = self.branch_case_conflict(name) {
+            return Err(CascadeError::branch(format!(
+                "Can't create branch '{name}': it differs only in case from existing branch \
+                 '{existing}', and branch names are case-insensitive on this system"
+            )));
+        }
+
         let target_commit = if let Some(target) = target {
             // Find the specified target commit/branch
             let target_obj = self.repo.revparse_single(target).map_err(|e| {
@@ -339,19 +796,34 @@ impl GitRepository {
     }
 
     /// Force-push a single branch to remote (simpler version for when branch is already updated locally)
-    pub fn force_push_single_branch(&self, branch_name: &str) -> Result<()> {
-        self.force_push_single_branch_with_options(branch_name, false, false)
+    ///
+    /// `expected_remote` is the commit Cascade last pushed for the branch; the push is
+    /// rejected if the remote has moved elsewhere since (see [`Self::resolve_push_lease`]).
+    pub fn force_push_single_branch(
+        &self,
+        branch_name: &str,
+        expected_remote: Option<&str>,
+    ) -> Result<()> {
+        self.force_push_single_branch_with_options(branch_name, false, false, expected_remote)
     }
 
     /// Force push with option to skip user confirmation (for automated operations like sync)
-    pub fn force_push_single_branch_auto(&self, branch_name: &str) -> Result<()> {
-        self.force_push_single_branch_with_options(branch_name, true, false)
+    pub fn force_push_single_branch_auto(
+        &self,
+        branch_name: &str,
+        expected_remote: Option<&str>,
+    ) -> Result<()> {
+        self.force_push_single_branch_with_options(branch_name, true, false, expected_remote)
     }
 
     /// Force push a single branch without fetching first (assumes fetch already done)
     /// Used in batch operations where we fetch once before pushing multiple branches
-    pub fn force_push_single_branch_auto_no_fetch(&self, branch_name: &str) -> Result<()> {
-        self.force_push_single_branch_with_options(branch_name, true, true)
+    pub fn force_push_single_branch_auto_no_fetch(
+        &self,
+        branch_name: &str,
+        expected_remote: Option<&str>,
+    ) -> Result<()> {
+        self.force_push_single_branch_with_options(branch_name, true, true, expected_remote)
     }
 
     fn force_push_single_branch_with_options(
@@ -359,7 +831,21 @@ impl GitRepository {
         branch_name: &str,
         auto_confirm: bool,
         skip_fetch: bool,
+        expected_remote: Option<&str>,
     ) -> Result<()> {
+        self.push_single_branch_forced(branch_name, auto_confirm, skip_fetch, expected_remote)
+            .inspect(|_| log_force_push(branch_name))
+    }
+
+    fn push_single_branch_forced(
+        &self,
+        branch_name: &str,
+        auto_confirm: bool,
+        skip_fetch: bool,
+        expected_remote: Option<&str>,
+    ) -> Result<()> {
+        self.ensure_not_protected(branch_name, "force-push")?;
+
         // Validate branch exists before attempting push
         // This provides a clearer error message than a failed git push
         if self.get_branch_commit_hash(branch_name).is_err() {
@@ -384,11 +870,8 @@ impl GitRepository {
         };
 
         if let Some(backup_info) = safety_result {
-            self.create_backup_branch(branch_name, &backup_info.remote_commit_id)?;
-            Output::sub_item(format!(
-                "Created backup branch: {}",
-                backup_info.backup_branch_name
-            ));
+            let backup = self.create_backup(branch_name, &backup_info.remote_commit_id)?;
+            Output::sub_item(format!("Created backup: {backup}"));
         }
 
         // Ensure index is closed before CLI command to prevent lock conflicts
@@ -401,9 +884,15 @@ impl GitRepository {
             .map_err(|e| CascadeError::branch(format!("Failed to create push marker: {}", e)))?;
 
         // Force push using git CLI (more reliable than git2 for TLS)
-        let output = std::process::Command::new("git")
-            .args(["push", "--force", "origin", branch_name])
-            .current_dir(&self.path)
+        let lease = self.resolve_push_lease(branch_name, branch_name, expected_remote);
+        let output = self
+            .git_remote_command()
+            .args([
+                "push",
+                &force_with_lease_arg(branch_name, lease.as_deref()),
+                "origin",
+                branch_name,
+            ])
             .output()
             .map_err(|e| {
                 // Clean up marker on error
@@ -418,6 +907,10 @@ impl GitRepository {
             let stderr = String::from_utf8_lossy(&output.stderr);
             let stdout = String::from_utf8_lossy(&output.stdout);
 
+            if stderr.contains("stale info") {
+                return Err(stale_lease_error(branch_name, lease.as_deref()));
+            }
+
             // Combine stderr and stdout for full error context
             let full_error = if !stdout.is_empty() {
                 format!("{}\n{}", stderr.trim(), stdout.trim())
@@ -473,6 +966,21 @@ impl GitRepository {
             .find_branch(name, git2::BranchType::Local)
             .map_err(|e| CascadeError::branch(format!("Could not find branch '{name}': {e}")))?;
 
+        if self.features.needs_git_cli() {
+            let output = self.run_worktree_git_cli(&["checkout", "--force", "--quiet", name])?;
+            if !output.status.success() {
+                return Err(CascadeError::branch(format!(
+                    "Could not checkout branch '{name}': {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            self.refresh_worktree_after_checkout();
+            if show_output {
+                Output::success(format!("Switched to branch '{name}'"));
+            }
+            return Ok(());
+        }
+
         let branch_ref = branch.get();
         let tree = branch_ref.peel_to_tree().map_err(|e| {
             CascadeError::branch(format!("Could not get tree for branch '{name}': {e}"))
@@ -508,6 +1016,8 @@ impl GitRepository {
             )));
         }
 
+        self.refresh_worktree_after_checkout();
+
         if show_output {
             Output::success(format!("Switched to branch '{name}'"));
         }
@@ -543,6 +1053,27 @@ impl GitRepository {
             CascadeError::branch(format!("Could not find commit '{commit_hash}': {e}"))
         })?;
 
+        if self.features.needs_git_cli() {
+            let output = self.run_worktree_git_cli(&[
+                "checkout",
+                "--force",
+                "--quiet",
+                "--detach",
+                commit_hash,
+            ])?;
+            if !output.status.success() {
+                return Err(CascadeError::branch(format!(
+                    "Could not checkout commit '{commit_hash}': {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            self.refresh_worktree_after_checkout();
+            Output::success(format!(
+                "Checked out commit '{commit_hash}' (detached HEAD)"
+            ));
+            return Ok(());
+        }
+
         let tree = commit.tree().map_err(|e| {
             CascadeError::branch(format!(
                 "Could not get tree for commit '{commit_hash}': {e}"
@@ -563,12 +1094,130 @@ impl GitRepository {
             ))
         })?;
 
+        self.refresh_worktree_after_checkout();
+
         Output::success(format!(
             "Checked out commit '{commit_hash}' (detached HEAD)"
         ));
         Ok(())
     }
 
+    /// Paths of the submodules registered in `.gitmodules`
+    pub fn submodule_paths(&self) -> Vec<String> {
+        self.repo
+            .submodules()
+            .map(|submodules| {
+                submodules
+                    .iter()
+                    .filter_map(|s| s.path().to_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether the repository has any submodules
+    pub fn has_submodules(&self) -> bool {
+        !self.submodule_paths().is_empty()
+    }
+
+    /// Initialize submodules and check out the commits recorded by HEAD.
+    ///
+    /// libgit2 checkouts only move the submodule pointers; the git CLI is used so
+    /// nested submodules and the user's credentials work as they do for `git`.
+    pub fn update_submodules(&self) -> Result<()> {
+        let output = std::process::Command::new("git")
+            .args(["submodule", "update", "--init", "--recursive"])
+            .current_dir(&self.path)
+            .env("CASCADE_SKIP_HOOKS", "1")
+            .output()
+            .map_err(|e| {
+                CascadeError::branch(format!("Failed to run git submodule update: {e}"))
+            })?;
+
+        if !output.status.success() {
+            return Err(CascadeError::branch(format!(
+                "git submodule update failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether the root `.gitattributes` routes any paths through the Git LFS filter
+    pub fn uses_lfs(&self) -> bool {
+        std::fs::read_to_string(self.path.join(".gitattributes"))
+            .map(|attributes| attributes.contains("filter=lfs"))
+            .unwrap_or(false)
+    }
+
+    /// Whether a path is stored in Git LFS according to `.gitattributes`
+    pub fn is_lfs_tracked(&self, path: &str) -> bool {
+        matches!(
+            self.repo.get_attr(
+                Path::new(path),
+                "filter",
+                git2::AttrCheckFlags::FILE_THEN_INDEX
+            ),
+            Ok(Some("lfs"))
+        )
+    }
+
+    /// Whether the `git lfs` extension is installed
+    pub fn lfs_available() -> bool {
+        std::process::Command::new("git")
+            .args(["lfs", "version"])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Replace LFS pointer files in the working tree with their content.
+    ///
+    /// libgit2 doesn't run the LFS smudge filter, so checkouts it performs leave
+    /// pointer files behind; `git lfs checkout` fills them in from the local LFS cache.
+    pub fn checkout_lfs_files(&self) -> Result<()> {
+        let output = std::process::Command::new("git")
+            .args(["lfs", "checkout"])
+            .current_dir(&self.path)
+            .output()
+            .map_err(|e| CascadeError::branch(format!("Failed to run git lfs checkout: {e}")))?;
+
+        if !output.status.success() {
+            return Err(CascadeError::branch(format!(
+                "git lfs checkout failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Bring submodules and LFS files in line with a new checkout; failures only warn
+    /// since the checkout itself succeeded
+    fn refresh_worktree_after_checkout(&self) {
+        if self.has_submodules() {
+            if let Err(e) = self.update_submodules() {
+                Output::warning(format!("Submodules may be out of date: {e}"));
+                Output::tip("Run 'git submodule update --init --recursive' to update them");
+            }
+        }
+
+        if self.uses_lfs() {
+            if !Self::lfs_available() {
+                static LFS_MISSING: std::sync::Once = std::sync::Once::new();
+                LFS_MISSING.call_once(|| {
+                    Output::warning(
+                        "This repository uses Git LFS but git-lfs is not installed; \
+                         LFS files are left as pointers",
+                    );
+                    Output::tip("Install Git LFS and run 'git lfs install' (see 'ca doctor')");
+                });
+            } else if let Err(e) = self.checkout_lfs_files() {
+                Output::warning(format!("LFS files may be out of date: {e}"));
+                Output::tip("Run 'git lfs pull' to fetch and check them out");
+            }
+        }
+    }
+
     /// Check if a branch exists
     pub fn branch_exists(&self, name: &str) -> bool {
         self.repo.find_branch(name, git2::BranchType::Local).is_ok()
@@ -589,15 +1238,15 @@ impl GitRepository {
         use std::process::Command;
 
         // Try: git fetch origin release/12.34:release/12.34
-        let fetch_result = Command::new("git")
+        let fetch_result = self
+            .git_remote_command()
             .args(["fetch", "origin", &format!("{name}:{name}")])
-            .current_dir(&self.path)
             .output();
 
         match fetch_result {
             Ok(output) => {
                 if output.status.success() {
-                    println!("✅ Successfully fetched '{name}' from origin");
+                    Output::success(format!("Successfully fetched '{name}' from origin"));
                     // 3. Check again locally after fetch
                     return Ok(self.repo.find_branch(name, git2::BranchType::Local).is_ok());
                 } else {
@@ -615,10 +1264,7 @@ impl GitRepository {
             crate::cli::output::Output::info("Trying alternative fetch patterns...");
 
             // Try: git fetch origin (to get all refs, then checkout locally)
-            let fetch_all_result = Command::new("git")
-                .args(["fetch", "origin"])
-                .current_dir(&self.path)
-                .output();
+            let fetch_all_result = self.git_remote_command().args(["fetch", "origin"]).output();
 
             if let Ok(output) = fetch_all_result {
                 if output.status.success() {
@@ -630,9 +1276,9 @@ impl GitRepository {
 
                     if let Ok(checkout_output) = checkout_result {
                         if checkout_output.status.success() {
-                            println!(
-                                "✅ Successfully created local branch '{name}' from origin/{name}"
-                            );
+                            Output::success(format!(
+                                "Created local branch '{name}' from origin/{name}"
+                            ));
                             return Ok(true);
                         }
                     }
@@ -744,6 +1390,176 @@ impl GitRepository {
         Ok((ahead, behind))
     }
 
+    /// Number of commits reachable from `reference` (a branch, remote branch or commit)
+    /// that `commit` doesn't contain
+    pub fn commits_behind(&self, commit: &str, reference: &str) -> Result<usize> {
+        let commit = self
+            .repo
+            .revparse_single(commit)
+            .and_then(|obj| obj.peel_to_commit())
+            .map_err(CascadeError::Git)?;
+        let target = self
+            .repo
+            .revparse_single(reference)
+            .and_then(|obj| obj.peel_to_commit())
+            .map_err(CascadeError::Git)?;
+        let (_, behind) = self
+            .repo
+            .graph_ahead_behind(commit.id(), target.id())
+            .map_err(CascadeError::Git)?;
+        Ok(behind)
+    }
+
+    /// Commits between the merge base and `head` whose change (patch-id) already exists on
+    /// `upstream` under a different commit, e.g. because it was cherry-picked there
+    pub fn commits_already_upstream(&self, upstream: &str, head: &str) -> Result<Vec<String>> {
+        let output = std::process::Command::new("git")
+            .args(["cherry", upstream, head])
+            .current_dir(&self.path)
+            .output()
+            .map_err(CascadeError::Io)?;
+        if !output.status.success() {
+            return Err(CascadeError::branch(format!(
+                "Failed to compare {head} with {upstream}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.strip_prefix("- "))
+            .map(|hash| hash.trim().to_string())
+            .collect())
+    }
+
+    /// Which of `ranges` (from, to) were squash-merged into `upstream`: the combined diff
+    /// of the range has the same patch-id as one of the commits on `upstream` that none
+    /// of the ranges contain. Returns the positions of the matching ranges.
+    pub fn ranges_landed_upstream(
+        &self,
+        upstream: &str,
+        ranges: &[(String, String)],
+    ) -> Result<Vec<usize>> {
+        if ranges.is_empty() {
+            return Ok(Vec::new());
+        }
+        let upstream_commit = self.resolve_reference(upstream)?;
+        let mut walk = self.repo.revwalk().map_err(CascadeError::Git)?;
+        walk.push(upstream_commit.id()).map_err(CascadeError::Git)?;
+        for (_, to) in ranges {
+            walk.hide(Oid::from_str(to).map_err(CascadeError::Git)?)
+                .map_err(CascadeError::Git)?;
+        }
+
+        let mut upstream_patches = HashSet::new();
+        for oid in walk {
+            let commit = self
+                .repo
+                .find_commit(oid.map_err(CascadeError::Git)?)
+                .map_err(CascadeError::Git)?;
+            // Merge commits have no single patch to compare
+            if commit.parent_count() != 1 {
+                continue;
+            }
+            let parent_tree = commit.parent(0).and_then(|p| p.tree()).ok();
+            let diff = self
+                .repo
+                .diff_tree_to_tree(parent_tree.as_ref(), commit.tree().ok().as_ref(), None)
+                .map_err(CascadeError::Git)?;
+            upstream_patches.insert(diff.patchid(None).map_err(CascadeError::Git)?);
+        }
+        if upstream_patches.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut landed = Vec::new();
+        for (position, (from, to)) in ranges.iter().enumerate() {
+            let from_tree = self.get_commit(from)?.tree().map_err(CascadeError::Git)?;
+            let to_tree = self.get_commit(to)?.tree().map_err(CascadeError::Git)?;
+            let diff = self
+                .repo
+                .diff_tree_to_tree(Some(&from_tree), Some(&to_tree), None)
+                .map_err(CascadeError::Git)?;
+            // An empty range would match any empty commit upstream
+            if diff.deltas().len() == 0 {
+                continue;
+            }
+            if upstream_patches.contains(&diff.patchid(None).map_err(CascadeError::Git)?) {
+                landed.push(position);
+            }
+        }
+        Ok(landed)
+    }
+
+    /// Replay `commits` (oldest first) onto `onto` entirely in memory and report the
+    /// files each one would conflict on. Only tree objects are written; refs, the index
+    /// and the working tree are left alone. A conflicting commit is assumed to be
+    /// resolved to its own content so later commits are still checked.
+    pub fn predict_rebase_conflicts(
+        &self,
+        commits: &[String],
+        onto: &str,
+    ) -> Result<Vec<Vec<String>>> {
+        let mut current = self
+            .repo
+            .revparse_single(onto)
+            .and_then(|obj| obj.peel_to_tree())
+            .map_err(CascadeError::Git)?;
+        let empty_tree_id = self
+            .repo
+            .treebuilder(None)
+            .and_then(|builder| builder.write())
+            .map_err(CascadeError::Git)?;
+
+        let mut predictions = Vec::with_capacity(commits.len());
+        for hash in commits {
+            let commit = self
+                .repo
+                .revparse_single(hash)
+                .and_then(|obj| obj.peel_to_commit())
+                .map_err(CascadeError::Git)?;
+            let theirs = commit.tree().map_err(CascadeError::Git)?;
+            let ancestor = match commit.parent(0) {
+                Ok(parent) => parent.tree().map_err(CascadeError::Git)?,
+                Err(_) => self
+                    .repo
+                    .find_tree(empty_tree_id)
+                    .map_err(CascadeError::Git)?,
+            };
+
+            let mut index = self
+                .repo
+                .merge_trees(&ancestor, &current, &theirs, None)
+                .map_err(CascadeError::Git)?;
+
+            if index.has_conflicts() {
+                let mut files = Vec::new();
+                for conflict in index.conflicts().map_err(CascadeError::Git)? {
+                    let conflict = conflict.map_err(CascadeError::Git)?;
+                    let entry = conflict
+                        .our
+                        .or(conflict.their)
+                        .or(conflict.ancestor)
+                        .map(|entry| String::from_utf8_lossy(&entry.path).to_string());
+                    if let Some(path) = entry {
+                        if !files.contains(&path) {
+                            files.push(path);
+                        }
+                    }
+                }
+                files.sort();
+                predictions.push(files);
+                current = theirs;
+            } else {
+                let tree_id = index.write_tree_to(&self.repo).map_err(CascadeError::Git)?;
+                current = self.repo.find_tree(tree_id).map_err(CascadeError::Git)?;
+                predictions.push(Vec::new());
+            }
+        }
+
+        Ok(predictions)
+    }
+
     /// Set upstream tracking for a branch
     pub fn set_upstream(&self, branch_name: &str, remote: &str, remote_branch: &str) -> Result<()> {
         let mut config = self.repo.config().map_err(CascadeError::Git)?;
@@ -807,8 +1623,33 @@ impl GitRepository {
         Ok(Some(commit_hash))
     }
 
+    /// Record the staged changes as a commit on top of HEAD without moving any ref.
+    /// Returns None when nothing is staged.
+    pub fn snapshot_staged_changes(&self, message: &str) -> Result<Option<String>> {
+        if self.get_staged_files()?.is_empty() {
+            return Ok(None);
+        }
+
+        let tree_oid = self.get_index_tree()?;
+        let tree = self.repo.find_tree(tree_oid).map_err(CascadeError::Git)?;
+        let head = self.get_head_commit()?;
+        let signature = self.get_signature()?;
+
+        let oid = self
+            .repo
+            .commit(None, &signature, &signature, message, &tree, &[&head])
+            .map_err(CascadeError::Git)?;
+
+        Ok(Some(oid.to_string()))
+    }
+
     /// Stage all changes
     pub fn stage_all(&self) -> Result<()> {
+        // libgit2 would stage LFS files' content instead of running the clean filter
+        if self.uses_lfs() {
+            return self.stage_with_git_cli(&["-A"]);
+        }
+
         let mut index = self.repo.index().map_err(CascadeError::Git)?;
 
         index
@@ -846,9 +1687,14 @@ impl GitRepository {
             return Ok(());
         }
 
+        // LFS files go through `git add` so the clean filter stores a pointer
+        let (lfs_paths, plain_paths): (Vec<&str>, Vec<&str>) = file_paths
+            .iter()
+            .partition(|file_path| self.is_lfs_tracked(file_path));
+
         let mut index = self.repo.index().map_err(CascadeError::Git)?;
 
-        for file_path in file_paths {
+        for file_path in plain_paths {
             index
                 .add_path(std::path::Path::new(file_path))
                 .map_err(CascadeError::Git)?;
@@ -857,6 +1703,12 @@ impl GitRepository {
         index.write().map_err(CascadeError::Git)?;
         drop(index); // Explicitly close index after staging
 
+        if !lfs_paths.is_empty() {
+            let mut args = vec!["--"];
+            args.extend(lfs_paths);
+            self.stage_with_git_cli(&args)?;
+        }
+
         tracing::debug!(
             "Staged {} specific files: {:?}",
             file_paths.len(),
@@ -865,6 +1717,31 @@ impl GitRepository {
         Ok(())
     }
 
+    /// Run `git add` with `args`, letting git apply clean filters such as Git LFS
+    fn stage_with_git_cli(&self, args: &[&str]) -> Result<()> {
+        self.ensure_index_closed()?;
+
+        let output = std::process::Command::new("git")
+            .arg("add")
+            .args(args)
+            .current_dir(&self.path)
+            .output()
+            .map_err(|e| CascadeError::branch(format!("Failed to run git add: {e}")))?;
+
+        if !output.status.success() {
+            return Err(CascadeError::branch(format!(
+                "git add failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        // Pick up the index git just wrote
+        self.repo
+            .index()
+            .and_then(|mut index| index.read(true))
+            .map_err(CascadeError::Git)
+    }
+
     /// Stage only files that had conflicts (safer for rebase operations)
     pub fn stage_conflict_resolved_files(&self) -> Result<()> {
         let conflicted_files = self.get_conflicted_files()?;
@@ -880,6 +1757,11 @@ impl GitRepository {
         Ok(())
     }
 
+    /// Check whether a merge, rebase, cherry-pick or similar operation is in progress
+    pub fn has_operation_in_progress(&self) -> bool {
+        self.repo.state() != git2::RepositoryState::Clean
+    }
+
     /// Clean up any in-progress merge/revert/cherry-pick state (removes CHERRY_PICK_HEAD etc.)
     pub fn cleanup_state(&self) -> Result<()> {
         let state = self.repo.state();
@@ -901,6 +1783,54 @@ impl GitRepository {
         &self.path
     }
 
+    /// Run a shell command in a temporary detached worktree checked out at `commit`, so
+    /// the user's working tree is left alone. The worktree is removed afterwards.
+    pub fn run_in_temp_worktree(
+        &self,
+        commit: &str,
+        command: &str,
+    ) -> Result<std::process::Output> {
+        let short = &commit[..commit.len().min(8)];
+        let dir = crate::utils::platform::secure_temp_dir()
+            .map_err(|e| CascadeError::config(format!("Failed to create temp directory: {e}")))?
+            .join(format!("check-{short}"));
+        let dir_arg = dir.to_string_lossy().to_string();
+
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(&self.path)
+                .env("CASCADE_SKIP_HOOKS", "1")
+                .output()
+                .map_err(|e| CascadeError::branch(format!("Failed to run git {}: {e}", args[0])))
+        };
+
+        let added = git(&["worktree", "add", "--detach", "--quiet", &dir_arg, commit])?;
+        if !added.status.success() {
+            return Err(CascadeError::branch(format!(
+                "Failed to check out {short} in a temporary worktree: {}",
+                String::from_utf8_lossy(&added.stderr).trim()
+            )));
+        }
+
+        let (shell, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        let output = std::process::Command::new(shell)
+            .args([flag, command])
+            .current_dir(&dir)
+            .env("CASCADE_COMMIT", commit)
+            .output()
+            .map_err(|e| CascadeError::config(format!("Failed to run '{command}': {e}")));
+
+        if let Err(e) = git(&["worktree", "remove", "--force", &dir_arg]) {
+            tracing::debug!("Could not remove temporary worktree {}: {}", dir_arg, e);
+        }
+        output
+    }
+
     /// Per-worktree git directory.
     /// Normal repos: /repo/.git/  |  Worktrees: /main/.git/worktrees/<name>/
     pub fn git_dir(&self) -> &Path {
@@ -1087,6 +2017,32 @@ impl GitRepository {
         Ok(target.to_string())
     }
 
+    /// Whether origin has the branch right now. Asks the remote, since fetching doesn't
+    /// prune the remote-tracking refs of branches deleted there.
+    pub fn remote_branch_exists(&self, branch_name: &str) -> Result<bool> {
+        let output = self
+            .git_remote_command()
+            .args([
+                "ls-remote",
+                "--exit-code",
+                "--heads",
+                "origin",
+                &format!("refs/heads/{branch_name}"),
+            ])
+            .output()
+            .map_err(|e| CascadeError::branch(format!("Failed to execute git ls-remote: {e}")))?;
+
+        // --exit-code exits with 2 when no ref matched
+        match output.status.code() {
+            Some(0) => Ok(true),
+            Some(2) => Ok(false),
+            _ => Err(CascadeError::branch(format!(
+                "Could not check origin for branch '{branch_name}': {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))),
+        }
+    }
+
     /// Validate git user configuration is properly set
     pub fn validate_git_user_config(&self) -> Result<()> {
         if let Ok(config) = self.repo.config() {
@@ -1227,6 +2183,8 @@ impl GitRepository {
                 let error_string = error.to_string();
                 error_string.contains("no callback set")
                     || error_string.contains("authentication required")
+                    || error_string.contains("SSH authentication failed")
+                    || error_string.contains("Failed to authenticate SSH session")
             }
 
             // Network errors that might be proxy/firewall related
@@ -1257,7 +2215,8 @@ impl GitRepository {
         let mut callbacks = git2::RemoteCallbacks::new();
 
         // Configure authentication with comprehensive credential support
-        let bitbucket_credentials = self.bitbucket_credentials.clone();
+        let remote_credentials = self.remote_credentials.clone();
+        let mut ssh_auth = ssh::SshAuthenticator::new(&self.ssh);
         callbacks.credentials(move |url, username_from_url, allowed_types| {
             tracing::debug!(
                 "Authentication requested for URL: {}, username: {:?}, allowed_types: {:?}",
@@ -1266,12 +2225,14 @@ impl GitRepository {
                 allowed_types
             );
 
-            // For SSH URLs with username
+            // SSH URLs without a user (ssh://host/repo) ask for one first
+            if allowed_types.contains(git2::CredentialType::USERNAME) {
+                return git2::Cred::username(username_from_url.unwrap_or("git"));
+            }
+
+            // SSH: agent keys, then ssh.key_path or the default identity files
             if allowed_types.contains(git2::CredentialType::SSH_KEY) {
-                if let Some(username) = username_from_url {
-                    tracing::debug!("Trying SSH key authentication for user: {}", username);
-                    return git2::Cred::ssh_key_from_agent(username);
-                }
+                return ssh_auth.next_credential(username_from_url.unwrap_or("git"));
             }
 
             // For HTTPS URLs, try multiple authentication methods in sequence
@@ -1282,25 +2243,25 @@ impl GitRepository {
                     return git2::Cred::default();
                 }
 
-                if url.contains("bitbucket") {
-                    if let Some(creds) = &bitbucket_credentials {
-                        // Method 1: Username + Token (common for Bitbucket)
-                        if let (Some(username), Some(token)) = (&creds.username, &creds.token) {
-                            tracing::debug!("Trying Bitbucket username + token authentication");
-                            return git2::Cred::userpass_plaintext(username, token);
-                        }
+                if let Some(creds) = remote_credentials.for_url(url) {
+                    // Method 1: Username + Token (common for Bitbucket)
+                    if let (Some(username), Some(token)) = (&creds.username, &creds.token) {
+                        tracing::debug!("Trying username + token authentication");
+                        return git2::Cred::userpass_plaintext(username, token);
+                    }
 
-                        // Method 2: Token as username, empty password (alternate Bitbucket format)
-                        if let Some(token) = &creds.token {
-                            tracing::debug!("Trying Bitbucket token-as-username authentication");
-                            return git2::Cred::userpass_plaintext(token, "");
-                        }
+                    // Method 2: Token as username, empty password (alternate Bitbucket format)
+                    if let Some(token) = &creds.token {
+                        tracing::debug!("Trying token-as-username authentication");
+                        return git2::Cred::userpass_plaintext(token, "");
+                    }
 
-                        // Method 3: Just username (will prompt for password or use credential helper)
-                        if let Some(username) = &creds.username {
-                            tracing::debug!("Trying Bitbucket username authentication (will use credential helper)");
-                            return git2::Cred::username(username);
-                        }
+                    // Method 3: Just username (will prompt for password or use credential helper)
+                    if let Some(username) = &creds.username {
+                        tracing::debug!(
+                            "Trying username authentication (will use credential helper)"
+                        );
+                        return git2::Cred::username(username);
                     }
                 }
 
@@ -1318,7 +2279,8 @@ impl GitRepository {
         // This matches what tools like Graphite, Sapling, and Phabricator do
         // Priority: 1. Use system certificates (default), 2. Manual overrides only if needed
 
-        let mut ssl_configured = false;
+        // Reason TLS verification is bypassed, if it is
+        let mut tls_bypass: Option<&'static str> = None;
 
         // Check for manual SSL overrides first (only when user explicitly needs them)
         if let Some(ssl_config) = &self.ssl_config {
@@ -1326,25 +2288,17 @@ impl GitRepository {
                 Output::warning(
                     "SSL certificate verification DISABLED via Cascade config - this is insecure!",
                 );
-                callbacks.certificate_check(|_cert, _host| {
-                    tracing::debug!("⚠️  Accepting invalid certificate for host: {}", _host);
-                    Ok(git2::CertificateCheckStatus::CertificateOk)
-                });
-                ssl_configured = true;
+                tls_bypass = Some("⚠️  Accepting invalid certificate");
             } else if let Some(ca_path) = &ssl_config.ca_bundle_path {
                 Output::info(format!(
                     "Using custom CA bundle from Cascade config: {ca_path}"
                 ));
-                callbacks.certificate_check(|_cert, host| {
-                    tracing::debug!("Using custom CA bundle for host: {}", host);
-                    Ok(git2::CertificateCheckStatus::CertificateOk)
-                });
-                ssl_configured = true;
+                tls_bypass = Some("Using custom CA bundle");
             }
         }
 
         // Check git config for manual overrides
-        if !ssl_configured {
+        if tls_bypass.is_none() {
             if let Ok(config) = self.repo.config() {
                 let ssl_verify = config.get_bool("http.sslVerify").unwrap_or(true);
 
@@ -1352,43 +2306,73 @@ impl GitRepository {
                     Output::warning(
                         "SSL certificate verification DISABLED via git config - this is insecure!",
                     );
-                    callbacks.certificate_check(|_cert, host| {
-                        tracing::debug!("⚠️  Bypassing SSL verification for host: {}", host);
-                        Ok(git2::CertificateCheckStatus::CertificateOk)
-                    });
-                    ssl_configured = true;
+                    tls_bypass = Some("⚠️  Bypassing SSL verification");
                 } else if let Ok(ca_path) = config.get_string("http.sslCAInfo") {
                     Output::info(format!("Using custom CA bundle from git config: {ca_path}"));
-                    callbacks.certificate_check(|_cert, host| {
-                        tracing::debug!("Using git config CA bundle for host: {}", host);
-                        Ok(git2::CertificateCheckStatus::CertificateOk)
-                    });
-                    ssl_configured = true;
+                    tls_bypass = Some("Using git config CA bundle");
                 }
             }
         }
 
+        // SSH host keys are checked against known_hosts whatever the TLS settings say;
+        // the origin's port tells which `[host]:port` entry applies
+        let origin_ssh = self
+            .repo
+            .find_remote("origin")
+            .ok()
+            .and_then(|remote| remote.url().map(str::to_string))
+            .filter(|url| ssh::is_ssh_url(url))
+            .and_then(|url| ssh::remote_host(&url));
+
         // DEFAULT BEHAVIOR: Use system certificates (like git CLI and other modern tools)
         // This should work out-of-the-box in corporate environments
-        if !ssl_configured {
+        if tls_bypass.is_none() {
             tracing::debug!(
                 "Using system certificate store for SSL verification (default behavior)"
             );
+        }
 
-            // For macOS with SecureTransport backend, try default certificate validation first
-            if cfg!(target_os = "macos") {
-                tracing::debug!("macOS detected - using default certificate validation");
-                // Don't set any certificate callback - let git2 use its default behavior
-                // This often works better with SecureTransport backend on macOS
-            } else {
-                // Use CertificatePassthrough for other platforms
-                callbacks.certificate_check(|_cert, host| {
-                    tracing::debug!("System certificate validation for host: {}", host);
-                    Ok(git2::CertificateCheckStatus::CertificatePassthrough)
-                });
-            }
+        // For macOS with SecureTransport backend, don't set any certificate callback for
+        // HTTPS remotes - let git2 use its default behavior, which often works better there
+        if tls_bypass.is_some() || origin_ssh.is_some() || !cfg!(target_os = "macos") {
+            let ssh_settings = self.ssh.clone();
+            callbacks.certificate_check(move |cert, host| {
+                if let Some(hostkey) = cert.as_hostkey() {
+                    let port = origin_ssh
+                        .as_ref()
+                        .filter(|(origin_host, _)| origin_host == host)
+                        .and_then(|(_, port)| *port);
+                    return ssh::verify_host_key(
+                        &ssh_settings,
+                        &ssh::known_host_name(host, port),
+                        hostkey,
+                    );
+                }
+                match tls_bypass {
+                    Some(reason) => {
+                        tracing::debug!("{} for host: {}", reason, host);
+                        Ok(git2::CertificateCheckStatus::CertificateOk)
+                    }
+                    None => {
+                        tracing::debug!("System certificate validation for host: {}", host);
+                        Ok(git2::CertificateCheckStatus::CertificatePassthrough)
+                    }
+                }
+            });
         }
 
+        // Transfer progress; returning false from the fetch callback cancels the transfer,
+        // which is how Ctrl-C stops a long fetch
+        let progress = Rc::new(TransferProgress::new());
+        let fetch_progress = Rc::clone(&progress);
+        callbacks.transfer_progress(move |stats| {
+            fetch_progress.fetch(&stats);
+            !interrupt::requested()
+        });
+        callbacks.push_transfer_progress(move |current, total, _bytes| {
+            progress.push(current, total);
+        });
+
         Ok(callbacks)
     }
 
@@ -1399,14 +2383,15 @@ impl GitRepository {
         index.write_tree().map_err(CascadeError::Git)
     }
 
-    /// Get repository status
+    /// Get repository status (ignored files are left out)
     pub fn get_status(&self) -> Result<git2::Statuses<'_>> {
-        self.repo.statuses(None).map_err(CascadeError::Git)
+        self.scan_status(&[], true)
     }
 
     /// Get a summary of repository status
     pub fn get_status_summary(&self) -> Result<GitStatusSummary> {
         let statuses = self.get_status()?;
+        let sparse_excluded = self.sparse_excluded_paths();
 
         let mut staged_files = 0;
         let mut unstaged_files = 0;
@@ -1414,6 +2399,11 @@ impl GitRepository {
 
         for status in statuses.iter() {
             let flags = status.status();
+            if flags == git2::Status::WT_DELETED
+                && status.path().is_some_and(|p| sparse_excluded.contains(p))
+            {
+                continue;
+            }
 
             if flags.intersects(
                 git2::Status::INDEX_MODIFIED
@@ -1460,7 +2450,8 @@ impl GitRepository {
         revwalk.push(to_oid).map_err(CascadeError::Git)?;
         revwalk.hide(from_oid).map_err(CascadeError::Git)?;
 
-        Ok(revwalk.count())
+        // Capped at `git.commit_walk_limit`
+        Ok(revwalk.take(self.walk_limit()).count())
     }
 
     /// Get remote URL for a given remote name
@@ -1476,6 +2467,10 @@ impl GitRepository {
         // Validate git user configuration before attempting commit operations
         self.validate_git_user_config()?;
 
+        if self.features.needs_git_cli() {
+            return self.cherry_pick_with_git_cli(commit_hash);
+        }
+
         let oid = Oid::from_str(commit_hash).map_err(CascadeError::Git)?;
         let commit = self.repo.find_commit(oid).map_err(CascadeError::Git)?;
 
@@ -1548,6 +2543,7 @@ impl GitRepository {
             self.ensure_index_closed()?;
 
             let cherry_pick_output = std::process::Command::new("git")
+                .args(self.rerere_config())
                 .args(["cherry-pick", commit_hash])
                 .current_dir(self.path())
                 .output()
@@ -1573,52 +2569,264 @@ impl GitRepository {
                 .and_then(|mut idx| idx.read(true).map(|_| ()))
                 .map_err(CascadeError::Git)?;
 
+            if let Some(head) = self.continue_with_recorded_resolutions(commit_hash)? {
+                return Ok(head);
+            }
+
             debug!("Conflicted state written and index reloaded - auto-resolve can now process conflicts");
 
-            return Err(CascadeError::branch(format!(
-                "Cherry-pick of {commit_hash} has conflicts that need manual resolution"
-            )));
+            return Err(CascadeError::branch(format!(
+                "Cherry-pick of {commit_hash} has conflicts that need manual resolution"
+            )));
+        }
+
+        // Write merged tree
+        let merged_tree_oid = index.write_tree_to(&self.repo).map_err(CascadeError::Git)?;
+        let merged_tree = self
+            .repo
+            .find_tree(merged_tree_oid)
+            .map_err(CascadeError::Git)?;
+
+        // Create new commit with original message (preserve it exactly)
+        let signature = self.get_signature()?;
+        let message = commit.message().unwrap_or("Cherry-picked commit");
+
+        let new_commit_oid = self
+            .repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &merged_tree,
+                &[&head_commit],
+            )
+            .map_err(CascadeError::Git)?;
+
+        // Update working directory to reflect the new commit
+        let new_commit = self
+            .repo
+            .find_commit(new_commit_oid)
+            .map_err(CascadeError::Git)?;
+        let new_tree = new_commit.tree().map_err(CascadeError::Git)?;
+
+        self.repo
+            .checkout_tree(
+                new_tree.as_object(),
+                Some(git2::build::CheckoutBuilder::new().force()),
+            )
+            .map_err(CascadeError::Git)?;
+        self.refresh_worktree_after_checkout();
+
+        tracing::debug!("Cherry-picked {} -> {}", commit_hash, new_commit_oid);
+        Ok(new_commit_oid.to_string())
+    }
+
+    /// Cherry-pick through the git CLI, which respects sparse checkout and fetches
+    /// missing blobs of a partial clone
+    fn cherry_pick_with_git_cli(&self, commit_hash: &str) -> Result<String> {
+        let mut args = self.rerere_config().to_vec();
+        args.extend(["cherry-pick", "--keep-redundant-commits", commit_hash]);
+        let output = self.run_worktree_git_cli(&args)?;
+
+        if !output.status.success() {
+            if let Some(head) = self.continue_with_recorded_resolutions(commit_hash)? {
+                return Ok(head);
+            }
+            if self.has_conflicts()? {
+                return Err(CascadeError::branch(format!(
+                    "Cherry-pick of {commit_hash} has conflicts that need manual resolution"
+                )));
+            }
+            return Err(CascadeError::branch(format!(
+                "Cherry-pick of {commit_hash} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        self.refresh_worktree_after_checkout();
+        let head = self.get_head_commit()?;
+        tracing::debug!("Cherry-picked {} -> {} (git CLI)", commit_hash, head.id());
+        Ok(head.id().to_string())
+    }
+
+    /// `-c` overrides that make a git CLI cherry-pick record its conflicts and reapply
+    /// (and stage) resolutions recorded for identical conflicts before
+    fn rerere_config(&self) -> &'static [&'static str] {
+        if self.rerere {
+            &["-c", "rerere.enabled=true", "-c", "rerere.autoupdate=true"]
+        } else {
+            &[]
+        }
+    }
+
+    /// Finish a conflicted cherry-pick when recorded resolutions took care of every
+    /// conflict. Returns the new HEAD, or `None` if conflicts remain for the user.
+    fn continue_with_recorded_resolutions(&self, commit_hash: &str) -> Result<Option<String>> {
+        if !self.rerere || !self.git_dir().join("CHERRY_PICK_HEAD").exists() {
+            return Ok(None);
+        }
+        if !self.get_conflicted_files()?.is_empty() {
+            return Ok(None);
+        }
+
+        let mut args = self.rerere_config().to_vec();
+        args.extend(["-c", "core.editor=true", "cherry-pick", "--continue"]);
+        let output = self.run_worktree_git_cli(&args)?;
+        if !output.status.success() {
+            debug!(
+                "Could not continue cherry-pick after reusing resolutions: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return Ok(None);
+        }
+
+        self.refresh_worktree_after_checkout();
+        Output::info(format!(
+            "Reused recorded conflict resolution for {}",
+            &commit_hash[..commit_hash.len().min(8)]
+        ));
+        let head = self.get_head_commit()?;
+        Ok(Some(head.id().to_string()))
+    }
+
+    /// Record how the conflicts of the in-progress cherry-pick were resolved so identical
+    /// conflicts are resolved automatically next time. No-op when `git.rerere` is off.
+    pub fn record_conflict_resolutions(&self) -> Result<()> {
+        if !self.rerere {
+            return Ok(());
+        }
+        let output = std::process::Command::new("git")
+            .args(["-c", "rerere.enabled=true", "rerere"])
+            .current_dir(&self.path)
+            .output()
+            .map_err(CascadeError::Io)?;
+        if output.status.success() {
+            debug!(
+                "Recorded conflict resolutions: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        } else {
+            debug!(
+                "git rerere failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
         }
+        Ok(())
+    }
 
-        // Write merged tree
-        let merged_tree_oid = index.write_tree_to(&self.repo).map_err(CascadeError::Git)?;
-        let merged_tree = self
+    /// Replay a commit on top of another commit entirely in memory.
+    ///
+    /// Unlike `cherry_pick`, this never touches HEAD, the index, or the working tree,
+    /// so it is safe for restructuring branches that aren't checked out. The original
+    /// author is preserved; `message` overrides the commit message when provided.
+    /// Returns a `Conflict` error if the change doesn't apply cleanly.
+    pub fn cherry_pick_onto(
+        &self,
+        commit_hash: &str,
+        onto: &str,
+        message: Option<&str>,
+    ) -> Result<String> {
+        let commit = self.get_commit(commit_hash)?;
+        let onto_commit = self.resolve_reference(onto)?;
+
+        let parent_tree = if commit.parent_count() > 0 {
+            commit.parent(0).map_err(CascadeError::Git)?.tree()?
+        } else {
+            let empty_tree_oid = self.repo.treebuilder(None)?.write()?;
+            self.repo.find_tree(empty_tree_oid)?
+        };
+        let onto_tree = onto_commit.tree().map_err(CascadeError::Git)?;
+        let commit_tree = commit.tree().map_err(CascadeError::Git)?;
+
+        let mut index = self
             .repo
-            .find_tree(merged_tree_oid)
+            .merge_trees(&parent_tree, &onto_tree, &commit_tree, None)
             .map_err(CascadeError::Git)?;
 
-        // Create new commit with original message (preserve it exactly)
-        let signature = self.get_signature()?;
-        let message = commit.message().unwrap_or("Cherry-picked commit");
+        if index.has_conflicts() {
+            let conflicted: Vec<String> = index
+                .conflicts()
+                .map_err(CascadeError::Git)?
+                .filter_map(|c| c.ok())
+                .filter_map(|c| c.our.or(c.their))
+                .filter_map(|entry| String::from_utf8(entry.path).ok())
+                .collect();
+            return Err(CascadeError::Conflict(format!(
+                "Commit {} does not apply cleanly onto {}: conflicts in {}",
+                &commit_hash[..commit_hash.len().min(8)],
+                onto,
+                conflicted.join(", ")
+            )));
+        }
 
-        let new_commit_oid = self
+        let tree_oid = index.write_tree_to(&self.repo).map_err(CascadeError::Git)?;
+        let tree = self.repo.find_tree(tree_oid).map_err(CascadeError::Git)?;
+        let committer = self.get_signature()?;
+        let message = message.unwrap_or_else(|| commit.message().unwrap_or(""));
+
+        let new_oid = self
             .repo
             .commit(
-                Some("HEAD"),
-                &signature,
-                &signature,
+                None,
+                &commit.author(),
+                &committer,
                 message,
-                &merged_tree,
-                &[&head_commit],
+                &tree,
+                &[&onto_commit],
             )
             .map_err(CascadeError::Git)?;
 
-        // Update working directory to reflect the new commit
-        let new_commit = self
-            .repo
-            .find_commit(new_commit_oid)
+        tracing::debug!("Replayed {} onto {} -> {}", commit_hash, onto, new_oid);
+        Ok(new_oid.to_string())
+    }
+
+    /// `cherry_pick_onto` for the `count` commits ending at `tip`, oldest first. Returns the
+    /// new tip; `message` rewords only the tip
+    pub fn cherry_pick_chain_onto(
+        &self,
+        tip: &str,
+        count: usize,
+        onto: &str,
+        message: Option<&str>,
+    ) -> Result<String> {
+        let mut onto = onto.to_string();
+        for commit in self.get_first_parent_chain(tip, count)? {
+            let message = if commit == tip { message } else { None };
+            onto = self.cherry_pick_onto(&commit, &onto, message)?;
+        }
+        Ok(onto)
+    }
+
+    /// Create a commit with the tree of `tree_commit` on top of `parent`, taking the author
+    /// and message from `template_commit`. Used to fold (squash) commits without a checkout.
+    pub fn commit_tree_onto(
+        &self,
+        tree_commit: &str,
+        parent: &str,
+        template_commit: &str,
+    ) -> Result<String> {
+        let tree = self
+            .get_commit(tree_commit)?
+            .tree()
             .map_err(CascadeError::Git)?;
-        let new_tree = new_commit.tree().map_err(CascadeError::Git)?;
+        let parent_commit = self.resolve_reference(parent)?;
+        let template = self.get_commit(template_commit)?;
+        let committer = self.get_signature()?;
 
-        self.repo
-            .checkout_tree(
-                new_tree.as_object(),
-                Some(git2::build::CheckoutBuilder::new().force()),
+        let new_oid = self
+            .repo
+            .commit(
+                None,
+                &template.author(),
+                &committer,
+                template.message().unwrap_or(""),
+                &tree,
+                &[&parent_commit],
             )
             .map_err(CascadeError::Git)?;
 
-        tracing::debug!("Cherry-picked {} -> {}", commit_hash, new_commit_oid);
-        Ok(new_commit_oid.to_string())
+        Ok(new_oid.to_string())
     }
 
     /// Check for merge conflicts in the index
@@ -1654,10 +2862,43 @@ impl GitRepository {
         Ok(conflicts)
     }
 
-    /// Fetch from remote origin
+    /// Fetch from remote origin, at most once per command run (see [`FetchPolicy`])
     pub fn fetch(&self) -> Result<()> {
+        self.fetch_with_policy(FetchPolicy::current())
+    }
+
+    fn fetch_with_policy(&self, policy: FetchPolicy) -> Result<()> {
+        let key = fetch_cache_key(&self.path);
+        let fetched = |repos: &Vec<PathBuf>| repos.contains(&key);
+        match policy {
+            FetchPolicy::Never => {
+                tracing::debug!("Skipping fetch (--no-fetch)");
+                return Ok(());
+            }
+            FetchPolicy::Once if FETCHED_REPOS.lock().is_ok_and(|repos| fetched(&repos)) => {
+                tracing::debug!("Already fetched origin in this run");
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        self.fetch_from_origin()?;
+        if let Ok(mut repos) = FETCHED_REPOS.lock() {
+            if !fetched(&repos) {
+                repos.push(key.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn fetch_from_origin(&self) -> Result<()> {
         tracing::debug!("Fetching from origin");
 
+        // libgit2 can't apply a partial clone's object filter and would download every blob
+        if self.features.partial_clone {
+            return self.fetch_with_git_cli();
+        }
+
         // CRITICAL: Ensure index is closed before fetch operation
         // This prevents "index is locked" errors when fetch is called after cherry-pick/commit
         self.ensure_index_closed()?;
@@ -1673,13 +2914,18 @@ impl GitRepository {
         // Fetch options with authentication and SSL config
         let mut fetch_options = git2::FetchOptions::new();
         fetch_options.remote_callbacks(callbacks);
+        fetch_options.proxy_options(self.proxy_options());
 
-        // Fetch with authentication
+        // Fetch with authentication; Ctrl-C cancels the transfer instead of killing the process
+        let _interrupt = InterruptGuard::new();
         match remote.fetch::<&str>(&[], Some(&mut fetch_options), None) {
             Ok(_) => {
                 tracing::debug!("Fetch completed successfully");
                 Ok(())
             }
+            Err(_) if interrupt::requested() => Err(interrupt::interrupted_error(
+                "fetch cancelled; nothing was changed",
+            )),
             Err(e) => {
                 if self.should_retry_with_default_credentials(&e) {
                     tracing::debug!(
@@ -1691,6 +2937,7 @@ impl GitRepository {
                     let callbacks = self.configure_remote_callbacks_with_fallback(true)?;
                     let mut fetch_options = git2::FetchOptions::new();
                     fetch_options.remote_callbacks(callbacks);
+                    fetch_options.proxy_options(self.proxy_options());
 
                     match remote.fetch::<&str>(&[], Some(&mut fetch_options), None) {
                         Ok(_) => {
@@ -1962,6 +3209,8 @@ impl GitRepository {
 
     /// Push current branch to remote
     pub fn push(&self, branch: &str) -> Result<()> {
+        self.ensure_not_protected(branch, "push")?;
+
         // Pushing branch to remote
 
         let mut remote = self
@@ -1991,6 +3240,7 @@ impl GitRepository {
         // Push options with authentication and SSL config
         let mut push_options = git2::PushOptions::new();
         push_options.remote_callbacks(callbacks);
+        push_options.proxy_options(self.proxy_options());
 
         // Attempt push with enhanced error reporting
         match remote.push(&[&refspec], Some(&mut push_options)) {
@@ -2016,6 +3266,7 @@ impl GitRepository {
                     let callbacks = self.configure_remote_callbacks_with_fallback(true)?;
                     let mut push_options = git2::PushOptions::new();
                     push_options.remote_callbacks(callbacks);
+                    push_options.proxy_options(self.proxy_options());
 
                     match remote.push(&[&refspec], Some(&mut push_options)) {
                         Ok(_) => {
@@ -2060,9 +3311,9 @@ impl GitRepository {
         // Ensure index is closed before CLI command
         self.ensure_index_closed()?;
 
-        let output = std::process::Command::new("git")
+        let output = self
+            .git_remote_command()
             .args(["push", "origin", branch])
-            .current_dir(&self.path)
             .output()
             .map_err(|e| CascadeError::branch(format!("Failed to execute git command: {e}")))?;
 
@@ -2095,9 +3346,9 @@ impl GitRepository {
         // Ensure index is closed before CLI command
         self.ensure_index_closed()?;
 
-        let output = std::process::Command::new("git")
+        let output = self
+            .git_remote_command()
             .args(["fetch", "origin"])
-            .current_dir(&self.path)
             .output()
             .map_err(|e| {
                 CascadeError::Git(git2::Error::from_str(&format!(
@@ -2127,9 +3378,9 @@ impl GitRepository {
         // Ensure index is closed before CLI command
         self.ensure_index_closed()?;
 
-        let output = std::process::Command::new("git")
+        let output = self
+            .git_remote_command()
             .args(["pull", "origin", branch])
-            .current_dir(&self.path)
             .output()
             .map_err(|e| {
                 CascadeError::Git(git2::Error::from_str(&format!(
@@ -2153,15 +3404,20 @@ impl GitRepository {
 
     /// Fallback force push method using git CLI instead of git2
     /// This is used when git2 has TLS/SSL issues but git CLI works fine
-    fn force_push_with_git_cli(&self, branch: &str) -> Result<()> {
+    fn force_push_with_git_cli(&self, branch: &str, lease: Option<&str>) -> Result<()> {
         tracing::debug!(
             "Using git CLI fallback for force push operation: {}",
             branch
         );
 
-        let output = std::process::Command::new("git")
-            .args(["push", "--force", "origin", branch])
-            .current_dir(&self.path)
+        let output = self
+            .git_remote_command()
+            .args([
+                "push",
+                &force_with_lease_arg(branch, lease),
+                "origin",
+                branch,
+            ])
             .output()
             .map_err(|e| CascadeError::branch(format!("Failed to execute git command: {e}")))?;
 
@@ -2170,6 +3426,9 @@ impl GitRepository {
             Ok(())
         } else {
             let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("stale info") {
+                return Err(stale_lease_error(branch, lease));
+            }
             let stdout = String::from_utf8_lossy(&output.stdout);
             let error_msg = format!(
                 "Git CLI force push failed for branch '{}': {}\nStdout: {}\nStderr: {}",
@@ -2192,6 +3451,7 @@ impl GitRepository {
     /// Internal branch deletion implementation with safety options
     fn delete_branch_with_options(&self, name: &str, force_unsafe: bool) -> Result<()> {
         debug!("Attempting to delete branch: {}", name);
+        self.ensure_not_protected(name, "delete")?;
 
         // Enhanced safety check: Detect unpushed commits before deletion
         if !force_unsafe {
@@ -2234,8 +3494,16 @@ impl GitRepository {
         revwalk.push(to_oid).map_err(CascadeError::Git)?;
         revwalk.hide(from_oid).map_err(CascadeError::Git)?;
 
+        let limit = self.walk_limit();
         let mut commits = Vec::new();
         for oid in revwalk {
+            if commits.len() == limit {
+                warn!(
+                    "Stopped after {} commits between '{}' and '{}' (git.commit_walk_limit)",
+                    limit, from, to
+                );
+                break;
+            }
             let oid = oid.map_err(CascadeError::Git)?;
             let commit = self.repo.find_commit(oid).map_err(CascadeError::Git)?;
             commits.push(commit);
@@ -2247,36 +3515,57 @@ impl GitRepository {
     /// Force push one branch's content to another branch name
     /// This is used to preserve PR history while updating branch contents after rebase
     pub fn force_push_branch(&self, target_branch: &str, source_branch: &str) -> Result<()> {
-        self.force_push_branch_with_options(target_branch, source_branch, false)
+        self.force_push_branch_with_options(target_branch, source_branch, false, None)
+    }
+
+    /// Force push one branch's content to another, refusing if the remote target moved
+    /// away from `expected_remote` (the commit Cascade last pushed for it)
+    pub fn force_push_branch_with_lease(
+        &self,
+        target_branch: &str,
+        source_branch: &str,
+        expected_remote: Option<&str>,
+    ) -> Result<()> {
+        self.force_push_branch_with_options(target_branch, source_branch, false, expected_remote)
     }
 
     /// Force push with explicit force flag to bypass safety checks
     pub fn force_push_branch_unsafe(&self, target_branch: &str, source_branch: &str) -> Result<()> {
-        self.force_push_branch_with_options(target_branch, source_branch, true)
+        self.force_push_branch_with_options(target_branch, source_branch, true, None)
     }
 
-    /// Internal force push implementation with safety options
     fn force_push_branch_with_options(
         &self,
         target_branch: &str,
         source_branch: &str,
         force_unsafe: bool,
+        expected_remote: Option<&str>,
+    ) -> Result<()> {
+        self.push_branch_forced(target_branch, source_branch, force_unsafe, expected_remote)
+            .inspect(|_| log_force_push(target_branch))
+    }
+
+    /// Internal force push implementation with safety options
+    fn push_branch_forced(
+        &self,
+        target_branch: &str,
+        source_branch: &str,
+        force_unsafe: bool,
+        expected_remote: Option<&str>,
     ) -> Result<()> {
         debug!(
             "Force pushing {} content to {} to preserve PR history",
             source_branch, target_branch
         );
+        self.ensure_not_protected(target_branch, "force-push")?;
 
         // Enhanced safety check: Detect potential data loss and get user confirmation
         if !force_unsafe {
             let safety_result = self.check_force_push_safety_enhanced(target_branch)?;
             if let Some(backup_info) = safety_result {
-                // Create backup branch before force push
-                self.create_backup_branch(target_branch, &backup_info.remote_commit_id)?;
-                Output::sub_item(format!(
-                    "Created backup branch: {}",
-                    backup_info.backup_branch_name
-                ));
+                // Back up the remote head before force push
+                let backup = self.create_backup(target_branch, &backup_info.remote_commit_id)?;
+                Output::sub_item(format!("Created backup: {backup}"));
             }
         }
 
@@ -2293,6 +3582,14 @@ impl GitRepository {
             ))
         })?;
 
+        // libgit2 has no lease support, so compare against the freshly fetched remote ref
+        let lease = self.resolve_push_lease(target_branch, source_branch, expected_remote);
+        if let Some(lease) = lease.as_deref() {
+            if self.get_remote_branch_head(target_branch).ok().as_deref() != Some(lease) {
+                return Err(stale_lease_error(target_branch, Some(lease)));
+            }
+        }
+
         // Force push to remote without modifying local target branch
         let mut remote = self
             .repo
@@ -2308,6 +3605,7 @@ impl GitRepository {
         // Push options for force push with SSL config
         let mut push_options = git2::PushOptions::new();
         push_options.remote_callbacks(callbacks);
+        push_options.proxy_options(self.proxy_options());
 
         match remote.push(&[&refspec], Some(&mut push_options)) {
             Ok(_) => {}
@@ -2322,6 +3620,7 @@ impl GitRepository {
                     let callbacks = self.configure_remote_callbacks_with_fallback(true)?;
                     let mut push_options = git2::PushOptions::new();
                     push_options.remote_callbacks(callbacks);
+                    push_options.proxy_options(self.proxy_options());
 
                     match remote.push(&[&refspec], Some(&mut push_options)) {
                         Ok(_) => {
@@ -2333,7 +3632,7 @@ impl GitRepository {
                                 "DefaultCredentials retry failed: {}, falling back to git CLI",
                                 retry_error
                             );
-                            return self.force_push_with_git_cli(target_branch);
+                            return self.force_push_with_git_cli(target_branch, lease.as_deref());
                         }
                     }
                 } else if self.should_fallback_to_git_cli(&e) {
@@ -2341,7 +3640,7 @@ impl GitRepository {
                         "Network/SSL error detected (class: {:?}, code: {:?}): {}, falling back to git CLI for force push operation",
                         e.class(), e.code(), e
                     );
-                    return self.force_push_with_git_cli(target_branch);
+                    return self.force_push_with_git_cli(target_branch, lease.as_deref());
                 } else {
                     return Err(CascadeError::config(format!(
                         "Failed to force push {target_branch}: {e}"
@@ -2357,6 +3656,40 @@ impl GitRepository {
         Ok(())
     }
 
+    /// Resolve the commit a force push of `remote_branch` expects to find on the remote.
+    ///
+    /// `expected` is the commit Cascade last pushed. If the remote has since moved but
+    /// `local_branch` already contains the new remote commit (the changes were incorporated,
+    /// or the branch was deliberately overwritten), nothing would be lost and the current
+    /// remote commit becomes the lease. `None` falls back to the remote-tracking ref.
+    fn resolve_push_lease(
+        &self,
+        remote_branch: &str,
+        local_branch: &str,
+        expected: Option<&str>,
+    ) -> Option<String> {
+        let expected = expected?;
+        let Ok(remote_head) = self.get_remote_branch_head(remote_branch) else {
+            return Some(expected.to_string());
+        };
+        if remote_head == expected {
+            return Some(remote_head);
+        }
+
+        let contains_remote = self.get_branch_head(local_branch).is_ok_and(|local| {
+            local == remote_head || self.is_descendant_of(&local, &remote_head).unwrap_or(false)
+        });
+        if contains_remote {
+            debug!(
+                "Remote {} moved to {} but is already contained locally; using it as the lease",
+                remote_branch, remote_head
+            );
+            Some(remote_head)
+        } else {
+            Some(expected.to_string())
+        }
+    }
+
     /// Enhanced safety check for force push operations with user confirmation
     /// Returns backup info if data would be lost and user confirms
     fn check_force_push_safety_enhanced(
@@ -2403,10 +3736,6 @@ impl GitRepository {
                         &remote.id().to_string(),
                     )?;
 
-                    // Create backup branch name with timestamp
-                    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-                    let backup_branch_name = format!("{target_branch}_backup_{timestamp}");
-
                     debug!(
                         "Force push to '{}' would overwrite {} commits on remote",
                         target_branch, commits_to_lose
@@ -2419,7 +3748,6 @@ impl GitRepository {
                             "Non-interactive environment detected, proceeding with backup creation"
                         );
                         return Ok(Some(ForceBackupInfo {
-                            backup_branch_name,
                             remote_commit_id: remote.id().to_string(),
                             commits_that_would_be_lost: commits_to_lose,
                         }));
@@ -2427,7 +3755,6 @@ impl GitRepository {
 
                     // Automatically create backup - this is normal stacked diff workflow
                     return Ok(Some(ForceBackupInfo {
-                        backup_branch_name,
                         remote_commit_id: remote.id().to_string(),
                         commits_that_would_be_lost: commits_to_lose,
                     }));
@@ -2478,18 +3805,14 @@ impl GitRepository {
                         &remote.id().to_string(),
                     )?;
 
-                    // Create backup branch name with timestamp
-                    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-                    let backup_branch_name = format!("{target_branch}_backup_{timestamp}");
-
                     tracing::debug!(
-                        "Auto-creating backup '{}' for force push to '{}' (would overwrite {} commits)",
-                        backup_branch_name, target_branch, commits_to_lose
+                        "Auto-creating backup for force push to '{}' (would overwrite {} commits)",
+                        target_branch,
+                        commits_to_lose
                     );
 
                     // Automatically create backup without confirmation
                     return Ok(Some(ForceBackupInfo {
-                        backup_branch_name,
                         remote_commit_id: remote.id().to_string(),
                         commits_that_would_be_lost: commits_to_lose,
                     }));
@@ -2500,38 +3823,218 @@ impl GitRepository {
         Ok(None)
     }
 
-    /// Create a backup branch pointing to the remote commit that would be lost
-    fn create_backup_branch(&self, original_branch: &str, remote_commit_id: &str) -> Result<()> {
-        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-        let backup_branch_name = format!("{original_branch}_backup_{timestamp}");
-
-        // Parse the commit ID
-        let commit_oid = Oid::from_str(remote_commit_id).map_err(|e| {
-            CascadeError::config(format!("Invalid commit ID {remote_commit_id}: {e}"))
-        })?;
-
-        // Find the commit
-        let commit = self.repo.find_commit(commit_oid).map_err(|e| {
-            CascadeError::config(format!("Failed to find commit {remote_commit_id}: {e}"))
-        })?;
+    /// Set `trailers` in the cascade note of `commit_hash`, keeping anything else the
+    /// note already says
+    pub fn add_trailers(&self, commit_hash: &str, trailers: &[(&str, &str)]) -> Result<()> {
+        let commit_oid = Oid::from_str(commit_hash)
+            .map_err(|e| CascadeError::config(format!("Invalid commit ID {commit_hash}: {e}")))?;
+        let existing = self.get_trailers(commit_hash).unwrap_or_default();
+        let note = trailers::merge_trailers(&existing, trailers);
+        if note == existing {
+            return Ok(());
+        }
 
-        // Create the backup branch
+        let signature = self.get_signature()?;
         self.repo
-            .branch(&backup_branch_name, &commit, false)
+            .note(
+                &signature,
+                &signature,
+                Some(TRAILERS_NOTES_REF),
+                commit_oid,
+                &note,
+                true,
+            )
             .map_err(|e| {
-                CascadeError::config(format!(
-                    "Failed to create backup branch {backup_branch_name}: {e}"
-                ))
+                CascadeError::config(format!("Failed to write note on {commit_hash}: {e}"))
             })?;
-
         debug!(
-            "Created backup branch '{}' pointing to {}",
-            backup_branch_name,
-            &remote_commit_id[..8]
+            "Recorded trailers on {}: {:?}",
+            &commit_hash[..8.min(commit_hash.len())],
+            trailers
         );
         Ok(())
     }
 
+    /// The cascade note of `commit_hash`, if it has one
+    pub fn get_trailers(&self, commit_hash: &str) -> Option<String> {
+        let commit_oid = Oid::from_str(commit_hash).ok()?;
+        let note = self
+            .repo
+            .find_note(Some(TRAILERS_NOTES_REF), commit_oid)
+            .ok()?;
+        note.message().map(str::to_string)
+    }
+
+    /// Publish the trailer notes to origin so other clones can read them. Notes another
+    /// clone published first are merged in, keeping the lines of both, before pushing.
+    ///
+    /// The git CLI is used since libgit2 has no notes merge.
+    pub fn push_trailer_notes(&self) -> Result<()> {
+        if self.repo.find_reference(TRAILERS_NOTES_REF).is_err()
+            || self.repo.find_remote("origin").is_err()
+        {
+            return Ok(());
+        }
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(&self.path)
+                .env("CASCADE_SKIP_HOOKS", "1")
+                .output()
+                .map_err(|e| CascadeError::branch(format!("Failed to run git {}: {e}", args[0])))
+        };
+
+        let remote_notes = format!("{TRAILERS_NOTES_REF}-origin");
+        // Fails when origin has no notes yet, leaving nothing to merge
+        let fetched = git(&[
+            "fetch",
+            "origin",
+            &format!("+{TRAILERS_NOTES_REF}:{remote_notes}"),
+        ])?;
+        if fetched.status.success() {
+            let merged = git(&[
+                "notes",
+                "--ref",
+                TRAILERS_NOTES_REF,
+                "merge",
+                "--strategy",
+                "cat_sort_uniq",
+                &remote_notes,
+            ])?;
+            if !merged.status.success() {
+                return Err(CascadeError::branch(format!(
+                    "Failed to merge origin's {TRAILERS_NOTES_REF}: {}",
+                    String::from_utf8_lossy(&merged.stderr).trim()
+                )));
+            }
+        }
+
+        let pushed = git(&["push", "origin", TRAILERS_NOTES_REF])?;
+        if !pushed.status.success() {
+            return Err(CascadeError::branch(format!(
+                "Failed to push {TRAILERS_NOTES_REF}: {}",
+                String::from_utf8_lossy(&pushed.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Record `commit_id` under `refs/cascade/backup/<branch>/<timestamp>` before it is
+    /// overwritten, then prune backups outside the `backups.*` retention policy.
+    /// Returns the name of the new backup ref.
+    pub fn create_backup(&self, branch_name: &str, commit_id: &str) -> Result<String> {
+        let commit_oid = Oid::from_str(commit_id)
+            .map_err(|e| CascadeError::config(format!("Invalid commit ID {commit_id}: {e}")))?;
+
+        // Make sure the commit exists locally so the ref keeps it reachable
+        self.repo
+            .find_commit(commit_oid)
+            .map_err(|e| CascadeError::config(format!("Failed to find commit {commit_id}: {e}")))?;
+
+        // Backups are keyed by second; step past any taken earlier in the same second
+        let mut at = chrono::Utc::now();
+        let mut refname = backups::backup_ref_name(branch_name, at);
+        while self.repo.find_reference(&refname).is_ok() {
+            at += chrono::Duration::seconds(1);
+            refname = backups::backup_ref_name(branch_name, at);
+        }
+
+        self.repo
+            .reference(
+                &refname,
+                commit_oid,
+                false,
+                &format!("cascade: backup of {branch_name}"),
+            )
+            .map_err(|e| CascadeError::config(format!("Failed to create backup {refname}: {e}")))?;
+
+        debug!(
+            "Created backup '{}' pointing to {}",
+            refname,
+            &commit_id[..8.min(commit_id.len())]
+        );
+
+        // Pruning is housekeeping; never fail the operation that asked for a backup
+        if let Err(e) = self.prune_backups() {
+            warn!("Could not prune old backups: {}", e);
+        }
+
+        Ok(refname)
+    }
+
+    /// All backup refs, grouped by branch with the newest backup first
+    pub fn list_backups(&self) -> Result<Vec<BackupRef>> {
+        let references = self
+            .repo
+            .references_glob(&format!("{BACKUP_REF_PREFIX}*"))
+            .map_err(|e| CascadeError::config(format!("Failed to list backups: {e}")))?;
+
+        let mut backups: Vec<BackupRef> = references
+            .flatten()
+            .filter_map(|reference| {
+                let refname = reference.name()?.to_string();
+                let commit = reference.peel_to_commit().ok()?;
+                BackupRef::parse(&refname, commit.id().to_string())
+            })
+            .collect();
+
+        backups.sort_by(|a, b| {
+            a.branch
+                .cmp(&b.branch)
+                .then_with(|| b.created_at.cmp(&a.created_at))
+        });
+        Ok(backups)
+    }
+
+    /// Delete backups outside the `backups.*` retention policy; returns how many were removed
+    pub fn prune_backups(&self) -> Result<usize> {
+        let backups = self.list_backups()?;
+        let prunable = backups::prunable_backups(&backups, &self.backup_policy, chrono::Utc::now());
+
+        for backup in &prunable {
+            let mut reference = self.repo.find_reference(&backup.refname).map_err(|e| {
+                CascadeError::config(format!("Failed to find backup {}: {e}", backup.refname))
+            })?;
+            reference.delete().map_err(|e| {
+                CascadeError::config(format!("Failed to delete backup {}: {e}", backup.refname))
+            })?;
+            debug!("Pruned backup '{}'", backup.refname);
+        }
+
+        Ok(prunable.len())
+    }
+
+    /// Point `backup.branch` back at the backed-up commit.
+    ///
+    /// The branch's current head is backed up first so the restore can itself be undone;
+    /// that backup's ref name is returned. Restoring the checked-out branch requires a
+    /// clean working tree, which is then reset to the restored commit.
+    pub fn restore_backup(&self, backup: &BackupRef) -> Result<Option<String>> {
+        let branch = backup.branch.as_str();
+        self.ensure_not_protected(branch, "restore")?;
+
+        let is_current = self.get_current_branch().ok().as_deref() == Some(branch);
+        if is_current && self.is_dirty()? {
+            return Err(CascadeError::branch(format!(
+                "Cannot restore '{branch}' while it is checked out with uncommitted changes. \
+                 Commit or stash them first."
+            )));
+        }
+
+        let previous = match self.get_branch_commit_hash(branch) {
+            Ok(head) if head == backup.commit_hash => return Ok(None),
+            Ok(head) => Some(self.create_backup(branch, &head)?),
+            Err(_) => None,
+        };
+
+        self.update_branch_to_commit(branch, &backup.commit_hash)?;
+        if is_current {
+            self.reset_to_head()?;
+        }
+
+        Ok(previous)
+    }
+
     /// Check if branch deletion is safe by detecting unpushed commits
     /// Returns safety info if there are concerns that need user attention
     fn check_branch_deletion_safety(
@@ -2629,15 +4132,15 @@ impl GitRepository {
         }
 
         // Interactive warning and confirmation
-        println!();
+        Output::spacing();
         Output::warning("BRANCH DELETION WARNING");
-        println!("Branch '{branch_name}' has potential issues:");
+        Output::line(format!("Branch '{branch_name}' has potential issues:"));
 
         if !safety_info.unpushed_commits.is_empty() {
-            println!(
-                "\n🔍 Unpushed commits ({} total):",
+            Output::section(format!(
+                "Unpushed commits ({} total)",
                 safety_info.unpushed_commits.len()
-            );
+            ));
 
             // Show details of unpushed commits
             for (i, commit_id) in safety_info.unpushed_commits.iter().take(5).enumerate() {
@@ -2645,21 +4148,20 @@ impl GitRepository {
                     if let Ok(commit) = self.repo.find_commit(oid) {
                         let short_hash = &commit_id[..8];
                         let summary = commit.summary().unwrap_or("<no message>");
-                        println!("  {}. {} - {}", i + 1, short_hash, summary);
+                        Output::numbered_item(i + 1, format!("{short_hash} - {summary}"));
                     }
                 }
             }
 
             if safety_info.unpushed_commits.len() > 5 {
-                println!(
-                    "  ... and {} more commits",
+                Output::sub_item(format!(
+                    "... and {} more commits",
                     safety_info.unpushed_commits.len() - 5
-                );
+                ));
             }
         }
 
         if !safety_info.is_merged_to_main {
-            println!();
             crate::cli::output::Output::section("Branch status");
             crate::cli::output::Output::bullet(format!(
                 "Not merged to '{}'",
@@ -2672,27 +4174,25 @@ impl GitRepository {
             }
         }
 
-        println!();
         crate::cli::output::Output::section("Safer alternatives");
         if !safety_info.unpushed_commits.is_empty() {
             if let Some(ref _remote) = safety_info.remote_tracking_branch {
-                println!("  • Push commits first: git push origin {branch_name}");
+                Output::bullet(format!("Push commits first: git push origin {branch_name}"));
             } else {
-                println!("  • Create and push to remote: git push -u origin {branch_name}");
+                Output::bullet(format!(
+                    "Create and push to remote: git push -u origin {branch_name}"
+                ));
             }
         }
         if !safety_info.is_merged_to_main {
-            println!(
-                "  • Merge to {} first: git checkout {} && git merge {branch_name}",
+            Output::bullet(format!(
+                "Merge to {} first: git checkout {} && git merge {branch_name}",
                 safety_info.main_branch_name, safety_info.main_branch_name
-            );
+            ));
         }
 
-        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Do you want to proceed with deleting this branch?")
-            .default(false)
-            .interact()
-            .map_err(|e| CascadeError::branch(format!("Failed to get user confirmation: {e}")))?;
+        let confirmed =
+            prompter().confirm("Do you want to proceed with deleting this branch?", false)?;
 
         if !confirmed {
             return Err(CascadeError::branch(
@@ -2817,56 +4317,44 @@ impl GitRepository {
         }
 
         // Interactive warning and confirmation
-        println!("\nCHECKOUT WARNING");
-        println!("Attempting to checkout: {}", target);
-        println!("You have uncommitted changes that could be lost:");
-
-        if !safety_info.modified_files.is_empty() {
-            println!("\nModified files ({}):", safety_info.modified_files.len());
-            for file in safety_info.modified_files.iter().take(10) {
-                println!("   - {file}");
-            }
-            if safety_info.modified_files.len() > 10 {
-                println!("   ... and {} more", safety_info.modified_files.len() - 10);
-            }
-        }
-
-        if !safety_info.staged_files.is_empty() {
-            println!("\nStaged files ({}):", safety_info.staged_files.len());
-            for file in safety_info.staged_files.iter().take(10) {
-                println!("   - {file}");
+        Output::spacing();
+        Output::warning("CHECKOUT WARNING");
+        Output::line(format!("Attempting to checkout: {target}"));
+        Output::line("You have uncommitted changes that could be lost:");
+
+        for (title, files, shown) in [
+            ("Modified files", &safety_info.modified_files, 10),
+            ("Staged files", &safety_info.staged_files, 10),
+            ("Untracked files", &safety_info.untracked_files, 5),
+        ] {
+            if files.is_empty() {
+                continue;
             }
-            if safety_info.staged_files.len() > 10 {
-                println!("   ... and {} more", safety_info.staged_files.len() - 10);
+            Output::section(format!("{title} ({})", files.len()));
+            for file in files.iter().take(shown) {
+                Output::bullet(file);
             }
-        }
-
-        if !safety_info.untracked_files.is_empty() {
-            println!("\nUntracked files ({}):", safety_info.untracked_files.len());
-            for file in safety_info.untracked_files.iter().take(5) {
-                println!("   - {file}");
-            }
-            if safety_info.untracked_files.len() > 5 {
-                println!("   ... and {} more", safety_info.untracked_files.len() - 5);
+            if files.len() > shown {
+                Output::sub_item(format!("... and {} more", files.len() - shown));
             }
         }
 
-        println!("\nOptions:");
-        println!("1. Stash changes and checkout (recommended)");
-        println!("2. Force checkout (WILL LOSE UNCOMMITTED CHANGES)");
-        println!("3. Cancel checkout");
+        Output::section("Options");
+        Output::numbered_item(1, "Stash changes and checkout (recommended)");
+        Output::numbered_item(2, "Force checkout (WILL LOSE UNCOMMITTED CHANGES)");
+        Output::numbered_item(3, "Cancel checkout");
 
         // Use proper selection dialog instead of y/n confirmation
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Choose an action")
-            .items(&[
+        let selection = prompter().select(
+            "Choose an action",
+            &[
                 "Stash changes and checkout (recommended)",
                 "Force checkout (WILL LOSE UNCOMMITTED CHANGES)",
                 "Cancel checkout",
-            ])
-            .default(0)
-            .interact()
-            .map_err(|e| CascadeError::branch(format!("Could not get user selection: {e}")))?;
+            ]
+            .map(String::from),
+            0,
+        )?;
 
         match selection {
             0 => {
@@ -2888,31 +4376,28 @@ impl GitRepository {
                         crate::cli::output::Output::error(format!("Failed to create stash: {e}"));
 
                         // If stash failed, provide better options
-                        use dialoguer::Select;
-                        let stash_failed_options = vec![
+                        let stash_failed_options = [
                             "Commit staged changes and proceed",
                             "Force checkout (WILL LOSE CHANGES)",
                             "Cancel and handle manually",
-                        ];
+                        ]
+                        .map(String::from);
 
-                        let stash_selection = Select::with_theme(&ColorfulTheme::default())
-                            .with_prompt("Stash failed. What would you like to do?")
-                            .items(&stash_failed_options)
-                            .default(0)
-                            .interact()
-                            .map_err(|e| {
-                                CascadeError::branch(format!("Could not get user selection: {e}"))
-                            })?;
+                        let stash_selection = prompter().select(
+                            "Stash failed. What would you like to do?",
+                            &stash_failed_options,
+                            0,
+                        )?;
 
                         match stash_selection {
                             0 => {
                                 // Try to commit staged changes
                                 let staged_files = self.get_staged_files()?;
                                 if !staged_files.is_empty() {
-                                    println!(
-                                        "📝 Committing {} staged files...",
+                                    Output::info(format!(
+                                        "Committing {} staged files...",
                                         staged_files.len()
-                                    );
+                                    ));
                                     match self
                                         .commit_staged_changes("WIP: Auto-commit before checkout")
                                     {
@@ -2931,16 +4416,16 @@ impl GitRepository {
                                             );
                                         }
                                         Err(commit_err) => {
-                                            println!(
-                                                "❌ Failed to commit staged changes: {commit_err}"
-                                            );
+                                            Output::error(format!(
+                                                "Failed to commit staged changes: {commit_err}"
+                                            ));
                                             return Err(CascadeError::branch(
                                                 "Could not commit staged changes".to_string(),
                                             ));
                                         }
                                     }
                                 } else {
-                                    println!("No staged changes to commit");
+                                    Output::info("No staged changes to commit");
                                 }
                             }
                             1 => {
@@ -3035,6 +4520,84 @@ impl GitRepository {
         }
     }
 
+    /// Stash all uncommitted changes, including untracked files but not `.cascade/`.
+    /// Returns the stash commit, or `None` when there was nothing to stash.
+    pub fn stash_all(&self, message: &str) -> Result<Option<String>> {
+        let stash_head = || self.repo.refname_to_id("refs/stash").ok();
+        let before = stash_head();
+
+        let output = std::process::Command::new("git")
+            .args([
+                "stash",
+                "push",
+                "--include-untracked",
+                "-m",
+                message,
+                "--",
+                ".",
+                ":(exclude).cascade",
+            ])
+            .current_dir(&self.path)
+            .output()
+            .map_err(|e| {
+                CascadeError::branch(format!("Failed to execute git stash command: {e}"))
+            })?;
+
+        if !output.status.success() {
+            return Err(CascadeError::branch(format!(
+                "Failed to stash changes: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(stash_head()
+            .filter(|head| Some(*head) != before)
+            .map(|head| head.to_string()))
+    }
+
+    /// Re-apply a stash created by [`stash_all`](Self::stash_all) and drop it once it
+    /// applied cleanly. Conflicted or refused applies leave the stash entry in place.
+    pub fn restore_stash(&self, stash_commit: &str) -> Result<StashRestore> {
+        let output = std::process::Command::new("git")
+            .args(["stash", "apply", stash_commit])
+            .current_dir(&self.path)
+            .output()
+            .map_err(|e| CascadeError::branch(format!("Failed to execute git stash apply: {e}")))?;
+
+        if !output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if stdout.contains("CONFLICT") {
+                let conflicted = self.get_conflicted_files().unwrap_or_default();
+                return Ok(StashRestore::Conflicted(conflicted));
+            }
+            return Ok(StashRestore::Failed(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        // `git stash drop` needs the entry's position, which may have moved
+        let list = std::process::Command::new("git")
+            .args(["stash", "list", "--format=%H"])
+            .current_dir(&self.path)
+            .output()
+            .map_err(|e| CascadeError::branch(format!("Failed to list stashes: {e}")))?;
+        let position = String::from_utf8_lossy(&list.stdout)
+            .lines()
+            .position(|line| line.trim() == stash_commit);
+        if let Some(position) = position {
+            let dropped = std::process::Command::new("git")
+                .args(["stash", "drop", &format!("stash@{{{position}}}")])
+                .current_dir(&self.path)
+                .output()
+                .map_err(|e| CascadeError::branch(format!("Failed to drop stash: {e}")))?;
+            if !dropped.status.success() {
+                warn!("Could not drop applied stash {}", stash_commit);
+            }
+        }
+
+        Ok(StashRestore::Applied)
+    }
+
     /// Get modified files in working directory
     fn get_modified_files(&self) -> Result<Vec<String>> {
         let mut opts = git2::StatusOptions::new();
@@ -3045,12 +4608,16 @@ impl GitRepository {
             .statuses(Some(&mut opts))
             .map_err(|e| CascadeError::branch(format!("Could not get repository status: {e}")))?;
 
+        let sparse_excluded = self.sparse_excluded_paths();
         let mut modified_files = Vec::new();
         for status in statuses.iter() {
             let flags = status.status();
             if flags.contains(git2::Status::WT_MODIFIED) || flags.contains(git2::Status::WT_DELETED)
             {
                 if let Some(path) = status.path() {
+                    if flags == git2::Status::WT_DELETED && sparse_excluded.contains(path) {
+                        continue;
+                    }
                     modified_files.push(path.to_string());
                 }
             }
@@ -3085,6 +4652,44 @@ impl GitRepository {
         Ok(staged_files)
     }
 
+    /// Files a commit touches, compared with its first parent
+    pub fn get_commit_files(&self, commit_hash: &str) -> Result<Vec<String>> {
+        let commit = self.get_commit(commit_hash)?;
+        let tree = commit.tree().map_err(CascadeError::Git)?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree().map_err(CascadeError::Git)?),
+            Err(_) => None,
+        };
+
+        let diff = self
+            .repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+            .map_err(CascadeError::Git)?;
+
+        Ok(diff
+            .deltas()
+            .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+            .map(|path| path.to_string_lossy().to_string())
+            .collect())
+    }
+
+    /// The `count` commits ending at `tip`, following first parents, oldest first
+    pub fn get_first_parent_chain(&self, tip: &str, count: usize) -> Result<Vec<String>> {
+        let mut commit = self.get_commit(tip)?;
+        let mut chain = vec![tip.to_string()];
+        while chain.len() < count {
+            commit = commit.parent(0).map_err(|_| {
+                CascadeError::branch(format!(
+                    "{} has fewer than {count} commits behind it",
+                    &tip[..8.min(tip.len())]
+                ))
+            })?;
+            chain.push(commit.id().to_string());
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+
     /// Count commits between two references
     fn count_commits_between(&self, from: &str, to: &str) -> Result<usize> {
         let commits = self.get_commits_between(from, to)?;
@@ -3128,6 +4733,18 @@ impl GitRepository {
     pub fn reset_to_head(&self) -> Result<()> {
         tracing::debug!("Resetting working directory and index to HEAD");
 
+        if self.features.needs_git_cli() {
+            let output = self.run_worktree_git_cli(&["reset", "--hard", "--quiet", "HEAD"])?;
+            if !output.status.success() {
+                return Err(CascadeError::branch(format!(
+                    "Failed to reset to HEAD: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            self.refresh_worktree_after_checkout();
+            return Ok(());
+        }
+
         let repo_path = self.path();
 
         // Use lock retry wrapper to handle stale locks automatically
@@ -3149,6 +4766,8 @@ impl GitRepository {
             Ok(())
         })?;
 
+        self.refresh_worktree_after_checkout();
+
         tracing::debug!("Successfully reset working directory to HEAD");
         Ok(())
     }
@@ -3171,15 +4790,15 @@ impl GitRepository {
             if let Some(branch_name) = branch.name().map_err(CascadeError::Git)? {
                 // Check if this branch contains the commit
                 if let Ok(branch_head) = branch.get().peel_to_commit() {
-                    // Walk the commit history from this branch's HEAD
-                    let mut revwalk = self.repo.revwalk().map_err(CascadeError::Git)?;
-                    revwalk.push(branch_head.id()).map_err(CascadeError::Git)?;
-
-                    for commit_oid in revwalk {
-                        let commit_oid = commit_oid.map_err(CascadeError::Git)?;
-                        if commit_oid == oid {
-                            return Ok(branch_name.to_string());
-                        }
+                    // Ancestry check instead of walking the branch's whole history
+                    let head = branch_head.id();
+                    if head == oid
+                        || self
+                            .repo
+                            .graph_descendant_of(head, oid)
+                            .map_err(CascadeError::Git)?
+                    {
+                        return Ok(branch_name.to_string());
                     }
                 }
             }
@@ -3413,6 +5032,69 @@ impl GitRepository {
     }
 }
 
+/// Record a completed force-push for `ca stats`
+fn log_force_push(branch: &str) {
+    debug!(
+        target: crate::utils::logging::OPS_TARGET,
+        op = "force_push",
+        branch,
+        "Force-pushed {branch}"
+    );
+}
+
+/// `git push` lease argument: pinned to `lease` when known, otherwise checked against
+/// the remote-tracking ref
+fn force_with_lease_arg(branch: &str, lease: Option<&str>) -> String {
+    match lease {
+        Some(commit) => format!("--force-with-lease=refs/heads/{branch}:{commit}"),
+        None => "--force-with-lease".to_string(),
+    }
+}
+
+/// Error for a force push whose lease no longer matches the remote branch
+fn stale_lease_error(branch: &str, lease: Option<&str>) -> CascadeError {
+    let expected = lease
+        .map(|commit| format!(" (expected {})", &commit[..commit.len().min(8)]))
+        .unwrap_or_default();
+    CascadeError::branch(format!(
+        "Refusing to force-push '{branch}': the remote branch changed since Cascade last pushed it{expected}.\n\
+         Someone else may have pushed to it, and overwriting it would discard their commits.\n\n\
+         To recover:\n  \
+         1. git fetch origin\n  \
+         2. git log {branch}..origin/{branch}   (review the new remote commits)\n  \
+         3. Bring those commits into '{branch}', or overwrite them deliberately with: git push --force origin {branch}\n  \
+         4. Re-run the command"
+    ))
+}
+
+/// Match a branch name against a `git.protected_branches` pattern, where `*` matches any
+/// run of characters (including `/`) and `?` matches a single character
+pub(crate) fn branch_pattern_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // Backtracking match: remember the last `*` and how much of the name it has consumed
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3472,6 +5154,578 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn test_protected_branch_guard() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        crate::config::initialize_repo(&repo_path, None).unwrap();
+        let config_path = crate::config::get_repo_config_dir(&repo_path)
+            .unwrap()
+            .join("config.json");
+        let mut settings = crate::config::Settings::load_from_file(&config_path).unwrap();
+        settings
+            .set_value("git.protected_branches", "main, release/*")
+            .unwrap();
+        settings.save_to_file(&config_path).unwrap();
+
+        let repo = GitRepository::open(&repo_path).unwrap();
+        assert!(repo.is_protected_branch("main"));
+        assert!(repo.is_protected_branch("release/2.1"));
+        assert!(!repo.is_protected_branch("feature/release"));
+
+        let err = repo.create_branch("release/2.1", None).unwrap_err();
+        assert!(err.to_string().contains("--allow-protected"));
+        assert!(repo.push("main").is_err());
+
+        repo.create_branch("feature/login", None).unwrap();
+        assert!(repo.branch_exists("feature/login"));
+    }
+
+    #[test]
+    fn test_branch_case_follows_ref_storage() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        crate::config::initialize_repo(&repo_path, None).unwrap();
+        let config_path = crate::config::get_repo_config_dir(&repo_path)
+            .unwrap()
+            .join("config.json");
+        let mut settings = crate::config::Settings::load_from_file(&config_path).unwrap();
+        settings
+            .set_value("git.protected_branches", "main")
+            .unwrap();
+        settings.save_to_file(&config_path).unwrap();
+
+        let repo = GitRepository::open(&repo_path).unwrap();
+        repo.create_branch("feature/login", None).unwrap();
+        let ignore_case = platform::case_insensitive_refs();
+        assert_eq!(repo.is_protected_branch("Main"), ignore_case);
+        assert_eq!(
+            repo.create_branch("Feature/Login", None).is_err(),
+            ignore_case,
+            "branches differing only in case collide where refs are case-insensitive"
+        );
+    }
+
+    #[test]
+    fn test_commits_behind_counts_new_base_commits() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let repo = GitRepository::open(&repo_path).unwrap();
+        let start = repo.get_head_commit_hash().unwrap();
+
+        create_commit(&repo_path, "Second", "second.txt");
+        create_commit(&repo_path, "Third", "third.txt");
+
+        assert_eq!(repo.commits_behind(&start, "HEAD").unwrap(), 2);
+        assert_eq!(repo.commits_behind("HEAD", &start).unwrap(), 0);
+        assert!(repo.commits_behind(&start, "origin/missing").is_err());
+    }
+
+    #[test]
+    fn test_commit_walks_stop_at_limit() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let start = GitRepository::open(&repo_path)
+            .unwrap()
+            .get_head_commit_hash()
+            .unwrap();
+        for i in 0..3 {
+            create_commit(&repo_path, &format!("Commit {i}"), &format!("file{i}.txt"));
+        }
+
+        let mut repo = GitRepository::open(&repo_path).unwrap();
+        let head = repo.get_head_commit_hash().unwrap();
+        assert_eq!(repo.get_commits_between(&start, &head).unwrap().len(), 3);
+
+        repo.commit_walk_limit = 2;
+        assert_eq!(repo.get_commits_between(&start, &head).unwrap().len(), 2);
+        assert_eq!(repo.get_commit_count_between(&start, &head).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_dirty_check_skips_ignored_and_honors_pathspec() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        std::fs::write(repo_path.join(".gitignore"), "target/\n").unwrap();
+        for args in [
+            &["add", ".gitignore"][..],
+            &["commit", "-qm", "Ignore target"][..],
+        ] {
+            Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+        }
+        std::fs::create_dir_all(repo_path.join("target/debug")).unwrap();
+        std::fs::write(repo_path.join("target/debug/out.o"), "binary").unwrap();
+
+        let repo = GitRepository::open(&repo_path).unwrap();
+        assert!(!repo.is_dirty().unwrap());
+        assert!(repo.get_untracked_files().unwrap().is_empty());
+
+        std::fs::create_dir_all(repo_path.join("docs")).unwrap();
+        std::fs::write(repo_path.join("docs/new.md"), "draft").unwrap();
+        assert!(repo.is_dirty().unwrap());
+        assert!(repo.is_dirty_in(&["docs"]).unwrap());
+        assert!(!repo.is_dirty_in(&["src"]).unwrap());
+        assert_eq!(repo.get_untracked_files().unwrap(), vec!["docs/new.md"]);
+    }
+
+    #[test]
+    fn test_fetch_once_per_run() {
+        let (_remote_dir, remote_path) = create_test_repo();
+        let (_temp_dir, repo_path) = create_test_repo();
+        let git = |dir: &PathBuf, args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        git(
+            &repo_path,
+            &["remote", "add", "origin", remote_path.to_str().unwrap()],
+        );
+        let branch = git(&remote_path, &["branch", "--show-current"]);
+        let origin_head = || git(&repo_path, &["rev-parse", &format!("origin/{branch}")]);
+
+        let repo = GitRepository::open(&repo_path).unwrap();
+        repo.fetch_with_policy(FetchPolicy::Once).unwrap();
+        let first = origin_head();
+        assert_eq!(first, git(&remote_path, &["rev-parse", "HEAD"]));
+
+        create_commit(&remote_path, "Pushed by someone else", "other.txt");
+
+        // Later fetches in the same run, even from another instance, reuse the first one
+        let reopened = GitRepository::open(&repo_path).unwrap();
+        reopened.fetch_with_policy(FetchPolicy::Once).unwrap();
+        reopened.fetch_with_policy(FetchPolicy::Never).unwrap();
+        assert_eq!(origin_head(), first);
+
+        reopened.fetch_with_policy(FetchPolicy::Always).unwrap();
+        assert_eq!(origin_head(), git(&remote_path, &["rev-parse", "HEAD"]));
+
+        // After a server-side change the next fetch goes to the remote again
+        create_commit(&remote_path, "Merged on the server", "merged.txt");
+        forget_fetch(&repo_path);
+        reopened.fetch_with_policy(FetchPolicy::Once).unwrap();
+        assert_eq!(origin_head(), git(&remote_path, &["rev-parse", "HEAD"]));
+    }
+
+    #[test]
+    fn test_commits_already_upstream_matches_by_patch() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let base = git(&["rev-parse", "HEAD"]);
+
+        git(&["checkout", "-q", "-b", "feature"]);
+        create_commit(&repo_path, "Landed elsewhere", "landed.txt");
+        let landed = git(&["rev-parse", "HEAD"]);
+        create_commit(&repo_path, "Still pending", "pending.txt");
+        let pending = git(&["rev-parse", "HEAD"]);
+
+        // Someone applies the first change to the base under a new commit
+        git(&["checkout", "-q", "-b", "upstream", &base]);
+        create_commit(&repo_path, "Unrelated upstream work", "other.txt");
+        git(&["cherry-pick", &landed]);
+
+        let repo = GitRepository::open(&repo_path).unwrap();
+        let found = repo.commits_already_upstream("upstream", &pending).unwrap();
+        assert_eq!(found, vec![landed]);
+    }
+
+    #[test]
+    fn test_ranges_landed_upstream_matches_squash_merges() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {args:?} failed");
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let base = git(&["rev-parse", "HEAD"]);
+
+        // Two entries of two commits each
+        git(&["checkout", "-q", "-b", "feature"]);
+        create_commit(&repo_path, "First, part 1", "first-1.txt");
+        create_commit(&repo_path, "First, part 2", "first-2.txt");
+        let first = git(&["rev-parse", "HEAD"]);
+        create_commit(&repo_path, "Second, part 1", "second-1.txt");
+        create_commit(&repo_path, "Second, part 2", "second-2.txt");
+        let second = git(&["rev-parse", "HEAD"]);
+
+        // The first entry is squash-merged after unrelated work
+        git(&["checkout", "-q", "-b", "upstream", &base]);
+        create_commit(&repo_path, "Unrelated upstream work", "other.txt");
+        git(&["merge", "--squash", &first]);
+        git(&["commit", "-q", "-m", "First (#1)"]);
+
+        let repo = GitRepository::open(&repo_path).unwrap();
+        let ranges = vec![
+            (base.clone(), first.clone()),
+            (first.clone(), second.clone()),
+            (base.clone(), second.clone()),
+        ];
+        assert_eq!(
+            repo.ranges_landed_upstream("upstream", &ranges).unwrap(),
+            [0]
+        );
+
+        // Both entries landed together as one squash commit
+        git(&["reset", "-q", "--hard", &base]);
+        git(&["merge", "--squash", &second]);
+        git(&["commit", "-q", "-m", "Whole stack (#2)"]);
+        assert_eq!(
+            repo.ranges_landed_upstream("upstream", &ranges).unwrap(),
+            [2]
+        );
+    }
+
+    #[test]
+    fn test_predict_rebase_conflicts_reports_files() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {args:?} failed");
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let base = git(&["rev-parse", "HEAD"]);
+
+        // Base moves on with a change to shared.txt
+        std::fs::write(repo_path.join("shared.txt"), "base\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-m", "Base change"]);
+        let new_base = git(&["rev-parse", "HEAD"]);
+
+        // The stack touches shared.txt differently, then an unrelated file
+        git(&["checkout", "-q", "-b", "feature", &base]);
+        std::fs::write(repo_path.join("shared.txt"), "feature\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-m", "Feature change"]);
+        let conflicting = git(&["rev-parse", "HEAD"]);
+        create_commit(&repo_path, "Unrelated", "unrelated.txt");
+        let clean = git(&["rev-parse", "HEAD"]);
+
+        let repo = GitRepository::open(&repo_path).unwrap();
+        let predictions = repo
+            .predict_rebase_conflicts(&[conflicting, clean.clone()], &new_base)
+            .unwrap();
+
+        assert_eq!(predictions, vec![vec!["shared.txt".to_string()], vec![]]);
+        // Nothing was touched
+        assert_eq!(git(&["rev-parse", "HEAD"]), clean);
+        assert_eq!(git(&["status", "--porcelain"]), "");
+    }
+
+    #[test]
+    fn test_git_remote_command_uses_network_proxy() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        crate::config::initialize_repo(&repo_path, None).unwrap();
+        let config_path = crate::config::get_repo_config_dir(&repo_path)
+            .unwrap()
+            .join("config.json");
+        let mut settings = crate::config::Settings::load_from_file(&config_path).unwrap();
+        let args = |settings: &crate::config::Settings| {
+            settings.save_to_file(&config_path).unwrap();
+            let repo = GitRepository::open(&repo_path).unwrap();
+            repo.git_remote_command()
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(args(&settings).is_empty());
+
+        settings
+            .set_value("network.proxy", "http://proxy.example.com:3128")
+            .unwrap();
+        assert_eq!(
+            args(&settings),
+            ["-c", "http.proxy=http://proxy.example.com:3128"]
+        );
+
+        settings.set_value("network.proxy", "none").unwrap();
+        assert_eq!(args(&settings), ["-c", "http.proxy="]);
+    }
+
+    #[test]
+    fn test_remote_credentials_are_routed_by_host() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        crate::config::initialize_repo(&repo_path, Some("https://git.company.com".to_string()))
+            .unwrap();
+        let config_path = crate::config::get_repo_config_dir(&repo_path)
+            .unwrap()
+            .join("config.json");
+        let mut settings = crate::config::Settings::load_from_file(&config_path).unwrap();
+        settings.set_value("bitbucket.username", "jane").unwrap();
+        settings.set_value("bitbucket.token", "bb-token").unwrap();
+        settings
+            .set_value("credentials.mirror.company.com.token", "mirror-token")
+            .unwrap();
+        settings.save_to_file(&config_path).unwrap();
+
+        let repo = GitRepository::open(&repo_path).unwrap();
+        let token = |url: &str| {
+            repo.remote_credentials
+                .for_url(url)
+                .and_then(|creds| creds.token.clone())
+        };
+        assert_eq!(
+            token("https://git.company.com/scm/proj/repo.git").as_deref(),
+            Some("bb-token")
+        );
+        assert_eq!(
+            token("https://mirror.company.com:8443/proj/repo.git").as_deref(),
+            Some("mirror-token")
+        );
+        assert_eq!(token("https://bitbucket.org/someone/else.git"), None);
+    }
+
+    #[test]
+    fn test_lfs_tracked_paths_are_staged() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let repo = GitRepository::open(&repo_path).unwrap();
+        assert!(!repo.uses_lfs());
+
+        std::fs::write(
+            repo_path.join(".gitattributes"),
+            "*.bin filter=lfs diff=lfs merge=lfs -text\n",
+        )
+        .unwrap();
+        assert!(repo.uses_lfs());
+        assert!(repo.is_lfs_tracked("assets/model.bin"));
+        assert!(!repo.is_lfs_tracked("src/main.rs"));
+
+        std::fs::write(repo_path.join("model.bin"), [0u8, 1, 2]).unwrap();
+        std::fs::write(repo_path.join("notes.txt"), "notes").unwrap();
+        repo.stage_files(&["model.bin", "notes.txt"]).unwrap();
+
+        let index = repo.repo.index().unwrap();
+        assert!(index.get_path(Path::new("model.bin"), 0).is_some());
+        assert!(index.get_path(Path::new("notes.txt"), 0).is_some());
+    }
+
+    #[test]
+    fn test_sparse_checkout_uses_git_cli() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        std::fs::create_dir(repo_path.join("vendor")).unwrap();
+        create_commit(&repo_path, "Add vendored lib", "vendor/lib.txt");
+        Command::new("git")
+            .args(["sparse-checkout", "set", "docs"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+        assert!(!repo_path.join("vendor/lib.txt").exists());
+
+        let repo = GitRepository::open(&repo_path).unwrap();
+        let features = repo.features();
+        assert!(features.sparse_checkout);
+        assert!(!features.partial_clone);
+        assert!(features.needs_git_cli());
+
+        repo.create_branch("feature", None).unwrap();
+        repo.checkout_branch("feature").unwrap();
+        create_commit(&repo_path, "Feature work", "feature.txt");
+        let feature_commit = repo.get_head_commit_hash().unwrap();
+
+        // Excluded paths stay out of the working tree across checkouts and cherry-picks
+        repo.checkout_branch("main")
+            .or_else(|_| repo.checkout_branch("master"))
+            .unwrap();
+        assert!(!repo_path.join("vendor/lib.txt").exists());
+        repo.cherry_pick(&feature_commit).unwrap();
+        assert!(repo_path.join("feature.txt").exists());
+        assert!(!repo_path.join("vendor/lib.txt").exists());
+        repo.reset_to_head().unwrap();
+        assert!(!repo_path.join("vendor/lib.txt").exists());
+
+        Command::new("git")
+            .args(["config", "remote.origin.promisor", "true"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+        let repo = GitRepository::open(&repo_path).unwrap();
+        assert!(repo.features().partial_clone);
+        assert_eq!(repo.promisor_remotes(), vec!["origin".to_string()]);
+    }
+
+    #[test]
+    fn test_backup_create_and_restore() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let repo = GitRepository::open(&repo_path).unwrap();
+
+        repo.create_branch("feature/auth", None).unwrap();
+        let original = repo.get_branch_commit_hash("feature/auth").unwrap();
+        create_commit(&repo_path, "Rewrite", "rewrite.txt");
+        let rewritten = repo.get_head_commit_hash().unwrap();
+
+        let backup = repo.create_backup("feature/auth", &original).unwrap();
+        assert!(backup.starts_with("refs/cascade/backup/feature/auth/"));
+        repo.update_branch_to_commit("feature/auth", &rewritten)
+            .unwrap();
+
+        // Backups stay out of the branch list
+        assert!(!repo
+            .list_branches()
+            .unwrap()
+            .iter()
+            .any(|b| b.contains("backup")));
+
+        let backups = repo.list_backups().unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].branch, "feature/auth");
+        assert_eq!(backups[0].commit_hash, original);
+
+        let previous = repo.restore_backup(&backups[0]).unwrap().unwrap();
+        assert_eq!(
+            repo.get_branch_commit_hash("feature/auth").unwrap(),
+            original
+        );
+        assert_ne!(previous, backup);
+
+        // The overwritten head was backed up and is listed first
+        let backups = repo.list_backups().unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(backups[0].commit_hash, rewritten);
+
+        // Restoring a backup the branch already points at is a no-op
+        assert!(repo.restore_backup(&backups[1]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_force_push_lease() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let remote_dir = TempDir::new().unwrap();
+        let git = |dir: &Path, args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {args:?} failed");
+        };
+        git(remote_dir.path(), &["init", "--bare", "-q"]);
+        git(
+            &repo_path,
+            &[
+                "remote",
+                "add",
+                "origin",
+                remote_dir.path().to_str().unwrap(),
+            ],
+        );
+
+        git(&repo_path, &["checkout", "-q", "-b", "feature"]);
+        create_commit(&repo_path, "Feature work", "feature.txt");
+        git(&repo_path, &["push", "-q", "origin", "feature"]);
+
+        let repo = GitRepository::open(&repo_path).unwrap();
+        let pushed = repo.get_branch_head("feature").unwrap();
+
+        // A teammate pushes on top of the branch
+        let teammate_dir = TempDir::new().unwrap();
+        git(
+            teammate_dir.path(),
+            &[
+                "clone",
+                "-q",
+                "-b",
+                "feature",
+                remote_dir.path().to_str().unwrap(),
+                ".",
+            ],
+        );
+        git(teammate_dir.path(), &["config", "user.name", "Teammate"]);
+        git(
+            teammate_dir.path(),
+            &["config", "user.email", "mate@test.com"],
+        );
+        std::fs::write(teammate_dir.path().join("mate.txt"), "theirs").unwrap();
+        git(teammate_dir.path(), &["add", "."]);
+        git(
+            teammate_dir.path(),
+            &["commit", "-q", "-m", "Teammate change"],
+        );
+        git(teammate_dir.path(), &["push", "-q", "origin", "feature"]);
+
+        // Rewriting our branch must not clobber their commit
+        git(
+            &repo_path,
+            &["commit", "-q", "--amend", "-m", "Feature work v2"],
+        );
+        let err = repo
+            .force_push_single_branch_auto("feature", Some(&pushed))
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("changed since Cascade last pushed it"));
+
+        // Once their commit is part of our branch, the push goes through
+        git(
+            &repo_path,
+            &["pull", "-q", "--no-rebase", "origin", "feature"],
+        );
+        repo.force_push_single_branch_auto("feature", Some(&pushed))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_remote_branch_exists_ignores_stale_tracking_refs() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let remote_dir = TempDir::new().unwrap();
+        let git = |dir: &Path, args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {args:?} failed");
+        };
+        git(remote_dir.path(), &["init", "--bare", "-q"]);
+        git(
+            &repo_path,
+            &[
+                "remote",
+                "add",
+                "origin",
+                remote_dir.path().to_str().unwrap(),
+            ],
+        );
+        git(&repo_path, &["branch", "login"]);
+        git(&repo_path, &["push", "-q", "origin", "login"]);
+
+        let repo = GitRepository::open(&repo_path).unwrap();
+        assert!(repo.remote_branch_exists("login").unwrap());
+
+        // Deleted on the remote, while origin/login is still there locally
+        git(remote_dir.path(), &["branch", "-D", "login"]);
+        assert!(repo.get_remote_branch_head("login").is_ok());
+        assert!(!repo.remote_branch_exists("login").unwrap());
+    }
+
+    #[test]
+    fn test_branch_pattern_matches() {
+        assert!(branch_pattern_matches("main", "main"));
+        assert!(!branch_pattern_matches("main", "maintenance"));
+        assert!(branch_pattern_matches("release/*", "release/1.0"));
+        assert!(branch_pattern_matches("release/*", "release/1.0/hotfix"));
+        assert!(!branch_pattern_matches("release/*", "releases/1.0"));
+        assert!(branch_pattern_matches("*-stable", "v2-stable"));
+        assert!(branch_pattern_matches("hotfix-?", "hotfix-1"));
+        assert!(!branch_pattern_matches("hotfix-?", "hotfix-12"));
+    }
+
     #[test]
     fn test_repository_info() {
         let (_temp_dir, repo_path) = create_test_repo();
@@ -3991,6 +6245,52 @@ mod tests {
         assert!(result.is_err(), "Cherry-pick with conflict should fail");
     }
 
+    #[test]
+    fn test_cherry_pick_reuses_recorded_resolution() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let base = git(&["rev-parse", "HEAD"]);
+
+        git(&["checkout", "-q", "-b", "feature"]);
+        std::fs::write(repo_path.join("conflict.txt"), "feature\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-m", "Feature change"]);
+        let feature = git(&["rev-parse", "HEAD"]);
+
+        git(&["checkout", "-q", "-b", "updated-base", &base]);
+        std::fs::write(repo_path.join("conflict.txt"), "base\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-m", "Base change"]);
+
+        // First sync: conflict, resolved by hand and recorded
+        git(&["checkout", "-q", "-b", "first-sync"]);
+        let repo = GitRepository::open(&repo_path).unwrap();
+        assert!(repo.cherry_pick(&feature).is_err());
+        std::fs::write(repo_path.join("conflict.txt"), "resolved\n").unwrap();
+        git(&["add", "conflict.txt"]);
+        repo.record_conflict_resolutions().unwrap();
+        git(&["-c", "core.editor=true", "cherry-pick", "--continue"]);
+
+        // Second sync of the same commit onto the same base resolves itself
+        git(&["checkout", "-q", "-b", "second-sync", "updated-base"]);
+        let repo = GitRepository::open(&repo_path).unwrap();
+        let picked = repo.cherry_pick(&feature).unwrap();
+
+        assert_eq!(git(&["rev-parse", "HEAD"]), picked);
+        assert_eq!(
+            std::fs::read_to_string(repo_path.join("conflict.txt")).unwrap(),
+            "resolved\n"
+        );
+        assert!(!repo.git_dir().join("CHERRY_PICK_HEAD").exists());
+    }
+
     #[test]
     fn test_reset_to_head_clears_staged_files() {
         let (_temp_dir, repo_path) = create_test_repo();
@@ -0,0 +1,70 @@
+//! Pull request trailers kept in git notes
+//!
+//! Once an entry has a pull request, its commit gets a `PR: <url>` trailer, and once it
+//! lands a `Landed-as: <sha>` trailer pointing at the commit the server merged it as.
+//! They live in notes under `refs/notes/cascade` rather than in the commit message, so
+//! recording them never rewrites a commit that has already been pushed or reviewed.
+//! `git log --notes=cascade` shows them next to each commit.
+
+/// Notes ref holding the trailers
+pub const TRAILERS_NOTES_REF: &str = "refs/notes/cascade";
+
+/// Trailer with the pull request's web URL
+pub const PR_TRAILER: &str = "PR";
+
+/// Trailer with the commit a squash or merge commit merge landed the entry as
+pub const LANDED_AS_TRAILER: &str = "Landed-as";
+
+/// `note` with `trailers` set: a key already present has its value replaced in place,
+/// new keys are appended, and any other lines are kept
+pub fn merge_trailers(note: &str, trailers: &[(&str, &str)]) -> String {
+    let mut lines: Vec<String> = note.lines().map(str::to_string).collect();
+    for (key, value) in trailers {
+        let line = format!("{key}: {value}");
+        match lines
+            .iter_mut()
+            .find(|existing| trailer_value(existing, key).is_some())
+        {
+            Some(existing) => *existing = line,
+            None => lines.push(line),
+        }
+    }
+    let mut merged = lines.join("\n");
+    merged.push('\n');
+    merged
+}
+
+/// The value of trailer `key` in `note`, if it has one
+pub fn find_trailer<'a>(note: &'a str, key: &str) -> Option<&'a str> {
+    note.lines().find_map(|line| trailer_value(line, key))
+}
+
+fn trailer_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let (name, value) = line.split_once(':')?;
+    name.trim().eq_ignore_ascii_case(key).then(|| value.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_trailers_replaces_and_appends() {
+        let note = merge_trailers("", &[(PR_TRAILER, "https://git.example.com/pr/1")]);
+        assert_eq!(note, "PR: https://git.example.com/pr/1\n");
+
+        let note = merge_trailers(
+            &format!("Reviewed-in: standup\n{note}"),
+            &[
+                (PR_TRAILER, "https://git.example.com/pr/2"),
+                (LANDED_AS_TRAILER, "abc123"),
+            ],
+        );
+        assert_eq!(
+            note,
+            "Reviewed-in: standup\nPR: https://git.example.com/pr/2\nLanded-as: abc123\n"
+        );
+        assert_eq!(find_trailer(&note, "landed-as"), Some("abc123"));
+        assert_eq!(find_trailer(&note, "Signed-off-by"), None);
+    }
+}
@@ -0,0 +1,236 @@
+//! Localized user-facing text
+//!
+//! Messages live in Fluent catalogs, `locales/<locale>/cascade.ftl`, compiled into the
+//! binary. English is the reference catalog and the fallback for anything a translation
+//! leaves out. The locale comes from `CASCADE_LOCALE`, then the `cascade.locale` setting,
+//! then `LC_ALL`, `LC_MESSAGES` and `LANG`. A catalog at `~/.cascade/locales/<locale>.ftl`
+//! takes precedence over the bundled one, so a translation can be tried without a rebuild.
+//!
+//! Look messages up with [`t!`](crate::t):
+//!
+//! ```
+//! use cascade_cli::t;
+//! assert_eq!(t!("recover-returning", branch = "main"), "Returning to branch: main");
+//! ```
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::FluentResource;
+use std::path::Path;
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::FluentArgs;
+
+/// Catalogs compiled into the binary, by locale
+const CATALOGS: &[(&str, &str)] = &[("en", include_str!("../locales/en/cascade.ftl"))];
+
+/// Locale every lookup falls back to
+const FALLBACK_LOCALE: &str = "en";
+
+/// Environment variable that picks the locale ahead of every other source
+pub const LOCALE_ENV: &str = "CASCADE_LOCALE";
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// Message bundles, most preferred first and English last
+struct Catalog {
+    bundles: Vec<FluentBundle<FluentResource>>,
+}
+
+impl Catalog {
+    /// The catalogs for `locale`: a user catalog, if given, then the bundled ones
+    fn new(locale: &LanguageIdentifier, user_source: Option<String>) -> Self {
+        let mut sources: Vec<String> = user_source.into_iter().collect();
+
+        let mut bundled: Vec<(LanguageIdentifier, &str)> = CATALOGS
+            .iter()
+            .filter(|(tag, _)| *tag != FALLBACK_LOCALE)
+            .filter_map(|(tag, source)| Some((tag.parse().ok()?, *source)))
+            .filter(|(tag, _): &(LanguageIdentifier, _)| {
+                tag.language == locale.language
+                    && (tag.region.is_none() || tag.region == locale.region)
+            })
+            .collect();
+        // A regional catalog (pt-BR) goes ahead of the plain language one (pt)
+        bundled.sort_by_key(|(tag, _)| tag.region.is_none());
+        sources.extend(bundled.into_iter().map(|(_, source)| source.to_string()));
+
+        let fallback = CATALOGS
+            .iter()
+            .find(|(tag, _)| *tag == FALLBACK_LOCALE)
+            .map(|(_, source)| source.to_string());
+        sources.extend(fallback);
+
+        let bundles = sources
+            .into_iter()
+            .map(|source| {
+                let resource =
+                    FluentResource::try_new(source).unwrap_or_else(|(resource, errors)| {
+                        tracing::warn!("Ignoring {} malformed message(s) in catalog", errors.len());
+                        resource
+                    });
+                let mut bundle = FluentBundle::new_concurrent(vec![locale.clone()]);
+                // Unicode isolation marks would end up in terminal output
+                bundle.set_use_isolating(false);
+                if let Err(errors) = bundle.add_resource(resource) {
+                    tracing::warn!("Ignoring {} duplicate message(s) in catalog", errors.len());
+                }
+                bundle
+            })
+            .collect();
+        Self { bundles }
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+        self.bundles.iter().find_map(|bundle| {
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, args, &mut errors);
+            if !errors.is_empty() {
+                tracing::debug!("Formatting message '{}': {:?}", id, errors);
+            }
+            Some(text.into_owned())
+        })
+    }
+}
+
+/// Load the catalog for the locale picked by the environment and `configured` (the
+/// `cascade.locale` setting). Only the first call has an effect.
+pub fn init(configured: Option<&str>) {
+    CATALOG.get_or_init(|| load(configured));
+}
+
+fn load(configured: Option<&str>) -> Catalog {
+    let locale = select_locale(configured);
+    let user_source = crate::config::get_config_dir()
+        .ok()
+        .and_then(|dir| user_catalog(&dir.join("locales"), &locale));
+    tracing::debug!("Using locale {}", locale);
+    Catalog::new(&locale, user_source)
+}
+
+/// The locale to show messages in: `CASCADE_LOCALE`, then `configured`, then `LC_ALL`,
+/// `LC_MESSAGES` and `LANG`, then English
+pub fn select_locale(configured: Option<&str>) -> LanguageIdentifier {
+    let env = |name: &str| std::env::var(name).ok();
+    [
+        env(LOCALE_ENV),
+        configured.map(str::to_string),
+        env("LC_ALL"),
+        env("LC_MESSAGES"),
+        env("LANG"),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|value| parse_locale(&value))
+    .unwrap_or_else(|| FALLBACK_LOCALE.parse().expect("valid fallback locale"))
+}
+
+/// Read a locale from a setting or a POSIX locale variable (`de_DE.UTF-8` is `de-DE`).
+/// The `C` and `POSIX` locales name no language and give `None`.
+pub fn parse_locale(value: &str) -> Option<LanguageIdentifier> {
+    let tag = value.split(['.', '@']).next()?.trim().replace('_', "-");
+    if tag.is_empty() || tag == "C" || tag == "POSIX" {
+        return None;
+    }
+    tag.parse().ok()
+}
+
+/// A translation in `dir` for `locale`, as `de-AT.ftl` or `de.ftl`
+fn user_catalog(dir: &Path, locale: &LanguageIdentifier) -> Option<String> {
+    [locale.to_string(), locale.language.to_string()]
+        .iter()
+        .map(|name| dir.join(format!("{name}.ftl")))
+        .find_map(|path| std::fs::read_to_string(path).ok())
+}
+
+/// The message `id` in the current locale, or `id` itself when no catalog has it
+pub fn message(id: &str, args: Option<&FluentArgs>) -> String {
+    CATALOG
+        .get_or_init(|| load(None))
+        .format(id, args)
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// Look up a localized message, with named arguments:
+/// `t!("recover-returning", branch = name)`
+#[macro_export]
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::message($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = $crate::i18n::FluentArgs::new();
+        $( args.set(stringify!($name), $value); )+
+        $crate::i18n::message($id, Some(&args))
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn locale(tag: &str) -> LanguageIdentifier {
+        tag.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!(parse_locale("de_DE.UTF-8"), Some(locale("de-DE")));
+        assert_eq!(parse_locale("fr"), Some(locale("fr")));
+        assert_eq!(parse_locale("sr_RS@latin"), Some(locale("sr-RS")));
+        assert_eq!(parse_locale("C"), None);
+        assert_eq!(parse_locale("POSIX"), None);
+        assert_eq!(parse_locale(""), None);
+    }
+
+    #[test]
+    fn test_english_catalog() {
+        let catalog = Catalog::new(&locale("en-US"), None);
+        assert_eq!(catalog.format("output-tip", None).as_deref(), Some("TIP:"));
+
+        let mut args = FluentArgs::new();
+        args.set("code", "CA200");
+        assert_eq!(
+            catalog.format("error-header", Some(&args)).as_deref(),
+            Some("Error [CA200]:")
+        );
+        assert!(catalog.format("no-such-message", None).is_none());
+    }
+
+    #[test]
+    fn test_translation_falls_back_to_english() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("de.ftl"),
+            "output-tip = TIPP:\nrecover-returning = Zurück zu Branch { $branch }\n",
+        )
+        .unwrap();
+
+        let source = user_catalog(dir.path(), &locale("de-AT"));
+        let catalog = Catalog::new(&locale("de-AT"), source);
+        assert_eq!(catalog.format("output-tip", None).as_deref(), Some("TIPP:"));
+        let mut args = FluentArgs::new();
+        args.set("branch", "main");
+        assert_eq!(
+            catalog.format("recover-returning", Some(&args)).as_deref(),
+            Some("Zurück zu Branch main")
+        );
+        assert_eq!(
+            catalog.format("output-next-steps", None).as_deref(),
+            Some("Next steps:")
+        );
+    }
+
+    #[test]
+    fn test_bundled_catalogs_parse() {
+        for (tag, source) in CATALOGS {
+            assert!(parse_locale(tag).is_some(), "{tag} is not a locale");
+            assert!(
+                FluentResource::try_new(source.to_string()).is_ok(),
+                "catalog {tag} has syntax errors"
+            );
+        }
+    }
+}
@@ -1,8 +1,13 @@
+// Everything shown to the user goes through `Output`, so that --plain, --no-color, --quiet
+// and JSON output hold everywhere; direct printing to stdout fails clippy
+#![deny(clippy::print_stdout)]
+
 pub mod bitbucket;
 pub mod cli;
 pub mod config;
 pub mod errors;
 pub mod git;
+pub mod i18n;
 pub mod stack;
 pub mod utils;
 
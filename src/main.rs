@@ -1,8 +1,13 @@
+use cascade_cli::cli::output::Output;
 use cascade_cli::cli::Cli;
 use clap::Parser;
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() {
     let cli = Cli::parse();
-    cli.run().await.map_err(anyhow::Error::new)
+    let json = cli.json_output();
+    if let Err(e) = cli.run().await {
+        Output::error_report(&e.report(), json);
+        std::process::exit(1);
+    }
 }
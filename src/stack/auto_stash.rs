@@ -0,0 +1,286 @@
+//! Automatic stashing around sync, rebase and switch
+//!
+//! With `cascade.auto_stash` enabled, uncommitted changes are stashed before the
+//! operation and re-applied once it finishes. The stash is recorded in the git dir so
+//! a sync paused on conflicts restores it after `ca sync continue` or `ca sync abort`.
+
+use super::SyncState;
+use crate::cli::output::Output;
+use crate::errors::{CascadeError, Result};
+use crate::git::{GitRepository, StashRestore};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const STATE_FILE: &str = "CASCADE_AUTO_STASH";
+
+/// A stash taken on behalf of a Cascade operation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AutoStash {
+    /// Operation that stashed the changes (`sync`, `rebase`, `switch`)
+    pub operation: String,
+    /// Commit of the stash entry
+    pub stash_commit: String,
+    /// Branch that was checked out when the changes were stashed
+    pub branch: String,
+    /// When the changes were stashed
+    pub created_at: DateTime<Utc>,
+}
+
+impl AutoStash {
+    fn state_path(repo_root: &Path) -> Result<PathBuf> {
+        Ok(crate::git::resolve_git_dir(repo_root)?.join(STATE_FILE))
+    }
+
+    /// Whether `cascade.auto_stash` is enabled for this repository
+    pub fn is_enabled(repo_root: &Path) -> bool {
+        crate::config::get_repo_config_dir(repo_root)
+            .ok()
+            .and_then(|dir| crate::config::Settings::load_from_file(&dir.join("config.json")).ok())
+            .is_some_and(|settings| settings.cascade.auto_stash)
+    }
+
+    /// Stash uncommitted changes before `operation` when `cascade.auto_stash` is enabled.
+    ///
+    /// Returns `None` without touching the working tree when auto-stash is disabled or
+    /// there is nothing to stash, so callers keep their usual dirty-tree handling.
+    pub fn stash_if_enabled(
+        repo_root: &Path,
+        git_repo: &GitRepository,
+        operation: &str,
+    ) -> Result<Option<Self>> {
+        if !Self::is_enabled(repo_root) || !git_repo.is_dirty()? {
+            return Ok(None);
+        }
+
+        if let Some(pending) = Self::load(repo_root)? {
+            return Err(CascadeError::branch(format!(
+                "Changes stashed by an earlier '{}' ({}) have not been restored yet. \
+                 Finish or abort that operation, or apply them with 'git stash apply {}'.",
+                pending.operation,
+                pending.created_at.format("%Y-%m-%d %H:%M"),
+                &pending.stash_commit[..8]
+            )));
+        }
+
+        let branch = git_repo.get_current_branch().unwrap_or_default();
+        let message = format!("cascade auto-stash before {operation} on {branch}");
+        let Some(stash_commit) = git_repo.stash_all(&message)? else {
+            return Ok(None);
+        };
+
+        let stash = Self {
+            operation: operation.to_string(),
+            stash_commit,
+            branch,
+            created_at: Utc::now(),
+        };
+        stash.save(repo_root)?;
+
+        Output::info(format!(
+            "Stashed uncommitted changes ({})",
+            &stash.stash_commit[..8]
+        ));
+        Ok(Some(stash))
+    }
+
+    /// Restore the pending stash once an operation has finished and pass its result on.
+    ///
+    /// While a sync or rebase is paused on conflicts the changes stay stashed; they are
+    /// restored by whichever command completes or aborts it.
+    pub fn finish<T>(repo_root: &Path, result: Result<T>) -> Result<T> {
+        let Ok(Some(stash)) = Self::load(repo_root) else {
+            return result;
+        };
+
+        if operation_paused(repo_root) {
+            Output::info(format!(
+                "Your uncommitted changes stay stashed ({}) until the {} is continued or aborted",
+                &stash.stash_commit[..8],
+                stash.operation
+            ));
+            return result;
+        }
+
+        if let Err(e) = stash.restore(repo_root) {
+            Output::warning(format!("Could not restore stashed changes: {e}"));
+            Output::tip(format!(
+                "Apply them manually with: git stash apply {}",
+                stash.stash_commit
+            ));
+        }
+        result
+    }
+
+    /// Re-apply the stashed changes and forget the record
+    pub fn restore(&self, repo_root: &Path) -> Result<()> {
+        let git_repo = GitRepository::open(repo_root)?;
+        let outcome = git_repo.restore_stash(&self.stash_commit)?;
+        Self::delete(repo_root)?;
+
+        match outcome {
+            StashRestore::Applied => {
+                Output::success("Restored stashed changes");
+            }
+            StashRestore::Conflicted(files) => {
+                Output::warning("Restoring stashed changes hit conflicts:");
+                for file in &files {
+                    Output::sub_item(file);
+                }
+                Output::tip("Resolve the conflict markers, then 'git add' the files");
+                Output::tip("The stash entry is kept; drop it with 'git stash drop' once resolved");
+            }
+            StashRestore::Failed(reason) => {
+                Output::warning(format!("Stashed changes were not restored: {reason}"));
+                Output::tip(format!(
+                    "Move the conflicting files aside, then run: git stash apply {}",
+                    self.stash_commit
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Save the auto-stash record to disk
+    pub fn save(&self, repo_root: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| CascadeError::config(format!("Failed to serialize auto-stash: {e}")))?;
+        std::fs::write(Self::state_path(repo_root)?, json)
+            .map_err(|e| CascadeError::config(format!("Failed to write auto-stash state: {e}")))
+    }
+
+    /// Load the pending auto-stash record, if any
+    pub fn load(repo_root: &Path) -> Result<Option<Self>> {
+        let state_path = Self::state_path(repo_root)?;
+        if !state_path.exists() {
+            return Ok(None);
+        }
+
+        let json = std::fs::read_to_string(&state_path)
+            .map_err(|e| CascadeError::config(format!("Failed to read auto-stash state: {e}")))?;
+        let stash = serde_json::from_str(&json)
+            .map_err(|e| CascadeError::config(format!("Failed to parse auto-stash state: {e}")))?;
+        Ok(Some(stash))
+    }
+
+    /// Delete the auto-stash record
+    pub fn delete(repo_root: &Path) -> Result<()> {
+        let state_path = Self::state_path(repo_root)?;
+        if state_path.exists() {
+            std::fs::remove_file(&state_path).map_err(|e| {
+                CascadeError::config(format!("Failed to delete auto-stash state: {e}"))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a sync or rebase stopped on conflicts and is waiting for the user
+fn operation_paused(repo_root: &Path) -> bool {
+    if SyncState::exists(repo_root) {
+        return true;
+    }
+    crate::git::resolve_git_dir(repo_root)
+        .map(|git_dir| {
+            ["CHERRY_PICK_HEAD", "rebase-merge", "rebase-apply"]
+                .iter()
+                .any(|name| git_dir.join(name).exists())
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+    }
+
+    fn create_repo_with_auto_stash() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path();
+        git(path, &["init", "-q"]);
+        git(path, &["config", "user.name", "Test"]);
+        git(path, &["config", "user.email", "test@test.com"]);
+        fs::write(path.join("README.md"), "# Test\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Initial commit"]);
+
+        crate::config::initialize_repo(path, None).unwrap();
+        let config_path = crate::config::get_repo_config_dir(path)
+            .unwrap()
+            .join("config.json");
+        let mut settings = crate::config::Settings::load_from_file(&config_path).unwrap();
+        settings.set_value("cascade.auto_stash", "true").unwrap();
+        settings.save_to_file(&config_path).unwrap();
+
+        temp_dir
+    }
+
+    #[test]
+    fn test_auto_stash_round_trip() {
+        let temp_dir = create_repo_with_auto_stash();
+        let path = temp_dir.path();
+        let git_repo = GitRepository::open(path).unwrap();
+
+        // Clean tree: nothing to stash
+        assert!(AutoStash::stash_if_enabled(path, &git_repo, "sync")
+            .unwrap()
+            .is_none());
+
+        fs::write(path.join("README.md"), "# Changed\n").unwrap();
+        fs::write(path.join("notes.txt"), "untracked\n").unwrap();
+
+        let stash = AutoStash::stash_if_enabled(path, &git_repo, "sync")
+            .unwrap()
+            .unwrap();
+        assert_eq!(stash.operation, "sync");
+        assert!(!git_repo.is_dirty().unwrap());
+        assert!(crate::config::get_repo_config_dir(path)
+            .unwrap()
+            .join("config.json")
+            .exists());
+
+        let result: Result<()> = AutoStash::finish(path, Ok(()));
+        assert!(result.is_ok());
+        assert_eq!(
+            fs::read_to_string(path.join("README.md")).unwrap(),
+            "# Changed\n"
+        );
+        assert!(path.join("notes.txt").exists());
+        assert!(AutoStash::load(path).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_auto_stash_kept_while_sync_paused() {
+        let temp_dir = create_repo_with_auto_stash();
+        let path = temp_dir.path();
+        let git_repo = GitRepository::open(path).unwrap();
+
+        fs::write(path.join("README.md"), "# Changed\n").unwrap();
+        AutoStash::stash_if_enabled(path, &git_repo, "sync")
+            .unwrap()
+            .unwrap();
+
+        let git_dir = crate::git::resolve_git_dir(path).unwrap();
+        fs::write(git_dir.join("CHERRY_PICK_HEAD"), "").unwrap();
+        let result: Result<()> = AutoStash::finish(path, Err(CascadeError::branch("conflicts")));
+        assert!(result.is_err());
+        assert!(AutoStash::load(path).unwrap().is_some());
+        assert!(!git_repo.is_dirty().unwrap());
+
+        fs::remove_file(git_dir.join("CHERRY_PICK_HEAD")).unwrap();
+        AutoStash::finish(path, Ok(())).unwrap();
+        assert!(AutoStash::load(path).unwrap().is_none());
+        assert!(git_repo.is_dirty().unwrap());
+    }
+}
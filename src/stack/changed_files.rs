@@ -0,0 +1,202 @@
+//! Files each stack entry touches, cached for quick overlap checks
+//!
+//! Diffing every entry's commits on each push would get slow on long stacks, so the file
+//! list of an entry is worked out once and kept in the git dir. Entries are keyed by their
+//! commits, which makes a cached list valid for as long as those commits are the entry's.
+
+use super::{Stack, StackEntry};
+use crate::errors::{CascadeError, Result};
+use crate::git::GitRepository;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+const STATE_FILE: &str = "CASCADE_CHANGED_FILES";
+
+/// Files touched by each entry, by the entry's top commit and commit count
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ChangedFilesIndex {
+    files: HashMap<String, Vec<String>>,
+}
+
+/// An earlier entry of the stack that touches some of the same files
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileOverlap {
+    /// 1-based position of the earlier entry
+    pub position: usize,
+    pub branch: String,
+    /// Files both entries touch, sorted
+    pub files: Vec<String>,
+}
+
+impl ChangedFilesIndex {
+    fn state_path(repo_root: &Path) -> Result<PathBuf> {
+        Ok(crate::git::resolve_git_dir(repo_root)?.join(STATE_FILE))
+    }
+
+    fn key(entry: &StackEntry) -> String {
+        format!("{}:{}", entry.commit_hash, entry.commit_count)
+    }
+
+    /// Load the index; a missing or unreadable file is treated as empty
+    pub fn load(repo_root: &Path) -> Self {
+        Self::state_path(repo_root)
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the index, keeping only the entries of `stacks`
+    pub fn save<'a>(
+        &mut self,
+        repo_root: &Path,
+        stacks: impl IntoIterator<Item = &'a Stack>,
+    ) -> Result<()> {
+        let live: HashSet<String> = stacks
+            .into_iter()
+            .flat_map(|stack| stack.entries.iter().map(Self::key))
+            .collect();
+        self.files.retain(|key, _| live.contains(key));
+
+        let json = serde_json::to_string(&self)
+            .map_err(|e| CascadeError::config(format!("Failed to serialize changed files: {e}")))?;
+        std::fs::write(Self::state_path(repo_root)?, json)
+            .map_err(|e| CascadeError::config(format!("Failed to write changed files: {e}")))
+    }
+
+    /// Files the entry's commits touch, sorted, from the index or worked out and added to it
+    pub fn files(&mut self, git_repo: &GitRepository, entry: &StackEntry) -> Result<&[String]> {
+        let key = Self::key(entry);
+        if !self.files.contains_key(&key) {
+            let mut files = BTreeSet::new();
+            for commit in git_repo.get_first_parent_chain(&entry.commit_hash, entry.commit_count)? {
+                files.extend(git_repo.get_commit_files(&commit)?);
+            }
+            self.files.insert(key.clone(), files.into_iter().collect());
+        }
+        Ok(&self.files[&key])
+    }
+
+    /// Unmerged entries below `entry_id` in `stack` that touch some of the same files,
+    /// nearest first
+    pub fn overlaps(
+        &mut self,
+        git_repo: &GitRepository,
+        stack: &Stack,
+        entry_id: &Uuid,
+    ) -> Result<Vec<FileOverlap>> {
+        let Some(index) = stack.entries.iter().position(|e| e.id == *entry_id) else {
+            return Ok(Vec::new());
+        };
+        let touched: HashSet<String> = self
+            .files(git_repo, &stack.entries[index])?
+            .iter()
+            .cloned()
+            .collect();
+
+        let mut overlaps = Vec::new();
+        for (position, earlier) in stack.entries[..index].iter().enumerate().rev() {
+            if earlier.is_merged {
+                continue;
+            }
+            let files: Vec<String> = self
+                .files(git_repo, earlier)?
+                .iter()
+                .filter(|file| touched.contains(*file))
+                .cloned()
+                .collect();
+            if !files.is_empty() {
+                overlaps.push(FileOverlap {
+                    position: position + 1,
+                    branch: earlier.branch.clone(),
+                    files,
+                });
+            }
+        }
+        Ok(overlaps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(repo: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(repo)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit(repo: &Path, files: &[&str], message: &str) -> String {
+        for file in files {
+            std::fs::write(repo.join(file), message).unwrap();
+        }
+        git(repo, &["add", "."]);
+        git(repo, &["commit", "-m", message]);
+        git(repo, &["rev-parse", "HEAD"])
+    }
+
+    #[test]
+    fn test_overlaps_and_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path();
+        git(repo, &["init"]);
+        git(repo, &["config", "user.name", "Test"]);
+        git(repo, &["config", "user.email", "test@test.com"]);
+        commit(repo, &["README.md"], "Initial");
+
+        let mut stack = Stack::new("demo".into(), "main".into(), None);
+        let first = commit(repo, &["a.rs", "b.rs"], "Touch a and b");
+        stack.push_entry("entry-1".into(), first, "Touch a and b".into());
+        let second = commit(repo, &["c.rs"], "Touch c");
+        stack.push_entry("entry-2".into(), second, "Touch c".into());
+        let third = commit(repo, &["b.rs", "c.rs", "d.rs"], "Touch b, c and d");
+        let third_id = stack.push_entry("entry-3".into(), third, "Touch b, c and d".into());
+
+        let git_repo = GitRepository::open(repo).unwrap();
+        let mut index = ChangedFilesIndex::load(repo);
+        let overlaps = index.overlaps(&git_repo, &stack, &third_id).unwrap();
+        assert_eq!(
+            overlaps,
+            vec![
+                FileOverlap {
+                    position: 2,
+                    branch: "entry-2".into(),
+                    files: vec!["c.rs".into()],
+                },
+                FileOverlap {
+                    position: 1,
+                    branch: "entry-1".into(),
+                    files: vec!["b.rs".into()],
+                },
+            ]
+        );
+
+        // Merged entries no longer count
+        stack.entries[0].is_merged = true;
+        assert_eq!(
+            index.overlaps(&git_repo, &stack, &third_id).unwrap().len(),
+            1
+        );
+
+        index.save(repo, [&stack]).unwrap();
+        let mut reloaded = ChangedFilesIndex::load(repo);
+        assert_eq!(reloaded.files.len(), 3);
+        assert_eq!(
+            reloaded.files(&git_repo, &stack.entries[2]).unwrap(),
+            ["b.rs", "c.rs", "d.rs"]
+        );
+
+        // Entries that left every stack are dropped on save
+        reloaded.save(repo, []).unwrap();
+        assert!(ChangedFilesIndex::load(repo).files.is_empty());
+    }
+}
@@ -200,6 +200,30 @@ impl CleanupManager {
             }
         }
 
+        // Squash merges land an entry under another commit, so its branch never looks
+        // merged; trust the entry's merged flag while the branch still points where it did
+        let landed_entry = stacks
+            .iter()
+            .filter(|stack| Some(stack.id) == stack_id)
+            .flat_map(|stack| &stack.entries)
+            .find(|entry| Some(entry.id) == entry_id && entry.is_merged);
+        if let Some(entry) = landed_entry {
+            if self.git_repo.get_branch_head(branch_name).ok().as_deref()
+                == Some(entry.commit_hash.as_str())
+            {
+                return Ok(Some(CleanupCandidate {
+                    branch_name: branch_name.to_string(),
+                    entry_id,
+                    stack_id,
+                    is_merged: true,
+                    has_remote,
+                    is_current,
+                    reason: CleanupReason::StackEntryMerged,
+                    safety_info: "Entry landed (e.g. squash-merged)".to_string(),
+                }));
+            }
+        }
+
         // Never clean up active stack branches - they're in use!
         if stack_id.is_some() {
             return Ok(None);
@@ -329,10 +353,16 @@ impl CleanupManager {
 
         // Delete the branch
         match candidate.reason {
-            CleanupReason::FullyMerged | CleanupReason::StackEntryMerged => {
+            CleanupReason::FullyMerged => {
                 // Safe to delete merged branches
                 self.git_repo.delete_branch(&candidate.branch_name)?;
             }
+            CleanupReason::StackEntryMerged => {
+                // The branch still points at the landed commit, whose change is on the base
+                // under another hash; the unpushed-commit check compares hashes and would
+                // count it as lost work
+                self.git_repo.delete_branch_unsafe(&candidate.branch_name)?;
+            }
             CleanupReason::Stale | CleanupReason::Orphaned => {
                 // Run through standard safety checks even for stale/orphaned branches
                 // This prevents accidental deletion when unpushed commits are present.
@@ -426,7 +456,7 @@ impl CleanupManager {
             "release",
         ];
 
-        protected_branches.contains(&branch_name)
+        protected_branches.contains(&branch_name) || self.git_repo.is_protected_branch(branch_name)
     }
 
     /// Get the age of the last commit on a branch in days
@@ -0,0 +1,297 @@
+use super::Stack;
+use crate::errors::{CascadeError, Result};
+use crate::git::GitRepository;
+use std::collections::HashSet;
+
+/// Which commits `ca push` adds to the stack
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CommitSelector {
+    /// Commits on the current branch that aren't stack entries yet
+    #[default]
+    Unpushed,
+    /// One specific commit
+    Commit(String),
+    /// Every commit after this reference, up to HEAD
+    Since(String),
+    /// Specific commits, pushed in the order given
+    List(Vec<String>),
+    /// Unpushed commits, narrowed down by picking them from a list
+    Interactive,
+}
+
+impl CommitSelector {
+    /// Build a selector from the `ca push` flags. At most one of them may be given
+    pub fn from_flags(
+        commit: Option<String>,
+        since: Option<String>,
+        commits: Option<String>,
+        interactive: bool,
+    ) -> Result<Self> {
+        let given = [
+            commit.is_some(),
+            since.is_some(),
+            commits.is_some(),
+            interactive,
+        ];
+        if given.iter().filter(|&&set| set).count() > 1 {
+            return Err(CascadeError::validation(
+                "Use only one of --commit, --since, --commits and --interactive",
+            ));
+        }
+
+        if let Some(commit) = commit {
+            return Ok(Self::Commit(commit));
+        }
+        if let Some(since) = since {
+            return Ok(Self::Since(since));
+        }
+        if let Some(commits) = commits {
+            let list = parse_commit_list(&commits);
+            if list.is_empty() {
+                return Err(CascadeError::validation(
+                    "--commits needs at least one commit",
+                ));
+            }
+            return Ok(Self::List(list));
+        }
+        if interactive {
+            return Ok(Self::Interactive);
+        }
+        Ok(Self::Unpushed)
+    }
+
+    /// Whether the user named the commits rather than leaving it to the stack
+    pub fn is_explicit(&self) -> bool {
+        matches!(self, Self::Commit(_) | Self::Since(_) | Self::List(_))
+    }
+
+    /// Resolve to full commit hashes, oldest first. `Interactive` resolves to the candidates
+    /// to pick from
+    pub fn resolve(&self, repo: &GitRepository, stack: &Stack) -> Result<Vec<String>> {
+        match self {
+            Self::Unpushed | Self::Interactive => unpushed_commits(repo, stack),
+            Self::Commit(reference) => {
+                Ok(vec![repo.resolve_reference(reference)?.id().to_string()])
+            }
+            Self::Since(reference) => {
+                let since = repo.resolve_reference(reference)?.id().to_string();
+                let head = repo.get_head_commit()?.id().to_string();
+                let mut commits: Vec<String> = repo
+                    .get_commits_between(&since, &head)?
+                    .into_iter()
+                    .map(|c| c.id().to_string())
+                    .collect();
+                commits.reverse();
+                Ok(commits)
+            }
+            Self::List(references) => references
+                .iter()
+                .map(|reference| Ok(repo.resolve_reference(reference)?.id().to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Split a comma-separated `--commits` value, dropping blanks
+pub fn parse_commit_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Commits on the current branch that aren't stack entries yet, oldest first.
+///
+/// On the base branch this walks back from HEAD to the first commit that is an entry; on any
+/// other branch it takes the commits the branch has on top of the base.
+pub fn unpushed_commits(repo: &GitRepository, stack: &Stack) -> Result<Vec<String>> {
+    // Every commit an entry covers, not just its tip
+    let mut tracked = HashSet::new();
+    for entry in &stack.entries {
+        match repo.get_first_parent_chain(&entry.commit_hash, entry.commit_count) {
+            Ok(commits) => tracked.extend(commits),
+            Err(_) => {
+                tracked.insert(entry.commit_hash.clone());
+            }
+        }
+    }
+    let is_entry = |hash: &str| tracked.contains(hash);
+    let current_branch = repo.get_current_branch()?;
+
+    let mut unpushed = Vec::new();
+    if current_branch == stack.base_branch {
+        let mut current_commit = repo.get_head_commit()?;
+        loop {
+            let commit_hash = current_commit.id().to_string();
+            if is_entry(&commit_hash) {
+                break;
+            }
+            unpushed.push(commit_hash);
+
+            match current_commit.parents().next() {
+                Some(parent) => current_commit = parent,
+                None => break,
+            }
+        }
+    } else {
+        let commits = repo
+            .get_commits_between(&stack.base_branch, &current_branch)
+            .map_err(|e| {
+                CascadeError::branch(format!(
+                    "Failed to calculate commits between '{}' and '{current_branch}': {e}. \
+                     This usually means the branches have diverged or don't share common history.",
+                    stack.base_branch
+                ))
+            })?;
+        unpushed.extend(
+            commits
+                .into_iter()
+                .map(|c| c.id().to_string())
+                .filter(|hash| !is_entry(hash)),
+        );
+    }
+
+    unpushed.reverse();
+    Ok(unpushed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(repo_path: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(repo_path)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit_file(repo_path: &Path, name: &str) -> String {
+        std::fs::write(repo_path.join(name), name).unwrap();
+        git(repo_path, &["add", "."]);
+        git(repo_path, &["commit", "-m", &format!("Add {name}")]);
+        git(repo_path, &["rev-parse", "HEAD"])
+    }
+
+    fn create_test_repo() -> (TempDir, GitRepository) {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path();
+        git(path, &["init", "-b", "main"]);
+        git(path, &["config", "user.name", "Test User"]);
+        git(path, &["config", "user.email", "test@example.com"]);
+        commit_file(path, "README.md");
+        let repo = GitRepository::open(path).unwrap();
+        (temp_dir, repo)
+    }
+
+    #[test]
+    fn test_from_flags() {
+        assert_eq!(
+            CommitSelector::from_flags(None, None, None, false).unwrap(),
+            CommitSelector::Unpushed
+        );
+        assert_eq!(
+            CommitSelector::from_flags(None, None, Some(" a1, ,b2 ".into()), false).unwrap(),
+            CommitSelector::List(vec!["a1".into(), "b2".into()])
+        );
+        assert_eq!(
+            CommitSelector::from_flags(None, None, None, true).unwrap(),
+            CommitSelector::Interactive
+        );
+        assert!(
+            CommitSelector::from_flags(Some("a1".into()), Some("HEAD~2".into()), None, false)
+                .is_err()
+        );
+        assert!(CommitSelector::from_flags(None, None, Some(",".into()), false).is_err());
+        assert!(!CommitSelector::Interactive.is_explicit());
+        assert!(CommitSelector::Since("HEAD~1".into()).is_explicit());
+    }
+
+    #[test]
+    fn test_unpushed_on_feature_branch_skips_entries() {
+        let (temp_dir, repo) = create_test_repo();
+        let path = temp_dir.path();
+        git(path, &["checkout", "-b", "work"]);
+        let first = commit_file(path, "one.txt");
+        let second = commit_file(path, "two.txt");
+        let third = commit_file(path, "three.txt");
+
+        let mut stack = Stack::new("s".into(), "main".into(), None);
+        assert_eq!(
+            CommitSelector::Unpushed.resolve(&repo, &stack).unwrap(),
+            vec![first.clone(), second.clone(), third.clone()]
+        );
+
+        stack.push_entry("one".into(), first, "Add one.txt".into());
+        assert_eq!(
+            CommitSelector::Interactive.resolve(&repo, &stack).unwrap(),
+            vec![second, third]
+        );
+    }
+
+    #[test]
+    fn test_unpushed_skips_every_commit_of_a_grouped_entry() {
+        let (temp_dir, repo) = create_test_repo();
+        let path = temp_dir.path();
+        git(path, &["checkout", "-b", "work"]);
+        commit_file(path, "one.txt");
+        let second = commit_file(path, "two.txt");
+        let third = commit_file(path, "three.txt");
+
+        let mut stack = Stack::new("s".into(), "main".into(), None);
+        let id = stack.push_entry("pair".into(), second, "Add one.txt".into());
+        stack.set_entry_commit_count(&id, 2);
+        assert_eq!(unpushed_commits(&repo, &stack).unwrap(), vec![third]);
+    }
+
+    #[test]
+    fn test_unpushed_on_base_branch_stops_at_entry() {
+        let (temp_dir, repo) = create_test_repo();
+        let path = temp_dir.path();
+        let first = commit_file(path, "one.txt");
+        let second = commit_file(path, "two.txt");
+
+        let mut stack = Stack::new("s".into(), "main".into(), None);
+        stack.push_entry("one".into(), first, "Add one.txt".into());
+        assert_eq!(unpushed_commits(&repo, &stack).unwrap(), vec![second]);
+    }
+
+    #[test]
+    fn test_since_and_list_resolve_oldest_first() {
+        let (temp_dir, repo) = create_test_repo();
+        let path = temp_dir.path();
+        let first = commit_file(path, "one.txt");
+        let second = commit_file(path, "two.txt");
+        let stack = Stack::new("s".into(), "main".into(), None);
+
+        assert_eq!(
+            CommitSelector::Since("HEAD~2".into())
+                .resolve(&repo, &stack)
+                .unwrap(),
+            vec![first.clone(), second.clone()]
+        );
+        assert_eq!(
+            CommitSelector::List(vec![second[..8].to_string(), "HEAD~1".into()])
+                .resolve(&repo, &stack)
+                .unwrap(),
+            vec![second.clone(), first]
+        );
+        assert_eq!(
+            CommitSelector::Commit("HEAD".into())
+                .resolve(&repo, &stack)
+                .unwrap(),
+            vec![second]
+        );
+        assert!(CommitSelector::Commit("nope".into())
+            .resolve(&repo, &stack)
+            .is_err());
+    }
+}
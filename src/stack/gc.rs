@@ -0,0 +1,303 @@
+//! Garbage collection of Cascade's own state
+//!
+//! Snapshots and cache files pile up in `.cascade` over months of use. `ca gc` removes the
+//! ones outside the `gc.*` retention policy, and the same pass runs in the background once
+//! every `gc.auto_after_operations` commands. Backup refs follow their own `backups.*`
+//! policy and are pruned alongside.
+
+use crate::config::GcSettings;
+use crate::errors::{CascadeError, Result};
+use crate::stack::snapshots::list_snapshots;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+const CACHE_DIR: &str = "cache";
+const STATE_FILE: &str = "gc.json";
+
+/// Kind of state file gc looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateKind {
+    Snapshot,
+    Cache,
+}
+
+/// A file under `.cascade` that gc may remove
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateFile {
+    pub path: PathBuf,
+    /// Snapshot id or path relative to the cache directory
+    pub label: String,
+    pub kind: StateKind,
+    /// Creation time for snapshots, last modification for cache files
+    pub modified: DateTime<Utc>,
+    pub size: u64,
+}
+
+/// Why a file falls outside the retention policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneReason {
+    /// Older than the maximum age
+    Expired,
+    /// Beyond the newest `gc.keep_snapshots` snapshots
+    OverCount,
+    /// Removed to bring the total size under `gc.max_size_mb`
+    OverSize,
+}
+
+impl std::fmt::Display for PruneReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PruneReason::Expired => write!(f, "expired"),
+            PruneReason::OverCount => write!(f, "over count"),
+            PruneReason::OverSize => write!(f, "over size"),
+        }
+    }
+}
+
+/// Counter behind the automatic gc, stored in `.cascade/gc.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GcState {
+    /// Commands run since the last gc
+    pub operations: u32,
+    pub last_run: Option<DateTime<Utc>>,
+}
+
+impl GcState {
+    /// Read the state, starting from zero when it is missing or unreadable
+    pub fn load(config_dir: &Path) -> Self {
+        std::fs::read_to_string(config_dir.join(STATE_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, config_dir: &Path) -> Result<()> {
+        crate::utils::atomic_file::write_json(&config_dir.join(STATE_FILE), self)
+    }
+
+    /// Count one command; returns true when `auto_after_operations` has been reached, in
+    /// which case the counter starts over
+    pub fn record_operation(&mut self, auto_after_operations: u32) -> bool {
+        if auto_after_operations == 0 {
+            return false;
+        }
+        self.operations += 1;
+        if self.operations < auto_after_operations {
+            return false;
+        }
+        self.operations = 0;
+        true
+    }
+}
+
+/// Snapshots and cache files in the Cascade config directory `config_dir`
+pub fn state_files(config_dir: &Path) -> Result<Vec<StateFile>> {
+    let mut files = Vec::new();
+    for snapshot in list_snapshots(config_dir)? {
+        let size = std::fs::metadata(&snapshot.path)
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        files.push(StateFile {
+            path: snapshot.path,
+            label: snapshot.id,
+            kind: StateKind::Snapshot,
+            modified: snapshot.manifest.created_at,
+            size,
+        });
+    }
+
+    let cache_dir = config_dir.join(CACHE_DIR);
+    collect_cache_files(&cache_dir, &cache_dir, &mut files);
+    Ok(files)
+}
+
+fn collect_cache_files(cache_dir: &Path, dir: &Path, files: &mut Vec<StateFile>) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_cache_files(cache_dir, &path, files);
+            continue;
+        }
+        let Ok(modified) = metadata.modified() else {
+            continue;
+        };
+        files.push(StateFile {
+            label: path
+                .strip_prefix(cache_dir)
+                .unwrap_or(&path)
+                .display()
+                .to_string(),
+            path,
+            kind: StateKind::Cache,
+            modified: modified.into(),
+            size: metadata.len(),
+        });
+    }
+}
+
+/// Files outside `policy`, with the reason for each.
+///
+/// Snapshots beyond the newest `keep_snapshots` or older than `snapshot_max_age_days`, and
+/// cache files older than `cache_max_age_days` go first. If what remains is still larger
+/// than `max_size_mb`, the oldest files go next; the newest snapshot is always kept.
+pub fn prunable_files<'a>(
+    files: &'a [StateFile],
+    policy: &GcSettings,
+    now: DateTime<Utc>,
+) -> Vec<(&'a StateFile, PruneReason)> {
+    let expired = |file: &StateFile, max_age_days: u32| {
+        max_age_days > 0 && (now - file.modified).num_days() >= i64::from(max_age_days)
+    };
+
+    let mut snapshots: Vec<&StateFile> = files
+        .iter()
+        .filter(|file| file.kind == StateKind::Snapshot)
+        .collect();
+    snapshots.sort_by_key(|file| std::cmp::Reverse(file.modified));
+    let newest_snapshot = snapshots.first().map(|file| file.path.clone());
+
+    let mut prunable = Vec::new();
+    let mut kept = Vec::new();
+    for (i, file) in snapshots.into_iter().enumerate() {
+        if expired(file, policy.snapshot_max_age_days) {
+            prunable.push((file, PruneReason::Expired));
+        } else if i >= policy.keep_snapshots {
+            prunable.push((file, PruneReason::OverCount));
+        } else {
+            kept.push(file);
+        }
+    }
+    for file in files.iter().filter(|file| file.kind == StateKind::Cache) {
+        if expired(file, policy.cache_max_age_days) {
+            prunable.push((file, PruneReason::Expired));
+        } else {
+            kept.push(file);
+        }
+    }
+
+    if policy.max_size_mb > 0 {
+        let limit = policy.max_size_mb * 1024 * 1024;
+        let mut total: u64 = kept.iter().map(|file| file.size).sum();
+        kept.sort_by_key(|file| file.modified);
+        for file in kept {
+            if total <= limit {
+                break;
+            }
+            if Some(&file.path) == newest_snapshot.as_ref() {
+                continue;
+            }
+            total -= file.size;
+            prunable.push((file, PruneReason::OverSize));
+        }
+    }
+
+    prunable
+}
+
+/// Delete `files`; returns how many bytes were freed
+pub fn remove_files(files: &[&StateFile]) -> Result<u64> {
+    let mut freed = 0;
+    for file in files {
+        std::fs::remove_file(&file.path).map_err(|e| {
+            CascadeError::config(format!("Failed to remove {}: {e}", file.path.display()))
+        })?;
+        debug!("Removed {}", file.path.display());
+        freed += file.size;
+    }
+    Ok(freed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(label: &str, kind: StateKind, days_old: i64, size_mb: u64) -> StateFile {
+        StateFile {
+            path: PathBuf::from(label),
+            label: label.to_string(),
+            kind,
+            modified: Utc::now() - chrono::Duration::days(days_old),
+            size: size_mb * 1024 * 1024,
+        }
+    }
+
+    fn labels(prunable: &[(&StateFile, PruneReason)]) -> Vec<(String, PruneReason)> {
+        let mut labels: Vec<_> = prunable
+            .iter()
+            .map(|(file, reason)| (file.label.clone(), *reason))
+            .collect();
+        labels.sort_by(|a, b| a.0.cmp(&b.0));
+        labels
+    }
+
+    #[test]
+    fn test_prunable_files_follow_policy() {
+        let policy = GcSettings {
+            keep_snapshots: 2,
+            snapshot_max_age_days: 30,
+            cache_max_age_days: 7,
+            max_size_mb: 0,
+            auto_after_operations: 0,
+        };
+        let files = vec![
+            file("s1", StateKind::Snapshot, 1, 1),
+            file("s2", StateKind::Snapshot, 2, 1),
+            file("s3", StateKind::Snapshot, 3, 1),
+            file("s4", StateKind::Snapshot, 40, 1),
+            file("c1", StateKind::Cache, 1, 1),
+            file("c2", StateKind::Cache, 10, 1),
+        ];
+
+        assert_eq!(
+            labels(&prunable_files(&files, &policy, Utc::now())),
+            vec![
+                ("c2".to_string(), PruneReason::Expired),
+                ("s3".to_string(), PruneReason::OverCount),
+                ("s4".to_string(), PruneReason::Expired),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_size_limit_removes_oldest_but_keeps_newest_snapshot() {
+        let policy = GcSettings {
+            keep_snapshots: 10,
+            snapshot_max_age_days: 0,
+            cache_max_age_days: 0,
+            max_size_mb: 5,
+            auto_after_operations: 0,
+        };
+        let files = vec![
+            file("s1", StateKind::Snapshot, 1, 8),
+            file("s2", StateKind::Snapshot, 2, 2),
+            file("c1", StateKind::Cache, 3, 1),
+        ];
+
+        assert_eq!(
+            labels(&prunable_files(&files, &policy, Utc::now())),
+            vec![
+                ("c1".to_string(), PruneReason::OverSize),
+                ("s2".to_string(), PruneReason::OverSize),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_record_operation_triggers_after_threshold() {
+        let mut state = GcState::default();
+        assert!(!state.record_operation(3));
+        assert!(!state.record_operation(3));
+        assert!(state.record_operation(3));
+        assert_eq!(state.operations, 0);
+        assert!(!state.record_operation(0));
+        assert_eq!(state.operations, 0);
+    }
+}
@@ -0,0 +1,222 @@
+//! User-defined lifecycle hooks
+//!
+//! Teams can drop executable scripts into `.cascade/hooks/` to run custom logic
+//! around Cascade operations (ticket updates, changelog generation, extra checks).
+//! Each script receives a JSON description of the operation on stdin.
+
+use super::{Stack, StackEntry};
+use crate::cli::output::Output;
+use crate::errors::{CascadeError, Result};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Operations that can trigger a script in `.cascade/hooks/`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleHook {
+    PreSubmit,
+    PostSubmit,
+    PreLand,
+    PostLand,
+    PostSync,
+}
+
+impl LifecycleHook {
+    /// Script name looked up in `.cascade/hooks/`
+    pub fn name(&self) -> &'static str {
+        match self {
+            LifecycleHook::PreSubmit => "pre-submit",
+            LifecycleHook::PostSubmit => "post-submit",
+            LifecycleHook::PreLand => "pre-land",
+            LifecycleHook::PostLand => "post-land",
+            LifecycleHook::PostSync => "post-sync",
+        }
+    }
+
+    /// Pre-operation hooks abort the operation when they exit non-zero
+    pub fn is_blocking(&self) -> bool {
+        matches!(self, LifecycleHook::PreSubmit | LifecycleHook::PreLand)
+    }
+}
+
+/// Directory holding user lifecycle hook scripts
+pub fn hooks_dir(repo_root: &Path) -> Result<PathBuf> {
+    Ok(crate::config::get_repo_config_dir(repo_root)?.join("hooks"))
+}
+
+/// Locate the script for a hook, if the user installed one
+pub fn find_hook_script(repo_root: &Path, hook: LifecycleHook) -> Result<Option<PathBuf>> {
+    let dir = hooks_dir(repo_root)?;
+
+    let candidates: Vec<PathBuf> = if cfg!(windows) {
+        ["bat", "cmd", "exe"]
+            .iter()
+            .map(|ext| dir.join(format!("{}.{ext}", hook.name())))
+            .collect()
+    } else {
+        vec![dir.join(hook.name())]
+    };
+
+    for candidate in candidates {
+        if !candidate.is_file() {
+            continue;
+        }
+        if !crate::utils::platform::is_executable(&candidate) {
+            Output::warning(format!(
+                "Skipping {} hook: {} is not executable (chmod +x it to enable)",
+                hook.name(),
+                candidate.display()
+            ));
+            return Ok(None);
+        }
+        return Ok(Some(candidate));
+    }
+
+    Ok(None)
+}
+
+/// Run a lifecycle hook with `context` on stdin.
+///
+/// Missing hooks are a no-op. A failing blocking hook returns an error so the caller
+/// can abort; failures of other hooks are reported as warnings.
+pub fn run_hook(repo_root: &Path, hook: LifecycleHook, context: Value) -> Result<()> {
+    let Some(script) = find_hook_script(repo_root, hook)? else {
+        return Ok(());
+    };
+
+    let mut payload = json!({
+        "hook": hook.name(),
+        "repository": repo_root.display().to_string(),
+    });
+    if let (Some(payload), Value::Object(fields)) = (payload.as_object_mut(), context) {
+        payload.extend(fields);
+    }
+
+    Output::sub_item(format!("Running {} hook", hook.name()));
+    let outcome = execute(&script, repo_root, hook, &payload);
+
+    let failure = match outcome {
+        Ok(status) if status.success() => return Ok(()),
+        Ok(status) => match status.code() {
+            Some(code) => format!("{} hook exited with status {code}", hook.name()),
+            None => format!("{} hook was terminated by a signal", hook.name()),
+        },
+        Err(e) => format!("could not run {} hook: {e}", hook.name()),
+    };
+
+    if hook.is_blocking() {
+        Err(CascadeError::validation(format!(
+            "Aborted: {failure} ({})",
+            script.display()
+        )))
+    } else {
+        Output::warning(failure);
+        Ok(())
+    }
+}
+
+fn execute(
+    script: &Path,
+    repo_root: &Path,
+    hook: LifecycleHook,
+    payload: &Value,
+) -> std::io::Result<std::process::ExitStatus> {
+    let mut child = Command::new(script)
+        .current_dir(repo_root)
+        .env("CASCADE_HOOK", hook.name())
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // Scripts that don't read stdin close it early; that's not an error
+        if let Err(e) = stdin.write_all(payload.to_string().as_bytes()) {
+            if e.kind() != std::io::ErrorKind::BrokenPipe {
+                return Err(e);
+            }
+        }
+    }
+
+    child.wait()
+}
+
+/// JSON description of a stack for hook payloads
+pub fn stack_context(stack: &Stack) -> Value {
+    json!({
+        "id": stack.id.to_string(),
+        "name": stack.name,
+        "base_branch": stack.base_branch,
+        "working_branch": stack.working_branch,
+        "entries": stack
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| entry_context(i + 1, entry))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// JSON description of a stack entry (with its 1-based position) for hook payloads
+pub fn entry_context(position: usize, entry: &StackEntry) -> Value {
+    json!({
+        "position": position,
+        "id": entry.id.to_string(),
+        "branch": entry.branch,
+        "commit": entry.commit_hash,
+        "message": entry.message,
+        "pull_request_id": entry.pull_request_id,
+        "is_merged": entry.is_merged,
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_repo_with_hook(hook: LifecycleHook, body: &str) -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        git2::Repository::init(temp_dir.path()).unwrap();
+
+        let dir = hooks_dir(temp_dir.path()).unwrap();
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join(hook.name());
+        fs::write(&script, format!("#!/bin/sh\n{body}\n")).unwrap();
+        crate::utils::platform::make_executable(&script).unwrap();
+
+        temp_dir
+    }
+
+    #[test]
+    fn test_hook_receives_json_context() {
+        let temp_dir = create_repo_with_hook(LifecycleHook::PostSync, "cat > payload.json");
+
+        run_hook(
+            temp_dir.path(),
+            LifecycleHook::PostSync,
+            json!({ "stack": { "name": "feature" } }),
+        )
+        .unwrap();
+
+        let payload: Value = serde_json::from_str(
+            &fs::read_to_string(temp_dir.path().join("payload.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(payload["hook"], "post-sync");
+        assert_eq!(payload["stack"]["name"], "feature");
+    }
+
+    #[test]
+    fn test_failing_hooks() {
+        let temp_dir = create_repo_with_hook(LifecycleHook::PreSubmit, "exit 3");
+        assert!(run_hook(temp_dir.path(), LifecycleHook::PreSubmit, json!({})).is_err());
+
+        // Post hooks only warn
+        let temp_dir = create_repo_with_hook(LifecycleHook::PostSubmit, "exit 3");
+        assert!(run_hook(temp_dir.path(), LifecycleHook::PostSubmit, json!({})).is_ok());
+
+        // No script installed is a no-op
+        assert!(run_hook(temp_dir.path(), LifecycleHook::PreLand, json!({})).is_ok());
+    }
+}
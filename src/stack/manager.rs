@@ -1,12 +1,13 @@
 use super::metadata::RepositoryMetadata;
-use super::{CommitMetadata, Stack, StackEntry, StackMetadata, StackStatus};
+use super::snapshots::{self, Snapshot};
+use super::{CommitMetadata, Stack, StackEntry, StackMetadata, StackOverrides, StackStatus};
 use crate::cli::output::Output;
-use crate::config::{get_repo_config_dir, Settings};
+use crate::cli::prompt::{prompter, Prompter};
+use crate::config::{get_repo_config_dir, Settings, StackTemplate};
 use crate::errors::{CascadeError, Result};
 use crate::git::GitRepository;
 use chrono::Utc;
-use dialoguer::{theme::ColorfulTheme, Select};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use tracing::{debug, warn};
@@ -32,6 +33,17 @@ pub enum BranchModification {
     },
 }
 
+/// Outcome of [`StackManager::restore_snapshot`]
+#[derive(Debug)]
+pub struct SnapshotRestore {
+    /// Branches moved back to their snapshotted commit
+    pub restored: Vec<String>,
+    /// Branches whose snapshotted commit no longer exists
+    pub missing: Vec<String>,
+    /// Snapshot of the state before the restore
+    pub previous: Snapshot,
+}
+
 /// Manages all stack operations and persistence
 pub struct StackManager {
     /// Git repository interface
@@ -159,6 +171,24 @@ impl StackManager {
         Ok(stack_id)
     }
 
+    /// Create a stack preset by `template`: its base applies unless `base_branch` is
+    /// given, and the rest becomes the stack's overrides
+    pub fn create_stack_from_template(
+        &mut self,
+        name: String,
+        base_branch: Option<String>,
+        description: Option<String>,
+        template: &StackTemplate,
+    ) -> Result<Uuid> {
+        let base_branch = base_branch.or_else(|| template.base.clone());
+        let stack_id = self.create_stack(name, base_branch, description)?;
+        if let Some(stack) = self.stacks.get_mut(&stack_id) {
+            stack.overrides = StackOverrides::from_template(template);
+        }
+        self.save_to_disk()?;
+        Ok(stack_id)
+    }
+
     /// Get a stack by ID
     pub fn get_stack(&self, stack_id: &Uuid) -> Option<&Stack> {
         self.stacks.get(stack_id)
@@ -264,6 +294,18 @@ impl StackManager {
             .remove(stack_id)
             .ok_or_else(|| CascadeError::config(format!("Stack with ID {stack_id} not found")))?;
 
+        // Dependent stacks inherit this stack's own base (and parent, if any)
+        for dependent in self.stacks.values_mut() {
+            if dependent.depends_on == Some(*stack_id) {
+                dependent.depends_on = stack.depends_on;
+                dependent.base_branch = stack.base_branch.clone();
+                dependent.updated_at = Utc::now();
+                if let Some(meta) = self.metadata.get_stack_mut(&dependent.id) {
+                    meta.base_branch = stack.base_branch.clone();
+                }
+            }
+        }
+
         // Remove metadata
         self.metadata.remove_stack(stack_id);
 
@@ -285,6 +327,469 @@ impl StackManager {
         Ok(stack)
     }
 
+    /// Make `stack_id` depend on `parent_id` (or clear the dependency with `None`).
+    /// The dependent stack's base becomes the parent's top entry branch.
+    /// Returns the new base branch.
+    pub fn set_stack_dependency(
+        &mut self,
+        stack_id: &Uuid,
+        parent_id: Option<Uuid>,
+    ) -> Result<String> {
+        if !self.stacks.contains_key(stack_id) {
+            return Err(CascadeError::config(format!(
+                "Stack with ID {stack_id} not found"
+            )));
+        }
+
+        let new_base = match parent_id {
+            Some(parent_id) => {
+                if parent_id == *stack_id {
+                    return Err(CascadeError::validation("A stack cannot depend on itself"));
+                }
+
+                // Walk the parent's dependency chain to reject cycles
+                let mut cursor = Some(parent_id);
+                while let Some(current) = cursor {
+                    if current == *stack_id {
+                        return Err(CascadeError::validation(
+                            "Dependency would create a cycle between stacks",
+                        ));
+                    }
+                    cursor = self.stacks.get(&current).and_then(|s| s.depends_on);
+                }
+
+                self.stacks
+                    .get(&parent_id)
+                    .ok_or_else(|| {
+                        CascadeError::config(format!("Stack with ID {parent_id} not found"))
+                    })?
+                    .dependency_base_branch()
+            }
+            None => {
+                // Fall back to the base of the stack we used to depend on
+                let stack = &self.stacks[stack_id];
+                match stack.depends_on.and_then(|id| self.stacks.get(&id)) {
+                    Some(old_parent) => old_parent.base_branch.clone(),
+                    None => stack.base_branch.clone(),
+                }
+            }
+        };
+
+        if let Some(stack) = self.stacks.get_mut(stack_id) {
+            stack.depends_on = parent_id;
+            stack.base_branch = new_base.clone();
+            stack.updated_at = Utc::now();
+        }
+        if let Some(meta) = self.metadata.get_stack_mut(stack_id) {
+            meta.base_branch = new_base.clone();
+        }
+
+        self.save_to_disk()?;
+        Ok(new_base)
+    }
+
+    /// Get all stacks that declare a dependency on the given stack
+    pub fn get_dependent_stacks(&self, parent_id: &Uuid) -> Vec<&Stack> {
+        self.stacks
+            .values()
+            .filter(|stack| stack.depends_on == Some(*parent_id))
+            .collect()
+    }
+
+    /// Point every stack that depends on `parent_id` at the parent's current top branch.
+    /// Returns `(stack name, old base, new base)` for each stack whose base changed.
+    pub fn retarget_dependent_stacks(
+        &mut self,
+        parent_id: &Uuid,
+    ) -> Result<Vec<(String, String, String)>> {
+        let new_base = match self.stacks.get(parent_id) {
+            Some(parent) => parent.dependency_base_branch(),
+            None => return Ok(Vec::new()),
+        };
+
+        let mut retargeted = Vec::new();
+        for stack in self.stacks.values_mut() {
+            if stack.depends_on == Some(*parent_id) && stack.base_branch != new_base {
+                let old_base = std::mem::replace(&mut stack.base_branch, new_base.clone());
+                stack.updated_at = Utc::now();
+                if let Some(meta) = self.metadata.get_stack_mut(&stack.id) {
+                    meta.base_branch = new_base.clone();
+                }
+                debug!(
+                    "Retargeted dependent stack '{}' from '{}' to '{}'",
+                    stack.name, old_base, new_base
+                );
+                retargeted.push((stack.name.clone(), old_base, new_base.clone()));
+            }
+        }
+
+        if !retargeted.is_empty() {
+            self.save_to_disk()?;
+        }
+
+        Ok(retargeted)
+    }
+
+    /// Set the stack setting `key` (one of `STACK_CONFIG_KEYS`) to `value`, or clear an
+    /// override with `None`.
+    ///
+    /// `base` changes the branch the stack is built on; the entries move onto it with the
+    /// next sync. It can't be cleared, and is managed by the parent for dependent stacks.
+    pub fn set_stack_config(
+        &mut self,
+        stack_id: &Uuid,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<()> {
+        let stack = self
+            .stacks
+            .get(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack with ID {stack_id} not found")))?;
+
+        if key == "base" {
+            let base = value.ok_or_else(|| {
+                CascadeError::config("The base branch can't be unset, only changed")
+            })?;
+            if stack.depends_on.is_some() {
+                return Err(CascadeError::validation(format!(
+                    "Stack '{}' depends on another stack, which sets its base. \
+                     Run 'ca stacks depend-on --clear' first.",
+                    stack.name
+                )));
+            }
+            if stack.entries.iter().any(|entry| entry.branch == base) {
+                return Err(CascadeError::validation(format!(
+                    "'{base}' is one of the stack's own branches"
+                )));
+            }
+            if !self.repo.branch_exists_or_fetch(base)? {
+                return Err(CascadeError::branch(format!(
+                    "Branch '{base}' doesn't exist locally or on the remote"
+                )));
+            }
+
+            if let Some(stack) = self.stacks.get_mut(stack_id) {
+                stack.base_branch = base.to_string();
+                stack.updated_at = Utc::now();
+            }
+            if let Some(meta) = self.metadata.get_stack_mut(stack_id) {
+                meta.base_branch = base.to_string();
+            }
+        } else if let Some(stack) = self.stacks.get_mut(stack_id) {
+            stack.overrides.set(key, value)?;
+            stack.updated_at = Utc::now();
+        }
+
+        self.save_to_disk()
+    }
+
+    /// Replace the labels of a stack, or of one of its entries when `entry_id` is given
+    pub fn set_labels(
+        &mut self,
+        stack_id: &Uuid,
+        entry_id: Option<&Uuid>,
+        labels: Vec<String>,
+    ) -> Result<()> {
+        let stack = self
+            .stacks
+            .get_mut(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+
+        match entry_id {
+            Some(entry_id) => {
+                if !stack.set_entry_labels(entry_id, labels) {
+                    return Err(CascadeError::config(format!(
+                        "Entry {entry_id} not found in stack {stack_id}"
+                    )));
+                }
+            }
+            None => {
+                stack.overrides.labels = (!labels.is_empty()).then_some(labels);
+                stack.updated_at = Utc::now();
+            }
+        }
+
+        self.save_to_disk()
+    }
+
+    /// Refresh a dependent stack's base from its parent before syncing it.
+    /// Returns true if the base branch changed.
+    pub fn refresh_dependency_base(&mut self, stack_id: &Uuid) -> Result<bool> {
+        let parent_id = match self.stacks.get(stack_id).and_then(|s| s.depends_on) {
+            Some(id) => id,
+            None => return Ok(false),
+        };
+        let old_base = self.stacks[stack_id].base_branch.clone();
+        self.retarget_dependent_stacks(&parent_id)?;
+        Ok(self.stacks[stack_id].base_branch != old_base)
+    }
+
+    /// Whether `stack_id`'s base is still an unmerged entry branch of the stack it depends
+    /// on. Once the parent has fully landed the base is the parent's trunk again, which
+    /// comes from the remote like any other base.
+    pub fn base_is_parent_branch(&self, stack_id: &Uuid) -> bool {
+        let Some(stack) = self.stacks.get(stack_id) else {
+            return false;
+        };
+        stack
+            .depends_on
+            .and_then(|parent_id| self.stacks.get(&parent_id))
+            .is_some_and(|parent| {
+                parent
+                    .entries
+                    .iter()
+                    .any(|entry| !entry.is_merged && entry.branch == stack.base_branch)
+            })
+    }
+
+    /// Move the entry at `index` (0-based) of `source_id` to the top of `target_id`.
+    ///
+    /// The moved commit is replayed onto the target stack's top branch and the source
+    /// stack's later entries are restacked over the gap. Every new commit is computed
+    /// before any branch is touched, so a conflict leaves the repository unchanged.
+    /// Returns the source branches that were restacked.
+    pub fn move_entry(
+        &mut self,
+        source_id: &Uuid,
+        index: usize,
+        target_id: &Uuid,
+    ) -> Result<Vec<String>> {
+        if source_id == target_id {
+            return Err(CascadeError::validation(
+                "Source and target stacks are the same",
+            ));
+        }
+
+        let source = self
+            .stacks
+            .get(source_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {source_id} not found")))?
+            .clone();
+        let target = self
+            .stacks
+            .get(target_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {target_id} not found")))?
+            .clone();
+
+        let entry = source.entries.get(index).cloned().ok_or_else(|| {
+            CascadeError::validation(format!(
+                "Entry {} does not exist (stack has {} entries)",
+                index + 1,
+                source.entries.len()
+            ))
+        })?;
+        if entry.is_merged {
+            return Err(CascadeError::validation(format!(
+                "Entry {} is already merged and cannot be moved",
+                index + 1
+            )));
+        }
+
+        let onto = target.dependency_base_branch();
+        if onto == entry.branch {
+            return Err(CascadeError::validation(format!(
+                "Stack '{}' is built on '{}'; move it off that branch first",
+                target.name, entry.branch
+            )));
+        }
+
+        let mut rewritten = vec![entry.branch.as_str()];
+        rewritten.extend(
+            source.entries[index + 1..]
+                .iter()
+                .map(|e| e.branch.as_str()),
+        );
+        rewritten.extend(source.working_branch.as_deref());
+        rewritten.extend(target.working_branch.as_deref());
+        let touches_current = self.check_rewrite_safety(&rewritten)?;
+
+        // Compute all new commits up front
+        let moved_commit = self.repo.cherry_pick_chain_onto(
+            &entry.commit_hash,
+            entry.commit_count,
+            &onto,
+            None,
+        )?;
+
+        let mut restacked = Vec::new();
+        let mut parent = match index {
+            0 => source.base_branch.clone(),
+            _ => source.entries[index - 1].commit_hash.clone(),
+        };
+        for later in &source.entries[index + 1..] {
+            if later.is_merged {
+                continue;
+            }
+            let new_commit = self.repo.cherry_pick_chain_onto(
+                &later.commit_hash,
+                later.commit_count,
+                &parent,
+                None,
+            )?;
+            parent = new_commit.clone();
+            restacked.push((later.id, later.branch.clone(), new_commit));
+        }
+
+        // Apply: move branch refs
+        self.repo
+            .update_branch_to_commit(&entry.branch, &moved_commit)?;
+        for (_, branch, new_commit) in &restacked {
+            self.repo.update_branch_to_commit(branch, new_commit)?;
+        }
+
+        // Keep working branches pointing at their stack's new top
+        if let Some(working) = &target.working_branch {
+            if self.repo.branch_exists(working) {
+                self.repo.update_branch_to_commit(working, &moved_commit)?;
+            }
+        }
+        if let Some(working) = &source.working_branch {
+            if self.repo.branch_exists(working) {
+                let source_top = match restacked.last() {
+                    Some((_, _, commit)) => commit.clone(),
+                    None => self.repo.resolve_reference(&parent)?.id().to_string(),
+                };
+                self.repo.update_branch_to_commit(working, &source_top)?;
+            }
+        }
+
+        if touches_current {
+            self.repo.reset_to_head()?;
+        }
+
+        // Apply: stack bookkeeping
+        if let Some(stack) = self.stacks.get_mut(source_id) {
+            for (entry_id, _, new_commit) in &restacked {
+                stack
+                    .update_entry_commit_hash(entry_id, new_commit.clone())
+                    .map_err(CascadeError::config)?;
+            }
+        }
+
+        self.transfer_entry(source_id, &entry.id, target_id, moved_commit)?;
+
+        self.save_to_disk()?;
+
+        Ok(restacked.into_iter().map(|(_, branch, _)| branch).collect())
+    }
+
+    /// Check that `branches` can be rewritten in place. Returns true when one of them is
+    /// checked out, in which case the caller must reset the working tree afterwards.
+    pub fn check_rewrite_safety(&self, branches: &[&str]) -> Result<bool> {
+        let touches_current = self.touches_current_branch(branches);
+        if touches_current && self.repo.is_dirty()? {
+            return Err(CascadeError::branch(
+                "Working directory has uncommitted changes. Commit or stash them first.",
+            ));
+        }
+        Ok(touches_current)
+    }
+
+    /// Whether the checked-out branch is one of `branches`
+    pub fn touches_current_branch(&self, branches: &[&str]) -> bool {
+        match self.repo.get_current_branch() {
+            Ok(current) => branches.contains(&current.as_str()),
+            Err(_) => false,
+        }
+    }
+
+    /// Hand an entry over to the top of another stack, pointing it at `new_commit`.
+    /// Bookkeeping only: branch refs are the caller's job and nothing is saved.
+    pub fn transfer_entry(
+        &mut self,
+        source_id: &Uuid,
+        entry_id: &Uuid,
+        target_id: &Uuid,
+        new_commit: String,
+    ) -> Result<()> {
+        if !self.stacks.contains_key(target_id) {
+            return Err(CascadeError::config(format!("Stack {target_id} not found")));
+        }
+
+        let source = self
+            .stacks
+            .get_mut(source_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {source_id} not found")))?;
+        let index = source
+            .entries
+            .iter()
+            .position(|e| e.id == *entry_id)
+            .ok_or_else(|| CascadeError::config(format!("Entry {entry_id} not found")))?;
+        let entry = source
+            .remove_entry_at(index)
+            .ok_or_else(|| CascadeError::config(format!("Entry {entry_id} not found")))?;
+
+        let mut moved = entry.clone();
+        moved.commit_hash = new_commit.clone();
+        if let Some(target) = self.stacks.get_mut(target_id) {
+            target.adopt_entry(moved);
+        }
+
+        self.metadata.remove_commit(&entry.commit_hash);
+        if let Some(stack_meta) = self.metadata.get_stack_mut(source_id) {
+            stack_meta.remove_commit(&entry.commit_hash);
+            stack_meta.remove_branch(&entry.branch);
+        }
+
+        let mut commit_metadata = CommitMetadata::new(
+            new_commit.clone(),
+            entry.message.clone(),
+            entry.id,
+            *target_id,
+            entry.branch.clone(),
+            entry.branch.clone(),
+        );
+        if let Some(pr_id) = &entry.pull_request_id {
+            commit_metadata.mark_submitted(pr_id.clone());
+        }
+        self.metadata.add_commit(commit_metadata);
+        if let Some(stack_meta) = self.metadata.get_stack_mut(target_id) {
+            stack_meta.add_branch(entry.branch.clone());
+            stack_meta.add_commit(new_commit);
+        }
+
+        self.refresh_stack_stats(source_id);
+        self.refresh_stack_stats(target_id);
+        Ok(())
+    }
+
+    /// Rebuild a stack's entry chain in `order`. Entries not listed are removed along with
+    /// their commit and branch metadata. Nothing is saved. Returns the removed entries.
+    pub fn reorder_stack_entries(
+        &mut self,
+        stack_id: &Uuid,
+        order: &[Uuid],
+    ) -> Result<Vec<StackEntry>> {
+        let stack = self
+            .stacks
+            .get_mut(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+        let removed = stack.set_entry_order(order);
+
+        for entry in &removed {
+            self.metadata.remove_commit(&entry.commit_hash);
+            if let Some(stack_meta) = self.metadata.get_stack_mut(stack_id) {
+                stack_meta.remove_commit(&entry.commit_hash);
+                stack_meta.remove_branch(&entry.branch);
+            }
+        }
+
+        self.refresh_stack_stats(stack_id);
+        Ok(removed)
+    }
+
+    /// Recompute the entry counters kept in a stack's metadata
+    fn refresh_stack_stats(&mut self, stack_id: &Uuid) {
+        if let (Some(stack), Some(stack_meta)) = (
+            self.stacks.get(stack_id),
+            self.metadata.get_stack_mut(stack_id),
+        ) {
+            let submitted = stack.entries.iter().filter(|e| e.is_submitted).count();
+            let merged = stack.entries.iter().filter(|e| e.is_merged).count();
+            stack_meta.update_stats(stack.entries.len(), submitted, merged);
+        }
+    }
+
     /// Push a commit to a stack
     pub fn push_to_stack(
         &mut self,
@@ -293,9 +798,9 @@ impl StackManager {
         message: String,
         source_branch: String,
     ) -> Result<Uuid> {
-        let stack_id = self.get_active_stack_id().ok_or_else(|| {
-            CascadeError::config("No active stack (current branch doesn't belong to any stack)")
-        })?;
+        let stack_id = self
+            .get_active_stack_id()
+            .ok_or_else(CascadeError::no_active_stack)?;
 
         // 🆕 RECONCILE METADATA: Sync entry commit hashes with current branch HEADs before validation
         // This prevents false "branch modification" errors from stale metadata (e.g., after ca sync)
@@ -437,10 +942,10 @@ impl StackManager {
                         stack_meta.set_current_branch(Some(current_branch.clone()));
                     }
 
-                    println!(
-                        "   ✅ Stack '{}' base branch updated to '{current_branch}'",
+                    Output::success(format!(
+                        "Stack '{}' base branch updated to '{current_branch}'",
                         stack.name
-                    );
+                    ));
                 }
             }
         }
@@ -504,9 +1009,9 @@ impl StackManager {
 
     /// Pop the top commit from the active stack
     pub fn pop_from_stack(&mut self) -> Result<StackEntry> {
-        let stack_id = self.get_active_stack_id().ok_or_else(|| {
-            CascadeError::config("No active stack (current branch doesn't belong to any stack)")
-        })?;
+        let stack_id = self
+            .get_active_stack_id()
+            .ok_or_else(CascadeError::no_active_stack)?;
 
         let stack = self
             .stacks
@@ -531,6 +1036,72 @@ impl StackManager {
         Ok(entry)
     }
 
+    /// Pop entries off the active stack until `keep` remain. Returns them lowest first
+    pub fn pop_to(&mut self, keep: usize) -> Result<Vec<StackEntry>> {
+        let len = self
+            .get_active_stack()
+            .map(|stack| stack.entries.len())
+            .ok_or_else(CascadeError::no_active_stack)?;
+        if keep >= len {
+            return Err(CascadeError::config(format!(
+                "The stack has {len} entries, so there's nothing to pop down to {keep}"
+            )));
+        }
+
+        let mut popped = Vec::with_capacity(len - keep);
+        for _ in keep..len {
+            popped.push(self.pop_from_stack()?);
+        }
+        popped.reverse();
+        Ok(popped)
+    }
+
+    /// Put popped entries back on top of a stack, keeping their IDs, branches and PR links.
+    /// `commits` gives each entry's commit, which changes when they were replayed
+    pub fn restore_entries(
+        &mut self,
+        stack_id: &Uuid,
+        entries: Vec<StackEntry>,
+        commits: &[String],
+    ) -> Result<()> {
+        let stack = self
+            .stacks
+            .get_mut(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+
+        let source_branch = stack.working_branch.clone();
+        let mut restored = Vec::with_capacity(entries.len());
+        for (mut entry, commit) in entries.into_iter().zip(commits) {
+            entry.commit_hash = commit.clone();
+            stack.adopt_entry(entry.clone());
+            restored.push(entry);
+        }
+
+        for entry in restored {
+            let mut commit_metadata = CommitMetadata::new(
+                entry.commit_hash.clone(),
+                entry.message.clone(),
+                entry.id,
+                *stack_id,
+                entry.branch.clone(),
+                source_branch
+                    .clone()
+                    .unwrap_or_else(|| entry.branch.clone()),
+            );
+            if let Some(pr_id) = &entry.pull_request_id {
+                commit_metadata.mark_submitted(pr_id.clone());
+            }
+            self.metadata.add_commit(commit_metadata);
+            if let Some(stack_meta) = self.metadata.get_stack_mut(stack_id) {
+                stack_meta.add_branch(entry.branch.clone());
+                stack_meta.add_commit(entry.commit_hash);
+            }
+        }
+
+        self.refresh_stack_stats(stack_id);
+        self.save_to_disk()
+    }
+
     /// Submit a stack entry for review (mark as submitted)
     pub fn submit_entry(
         &mut self,
@@ -711,6 +1282,66 @@ impl StackManager {
         Ok(())
     }
 
+    /// Detach an entry from its (declined) pull request so `ca submit` opens a new one
+    pub fn unsubmit_entry(&mut self, stack_id: &Uuid, entry_id: &Uuid) -> Result<()> {
+        let stack = self
+            .stacks
+            .get_mut(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+
+        let commit_hash = stack
+            .get_entry(entry_id)
+            .map(|e| e.commit_hash.clone())
+            .ok_or_else(|| CascadeError::config(format!("Entry {entry_id} not found")))?;
+        stack.mark_entry_unsubmitted(entry_id);
+
+        if let Some(commit_meta) = self.metadata.commits.get_mut(&commit_hash) {
+            commit_meta.mark_unsubmitted();
+        }
+        if let Some(stack_meta) = self.metadata.get_stack_mut(stack_id) {
+            let submitted_count = stack.entries.iter().filter(|e| e.is_submitted).count();
+            let merged_count = stack.entries.iter().filter(|e| e.is_merged).count();
+            stack_meta.update_stats(stack.entries.len(), submitted_count, merged_count);
+        }
+
+        self.save_to_disk()
+    }
+
+    /// Record a stack's status (e.g. `NeedsSync` after entries below others were merged)
+    pub fn set_stack_status(&mut self, stack_id: &Uuid, status: StackStatus) -> Result<()> {
+        let stack = self
+            .stacks
+            .get_mut(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+        if stack.status != status {
+            stack.update_status(status);
+            self.save_to_disk()?;
+        }
+        Ok(())
+    }
+
+    /// Remember the commit an entry's branch was pushed at, so later force pushes can
+    /// use it as their lease
+    pub fn set_entry_remote_commit(
+        &mut self,
+        stack_id: &Uuid,
+        entry_id: &Uuid,
+        commit_hash: String,
+    ) -> Result<()> {
+        let stack = self
+            .stacks
+            .get_mut(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+
+        if !stack.set_entry_remote_commit(entry_id, commit_hash) {
+            return Err(CascadeError::config(format!(
+                "Entry {entry_id} not found in stack {stack_id}"
+            )));
+        }
+
+        self.save_to_disk()
+    }
+
     /// Repair data consistency issues in all stacks
     pub fn repair_all_stacks(&mut self) -> Result<()> {
         for stack in self.stacks.values_mut() {
@@ -745,6 +1376,109 @@ impl StackManager {
         &self.repo_path
     }
 
+    /// Snapshot the stack metadata and the commit of every stack branch before `operation`
+    pub fn take_snapshot(&self, operation: &str) -> Result<Snapshot> {
+        let mut branches = BTreeMap::new();
+        for stack in self.stacks.values() {
+            let stack_branches = stack
+                .entries
+                .iter()
+                .map(|entry| &entry.branch)
+                .chain(stack.working_branch.as_ref());
+            for branch in stack_branches {
+                // Restoring must never rewind a base branch
+                if *branch == stack.base_branch || self.repo.is_protected_branch(branch) {
+                    continue;
+                }
+                if let Ok(head) = self.repo.get_branch_head(branch) {
+                    branches.insert(branch.clone(), head);
+                }
+            }
+        }
+
+        let manifest = snapshots::SnapshotManifest {
+            operation: operation.to_string(),
+            created_at: Utc::now(),
+            current_branch: self.repo.get_current_branch().ok(),
+            branches,
+        };
+        let serialize = |value: serde_json::Result<Vec<u8>>| {
+            value.map_err(|e| CascadeError::config(format!("Failed to serialize stacks: {e}")))
+        };
+        let files = [
+            (
+                "stacks.json",
+                serialize(serde_json::to_vec_pretty(&self.stacks))?,
+            ),
+            (
+                "metadata.json",
+                serialize(serde_json::to_vec_pretty(&self.metadata))?,
+            ),
+        ];
+        snapshots::write_snapshot(&self.config_dir, manifest, &files)
+    }
+
+    /// [`Self::take_snapshot`] for operations that go ahead without one: a failure is
+    /// reported, not returned
+    pub fn snapshot_before(&self, operation: &str) -> Option<Snapshot> {
+        match self.take_snapshot(operation) {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                Output::warning(format!(
+                    "Could not snapshot the stacks before {operation}: {e}"
+                ));
+                None
+            }
+        }
+    }
+
+    /// Put the stack metadata and stack branches back the way `snapshot` recorded them.
+    /// The current state is snapshotted first, so the restore can be undone the same way.
+    pub fn restore_snapshot(&mut self, snapshot: &Snapshot) -> Result<SnapshotRestore> {
+        if self.repo.is_dirty()? {
+            return Err(CascadeError::branch(
+                "Cannot restore a snapshot with uncommitted changes. Commit or stash them first.",
+            ));
+        }
+        let files = snapshots::snapshot_files(snapshot)?;
+        let previous = self.take_snapshot("restore")?;
+
+        let current_branch = self.repo.get_current_branch().ok();
+        let mut restored = Vec::new();
+        let mut missing = Vec::new();
+        for (branch, commit) in &snapshot.manifest.branches {
+            if self.repo.get_branch_head(branch).ok().as_ref() == Some(commit) {
+                continue;
+            }
+            // The commits of a rewritten branch stay around for as long as reflogs keep them
+            if !self.repo.commit_exists(commit)? {
+                missing.push(branch.clone());
+                continue;
+            }
+            self.repo.update_branch_to_commit(branch, commit)?;
+            restored.push(branch.clone());
+        }
+        if current_branch.is_some_and(|current| restored.contains(&current)) {
+            self.repo.reset_to_head()?;
+        }
+
+        for (name, file) in [
+            ("stacks.json", &self.stacks_file),
+            ("metadata.json", &self.metadata_file),
+        ] {
+            if let Some(contents) = files.get(name) {
+                crate::utils::atomic_file::write_bytes(file, contents)?;
+            }
+        }
+        self.load_from_disk()?;
+
+        Ok(SnapshotRestore {
+            restored,
+            missing,
+            previous,
+        })
+    }
+
     // Edit mode management methods
 
     /// Check if currently in edit mode
@@ -1078,7 +1812,6 @@ impl StackManager {
         }
 
         // Show detected modifications
-        println!();
         Output::section(format!("Branch modifications detected in '{}'", stack.name));
         for (i, modification) in modifications.iter().enumerate() {
             match modification {
@@ -1093,17 +1826,15 @@ impl StackManager {
                     extra_commit_messages,
                     ..
                 } => {
-                    println!(
-                        "   {}. Branch '{}' has {} extra commit(s)",
+                    Output::numbered_item(
                         i + 1,
-                        branch,
-                        extra_commit_count
+                        format!("Branch '{branch}' has {extra_commit_count} extra commit(s)"),
                     );
-                    println!(
-                        "      Expected: {} | Actual: {}",
+                    Output::sub_item(format!(
+                        "Expected: {} | Actual: {}",
                         &expected_commit[..8],
                         &actual_commit[..8]
-                    );
+                    ));
 
                     // Show extra commit messages (first few only)
                     for (j, message) in extra_commit_messages.iter().enumerate() {
@@ -1193,24 +1924,21 @@ impl StackManager {
                 extra_commit_count,
                 ..
             } => {
-                println!();
+                Output::spacing();
                 Output::info(format!(
                     "Branch '{}' has {} extra commit(s)",
                     branch, extra_commit_count
                 ));
-                let options = vec![
+                let options = [
                     "Incorporate - Update stack entry to include extra commits",
                     "Split - Create new stack entry for extra commits",
                     "Reset - Remove extra commits (DESTRUCTIVE)",
                     "Skip - Leave as-is for now",
-                ];
+                ]
+                .map(String::from);
 
-                let choice = Select::with_theme(&ColorfulTheme::default())
-                    .with_prompt("Choose how to handle extra commits")
-                    .default(0)
-                    .items(&options)
-                    .interact()
-                    .map_err(|e| CascadeError::config(format!("Failed to get user choice: {e}")))?;
+                let choice =
+                    prompter().select("Choose how to handle extra commits", &options, 0)?;
 
                 match choice {
                     0 => {
@@ -1222,7 +1950,11 @@ impl StackManager {
                         Ok(false) // Not skipped
                     }
                     2 => {
+                        let snapshot = self.snapshot_before("reset");
                         self.reset_branch_destructive(branch, expected_commit)?;
+                        if let Some(snapshot) = snapshot {
+                            Output::tip(format!("Undo with: ca snapshots restore {}", snapshot.id));
+                        }
                         Ok(false) // Not skipped
                     }
                     3 => {
@@ -1246,6 +1978,14 @@ impl StackManager {
         mode: &str,
     ) -> Result<()> {
         Output::info(format!("🤖 Applying automatic fix mode: {mode}"));
+        let resets = modifications
+            .iter()
+            .any(|m| matches!(m, BranchModification::ExtraCommits { .. }));
+        let snapshot = if mode == "reset" && resets {
+            self.snapshot_before("reset")
+        } else {
+            None
+        };
 
         for modification in modifications {
             match (modification, mode) {
@@ -1300,6 +2040,9 @@ impl StackManager {
 
         self.save_to_disk()?;
         Output::success(format!("Auto-fix completed for mode: {mode}"));
+        if let Some(snapshot) = snapshot {
+            Output::tip(format!("Undo with: ca snapshots restore {}", snapshot.id));
+        }
         Ok(())
     }
 
@@ -1335,6 +2078,11 @@ impl StackManager {
             stack
                 .update_entry_commit_hash(&entry_id, new_head.clone())
                 .map_err(CascadeError::config)?;
+            // The entry now spans its old commits plus the extra ones
+            let commit_count = stack
+                .get_entry(&entry_id)
+                .map_or(1, |e| e.commit_count + extra_commits.len());
+            stack.set_entry_commit_count(&entry_id, commit_count);
 
             Output::success(format!(
                 "Incorporated {} commit(s) into entry '{}'",
@@ -1406,6 +2154,11 @@ impl StackManager {
             pull_request_id: None,
             is_synced: false,
             is_merged: false,
+            remote_commit_hash: None,
+            rewrites: Vec::new(),
+            picked_from: None,
+            commit_count: extra_commits.len().max(1),
+            labels: Vec::new(),
         };
 
         // Insert the new entry after the current one
@@ -1415,12 +2168,14 @@ impl StackManager {
         self.repo
             .reset_branch_to_commit(branch, &original_commit_hash)?;
 
-        println!(
-            "   ✅ Split {} commit(s) into new entry '{}'",
+        Output::success(format!(
+            "Split {} commit(s) into new entry '{}'",
             extra_commits.len(),
             new_branch
-        );
-        println!("      Original branch '{branch}' reset to expected commit");
+        ));
+        Output::sub_item(format!(
+            "Original branch '{branch}' reset to expected commit"
+        ));
 
         Ok(())
     }
@@ -1605,6 +2360,70 @@ mod tests {
         assert_eq!(manager.get_active_stack_id(), Some(stack1_id));
     }
 
+    #[test]
+    fn test_pop_to_and_restore_entries() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        Command::new("git")
+            .args(["checkout", "-b", "feature/pop-work"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("pop-stack".to_string(), None, None)
+            .unwrap();
+
+        for n in 1..=3 {
+            std::fs::write(repo_path.join(format!("file{n}.txt")), "content").unwrap();
+            Command::new("git")
+                .args(["add", "."])
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            Command::new("git")
+                .args(["commit", "-m", &format!("Change {n}")])
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            let hash = Command::new("git")
+                .args(["rev-parse", "HEAD"])
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            manager
+                .push_to_stack(
+                    format!("feature/change-{n}"),
+                    String::from_utf8_lossy(&hash.stdout).trim().to_string(),
+                    format!("Change {n}"),
+                    "feature/pop-work".to_string(),
+                )
+                .unwrap();
+        }
+        let before: Vec<Uuid> = manager
+            .get_stack(&stack_id)
+            .unwrap()
+            .entries
+            .iter()
+            .map(|e| e.id)
+            .collect();
+
+        assert!(manager.pop_to(3).is_err());
+        let popped = manager.pop_to(1).unwrap();
+        assert_eq!(popped.len(), 2);
+        assert_eq!(popped[0].message, "Change 2");
+        assert_eq!(manager.get_stack(&stack_id).unwrap().entries.len(), 1);
+
+        let commits: Vec<String> = popped.iter().map(|e| e.commit_hash.clone()).collect();
+        manager
+            .restore_entries(&stack_id, popped, &commits)
+            .unwrap();
+        let manager = StackManager::new(&repo_path).unwrap();
+        let stack = manager.get_stack(&stack_id).unwrap();
+        let after: Vec<Uuid> = stack.entries.iter().map(|e| e.id).collect();
+        assert_eq!(after, before);
+        assert_eq!(stack.entries[2].parent_id, Some(stack.entries[1].id));
+    }
+
     #[test]
     fn test_delete_stack() {
         let (_temp_dir, repo_path) = create_test_repo();
@@ -1621,6 +2440,207 @@ mod tests {
         assert!(manager.get_active_stack().is_none());
     }
 
+    #[test]
+    fn test_stack_dependency() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let mut manager = StackManager::new(&repo_path).unwrap();
+
+        let parent_id = manager
+            .create_stack("parent".to_string(), None, None)
+            .unwrap();
+        let child_id = manager
+            .create_stack("child".to_string(), None, None)
+            .unwrap();
+
+        // Empty parent: dependent stack builds on the parent's base
+        let base = manager
+            .set_stack_dependency(&child_id, Some(parent_id))
+            .unwrap();
+        assert_eq!(base, manager.get_stack(&parent_id).unwrap().base_branch);
+
+        // Cycles are rejected
+        assert!(manager
+            .set_stack_dependency(&parent_id, Some(child_id))
+            .is_err());
+        assert!(manager
+            .set_stack_dependency(&child_id, Some(child_id))
+            .is_err());
+
+        // Parent grows: retargeting moves the child onto the new top branch
+        manager.stacks.get_mut(&parent_id).unwrap().push_entry(
+            "parent-1".to_string(),
+            "hash1".to_string(),
+            "msg1".to_string(),
+        );
+        let retargeted = manager.retarget_dependent_stacks(&parent_id).unwrap();
+        assert_eq!(retargeted.len(), 1);
+        assert_eq!(
+            manager.get_stack(&child_id).unwrap().base_branch,
+            "parent-1"
+        );
+
+        // Deleting the parent hands its base down to the child
+        let parent_base = manager.get_stack(&parent_id).unwrap().base_branch.clone();
+        manager.delete_stack(&parent_id).unwrap();
+        let child = manager.get_stack(&child_id).unwrap();
+        assert_eq!(child.depends_on, None);
+        assert_eq!(child.base_branch, parent_base);
+    }
+
+    #[test]
+    fn test_move_entry_between_stacks() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        // Three stacked commits on their own branches
+        let base = git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+        let mut hashes = Vec::new();
+        for name in ["a", "b", "c"] {
+            git(&["checkout", "-b", &format!("source-{name}")]);
+            std::fs::write(repo_path.join(format!("{name}.txt")), name).unwrap();
+            git(&["add", "."]);
+            git(&["commit", "-m", &format!("Add {name}")]);
+            hashes.push(git(&["rev-parse", "HEAD"]));
+        }
+        git(&["checkout", &base]);
+
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let source_id = manager
+            .create_stack("source".to_string(), Some(base.clone()), None)
+            .unwrap();
+        let target_id = manager
+            .create_stack("target".to_string(), Some(base.clone()), None)
+            .unwrap();
+        for (name, hash) in ["a", "b", "c"].iter().zip(&hashes) {
+            manager.stacks.get_mut(&source_id).unwrap().push_entry(
+                format!("source-{name}"),
+                hash.clone(),
+                format!("Add {name}"),
+            );
+        }
+
+        let restacked = manager.move_entry(&source_id, 1, &target_id).unwrap();
+        assert_eq!(restacked, vec!["source-c".to_string()]);
+
+        // The moved entry sits directly on the target's base
+        let target = manager.get_stack(&target_id).unwrap();
+        assert_eq!(target.entries.len(), 1);
+        assert_eq!(target.entries[0].branch, "source-b");
+        assert_eq!(git(&["rev-parse", "source-b^"]), git(&["rev-parse", &base]));
+
+        // The remaining source entries close the gap
+        let source = manager.get_stack(&source_id).unwrap();
+        let branches: Vec<_> = source.entries.iter().map(|e| e.branch.as_str()).collect();
+        assert_eq!(branches, vec!["source-a", "source-c"]);
+        assert_eq!(source.entries[1].parent_id, Some(source.entries[0].id));
+        assert_eq!(git(&["rev-parse", "source-c^"]), hashes[0]);
+        assert_eq!(
+            source.entries[1].commit_hash,
+            git(&["rev-parse", "source-c"])
+        );
+        assert!(git(&["ls-tree", "--name-only", "source-c"]).contains("c.txt"));
+        assert!(!git(&["ls-tree", "--name-only", "source-c"]).contains("b.txt"));
+
+        // Moving onto the same stack is rejected
+        assert!(manager.move_entry(&source_id, 0, &source_id).is_err());
+    }
+
+    #[test]
+    fn test_unsubmit_entry_persists() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let head = String::from_utf8_lossy(
+            &Command::new("git")
+                .args(["rev-parse", "HEAD"])
+                .current_dir(&repo_path)
+                .output()
+                .unwrap()
+                .stdout,
+        )
+        .trim()
+        .to_string();
+
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("declined".to_string(), None, None)
+            .unwrap();
+        let entry_id = manager.stacks.get_mut(&stack_id).unwrap().push_entry(
+            "declined-1".to_string(),
+            head,
+            "Declined change".to_string(),
+        );
+        manager
+            .submit_entry(&stack_id, &entry_id, "7".to_string())
+            .unwrap();
+
+        manager.unsubmit_entry(&stack_id, &entry_id).unwrap();
+        manager
+            .set_stack_status(&stack_id, StackStatus::NeedsSync)
+            .unwrap();
+
+        let manager = StackManager::new(&repo_path).unwrap();
+        let stack = manager.get_stack(&stack_id).unwrap();
+        let entry = stack.get_entry(&entry_id).unwrap();
+        assert!(!entry.is_submitted);
+        assert_eq!(entry.pull_request_id, None);
+        assert_eq!(stack.status, StackStatus::NeedsSync);
+    }
+
+    #[test]
+    fn test_restore_snapshot() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let head = git(&["rev-parse", "HEAD"]);
+
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("login".to_string(), None, None)
+            .unwrap();
+        git(&["branch", "part-one"]);
+        let entry_id = manager.stacks.get_mut(&stack_id).unwrap().push_entry(
+            "part-one".to_string(),
+            head.clone(),
+            "Add part one".to_string(),
+        );
+        manager.save_to_disk().unwrap();
+        let snapshot = manager.take_snapshot("rebase").unwrap();
+        assert_eq!(snapshot.manifest.branches.get("part-one"), Some(&head));
+
+        // Rewrite the branch and the metadata after the snapshot
+        git(&["commit", "--allow-empty", "-m", "Rewritten"]);
+        let rewritten = git(&["rev-parse", "HEAD"]);
+        git(&["branch", "-f", "part-one", &rewritten]);
+        manager
+            .submit_entry(&stack_id, &entry_id, "7".to_string())
+            .unwrap();
+
+        let result = manager.restore_snapshot(&snapshot).unwrap();
+        assert_eq!(result.restored, vec!["part-one".to_string()]);
+        assert!(result.missing.is_empty());
+        assert_eq!(git(&["rev-parse", "part-one"]), head);
+        let entry = manager.get_stack(&stack_id).unwrap().get_entry(&entry_id);
+        assert_eq!(entry.unwrap().pull_request_id, None);
+
+        // The state the restore replaced is a snapshot of its own
+        assert_eq!(
+            result.previous.manifest.branches.get("part-one"),
+            Some(&rewritten)
+        );
+    }
+
     #[test]
     fn test_validation() {
         let (_temp_dir, repo_path) = create_test_repo();
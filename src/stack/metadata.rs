@@ -130,6 +130,13 @@ impl CommitMetadata {
         self.updated_at = Utc::now();
     }
 
+    /// Forget the pull request, e.g. after it was declined
+    pub fn mark_unsubmitted(&mut self) {
+        self.is_submitted = false;
+        self.pull_request_id = None;
+        self.updated_at = Utc::now();
+    }
+
     /// Mark commit as merged
     pub fn mark_merged(&mut self, merged: bool) {
         self.is_merged = merged;
@@ -6,19 +6,46 @@
 //! - Branch relationship management
 //! - Commit tracking and dependencies
 
+pub mod auto_stash;
+pub mod changed_files;
 pub mod cleanup;
+pub mod commit_selector;
+pub mod gc;
+pub mod lifecycle;
 pub mod manager;
 pub mod metadata;
+pub mod operation;
+pub mod ownership;
+pub mod pick_state;
+pub mod plan;
+pub mod popped;
+pub mod pr_summary;
 pub mod rebase;
+pub mod snapshots;
 #[allow(clippy::module_inception)]
 pub mod stack;
 pub mod sync_state;
 
+pub use auto_stash::AutoStash;
+pub use changed_files::{ChangedFilesIndex, FileOverlap};
 pub use cleanup::{
     CleanupCandidate, CleanupManager, CleanupOptions, CleanupReason, CleanupResult, CleanupStats,
 };
-pub use manager::StackManager;
+pub use commit_selector::CommitSelector;
+pub use lifecycle::LifecycleHook;
+pub use manager::{SnapshotRestore, StackManager};
 pub use metadata::{CommitMetadata, EditModeState, StackMetadata};
-pub use rebase::{RebaseManager, RebaseOptions, RebaseResult, RebaseStrategy};
-pub use stack::{Stack, StackEntry, StackStatus};
+pub use operation::OperationState;
+pub use pick_state::PickState;
+pub use plan::{PlannedAction, PlannedCommit, PlannedStep, RebasePlan};
+pub use popped::{ClosedPrs, PoppedEntries};
+pub use pr_summary::{PrSummary, PrSummaryCache};
+pub use rebase::{
+    EditAction, EditStep, RebaseManager, RebaseOptions, RebaseResult, RebaseStrategy,
+};
+pub use snapshots::Snapshot;
+pub use stack::{
+    validate_label, CommitRewrite, Stack, StackEntry, StackOverrides, StackStatus, SupersededEntry,
+    STACK_CONFIG_KEYS,
+};
 pub use sync_state::SyncState;
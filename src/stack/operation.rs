@@ -0,0 +1,149 @@
+//! Sentinel for the Cascade operation rewriting the repository
+//!
+//! Sync, rebase, land, autoland and pop record themselves in `.git/CASCADE_OPERATION`
+//! before they start and remove the record when they return, whatever the outcome. A
+//! record left behind means the process died part way through (a crash, a kill, a closed
+//! terminal); the next command points at it and `ca recover` resumes or aborts it.
+
+use super::snapshots::{list_snapshots, Snapshot};
+use crate::errors::{CascadeError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const STATE_FILE: &str = "CASCADE_OPERATION";
+
+/// An operation that was running, or is still running
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OperationState {
+    /// Operation name (`sync`, `rebase`, `land`, `autoland`, `pop`)
+    pub operation: String,
+    /// Arguments the command ran with, replayed by `ca recover --resume`
+    pub args: Vec<String>,
+    /// Branch checked out when the operation started
+    pub original_branch: Option<String>,
+    /// Process that ran the operation
+    pub pid: u32,
+    pub started_at: DateTime<Utc>,
+}
+
+impl OperationState {
+    fn state_path(repo_root: &Path) -> Result<PathBuf> {
+        Ok(crate::git::resolve_git_dir(repo_root)?.join(STATE_FILE))
+    }
+
+    /// Record that `operation` is starting in this process
+    pub fn begin(
+        repo_root: &Path,
+        operation: &str,
+        args: Vec<String>,
+        original_branch: Option<String>,
+    ) -> Result<Self> {
+        let state = Self {
+            operation: operation.to_string(),
+            args,
+            original_branch,
+            pid: std::process::id(),
+            started_at: Utc::now(),
+        };
+        crate::utils::atomic_file::write_json(&Self::state_path(repo_root)?, &state)?;
+        tracing::debug!("Recorded start of {}", operation);
+        Ok(state)
+    }
+
+    /// The operation that didn't finish, if there is one
+    pub fn load(repo_root: &Path) -> Result<Option<Self>> {
+        let state_path = Self::state_path(repo_root)?;
+        if !state_path.exists() {
+            return Ok(None);
+        }
+
+        let json = std::fs::read_to_string(&state_path)
+            .map_err(|e| CascadeError::config(format!("Failed to read operation state: {e}")))?;
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| CascadeError::config(format!("Failed to parse operation state: {e}")))
+    }
+
+    /// Delete the record once the operation has returned or been recovered
+    pub fn delete(repo_root: &Path) -> Result<()> {
+        let state_path = Self::state_path(repo_root)?;
+        if state_path.exists() {
+            std::fs::remove_file(&state_path).map_err(|e| {
+                CascadeError::config(format!("Failed to delete operation state: {e}"))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// The command line the operation ran with, e.g. `ca sync --cleanup`
+    pub fn command_line(&self) -> String {
+        std::iter::once("ca")
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The first snapshot taken after the operation started, holding the stacks as they
+    /// were before it changed anything
+    pub fn snapshot(&self, config_dir: &Path) -> Result<Option<Snapshot>> {
+        Ok(list_snapshots(config_dir)?
+            .into_iter()
+            .rev()
+            .find(|snapshot| snapshot.manifest.created_at >= self.started_at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stack::snapshots::{write_snapshot, SnapshotManifest};
+    use tempfile::TempDir;
+
+    fn init_repo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_begin_load_and_delete() {
+        let repo = init_repo();
+        assert!(OperationState::load(repo.path()).unwrap().is_none());
+
+        let args = vec!["sync".to_string(), "--cleanup".to_string()];
+        let state =
+            OperationState::begin(repo.path(), "sync", args, Some("feature".into())).unwrap();
+        assert_eq!(state.command_line(), "ca sync --cleanup");
+        assert_eq!(state.pid, std::process::id());
+        assert_eq!(OperationState::load(repo.path()).unwrap(), Some(state));
+
+        OperationState::delete(repo.path()).unwrap();
+        assert!(OperationState::load(repo.path()).unwrap().is_none());
+        OperationState::delete(repo.path()).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_taken_after_start() {
+        let repo = init_repo();
+        let config_dir = TempDir::new().unwrap();
+        let manifest = |operation: &str, created_at| SnapshotManifest {
+            operation: operation.to_string(),
+            created_at,
+            current_branch: None,
+            branches: Default::default(),
+        };
+
+        let state = OperationState::begin(repo.path(), "rebase", Vec::new(), None).unwrap();
+        let before = state.started_at - chrono::Duration::minutes(5);
+        write_snapshot(config_dir.path(), manifest("land", before), &[]).unwrap();
+        assert!(state.snapshot(config_dir.path()).unwrap().is_none());
+
+        let first = state.started_at + chrono::Duration::seconds(1);
+        let later = state.started_at + chrono::Duration::seconds(2);
+        write_snapshot(config_dir.path(), manifest("rebase", first), &[]).unwrap();
+        write_snapshot(config_dir.path(), manifest("restore", later), &[]).unwrap();
+        let snapshot = state.snapshot(config_dir.path()).unwrap().unwrap();
+        assert_eq!(snapshot.manifest.operation, "rebase");
+    }
+}
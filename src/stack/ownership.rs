@@ -0,0 +1,169 @@
+//! Regrouping a stack's commits along code ownership boundaries
+//!
+//! A pull request needs a review from the owners of every file it touches, so an entry
+//! mixing files of several teams waits on all of them. `ca stacks suggest-split` groups
+//! commits with the same owners into one entry. A commit only moves down past commits
+//! that touch none of its files, so the suggested order replays without new conflicts.
+
+use crate::git::CodeOwners;
+use std::collections::BTreeSet;
+
+/// A commit of the stack with the owners of the files it touches
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedCommit {
+    pub hash: String,
+    pub summary: String,
+    pub files: Vec<String>,
+    /// Owners of any of the files; empty when none of them has an owner
+    pub owners: BTreeSet<String>,
+}
+
+impl OwnedCommit {
+    pub fn new(hash: String, summary: String, files: Vec<String>, codeowners: &CodeOwners) -> Self {
+        let owners = files
+            .iter()
+            .flat_map(|file| codeowners.owners(file).iter().cloned())
+            .collect();
+        Self {
+            hash,
+            summary,
+            files,
+            owners,
+        }
+    }
+
+    fn shares_files(&self, other: &OwnedCommit) -> bool {
+        self.files.iter().any(|file| other.files.contains(file))
+    }
+}
+
+/// One entry of the suggested layout
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuggestedEntry {
+    pub owners: BTreeSet<String>,
+    /// Positions in the commit list, in the order they'd be replayed
+    pub commits: Vec<usize>,
+}
+
+/// Group `commits` (bottom of the stack first) into entries with one owner set each.
+/// Commits touching only unowned files stay with the entry before them.
+pub fn suggest_entries(commits: &[OwnedCommit]) -> Vec<SuggestedEntry> {
+    let mut entries: Vec<SuggestedEntry> = Vec::new();
+    for (index, commit) in commits.iter().enumerate() {
+        if commit.owners.is_empty() {
+            match entries.last_mut() {
+                Some(last) => last.commits.push(index),
+                None => entries.push(SuggestedEntry {
+                    owners: BTreeSet::new(),
+                    commits: vec![index],
+                }),
+            }
+            continue;
+        }
+
+        // The nearest entry with the same owners, unless that means jumping over a
+        // commit that touches the same files
+        let mut target = None;
+        for (position, entry) in entries.iter().enumerate().rev() {
+            if entry.owners == commit.owners {
+                target = Some(position);
+                break;
+            }
+            if entry
+                .commits
+                .iter()
+                .any(|&other| commits[other].shares_files(commit))
+            {
+                break;
+            }
+        }
+        match target {
+            Some(position) => entries[position].commits.push(index),
+            None => entries.push(SuggestedEntry {
+                owners: commit.owners.clone(),
+                commits: vec![index],
+            }),
+        }
+    }
+    entries
+}
+
+/// A commit's files by owner set, when they fall under more than one; splitting the
+/// commit along these lines would let each part go to its own reviewers
+pub fn owner_groups(files: &[String], codeowners: &CodeOwners) -> Vec<(Vec<String>, Vec<String>)> {
+    let mut groups: Vec<(Vec<String>, Vec<String>)> = Vec::new();
+    for file in files {
+        let owners = codeowners.owners(file).to_vec();
+        match groups.iter_mut().find(|(group, _)| *group == owners) {
+            Some((_, group_files)) => group_files.push(file.clone()),
+            None => groups.push((owners, vec![file.clone()])),
+        }
+    }
+    if groups.len() < 2 {
+        groups.clear();
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(codeowners: &CodeOwners, name: &str, files: &[&str]) -> OwnedCommit {
+        OwnedCommit::new(
+            name.to_string(),
+            name.to_string(),
+            files.iter().map(|f| f.to_string()).collect(),
+            codeowners,
+        )
+    }
+
+    #[test]
+    fn test_commits_group_by_owner_without_crossing_shared_files() {
+        let codeowners = CodeOwners::parse("/api/ @api\n/web/ @web\n/docs/\n");
+        let commits = vec![
+            commit(&codeowners, "api-1", &["api/a.rs"]),
+            commit(&codeowners, "web-1", &["web/page.ts"]),
+            // Independent of web-1, so it joins api-1
+            commit(&codeowners, "api-2", &["api/b.rs"]),
+            // Unowned: stays with the entry before it
+            commit(&codeowners, "docs", &["docs/guide.md"]),
+            commit(&codeowners, "web-2", &["web/page.ts"]),
+            // Touches a file web-2 changes, so it can't move below it
+            commit(&codeowners, "api-3", &["api/b.rs", "web/page.ts"]),
+        ];
+
+        let entries = suggest_entries(&commits);
+        let layout: Vec<Vec<&str>> = entries
+            .iter()
+            .map(|e| {
+                e.commits
+                    .iter()
+                    .map(|&i| commits[i].hash.as_str())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            layout,
+            vec![
+                vec!["api-1", "api-2"],
+                vec!["web-1", "docs", "web-2"],
+                vec!["api-3"],
+            ]
+        );
+        assert_eq!(
+            entries[2].owners,
+            BTreeSet::from(["@api".to_string(), "@web".to_string()])
+        );
+
+        let groups = owner_groups(&commits[5].files, &codeowners);
+        assert_eq!(
+            groups,
+            vec![
+                (vec!["@api".to_string()], vec!["api/b.rs".to_string()]),
+                (vec!["@web".to_string()], vec!["web/page.ts".to_string()]),
+            ]
+        );
+        assert!(owner_groups(&commits[0].files, &codeowners).is_empty());
+    }
+}
@@ -0,0 +1,51 @@
+use crate::errors::{CascadeError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const STATE_FILE: &str = "CASCADE_PICK_STATE";
+
+/// A `ca pick` paused on conflicts, finished by `ca pick --continue`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PickState {
+    /// ID of the stack the commit is picked into
+    pub stack_id: String,
+    /// Full hash of the commit being picked
+    pub source_commit: String,
+    /// Branch name requested for the new entry
+    pub branch: Option<String>,
+}
+
+impl PickState {
+    fn state_path(repo_root: &Path) -> Result<PathBuf> {
+        Ok(crate::git::resolve_git_dir(repo_root)?.join(STATE_FILE))
+    }
+
+    /// Save pick state to disk
+    pub fn save(&self, repo_root: &Path) -> Result<()> {
+        crate::utils::atomic_file::write_json(&Self::state_path(repo_root)?, self)
+    }
+
+    /// Load the paused pick, if there is one
+    pub fn load(repo_root: &Path) -> Result<Option<Self>> {
+        let state_path = Self::state_path(repo_root)?;
+        if !state_path.exists() {
+            return Ok(None);
+        }
+
+        let json = std::fs::read_to_string(&state_path)
+            .map_err(|e| CascadeError::config(format!("Failed to read pick state: {e}")))?;
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| CascadeError::config(format!("Failed to parse pick state: {e}")))
+    }
+
+    /// Delete pick state file
+    pub fn delete(repo_root: &Path) -> Result<()> {
+        let state_path = Self::state_path(repo_root)?;
+        if state_path.exists() {
+            std::fs::remove_file(&state_path)
+                .map_err(|e| CascadeError::config(format!("Failed to delete pick state: {e}")))?;
+        }
+        Ok(())
+    }
+}
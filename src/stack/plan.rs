@@ -0,0 +1,208 @@
+//! What a rebase of a stack would do, worked out without touching the repository
+//!
+//! `ca sync --dry-run` and `ca rebase --dry-run` walk the stack the way the force-push
+//! rebase does: entries that already sit on the branch below them stay put, and from the
+//! first entry that doesn't, every entry above is replayed onto its new base. Only refs
+//! and commits are read.
+
+use super::Stack;
+use crate::errors::Result;
+use crate::git::GitRepository;
+use uuid::Uuid;
+
+/// Every entry of a stack and what a rebase would do with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebasePlan {
+    /// What the stack would be rebased onto, as shown to the user
+    pub target: String,
+    /// Bottom of the stack first
+    pub steps: Vec<PlannedStep>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedStep {
+    pub branch: String,
+    pub pull_request_id: Option<String>,
+    pub action: PlannedAction,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlannedAction {
+    /// Merged, or already on the base as an equivalent commit; left out of the rebase
+    Skip,
+    /// Already on the right base; the branch stays where it is
+    Keep,
+    /// The entry's commits are cherry-picked onto `onto` and the branch moves off `from`
+    Move {
+        from: String,
+        onto: String,
+        commits: Vec<PlannedCommit>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedCommit {
+    pub hash: String,
+    pub summary: String,
+}
+
+impl RebasePlan {
+    /// Plan a rebase of `stack` onto `target_commit`, shown as `target`. Entries marked
+    /// merged or listed in `skip` are left out, as the rebase leaves them out.
+    pub fn build(
+        git_repo: &GitRepository,
+        stack: &Stack,
+        target: &str,
+        target_commit: &str,
+        skip: &[Uuid],
+    ) -> Result<Self> {
+        let skipped = |id: &Uuid| skip.contains(id);
+        let rebased = || {
+            stack
+                .entries
+                .iter()
+                .filter(|entry| !entry.is_merged && !skipped(&entry.id))
+        };
+
+        // The rebase stops early when every entry already sits on the target
+        let up_to_date = rebased().all(|entry| {
+            git_repo
+                .is_commit_based_on(&entry.commit_hash, target_commit)
+                .unwrap_or(false)
+        });
+
+        let mut steps = Vec::with_capacity(stack.entries.len());
+        let mut base = (target.to_string(), target_commit.to_string());
+        let mut moving = false;
+        for entry in &stack.entries {
+            let action = if entry.is_merged || skipped(&entry.id) {
+                PlannedAction::Skip
+            } else if up_to_date
+                || (!moving
+                    && git_repo
+                        .is_commit_based_on(&entry.commit_hash, &base.1)
+                        .unwrap_or(false))
+            {
+                PlannedAction::Keep
+            } else {
+                // Once an entry moves, everything above it is rebuilt on top of it
+                moving = true;
+                let commits = git_repo
+                    .get_first_parent_chain(&entry.commit_hash, entry.commit_count)?
+                    .into_iter()
+                    .map(|hash| {
+                        let summary = git_repo
+                            .get_commit(&hash)
+                            .ok()
+                            .and_then(|commit| commit.summary().map(str::to_string))
+                            .unwrap_or_default();
+                        PlannedCommit { hash, summary }
+                    })
+                    .collect();
+                PlannedAction::Move {
+                    from: entry.commit_hash.clone(),
+                    onto: base.0.clone(),
+                    commits,
+                }
+            };
+            if action != PlannedAction::Skip {
+                base = (entry.branch.clone(), entry.commit_hash.clone());
+            }
+            steps.push(PlannedStep {
+                branch: entry.branch.clone(),
+                pull_request_id: entry.pull_request_id.clone(),
+                action,
+            });
+        }
+
+        Ok(Self {
+            target: target.to_string(),
+            steps,
+        })
+    }
+
+    /// The steps whose branch would move
+    pub fn moves(&self) -> impl Iterator<Item = &PlannedStep> {
+        self.steps
+            .iter()
+            .filter(|step| matches!(step.action, PlannedAction::Move { .. }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(repo: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(repo)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit(repo: &Path, file: &str, message: &str) -> String {
+        std::fs::write(repo.join(file), message).unwrap();
+        git(repo, &["add", "."]);
+        git(repo, &["commit", "-m", message]);
+        git(repo, &["rev-parse", "HEAD"])
+    }
+
+    #[test]
+    fn test_plan_moves_entries_above_the_first_stale_one() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path();
+        git(repo, &["init", "-b", "main"]);
+        git(repo, &["config", "user.name", "Test"]);
+        git(repo, &["config", "user.email", "test@test.com"]);
+        let base = commit(repo, "README.md", "Initial");
+
+        git(repo, &["checkout", "-b", "feature"]);
+        let first = commit(repo, "a.txt", "Add a");
+        let second = commit(repo, "b.txt", "Add b");
+        let third = commit(repo, "c.txt", "Add c");
+        git(repo, &["branch", "entry-a", &first]);
+        git(repo, &["branch", "entry-b", &second]);
+        git(repo, &["branch", "entry-c", &third]);
+
+        let mut stack = Stack::new("demo".into(), "main".into(), None);
+        stack.push_entry("entry-a".into(), first.clone(), "Add a".into());
+        let b = stack.push_entry("entry-b".into(), second.clone(), "Add b".into());
+        stack.push_entry("entry-c".into(), third.clone(), "Add c".into());
+        let git_repo = GitRepository::open(repo).unwrap();
+
+        // Nothing new on main: every branch stays
+        let plan = RebasePlan::build(&git_repo, &stack, "main", &base, &[]).unwrap();
+        assert_eq!(plan.moves().count(), 0);
+
+        // Main moved on and entry b is left out: a and c are replayed, c onto a
+        git(repo, &["checkout", "main"]);
+        let new_base = commit(repo, "d.txt", "Upstream change");
+        let plan = RebasePlan::build(&git_repo, &stack, "main", &new_base, &[b]).unwrap();
+        let actions: Vec<_> = plan.steps.iter().map(|step| &step.action).collect();
+        assert_eq!(
+            actions[0],
+            &PlannedAction::Move {
+                from: first.clone(),
+                onto: "main".into(),
+                commits: vec![PlannedCommit {
+                    hash: first.clone(),
+                    summary: "Add a".into()
+                }],
+            }
+        );
+        assert_eq!(actions[1], &PlannedAction::Skip);
+        match actions[2] {
+            PlannedAction::Move { onto, commits, .. } => {
+                assert_eq!(onto, "entry-a");
+                assert_eq!(commits[0].hash, third);
+            }
+            other => panic!("expected entry-c to move, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Entries removed with `ca pop`, kept so `ca pop --restore-last` can put them back
+//!
+//! Every pop records the entries it removed in `.cascade/popped.json`, newest last, and
+//! points a `cascade/popped/<stack>/<timestamp>` branch at the top popped commit. The
+//! branch keeps the commits reachable after the entries' own branches are deleted and the
+//! working branch is moved back.
+
+use super::StackEntry;
+use crate::config::RemovedPrAction;
+use crate::errors::{CascadeError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File under `.cascade` holding the popped entries
+pub const POPPED_FILE: &str = "popped.json";
+
+/// Prefix of the branches holding popped commits
+pub const POPPED_BRANCH_PREFIX: &str = "cascade/popped/";
+
+/// Entries removed from a stack by one `ca pop`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PoppedEntries {
+    /// ID of the stack the entries were popped from
+    pub stack_id: String,
+    /// Branch pointing at the top popped commit
+    pub branch: String,
+    /// Commit the lowest popped entry was built on
+    pub base_commit: String,
+    /// The entries as they were, lowest first
+    pub entries: Vec<StackEntry>,
+    pub popped_at: DateTime<Utc>,
+    /// Pull requests the pop declined or drafted, reverted on restore
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_prs: Option<ClosedPrs>,
+}
+
+/// Pull requests of removed entries that were declined or converted to drafts
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClosedPrs {
+    pub action: RemovedPrAction,
+    pub pr_ids: Vec<u64>,
+}
+
+impl PoppedEntries {
+    /// Name of the branch holding commits popped from `stack_name` at `at`
+    pub fn branch_name(stack_name: &str, at: DateTime<Utc>) -> String {
+        format!(
+            "{POPPED_BRANCH_PREFIX}{stack_name}/{}",
+            at.format("%Y%m%dT%H%M%SZ")
+        )
+    }
+
+    fn path(config_dir: &Path) -> PathBuf {
+        config_dir.join(POPPED_FILE)
+    }
+
+    /// Every recorded pop, oldest first
+    pub fn load_all(config_dir: &Path) -> Result<Vec<Self>> {
+        let path = Self::path(config_dir);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| CascadeError::config(format!("Failed to read popped entries: {e}")))?;
+        serde_json::from_str(&json)
+            .map_err(|e| CascadeError::config(format!("Failed to parse popped entries: {e}")))
+    }
+
+    fn save_all(config_dir: &Path, records: &[Self]) -> Result<()> {
+        let path = Self::path(config_dir);
+        if records.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(&path).map_err(|e| {
+                    CascadeError::config(format!("Failed to delete popped entries: {e}"))
+                })?;
+            }
+            return Ok(());
+        }
+        crate::utils::atomic_file::write_json(&path, &records)
+    }
+
+    /// Add this pop after the ones already recorded
+    pub fn record(&self, config_dir: &Path) -> Result<()> {
+        let mut records = Self::load_all(config_dir)?;
+        records.push(self.clone());
+        Self::save_all(config_dir, &records)
+    }
+
+    /// The most recent pop from the given stack
+    pub fn last_for(config_dir: &Path, stack_id: &str) -> Result<Option<Self>> {
+        Ok(Self::load_all(config_dir)?
+            .into_iter()
+            .rev()
+            .find(|record| record.stack_id == stack_id))
+    }
+
+    /// Drop the pop stashed on `branch`, once it's been restored
+    pub fn forget(config_dir: &Path, branch: &str) -> Result<()> {
+        let mut records = Self::load_all(config_dir)?;
+        records.retain(|record| record.branch != branch);
+        Self::save_all(config_dir, &records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stack::Stack;
+    use tempfile::TempDir;
+
+    fn record(stack_id: &str, branch: &str) -> PoppedEntries {
+        let mut stack = Stack::new("s".into(), "main".into(), None);
+        stack.push_entry("feature-2".into(), "b".repeat(40), "Second".into());
+        PoppedEntries {
+            stack_id: stack_id.to_string(),
+            branch: branch.to_string(),
+            base_commit: "a".repeat(40),
+            entries: stack.entries,
+            popped_at: Utc::now(),
+            closed_prs: None,
+        }
+    }
+
+    #[test]
+    fn test_record_and_forget() {
+        let dir = TempDir::new().unwrap();
+        assert!(PoppedEntries::last_for(dir.path(), "s1").unwrap().is_none());
+
+        record("s1", "cascade/popped/s/1")
+            .record(dir.path())
+            .unwrap();
+        record("s2", "cascade/popped/t/1")
+            .record(dir.path())
+            .unwrap();
+        record("s1", "cascade/popped/s/2")
+            .record(dir.path())
+            .unwrap();
+
+        let last = PoppedEntries::last_for(dir.path(), "s1").unwrap().unwrap();
+        assert_eq!(last.branch, "cascade/popped/s/2");
+        assert_eq!(last.entries[0].branch, "feature-2");
+
+        PoppedEntries::forget(dir.path(), &last.branch).unwrap();
+        let last = PoppedEntries::last_for(dir.path(), "s1").unwrap().unwrap();
+        assert_eq!(last.branch, "cascade/popped/s/1");
+
+        PoppedEntries::forget(dir.path(), "cascade/popped/s/1").unwrap();
+        PoppedEntries::forget(dir.path(), "cascade/popped/t/1").unwrap();
+        assert!(!dir.path().join(POPPED_FILE).exists());
+    }
+
+    #[test]
+    fn test_branch_name() {
+        let at = DateTime::parse_from_rfc3339("2025-03-14T09:26:53Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            PoppedEntries::branch_name("auth", at),
+            "cascade/popped/auth/20250314T092653Z"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Last known pull request counts for each stack, and pull request titles
+//!
+//! Asking Bitbucket for PR state is far too slow for `ca prompt`, so every command that
+//! already fetches it records the counts in the git dir for the prompt to read later.
+//! Titles are kept the same way for `ca search`.
+
+use crate::errors::{CascadeError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+const STATE_FILE: &str = "CASCADE_PR_SUMMARY";
+
+/// Pull request counts of one stack as of the last Bitbucket check
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PrSummary {
+    /// Open pull requests
+    pub open: usize,
+    /// Open pull requests that are approved, passing and mergeable
+    pub ready_to_land: usize,
+    /// Open pull requests whose latest build failed
+    #[serde(default)]
+    pub failing_builds: usize,
+    /// When the counts were fetched
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Cached [`PrSummary`] of every stack in the repository
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PrSummaryCache {
+    pub stacks: HashMap<Uuid, PrSummary>,
+    /// Last seen title of each pull request, by pull request ID
+    #[serde(default)]
+    pub titles: HashMap<String, String>,
+}
+
+impl PrSummaryCache {
+    fn state_path(repo_root: &Path) -> Result<PathBuf> {
+        Ok(crate::git::resolve_git_dir(repo_root)?.join(STATE_FILE))
+    }
+
+    /// Load the cache; a missing or unreadable file is treated as empty
+    pub fn load(repo_root: &Path) -> Self {
+        Self::state_path(repo_root)
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Last recorded counts for a stack
+    pub fn get(&self, stack_id: &Uuid) -> Option<&PrSummary> {
+        self.stacks.get(stack_id)
+    }
+
+    /// Record fresh counts for a stack, keeping those of the others
+    pub fn record(repo_root: &Path, stack_id: Uuid, summary: PrSummary) -> Result<()> {
+        let mut cache = Self::load(repo_root);
+        cache.stacks.insert(stack_id, summary);
+        cache.save(repo_root)
+    }
+
+    /// Record the titles of pull requests, by ID
+    pub fn record_titles(
+        repo_root: &Path,
+        titles: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<()> {
+        let mut cache = Self::load(repo_root);
+        let before = cache.titles.clone();
+        cache.titles.extend(titles);
+        if cache.titles == before {
+            return Ok(());
+        }
+        cache.save(repo_root)
+    }
+
+    fn save(&self, repo_root: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| CascadeError::config(format!("Failed to serialize PR summary: {e}")))?;
+        std::fs::write(Self::state_path(repo_root)?, json)
+            .map_err(|e| CascadeError::config(format!("Failed to write PR summary: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        git2::Repository::init(temp_dir.path()).unwrap();
+        assert!(PrSummaryCache::load(temp_dir.path()).stacks.is_empty());
+
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let summary = |ready_to_land| PrSummary {
+            open: 3,
+            ready_to_land,
+            failing_builds: 0,
+            checked_at: Utc::now(),
+        };
+        PrSummaryCache::record(temp_dir.path(), first, summary(1)).unwrap();
+        PrSummaryCache::record(temp_dir.path(), second, summary(2)).unwrap();
+        PrSummaryCache::record(temp_dir.path(), first, summary(0)).unwrap();
+
+        PrSummaryCache::record_titles(temp_dir.path(), [("7".into(), "Add parser".into())])
+            .unwrap();
+
+        let cache = PrSummaryCache::load(temp_dir.path());
+        assert_eq!(cache.get(&first).unwrap().ready_to_land, 0);
+        assert_eq!(cache.get(&second).unwrap().ready_to_land, 2);
+        assert_eq!(cache.titles["7"], "Add parser");
+    }
+
+    #[test]
+    fn test_load_summary_without_failing_builds() {
+        let summary: PrSummary = serde_json::from_str(
+            r#"{"open":2,"ready_to_land":1,"checked_at":"2024-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(summary.failing_builds, 0);
+    }
+}
@@ -1,9 +1,12 @@
+use crate::cli::output::Output;
 use crate::errors::{CascadeError, Result};
 use crate::git::{ConflictAnalyzer, GitRepository};
-use crate::stack::{Stack, StackManager, SyncState};
+use crate::stack::{Stack, StackEntry, StackManager, SyncState};
+use crate::utils::interrupt::{self, InterruptGuard};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 use tracing::debug;
 use uuid::Uuid;
 
@@ -83,6 +86,28 @@ pub struct RebaseResult {
     pub summary: String,
 }
 
+/// What to do with a stack entry in an edit plan (`ca stacks edit`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditAction {
+    /// Keep the entry
+    Pick,
+    /// Keep the entry with a new commit message
+    Reword(String),
+    /// Fold the entry's changes into the entry above it, keeping that entry's message
+    Squash,
+    /// Remove the entry from the stack
+    Drop,
+    /// Move the entry to the top of another stack (by name)
+    Move(String),
+}
+
+/// A single step of an edit plan; steps are listed from the bottom of the stack up
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditStep {
+    pub entry_id: Uuid,
+    pub action: EditAction,
+}
+
 /// RAII guard to ensure temporary branches are cleaned up even on error/panic
 ///
 /// This stores branch names and provides a cleanup method that can be called
@@ -192,18 +217,295 @@ impl RebaseManager {
             .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?
             .clone();
 
+        debug!(
+            target: crate::utils::logging::OPS_TARGET,
+            op = "sync",
+            stack = %stack.name,
+            "Rebasing stack {}",
+            stack.name
+        );
+        self.stack_manager.snapshot_before("rebase");
+
         match self.options.strategy {
             RebaseStrategy::ForcePush => self.rebase_with_force_push(&stack),
             RebaseStrategy::Interactive => self.rebase_interactive(&stack),
         }
     }
 
+    /// Restructure a stack according to an edit plan (reorder, reword, squash, drop, move).
+    ///
+    /// Unmerged entries missing from the plan are dropped. Every new commit is computed in
+    /// memory before any branch is updated, so a conflict leaves the repository unchanged.
+    /// The branch mapping of the result lists the rewritten branches of this stack.
+    pub fn apply_edit_plan(&mut self, stack_id: &Uuid, steps: &[EditStep]) -> Result<RebaseResult> {
+        self.execute_edit_plan(stack_id, steps, None)
+    }
+
+    /// Fold `fixup_commit` (e.g. a snapshot of staged changes) into an entry, optionally
+    /// rewording it, and restack the entries above it. The caller is responsible for
+    /// making sure the working tree only holds the changes being folded in.
+    pub fn amend_entry(
+        &mut self,
+        stack_id: &Uuid,
+        entry_id: &Uuid,
+        fixup_commit: &str,
+        message: Option<String>,
+    ) -> Result<RebaseResult> {
+        let stack = self
+            .stack_manager
+            .get_stack(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+
+        let steps: Vec<EditStep> = stack
+            .entries
+            .iter()
+            .filter(|e| !e.is_merged)
+            .map(|e| EditStep {
+                entry_id: e.id,
+                action: match &message {
+                    Some(message) if e.id == *entry_id => EditAction::Reword(message.clone()),
+                    _ => EditAction::Pick,
+                },
+            })
+            .collect();
+
+        self.execute_edit_plan(stack_id, &steps, Some((entry_id, fixup_commit)))
+    }
+
+    fn execute_edit_plan(
+        &mut self,
+        stack_id: &Uuid,
+        steps: &[EditStep],
+        fixup: Option<(&Uuid, &str)>,
+    ) -> Result<RebaseResult> {
+        let stack = self
+            .stack_manager
+            .get_stack(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?
+            .clone();
+
+        // Merged entries stay where they are; the plan covers everything above them
+        let merged: Vec<&StackEntry> = stack.entries.iter().filter(|e| e.is_merged).collect();
+        let mut onto = merged
+            .last()
+            .map(|e| e.commit_hash.clone())
+            .unwrap_or_else(|| stack.base_branch.clone());
+
+        let mut seen = std::collections::HashSet::new();
+        for step in steps {
+            let entry = stack.get_entry(&step.entry_id).ok_or_else(|| {
+                CascadeError::validation(format!("Entry {} is not in this stack", step.entry_id))
+            })?;
+            if entry.is_merged {
+                return Err(CascadeError::validation(format!(
+                    "Entry '{}' is already merged and cannot be edited",
+                    entry.short_message(40)
+                )));
+            }
+            if !seen.insert(step.entry_id) {
+                return Err(CascadeError::validation(format!(
+                    "Entry '{}' appears more than once in the plan",
+                    entry.short_message(40)
+                )));
+            }
+        }
+
+        // Resolve move targets and where each one's new entries go
+        let mut targets: HashMap<String, (Uuid, String)> = HashMap::new();
+        for step in steps {
+            if let EditAction::Move(name) = &step.action {
+                if targets.contains_key(name) {
+                    continue;
+                }
+                let target = self
+                    .stack_manager
+                    .get_stack_by_name(name)
+                    .ok_or_else(|| CascadeError::config(format!("Stack '{name}' not found")))?;
+                if target.id == *stack_id {
+                    return Err(CascadeError::validation(format!(
+                        "Cannot move entries of '{name}' into itself"
+                    )));
+                }
+                targets.insert(name.clone(), (target.id, target.dependency_base_branch()));
+            }
+        }
+
+        let mut rewritten: Vec<&str> = stack.entries.iter().map(|e| e.branch.as_str()).collect();
+        rewritten.extend(stack.working_branch.as_deref());
+        let target_working: Vec<String> = targets
+            .values()
+            .filter_map(|(id, _)| self.stack_manager.get_stack(id))
+            .filter_map(|s| s.working_branch.clone())
+            .collect();
+        rewritten.extend(target_working.iter().map(String::as_str));
+        let touches_current = match fixup {
+            // The pending changes are exactly what gets folded in
+            Some(_) => self.stack_manager.touches_current_branch(&rewritten),
+            None => self.stack_manager.check_rewrite_safety(&rewritten)?,
+        };
+
+        // Compute all new commits up front: (entry, parent, commit, new message)
+        let mut kept: Vec<(Uuid, String, String, Option<String>)> = Vec::new();
+        let mut moved: Vec<(Uuid, Uuid, String)> = Vec::new();
+        for step in steps {
+            let entry = &stack.entry_map[&step.entry_id];
+            match &step.action {
+                EditAction::Pick => {
+                    // Untouched entries keep their commits (and PR branch) as-is
+                    let onto_id = self.git_repo.resolve_reference(&onto)?.id();
+                    let first = self
+                        .git_repo
+                        .get_first_parent_chain(&entry.commit_hash, entry.commit_count)?
+                        .remove(0);
+                    let commit =
+                        if self.git_repo.get_commit(&first)?.parent_id(0).ok() == Some(onto_id) {
+                            entry.commit_hash.clone()
+                        } else {
+                            self.pick_entry_onto(entry, &onto, None)?
+                        };
+                    kept.push((entry.id, self.tip_parent(&commit)?, commit.clone(), None));
+                    onto = commit;
+                }
+                EditAction::Reword(message) => {
+                    let commit = self.pick_entry_onto(entry, &onto, Some(message))?;
+                    kept.push((
+                        entry.id,
+                        self.tip_parent(&commit)?,
+                        commit.clone(),
+                        Some(message.clone()),
+                    ));
+                    onto = commit;
+                }
+                EditAction::Squash => {
+                    let (_, parent, commit, _) = kept.last_mut().ok_or_else(|| {
+                        CascadeError::validation(format!(
+                            "Cannot squash '{}': there is no entry above it to squash into",
+                            entry.short_message(40)
+                        ))
+                    })?;
+                    let picked = self.pick_entry_onto(entry, &onto, None)?;
+                    *commit = self.git_repo.commit_tree_onto(&picked, parent, commit)?;
+                    onto = commit.clone();
+                }
+                EditAction::Drop => {}
+                EditAction::Move(name) => {
+                    let (target_id, target_onto) =
+                        targets.get_mut(name).expect("move targets resolved above");
+                    let commit = self.pick_entry_onto(entry, target_onto, None)?;
+                    *target_onto = commit.clone();
+                    moved.push((entry.id, *target_id, commit));
+                }
+            }
+
+            if let Some((fixup_entry, fixup_commit)) = fixup {
+                if *fixup_entry == step.entry_id {
+                    let (_, parent, commit, _) = kept
+                        .last_mut()
+                        .filter(|(id, _, _, _)| id == fixup_entry)
+                        .ok_or_else(|| {
+                            CascadeError::validation("Changes can only be folded into a kept entry")
+                        })?;
+                    let picked = self.git_repo.cherry_pick_onto(fixup_commit, commit, None)?;
+                    *commit = self.git_repo.commit_tree_onto(&picked, parent, commit)?;
+                    onto = commit.clone();
+                }
+            }
+        }
+
+        // Apply: branch refs
+        let mut branch_mapping = HashMap::new();
+        let mut new_commits = Vec::new();
+        for (entry_id, _, commit, _) in &kept {
+            let entry = &stack.entry_map[entry_id];
+            if *commit != entry.commit_hash {
+                self.git_repo
+                    .update_branch_to_commit(&entry.branch, commit)?;
+                branch_mapping.insert(entry.branch.clone(), entry.branch.clone());
+                new_commits.push(commit.clone());
+            }
+        }
+        for (entry_id, _, commit) in &moved {
+            let entry = &stack.entry_map[entry_id];
+            self.git_repo
+                .update_branch_to_commit(&entry.branch, commit)?;
+        }
+
+        // Keep working branches pointing at their stack's new top
+        if let Some(working) = &stack.working_branch {
+            if self.git_repo.branch_exists(working) {
+                let top = self.git_repo.resolve_reference(&onto)?.id().to_string();
+                self.git_repo.update_branch_to_commit(working, &top)?;
+            }
+        }
+        for (target_id, target_onto) in targets.values() {
+            let working = self
+                .stack_manager
+                .get_stack(target_id)
+                .and_then(|s| s.working_branch.clone());
+            if let Some(working) = working {
+                if self.git_repo.branch_exists(&working) {
+                    let top = self
+                        .git_repo
+                        .resolve_reference(target_onto)?
+                        .id()
+                        .to_string();
+                    self.git_repo.update_branch_to_commit(&working, &top)?;
+                }
+            }
+        }
+
+        if touches_current {
+            self.git_repo.reset_to_head()?;
+        }
+
+        // Apply: stack bookkeeping
+        for (entry_id, target_id, commit) in &moved {
+            self.stack_manager
+                .transfer_entry(stack_id, entry_id, target_id, commit.clone())?;
+        }
+
+        let stack_mut = self
+            .stack_manager
+            .get_stack_mut(stack_id)
+            .ok_or_else(|| CascadeError::config(format!("Stack {stack_id} not found")))?;
+        for (entry_id, _, commit, message) in &kept {
+            stack_mut
+                .update_entry_commit_hash(entry_id, commit.clone())
+                .map_err(CascadeError::config)?;
+            if let (Some(message), Some(entry)) = (message, stack_mut.get_entry_mut(entry_id)) {
+                entry.message = message.clone();
+            }
+        }
+        stack_mut.repair_data_consistency();
+
+        let order: Vec<Uuid> = merged
+            .iter()
+            .map(|e| e.id)
+            .chain(kept.iter().map(|(id, _, _, _)| *id))
+            .collect();
+        let removed = self.stack_manager.reorder_stack_entries(stack_id, &order)?;
+
+        self.stack_manager.save_to_disk()?;
+
+        Ok(RebaseResult {
+            success: true,
+            branch_mapping,
+            conflicts: Vec::new(),
+            new_commits,
+            error: None,
+            summary: format!(
+                "{} entries kept, {} removed, {} moved",
+                kept.len(),
+                removed.len(),
+                moved.len()
+            ),
+        })
+    }
+
     /// Rebase using force-push strategy (industry standard for stacked diffs)
     /// This updates local branches in-place, then force-pushes ONLY branches with existing PRs
     /// to preserve PR history - the approach used by Graphite, Phabricator, spr, etc.
     fn rebase_with_force_push(&mut self, stack: &Stack) -> Result<RebaseResult> {
-        use crate::cli::output::Output;
-
         // Check if there's an in-progress cherry-pick from a previous failed sync
         if self.has_in_progress_cherry_pick()? {
             return self.handle_in_progress_cherry_pick(stack);
@@ -301,7 +603,7 @@ impl RebaseManager {
 
         // Handle empty stack early (no unmerged entries)
         if entry_count == 0 {
-            println!();
+            Output::spacing();
             if stack.entries.is_empty() {
                 Output::info("Stack has no entries yet");
                 Output::tip("Use 'ca push' to add commits to this stack");
@@ -313,7 +615,7 @@ impl RebaseManager {
             }
 
             // Print success with summary (consistent with non-empty path)
-            println!();
+            Output::spacing();
             Output::success(&result.summary);
 
             // Save metadata and return
@@ -333,7 +635,7 @@ impl RebaseManager {
             });
 
         if all_up_to_date {
-            println!();
+            Output::spacing();
             Output::success("Stack is already up-to-date with base branch");
             result.summary = "Stack is up-to-date".to_string();
             result.success = true;
@@ -353,11 +655,28 @@ impl RebaseManager {
             current_entry_branch: String::new(),
             current_temp_branch: String::new(),
             temp_branches: Vec::new(),
+            previous_base: None,
+            pending_commits: Vec::new(),
         };
 
         // Remove any stale sync state before starting
         let _ = SyncState::delete(&repo_root);
 
+        // Ctrl-C stops the rebase between entries; remember where every entry branch was so
+        // an interrupted sync can put them back
+        let _interrupt = InterruptGuard::new();
+        let original_heads: Vec<(String, String)> = stack
+            .entries
+            .iter()
+            .filter(|entry| !entry.is_merged)
+            .filter_map(|entry| {
+                self.git_repo
+                    .get_branch_head(&entry.branch)
+                    .ok()
+                    .map(|head| (entry.branch.clone(), head))
+            })
+            .collect();
+
         // Phase 1: Rebase all entries locally (libgit2 only - no CLI commands)
         // Track which branches actually changed so we only push what’s needed
         let mut branches_with_new_commits: std::collections::HashSet<String> =
@@ -368,6 +687,20 @@ impl RebaseManager {
             let original_branch = &entry.branch;
             let entry_id_str = entry.id.to_string();
 
+            if interrupt::requested() {
+                self.restore_interrupted_rebase(
+                    &original_heads,
+                    &temp_branches,
+                    original_branch_for_cleanup
+                        .as_deref()
+                        .unwrap_or(&target_base),
+                );
+                let _ = SyncState::delete(&repo_root);
+                return Err(interrupt::interrupted_error(
+                    "sync stopped; all stack branches were restored to where they were",
+                ));
+            }
+
             sync_state.remaining_entry_ids = stack
                 .entries
                 .iter()
@@ -456,9 +789,7 @@ impl RebaseManager {
             sync_state.save(&repo_root)?;
 
             // Cherry-pick the commit onto the temp branch (NOT the protected base!)
-            match crate::utils::git_lock::retry_on_lock(4, || {
-                self.cherry_pick_commit(&entry.commit_hash)
-            }) {
+            match self.cherry_pick_entry(entry, &mut sync_state, &repo_root) {
                 Ok(new_commit_hash) => {
                     result.new_commits.push(new_commit_hash.clone());
 
@@ -490,7 +821,7 @@ impl RebaseManager {
                         } else {
                             "├─"
                         };
-                        println!("   {} {} (PR #{})", tree_char, original_branch, pr_num);
+                        Output::line(format!("   {tree_char} {original_branch} (PR #{pr_num})"));
                         branches_to_push.push((
                             original_branch.clone(),
                             pr_num.clone(),
@@ -569,9 +900,19 @@ impl RebaseManager {
                     }
 
                     result.conflicts.push(entry.commit_hash.clone());
+                    debug!(
+                        target: crate::utils::logging::OPS_TARGET,
+                        op = "conflict",
+                        stack = %stack.name,
+                        branch = %entry.branch,
+                        "Conflict while rebasing {}",
+                        entry.branch
+                    );
 
-                    if !self.options.auto_resolve {
-                        println!();
+                    // Auto-resolution commits the entry in one go, so a group stopped before
+                    // its last commit is left to 'ca sync continue'
+                    if !self.options.auto_resolve || !sync_state.pending_commits.is_empty() {
+                        Output::spacing();
                         Output::error(e.to_string());
                         result.success = false;
                         result.error = Some(format!(
@@ -703,10 +1044,9 @@ impl RebaseManager {
                                         } else {
                                             "├─"
                                         };
-                                        println!(
-                                            "   {} {} (PR #{})",
-                                            tree_char, original_branch, pr_num
-                                        );
+                                        Output::line(format!(
+                                            "   {tree_char} {original_branch} (PR #{pr_num})"
+                                        ));
                                         branches_to_push.push((
                                             original_branch.clone(),
                                             pr_num.clone(),
@@ -795,7 +1135,7 @@ impl RebaseManager {
         let mut successful_pushes = 0; // Track successful pushes for summary
 
         if !result.success {
-            println!();
+            Output::spacing();
             Output::error("Rebase failed - not pushing any branches");
             // Error details are in result.error, will be returned at end of function
             // Skip the push phase and jump straight to cleanup/return
@@ -804,7 +1144,7 @@ impl RebaseManager {
             // This batch approach prevents index lock conflicts between libgit2 and git CLI
 
             if !branches_to_push.is_empty() {
-                println!();
+                Output::spacing();
 
                 // Fetch once before pushing all branches (avoid redundant fetches per branch)
                 if let Err(e) = self.git_repo.fetch_with_retry() {
@@ -815,9 +1155,30 @@ impl RebaseManager {
                 // Push all branches (using no-fetch variant since we already fetched)
                 let mut push_results = Vec::new();
                 for (branch_name, _pr_num, _index) in branches_to_push.iter() {
-                    let result = self
-                        .git_repo
-                        .force_push_single_branch_auto_no_fetch(branch_name);
+                    // Local branches are already rebased; an interrupt only skips the pushes left
+                    if interrupt::requested() {
+                        push_results.push((
+                            branch_name.clone(),
+                            Err(interrupt::interrupted_error("push skipped")),
+                        ));
+                        continue;
+                    }
+                    let entry = stack.entries.iter().find(|e| e.branch == *branch_name);
+                    let result = self.git_repo.force_push_single_branch_auto_no_fetch(
+                        branch_name,
+                        entry.and_then(|e| e.remote_commit_hash.as_deref()),
+                    );
+                    if let (Ok(_), Some(entry)) = (&result, entry) {
+                        if let Ok(pushed_commit) = self.git_repo.get_branch_head(branch_name) {
+                            if let Err(e) = self.stack_manager.set_entry_remote_commit(
+                                &stack.id,
+                                &entry.id,
+                                pushed_commit,
+                            ) {
+                                debug!("Could not record pushed commit for {}: {}", branch_name, e);
+                            }
+                        }
+                    }
                     push_results.push((branch_name.clone(), result));
                 }
 
@@ -828,23 +1189,23 @@ impl RebaseManager {
                         Ok(_) => {
                             debug!("Pushed {} successfully", branch_name);
                             successful_pushes += 1;
-                            println!(
-                                "   ✓ Pushed {} ({}/{})",
+                            Output::sub_item(format!(
+                                "Pushed {} ({}/{})",
                                 branch_name,
                                 index + 1,
                                 pushed_count
-                            );
+                            ));
                         }
                         Err(e) => {
                             failed_pushes += 1;
-                            println!("   ⚠ Could not push '{}': {}", branch_name, e);
+                            Output::warning(format!("Could not push '{}': {}", branch_name, e));
                         }
                     }
                 }
 
                 // If any pushes failed, show recovery instructions
                 if failed_pushes > 0 {
-                    println!(); // Spacing
+                    Output::spacing();
                     Output::warning(format!(
                         "{} branch(es) failed to push to remote",
                         failed_pushes
@@ -986,7 +1347,13 @@ impl RebaseManager {
                                                             return false;
                                                         }
                                                     }
-                                                    true
+                                                    // Commits below the entries come from the
+                                                    // previous base, e.g. after a retarget
+                                                    !stack.entries.iter().any(|e| {
+                                                        self.git_repo
+                                                            .is_descendant_of(&e.commit_hash, &hash)
+                                                            .unwrap_or(false)
+                                                    })
                                                 })
                                                 .collect();
 
@@ -1011,7 +1378,7 @@ impl RebaseManager {
                                                     "Cannot sync: Working branch '{}' has {} commit(s) not in the stack",
                                                     working_branch_name, new_commits.len()
                                                 ));
-                                                println!();
+                                                Output::spacing();
                                                 Output::sub_item(
                                                     "These commits would be lost if we proceed:",
                                                 );
@@ -1033,13 +1400,13 @@ impl RebaseManager {
                                                         new_commits.len() - 5
                                                     ));
                                                 }
-                                                println!();
+                                                Output::spacing();
                                                 Output::tip(
                                                     "Add these commits to the stack first:",
                                                 );
                                                 Output::bullet("Run: ca push");
                                                 Output::bullet("Then run: ca sync");
-                                                println!();
+                                                Output::spacing();
 
                                                 if let Some(ref orig) = original_branch_for_cleanup
                                                 {
@@ -1109,7 +1476,7 @@ impl RebaseManager {
         // If rebase failed, we'll have an error message but no summary
 
         // Display result with proper formatting
-        println!();
+        Output::spacing();
         if result.success {
             Output::success(&result.summary);
         } else {
@@ -1147,31 +1514,40 @@ impl RebaseManager {
             summary: String::new(),
         };
 
-        println!("Interactive Rebase for Stack: {}", stack.name);
-        println!("   Base branch: {}", stack.base_branch);
-        println!("   Entries: {}", stack.entries.len());
+        Output::section(format!("Interactive Rebase for Stack: {}", stack.name));
+        Output::sub_item(format!("Base branch: {}", stack.base_branch));
+        Output::sub_item(format!("Entries: {}", stack.entries.len()));
 
         if self.options.interactive {
-            println!("\nChoose action for each commit:");
-            println!("  (p)ick   - apply the commit");
-            println!("  (s)kip   - skip this commit");
-            println!("  (e)dit   - edit the commit message");
-            println!("  (q)uit   - abort the rebase");
+            Output::section("Choose action for each commit");
+            Output::line("  (p)ick   - apply the commit");
+            Output::line("  (s)kip   - skip this commit");
+            Output::line("  (e)dit   - edit the commit message");
+            Output::line("  (q)uit   - abort the rebase");
         }
 
         // For now, automatically pick all commits
         // In a real implementation, this would prompt the user
         for entry in &stack.entries {
-            println!(
+            Output::line(format!(
                 "  {} {} - {}",
                 entry.short_hash(),
                 entry.branch,
                 entry.short_message(50)
-            );
+            ));
 
             // Auto-pick for demo purposes
-            match self.cherry_pick_commit(&entry.commit_hash) {
-                Ok(new_commit) => result.new_commits.push(new_commit),
+            let picked = self
+                .git_repo
+                .get_first_parent_chain(&entry.commit_hash, entry.commit_count)
+                .and_then(|commits| {
+                    commits
+                        .iter()
+                        .map(|commit| self.cherry_pick_commit(commit))
+                        .collect::<Result<Vec<_>>>()
+                });
+            match picked {
+                Ok(new_commits) => result.new_commits.extend(new_commits),
                 Err(_) => result.conflicts.push(entry.commit_hash.clone()),
             }
         }
@@ -1183,6 +1559,51 @@ impl RebaseManager {
         Ok(result)
     }
 
+    /// Cherry-pick all of an entry's commits onto `onto` without a checkout, returning the
+    /// new tip. `message` rewords the tip
+    fn pick_entry_onto(
+        &self,
+        entry: &StackEntry,
+        onto: &str,
+        message: Option<&str>,
+    ) -> Result<String> {
+        self.git_repo
+            .cherry_pick_chain_onto(&entry.commit_hash, entry.commit_count, onto, message)
+    }
+
+    /// Parent of an entry's tip commit, which squashed-in changes are folded on top of
+    fn tip_parent(&self, commit: &str) -> Result<String> {
+        Ok(self
+            .git_repo
+            .get_commit(commit)?
+            .parent_id(0)
+            .map_err(CascadeError::Git)?
+            .to_string())
+    }
+
+    /// Cherry-pick an entry's commits onto the current branch, oldest first, returning the
+    /// new tip. Commits still to pick are kept in the sync state so `ca sync continue` can
+    /// finish the entry after a conflict
+    fn cherry_pick_entry(
+        &self,
+        entry: &StackEntry,
+        sync_state: &mut SyncState,
+        repo_root: &Path,
+    ) -> Result<String> {
+        let commits = self
+            .git_repo
+            .get_first_parent_chain(&entry.commit_hash, entry.commit_count)?;
+        for (index, commit) in commits.iter().enumerate() {
+            let pending = commits[index + 1..].to_vec();
+            if pending != sync_state.pending_commits {
+                sync_state.pending_commits = pending;
+                sync_state.save(repo_root)?;
+            }
+            crate::utils::git_lock::retry_on_lock(4, || self.cherry_pick_commit(commit))?;
+        }
+        Ok(self.git_repo.get_head_commit()?.id().to_string())
+    }
+
     /// Cherry-pick a commit onto the current branch
     fn cherry_pick_commit(&self, commit_hash: &str) -> Result<String> {
         // Use the real cherry-pick implementation from GitRepository
@@ -1639,7 +2060,10 @@ impl RebaseManager {
                 all_imports.dedup();
                 Ok(Some(all_imports.join("\n")))
             }
-            ConflictType::Structural | ConflictType::ContentOverlap | ConflictType::Complex => {
+            ConflictType::Structural
+            | ConflictType::ContentOverlap
+            | ConflictType::Complex
+            | ConflictType::SubmodulePointer => {
                 // These require manual resolution
                 Ok(None)
             }
@@ -1700,6 +2124,38 @@ impl RebaseManager {
 
     /// Update a stack entry with new commit information
     /// NOTE: We keep the original branch name to preserve PR mapping, only update commit hash
+    /// Undo a rebase stopped by Ctrl-C: move entry branches back to their original commits,
+    /// return to the branch the user was on and drop the temp branches
+    fn restore_interrupted_rebase(
+        &self,
+        original_heads: &[(String, String)],
+        temp_branches: &[String],
+        return_to: &str,
+    ) {
+        let _ = std::process::Command::new("git")
+            .args(["cherry-pick", "--abort"])
+            .current_dir(self.git_repo.path())
+            .output();
+
+        // We're on a temp branch here, so the entry branches can move freely
+        for (branch, head) in original_heads {
+            if let Err(e) = self.git_repo.update_branch_to_commit(branch, head) {
+                Output::warning(format!(
+                    "Could not restore '{branch}' to {}: {e}",
+                    &head[..8]
+                ));
+            }
+        }
+        if let Err(e) = self.git_repo.checkout_branch_unsafe(return_to) {
+            Output::warning(format!("Could not return to '{return_to}': {e}"));
+        }
+        for temp_branch in temp_branches {
+            if let Err(e) = self.git_repo.delete_branch_unsafe(temp_branch) {
+                debug!("Could not delete temp branch {}: {}", temp_branch, e);
+            }
+        }
+    }
+
     fn update_stack_entry(
         &mut self,
         stack_id: Uuid,
@@ -1826,6 +2282,7 @@ impl RebaseManager {
 
         // Stage resolved files
         self.git_repo.stage_conflict_resolved_files()?;
+        self.git_repo.record_conflict_resolutions()?;
 
         tracing::debug!("Rebase continued successfully");
         Ok(())
@@ -1839,14 +2296,12 @@ impl RebaseManager {
 
     /// Handle resuming an in-progress cherry-pick from a previous failed sync
     fn handle_in_progress_cherry_pick(&mut self, stack: &Stack) -> Result<RebaseResult> {
-        use crate::cli::output::Output;
-
         let git_dir = self.git_repo.git_dir();
 
         Output::section("Resuming in-progress sync");
-        println!();
+        Output::spacing();
         Output::info("Detected unfinished cherry-pick from previous sync");
-        println!();
+        Output::spacing();
 
         // Check if conflicts are resolved
         if self.git_repo.has_conflicts()? {
@@ -1897,6 +2352,7 @@ impl RebaseManager {
 
         // Stage all resolved files
         self.git_repo.stage_conflict_resolved_files()?;
+        self.git_repo.record_conflict_resolutions()?;
 
         // Complete the cherry-pick by committing
         let cherry_pick_msg_file = git_dir.join("CHERRY_PICK_MSG");
@@ -1919,9 +2375,9 @@ impl RebaseManager {
                     let _ = std::fs::remove_file(&cherry_pick_msg_file);
                 }
 
-                println!();
+                Output::spacing();
                 Output::info("Continuing with rest of stack...");
-                println!();
+                Output::spacing();
 
                 // Now continue with the rest of the rebase
                 // We need to restart the full rebase since we don't track which entry we were on
@@ -2366,4 +2822,324 @@ mod tests {
         assert!(result.has_conflicts());
         assert_eq!(result.success_count(), 1);
     }
+
+    #[test]
+    fn test_apply_edit_plan() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        crate::config::initialize_repo(&repo_path, Some("https://test.bitbucket.com".to_string()))
+            .unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        let base = git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("edit".to_string(), Some(base.clone()), None)
+            .unwrap();
+        let mut ids = Vec::new();
+        for name in ["a", "b", "c", "d"] {
+            git(&["checkout", "-b", &format!("edit-{name}")]);
+            std::fs::write(repo_path.join(format!("{name}.txt")), name).unwrap();
+            git(&["add", "."]);
+            git(&["commit", "-m", &format!("Add {name}")]);
+            let hash = git(&["rev-parse", "HEAD"]);
+            ids.push(manager.get_stack_mut(&stack_id).unwrap().push_entry(
+                format!("edit-{name}"),
+                hash,
+                format!("Add {name}"),
+            ));
+        }
+        manager.save_to_disk().unwrap();
+        git(&["checkout", &base]);
+
+        // c first, then a reworded with b folded in; d is left out (dropped)
+        let steps = vec![
+            EditStep {
+                entry_id: ids[2],
+                action: EditAction::Pick,
+            },
+            EditStep {
+                entry_id: ids[0],
+                action: EditAction::Reword("Add a and b".to_string()),
+            },
+            EditStep {
+                entry_id: ids[1],
+                action: EditAction::Squash,
+            },
+        ];
+
+        let git_repo = GitRepository::open(&repo_path).unwrap();
+        let mut rebase_manager = RebaseManager::new(manager, git_repo, RebaseOptions::default());
+        let result = rebase_manager.apply_edit_plan(&stack_id, &steps).unwrap();
+        assert!(result.success);
+        let manager = rebase_manager.into_stack_manager();
+
+        let stack = manager.get_stack(&stack_id).unwrap();
+        let branches: Vec<_> = stack.entries.iter().map(|e| e.branch.as_str()).collect();
+        assert_eq!(branches, vec!["edit-c", "edit-a"]);
+        assert_eq!(stack.entries[1].message, "Add a and b");
+        assert_eq!(stack.entries[1].parent_id, Some(ids[2]));
+
+        assert_eq!(git(&["rev-parse", "edit-c^"]), git(&["rev-parse", &base]));
+        assert_eq!(
+            git(&["rev-parse", "edit-a^"]),
+            git(&["rev-parse", "edit-c"])
+        );
+        assert_eq!(git(&["log", "-1", "--format=%s", "edit-a"]), "Add a and b");
+        let files = git(&["ls-tree", "--name-only", "edit-a"]);
+        assert!(files.contains("a.txt") && files.contains("b.txt") && files.contains("c.txt"));
+        assert!(!files.contains("d.txt"));
+
+        // Re-applying the current order leaves every commit alone
+        let git_repo = GitRepository::open(&repo_path).unwrap();
+        let mut rebase_manager = RebaseManager::new(manager, git_repo, RebaseOptions::default());
+        let unchanged = vec![
+            EditStep {
+                entry_id: ids[2],
+                action: EditAction::Pick,
+            },
+            EditStep {
+                entry_id: ids[0],
+                action: EditAction::Pick,
+            },
+        ];
+        let result = rebase_manager
+            .apply_edit_plan(&stack_id, &unchanged)
+            .unwrap();
+        assert!(result.branch_mapping.is_empty());
+
+        // A squash with nothing above it is rejected
+        let bad_plan = vec![EditStep {
+            entry_id: ids[2],
+            action: EditAction::Squash,
+        }];
+        assert!(rebase_manager
+            .apply_edit_plan(&stack_id, &bad_plan)
+            .is_err());
+    }
+
+    #[test]
+    fn test_working_branch_check_skips_old_base_commits_only() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        crate::config::initialize_repo(&repo_path, Some("https://test.bitbucket.com".to_string()))
+            .unwrap();
+        std::fs::write(repo_path.join(".git/info/exclude"), ".cascade/\n").unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let commit = |file: &str, message: &str| {
+            std::fs::write(repo_path.join(file), message).unwrap();
+            git(&["add", file]);
+            git(&["commit", "-m", message]);
+        };
+
+        let base = git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+        git(&["checkout", "-b", "develop"]);
+        commit("develop.txt", "Develop work");
+        git(&["checkout", &base]);
+        commit("old.txt", "Old base work");
+
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("filter".to_string(), Some(base.clone()), None)
+            .unwrap();
+        for name in ["a", "b"] {
+            git(&["checkout", "-b", &format!("filter-{name}")]);
+            commit(&format!("{name}.txt"), &format!("Add {name}"));
+            let hash = git(&["rev-parse", "HEAD"]);
+            manager.get_stack_mut(&stack_id).unwrap().push_entry(
+                format!("filter-{name}"),
+                hash,
+                format!("Add {name}"),
+            );
+        }
+        git(&["checkout", "-b", "filter-work"]);
+        manager
+            .update_stack_working_branch("filter", "filter-work".to_string())
+            .unwrap();
+        manager.save_to_disk().unwrap();
+
+        let options = || RebaseOptions {
+            target_base: Some("develop".to_string()),
+            skip_pull: Some(true),
+            ..RebaseOptions::default()
+        };
+
+        // Moving onto develop leaves the old base's commit below the working branch;
+        // it is not new work, so the working branch follows the top entry
+        let git_repo = GitRepository::open(&repo_path).unwrap();
+        let mut rebase_manager = RebaseManager::new(manager, git_repo, options());
+        assert!(rebase_manager.rebase_stack(&stack_id).unwrap().success);
+        let mut manager = rebase_manager.into_stack_manager();
+        assert_eq!(
+            git(&["rev-parse", "filter-work"]),
+            git(&["rev-parse", "filter-b"])
+        );
+        manager
+            .set_stack_config(&stack_id, "base", Some("develop"))
+            .unwrap();
+
+        // A commit made on the working branch itself is kept and stops the sync
+        git(&["checkout", "filter-work"]);
+        commit("wip.txt", "Work in progress");
+        let wip = git(&["rev-parse", "HEAD"]);
+        git(&["checkout", "develop"]);
+        commit("more.txt", "More develop work");
+        git(&["checkout", "filter-work"]);
+
+        let git_repo = GitRepository::open(&repo_path).unwrap();
+        let mut rebase_manager = RebaseManager::new(manager, git_repo, options());
+        let err = rebase_manager.rebase_stack(&stack_id).unwrap_err();
+        assert!(err.to_string().contains("1 untracked commit"));
+        assert_eq!(git(&["rev-parse", "filter-work"]), wip);
+    }
+
+    #[test]
+    fn test_edit_plan_keeps_every_commit_of_a_grouped_entry() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        crate::config::initialize_repo(&repo_path, Some("https://test.bitbucket.com".to_string()))
+            .unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let commit = |name: &str| {
+            std::fs::write(repo_path.join(format!("{name}.txt")), name).unwrap();
+            git(&["add", "."]);
+            git(&["commit", "-m", &format!("Add {name}")]);
+            git(&["rev-parse", "HEAD"])
+        };
+
+        let base = git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("group".to_string(), Some(base.clone()), None)
+            .unwrap();
+
+        // Entry one covers two commits, entry two a single one
+        git(&["checkout", "-b", "group-one"]);
+        commit("a");
+        let tip = commit("b");
+        let stack = manager.get_stack_mut(&stack_id).unwrap();
+        let one = stack.push_entry("group-one".to_string(), tip, "Add a".to_string());
+        stack.set_entry_commit_count(&one, 2);
+        git(&["checkout", "-b", "group-two"]);
+        let tip = commit("c");
+        let two = manager.get_stack_mut(&stack_id).unwrap().push_entry(
+            "group-two".to_string(),
+            tip,
+            "Add c".to_string(),
+        );
+        manager.save_to_disk().unwrap();
+        git(&["checkout", &base]);
+
+        let steps = vec![
+            EditStep {
+                entry_id: two,
+                action: EditAction::Pick,
+            },
+            EditStep {
+                entry_id: one,
+                action: EditAction::Pick,
+            },
+        ];
+        let git_repo = GitRepository::open(&repo_path).unwrap();
+        let mut rebase_manager = RebaseManager::new(manager, git_repo, RebaseOptions::default());
+        assert!(
+            rebase_manager
+                .apply_edit_plan(&stack_id, &steps)
+                .unwrap()
+                .success
+        );
+        let manager = rebase_manager.into_stack_manager();
+
+        let stack = manager.get_stack(&stack_id).unwrap();
+        assert_eq!(stack.entries[1].branch, "group-one");
+        assert_eq!(stack.entries[1].commit_count, 2);
+        assert_eq!(
+            git(&["log", "--format=%s", "group-two..group-one"]),
+            "Add b\nAdd a"
+        );
+        assert_eq!(
+            git(&["rev-parse", "group-one~2"]),
+            git(&["rev-parse", "group-two"])
+        );
+    }
+
+    #[test]
+    fn test_amend_entry_folds_staged_changes() {
+        let (_temp_dir, repo_path) = create_test_repo();
+        crate::config::initialize_repo(&repo_path, Some("https://test.bitbucket.com".to_string()))
+            .unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        let base = git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+        let mut manager = StackManager::new(&repo_path).unwrap();
+        let stack_id = manager
+            .create_stack("amend".to_string(), Some(base), None)
+            .unwrap();
+        let mut ids = Vec::new();
+        for name in ["a", "b"] {
+            git(&["checkout", "-b", &format!("amend-{name}")]);
+            std::fs::write(repo_path.join(format!("{name}.txt")), name).unwrap();
+            git(&["add", "."]);
+            git(&["commit", "-m", &format!("Add {name}")]);
+            let hash = git(&["rev-parse", "HEAD"]);
+            ids.push(manager.get_stack_mut(&stack_id).unwrap().push_entry(
+                format!("amend-{name}"),
+                hash,
+                format!("Add {name}"),
+            ));
+        }
+        manager.save_to_disk().unwrap();
+
+        // Stage a follow-up to entry #1 while sitting on the top branch
+        std::fs::write(repo_path.join("a.txt"), "a, revised").unwrap();
+        git(&["add", "a.txt"]);
+
+        let git_repo = GitRepository::open(&repo_path).unwrap();
+        let fixup = git_repo
+            .snapshot_staged_changes("fixup! Add a")
+            .unwrap()
+            .unwrap();
+        let mut rebase_manager = RebaseManager::new(manager, git_repo, RebaseOptions::default());
+        let result = rebase_manager
+            .amend_entry(&stack_id, &ids[0], &fixup, None)
+            .unwrap();
+        assert_eq!(result.branch_mapping.len(), 2);
+
+        assert_eq!(git(&["show", "amend-a:a.txt"]), "a, revised");
+        assert_eq!(git(&["log", "-1", "--format=%s", "amend-a"]), "Add a");
+        assert_eq!(
+            git(&["rev-parse", "amend-b^"]),
+            git(&["rev-parse", "amend-a"])
+        );
+        assert_eq!(git(&["status", "--porcelain", "--", "a.txt"]), "");
+
+        let manager = rebase_manager.into_stack_manager();
+        let stack = manager.get_stack(&stack_id).unwrap();
+        assert_eq!(stack.entries[0].commit_hash, git(&["rev-parse", "amend-a"]));
+    }
 }
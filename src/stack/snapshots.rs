@@ -0,0 +1,253 @@
+//! Snapshots of all stacks, taken before destructive operations
+//!
+//! Before a rebase, a land or `validate --fix reset`, Cascade writes
+//! `.cascade/snapshots/<timestamp>-<operation>.tar.gz` holding the stack metadata and the
+//! commit every stack branch pointed at. Where `ca backups` restores one branch,
+//! `ca snapshots restore` puts the whole set back at once.
+
+use crate::errors::{CascadeError, Result};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Directory under `.cascade` that holds snapshots
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+
+const EXTENSION: &str = ".tar.gz";
+const MANIFEST: &str = "snapshot.json";
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// What a snapshot records besides the metadata files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// Operation the snapshot was taken before, e.g. `rebase`
+    pub operation: String,
+    pub created_at: DateTime<Utc>,
+    /// Branch that was checked out
+    pub current_branch: Option<String>,
+    /// Commit each stack branch pointed at
+    pub branches: BTreeMap<String, String>,
+}
+
+/// A snapshot archive on disk
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// File name without extension, e.g. `20250314T092653Z-rebase`, accepted by
+    /// `ca snapshots restore`
+    pub id: String,
+    pub path: PathBuf,
+    pub manifest: SnapshotManifest,
+}
+
+/// Snapshot directory of the Cascade config directory `config_dir`
+pub fn snapshots_dir(config_dir: &Path) -> PathBuf {
+    config_dir.join(SNAPSHOTS_DIR)
+}
+
+/// Write a snapshot holding `manifest` and the metadata `files` (name and contents)
+pub fn write_snapshot(
+    config_dir: &Path,
+    manifest: SnapshotManifest,
+    files: &[(&str, Vec<u8>)],
+) -> Result<Snapshot> {
+    let dir = snapshots_dir(config_dir);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| CascadeError::config(format!("Failed to create {}: {e}", dir.display())))?;
+
+    // Snapshots are keyed by second; step past any taken earlier in the same second
+    let mut at = manifest.created_at;
+    let (id, path) = loop {
+        let id = format!("{}-{}", at.format(TIMESTAMP_FORMAT), manifest.operation);
+        let path = dir.join(format!("{id}{EXTENSION}"));
+        if !path.exists() {
+            break (id, path);
+        }
+        at += chrono::Duration::seconds(1);
+    };
+
+    let mut archive = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| CascadeError::config(format!("Failed to serialize snapshot: {e}")))?;
+    append(&mut archive, MANIFEST, &manifest_json, manifest.created_at)?;
+    for (name, contents) in files {
+        append(&mut archive, name, contents, manifest.created_at)?;
+    }
+    let bytes = archive
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| CascadeError::config(format!("Failed to write snapshot {id}: {e}")))?;
+    crate::utils::atomic_file::write_bytes(&path, &bytes)?;
+
+    debug!("Wrote snapshot {}", path.display());
+    Ok(Snapshot { id, path, manifest })
+}
+
+/// All readable snapshots, newest first
+pub fn list_snapshots(config_dir: &Path) -> Result<Vec<Snapshot>> {
+    let dir = snapshots_dir(config_dir);
+    let Ok(read_dir) = std::fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut snapshots = Vec::new();
+    for path in read_dir.flatten().map(|entry| entry.path()) {
+        let Some(id) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(EXTENSION))
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        match read_files(&path).and_then(|files| parse_manifest(&files)) {
+            Ok(manifest) => snapshots.push(Snapshot { id, path, manifest }),
+            Err(e) => debug!("Skipping unreadable snapshot {}: {}", path.display(), e),
+        }
+    }
+
+    snapshots.sort_by(|a, b| {
+        b.manifest
+            .created_at
+            .cmp(&a.manifest.created_at)
+            .then_with(|| b.id.cmp(&a.id))
+    });
+    Ok(snapshots)
+}
+
+/// The snapshot with id `id`, or the newest one
+pub fn find_snapshot(config_dir: &Path, id: Option<&str>) -> Result<Snapshot> {
+    let snapshots = list_snapshots(config_dir)?;
+    match id {
+        Some(id) => snapshots
+            .into_iter()
+            .find(|snapshot| snapshot.id == id)
+            .ok_or_else(|| {
+                CascadeError::config(format!(
+                    "No snapshot '{id}'. Run 'ca snapshots list' to see available snapshots."
+                ))
+            }),
+        None => snapshots
+            .into_iter()
+            .next()
+            .ok_or_else(|| CascadeError::config("No snapshots found")),
+    }
+}
+
+/// Metadata files stored in a snapshot, by name
+pub fn snapshot_files(snapshot: &Snapshot) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut files = read_files(&snapshot.path)?;
+    files.remove(MANIFEST);
+    Ok(files)
+}
+
+fn parse_manifest(files: &BTreeMap<String, Vec<u8>>) -> Result<SnapshotManifest> {
+    let manifest = files
+        .get(MANIFEST)
+        .ok_or_else(|| CascadeError::config(format!("Snapshot has no {MANIFEST}")))?;
+    serde_json::from_slice(manifest)
+        .map_err(|e| CascadeError::config(format!("Invalid {MANIFEST}: {e}")))
+}
+
+fn read_files(path: &Path) -> Result<BTreeMap<String, Vec<u8>>> {
+    let read_error =
+        |e: std::io::Error| CascadeError::config(format!("Failed to read {}: {e}", path.display()));
+    let file = std::fs::File::open(path).map_err(read_error)?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+    let mut files = BTreeMap::new();
+    for entry in archive.entries().map_err(read_error)? {
+        let mut entry = entry.map_err(read_error)?;
+        let name = entry
+            .path()
+            .map_err(read_error)?
+            .to_string_lossy()
+            .to_string();
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents).map_err(read_error)?;
+        files.insert(name, contents);
+    }
+    Ok(files)
+}
+
+fn append<W: std::io::Write>(
+    archive: &mut tar::Builder<W>,
+    name: &str,
+    contents: &[u8],
+    modified: DateTime<Utc>,
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(modified.timestamp().max(0) as u64);
+    header.set_cksum();
+    archive
+        .append_data(&mut header, name, contents)
+        .map_err(|e| CascadeError::config(format!("Failed to add {name} to snapshot: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn manifest(operation: &str, at: DateTime<Utc>) -> SnapshotManifest {
+        SnapshotManifest {
+            operation: operation.to_string(),
+            created_at: at,
+            current_branch: Some("login".to_string()),
+            branches: BTreeMap::from([("part-one".to_string(), "a".repeat(40))]),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let config_dir = TempDir::new().unwrap();
+        let at = Utc.with_ymd_and_hms(2025, 3, 14, 9, 26, 53).unwrap();
+
+        let first = write_snapshot(
+            config_dir.path(),
+            manifest("rebase", at),
+            &[("stacks.json", b"{}".to_vec())],
+        )
+        .unwrap();
+        assert_eq!(first.id, "20250314T092653Z-rebase");
+        // A second snapshot in the same second gets the next one
+        let second = write_snapshot(config_dir.path(), manifest("rebase", at), &[]).unwrap();
+        assert_eq!(second.id, "20250314T092654Z-rebase");
+        let land = manifest("land", at + chrono::Duration::minutes(5));
+        write_snapshot(config_dir.path(), land, &[]).unwrap();
+
+        let ids: Vec<String> = list_snapshots(config_dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|snapshot| snapshot.id)
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                "20250314T093153Z-land",
+                "20250314T092654Z-rebase",
+                "20250314T092653Z-rebase"
+            ]
+        );
+
+        let found = find_snapshot(config_dir.path(), Some(&first.id)).unwrap();
+        assert_eq!(found.manifest, manifest("rebase", at));
+        assert_eq!(
+            snapshot_files(&found).unwrap(),
+            BTreeMap::from([("stacks.json".to_string(), b"{}".to_vec())])
+        );
+        assert_eq!(
+            find_snapshot(config_dir.path(), None).unwrap().id,
+            "20250314T093153Z-land"
+        );
+        assert!(find_snapshot(config_dir.path(), Some("missing")).is_err());
+    }
+}
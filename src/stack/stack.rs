@@ -1,3 +1,6 @@
+use crate::bitbucket::pull_request::MergeStrategy;
+use crate::config::StackTemplate;
+use crate::errors::CascadeError;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -31,6 +34,171 @@ pub struct StackEntry {
     /// Whether this entry's PR has been merged
     #[serde(default)]
     pub is_merged: bool,
+    /// Commit the remote branch pointed at after Cascade last pushed it, used as the
+    /// lease for force pushes
+    #[serde(default)]
+    pub remote_commit_hash: Option<String>,
+    /// Force pushes that replaced the remote branch's commit, oldest first
+    #[serde(default)]
+    pub rewrites: Vec<CommitRewrite>,
+    /// Commit this entry was cherry-picked from with `ca pick`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picked_from: Option<String>,
+    /// Commits the entry covers, ending at `commit_hash` and following first parents
+    #[serde(
+        default = "default_commit_count",
+        skip_serializing_if = "is_single_commit"
+    )]
+    pub commit_count: usize,
+    /// Labels of this entry, listed on its pull request after the stack's
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+}
+
+fn default_commit_count() -> usize {
+    1
+}
+
+fn is_single_commit(count: &usize) -> bool {
+    *count == 1
+}
+
+/// A force push that replaced an entry's remote commit with another
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommitRewrite {
+    /// Commit reviewers last saw on the remote branch
+    pub old_commit: String,
+    /// Commit that replaced it
+    pub new_commit: String,
+    pub rewritten_at: DateTime<Utc>,
+}
+
+/// Rewrites kept per entry; older ones are dropped
+const MAX_REWRITES: usize = 20;
+
+/// An entry taken out of the stack because its change became obsolete (e.g. it landed
+/// upstream in another form)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SupersededEntry {
+    pub message: String,
+    /// Commit the entry had when it was removed
+    pub commit_hash: String,
+    pub branch: String,
+    /// PR that was closed along with it
+    pub pull_request_id: Option<String>,
+    pub superseded_at: DateTime<Utc>,
+}
+
+/// Keys accepted by `ca stacks config <name> set`
+pub const STACK_CONFIG_KEYS: &[&str] = &[
+    "base",
+    "merge_strategy",
+    "reviewers",
+    "draft",
+    "branch_prefix",
+    "labels",
+    "pr_description_template",
+];
+
+/// Settings a stack uses instead of the repository configuration; unset fields fall back
+/// to it
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StackOverrides {
+    /// Merge strategy `ca land` uses unless `--strategy` is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_strategy: Option<MergeStrategy>,
+    /// Reviewers added to the stack's pull requests instead of `bitbucket.default_reviewers`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewers: Option<Vec<String>>,
+    /// Whether `ca submit` creates drafts, instead of `submit_policy.draft_by_default`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft: Option<bool>,
+    /// Put in front of generated entry branch names
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_prefix: Option<String>,
+    /// Labels listed on the stack's pull requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+    /// Description new pull requests start with, instead of `cascade.pr_description_template`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_description_template: Option<String>,
+}
+
+impl StackOverrides {
+    /// Overrides a stack created from `template` starts with
+    pub fn from_template(template: &StackTemplate) -> Self {
+        Self {
+            reviewers: (!template.reviewers.is_empty()).then(|| template.reviewers.clone()),
+            branch_prefix: template.branch_prefix.clone(),
+            labels: (!template.labels.is_empty()).then(|| template.labels.clone()),
+            pr_description_template: template.pr_description_template.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// Set `key` to `value`, or clear the override with `None`
+    pub fn set(&mut self, key: &str, value: Option<&str>) -> crate::errors::Result<()> {
+        let list = |value: &str| -> Vec<String> {
+            value
+                .split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        };
+        match key {
+            "merge_strategy" => self.merge_strategy = value.map(str::parse).transpose()?,
+            "reviewers" => self.reviewers = value.map(list),
+            "labels" => self.labels = value.map(list),
+            "branch_prefix" => self.branch_prefix = value.map(str::to_string),
+            "pr_description_template" => self.pr_description_template = value.map(str::to_string),
+            "draft" => {
+                self.draft = value
+                    .map(|value| {
+                        value.parse().map_err(|_| {
+                            CascadeError::config(format!("Invalid boolean value: {value}"))
+                        })
+                    })
+                    .transpose()?
+            }
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+
+    /// Value of the override `key`, `None` when it isn't set
+    pub fn get(&self, key: &str) -> crate::errors::Result<Option<String>> {
+        Ok(match key {
+            "merge_strategy" => self
+                .merge_strategy
+                .as_ref()
+                .map(|strategy| strategy.as_str().to_string()),
+            "reviewers" => self.reviewers.as_ref().map(|reviewers| reviewers.join(",")),
+            "draft" => self.draft.map(|draft| draft.to_string()),
+            "branch_prefix" => self.branch_prefix.clone(),
+            "labels" => self.labels.as_ref().map(|labels| labels.join(",")),
+            "pr_description_template" => self.pr_description_template.clone(),
+            _ => return Err(unknown_key(key)),
+        })
+    }
+}
+
+/// Check a label given on the command line, returning it trimmed. Labels are stored in
+/// comma-separated settings, so they can't contain commas or whitespace.
+pub fn validate_label(label: &str) -> crate::errors::Result<String> {
+    let label = label.trim();
+    if label.is_empty() || label.contains(',') || label.contains(char::is_whitespace) {
+        return Err(CascadeError::validation(format!(
+            "Invalid label '{label}': labels can't be empty or contain commas or spaces"
+        )));
+    }
+    Ok(label.to_string())
+}
+
+fn unknown_key(key: &str) -> CascadeError {
+    CascadeError::config(format!(
+        "Unknown stack setting '{key}' (expected one of: {})",
+        STACK_CONFIG_KEYS.join(", ")
+    ))
 }
 
 /// Represents the status of a stack
@@ -77,6 +245,15 @@ pub struct Stack {
     pub updated_at: DateTime<Utc>,
     /// Whether this stack is active (current working stack)
     pub is_active: bool,
+    /// Stack this one is built on top of (its base is the parent's top entry branch)
+    #[serde(default)]
+    pub depends_on: Option<Uuid>,
+    /// Entries dropped as obsolete, oldest first
+    #[serde(default)]
+    pub superseded: Vec<SupersededEntry>,
+    /// Settings this stack uses instead of the repository configuration
+    #[serde(default)]
+    pub overrides: StackOverrides,
 }
 
 impl Stack {
@@ -95,9 +272,24 @@ impl Stack {
             created_at: now,
             updated_at: now,
             is_active: false,
+            depends_on: None,
+            superseded: Vec::new(),
+            overrides: StackOverrides::default(),
         }
     }
 
+    /// Remember an entry that was removed from the stack as obsolete
+    pub fn record_superseded(&mut self, entry: &StackEntry) {
+        self.superseded.push(SupersededEntry {
+            message: entry.message.clone(),
+            commit_hash: entry.commit_hash.clone(),
+            branch: entry.branch.clone(),
+            pull_request_id: entry.pull_request_id.clone(),
+            superseded_at: Utc::now(),
+        });
+        self.updated_at = Utc::now();
+    }
+
     /// Add a new entry to the top of the stack
     pub fn push_entry(&mut self, branch: String, commit_hash: String, message: String) -> Uuid {
         let now = Utc::now();
@@ -119,6 +311,11 @@ impl Stack {
             pull_request_id: None,
             is_synced: false,
             is_merged: false,
+            remote_commit_hash: None,
+            rewrites: Vec::new(),
+            picked_from: None,
+            commit_count: 1,
+            labels: Vec::new(),
         };
 
         // Update parent's children if exists
@@ -136,6 +333,28 @@ impl Stack {
         entry_id
     }
 
+    /// Append an existing entry (e.g. one moved from another stack) to the top of the stack,
+    /// keeping its ID, branch and PR link
+    pub fn adopt_entry(&mut self, mut entry: StackEntry) {
+        let now = Utc::now();
+        let parent_id = self.entries.last().map(|e| e.id);
+
+        entry.parent_id = parent_id;
+        entry.children.clear();
+        entry.updated_at = now;
+
+        if let Some(parent_id) = parent_id {
+            if let Some(parent) = self.entry_map.get_mut(&parent_id) {
+                parent.children.push(entry.id);
+            }
+        }
+
+        self.entries.push(entry.clone());
+        self.entry_map.insert(entry.id, entry);
+        self.sync_entries_from_map();
+        self.updated_at = now;
+    }
+
     /// Remove the top entry from the stack
     pub fn pop_entry(&mut self) -> Option<StackEntry> {
         if let Some(entry) = self.entries.pop() {
@@ -162,9 +381,10 @@ impl Stack {
             return None;
         }
 
+        // The map holds the authoritative parent/children links
         let entry = self.entries.remove(index);
         let entry_id = entry.id;
-        self.entry_map.remove(&entry_id);
+        let entry = self.entry_map.remove(&entry_id).unwrap_or(entry);
 
         // Reparent children to the removed entry's parent
         for &child_id in &entry.children {
@@ -190,6 +410,35 @@ impl Stack {
         Some(entry)
     }
 
+    /// Rebuild the entry chain in the given order. Entries whose IDs are not listed are
+    /// removed and returned (in their original order).
+    pub fn set_entry_order(&mut self, order: &[Uuid]) -> Vec<StackEntry> {
+        let mut remaining = std::mem::take(&mut self.entry_map);
+
+        let mut entries: Vec<StackEntry> = Vec::with_capacity(order.len());
+        for id in order {
+            if let Some(mut entry) = remaining.remove(id) {
+                entry.parent_id = entries.last().map(|parent| parent.id);
+                entry.children.clear();
+                if let Some(parent) = entries.last_mut() {
+                    parent.children.push(entry.id);
+                }
+                entries.push(entry);
+            }
+        }
+
+        let removed = self
+            .entries
+            .iter()
+            .filter_map(|entry| remaining.remove(&entry.id))
+            .collect();
+
+        self.entry_map = entries.iter().map(|e| (e.id, e.clone())).collect();
+        self.entries = entries;
+        self.updated_at = Utc::now();
+        removed
+    }
+
     /// Get an entry by ID
     pub fn get_entry(&self, id: &Uuid) -> Option<&StackEntry> {
         self.entry_map.get(id)
@@ -243,6 +492,15 @@ impl Stack {
         self.entries.last()
     }
 
+    /// Position of the entry `branch` belongs to, when that entry has others stacked on top.
+    /// Merged entries are skipped; committing on their branches doesn't touch the stack.
+    pub fn lower_entry_index(&self, branch: &str) -> Option<usize> {
+        let last = self.entries.len().checked_sub(1)?;
+        self.entries[..last]
+            .iter()
+            .position(|entry| entry.branch == branch && !entry.is_merged)
+    }
+
     /// Get all entries that are children of the given entry
     pub fn get_children(&self, entry_id: &Uuid) -> Vec<&StackEntry> {
         if let Some(entry) = self.get_entry(entry_id) {
@@ -294,6 +552,20 @@ impl Stack {
         }
     }
 
+    /// Detach an entry from its pull request so it can be submitted again
+    pub fn mark_entry_unsubmitted(&mut self, entry_id: &Uuid) -> bool {
+        if let Some(entry) = self.get_entry_mut(entry_id) {
+            entry.is_submitted = false;
+            entry.pull_request_id = None;
+            entry.updated_at = Utc::now();
+            self.updated_at = Utc::now();
+            self.sync_entries_from_map();
+            true
+        } else {
+            false
+        }
+    }
+
     /// Synchronize the entries vector with the entry_map (entry_map is source of truth)
     fn sync_entries_from_map(&mut self) {
         for entry in &mut self.entries {
@@ -336,6 +608,95 @@ impl Stack {
         }
     }
 
+    /// Record the commit the entry's remote branch points at after a push.
+    ///
+    /// When this replaces a different commit, the old→new pair is kept in `rewrites`.
+    pub fn set_entry_remote_commit(&mut self, entry_id: &Uuid, commit_hash: String) -> bool {
+        if let Some(entry) = self.get_entry_mut(entry_id) {
+            if let Some(old_commit) = entry.remote_commit_hash.take() {
+                if old_commit != commit_hash {
+                    entry.rewrites.push(CommitRewrite {
+                        old_commit,
+                        new_commit: commit_hash.clone(),
+                        rewritten_at: Utc::now(),
+                    });
+                    let excess = entry.rewrites.len().saturating_sub(MAX_REWRITES);
+                    entry.rewrites.drain(..excess);
+                }
+            }
+            entry.remote_commit_hash = Some(commit_hash);
+            entry.updated_at = Utc::now();
+            self.updated_at = Utc::now();
+            self.sync_entries_from_map();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Record the commit a `ca pick` entry was cherry-picked from
+    pub fn set_entry_picked_from(&mut self, entry_id: &Uuid, source_commit: String) -> bool {
+        if let Some(entry) = self.get_entry_mut(entry_id) {
+            entry.picked_from = Some(source_commit);
+            entry.updated_at = Utc::now();
+            self.updated_at = Utc::now();
+            self.sync_entries_from_map();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Set how many commits, ending at its commit, an entry covers
+    pub fn set_entry_commit_count(&mut self, entry_id: &Uuid, commit_count: usize) -> bool {
+        if let Some(entry) = self.get_entry_mut(entry_id) {
+            entry.commit_count = commit_count.max(1);
+            entry.updated_at = Utc::now();
+            self.updated_at = Utc::now();
+            self.sync_entries_from_map();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Replace an entry's labels
+    pub fn set_entry_labels(&mut self, entry_id: &Uuid, labels: Vec<String>) -> bool {
+        if let Some(entry) = self.get_entry_mut(entry_id) {
+            entry.labels = labels;
+            entry.updated_at = Utc::now();
+            self.updated_at = Utc::now();
+            self.sync_entries_from_map();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Labels of the stack itself
+    pub fn labels(&self) -> &[String] {
+        self.overrides.labels.as_deref().unwrap_or_default()
+    }
+
+    /// Labels listed on an entry's pull request: the stack's, then the entry's own
+    pub fn pr_labels(&self, entry: &StackEntry) -> Vec<String> {
+        let mut labels = self.labels().to_vec();
+        for label in &entry.labels {
+            if !labels.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+                labels.push(label.clone());
+            }
+        }
+        labels
+    }
+
+    /// Whether the stack or one of its entries carries `label`, ignoring case
+    pub fn has_label(&self, label: &str) -> bool {
+        self.labels()
+            .iter()
+            .chain(self.entries.iter().flat_map(|entry| &entry.labels))
+            .any(|l| l.eq_ignore_ascii_case(label))
+    }
+
     /// Update stack status
     pub fn update_status(&mut self, status: StackStatus) {
         self.status = status;
@@ -348,6 +709,17 @@ impl Stack {
         self.updated_at = Utc::now();
     }
 
+    /// Branch that a dependent stack should use as its base: the top unmerged
+    /// entry branch, or this stack's own base once everything has merged
+    pub fn dependency_base_branch(&self) -> String {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| !entry.is_merged)
+            .map(|entry| entry.branch.clone())
+            .unwrap_or_else(|| self.base_branch.clone())
+    }
+
     /// Get all branch names in this stack
     pub fn get_branch_names(&self) -> Vec<String> {
         self.entries
@@ -533,6 +905,11 @@ impl StackEntry {
         }
     }
 
+    /// Whether the entry covers more than one commit
+    pub fn is_grouped(&self) -> bool {
+        self.commit_count > 1
+    }
+
     /// Get a short version of the commit message
     pub fn short_message(&self, max_len: usize) -> String {
         let trimmed = self.message.trim();
@@ -716,6 +1093,30 @@ mod tests {
         assert!(!stack.get_entry(&entry_id).unwrap().is_merged);
     }
 
+    #[test]
+    fn test_dependency_base_branch() {
+        let mut stack = Stack::new("parent".to_string(), "main".to_string(), None);
+        assert_eq!(stack.dependency_base_branch(), "main");
+
+        let first = stack.push_entry(
+            "parent-1".to_string(),
+            "hash1".to_string(),
+            "msg1".to_string(),
+        );
+        let second = stack.push_entry(
+            "parent-2".to_string(),
+            "hash2".to_string(),
+            "msg2".to_string(),
+        );
+        assert_eq!(stack.dependency_base_branch(), "parent-2");
+
+        stack.mark_entry_merged(&second, true);
+        assert_eq!(stack.dependency_base_branch(), "parent-1");
+
+        stack.mark_entry_merged(&first, true);
+        assert_eq!(stack.dependency_base_branch(), "main");
+    }
+
     #[test]
     fn test_branch_names() {
         let mut stack = Stack::new("test".to_string(), "main".to_string(), None);
@@ -736,4 +1137,156 @@ mod tests {
         let branches = stack.get_branch_names();
         assert_eq!(branches, vec!["feature-1", "feature-2"]);
     }
+
+    #[test]
+    fn test_lower_entry_index() {
+        let mut stack = Stack::new("test".to_string(), "main".to_string(), None);
+        assert_eq!(stack.lower_entry_index("feature-1"), None);
+
+        let first = stack.push_entry("feature-1".into(), "abc123".into(), "One".into());
+        assert_eq!(stack.lower_entry_index("feature-1"), None, "top entry");
+
+        stack.push_entry("feature-2".into(), "def456".into(), "Two".into());
+        stack.push_entry("feature-3".into(), "fed789".into(), "Three".into());
+        assert_eq!(stack.lower_entry_index("feature-1"), Some(0));
+        assert_eq!(stack.lower_entry_index("feature-2"), Some(1));
+        assert_eq!(stack.lower_entry_index("feature-3"), None);
+        assert_eq!(stack.lower_entry_index("main"), None);
+
+        stack.mark_entry_merged(&first, true);
+        assert_eq!(stack.lower_entry_index("feature-1"), None);
+    }
+
+    #[test]
+    fn test_remote_commit_changes_are_recorded_as_rewrites() {
+        let mut stack = Stack::new("test".to_string(), "main".to_string(), None);
+        let id = stack.push_entry(
+            "feature-1".to_string(),
+            "hash1".to_string(),
+            "msg1".to_string(),
+        );
+
+        stack.set_entry_remote_commit(&id, "aaa".to_string());
+        stack.set_entry_remote_commit(&id, "aaa".to_string());
+        assert!(stack.get_entry(&id).unwrap().rewrites.is_empty());
+
+        stack.set_entry_remote_commit(&id, "bbb".to_string());
+        let rewrites = &stack.get_entry(&id).unwrap().rewrites;
+        assert_eq!(rewrites.len(), 1);
+        assert_eq!(
+            (
+                rewrites[0].old_commit.as_str(),
+                rewrites[0].new_commit.as_str()
+            ),
+            ("aaa", "bbb")
+        );
+    }
+
+    #[test]
+    fn test_picked_from_is_recorded_and_persisted() {
+        let mut stack = Stack::new("test".to_string(), "main".to_string(), None);
+        let id = stack.push_entry(
+            "feature-1".to_string(),
+            "hash1".to_string(),
+            "msg1".to_string(),
+        );
+        assert!(!serde_json::to_string(&stack)
+            .unwrap()
+            .contains("picked_from"));
+
+        assert!(stack.set_entry_picked_from(&id, "source".to_string()));
+        assert!(!stack.set_entry_picked_from(&Uuid::new_v4(), "source".to_string()));
+
+        let loaded: Stack = serde_json::from_str(&serde_json::to_string(&stack).unwrap()).unwrap();
+        assert_eq!(loaded.entries[0].picked_from.as_deref(), Some("source"));
+    }
+
+    #[test]
+    fn test_commit_count_defaults_to_one_and_persists() {
+        let mut stack = Stack::new("test".to_string(), "main".to_string(), None);
+        let id = stack.push_entry(
+            "feature-1".to_string(),
+            "hash1".to_string(),
+            "msg1".to_string(),
+        );
+        assert!(!stack.entries[0].is_grouped());
+        assert!(!serde_json::to_string(&stack)
+            .unwrap()
+            .contains("commit_count"));
+
+        assert!(stack.set_entry_commit_count(&id, 3));
+        let loaded: Stack = serde_json::from_str(&serde_json::to_string(&stack).unwrap()).unwrap();
+        assert_eq!(loaded.entries[0].commit_count, 3);
+        assert!(loaded.entries[0].is_grouped());
+    }
+
+    #[test]
+    fn test_record_superseded_keeps_entry_details() {
+        let mut stack = Stack::new("test".to_string(), "main".to_string(), None);
+        let id = stack.push_entry(
+            "feature-1".to_string(),
+            "hash1".to_string(),
+            "Obsolete fix".to_string(),
+        );
+        stack.mark_entry_submitted(&id, "42".to_string());
+        let entry = stack.get_entry(&id).unwrap().clone();
+
+        stack.record_superseded(&entry);
+
+        assert_eq!(stack.superseded.len(), 1);
+        assert_eq!(stack.superseded[0].branch, "feature-1");
+        assert_eq!(stack.superseded[0].commit_hash, "hash1");
+        assert_eq!(stack.superseded[0].pull_request_id.as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_overrides_set_get_and_unset() {
+        let mut stack = Stack::new("hotfix".to_string(), "main".to_string(), None);
+        let overrides = &mut stack.overrides;
+
+        overrides.set("merge_strategy", Some("merge")).unwrap();
+        overrides.set("reviewers", Some("alice, bob")).unwrap();
+        overrides.set("draft", Some("false")).unwrap();
+        assert_eq!(overrides.merge_strategy, Some(MergeStrategy::Merge));
+        assert_eq!(
+            overrides.get("reviewers").unwrap().as_deref(),
+            Some("alice,bob")
+        );
+        assert_eq!(overrides.draft, Some(false));
+
+        overrides.set("draft", None).unwrap();
+        assert_eq!(overrides.get("draft").unwrap(), None);
+
+        assert!(overrides.set("merge_strategy", Some("rebase")).is_err());
+        assert!(overrides.set("color", Some("red")).is_err());
+
+        // Stacks saved before overrides existed load with none set
+        let mut json = serde_json::to_value(&stack).unwrap();
+        json.as_object_mut().unwrap().remove("overrides");
+        let loaded: Stack = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded.overrides, StackOverrides::default());
+    }
+
+    #[test]
+    fn test_labels_on_stack_and_entries() {
+        let mut stack = Stack::new("release".to_string(), "main".to_string(), None);
+        let first = stack.push_entry("a".into(), "a".repeat(40), "A".into());
+        stack.push_entry("b".into(), "b".repeat(40), "B".into());
+        stack.overrides.set("labels", Some("urgent")).unwrap();
+        assert!(stack.set_entry_labels(&first, vec!["URGENT".into(), "api".into()]));
+
+        assert_eq!(stack.get_entry(&first).unwrap().labels, ["URGENT", "api"]);
+        assert_eq!(stack.pr_labels(&stack.entries[0]), ["urgent", "api"]);
+        assert_eq!(stack.pr_labels(&stack.entries[1]), ["urgent"]);
+        assert!(stack.has_label("API"));
+        assert!(!stack.has_label("docs"));
+
+        assert_eq!(validate_label(" hotfix ").unwrap(), "hotfix");
+        assert!(validate_label("a,b").is_err());
+        assert!(validate_label("two words").is_err());
+
+        // Entries without labels don't write the field
+        let json = serde_json::to_value(&stack.entries[1]).unwrap();
+        assert!(json.get("labels").is_none());
+    }
 }
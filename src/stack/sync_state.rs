@@ -23,6 +23,12 @@ pub struct SyncState {
     pub current_temp_branch: String,
     /// All temp branches created so far (for cleanup)
     pub temp_branches: Vec<String>,
+    /// Stack base before `ca stacks retarget`, put back by `ca sync abort`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_base: Option<String>,
+    /// Commits of the current entry still to cherry-pick after the conflicting one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending_commits: Vec<String>,
 }
 
 impl SyncState {
@@ -76,6 +82,17 @@ impl SyncState {
         Ok(())
     }
 
+    /// Remember the stack's base from before a retarget in the paused sync, if there is one
+    pub fn record_previous_base(repo_root: &Path, previous_base: &str) -> Result<bool> {
+        if !Self::exists(repo_root) {
+            return Ok(false);
+        }
+        let mut state = Self::load(repo_root)?;
+        state.previous_base = Some(previous_base.to_string());
+        state.save(repo_root)?;
+        Ok(true)
+    }
+
     /// Check if sync state exists
     pub fn exists(repo_root: &Path) -> bool {
         crate::git::resolve_git_dir(repo_root)
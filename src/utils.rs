@@ -6,15 +6,28 @@ use std::path::Path;
 /// Platform-specific utilities for cross-platform compatibility
 pub mod platform;
 
+/// Rotating debug log behind `ca logs`
+pub mod logging;
+
 /// Terminal spinner utilities for progress indication
 pub mod spinner;
 
+/// Progress bars for fetch and push transfers
+pub mod progress;
+
+/// Ctrl-C handling that lets long operations stop at a safe point
+pub mod interrupt;
+
+/// Release lookup, install channel detection and checksums for `ca update`
+pub mod release;
+
 /// Atomic file operations to prevent corruption during writes
 pub mod atomic_file {
     use super::*;
 
     /// Write JSON data to a file atomically using a temporary file + rename strategy with file locking
     pub fn write_json<T: Serialize>(path: &Path, data: &T) -> Result<()> {
+        let path = &platform::long_path(path);
         with_concurrent_file_lock(path, || {
             let content = serde_json::to_string_pretty(data)
                 .map_err(|e| CascadeError::config(format!("Failed to serialize data: {e}")))?;
@@ -25,6 +38,7 @@ pub mod atomic_file {
 
     /// Write string content to a file atomically using a temporary file + rename strategy with file locking
     pub fn write_string(path: &Path, content: &str) -> Result<()> {
+        let path = &platform::long_path(path);
         with_concurrent_file_lock(path, || write_string_unlocked(path, content))
     }
 
@@ -112,6 +126,7 @@ pub mod atomic_file {
 
     /// Write binary data to a file atomically with file locking
     pub fn write_bytes(path: &Path, data: &[u8]) -> Result<()> {
+        let path = &platform::long_path(path);
         with_concurrent_file_lock(path, || {
             let temp_path = path.with_extension("tmp");
 
@@ -192,7 +207,10 @@ pub mod path_validation {
                 )));
             }
 
-            Ok(canonical_path)
+            // Keep the `\\?\` prefix canonicalize adds on Windows only where the length needs it
+            Ok(platform::long_path(&platform::strip_verbatim(
+                &canonical_path,
+            )))
         }
     }
 
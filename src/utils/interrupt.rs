@@ -0,0 +1,114 @@
+use crate::errors::CascadeError;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Exit status for a process ended by Ctrl-C (128 + SIGINT)
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+static STATE: InterruptState = InterruptState::new();
+
+/// How many operations are guarded and whether Ctrl-C asked them to stop
+struct InterruptState {
+    guards: AtomicUsize,
+    requested: AtomicBool,
+}
+
+impl InterruptState {
+    const fn new() -> Self {
+        Self {
+            guards: AtomicUsize::new(0),
+            requested: AtomicBool::new(false),
+        }
+    }
+
+    /// Record a Ctrl-C. Returns false when the process should exit instead: nothing is
+    /// guarded, or this is the second Ctrl-C
+    fn interrupt(&self) -> bool {
+        self.guards.load(Ordering::SeqCst) > 0 && !self.requested.swap(true, Ordering::SeqCst)
+    }
+
+    fn enter(&self) {
+        self.guards.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn exit(&self) {
+        // The last guard out clears the request so the next operation starts fresh
+        if self.guards.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.requested.store(false, Ordering::SeqCst);
+        }
+    }
+
+    fn requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+}
+
+/// Take over Ctrl-C for this process. Outside an [`InterruptGuard`] it exits right away, as
+/// before; inside one the first Ctrl-C asks the operation to stop at its next safe point
+/// and a second one exits.
+pub fn install() {
+    tokio::spawn(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if STATE.interrupt() {
+                eprintln!(
+                    "\nStopping at the next safe point... (press Ctrl-C again to force quit)"
+                );
+            } else {
+                std::process::exit(INTERRUPTED_EXIT_CODE);
+            }
+        }
+    });
+}
+
+/// Whether Ctrl-C was pressed while an operation was guarded
+pub fn requested() -> bool {
+    STATE.requested()
+}
+
+/// Error for an operation that stopped because of Ctrl-C
+pub fn interrupted_error(what: &str) -> CascadeError {
+    CascadeError::validation(format!("Interrupted: {what}"))
+}
+
+/// While alive, Ctrl-C is deferred to the holder's safe points
+pub struct InterruptGuard(());
+
+impl InterruptGuard {
+    pub fn new() -> Self {
+        STATE.enter();
+        Self(())
+    }
+}
+
+impl Default for InterruptGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        STATE.exit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ctrl_c_is_deferred_only_while_guarded() {
+        let state = InterruptState::new();
+        assert!(!state.interrupt(), "unguarded Ctrl-C exits");
+
+        state.enter();
+        state.enter();
+        assert!(state.interrupt(), "first Ctrl-C is deferred");
+        assert!(state.requested());
+        assert!(!state.interrupt(), "second Ctrl-C exits");
+
+        state.exit();
+        assert!(state.requested(), "still stopping while a guard is alive");
+        state.exit();
+        assert!(!state.requested(), "cleared once every guard is gone");
+    }
+}
@@ -159,12 +159,88 @@ pub fn shell_completion_dirs() -> Vec<(String, PathBuf)> {
 /// Get platform-specific Git hook script extension
 pub fn git_hook_extension() -> &'static str {
     if cfg!(windows) {
-        ".bat"
+        ".cmd"
     } else {
         ""
     }
 }
 
+/// Hook Git for Windows actually runs. Git looks for the extension-less hook name and
+/// runs it with its bundled sh, so this hands over to the `<hook>.cmd` next to it.
+/// Written with LF endings: sh chokes on CRLF.
+pub fn windows_hook_shim(hook_name: &str) -> String {
+    format!(
+        "#!/bin/sh\n\
+         # Cascade CLI Hook shim - {hook_name}\n\
+         # Git for Windows runs hooks through sh; the hook itself is {hook_name}.cmd\n\
+         exec cmd.exe //d //c \"$(cygpath -w \"$0\").cmd\" \"$@\"\n"
+    )
+}
+
+/// Convert to CRLF line endings, which cmd.exe needs to follow labels and `goto` reliably
+pub fn to_crlf(content: &str) -> String {
+    normalize_line_endings(content).replace('\n', "\r\n")
+}
+
+/// Whether branch names that differ only in case are the same branch on this platform.
+/// Git stores loose refs as files, so on the default Windows and macOS filesystems
+/// `Feature/x` and `feature/x` collide.
+pub fn case_insensitive_refs() -> bool {
+    cfg!(any(windows, target_os = "macos"))
+}
+
+/// Compare branch names the way this platform's ref storage does
+pub fn same_branch_name(a: &str, b: &str) -> bool {
+    if case_insensitive_refs() {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+/// Paths past this length need the `\\?\` prefix for Windows file APIs
+const WINDOWS_MAX_PATH: usize = 260;
+
+/// Make a long absolute path usable on Windows by giving it the `\\?\` prefix, which
+/// lifts the 260-character limit. Other paths, and every path on other platforms, are
+/// returned unchanged.
+pub fn long_path(path: &Path) -> PathBuf {
+    if cfg!(windows) {
+        if let Some(verbatim) = verbatim_form(&path.to_string_lossy()) {
+            return PathBuf::from(verbatim);
+        }
+    }
+    path.to_path_buf()
+}
+
+fn verbatim_form(path: &str) -> Option<String> {
+    if path.len() < WINDOWS_MAX_PATH || path.starts_with(r"\\?\") {
+        return None;
+    }
+    // Verbatim paths skip normalization, so they must use backslashes only
+    let path = path.replace('/', r"\");
+    if let Some(unc) = path.strip_prefix(r"\\") {
+        return Some(format!(r"\\?\UNC\{unc}"));
+    }
+    let bytes = path.as_bytes();
+    let is_drive_absolute =
+        bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\';
+    is_drive_absolute.then(|| format!(r"\\?\{path}"))
+}
+
+/// Drop the `\\?\` prefix `canonicalize` adds on Windows, for paths handed to git, hook
+/// scripts or the user, none of which understand it
+pub fn strip_verbatim(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    if let Some(unc) = text.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{unc}"))
+    } else if let Some(rest) = text.strip_prefix(r"\\?\") {
+        PathBuf::from(rest)
+    } else {
+        path.to_path_buf()
+    }
+}
+
 /// Create platform-specific Git hook content
 pub fn create_git_hook_content(hook_name: &str, command: &str) -> String {
     #[cfg(windows)]
@@ -229,6 +305,24 @@ fn which_shell(shell_name: &str) -> Option<PathBuf> {
     None
 }
 
+/// Find an executable in PATH. On Windows `name` may also be a `.exe`, `.cmd` or `.bat`
+/// file, so scripts work as well as binaries.
+pub fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    let candidates: &[&str] = if cfg!(windows) {
+        &[".exe", ".cmd", ".bat"]
+    } else {
+        &[""]
+    };
+
+    std::env::split_paths(&path_var).find_map(|dir| {
+        candidates
+            .iter()
+            .map(|extension| dir.join(format!("{name}{extension}")))
+            .find(|path| path.is_file() && is_executable(path))
+    })
+}
+
 /// Get platform-specific temporary directory with proper permissions
 pub fn secure_temp_dir() -> std::io::Result<PathBuf> {
     let temp_dir = std::env::temp_dir();
@@ -330,7 +424,7 @@ mod tests {
     fn test_git_hook_extension() {
         let ext = git_hook_extension();
         if cfg!(windows) {
-            assert_eq!(ext, ".bat");
+            assert_eq!(ext, ".cmd");
         } else {
             assert_eq!(ext, "");
         }
@@ -344,6 +438,66 @@ mod tests {
         assert_eq!(normalize_line_endings("hello world"), "hello world");
     }
 
+    #[test]
+    fn test_windows_hook_files() {
+        let crlf = to_crlf("@echo off\r\nset A=1\nexit /b 0\n");
+        assert_eq!(crlf, "@echo off\r\nset A=1\r\nexit /b 0\r\n");
+
+        let shim = windows_hook_shim("pre-push");
+        assert!(shim.starts_with("#!/bin/sh\n"));
+        assert!(!shim.contains('\r'), "sh can't run CRLF scripts");
+        assert!(shim.contains("pre-push.cmd"));
+    }
+
+    #[test]
+    fn test_long_paths() {
+        let long_dir = "a".repeat(WINDOWS_MAX_PATH);
+        assert_eq!(
+            verbatim_form(&format!(r"C:\repo\{long_dir}")),
+            Some(format!(r"\\?\C:\repo\{long_dir}"))
+        );
+        assert_eq!(
+            verbatim_form(&format!("C:/repo/{long_dir}")),
+            Some(format!(r"\\?\C:\repo\{long_dir}"))
+        );
+        assert_eq!(
+            verbatim_form(&format!(r"\\server\share\{long_dir}")),
+            Some(format!(r"\\?\UNC\server\share\{long_dir}"))
+        );
+        assert_eq!(verbatim_form(r"C:\repo\short"), None);
+        assert_eq!(
+            verbatim_form(&format!(r"repo\{long_dir}")),
+            None,
+            "relative"
+        );
+
+        assert_eq!(
+            strip_verbatim(Path::new(r"\\?\C:\repo")),
+            PathBuf::from(r"C:\repo")
+        );
+        assert_eq!(
+            strip_verbatim(Path::new(r"\\?\UNC\server\share")),
+            PathBuf::from(r"\\server\share")
+        );
+        assert_eq!(
+            strip_verbatim(Path::new("/home/dev/repo")),
+            PathBuf::from("/home/dev/repo")
+        );
+        if !cfg!(windows) {
+            let path = PathBuf::from(format!("/{long_dir}"));
+            assert_eq!(long_path(&path), path);
+        }
+    }
+
+    #[test]
+    fn test_same_branch_name() {
+        assert!(same_branch_name("feature/x", "feature/x"));
+        assert_eq!(
+            same_branch_name("Feature/X", "feature/x"),
+            cfg!(any(windows, target_os = "macos"))
+        );
+    }
+
     #[test]
     fn test_shell_completion_dirs() {
         let dirs = shell_completion_dirs();
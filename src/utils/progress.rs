@@ -0,0 +1,69 @@
+use crate::cli::output::Output;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::cell::RefCell;
+
+/// Progress bar for one fetch or push. It only appears once the transfer turns out to move
+/// enough objects to be worth watching, and clears itself when dropped. Plain output
+/// never shows it.
+#[derive(Default)]
+pub struct TransferProgress {
+    bar: RefCell<Option<ProgressBar>>,
+}
+
+impl TransferProgress {
+    /// Transfers smaller than this finish before a bar is worth drawing
+    const MIN_OBJECTS: usize = 100;
+    const TEMPLATE: &'static str = "{msg:18} [{bar:30.green}] {pos}/{len}";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update from a fetch: objects received, then deltas resolved
+    pub fn fetch(&self, progress: &git2::Progress<'_>) {
+        if progress.received_objects() < progress.total_objects() {
+            self.show(
+                "Receiving objects",
+                progress.received_objects(),
+                progress.total_objects(),
+            );
+        } else if progress.total_deltas() > 0 {
+            self.show(
+                "Resolving deltas",
+                progress.indexed_deltas(),
+                progress.total_deltas(),
+            );
+        }
+    }
+
+    /// Update from a push: objects written to the remote
+    pub fn push(&self, current: usize, total: usize) {
+        self.show("Writing objects", current, total);
+    }
+
+    fn show(&self, stage: &'static str, position: usize, total: usize) {
+        let mut bar = self.bar.borrow_mut();
+        if bar.is_none() && (total < Self::MIN_OBJECTS || Output::is_plain()) {
+            return;
+        }
+        let bar = bar.get_or_insert_with(|| {
+            let bar = ProgressBar::new(total as u64);
+            bar.set_style(
+                ProgressStyle::with_template(Self::TEMPLATE)
+                    .unwrap_or_else(|_| ProgressStyle::default_bar()),
+            );
+            bar
+        });
+        bar.set_message(stage);
+        bar.set_length(total as u64);
+        bar.set_position(position as u64);
+    }
+}
+
+impl Drop for TransferProgress {
+    fn drop(&mut self) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+        }
+    }
+}
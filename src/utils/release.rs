@@ -0,0 +1,351 @@
+use crate::errors::{CascadeError, Result};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::path::Path;
+use std::time::Duration;
+
+/// Published releases on GitHub, newest first
+const RELEASES_URL: &str = "https://api.github.com/repos/JAManfredi/cascade-cli/releases";
+
+/// Overrides [`RELEASES_URL`], for organizations that mirror releases internally
+pub const RELEASES_URL_ENV: &str = "CASCADE_RELEASES_URL";
+
+/// `sha256sum` output for every asset of a release
+pub const CHECKSUMS_ASSET: &str = "checksums.txt";
+
+/// A release as returned by the GitHub releases API
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Release notes (markdown)
+    #[serde(default)]
+    pub body: Option<String>,
+    pub html_url: String,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub published_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    #[serde(default)]
+    pub size: u64,
+}
+
+impl Release {
+    /// Version without the tag's `v` prefix
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    pub fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+
+    pub fn is_newer_than(&self, version: &str) -> bool {
+        compare_versions(self.version(), version) == Ordering::Greater
+    }
+
+    /// Breaking changes called out in the release notes
+    pub fn breaking_changes(&self) -> Vec<String> {
+        self.body
+            .as_deref()
+            .map(breaking_changes)
+            .unwrap_or_default()
+    }
+}
+
+/// Fetch the latest stable release
+pub async fn latest_release(timeout: Duration) -> Result<Release> {
+    releases(timeout)
+        .await?
+        .into_iter()
+        .max_by(|a, b| compare_versions(a.version(), b.version()))
+        .ok_or_else(|| CascadeError::config("No releases have been published yet"))
+}
+
+/// Stable releases newer than `version`, newest first
+pub async fn releases_since(version: &str, timeout: Duration) -> Result<Vec<Release>> {
+    let mut newer: Vec<Release> = releases(timeout)
+        .await?
+        .into_iter()
+        .filter(|release| release.is_newer_than(version))
+        .collect();
+    newer.sort_by(|a, b| compare_versions(b.version(), a.version()));
+    Ok(newer)
+}
+
+/// Published stable releases (drafts and pre-releases are skipped)
+async fn releases(timeout: Duration) -> Result<Vec<Release>> {
+    let url = std::env::var(RELEASES_URL_ENV).unwrap_or_else(|_| RELEASES_URL.to_string());
+    let response = client(timeout)?
+        .get(&url)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| CascadeError::config(format!("Could not reach {url}: {e}")))?;
+    if !response.status().is_success() {
+        return Err(CascadeError::config(format!(
+            "Could not look up the latest release: {url} returned {}",
+            response.status()
+        )));
+    }
+    let releases: Vec<Release> = response
+        .json()
+        .await
+        .map_err(|e| CascadeError::config(format!("Unexpected release data from {url}: {e}")))?;
+    Ok(releases
+        .into_iter()
+        .filter(|release| !release.draft && !release.prerelease)
+        .collect())
+}
+
+/// Lines of release notes that announce breaking changes: everything under a heading that
+/// mentions "breaking", and any other line marked BREAKING
+pub fn breaking_changes(notes: &str) -> Vec<String> {
+    let mut changes = Vec::new();
+    let mut in_section = false;
+    for line in notes.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            in_section = trimmed.to_lowercase().contains("breaking");
+            continue;
+        }
+        let item = trimmed.trim_start_matches(['-', '*', '+']).trim();
+        if item.is_empty() {
+            continue;
+        }
+        if in_section || item.contains("BREAKING") {
+            changes.push(item.to_string());
+        }
+    }
+    changes
+}
+
+/// Download a release asset
+pub async fn download(asset: &ReleaseAsset, timeout: Duration) -> Result<Vec<u8>> {
+    let response = client(timeout)?
+        .get(&asset.browser_download_url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| CascadeError::config(format!("Could not download {}: {e}", asset.name)))?;
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| CascadeError::config(format!("Could not download {}: {e}", asset.name)))?;
+    Ok(bytes.to_vec())
+}
+
+fn client(timeout: Duration) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .user_agent(concat!("cascade-cli/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| CascadeError::config(format!("Could not create HTTP client: {e}")))
+}
+
+/// Compare `major.minor.patch[-pre]` versions; a pre-release sorts before its release
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> (Vec<u64>, Option<&str>) {
+        let version = version.trim().trim_start_matches('v');
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+        let mut parts: Vec<u64> = core
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect();
+        parts.resize(3, 0);
+        (parts, pre)
+    }
+
+    let (a_parts, a_pre) = split(a);
+    let (b_parts, b_pre) = split(b);
+    a_parts.cmp(&b_parts).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => a.cmp(b),
+    })
+}
+
+/// How this `ca` binary was installed, which decides how it gets updated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallChannel {
+    Homebrew,
+    Cargo,
+    Scoop,
+    /// A release binary copied into place by hand or by `ca update`
+    Binary,
+}
+
+impl InstallChannel {
+    /// Channel of the running executable
+    pub fn detect() -> Self {
+        std::env::current_exe()
+            .and_then(|exe| exe.canonicalize())
+            .map(|exe| Self::from_path(&exe))
+            .unwrap_or(InstallChannel::Binary)
+    }
+
+    pub fn from_path(path: &Path) -> Self {
+        let path = path.to_string_lossy().replace('\\', "/").to_lowercase();
+        if path.contains("/cellar/") || path.contains("/homebrew/") || path.contains("/linuxbrew/")
+        {
+            InstallChannel::Homebrew
+        } else if path.contains("/scoop/") {
+            InstallChannel::Scoop
+        } else if path.contains("/.cargo/bin/") || path.contains("/cargo/bin/") {
+            InstallChannel::Cargo
+        } else {
+            InstallChannel::Binary
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InstallChannel::Homebrew => "homebrew",
+            InstallChannel::Cargo => "cargo",
+            InstallChannel::Scoop => "scoop",
+            InstallChannel::Binary => "binary",
+        }
+    }
+
+    /// Command that updates a package-manager install; None when `ca update` does it
+    pub fn upgrade_command(&self) -> Option<&'static str> {
+        match self {
+            InstallChannel::Homebrew => Some("brew upgrade cascade-cli"),
+            InstallChannel::Cargo => Some("cargo install cascade-cli --force"),
+            InstallChannel::Scoop => Some("scoop update cascade-cli"),
+            InstallChannel::Binary => None,
+        }
+    }
+}
+
+/// Release asset built for this platform, matching the release workflow's names
+pub fn platform_asset() -> Option<String> {
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        _ => return None,
+    };
+    match std::env::consts::OS {
+        "linux" => Some(format!("ca-linux-{arch}.tar.gz")),
+        "macos" => Some(format!("ca-macos-{arch}.tar.gz")),
+        "windows" => Some(format!("ca-windows-{arch}.exe.zip")),
+        _ => None,
+    }
+}
+
+/// Checksum listed for `asset` in `sha256sum` output
+pub fn expected_checksum(checksums: &str, asset: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let hash = fields.next()?;
+        // `sha256sum -b` marks binary mode with a leading '*'
+        let name = fields.next()?.trim_start_matches('*');
+        (name == asset).then(|| hash.to_lowercase())
+    })
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("0.2.0", "0.1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("v1.10.0", "1.9.3"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.0-rc.1", "1.0.0"), Ordering::Less);
+        assert_eq!(
+            compare_versions("1.0.0-rc.2", "1.0.0-rc.1"),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_breaking_changes_from_release_notes() {
+        let notes = "\
+## What's New
+- Faster sync
+- BREAKING: `ca stacks push` was removed
+
+### Breaking Changes
+- `cascade.land_mode` defaults to sequential
+* Config moved to .cascade/
+
+### Fixes
+- Nothing breaking here
+";
+        assert_eq!(
+            breaking_changes(notes),
+            vec![
+                "BREAKING: `ca stacks push` was removed",
+                "`cascade.land_mode` defaults to sequential",
+                "Config moved to .cascade/",
+            ]
+        );
+        assert!(breaking_changes("- Faster sync").is_empty());
+    }
+
+    #[test]
+    fn test_install_channel_from_path() {
+        let channel = |path: &str| InstallChannel::from_path(Path::new(path));
+        assert_eq!(
+            channel("/opt/homebrew/Cellar/cascade-cli/0.1.0/bin/ca"),
+            InstallChannel::Homebrew
+        );
+        assert_eq!(
+            channel("/home/linuxbrew/.linuxbrew/bin/ca"),
+            InstallChannel::Homebrew
+        );
+        assert_eq!(channel("/home/dev/.cargo/bin/ca"), InstallChannel::Cargo);
+        assert_eq!(
+            channel(r"C:\Users\dev\scoop\apps\cascade-cli\current\ca.exe"),
+            InstallChannel::Scoop
+        );
+        assert_eq!(channel("/usr/local/bin/ca"), InstallChannel::Binary);
+        assert_eq!(InstallChannel::Binary.upgrade_command(), None);
+    }
+
+    #[test]
+    fn test_expected_checksum() {
+        let checksums = "\
+ABC123  ca-linux-x64.tar.gz
+def456 *ca-windows-x64.exe.zip
+";
+        assert_eq!(
+            expected_checksum(checksums, "ca-linux-x64.tar.gz").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(
+            expected_checksum(checksums, "ca-windows-x64.exe.zip").as_deref(),
+            Some("def456")
+        );
+        assert_eq!(expected_checksum(checksums, "ca-macos-x64.tar.gz"), None);
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -1,16 +1,22 @@
+use crate::cli::output::Output;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::time::Duration;
 
 /// Lightweight wrapper around `indicatif`'s spinner progress bar with
 /// convenience helpers for printing output while the spinner is active.
+///
+/// With plain output the spinner is never drawn: its message and the lines printed
+/// under it are written as ordinary lines instead.
 pub struct Spinner {
     pb: ProgressBar,
+    plain: bool,
 }
 
 /// Cloneable handle that allows printing while a spinner is active.
 #[derive(Debug, Clone)]
 pub struct SpinnerPrinter {
     pb: ProgressBar,
+    plain: bool,
 }
 
 impl Spinner {
@@ -18,6 +24,14 @@ impl Spinner {
     const TEMPLATE: &'static str = "{spinner:.green} {msg}";
 
     fn new_internal(message: String) -> Self {
+        if Output::is_plain() {
+            Output::line(&message);
+            return Spinner {
+                pb: ProgressBar::hidden(),
+                plain: true,
+            };
+        }
+
         let pb = ProgressBar::new_spinner();
         pb.set_style(
             ProgressStyle::with_template(Self::TEMPLATE)
@@ -30,7 +44,7 @@ impl Spinner {
         // This ensures the spinner appears at the correct position
         std::thread::sleep(Duration::from_millis(20));
 
-        Spinner { pb }
+        Spinner { pb, plain: false }
     }
 
     /// Start a spinner with the provided message.
@@ -48,7 +62,7 @@ impl Spinner {
 
     /// Print a line while keeping the spinner intact.
     pub fn println<T: AsRef<str>>(&self, message: T) {
-        self.pb.println(message.as_ref());
+        print_line(&self.pb, self.plain, message.as_ref());
     }
 
     /// Obtain a cloneable printer handle that can be used to emit lines from
@@ -56,6 +70,7 @@ impl Spinner {
     pub fn printer(&self) -> SpinnerPrinter {
         SpinnerPrinter {
             pb: self.pb.clone(),
+            plain: self.plain,
         }
     }
 
@@ -71,11 +86,17 @@ impl Spinner {
 
     /// Stop the spinner and replace it with a final message.
     pub fn stop_with_message(&self, message: &str) {
+        if self.plain {
+            Output::line(message);
+        }
         self.pb.finish_with_message(message.to_string());
     }
 
     /// Update the spinner message while it is running.
     pub fn update_message(&self, new_message: String) {
+        if self.plain {
+            Output::line(&new_message);
+        }
         self.pb.set_message(new_message);
     }
 }
@@ -91,7 +112,7 @@ impl Drop for Spinner {
 impl SpinnerPrinter {
     /// Print a line beneath the spinner.
     pub fn println<T: AsRef<str>>(&self, message: T) {
-        self.pb.println(message.as_ref());
+        print_line(&self.pb, self.plain, message.as_ref());
     }
 
     /// Temporarily suspend the spinner while running the provided closure.
@@ -100,6 +121,15 @@ impl SpinnerPrinter {
     }
 }
 
+/// A hidden (plain) spinner drops the lines `ProgressBar::println` is given
+fn print_line(pb: &ProgressBar, plain: bool, message: &str) {
+    if plain {
+        Output::line(message);
+    } else {
+        pb.println(message);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
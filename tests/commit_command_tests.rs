@@ -0,0 +1,90 @@
+/// Integration tests for the `ca commit` command
+use cascade_cli::stack::StackManager;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+
+#[path = "integration/test_helpers.rs"]
+mod test_helpers;
+
+fn run_ca(args: &[&str], cwd: &Path) -> (bool, String) {
+    let output = Command::new(test_helpers::get_binary_path())
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .output()
+        .expect("Failed to execute ca");
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    (output.status.success(), text)
+}
+
+fn git(args: &[&str], cwd: &Path) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .expect("Failed to run git");
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn setup_stack() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path();
+
+    git(&["init", "-b", "main"], repo_path);
+    git(&["config", "user.name", "Test User"], repo_path);
+    git(&["config", "user.email", "test@example.com"], repo_path);
+    std::fs::write(repo_path.join("README.md"), "# Test Repo").unwrap();
+    git(&["add", "."], repo_path);
+    git(&["commit", "-m", "Initial commit"], repo_path);
+
+    let (ok, out) = run_ca(&["init"], repo_path);
+    assert!(ok, "ca init failed: {out}");
+    git(&["checkout", "-b", "feature/work"], repo_path);
+    let (ok, out) = run_ca(&["stacks", "create", "work"], repo_path);
+    assert!(ok, "ca stacks create failed: {out}");
+
+    temp_dir
+}
+
+#[test]
+fn test_commit_adds_entry_and_amend_updates_it() {
+    if !test_helpers::get_binary_path().exists() {
+        eprintln!("Skipping test: ca binary not found");
+        return;
+    }
+    let temp_dir = setup_stack();
+    let repo_path = temp_dir.path();
+
+    std::fs::write(repo_path.join("a.txt"), "one").unwrap();
+    git(&["add", "a.txt"], repo_path);
+    let (ok, out) = run_ca(&["commit", "-m", "Add a", "--yes"], repo_path);
+    assert!(ok, "ca commit failed: {out}");
+
+    let head = git(&["rev-parse", "HEAD"], repo_path);
+    let manager = StackManager::new(repo_path).unwrap();
+    let stack = manager.get_stack_by_name("work").unwrap();
+    assert_eq!(stack.entries.len(), 1);
+    assert_eq!(stack.entries[0].commit_hash, head);
+
+    // -a picks up the modified tracked file; --amend rewrites the same entry
+    std::fs::write(repo_path.join("a.txt"), "two").unwrap();
+    let (ok, out) = run_ca(&["commit", "-a", "--amend", "-m", "Add a (v2)"], repo_path);
+    assert!(ok, "ca commit --amend failed: {out}");
+
+    let amended = git(&["rev-parse", "HEAD"], repo_path);
+    assert_ne!(amended, head);
+    let manager = StackManager::new(repo_path).unwrap();
+    let stack = manager.get_stack_by_name("work").unwrap();
+    assert_eq!(stack.entries.len(), 1);
+    assert_eq!(stack.entries[0].commit_hash, amended);
+    assert_eq!(
+        git(&["rev-parse", &stack.entries[0].branch], repo_path),
+        amended
+    );
+}
@@ -0,0 +1,216 @@
+//! Integration tests for `ca hook-exec`, the logic behind installed Git hooks
+#![cfg(unix)]
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+
+#[path = "integration/test_helpers.rs"]
+mod test_helpers;
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(repo)
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success(), "git {args:?} failed");
+}
+
+fn init_repo() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    git(dir.path(), &["init"]);
+    git(dir.path(), &["config", "user.name", "Test"]);
+    git(dir.path(), &["config", "user.email", "test@test.com"]);
+    git(dir.path(), &["commit", "--allow-empty", "-m", "Initial"]);
+    dir
+}
+
+fn write_hook(repo: &Path, name: &str, script: &str) {
+    use std::os::unix::fs::PermissionsExt;
+    let path = repo.join(".git/hooks").join(name);
+    std::fs::write(&path, script).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn hook_exec(repo: &Path, home: &Path, args: &[&str], stdin: &str) -> std::process::Output {
+    let mut child = Command::new(test_helpers::get_binary_path())
+        .arg("hook-exec")
+        .args(args)
+        .current_dir(repo)
+        .env("HOME", home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute ca");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_hook_exec_chains_to_original_hook() {
+    if !test_helpers::get_binary_path().exists() {
+        eprintln!("Skipping test: ca binary not found");
+        return;
+    }
+    let repo = init_repo();
+    let home = TempDir::new().unwrap();
+    write_hook(
+        repo.path(),
+        "pre-push",
+        "#!/bin/sh\necho \"$1 $2\" > pushed.txt\ncat >> pushed.txt\nexit 3\n",
+    );
+
+    let refs = "refs/heads/main 1111 refs/heads/main 0000\n";
+    let output = hook_exec(
+        repo.path(),
+        home.path(),
+        &["pre-push", "origin", "git@example.com:repo.git"],
+        refs,
+    );
+    assert_eq!(output.status.code(), Some(3), "original exit code is kept");
+    let pushed = std::fs::read_to_string(repo.path().join("pushed.txt")).unwrap();
+    assert_eq!(pushed, format!("origin git@example.com:repo.git\n{refs}"));
+}
+
+#[test]
+fn test_hook_exec_rejects_short_commit_messages() {
+    if !test_helpers::get_binary_path().exists() {
+        eprintln!("Skipping test: ca binary not found");
+        return;
+    }
+    let repo = init_repo();
+    let home = TempDir::new().unwrap();
+    cascade_cli::config::initialize_repo(repo.path(), None).unwrap();
+    let message_file = repo.path().join(".git/COMMIT_EDITMSG");
+
+    std::fs::write(&message_file, "wip\n# Please enter the commit message\n").unwrap();
+    let output = hook_exec(
+        repo.path(),
+        home.path(),
+        &["commit-msg", ".git/COMMIT_EDITMSG"],
+        "",
+    );
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Commit message too short"), "{stdout}");
+
+    std::fs::write(&message_file, "Add the login endpoint\n").unwrap();
+    let output = hook_exec(
+        repo.path(),
+        home.path(),
+        &["commit-msg", ".git/COMMIT_EDITMSG"],
+        "",
+    );
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_hook_exec_rejects_unknown_hooks() {
+    if !test_helpers::get_binary_path().exists() {
+        eprintln!("Skipping test: ca binary not found");
+        return;
+    }
+    let repo = init_repo();
+    let home = TempDir::new().unwrap();
+    let output = hook_exec(repo.path(), home.path(), &["post-merge"], "");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown hook type"));
+}
+
+#[test]
+fn test_hooks_run_dry_run_reports_without_chaining() {
+    if !test_helpers::get_binary_path().exists() {
+        eprintln!("Skipping test: ca binary not found");
+        return;
+    }
+    let repo = init_repo();
+    let home = TempDir::new().unwrap();
+    cascade_cli::config::initialize_repo(repo.path(), None).unwrap();
+    git(repo.path(), &["commit", "--allow-empty", "-m", "wip"]);
+    write_hook(repo.path(), "commit-msg", "#!/bin/sh\ntouch original-ran\n");
+
+    // Without a message file, the HEAD commit's message is checked
+    let output = Command::new(test_helpers::get_binary_path())
+        .args(["hooks", "run", "commit-msg", "--dry-run"])
+        .current_dir(repo.path())
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to execute ca");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("Commit message too short"), "{stdout}");
+    assert!(stdout.contains("commit-msg would stop git"), "{stdout}");
+    assert!(!repo.path().join("original-ran").exists());
+}
+
+#[test]
+fn test_post_commit_moves_commits_off_lower_entries() {
+    if !test_helpers::get_binary_path().exists() {
+        eprintln!("Skipping test: ca binary not found");
+        return;
+    }
+    let repo = init_repo();
+    let home = TempDir::new().unwrap();
+    let ca = |args: &[&str]| {
+        let output = Command::new(test_helpers::get_binary_path())
+            .args(args)
+            .current_dir(repo.path())
+            .env("HOME", home.path())
+            .stdin(Stdio::null())
+            .output()
+            .expect("Failed to execute ca");
+        assert!(
+            output.status.success(),
+            "ca {args:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    };
+    let commit = |file: &str, message: &str| {
+        std::fs::write(repo.path().join(file), message).unwrap();
+        git(repo.path(), &["add", file]);
+        git(repo.path(), &["commit", "-m", message]);
+    };
+    let head_of = |branch: &str| {
+        let output = Command::new("git")
+            .args(["rev-parse", branch])
+            .current_dir(repo.path())
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+
+    git(repo.path(), &["branch", "-M", "main"]);
+    ca(&["init"]);
+    git(repo.path(), &["checkout", "-b", "login"]);
+    ca(&["stacks", "create", "login", "--base", "main"]);
+    commit("one.txt", "Add the first part");
+    ca(&["push", "--yes", "--branch", "part-one"]);
+    commit("two.txt", "Add the second part");
+    ca(&["push", "--yes", "--branch", "part-two"]);
+    let entry_commit = head_of("part-one");
+
+    git(repo.path(), &["checkout", "part-one"]);
+    commit("fix.txt", "Fix the first part");
+    let stdout =
+        String::from_utf8_lossy(&hook_exec(repo.path(), home.path(), &["post-commit"], "").stdout)
+            .to_string();
+    assert!(stdout.contains("Moved 1 commit(s) to 'login'"), "{stdout}");
+
+    assert_eq!(head_of("part-one"), entry_commit, "entry branch is reset");
+    assert_eq!(head_of("HEAD"), head_of("login"));
+    assert_eq!(head_of("login~1"), head_of("part-two"));
+    let stacks = std::fs::read_to_string(repo.path().join(".cascade/stacks.json")).unwrap();
+    assert!(
+        stacks.contains(&head_of("login")),
+        "moved commit is a new entry"
+    );
+}
@@ -1,3 +1,4 @@
+use cascade_cli::bitbucket::recording::HttpRecording;
 use cascade_cli::bitbucket::{BitbucketClient, PullRequestManager};
 use cascade_cli::config::BitbucketConfig;
 use serde_json::json;
@@ -37,6 +38,7 @@ async fn test_bitbucket_client_operations() {
         default_reviewers: vec![],
         accept_invalid_certs: None,
         ca_bundle_path: None,
+        ..Default::default()
     };
 
     let client = BitbucketClient::new(&config).unwrap();
@@ -86,6 +88,7 @@ async fn test_pull_request_management() {
         default_reviewers: vec![],
         accept_invalid_certs: None,
         ca_bundle_path: None,
+        ..Default::default()
     };
 
     let client = BitbucketClient::new(&config).unwrap();
@@ -125,6 +128,7 @@ async fn test_api_error_handling() {
         default_reviewers: vec![],
         accept_invalid_certs: None,
         ca_bundle_path: None,
+        ..Default::default()
     };
 
     let client = BitbucketClient::new(&config).unwrap();
@@ -147,6 +151,7 @@ async fn test_authentication_methods() {
         default_reviewers: vec![],
         accept_invalid_certs: None,
         ca_bundle_path: None,
+        ..Default::default()
     };
 
     let client = BitbucketClient::new(&token_config);
@@ -162,6 +167,7 @@ async fn test_authentication_methods() {
         default_reviewers: vec![],
         accept_invalid_certs: None,
         ca_bundle_path: None,
+        ..Default::default()
     };
 
     let client = BitbucketClient::new(&user_pass_config);
@@ -177,8 +183,63 @@ async fn test_authentication_methods() {
         default_reviewers: vec![],
         accept_invalid_certs: None,
         ca_bundle_path: None,
+        ..Default::default()
     };
 
     let client = BitbucketClient::new(&no_auth_config);
     assert!(client.is_err());
 }
+
+/// Traffic recorded against one server replays without any server at all
+#[tokio::test]
+async fn test_recorded_traffic_replays_offline() {
+    let mut server = mockito::Server::new_async().await;
+    let _pr_mock = server
+        .mock(
+            "GET",
+            "/rest/api/1.0/projects/TEST/repos/test-repo/pull-requests/7",
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(json!({ "id": 7, "state": "OPEN" }).to_string())
+        .expect(1)
+        .create_async()
+        .await;
+
+    let fixtures = tempfile::TempDir::new().unwrap();
+    let fixture = fixtures.path().join("traffic.json");
+    let config = |url: String| BitbucketConfig {
+        url,
+        project: "TEST".to_string(),
+        repo: "test-repo".to_string(),
+        username: Some("testuser".to_string()),
+        token: Some("testtoken".to_string()),
+        ..Default::default()
+    };
+
+    let recorder = BitbucketClient::new(&config(server.url()))
+        .unwrap()
+        .with_recording(HttpRecording::record(&fixture).unwrap());
+    let live: serde_json::Value = recorder.get("pull-requests/7").await.unwrap();
+    let missing = recorder.get::<serde_json::Value>("pull-requests/8").await;
+    assert!(missing.is_err());
+
+    let recording = std::fs::read_to_string(&fixture).unwrap();
+    assert!(!recording.contains("testtoken"));
+
+    let offline = BitbucketClient::new(&config("http://127.0.0.1:9".to_string()))
+        .unwrap()
+        .with_recording(HttpRecording::replay(&fixture).unwrap());
+    let replayed: serde_json::Value = offline.get("pull-requests/7").await.unwrap();
+    assert_eq!(replayed, live);
+
+    let error = offline
+        .get::<serde_json::Value>("pull-requests/8")
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("501"));
+    assert!(offline
+        .get::<serde_json::Value>("pull-requests/9")
+        .await
+        .is_err());
+}
@@ -146,6 +146,40 @@ async fn test_error_recovery_scenarios() {
     }
 }
 
+/// The tutorial runs every step in its sandbox without a Bitbucket server
+#[tokio::test]
+async fn test_tutorial_completes_offline() {
+    let temp_dir = TempDir::new().unwrap();
+    let sandbox = temp_dir.path().join("sandbox");
+
+    let binary_path = super::test_helpers::get_binary_path();
+    let output = Command::new(&binary_path)
+        .args(["tutorial", "--yes", "--dir"])
+        .arg(&sandbox)
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to run tutorial");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Tutorial failed. Stderr: {}\nStdout: {stdout}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(stdout.matches("Checkpoint:").count(), 6);
+    assert!(stdout.contains("Tutorial complete"));
+    assert!(sandbox.join("my-app/.cascade").exists());
+
+    // Refuses to reuse a sandbox that already has content
+    let output = Command::new(&binary_path)
+        .args(["tutorial", "--yes", "--dir"])
+        .arg(&sandbox)
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to run tutorial");
+    assert!(!output.status.success());
+}
+
 async fn create_test_git_repo() -> (TempDir, PathBuf) {
     let temp_dir = TempDir::new().unwrap();
     let repo_path = temp_dir.path().to_path_buf();
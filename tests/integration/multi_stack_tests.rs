@@ -82,6 +82,72 @@ async fn test_multi_stack_creation_and_switching() {
     }
 }
 
+/// Test that `ca feature` creates the stack and checks out its working branch
+#[tokio::test]
+async fn test_feature_creates_stack_and_branch() {
+    let (_temp_dir, repo_path) = create_test_git_repo().await;
+
+    cascade_cli::config::initialize_repo(
+        &repo_path,
+        Some("https://test.bitbucket.com".to_string()),
+    )
+    .unwrap();
+
+    let base_output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(&repo_path)
+        .output()
+        .unwrap();
+    let base_branch = String::from_utf8_lossy(&base_output.stdout)
+        .trim()
+        .to_string();
+
+    let binary_path = super::test_helpers::get_binary_path();
+    let output = Command::new(&binary_path)
+        .args(["feature", "User Auth", "--base", &base_branch])
+        .current_dir(&repo_path)
+        .output()
+        .expect("Feature command should run");
+
+    assert!(
+        output.status.success(),
+        "Feature command should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let head_output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(&repo_path)
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&head_output.stdout).trim(),
+        "user-auth"
+    );
+
+    let list_output = Command::new(&binary_path)
+        .args(["stacks", "list"])
+        .current_dir(&repo_path)
+        .output()
+        .expect("Stack listing should work");
+    assert!(String::from_utf8_lossy(&list_output.stdout).contains("User Auth"));
+
+    // A second feature with the same name must fail without creating another branch
+    let output = Command::new(&binary_path)
+        .args(["feature", "User Auth", "--base", &base_branch])
+        .current_dir(&repo_path)
+        .output()
+        .expect("Feature command should run");
+    assert!(!output.status.success());
+
+    let branches = Command::new("git")
+        .args(["branch", "--list", "user-auth-*"])
+        .current_dir(&repo_path)
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&branches.stdout).trim().is_empty());
+}
+
 /// Test stack state consistency after git operations
 #[tokio::test]
 async fn test_stack_state_after_manual_git_ops() {
@@ -59,6 +59,7 @@ async fn test_api_rate_limiting_behavior() {
         default_reviewers: vec![],
         accept_invalid_certs: None,
         ca_bundle_path: None,
+        ..Default::default()
     };
 
     let client = BitbucketClient::new(&config).unwrap();
@@ -120,6 +121,7 @@ async fn test_network_timeout_handling() {
         default_reviewers: vec![],
         accept_invalid_certs: None,
         ca_bundle_path: None,
+        ..Default::default()
     };
 
     let client = BitbucketClient::new(&config).unwrap();
@@ -180,6 +182,7 @@ async fn test_authentication_token_expiration() {
         default_reviewers: vec![],
         accept_invalid_certs: None,
         ca_bundle_path: None,
+        ..Default::default()
     };
 
     let client = BitbucketClient::new(&config).unwrap();
@@ -250,6 +253,7 @@ async fn test_partial_api_operation_failures() {
         default_reviewers: vec![],
         accept_invalid_certs: None,
         ca_bundle_path: None,
+        ..Default::default()
     };
 
     let client = BitbucketClient::new(&config).unwrap();
@@ -279,6 +283,7 @@ async fn test_network_interruption_scenarios() {
         default_reviewers: vec![],
         accept_invalid_certs: None,
         ca_bundle_path: None,
+        ..Default::default()
     };
 
     let client_result = BitbucketClient::new(&config);
@@ -0,0 +1,74 @@
+//! Integration tests for `ca-<name>` plugins
+#![cfg(unix)]
+
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+#[path = "integration/test_helpers.rs"]
+mod test_helpers;
+
+fn write_plugin(dir: &Path, name: &str, script: &str) {
+    use std::os::unix::fs::PermissionsExt;
+    let path = dir.join(name);
+    std::fs::write(&path, script).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn run_ca(args: &[&str], cwd: &Path, plugin_dir: &Path) -> std::process::Output {
+    let path = format!(
+        "{}:{}",
+        plugin_dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    Command::new(test_helpers::get_binary_path())
+        .args(args)
+        .current_dir(cwd)
+        .env("PATH", path)
+        .output()
+        .expect("Failed to execute ca")
+}
+
+#[test]
+fn test_unknown_command_runs_plugin_with_context() {
+    if !test_helpers::get_binary_path().exists() {
+        eprintln!("Skipping test: ca binary not found");
+        return;
+    }
+    let plugin_dir = TempDir::new().unwrap();
+    let work_dir = TempDir::new().unwrap();
+    write_plugin(
+        plugin_dir.path(),
+        "ca-hello",
+        "#!/bin/sh\necho \"args: $*\"\necho \"$CASCADE_CONTEXT\"\nexit 3\n",
+    );
+
+    let output = run_ca(
+        &["hello", "--loud", "world"],
+        work_dir.path(),
+        plugin_dir.path(),
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(3), "plugin exit code is kept");
+    assert!(stdout.contains("args: --loud world"), "{stdout}");
+
+    let context: serde_json::Value = serde_json::from_str(stdout.lines().nth(1).unwrap()).unwrap();
+    assert_eq!(context["version"], env!("CARGO_PKG_VERSION"));
+    assert!(
+        context["repo_root"].is_null(),
+        "not run inside a repository"
+    );
+}
+
+#[test]
+fn test_unknown_command_without_plugin_fails() {
+    if !test_helpers::get_binary_path().exists() {
+        eprintln!("Skipping test: ca binary not found");
+        return;
+    }
+    let empty_dir = TempDir::new().unwrap();
+    let output = run_ca(&["no-such-plugin"], empty_dir.path(), empty_dir.path());
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("ca-no-such-plugin"), "{stderr}");
+}
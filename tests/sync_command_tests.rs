@@ -518,3 +518,31 @@ fn test_sync_multiple_stacks() {
         "Stack 2 branches should exist"
     );
 }
+
+#[test]
+fn test_sync_dry_run_runs_while_an_operation_is_interrupted() {
+    if !ca_binary_exists() {
+        eprintln!("Skipping test: ca binary not found");
+        return;
+    }
+    let (temp_dir, _) = setup_test_repo().unwrap();
+    let repo_path = temp_dir.path();
+    create_test_stack(repo_path, "test-stack").unwrap();
+
+    // A sync that crashed part way left its sentinel behind
+    std::fs::write(
+        repo_path.join(".git").join("CASCADE_OPERATION"),
+        r#"{"operation":"sync","args":["sync"],"original_branch":"main","pid":999999,"started_at":"2026-01-01T00:00:00Z"}"#,
+    )
+    .unwrap();
+
+    let (success, stdout, stderr) = run_ca_command(&["sync", "--dry-run"], repo_path).unwrap();
+    assert!(
+        success,
+        "A dry run should still run\nstdout: {stdout}\nstderr: {stderr}"
+    );
+
+    let (success, _, _) = run_ca_command(&["sync"], repo_path).unwrap();
+    assert!(!success, "A real sync should wait for 'ca recover'");
+    assert!(repo_path.join(".git").join("CASCADE_OPERATION").exists());
+}